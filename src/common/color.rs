@@ -59,6 +59,19 @@ impl HexColor {
             alpha: scale(a),
         }
     }
+
+    /// Convert to an X11 Color with the channels premultiplied by alpha, as XRender fills and
+    /// blends expect for translucent colors
+    pub fn to_premultiplied_x11_color(self) -> Color {
+        let color = self.to_x11_color();
+        let premultiply = |v: u16| (u32::from(v) * u32::from(color.alpha) / 0xFFFF) as u16;
+        Color {
+            red: premultiply(color.red),
+            green: premultiply(color.green),
+            blue: premultiply(color.blue),
+            alpha: color.alpha,
+        }
+    }
 }
 
 /// Convert HEX string to egui::Color32
@@ -144,6 +157,22 @@ mod tests {
         assert_eq!(x11.blue, 0x2020);
     }
 
+    #[test]
+    fn test_hex_color_to_premultiplied_x11() {
+        let x11 = HexColor(0x80_FF_40_00).to_premultiplied_x11_color();
+        assert_eq!(x11.alpha, 0x8080);
+        assert_eq!(x11.red, 0x8080);
+        assert_eq!(x11.green, 0x2040);
+        assert_eq!(x11.blue, 0);
+
+        // Opaque colors are unchanged
+        let opaque = HexColor(0xFF_80_40_20).to_premultiplied_x11_color();
+        assert_eq!(
+            (opaque.alpha, opaque.red, opaque.green, opaque.blue),
+            (0xFFFF, 0x8080, 0x4040, 0x2020)
+        );
+    }
+
     #[test]
     fn test_opacity_percent() {
         let opacity = Opacity::from_percent(75);
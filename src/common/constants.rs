@@ -28,6 +28,28 @@ pub mod x11 {
 
     /// WM_CHANGE_STATE iconic value (requests the WM to minimize)
     pub const ICONIC_STATE: u32 = 3;
+
+    /// _NET_MOVERESIZE_WINDOW flags: static gravity (coordinates are the client window's own,
+    /// not its frame's), x/y/width/height present (bits 8-11), pager source (bits 12-13)
+    pub const MOVERESIZE_STATIC_ALL: u32 = 10 | (0b1111 << 8) | (2 << 12);
+
+    /// Activation timestamps older than this (ms of server time) are replaced with a fresh one
+    pub const FOCUS_TIMESTAMP_MAX_AGE_MS: u32 = 5_000;
+
+    /// How long to wait for the WM to report the new active window after an activation request
+    pub const FOCUS_VERIFY_TIMEOUT_MS: u64 = 100;
+
+    /// Interval between `_NET_ACTIVE_WINDOW` reads while verifying focus (main loop timer)
+    pub const FOCUS_VERIFY_POLL_MS: u64 = 10;
+
+    /// Images at least this large are uploaded through MIT-SHM instead of PutImage
+    pub const SHM_MIN_UPLOAD_BYTES: usize = 64 * 1024;
+
+    /// Environment variable enabling the daemon's round-trip audit (`1` = on)
+    pub const ROUNDTRIP_AUDIT_ENV: &str = "EVE_PREVIEW_ROUNDTRIP_AUDIT";
+
+    /// Interval between round-trip audit summaries
+    pub const ROUNDTRIP_AUDIT_INTERVAL_MS: u64 = 1000;
 }
 
 /// Input event constants (from evdev)
@@ -51,6 +73,28 @@ pub mod input {
     pub const BTN_LEFT: u16 = 272;
     /// Button code for right mouse button (BTN_RIGHT = 0x111)
     pub const BTN_RIGHT: u16 = 273;
+
+    /// How long a hotkey test waits for the binding to be pressed
+    pub const HOTKEY_TEST_SECS: u64 = 10;
+
+    /// Keys handed out (with Ctrl) to imported characters: F1-F12
+    pub const IMPORT_HOTKEY_KEYS: [u16; 12] = [59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 87, 88];
+}
+
+/// Wayland backend constants
+pub mod wayland {
+    /// Layer-shell namespace of preview surfaces (lets compositors match them in window rules)
+    pub const LAYER_NAMESPACE: &str = "eve-preview";
+
+    /// Interval between captures of each previewed client
+    pub const CAPTURE_INTERVAL_MS: u64 = 100;
+
+    /// Environment variable forcing a daemon backend (`x11` or `wayland`)
+    pub const BACKEND_ENV: &str = "EVE_PREVIEW_BACKEND";
+
+    /// Linux input event codes reported in `wl_pointer.button`
+    pub const BTN_LEFT: u32 = 0x110;
+    pub const BTN_RIGHT: u32 = 0x111;
 }
 
 /// Mouse button constants
@@ -59,6 +103,10 @@ pub mod mouse {
     pub const BUTTON_LEFT: u8 = 1;
     /// Right mouse button number
     pub const BUTTON_RIGHT: u8 = 3;
+    /// Scroll wheel up (reported as a button press)
+    pub const BUTTON_SCROLL_UP: u8 = 4;
+    /// Scroll wheel down (reported as a button press)
+    pub const BUTTON_SCROLL_DOWN: u8 = 5;
 }
 
 /// EVE Online window detection constants
@@ -71,12 +119,49 @@ pub mod eve {
 
     /// Display name for logged-out character (shown in logs)
     pub const LOGGED_OUT_DISPLAY_NAME: &str = "login_screen";
+
+    /// EVE image server base URL (character portraits)
+    pub const IMAGE_SERVER_URL: &str = "https://images.evetech.net";
+
+    /// Portrait size requested from the image server (scaled down locally)
+    pub const PORTRAIT_FETCH_SIZE: u16 = 128;
+
+    /// Wait before retrying a failed portrait download (seconds)
+    pub const PORTRAIT_RETRY_SECS: u64 = 600;
+
+    /// Limit on a whole portrait download (seconds)
+    pub const PORTRAIT_TIMEOUT_SECS: u64 = 15;
 }
 
 /// Default window positioning constants
 pub mod positioning {
     /// Padding offset from source window when spawning thumbnails
     pub const DEFAULT_SPAWN_OFFSET: i16 = 20;
+
+    /// Gap between tiles (and screen edges) in the overview
+    pub const OVERVIEW_GAP: u16 = 24;
+}
+
+/// Switch OSD appearance and timing
+pub mod osd {
+    /// How long the OSD stays fully visible before fading
+    pub const VISIBLE_MS: u64 = 900;
+    /// Duration of the fade-out
+    pub const FADE_MS: u64 = 300;
+    /// Interval between opacity steps while fading out
+    pub const FADE_STEP_MS: u64 = 30;
+
+    /// Space between the text and the OSD edge in pixels
+    pub const PADDING: u16 = 12;
+    /// Distance from the pointer to the OSD's top-left corner in pixels
+    pub const CURSOR_OFFSET: i16 = 24;
+    /// Space between a thumbnail and its hover tooltip in pixels
+    pub const TOOLTIP_GAP: i16 = 6;
+
+    /// Background (translucent dark grey, ARGB)
+    pub const BACKGROUND_COLOR: u32 = 0xE0202020;
+    /// Text color (ARGB)
+    pub const TEXT_COLOR: u32 = 0xFFFFFFFF;
 }
 
 /// Fixed-point arithmetic constants (X11 render transforms)
@@ -109,6 +194,12 @@ pub mod config {
     /// Configuration filename
     pub const FILENAME: &str = "config.json";
 
+    /// Session history filename (last-seen timestamps, kept next to the config)
+    pub const SESSION_FILENAME: &str = "session.json";
+
+    /// Default age in days after which a character counts as stale
+    pub const STALE_CHARACTER_DAYS: u32 = 90;
+
     /// Backup constants
     pub mod backup {
         /// Directory name for backups (relative to app config dir)
@@ -123,16 +214,25 @@ pub mod config {
         /// Default retention count
         pub const RETENTION_COUNT: u32 = 30;
     }
+
+    pub mod export {
+        /// Directory name for scrubbed config exports (relative to app config dir)
+        pub const SUBDIR: &str = "exports";
+    }
 }
 
 /// Manager-specific constants (egui manager window)
 pub mod manager_ui {
     use egui;
 
-    /// Layout spacing
+    /// Layout spacing (comfortable density; see `COMPACT_SPACING_FACTOR`)
     pub const SECTION_SPACING: f32 = 15.0;
     pub const ITEM_SPACING: f32 = 8.0;
 
+    /// Compact density: layout spacing and widget padding are scaled by these factors
+    pub const COMPACT_SPACING_FACTOR: f32 = 0.5;
+    pub const COMPACT_WIDGET_FACTOR: f32 = 0.6;
+
     /// Status colors
     pub const STATUS_RUNNING: egui::Color32 = egui::Color32::from_rgb(100, 200, 100);
     pub const STATUS_STARTING: egui::Color32 = egui::Color32::from_rgb(255, 200, 0);
@@ -146,8 +246,45 @@ pub mod manager_ui {
     /// Daemon monitoring
     pub const DAEMON_CHECK_INTERVAL_MS: u64 = 500;
 
-    /// Debounce delay for config auto-saving, ie preview window position updates
-    pub const AUTO_SAVE_DELAY_MS: u64 = 1000;
+    /// How long a stopping daemon gets to clean up after SIGTERM before it is killed
+    pub const DAEMON_STOP_TIMEOUT_MS: u64 = 500;
+
+    /// How long to wait for the daemon to report its active clients before giving up
+    pub const CLIENT_LAYOUT_TIMEOUT_MS: u64 = 2000;
+
+    /// How long a fleet launch waits for a launched character to log in before starting the next
+    pub const FLEET_LAUNCH_APPEAR_TIMEOUT_SECS: u64 = 180;
+
+    /// Interval between IPC liveness pings to the daemon
+    pub const PING_INTERVAL_MS: u64 = 3000;
+
+    /// Quiet period before the last focused character is written to disk
+    pub const FOCUS_SAVE_DELAY_MS: u64 = 5000;
+
+    /// Delay before the first automatic reconnect after the daemon exits unexpectedly (doubles per attempt)
+    pub const RECONNECT_BASE_DELAY_MS: u64 = 1000;
+    pub const RECONNECT_MAX_DELAY_MS: u64 = 30_000;
+    pub const RECONNECT_MAX_ATTEMPTS: u32 = 5;
+
+    /// A connection that stayed up this long resets the reconnect attempt counter
+    pub const RECONNECT_STABLE_SECS: u64 = 60;
+
+    /// Interval between still captures when previews fall back to the Manager (no Composite)
+    pub const FALLBACK_CAPTURE_INTERVAL_MS: u64 = 2000;
+
+    /// How long the window inspector waits for a click before giving up
+    pub const INSPECTOR_TIMEOUT_SECS: u64 = 15;
+
+    /// Typing pause before a custom rule's patterns are matched against live windows
+    pub const RULE_PREVIEW_DEBOUNCE_MS: u64 = 300;
+
+    /// Synthetic input audit entries kept for the diagnostics view
+    pub const SYNTHETIC_INPUT_LOG_LIMIT: usize = 200;
+
+    /// X errors within the spike window that trigger a warning in the header
+    pub const X_ERROR_SPIKE_THRESHOLD: u64 = 50;
+    /// Sliding window for X error spike detection
+    pub const X_ERROR_SPIKE_WINDOW_SECS: u64 = 60;
 }
 
 /// Default configuration values
@@ -160,6 +297,16 @@ pub mod defaults {
 
         /// Default Manager window height in pixels
         pub const WINDOW_HEIGHT: u16 = 450;
+
+        /// Default Manager UI scale (zoom factor)
+        pub const UI_SCALE: f32 = 1.0;
+        pub const UI_SCALE_MIN: f32 = 0.5;
+        pub const UI_SCALE_MAX: f32 = 3.0;
+
+        /// Default body text size in points (egui's own default)
+        pub const UI_FONT_SIZE: f32 = 12.5;
+        pub const UI_FONT_SIZE_MIN: f32 = 8.0;
+        pub const UI_FONT_SIZE_MAX: f32 = 32.0;
     }
 
     /// Thumbnail window settings
@@ -182,6 +329,130 @@ pub mod defaults {
         pub const MIN_HEIGHT: u16 = 25;
         /// Maximum thumbnail height in pixels
         pub const MAX_HEIGHT: u16 = 2000;
+
+        /// Largest width change per scroll-wheel notch in pixels
+        pub const SCROLL_RESIZE_STEP_MAX: u16 = 200;
+
+        /// Default corner radius for rounded-rect thumbnails in pixels
+        pub const CORNER_RADIUS: u16 = 12;
+
+        /// Default corner icon size in pixels
+        pub const ICON_SIZE: u16 = 32;
+
+        /// Distance of the corner icon from the thumbnail edge in pixels
+        pub const ICON_MARGIN: i16 = 6;
+
+        /// Default cycle-order badge height in pixels
+        pub const CYCLE_BADGE_SIZE: u16 = 20;
+
+        /// Default status badge icon size in pixels
+        pub const STATUS_BADGE_SIZE: u16 = 14;
+
+        /// Gap between neighbouring status badges in pixels
+        pub const STATUS_BADGE_SPACING: i16 = 3;
+
+        /// Dimming laid over the frozen last frame of a minimized client
+        pub const FROZEN_FRAME_DIM: x11rb::protocol::render::Color =
+            x11rb::protocol::render::Color {
+                red: 0,
+                green: 0,
+                blue: 0,
+                alpha: 0x9999,
+            };
+
+        /// Cycle-order badge background (translucent black)
+        pub const CYCLE_BADGE_BACKGROUND: x11rb::protocol::render::Color =
+            x11rb::protocol::render::Color {
+                red: 0,
+                green: 0,
+                blue: 0,
+                alpha: 0xC000,
+            };
+
+        /// Size of a newly enabled crop region, from the client's top-left corner
+        pub const CROP_WIDTH: u16 = 400;
+        pub const CROP_HEIGHT: u16 = 300;
+
+        /// Default tint over unfocused thumbnails with the tint effect (#AARRGGBB)
+        pub const UNFOCUSED_TINT_COLOR: &str = "#66000000";
+
+        /// Pixel density the default thumbnail size is meant for (scale 1.0)
+        pub const REFERENCE_DPI: f32 = 96.0;
+        /// Upper bound for the HiDPI scale of default thumbnail sizes
+        pub const MAX_DPI_SCALE: f32 = 3.0;
+        /// Physical sizes giving a density outside this range are ignored as bogus
+        pub const MIN_PLAUSIBLE_DPI: f32 = 50.0;
+        pub const MAX_PLAUSIBLE_DPI: f32 = 600.0;
+        /// Range of the per-monitor multipliers for default thumbnail sizes
+        pub const MONITOR_SCALE_MIN: f32 = 0.25;
+        pub const MONITOR_SCALE_MAX: f32 = 4.0;
+
+        /// Default magnification of hovered thumbnails (1.0 = hover zoom off)
+        pub const HOVER_ZOOM: f32 = 1.0;
+
+        /// Default refresh interval for screenshot preview mode in seconds
+        pub const SNAPSHOT_INTERVAL_SECS: u32 = 10;
+    }
+
+    /// Advanced timing settings (tunable per profile for slow window managers)
+    pub mod timing {
+        /// Hysteresis before thumbnails hide after EVE loses focus
+        pub const FOCUS_LOSS_DELAY_MS: u32 = 100;
+        /// Upper bound for the focus-loss delay
+        pub const FOCUS_LOSS_DELAY_MAX_MS: u32 = 2000;
+        /// Fade duration given to configs from before it was configurable that had fading on
+        pub const FOCUS_LOSS_FADE_MS: u32 = 250;
+        /// Upper bound for the focus fade duration
+        pub const FOCUS_LOSS_FADE_MAX_MS: u32 = 2000;
+        /// Upper bound for the hover dwell before a thumbnail zooms
+        pub const HOVER_ZOOM_DELAY_MAX_MS: u32 = 2000;
+        /// Interval between opacity steps while fading in or out
+        pub const FOCUS_LOSS_FADE_STEP_MS: u64 = 25;
+        /// How often to re-check focus while an exempt (non-EVE) app keeps thumbnails shown
+        pub const HIDE_EXEMPT_RECHECK_MS: u64 = 500;
+        /// Minimum time between damage repaints of one live thumbnail (one per ~60 Hz frame)
+        pub const DAMAGE_FRAME_MS: u32 = 16;
+        /// Upper bound for the damage repaint interval
+        pub const DAMAGE_FRAME_MAX_MS: u32 = 1000;
+        /// How often unidentified windows are re-checked after MapNotify
+        pub const IDENTITY_RETRY_INTERVAL_MS: u32 = 500;
+        /// Lower bound for the identity re-check interval
+        pub const IDENTITY_RETRY_INTERVAL_MIN_MS: u32 = 50;
+        /// Upper bound for the identity re-check interval
+        pub const IDENTITY_RETRY_INTERVAL_MAX_MS: u32 = 5000;
+        /// How long after MapNotify an unidentified window keeps being re-checked
+        pub const IDENTITY_RETRY_WINDOW_MS: u32 = 5000;
+        /// Upper bound for the identity re-check window
+        pub const IDENTITY_RETRY_WINDOW_MAX_MS: u32 = 60_000;
+        /// How often tracked windows are checked for a lost event mask
+        pub const EVENT_MASK_AUDIT_INTERVAL_SECS: u64 = 30;
+        /// How often timelapse recordings are checked for due frames
+        pub const TIMELAPSE_CHECK_INTERVAL_SECS: u64 = 1;
+        /// Default interval between timelapse frames
+        pub const TIMELAPSE_INTERVAL_SECS: u32 = 30;
+        /// Frames kept per character; the oldest are deleted beyond this (a day at 30 s)
+        pub const TIMELAPSE_MAX_FRAMES: usize = 2880;
+        /// How often the visibility schedule is evaluated
+        pub const SCHEDULE_CHECK_INTERVAL_SECS: u64 = 30;
+
+        /// Minimize requests sent at once when minimizing other clients
+        pub const MINIMIZE_BATCH_SIZE: usize = 4;
+        /// Pause between minimize batches, so the window manager keeps up
+        pub const MINIMIZE_BATCH_INTERVAL_MS: u64 = 30;
+        /// Delay before checking that a client was actually minimized
+        pub const MINIMIZE_VERIFY_DELAY_MS: u64 = 200;
+        /// Minimize requests sent to one client before giving up
+        pub const MINIMIZE_MAX_ATTEMPTS: u8 = 3;
+
+        /// Quiet period before intermediate thumbnail position updates are reported to the Manager
+        pub const POSITION_DEBOUNCE_MS: u32 = 250;
+        /// Upper bound for the position report debounce
+        pub const POSITION_DEBOUNCE_MAX_MS: u32 = 2000;
+
+        /// Debounce delay for config auto-saving, ie preview window position updates
+        pub const AUTO_SAVE_DELAY_MS: u32 = 1000;
+        /// Upper bound for the auto-save debounce
+        pub const AUTO_SAVE_DELAY_MAX_MS: u32 = 10_000;
     }
 
     /// Border appearance settings
@@ -197,6 +468,21 @@ pub mod defaults {
 
         /// Default inactive border color
         pub const INACTIVE_COLOR: &str = "#707070";
+
+        /// Border color marking clients connected to a test server (Singularity, Thunderdome, ...)
+        pub const TEST_SERVER_COLOR: &str = "#FF8C00";
+
+        /// Fill of the padding between border and content (opaque black)
+        pub const PADDING_COLOR: x11rb::protocol::render::Color = x11rb::protocol::render::Color {
+            red: 0,
+            green: 0,
+            blue: 0,
+            alpha: 0xFFFF,
+        };
+
+        /// Upper bound of the padding and glow settings in the Manager, in pixels
+        pub const PADDING_MAX: u16 = 20;
+        pub const GLOW_MAX: u16 = 16;
     }
 
     /// Text overlay settings
@@ -213,6 +499,26 @@ pub mod defaults {
         /// Default text color
         pub const COLOR: &str = "#40FF00";
 
+        /// Always show character notes as a secondary label line
+        pub const SHOW_NOTES: bool = false;
+
+        /// Vertical gap between the name and notes lines in pixels
+        pub const NOTES_LINE_SPACING: i16 = 4;
+
+        /// Default color of the label outline or drop shadow (#AARRGGBB)
+        pub const EFFECT_COLOR: &str = "#CC000000";
+        /// Default drop shadow offset in pixels
+        pub const SHADOW_OFFSET: i16 = 2;
+        /// Largest drop shadow offset in pixels
+        pub const SHADOW_OFFSET_MAX: i16 = 10;
+        /// Default label outline width in pixels
+        pub const OUTLINE_WIDTH: u16 = 1;
+        /// Largest label outline width in pixels
+        pub const OUTLINE_WIDTH_MAX: u16 = 4;
+
+        /// Per-character font renderers kept loaded at once
+        pub const FONT_CACHE_CAPACITY: usize = 8;
+
         /// Preferred TrueType fonts (tried in order)
         /// First available font will be selected
         pub const FONT_CANDIDATES: &[&str] =
@@ -236,10 +542,22 @@ pub mod defaults {
         /// Minimize other clients when switching via hotkey
         pub const MINIMIZE_CLIENTS_ON_SWITCH: bool = false;
 
-        /// Require EVE window focus for hotkey activation
-        pub const HOTKEY_REQUIRE_EVE_FOCUS: bool = true;
+        /// Text shown on minimized clients
+        pub const MINIMIZED_TEXT: &str = "MINIMIZED";
+
+        /// Fill color of minimized clients
+        pub const MINIMIZED_BACKGROUND: &str = "#000000";
 
         /// Hide thumbnails when EVE window loses focus
         pub const HIDE_WHEN_NO_FOCUS: bool = false;
+
+        /// Size multiplier applied to the next cycle thumbnail while the peek hotkey is held
+        pub const PEEK_SCALE: f32 = 2.0;
+
+        /// How long the suspend hotkey releases the other hotkeys (e.g. while typing in chat)
+        pub const HOTKEY_SUSPEND_SECS: u32 = 10;
+
+        /// Leave test-server clients out of cycle group rotation
+        pub const EXCLUDE_TEST_SERVERS_FROM_CYCLE: bool = true;
     }
 }
@@ -1,9 +1,71 @@
 use ipc_channel::ipc::{IpcReceiver, IpcSender};
 use serde::{Deserialize, Serialize};
 
-use crate::config::DaemonConfig;
+use crate::common::types::EveServer;
+use crate::config::profile::LayoutSlot;
+use crate::config::{DaemonConfig, HotkeyBinding};
+
+/// Version of the Manager <-> Daemon message protocol.
+///
+/// Bump whenever a message is added or changed, including any change to the layout of
+/// `DaemonConfig` (e.g. a new `Profile` field). Messages are bincode-encoded by variant index,
+/// so new variants must be appended to the end of `ConfigMessage`/`DaemonMessage`.
+pub const PROTOCOL_VERSION: u32 = 21;
+
+/// Oldest peer protocol version this build can still talk to.
+///
+/// Protocol 20 made the Manager's hello precede its first config, and `DaemonConfig` has kept
+/// its bincode layout since. Messages added later are only sent once the peer has negotiated
+/// a version that knows them. Older peers refuse the handshake (the Manager then restarts the
+/// daemon from its own executable).
+pub const MIN_PROTOCOL_VERSION: u32 = 20;
+
+/// Protocol version that introduced `RunStartupActions`
+pub const STARTUP_ACTIONS_PROTOCOL_VERSION: u32 = 21;
+
+/// Handshake exchanged once after the IPC channels are connected.
+///
+/// Both sides send their hello as soon as the channels are up; the Manager's precedes its
+/// first config, and the daemon refuses a config that arrives without one. Peers that never
+/// send one predate the handshake (protocol 1).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolHello {
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    pub app_version: String,
+}
+
+impl ProtocolHello {
+    /// Hello describing this build
+    pub fn current() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Implied hello of a peer built before the handshake existed
+    pub fn legacy() -> Self {
+        Self {
+            protocol_version: 1,
+            min_protocol_version: 1,
+            app_version: "unknown".to_string(),
+        }
+    }
+
+    /// Highest protocol version both sides speak, or None if they have no common version
+    pub fn negotiate(&self, peer: &ProtocolHello) -> Option<u32> {
+        let version = self.protocol_version.min(peer.protocol_version);
+        let required = self.min_protocol_version.max(peer.min_protocol_version);
+        (version >= required).then_some(version)
+    }
+}
 
 /// Messages sent from Manager to Daemon
+///
+/// None of these carry input for EVE clients; the Manager has no way to make the Daemon send
+/// input anywhere (see `x11::input_guard`).
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ConfigMessage {
     /// Full state synchronization.
@@ -26,10 +88,240 @@ pub enum ConfigMessage {
         width: u16,
         height: u16,
     },
+
+    /// Asks the Daemon for the current on-screen layout of all active clients.
+    ///
+    /// Answered with `DaemonMessage::ClientLayout`.
+    RequestClientLayout,
+
+    /// Manager half of the version handshake, sent before the first `Full` (protocol 20)
+    Hello(ProtocolHello),
+
+    /// Liveness probe answered with `DaemonMessage::Pong` carrying the same sequence number.
+    ///
+    /// Unlike the Daemon's timer-driven heartbeat, a pong proves the event loop is still
+    /// processing Manager messages.
+    Ping(u64),
+
+    /// Asks for the current contents of a tracked client, answered with `DaemonMessage::Frame`.
+    CaptureFrame { character: String },
+
+    /// Asks which managed windows a custom rule with these patterns would match, answered
+    /// with `DaemonMessage::MatchingWindows` (protocol 10).
+    MatchWindows {
+        title_pattern: Option<String>,
+        class_pattern: Option<String>,
+    },
+
+    /// Shows or hides all previews, like the toggle-previews hotkey (protocol 12).
+    TogglePreviews,
+
+    /// Pauses or resumes the daemon (protocol 13)
+    SetPaused(bool),
+
+    /// Asks for the state of every running client; answered with
+    /// `DaemonMessage::SessionCaptured` (protocol 14)
+    CaptureSession,
+
+    /// Puts clients and thumbnails back as saved in a snapshot; answered with
+    /// `DaemonMessage::SessionRestored` (protocol 14)
+    RestoreSession {
+        clients: Vec<ClientSnapshot>,
+        focused: Option<String>,
+    },
+
+    /// Listens for one press of a binding with the daemon's hotkey backend, before it is saved;
+    /// answered with `DaemonMessage::HotkeyTested` (protocol 15)
+    TestHotkey(HotkeyBinding),
+
+    /// Shows text in the daemon's OSD, e.g. the profile a restarted daemon switched to
+    /// (protocol 16)
+    Announce(String),
+
+    /// Activates a character's client if it is running, e.g. the one focused before the last
+    /// shutdown (protocol 17)
+    FocusCharacter(String),
+
+    /// Runs the profile's startup actions. Only the first daemon of a Manager session gets it,
+    /// not one restarted after a crash (protocol 21).
+    RunStartupActions,
+}
+
+/// On-screen geometry of a single active client's thumbnail
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientGeometry {
+    pub name: String,
+    pub is_custom: bool,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// One running character's client as saved in a session snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientSnapshot {
+    pub name: String,
+    /// Client window geometry (root coordinates, excluding the WM frame)
+    pub window: LayoutSlot,
+    pub minimized: bool,
+    /// Thumbnail geometry (None when the character had no thumbnail)
+    pub thumbnail: Option<LayoutSlot>,
+}
+
+/// (title pattern, class pattern) of a custom rule preview
+pub type RulePatterns = (Option<String>, Option<String>);
+
+/// A managed window matched by a custom rule preview
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowMatch {
+    pub window: u32,
+    pub title: String,
+    pub class: String,
+}
+
+/// Wine environment an EVE client runs in, read from its process environment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WinePrefixInfo {
+    /// Wine prefix directory (`WINEPREFIX`, or `~/.wine` when unset)
+    pub prefix: Option<String>,
+    /// Proton build name when launched through Steam (e.g. "Proton 9.0")
+    pub proton: Option<String>,
+    /// Steam library the prefix lives in
+    pub steam_library: Option<String>,
+    /// Server the client connects to (from its launch arguments or prefix path)
+    pub server: EveServer,
+}
+
+/// Counters and gauges sampled by the Daemon on each heartbeat
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DaemonMetrics {
+    pub uptime_secs: u64,
+    /// X11 events processed since startup
+    pub events_total: u64,
+    /// X11 events processed per second since the previous sample
+    pub events_per_sec: f64,
+    /// Thumbnail composite (capture) operations since startup
+    pub composite_ops_total: u64,
+    /// X protocol errors received since startup
+    pub x_errors_total: u64,
+    /// Messages received from the Manager since startup
+    pub ipc_received_total: u64,
+    /// Messages sent to the Manager since startup
+    pub ipc_sent_total: u64,
+    /// Resident memory of the Daemon process
+    pub resident_memory_bytes: Option<u64>,
+}
+
+impl DaemonMetrics {
+    /// Renders the metrics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            out.push_str(&format!(
+                "# HELP eve_preview_{name} {help}\n# TYPE eve_preview_{name} {kind}\n\
+                 eve_preview_{name} {value}\n"
+            ));
+        };
+        metric(
+            "uptime_seconds",
+            "gauge",
+            "Seconds since the daemon started",
+            self.uptime_secs.to_string(),
+        );
+        metric(
+            "events_total",
+            "counter",
+            "X11 events processed",
+            self.events_total.to_string(),
+        );
+        metric(
+            "events_per_second",
+            "gauge",
+            "X11 events processed per second",
+            format!("{:.2}", self.events_per_sec),
+        );
+        metric(
+            "composite_operations_total",
+            "counter",
+            "Thumbnail composite operations",
+            self.composite_ops_total.to_string(),
+        );
+        metric(
+            "x_errors_total",
+            "counter",
+            "X protocol errors received",
+            self.x_errors_total.to_string(),
+        );
+        metric(
+            "ipc_received_total",
+            "counter",
+            "Messages received from the manager",
+            self.ipc_received_total.to_string(),
+        );
+        metric(
+            "ipc_sent_total",
+            "counter",
+            "Messages sent to the manager",
+            self.ipc_sent_total.to_string(),
+        );
+        if let Some(bytes) = self.resident_memory_bytes {
+            metric(
+                "resident_memory_bytes",
+                "gauge",
+                "Resident memory of the daemon process",
+                bytes.to_string(),
+            );
+        }
+        out
+    }
+}
+
+/// Asynchronous X errors of one kind caused by one request type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XErrorCount {
+    /// Error kind (e.g. "Window", "RenderPicture")
+    pub error: String,
+    /// Request that caused it (e.g. "GetProperty", "RENDER::Composite")
+    pub request: String,
+    pub count: u64,
+}
+
+/// Kind of synthetic input event sent to a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyntheticInputKind {
+    /// MotionNotify after activation so the client notices the pointer (XWayland workaround)
+    PointerRefresh,
+    /// Passthrough click from a thumbnail
+    Click { button: u8 },
+}
+
+impl SyntheticInputKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PointerRefresh => "pointer refresh",
+            Self::Click { .. } => "click",
+        }
+    }
+
+    pub fn is_click(&self) -> bool {
+        matches!(self, Self::Click { .. })
+    }
+}
+
+/// Audit log entry for one synthetic input event the Daemon sent (or refused to send)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntheticInput {
+    /// Wall-clock time in milliseconds since the Unix epoch
+    pub unix_ms: u64,
+    pub kind: SyntheticInputKind,
+    pub window: u32,
+    /// Refused by the broadcast guard (nothing was sent)
+    pub refused: bool,
 }
 
 /// Messages sent from Daemon to Manager
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DaemonMessage {
     /// Log message from daemon
     Log {
@@ -61,8 +353,162 @@ pub enum DaemonMessage {
     RequestProfileSwitch(String),
     /// Periodic heartbeat (optional)
     Heartbeat,
+    /// Snapshot of all active clients and their thumbnail geometry (reply to `RequestClientLayout`)
+    ClientLayout(Vec<ClientGeometry>),
+    /// Daemon half of the version handshake, sent right after bootstrap
+    Hello(ProtocolHello),
+    /// Reply to `ConfigMessage::Ping`
+    Pong(u64),
+    /// Wine prefix of a newly detected EVE client (protocol 4, server added in 5)
+    ClientPrefix {
+        name: String,
+        info: WinePrefixInfo,
+    },
+    /// Synthetic input sent or refused since the last report (protocol 6)
+    SyntheticInput(Vec<SyntheticInput>),
+    /// Reply to `CaptureFrame`: the client's current frame as PNG, or why it couldn't be captured
+    Frame {
+        character: String,
+        png: Result<Vec<u8>, String>,
+    },
+    /// Metrics sample, sent with every heartbeat (protocol 8)
+    Metrics(DaemonMetrics),
+    /// X errors received since the previous report, most frequent first (protocol 9)
+    XErrors(Vec<XErrorCount>),
+    /// Reply to `MatchWindows`, echoing the patterns it was computed for (protocol 10)
+    MatchingWindows {
+        title_pattern: Option<String>,
+        class_pattern: Option<String>,
+        windows: Vec<WindowMatch>,
+    },
+    /// Geometry of one custom source instance, sent alongside its `PositionChanged`
+    /// (protocol 11)
+    SourceGeometryChanged {
+        rule_id: u32,
+        instance: u32,
+        x: i16,
+        y: i16,
+        width: u16,
+        height: u16,
+    },
+    /// The daemon was paused or resumed, e.g. by the pause hotkey (protocol 13)
+    PausedChanged(bool),
+    /// Reply to `CaptureSession` (protocol 14)
+    SessionCaptured {
+        clients: Vec<ClientSnapshot>,
+        focused: Option<String>,
+    },
+    /// Reply to `RestoreSession`, listing the characters that weren't running (protocol 14)
+    SessionRestored {
+        missing: Vec<String>,
+    },
+    /// Reply to `TestHotkey` (protocol 15)
+    HotkeyTested {
+        binding: HotkeyBinding,
+        result: HotkeyTestResult,
+    },
+    /// An EVE client got focus; focus moving to other applications is not reported
+    /// (protocol 17)
+    FocusChanged(String),
+    /// A tracked client went away (closed, marked `_EPM_IGNORE`, or its character logged out)
+    /// (protocol 18)
+    CharacterRemoved {
+        name: String,
+        is_custom: bool,
+    },
+}
+
+/// Outcome of a `TestHotkey` request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HotkeyTestResult {
+    /// The daemon's backend saw the binding pressed
+    Received,
+    /// The running profile already binds this key, so the daemon is listening for it already
+    AlreadyBound,
+    /// The binding couldn't be grabbed or wasn't pressed in time
+    Failed(String),
 }
 
 /// The bootstrap payload sent over the initial server channel.
 /// Contains the channel for receiving config updates and the channel for sending status updates.
 pub type BootstrapMessage = (IpcSender<ConfigMessage>, IpcReceiver<DaemonMessage>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hello(protocol_version: u32, min_protocol_version: u32) -> ProtocolHello {
+        ProtocolHello {
+            protocol_version,
+            min_protocol_version,
+            app_version: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_negotiate_same_version() {
+        let current = ProtocolHello::current();
+        assert_eq!(current.negotiate(&current), Some(PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_common_subset() {
+        assert_eq!(hello(3, 1).negotiate(&ProtocolHello::legacy()), Some(1));
+        assert_eq!(ProtocolHello::legacy().negotiate(&hello(3, 1)), Some(1));
+    }
+
+    #[test]
+    fn test_negotiate_rejects_incompatible_peers() {
+        // Peer dropped support for our version
+        assert_eq!(hello(2, 1).negotiate(&hello(5, 3)), None);
+        // We dropped support for the peer's version
+        assert_eq!(hello(5, 3).negotiate(&ProtocolHello::legacy()), None);
+    }
+
+    #[test]
+    fn test_negotiate_with_older_compatible_build() {
+        let current = ProtocolHello::current();
+        let older = hello(MIN_PROTOCOL_VERSION, MIN_PROTOCOL_VERSION);
+        const { assert!(MIN_PROTOCOL_VERSION < PROTOCOL_VERSION) };
+        assert_eq!(current.negotiate(&older), Some(MIN_PROTOCOL_VERSION));
+        assert_eq!(older.negotiate(&current), Some(MIN_PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn test_negotiate_rejects_builds_before_minimum() {
+        let current = ProtocolHello::current();
+        let older = hello(MIN_PROTOCOL_VERSION - 1, 1);
+        assert_eq!(current.negotiate(&older), None);
+        assert_eq!(older.negotiate(&current), None);
+        assert_eq!(current.negotiate(&ProtocolHello::legacy()), None);
+    }
+
+    #[test]
+    fn test_startup_flag_stays_off_the_wire() {
+        // Ephemeral fields stay off the wire, so older Managers' configs still decode
+        let mut config = DaemonConfig::for_profile(crate::config::profile::Profile::default());
+        config.run_startup_actions = true;
+        let (tx, rx) = ipc_channel::ipc::channel().unwrap();
+        tx.send(ConfigMessage::Full(Box::new(config))).unwrap();
+        match rx.recv().unwrap() {
+            ConfigMessage::Full(config) => assert!(!config.run_startup_actions),
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_metrics_prometheus_format() {
+        let metrics = DaemonMetrics {
+            events_total: 42,
+            events_per_sec: 1.5,
+            resident_memory_bytes: None,
+            ..Default::default()
+        };
+        let text = metrics.to_prometheus();
+        assert!(
+            text.contains("# TYPE eve_preview_events_total counter\neve_preview_events_total 42\n")
+        );
+        assert!(text.contains("eve_preview_events_per_second 1.50\n"));
+        assert!(!text.contains("resident_memory"));
+    }
+}
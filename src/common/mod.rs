@@ -6,4 +6,5 @@ pub mod color;
 pub mod constants;
 pub mod debug;
 pub mod ipc;
+pub mod shm;
 pub mod types;
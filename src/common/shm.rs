@@ -0,0 +1,31 @@
+//! Anonymous files for shared-memory buffers
+//!
+//! Used by the Wayland `wl_shm` buffers and the X11 MIT-SHM uploads. Callers move pixels with
+//! `pread`/`pwrite` rather than mapping the file, which keeps them free of unsafe code.
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::common::constants::config;
+
+/// Creates a file of `size` bytes that is unlinked right away, so it disappears with its fd
+pub fn anonymous_file(size: u64) -> Result<File> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let dir = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+    let path = dir.join(format!(
+        "{}-shm-{}-{}",
+        config::APP_DIR,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .context(format!("Failed to create shm file {}", path.display()))?;
+    let _ = std::fs::remove_file(&path);
+    file.set_len(size).context("Failed to size shm file")?;
+    Ok(file)
+}
@@ -0,0 +1,148 @@
+//! Character alias templates
+//!
+//! Aliases map a character name to the text shown on thumbnails and in the Manager. An alias is
+//! a template that may reference the character:
+//!
+//! - `{name}`: full character name
+//! - `{first}` / `{last}`: first and last word of the name
+//! - `{initials}`: first letter of every word
+//! - `{order}`: the character's ordering key (empty if unset)
+//!
+//! `{{` and `}}` produce literal braces; unknown variables are kept as written.
+
+use std::cmp::Ordering;
+
+use super::CharacterSettings;
+
+/// Expands an alias template for `character_name`
+pub fn expand_template(template: &str, character_name: &str, order: Option<i32>) -> String {
+    let mut out = String::with_capacity(template.len() + character_name.len());
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        if rest.starts_with('}') {
+            out.push('}');
+            rest = &rest[1..];
+            continue;
+        }
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let words = || character_name.split_whitespace();
+        match &rest[1..end] {
+            "name" => out.push_str(character_name),
+            "first" => out.push_str(words().next().unwrap_or_default()),
+            "last" => out.push_str(words().next_back().unwrap_or_default()),
+            "initials" => out.extend(words().filter_map(|w| w.chars().next())),
+            "order" => {
+                if let Some(order) = order {
+                    out.push_str(&order.to_string());
+                }
+            }
+            _ => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Display name for a character.
+///
+/// The character's own alias wins; otherwise the profile-wide `default_template` is used
+/// (an empty template means "show the plain name").
+pub fn display_name(
+    default_template: &str,
+    settings: Option<&CharacterSettings>,
+    character_name: &str,
+) -> String {
+    let order = settings.and_then(|s| s.display_order);
+    let template = settings
+        .and_then(|s| s.alias.as_deref())
+        .filter(|alias| !alias.trim().is_empty())
+        .unwrap_or(default_template);
+
+    // Logged-out clients have no name to decorate
+    if template.trim().is_empty() || character_name.is_empty() {
+        return character_name.to_string();
+    }
+    let expanded = expand_template(template, character_name, order);
+    if expanded.trim().is_empty() {
+        character_name.to_string()
+    } else {
+        expanded
+    }
+}
+
+/// Listing order for characters: explicit ordering keys first (ascending), then by display name
+pub fn compare_for_display(a: (Option<i32>, &str), b: (Option<i32>, &str)) -> Ordering {
+    let rank = |order: Option<i32>| (order.is_none(), order.unwrap_or_default());
+    rank(a.0)
+        .cmp(&rank(b.0))
+        .then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_template_variables() {
+        assert_eq!(
+            expand_template("[{order}] Hauler – {name}", "Jane Doe", Some(1)),
+            "[1] Hauler – Jane Doe"
+        );
+        assert_eq!(
+            expand_template("{first}/{last}/{initials}", "Jane Q Doe", None),
+            "Jane/Doe/JQD"
+        );
+        assert_eq!(expand_template("#{order}", "Jane", None), "#");
+    }
+
+    #[test]
+    fn test_expand_template_escapes_and_unknown() {
+        assert_eq!(
+            expand_template("{{name}} {unknown} {name", "Jane", None),
+            "{name} {unknown} {name"
+        );
+    }
+
+    #[test]
+    fn test_display_name_precedence() {
+        let mut settings = CharacterSettings::new(0, 0, 0, 0);
+        assert_eq!(display_name("", Some(&settings), "Jane"), "Jane");
+        assert_eq!(display_name("> {name}", Some(&settings), "Jane"), "> Jane");
+
+        settings.alias = Some("Scout".to_string());
+        assert_eq!(display_name("> {name}", Some(&settings), "Jane"), "Scout");
+
+        // A template that expands to nothing falls back to the name
+        settings.alias = Some("{order}".to_string());
+        assert_eq!(display_name("", Some(&settings), "Jane"), "Jane");
+    }
+
+    #[test]
+    fn test_compare_for_display() {
+        let mut entries = [
+            (None, "bravo"),
+            (Some(2), "zulu"),
+            (None, "Alpha"),
+            (Some(1), "yankee"),
+        ];
+        entries.sort_by(|a, b| compare_for_display(*a, *b));
+        assert_eq!(
+            entries.iter().map(|e| e.1).collect::<Vec<_>>(),
+            ["yankee", "zulu", "Alpha", "bravo"]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Character-specific types and settings for EVE Online windows
 
-use super::geometry::{Dimensions, Position};
+use super::geometry::{CropRegion, Dimensions, Position, ThumbnailShape};
 use serde::{Deserialize, Serialize};
 
 /// EVE Online window type classification
@@ -61,6 +61,33 @@ pub enum PreviewMode {
     Live,
     /// Static solid color fill
     Static { color: String },
+    /// Live content refreshed at most once every `interval_secs` (low-power)
+    Snapshot { interval_secs: u32 },
+}
+
+impl PreviewMode {
+    /// Minimum time between captures, or None if every damage event is rendered
+    pub fn snapshot_interval(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Snapshot { interval_secs } => Some(std::time::Duration::from_secs(
+                u64::from(*interval_secs).max(1),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Small icon composited into the top-right corner of a thumbnail
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CornerIcon {
+    /// No icon (default)
+    #[default]
+    None,
+    /// Character portrait from the EVE image server (cached on disk)
+    Portrait { character_id: u64 },
+    /// Custom PNG file
+    Custom { path: String },
 }
 
 /// Per-character settings: position and thumbnail dimensions
@@ -80,12 +107,33 @@ pub struct CharacterSettings {
     pub override_active_border_size: Option<u16>,
     pub override_inactive_border_size: Option<u16>,
     pub override_text_color: Option<String>,
+    /// Label font family (None = use profile setting)
+    pub override_text_font: Option<String>,
+    /// Label size in pixels (None = use profile setting)
+    pub override_text_size: Option<u16>,
     pub preview_mode: PreviewMode,
     /// If true, this character is exempt from minimize-on-switch behavior
     pub exempt_from_minimize: bool,
     /// Per-character override for preview rendering.
     /// None = use global setting, Some(true) = always show, Some(false) = always hide
     pub override_render_preview: Option<bool>,
+    /// If true, this thumbnail stays visible when hide-when-no-focus hides the others
+    pub keep_visible_without_focus: bool,
+    /// If true, this thumbnail stays visible when the toggle hotkey, panic or schedule hide all
+    pub always_visible: bool,
+    /// Per-character override for the thumbnail shape (None = use profile setting)
+    pub override_shape: Option<ThumbnailShape>,
+    /// Icon drawn in the top-right corner of the thumbnail
+    pub corner_icon: CornerIcon,
+    /// Ordering key used when listing characters (lower first, unset last) and as `{order}` in alias templates
+    pub display_order: Option<i32>,
+    /// Stacking priority: where thumbnails overlap, higher values stay on top. 0 is the
+    /// default; negative values go below thumbnails left at 0.
+    pub stack_priority: i32,
+    /// Save a timelapse frame of this client every N seconds (None = not recording)
+    pub timelapse_interval_secs: Option<u32>,
+    /// Part of the client shown in the thumbnail (None = the whole client)
+    pub crop: Option<CropRegion>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -111,11 +159,31 @@ struct CharacterSettingsProxy {
     #[serde(default)]
     override_text_color: Option<String>,
     #[serde(default)]
+    override_text_font: Option<String>,
+    #[serde(default)]
+    override_text_size: Option<u16>,
+    #[serde(default)]
     preview_mode: PreviewMode,
     #[serde(default)]
     exempt_from_minimize: bool,
     #[serde(default)]
     override_render_preview: Option<bool>,
+    #[serde(default)]
+    keep_visible_without_focus: bool,
+    #[serde(default)]
+    always_visible: bool,
+    #[serde(default)]
+    override_shape: Option<ThumbnailShape>,
+    #[serde(default)]
+    corner_icon: CornerIcon,
+    #[serde(default)]
+    display_order: Option<i32>,
+    #[serde(default)]
+    stack_priority: i32,
+    #[serde(default)]
+    timelapse_interval_secs: Option<u32>,
+    #[serde(default)]
+    crop: Option<CropRegion>,
 }
 
 impl From<CharacterSettings> for CharacterSettingsProxy {
@@ -132,9 +200,19 @@ impl From<CharacterSettings> for CharacterSettingsProxy {
             override_active_border_size: settings.override_active_border_size,
             override_inactive_border_size: settings.override_inactive_border_size,
             override_text_color: settings.override_text_color,
+            override_text_font: settings.override_text_font,
+            override_text_size: settings.override_text_size,
             preview_mode: settings.preview_mode,
             exempt_from_minimize: settings.exempt_from_minimize,
             override_render_preview: settings.override_render_preview,
+            keep_visible_without_focus: settings.keep_visible_without_focus,
+            always_visible: settings.always_visible,
+            override_shape: settings.override_shape,
+            corner_icon: settings.corner_icon,
+            display_order: settings.display_order,
+            stack_priority: settings.stack_priority,
+            timelapse_interval_secs: settings.timelapse_interval_secs,
+            crop: settings.crop,
         }
     }
 }
@@ -155,9 +233,19 @@ impl From<CharacterSettingsProxy> for CharacterSettings {
             override_active_border_size: proxy.override_active_border_size,
             override_inactive_border_size: proxy.override_inactive_border_size,
             override_text_color: proxy.override_text_color,
+            override_text_font: proxy.override_text_font,
+            override_text_size: proxy.override_text_size,
             preview_mode: proxy.preview_mode,
             exempt_from_minimize: proxy.exempt_from_minimize,
             override_render_preview: proxy.override_render_preview,
+            keep_visible_without_focus: proxy.keep_visible_without_focus,
+            always_visible: proxy.always_visible,
+            override_shape: proxy.override_shape,
+            corner_icon: proxy.corner_icon,
+            display_order: proxy.display_order,
+            stack_priority: proxy.stack_priority,
+            timelapse_interval_secs: proxy.timelapse_interval_secs,
+            crop: proxy.crop,
         }
     }
 }
@@ -175,9 +263,19 @@ impl CharacterSettings {
             override_active_border_size: None,
             override_inactive_border_size: None,
             override_text_color: None,
+            override_text_font: None,
+            override_text_size: None,
             preview_mode: PreviewMode::default(),
             exempt_from_minimize: false,
             override_render_preview: None,
+            keep_visible_without_focus: false,
+            always_visible: false,
+            override_shape: None,
+            corner_icon: CornerIcon::default(),
+            display_order: None,
+            stack_priority: 0,
+            timelapse_interval_secs: None,
+            crop: None,
         }
     }
 
@@ -190,6 +288,23 @@ impl CharacterSettings {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_preview_mode_snapshot_interval() {
+        assert_eq!(PreviewMode::Live.snapshot_interval(), None);
+        assert_eq!(
+            PreviewMode::Snapshot { interval_secs: 30 }.snapshot_interval(),
+            Some(std::time::Duration::from_secs(30))
+        );
+        // A zero interval would mean live rendering with extra bookkeeping
+        assert_eq!(
+            PreviewMode::Snapshot { interval_secs: 0 }.snapshot_interval(),
+            Some(std::time::Duration::from_secs(1))
+        );
+
+        let json = serde_json::to_string(&PreviewMode::Snapshot { interval_secs: 5 }).unwrap();
+        assert_eq!(json, r#"{"snapshot":{"interval_secs":5}}"#);
+    }
+
     #[test]
     fn test_thumbnail_state_normal_unfocused() {
         let state = ThumbnailState::Normal { focused: false };
@@ -0,0 +1,236 @@
+//! Rules recognizing game client windows by title (and optionally class)
+
+use regex_automata::meta::Regex;
+use regex_automata::util::primitives::PatternID;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tracing::{debug, warn};
+
+use super::character::EveWindowType;
+use crate::common::constants::eve;
+
+/// Recognizes one kind of game client window.
+///
+/// The EVE rule ships by default; extra rules let the same preview engine track other Wine
+/// games or EVE forks without code changes. Rules are tried in order and the first match wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientDetectionRule {
+    /// Display name (settings UI and logs only)
+    pub name: String,
+    /// Title prefix of a logged-in client; the rest of the title is the character name
+    pub title_prefix: String,
+    /// Regexes for localized titles; the `character` named capture is the character name
+    /// (e.g. `^星战前夜 - (?P<character>.+)$`)
+    pub title_patterns: Vec<String>,
+    /// Exact title of a logged-out client (character select), if the game has one
+    pub logged_out_title: Option<String>,
+    /// Case-insensitive substring the WM_CLASS must contain (None = any class)
+    pub class_pattern: Option<String>,
+    /// Character names containing one of these (case-insensitive) are ignored
+    pub ignored_names: Vec<String>,
+}
+
+impl ClientDetectionRule {
+    /// The built-in EVE Online rule
+    pub fn eve() -> Self {
+        Self {
+            name: "EVE Online".to_string(),
+            title_prefix: eve::WINDOW_TITLE_PREFIX.to_string(),
+            title_patterns: Vec::new(),
+            logged_out_title: Some(eve::LOGGED_OUT_TITLE.to_string()),
+            class_pattern: None,
+            // Steam's container window briefly carries an "EVE - steam_app_<id>" title
+            ignored_names: vec!["steam_app_".to_string()],
+        }
+    }
+
+    /// Whether matching this rule requires the window's WM_CLASS
+    pub fn needs_class(&self) -> bool {
+        self.class_pattern.is_some()
+    }
+
+    /// Identifies a window from its title and class (the class is only consulted when the rule
+    /// has a class pattern)
+    pub fn identify(&self, title: &str, class: Option<&str>) -> Option<EveWindowType> {
+        self.explain(title, class).ok()
+    }
+
+    /// Like [`Self::identify`], but says why a window doesn't match (window inspector)
+    pub fn explain(&self, title: &str, class: Option<&str>) -> Result<EveWindowType, String> {
+        if let Some(pattern) = &self.class_pattern
+            && !class.is_some_and(|c| c.to_lowercase().contains(&pattern.to_lowercase()))
+        {
+            return Err(format!("Class doesn't contain \"{}\"", pattern));
+        }
+
+        if let Some(name) = self.character_name(title) {
+            let lower = name.to_lowercase();
+            if let Some(ignored) = self
+                .ignored_names
+                .iter()
+                .map(|i| i.trim())
+                .find(|i| !i.is_empty() && lower.contains(&i.to_lowercase()))
+            {
+                debug!(rule = %self.name, name = %name, ignored = %ignored, "Ignored client title");
+                return Err(format!(
+                    "Name \"{}\" is ignored (contains \"{}\")",
+                    name, ignored
+                ));
+            }
+            return Ok(EveWindowType::LoggedIn(name));
+        }
+
+        if self.logged_out_title.as_deref() == Some(title) {
+            return Ok(EveWindowType::LoggedOut);
+        }
+        Err(if self.title_patterns.is_empty() {
+            format!("Title doesn't start with \"{}\"", self.title_prefix)
+        } else {
+            format!(
+                "Title doesn't start with \"{}\" or match a title pattern",
+                self.title_prefix
+            )
+        })
+    }
+
+    /// Character name of a logged-in title, from the prefix or the first matching pattern
+    fn character_name(&self, title: &str) -> Option<String> {
+        if !self.title_prefix.is_empty()
+            && let Some(name) = title.strip_prefix(&self.title_prefix)
+        {
+            return Some(name.to_string());
+        }
+        self.title_patterns
+            .iter()
+            .filter(|p| !p.is_empty())
+            .find_map(|p| capture_character(p, title))
+    }
+}
+
+/// Name of the capture group holding the character name in title patterns
+const CHARACTER_CAPTURE: &str = "character";
+
+/// Compiled title patterns (None = invalid, warned once)
+static PATTERN_CACHE: LazyLock<Mutex<HashMap<String, Option<Regex>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Checks that a title pattern compiles and has a `character` named capture
+pub fn validate_title_pattern(pattern: &str) -> Result<(), String> {
+    let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+    if regex
+        .group_info()
+        .to_index(PatternID::ZERO, CHARACTER_CAPTURE)
+        .is_none()
+    {
+        return Err(format!(
+            "Pattern needs a (?P<{}>...) capture",
+            CHARACTER_CAPTURE
+        ));
+    }
+    Ok(())
+}
+
+/// Runs a title pattern and returns its non-empty `character` capture
+fn capture_character(pattern: &str, title: &str) -> Option<String> {
+    let regex = {
+        let mut cache = PATTERN_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .entry(pattern.to_string())
+            .or_insert_with(|| match validate_title_pattern(pattern) {
+                Ok(()) => Regex::new(pattern).ok(),
+                Err(e) => {
+                    warn!(pattern = %pattern, error = %e, "Ignoring invalid title pattern");
+                    None
+                }
+            })
+            .clone()?
+    };
+
+    let mut caps = regex.create_captures();
+    regex.captures(title, &mut caps);
+    let span = caps.get_group_by_name(CHARACTER_CAPTURE)?;
+    let name = &title[span.range()];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Identifies a window with the first matching rule
+pub fn identify_client(
+    rules: &[ClientDetectionRule],
+    title: &str,
+    class: Option<&str>,
+) -> Option<EveWindowType> {
+    rules.iter().find_map(|rule| rule.identify(title, class))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eve_rule() {
+        let rules = [ClientDetectionRule::eve()];
+        assert_eq!(
+            identify_client(&rules, "EVE - Jane Doe", None),
+            Some(EveWindowType::LoggedIn("Jane Doe".to_string()))
+        );
+        assert_eq!(
+            identify_client(&rules, "EVE", None),
+            Some(EveWindowType::LoggedOut)
+        );
+        assert_eq!(identify_client(&rules, "EVE - steam_app_8500", None), None);
+        assert_eq!(identify_client(&rules, "Firefox", None), None);
+        assert_eq!(identify_client(&[], "EVE - Jane Doe", None), None);
+    }
+
+    #[test]
+    fn test_class_pattern_and_order() {
+        let other = ClientDetectionRule {
+            name: "Other".to_string(),
+            title_prefix: "Game: ".to_string(),
+            class_pattern: Some("game.exe".to_string()),
+            ..Default::default()
+        };
+        let rules = [ClientDetectionRule::eve(), other];
+
+        assert_eq!(
+            identify_client(&rules, "Game: Bob", Some("GAME.EXE")),
+            Some(EveWindowType::LoggedIn("Bob".to_string()))
+        );
+        assert_eq!(identify_client(&rules, "Game: Bob", Some("steam")), None);
+        assert_eq!(identify_client(&rules, "Game: Bob", None), None);
+        // An empty prefix never matches every window
+        assert_eq!(
+            ClientDetectionRule::default().identify("Anything", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_title_patterns() {
+        let rule = ClientDetectionRule {
+            name: "Serenity".to_string(),
+            title_patterns: vec![
+                "(unclosed".to_string(),
+                "^星战前夜 - (?P<character>.+)$".to_string(),
+            ],
+            logged_out_title: Some("星战前夜".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            rule.identify("星战前夜 - 张三", None),
+            Some(EveWindowType::LoggedIn("张三".to_string()))
+        );
+        assert_eq!(
+            rule.identify("星战前夜", None),
+            Some(EveWindowType::LoggedOut)
+        );
+        assert_eq!(rule.identify("EVE - Jane Doe", None), None);
+
+        assert!(validate_title_pattern("^EVE - (?P<character>.+)$").is_ok());
+        assert!(validate_title_pattern("^EVE - (.+)$").is_err());
+        assert!(validate_title_pattern("(unclosed").is_err());
+    }
+}
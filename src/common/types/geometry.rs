@@ -113,6 +113,62 @@ impl TextOffset {
     }
 }
 
+/// Part of a client window shown in its thumbnail, in client pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct CropRegion {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl CropRegion {
+    /// Create a crop region
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The whole of a `size`-sized window
+    pub fn full(size: Dimensions) -> Self {
+        Self::new(0, 0, size.width, size.height)
+    }
+
+    /// Region to capture from a `src_size` client: `crop` clipped to the client, or the whole
+    /// client without a crop or when less than 2x2 pixels of it are left (e.g. after the client
+    /// shrank)
+    pub fn resolve(crop: Option<Self>, src_size: Dimensions) -> Self {
+        crop.and_then(|crop| {
+            let width = crop.width.min(src_size.width.saturating_sub(crop.x));
+            let height = crop.height.min(src_size.height.saturating_sub(crop.y));
+            (width > 1 && height > 1).then_some(Self::new(crop.x, crop.y, width, height))
+        })
+        .unwrap_or(Self::full(src_size))
+    }
+
+    /// Size of the region
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions::new(self.width, self.height)
+    }
+}
+
+/// Outline of a thumbnail window, applied with the XShape extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailShape {
+    /// Plain rectangle (no shape mask)
+    #[default]
+    Rectangle,
+    /// Rectangle with rounded corners (radius from profile)
+    RoundedRect,
+    /// Ellipse inscribed in the thumbnail (a circle for square thumbnails)
+    Circle,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,4 +259,29 @@ mod tests {
         assert_eq!(offset2.x, 15);
         assert_eq!(offset2.y, 25);
     }
+
+    #[test]
+    fn test_crop_region_resolve() {
+        let src = Dimensions::new(1920, 1080);
+        assert_eq!(
+            CropRegion::resolve(None, src),
+            CropRegion::new(0, 0, 1920, 1080)
+        );
+
+        let crop = CropRegion::new(1500, 200, 300, 600);
+        assert_eq!(CropRegion::resolve(Some(crop), src), crop);
+
+        // Clipped to a smaller client
+        let small = Dimensions::new(1600, 700);
+        assert_eq!(
+            CropRegion::resolve(Some(crop), small),
+            CropRegion::new(1500, 200, 100, 500)
+        );
+
+        // Nothing left of the crop: whole client
+        assert_eq!(
+            CropRegion::resolve(Some(crop), Dimensions::new(1280, 720)),
+            CropRegion::new(0, 0, 1280, 720)
+        );
+    }
 }
@@ -2,9 +2,14 @@
 //!
 //! Refactored into sub-modules for better organization.
 
+pub mod alias;
 pub mod character;
+pub mod detection;
 pub mod geometry;
+pub mod server;
 
 // Re-export specific types to maintain compatibility
-pub use character::{CharacterSettings, EveWindowType, PreviewMode, ThumbnailState};
-pub use geometry::{Dimensions, Position, TextOffset};
+pub use character::{CharacterSettings, CornerIcon, EveWindowType, PreviewMode, ThumbnailState};
+pub use detection::{ClientDetectionRule, identify_client, validate_title_pattern};
+pub use geometry::{CropRegion, Dimensions, Position, TextOffset, ThumbnailShape};
+pub use server::EveServer;
@@ -0,0 +1,94 @@
+//! EVE server identification
+//!
+//! Test-server clients (Singularity, Thunderdome, Duality) run the same character names as
+//! Tranquility, so they are told apart by the launch arguments or the install/prefix path.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EveServer {
+    #[default]
+    Tranquility,
+    Singularity,
+    Thunderdome,
+    Duality,
+}
+
+impl EveServer {
+    pub fn is_test_server(self) -> bool {
+        self != EveServer::Tranquility
+    }
+
+    /// Short tag drawn on thumbnails of test-server clients
+    pub fn badge(self) -> &'static str {
+        match self {
+            EveServer::Tranquility => "TQ",
+            EveServer::Singularity => "SISI",
+            EveServer::Thunderdome => "TD",
+            EveServer::Duality => "DUALITY",
+        }
+    }
+
+    /// Server selected by a `/server:<name>` client argument
+    pub fn from_launch_args<'a>(args: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        args.into_iter().find_map(|arg| {
+            let lower = arg.to_lowercase();
+            let (_, server) = lower.split_once("/server:")?;
+            Self::from_name(server)
+        })
+    }
+
+    /// Server guessed from an install or Wine prefix path (e.g. `~/Games/eve-sisi`)
+    pub fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_lowercase();
+        lower
+            .split(['/', '\\', '-', '_', '.', ' '])
+            .find_map(Self::from_name)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.starts_with("singularity") || name == "sisi" {
+            Some(EveServer::Singularity)
+        } else if name.starts_with("thunderdome") {
+            Some(EveServer::Thunderdome)
+        } else if name.starts_with("duality") {
+            Some(EveServer::Duality)
+        } else if name.starts_with("tranquility") || name == "tq" {
+            Some(EveServer::Tranquility)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_launch_args() {
+        assert_eq!(
+            EveServer::from_launch_args(["C:\\EVE\\bin64\\exefile.exe", "/server:Singularity"]),
+            Some(EveServer::Singularity)
+        );
+        assert_eq!(
+            EveServer::from_launch_args(["exefile.exe", "/noconsole"]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            EveServer::from_path("/home/pilot/Games/eve-sisi/pfx"),
+            Some(EveServer::Singularity)
+        );
+        assert_eq!(
+            EveServer::from_path("/home/pilot/EVE_Thunderdome"),
+            Some(EveServer::Thunderdome)
+        );
+        // Substrings of other words don't count
+        assert_eq!(EveServer::from_path("/home/sisinski/.wine"), None);
+    }
+}
@@ -126,7 +126,7 @@ impl BackupManager {
         }
 
         // Sort by timestamp descending (newest first)
-        backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
 
         Ok(backups)
     }
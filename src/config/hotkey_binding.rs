@@ -99,6 +99,17 @@ impl HotkeyBinding {
             && self.super_key == super_key
     }
 
+    /// Whether `other` is the same key and modifiers (source devices aside)
+    pub fn same_keys(&self, other: &HotkeyBinding) -> bool {
+        self.matches(
+            other.key_code,
+            other.ctrl,
+            other.shift,
+            other.alt,
+            other.super_key,
+        )
+    }
+
     /// Convert to array format for JSON serialization
     /// Format: [modifier_keys..., main_key]
     /// Example: ["KEY_LEFTSHIFT", "KEY_TAB"]
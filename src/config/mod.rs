@@ -8,8 +8,14 @@ pub mod backup;
 pub mod hotkey_binding;
 pub mod profile;
 pub mod runtime;
+pub mod scrub;
 pub mod serialization;
+pub mod session;
 
 pub use hotkey_binding::HotkeyBinding;
-pub use profile::HotkeyBackendType;
+pub use profile::{
+    BadgeCorner, BorderStyle, CaptureBackendType, HotkeyBackendType, HotkeyFocusContext,
+    HoverZoomAnchor, MinimizedImage, PointerWarpMode, ScaleFilter, SwitchOsdMode, TextEffect,
+    UnfocusedEffect,
+};
 pub use runtime::{DaemonConfig, DisplayConfig};
@@ -5,12 +5,13 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use tracing::info;
 
-use crate::common::types::CharacterSettings;
+use crate::common::ipc::{ClientGeometry, ClientSnapshot};
+use crate::common::types::{CharacterSettings, ClientDetectionRule};
 
 /// A named group of characters for cycling
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +47,98 @@ impl CycleGroup {
             hotkey_backward: None,
         }
     }
+
+    /// Appends every active client not already in the group, in screen layout order.
+    ///
+    /// Returns the number of slots added.
+    pub fn add_active_clients(&mut self, clients: &[ClientGeometry]) -> usize {
+        let mut added = 0;
+        for client in layout_order(clients) {
+            let slot = CycleSlot::from_client(client);
+            if !self.cycle_list.contains(&slot) {
+                self.cycle_list.push(slot);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Removes every slot whose client is not currently active.
+    ///
+    /// Returns the number of slots removed.
+    pub fn remove_inactive(&mut self, clients: &[ClientGeometry]) -> usize {
+        let before = self.cycle_list.len();
+        self.cycle_list
+            .retain(|slot| clients.iter().any(|c| CycleSlot::from_client(c) == *slot));
+        before - self.cycle_list.len()
+    }
+
+    /// Reorders active slots to follow the on-screen layout (left-to-right, top-to-bottom).
+    ///
+    /// Slots without an active client keep their relative order at the end of the list.
+    /// Returns true if the order changed.
+    pub fn sort_by_layout(&mut self, clients: &[ClientGeometry]) -> bool {
+        let order: Vec<CycleSlot> = layout_order(clients)
+            .into_iter()
+            .map(CycleSlot::from_client)
+            .collect();
+
+        let mut sorted: Vec<CycleSlot> = order
+            .iter()
+            .filter(|slot| self.cycle_list.contains(slot))
+            .cloned()
+            .collect();
+        sorted.extend(
+            self.cycle_list
+                .iter()
+                .filter(|slot| !order.contains(slot))
+                .cloned(),
+        );
+
+        if sorted == self.cycle_list {
+            return false;
+        }
+        self.cycle_list = sorted;
+        true
+    }
+}
+
+impl CycleSlot {
+    fn from_client(client: &ClientGeometry) -> Self {
+        if client.is_custom {
+            CycleSlot::Source(client.name.clone())
+        } else {
+            CycleSlot::Eve(client.name.clone())
+        }
+    }
+}
+
+/// Sorts clients into reading order: rows top-to-bottom, each row left-to-right.
+///
+/// Thumbnails whose top edge lies within half a thumbnail height of the first one in a row
+/// are treated as the same row, so slightly misaligned layouts still sort as expected.
+fn layout_order(clients: &[ClientGeometry]) -> Vec<&ClientGeometry> {
+    let mut by_y: Vec<&ClientGeometry> = clients.iter().collect();
+    by_y.sort_by_key(|c| (c.y, c.x));
+
+    let mut rows: Vec<Vec<&ClientGeometry>> = Vec::new();
+    for client in by_y {
+        match rows.last_mut() {
+            Some(row)
+                if (client.y as i32 - row[0].y as i32) < (row[0].height as i32 / 2).max(1) =>
+            {
+                row.push(client)
+            }
+            _ => rows.push(vec![client]),
+        }
+    }
+
+    rows.into_iter()
+        .flat_map(|mut row| {
+            row.sort_by_key(|c| c.x);
+            row
+        })
+        .collect()
 }
 
 // Helper for migrating legacy string list to CycleSlot::Eve
@@ -101,6 +194,10 @@ where
 /// Rule for identifying and naming arbitrary application windows
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomWindowRule {
+    /// Stable identifier keying per-instance geometry, so it survives alias changes
+    /// (0 = not assigned yet; ids are assigned on load)
+    #[serde(default)]
+    pub id: u32,
     /// Pattern to match window title (optional)
     pub title_pattern: Option<String>,
     /// Pattern to match window class/process (optional)
@@ -146,6 +243,232 @@ pub struct CustomWindowRule {
     pub hotkey: Option<crate::config::HotkeyBinding>,
 }
 
+impl CustomWindowRule {
+    /// Whether a window with this title and class is a source for this rule
+    pub fn matches(&self, title: &str, class: &str) -> bool {
+        self.mismatch_reason(title, class).is_none()
+    }
+
+    /// Why a window doesn't match (None = it matches). Every pattern the rule defines must be
+    /// contained (case-insensitively) in the title or class; a rule without patterns matches nothing.
+    pub fn mismatch_reason(&self, title: &str, class: &str) -> Option<String> {
+        Self::pattern_mismatch(
+            self.title_pattern.as_deref(),
+            self.class_pattern.as_deref(),
+            title,
+            class,
+        )
+    }
+
+    /// `mismatch_reason` for bare patterns (e.g. a rule still being edited)
+    pub fn pattern_mismatch(
+        title_pattern: Option<&str>,
+        class_pattern: Option<&str>,
+        title: &str,
+        class: &str,
+    ) -> Option<String> {
+        if title_pattern.is_none() && class_pattern.is_none() {
+            return Some("Rule has no title or class pattern".to_string());
+        }
+        if let Some(pattern) = title_pattern
+            && !title.to_lowercase().contains(&pattern.to_lowercase())
+        {
+            return Some(format!("Title doesn't contain \"{}\"", pattern));
+        }
+        if let Some(pattern) = class_pattern
+            && !class.to_lowercase().contains(&pattern.to_lowercase())
+        {
+            return Some(format!("Class doesn't contain \"{}\"", pattern));
+        }
+        None
+    }
+}
+
+/// Switches to another profile when a client started from a matching Wine prefix gains focus
+/// (e.g. a separate Singularity install)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefixProfileRule {
+    /// Case-insensitive substring of the prefix path
+    pub prefix_pattern: String,
+    /// Name of the profile to activate
+    pub profile: String,
+}
+
+impl PrefixProfileRule {
+    pub fn matches(&self, prefix: &str) -> bool {
+        let pattern = self.prefix_pattern.trim();
+        !pattern.is_empty() && prefix.to_lowercase().contains(&pattern.to_lowercase())
+    }
+}
+
+/// Hides all previews during a recurring local-time window (e.g. working hours)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisibilityScheduleRule {
+    /// Days the window starts on, Monday first
+    pub days: [bool; 7],
+    /// Start of the hidden window in minutes after midnight
+    pub start_minute: u16,
+    /// End of the hidden window in minutes after midnight (before the start = spans midnight,
+    /// equal to the start = the whole day)
+    pub end_minute: u16,
+}
+
+impl Default for VisibilityScheduleRule {
+    fn default() -> Self {
+        Self {
+            days: [true, true, true, true, true, false, false],
+            start_minute: 9 * 60,
+            end_minute: 17 * 60,
+        }
+    }
+}
+
+impl VisibilityScheduleRule {
+    const DAY_MINUTES: u16 = 24 * 60;
+
+    /// Whether previews are hidden at `minute` after midnight on `weekday`
+    pub fn hides_at(&self, weekday: chrono::Weekday, minute: u16) -> bool {
+        let start = self.start_minute % Self::DAY_MINUTES;
+        let length = match (self.end_minute % Self::DAY_MINUTES + Self::DAY_MINUTES - start)
+            % Self::DAY_MINUTES
+        {
+            0 => Self::DAY_MINUTES,
+            length => length,
+        };
+        let today = weekday.num_days_from_monday() as usize;
+        let yesterday = weekday.pred().num_days_from_monday() as usize;
+
+        (self.days[today] && minute >= start && minute - start < length)
+            || (self.days[yesterday] && minute + Self::DAY_MINUTES - start < length)
+    }
+}
+
+/// Thumbnail geometry of one character within a named layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutSlot {
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Saved geometry of one instance of a custom source. Rules without `limit` can match
+/// several windows at once; each gets its own slot, numbered in detection order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceGeometry {
+    pub rule_id: u32,
+    pub instance: u32,
+    pub slot: LayoutSlot,
+}
+
+impl SourceGeometry {
+    /// Geometry saved for this instance, if any
+    pub fn find(list: &[SourceGeometry], rule_id: u32, instance: u32) -> Option<LayoutSlot> {
+        list.iter()
+            .find(|g| g.rule_id == rule_id && g.instance == instance)
+            .map(|g| g.slot)
+    }
+
+    /// Saves the geometry of an instance; returns true if anything changed
+    pub fn upsert(
+        list: &mut Vec<SourceGeometry>,
+        rule_id: u32,
+        instance: u32,
+        slot: LayoutSlot,
+    ) -> bool {
+        match list
+            .iter_mut()
+            .find(|g| g.rule_id == rule_id && g.instance == instance)
+        {
+            Some(existing) if existing.slot == slot => false,
+            Some(existing) => {
+                existing.slot = slot;
+                true
+            }
+            None => {
+                list.push(SourceGeometry {
+                    rule_id,
+                    instance,
+                    slot,
+                });
+                true
+            }
+        }
+    }
+}
+
+/// Named arrangement of thumbnails, saved from the current positions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThumbnailLayout {
+    pub name: String,
+    /// Character name → thumbnail geometry
+    pub thumbnails: HashMap<String, LayoutSlot>,
+}
+
+/// Saved session: which characters were running, where their clients and thumbnails were,
+/// which clients were minimized and which had focus
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub name: String,
+    /// Unix time (seconds) the snapshot was taken
+    pub saved_at: u64,
+    pub clients: Vec<ClientSnapshot>,
+    /// Character whose client had focus
+    pub focused: Option<String>,
+}
+
+/// One client in the fleet launch order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchEntry {
+    pub character: String,
+    /// Shell command starting the character's client (e.g. a launcher with a profile argument)
+    pub command: String,
+    /// Seconds to wait before launching, counted from the previous character logging in
+    pub delay_secs: u32,
+}
+
+/// Actions the daemon runs once its initial scan is done (i.e. on every start or profile
+/// switch), so a single switch sets up the whole session. Empty names mean "skip".
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupActions {
+    /// Name of the thumbnail layout to apply
+    pub apply_layout: String,
+    /// Minimize every client except this character
+    pub minimize_all_except: String,
+    /// Character whose client gets focus
+    pub focus_character: String,
+    /// Show (`true`) or hide (`false`) previews; `None` leaves them as they are
+    pub previews: Option<bool>,
+}
+
+/// A game account owning several characters.
+///
+/// Account settings are defaults: a character's own overrides still win. A character belongs
+/// to at most one account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Account {
+    pub name: String,
+    /// Member characters, in the order they were added
+    pub characters: Vec<String>,
+    /// Inactive border color for the account's thumbnails (hex)
+    pub color: Option<String>,
+    /// Short tag shown before the name on thumbnails, e.g. "A1"
+    pub tag: Option<String>,
+    /// Exempt every character of the account from minimize-on-switch
+    pub exempt_from_minimize: bool,
+}
+
+impl Account {
+    /// Tag to show on thumbnails, if set and not blank
+    pub fn label_tag(&self) -> Option<&str> {
+        self.tag.as_deref().map(str::trim).filter(|t| !t.is_empty())
+    }
+}
+
 /// Hotkey backend type selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -154,6 +477,193 @@ pub enum HotkeyBackendType {
     X11,
     /// evdev raw input backend (optional, requires input group membership)
     Evdev,
+    /// xdg-desktop-portal GlobalShortcuts (Wayland compositors; triggers confirmed by the desktop)
+    Portal,
+}
+
+/// Thumbnail capture backend selection (see `daemon::capture`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureBackendType {
+    /// Pick the best backend the X server supports (default)
+    #[default]
+    Auto,
+    /// Render straight from Composite's offscreen copy of the client (fast, server-side)
+    Composite,
+    /// GetImage/PutImage copies through the daemon on every repaint (slow, for debugging)
+    Screenshot,
+    /// OpenGL ES scales texture-from-pixmap images on the GPU (for many clients; opt-in)
+    Gpu,
+    /// MIT-SHM GetImage into shared memory, scaled in the daemon (local displays only)
+    Shm,
+}
+
+/// RENDER filter used to scale clients down to thumbnails (see `daemon::capture`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScaleFilter {
+    /// Nearest pixel: cheapest, but small thumbnails shimmer
+    Nearest,
+    /// Bilinear, through an intermediate image when shrinking a lot (default)
+    #[default]
+    Bilinear,
+    /// The server's "good" filter (a proper downscaling convolution with recent pixman)
+    Good,
+    /// The server's "best" filter: highest quality, most CPU
+    Best,
+}
+
+impl ScaleFilter {
+    /// Name of the filter in the RENDER protocol
+    pub fn render_name(self) -> &'static str {
+        match self {
+            Self::Nearest => "nearest",
+            Self::Bilinear => "bilinear",
+            Self::Good => "good",
+            Self::Best => "best",
+        }
+    }
+}
+
+/// Where the pointer goes when a client is activated by hotkey (see `daemon::pointer_warp`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerWarpMode {
+    /// Leave the pointer alone; activation only refreshes the client's hover state (default)
+    #[default]
+    Off,
+    /// Center of the activated client
+    Center,
+    /// Where the pointer was when focus last left that client window, else the center
+    Remembered,
+}
+
+/// Picture drawn in the middle of a minimized client's thumbnail
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimizedImage {
+    /// Text only (default)
+    #[default]
+    None,
+    /// The character's corner icon (portrait or custom PNG)
+    CornerIcon,
+    /// A PNG file
+    Custom { path: String },
+}
+
+/// Line style of a thumbnail border
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderStyle {
+    /// One solid line (default)
+    #[default]
+    Solid,
+    /// Dashes three border widths long
+    Dashed,
+    /// Two thin lines with a gap between them (needs a border of 3 pixels or more)
+    Double,
+    /// Fades from the border color at the edge to transparent at the inner side
+    InnerGlow,
+}
+
+/// Effect drawn behind label text so it stays readable over bright backgrounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextEffect {
+    /// Plain text (default)
+    #[default]
+    None,
+    /// Outline around every glyph
+    Outline,
+    /// Offset drop shadow
+    Shadow,
+}
+
+/// How thumbnails of unfocused clients are recolored, so the focused one stands out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnfocusedEffect {
+    /// Full color everywhere (default)
+    #[default]
+    None,
+    /// Grayscale, keeping each pixel's brightness
+    Grayscale,
+    /// Covered with the profile's tint color
+    Tint,
+}
+
+/// Where the switch OSD (see `daemon::osd`) appears when a client is activated by hotkey or click
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwitchOsdMode {
+    /// No OSD (default)
+    #[default]
+    Off,
+    /// Next to the mouse pointer
+    Cursor,
+    /// Center of the monitor the activated client is on
+    Center,
+}
+
+/// Part of a hovered thumbnail that stays in place while hover zoom enlarges it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoverZoomAnchor {
+    /// Grow evenly in every direction (default)
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Thumbnail corner holding the cycle-order badge or the status badges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgeCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Which focused window lets hotkeys fire. Panic, pause, suspend and reveal work in any context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HotkeyFocusContext {
+    /// Whatever is focused
+    Always,
+    /// An EVE client or any custom source (default)
+    #[default]
+    Clients,
+    /// An EVE client
+    Eve,
+    /// One of the custom sources listed in `hotkey_focus_sources`
+    Sources,
+}
+
+/// Light/dark mode of the manager window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagerTheme {
+    /// Follow the desktop's preference (default)
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+/// Spacing of the manager window's layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiDensity {
+    /// Roomy layout (default)
+    #[default]
+    Comfortable,
+    /// Tighter spacing and smaller widgets, to fit tabs on small screens without scrolling
+    Compact,
 }
 
 /// Top-level configuration with profile support
@@ -180,6 +690,30 @@ pub struct GlobalSettings {
     pub backup_interval_days: u32,
     #[serde(default = "default_backup_retention_count")]
     pub backup_retention_count: u32,
+    /// Localhost port serving daemon metrics in Prometheus format (0 = disabled)
+    #[serde(default)]
+    pub metrics_port: u16,
+    /// Light/dark mode of the manager window
+    #[serde(default)]
+    pub theme: ManagerTheme,
+    /// Accent color of the manager window (#RRGGBB or #AARRGGBB, empty = egui default)
+    #[serde(default)]
+    pub accent_color: String,
+    /// Zoom factor of the manager window (also changed with Ctrl +/-)
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Body text size of the manager window in points; other text styles scale along
+    #[serde(default = "default_ui_font_size")]
+    pub ui_font_size: f32,
+    /// Layout density of the manager window
+    #[serde(default)]
+    pub ui_density: UiDensity,
+    /// Focus the last focused character's client again on the next launch
+    #[serde(default = "default_restore_last_focus")]
+    pub restore_last_focus: bool,
+    /// Character whose client had focus last (kept across restarts; empty = none yet)
+    #[serde(default)]
+    pub last_focused_character: String,
 }
 
 /// Profile - A complete set of visual and behavioral settings
@@ -188,12 +722,19 @@ pub struct GlobalSettings {
 pub struct Profile {
     pub profile_name: String,
     pub profile_description: String,
+    /// Read-only: nothing (settings or thumbnail positions) is written to this profile until
+    /// it is explicitly unlocked
+    pub profile_locked: bool,
 
     // Thumbnail default dimensions
     /// Default thumbnail width for new characters
     pub thumbnail_default_width: u16,
     /// Default thumbnail height for new characters
     pub thumbnail_default_height: u16,
+    /// Scale default sizes by the target monitor's pixel density (RandR physical size)
+    pub thumbnail_hidpi_scaling: bool,
+    /// Extra multiplier for default sizes per RandR monitor name (e.g. "DP-1")
+    pub thumbnail_monitor_scales: HashMap<String, f32>,
 
     // Thumbnail visual settings
     /// Enable/disable thumbnail rendering entirely (daemon still runs for hotkeys)
@@ -205,11 +746,62 @@ pub struct Profile {
     pub thumbnail_inactive_border: bool,
     pub thumbnail_inactive_border_size: u16,
     pub thumbnail_inactive_border_color: String,
+    /// Border color for clients connected to a test server (Singularity, Thunderdome, ...)
+    pub thumbnail_test_server_border_color: String,
+    /// Gap in pixels between the border and the client content
+    pub thumbnail_border_padding: u16,
+    /// Width in pixels of a glow fading inward from the border (0 = off)
+    pub thumbnail_border_glow: u16,
+    /// Line style of the focused thumbnail's border
+    pub thumbnail_active_border_style: BorderStyle,
+    /// Line style of unfocused thumbnails' borders
+    pub thumbnail_inactive_border_style: BorderStyle,
+    /// Border style and width of unfocused clients skipped by cycling (None = as unfocused)
+    pub thumbnail_skipped_border_style: Option<BorderStyle>,
+    pub thumbnail_skipped_border_size: Option<u16>,
+    /// Border style and width of minimized clients (None = as unfocused)
+    pub thumbnail_minimized_border_style: Option<BorderStyle>,
+    pub thumbnail_minimized_border_size: Option<u16>,
+    /// Recoloring of unfocused thumbnails (back to full color on focus)
+    pub thumbnail_unfocused_effect: UnfocusedEffect,
+    /// Tint drawn over unfocused thumbnails with the tint effect (#AARRGGBB, alpha = strength)
+    pub thumbnail_unfocused_tint_color: String,
+    /// Outline of thumbnail windows (rectangle, rounded rect or circle)
+    pub thumbnail_shape: crate::common::types::ThumbnailShape,
+    /// Corner radius in pixels for the rounded-rect shape
+    pub thumbnail_corner_radius: u16,
+    /// Size in pixels of per-character corner icons
+    pub thumbnail_icon_size: u16,
+    /// Show each character's position in its cycle group as a numbered badge
+    pub thumbnail_cycle_badge: bool,
+    pub thumbnail_cycle_badge_corner: BadgeCorner,
+    /// Badge height in pixels
+    pub thumbnail_cycle_badge_size: u16,
+    /// Show small icons for client states (minimized, skipped, hotkey-bound, custom source)
+    pub thumbnail_status_badges: bool,
+    pub thumbnail_status_badge_corner: BadgeCorner,
+    /// Icon size in pixels
+    pub thumbnail_status_badge_size: u16,
     pub thumbnail_text_size: u16,
     pub thumbnail_text_x: i16,
     pub thumbnail_text_y: i16,
     pub thumbnail_text_font: String,
     pub thumbnail_text_color: String,
+    /// Outline or drop shadow behind the label text
+    pub thumbnail_text_effect: TextEffect,
+    /// Color of the outline or shadow (#AARRGGBB)
+    pub thumbnail_text_effect_color: String,
+    /// Drop shadow offset from the text in pixels
+    pub thumbnail_text_shadow_x: i16,
+    pub thumbnail_text_shadow_y: i16,
+    /// Outline width in pixels
+    pub thumbnail_text_outline_width: u16,
+    /// Always render character notes as a secondary label line
+    /// When disabled, notes are only shown while hovering the thumbnail
+    pub thumbnail_show_notes: bool,
+    /// Default alias template for characters without their own alias (empty = plain name)
+    /// See `common::types::alias` for the supported variables
+    pub thumbnail_alias_template: String,
 
     // Thumbnail behavior settings
     /// Automatically save thumbnail positions when dragged
@@ -217,15 +809,73 @@ pub struct Profile {
     pub thumbnail_auto_save_position: bool,
     pub thumbnail_snap_threshold: u16,
     pub thumbnail_hide_not_focused: bool,
+    /// Fade duration in milliseconds when thumbnails hide on focus loss and reappear on focus
+    /// gain (0 = hide and show instantly)
+    pub thumbnail_hide_fade_ms: u32,
+    /// Window classes (e.g. Discord, Mumble) whose focus does not count as EVE losing focus
+    pub thumbnail_hide_exempt_classes: Vec<String>,
     /// When a new character logs in without saved coordinates, inherit the previous character's thumbnail position
     /// This keeps thumbnails in place when swapping characters on the same EVE client
     pub thumbnail_preserve_position_on_swap: bool,
+    /// How thumbnails read client contents (Auto picks the best available; forcing one is for debugging)
+    pub thumbnail_capture_backend: CaptureBackendType,
+    /// Filter for scaling clients down to thumbnails (quality vs X server CPU)
+    pub thumbnail_scale_filter: ScaleFilter,
+    /// Present thumbnail repaints at vblank through the Present extension (no tearing, up to
+    /// one frame of extra latency)
+    pub thumbnail_vsync: bool,
+    /// If true, Ctrl+left-click on a thumbnail is forwarded as a click into the source window
+    /// instead of focusing it
+    pub thumbnail_click_passthrough: bool,
+    /// Magnification of a thumbnail while the pointer is over it (1.0 = off)
+    pub thumbnail_hover_zoom: f32,
+    /// Part of a hovered thumbnail that stays in place while it is zoomed
+    pub thumbnail_hover_zoom_anchor: HoverZoomAnchor,
+    /// How long the pointer must rest on a thumbnail before it zooms, in ms (0 = immediately)
+    pub thumbnail_hover_zoom_delay_ms: u32,
+    /// Show a tooltip with the character's details next to the hovered thumbnail
+    pub thumbnail_hover_tooltip: bool,
+    /// Width change per scroll-wheel notch over a thumbnail, in pixels (0 = off)
+    pub thumbnail_scroll_resize_step: u16,
+    /// Keep scroll-resized thumbnails at the aspect ratio of the default thumbnail size
+    pub thumbnail_scroll_resize_lock_aspect: bool,
+
+    // Advanced timing settings
+    /// Delay before thumbnails hide after EVE loses focus (hysteresis against focus flicker)
+    pub timing_focus_loss_delay_ms: u32,
+    /// Quiet period before moved/resized thumbnail positions are reported to the Manager
+    pub timing_position_debounce_ms: u32,
+    /// Debounce before reported positions are auto-saved to disk
+    pub timing_auto_save_delay_ms: u32,
+    /// Minimum time between damage repaints of one live thumbnail
+    pub timing_damage_frame_ms: u32,
+    /// How often windows mapped without a recognizable title are re-checked
+    pub timing_identity_retry_interval_ms: u32,
+    /// How long after mapping such windows keep being re-checked
+    pub timing_identity_retry_window_ms: u32,
 
     // Client behavior settings
     pub client_minimize_on_switch: bool,
     /// When minimized, show "MINIMIZED" text overlay
     /// When minimized, show "MINIMIZED" text overlay
     pub client_minimize_show_overlay: bool,
+    /// Switching back to a client that was minimized on switch keeps the previously focused
+    /// client up, so two clients can be toggled between
+    pub client_minimize_alt_restore: bool,
+    /// Minimized clients keep their last captured frame, dimmed, instead of a black preview
+    pub client_minimize_freeze_frame: bool,
+    /// Text shown on minimized clients when the overlay is enabled
+    pub client_minimize_text: String,
+    /// Hex fill color of minimized clients (unless the last frame is kept)
+    pub client_minimize_background: String,
+    pub client_minimize_image: MinimizedImage,
+    /// Moves the pointer into clients activated by hotkey
+    pub client_pointer_warp: PointerWarpMode,
+    /// Brief "→ Character [3/7]" toast when a client is activated by hotkey or click; also
+    /// confirms profile switches and toggles (pause, previews, hotkey suspension, skip)
+    pub client_switch_osd: SwitchOsdMode,
+    /// Speak the character's name aloud when a hotkey switches to it
+    pub client_switch_speech: bool,
 
     // Hotkey settings (per-profile)
     /// Hotkey backend selection (X11 or evdev)
@@ -244,8 +894,17 @@ pub struct Profile {
     /// Include logged-out characters in hotkey cycle if they were previously logged in during this session
     pub hotkey_logged_out_cycle: bool,
 
-    /// Require EVE window focused for hotkeys to work
-    pub hotkey_require_eve_focus: bool,
+    /// Which focused window lets hotkeys fire (replaces the old `hotkey_require_eve_focus`)
+    pub hotkey_focus_context: HotkeyFocusContext,
+
+    /// Custom source aliases whose focus lets hotkeys fire under `HotkeyFocusContext::Sources`
+    pub hotkey_focus_sources: Vec<String>,
+
+    /// WM_CLASSes of apps (e.g. a browser) that disable hotkeys while focused, in any context
+    pub hotkey_blocked_classes: Vec<String>,
+
+    /// Leave test-server clients out of cycle group rotation
+    pub hotkey_cycle_exclude_test_servers: bool,
 
     /// Reset cycle index to the beginning when switching between cycle groups
     pub hotkey_cycle_reset_index: bool,
@@ -259,6 +918,37 @@ pub struct Profile {
     /// Hotkey to toggle visibility of all thumbnails (ephemeral)
     pub hotkey_toggle_previews: Option<crate::config::HotkeyBinding>,
 
+    /// Hotkey to open/close the overview of all clients (ephemeral)
+    pub hotkey_overview: Option<crate::config::HotkeyBinding>,
+
+    /// Hotkey to flip back to the previously focused character, whatever the cycle order
+    pub hotkey_flip: Option<crate::config::HotkeyBinding>,
+
+    /// Hold to temporarily enlarge the next thumbnail in the cycle (peek without switching)
+    pub hotkey_peek: Option<crate::config::HotkeyBinding>,
+
+    /// Size multiplier applied while the peek hotkey is held
+    pub hotkey_peek_scale: f32,
+
+    /// Hold to show thumbnails hidden by hide-when-no-focus without switching back to EVE
+    pub hotkey_reveal: Option<crate::config::HotkeyBinding>,
+
+    /// Minimizes every EVE client and hides all thumbnails; a second press restores them
+    pub hotkey_panic: Option<crate::config::HotkeyBinding>,
+
+    /// WM_CLASS of a window to focus when the panic hotkey fires (empty = none)
+    pub hotkey_panic_decoy_class: String,
+
+    /// Pauses the whole daemon (thumbnails hidden, other hotkeys released); press again to resume
+    pub hotkey_pause: Option<crate::config::HotkeyBinding>,
+
+    /// Releases every other hotkey for `hotkey_suspend_secs` (e.g. to type Tab in EVE chat);
+    /// press again to resume early
+    pub hotkey_suspend: Option<crate::config::HotkeyBinding>,
+
+    /// How long the suspend hotkey keeps hotkeys released, in seconds
+    pub hotkey_suspend_secs: u32,
+
     /// Per-character hotkey assignments (character_name -> optional binding)
     /// Allows direct switching to specific characters with dedicated hotkeys
     /// Display order follows hotkey_cycle_group
@@ -270,12 +960,47 @@ pub struct Profile {
     /// Per-profile custom source positions and dimensions (separate from characters)
     pub custom_source_thumbnails: HashMap<String, CharacterSettings>,
 
+    /// Per-instance custom source positions and dimensions, keyed by rule id
+    pub custom_source_geometry: Vec<SourceGeometry>,
+
+    /// Accounts grouping characters, with defaults their characters inherit
+    pub accounts: Vec<Account>,
+
+    /// Profiles to switch to when a client from a matching Wine prefix gains focus
+    pub prefix_profile_rules: Vec<PrefixProfileRule>,
+
+    /// Time windows during which all previews are hidden (the toggle-previews hotkey overrides
+    /// until the next start or end)
+    pub visibility_schedule: Vec<VisibilityScheduleRule>,
+
+    /// Saved thumbnail arrangements (applied by startup actions)
+    pub thumbnail_layouts: Vec<ThumbnailLayout>,
+
+    /// Saved sessions (client geometry, minimized state, focus and thumbnails)
+    pub session_snapshots: Vec<SessionSnapshot>,
+
+    /// Actions run once the daemon finished its initial scan
+    pub startup_actions: StartupActions,
+
+    /// Clients started one after another by "Launch fleet"
+    pub launch_order: Vec<LaunchEntry>,
+
+    /// Characters archived by the stale character cleanup (restorable, ignored by the daemon)
+    pub archived_characters: HashMap<String, CharacterSettings>,
+
     /// Custom window matching rules for external applications
     pub custom_windows: Vec<CustomWindowRule>,
+
+    /// Rules recognizing game client windows (EVE by default, first match wins)
+    pub client_detection_rules: Vec<ClientDetectionRule>,
 }
 
 // Default value functions
 // Default value functions
+pub(crate) fn default_client_detection_rules() -> Vec<ClientDetectionRule> {
+    vec![ClientDetectionRule::eve()]
+}
+
 pub(crate) fn default_border_size() -> u16 {
     crate::common::constants::defaults::border::SIZE
 }
@@ -288,6 +1013,10 @@ pub(crate) fn default_hotkey_backend() -> HotkeyBackendType {
     HotkeyBackendType::X11
 }
 
+pub(crate) fn default_restore_last_focus() -> bool {
+    true
+}
+
 pub(crate) fn default_backup_enabled() -> bool {
     crate::common::constants::config::backup::ENABLED
 }
@@ -308,6 +1037,14 @@ pub(crate) fn default_window_height() -> u16 {
     crate::common::constants::defaults::manager::WINDOW_HEIGHT
 }
 
+pub(crate) fn default_ui_scale() -> f32 {
+    crate::common::constants::defaults::manager::UI_SCALE
+}
+
+pub(crate) fn default_ui_font_size() -> f32 {
+    crate::common::constants::defaults::manager::UI_FONT_SIZE
+}
+
 pub(crate) fn default_snap_threshold() -> u16 {
     crate::common::constants::defaults::behavior::SNAP_THRESHOLD
 }
@@ -316,6 +1053,30 @@ pub(crate) fn default_preserve_thumbnail_position_on_swap() -> bool {
     crate::common::constants::defaults::behavior::PRESERVE_POSITION_ON_SWAP
 }
 
+pub(crate) fn default_focus_loss_delay_ms() -> u32 {
+    crate::common::constants::defaults::timing::FOCUS_LOSS_DELAY_MS
+}
+
+pub(crate) fn default_position_debounce_ms() -> u32 {
+    crate::common::constants::defaults::timing::POSITION_DEBOUNCE_MS
+}
+
+pub(crate) fn default_auto_save_delay_ms() -> u32 {
+    crate::common::constants::defaults::timing::AUTO_SAVE_DELAY_MS
+}
+
+pub(crate) fn default_damage_frame_ms() -> u32 {
+    crate::common::constants::defaults::timing::DAMAGE_FRAME_MS
+}
+
+pub(crate) fn default_identity_retry_interval_ms() -> u32 {
+    crate::common::constants::defaults::timing::IDENTITY_RETRY_INTERVAL_MS
+}
+
+pub(crate) fn default_identity_retry_window_ms() -> u32 {
+    crate::common::constants::defaults::timing::IDENTITY_RETRY_WINDOW_MS
+}
+
 pub(crate) fn default_thumbnail_width() -> u16 {
     crate::common::constants::defaults::thumbnail::WIDTH
 }
@@ -324,10 +1085,66 @@ pub(crate) fn default_thumbnail_height() -> u16 {
     crate::common::constants::defaults::thumbnail::HEIGHT
 }
 
+pub(crate) fn default_hidpi_scaling() -> bool {
+    true
+}
+
+pub(crate) fn default_scroll_resize_lock_aspect() -> bool {
+    true
+}
+
 pub(crate) fn default_thumbnail_enabled() -> bool {
     true // Default: thumbnails enabled
 }
 
+pub(crate) fn default_corner_radius() -> u16 {
+    crate::common::constants::defaults::thumbnail::CORNER_RADIUS
+}
+
+pub(crate) fn default_text_effect_color() -> String {
+    crate::common::constants::defaults::text::EFFECT_COLOR.to_string()
+}
+
+pub(crate) fn default_text_shadow_offset() -> i16 {
+    crate::common::constants::defaults::text::SHADOW_OFFSET
+}
+
+pub(crate) fn default_text_outline_width() -> u16 {
+    crate::common::constants::defaults::text::OUTLINE_WIDTH
+}
+
+pub(crate) fn default_unfocused_tint_color() -> String {
+    crate::common::constants::defaults::thumbnail::UNFOCUSED_TINT_COLOR.to_string()
+}
+
+pub(crate) fn default_icon_size() -> u16 {
+    crate::common::constants::defaults::thumbnail::ICON_SIZE
+}
+
+pub(crate) fn default_cycle_badge_size() -> u16 {
+    crate::common::constants::defaults::thumbnail::CYCLE_BADGE_SIZE
+}
+
+pub(crate) fn default_status_badge_corner() -> BadgeCorner {
+    BadgeCorner::BottomLeft
+}
+
+pub(crate) fn default_status_badge_size() -> u16 {
+    crate::common::constants::defaults::thumbnail::STATUS_BADGE_SIZE
+}
+
+pub(crate) fn default_hover_zoom() -> f32 {
+    crate::common::constants::defaults::thumbnail::HOVER_ZOOM
+}
+
+pub(crate) fn default_peek_scale() -> f32 {
+    crate::common::constants::defaults::behavior::PEEK_SCALE
+}
+
+pub(crate) fn default_hotkey_suspend_secs() -> u32 {
+    crate::common::constants::defaults::behavior::HOTKEY_SUSPEND_SECS
+}
+
 pub(crate) fn default_border_enabled() -> bool {
     crate::common::constants::defaults::border::ENABLED
 }
@@ -336,10 +1153,26 @@ pub(crate) fn default_inactive_border_enabled() -> bool {
     false // Default: inactive borders disabled
 }
 
+pub(crate) fn default_minimized_text() -> String {
+    crate::common::constants::defaults::behavior::MINIMIZED_TEXT.to_string()
+}
+
+pub(crate) fn default_minimized_background() -> String {
+    crate::common::constants::defaults::behavior::MINIMIZED_BACKGROUND.to_string()
+}
+
 pub(crate) fn default_inactive_border_color() -> String {
     crate::common::constants::defaults::border::INACTIVE_COLOR.to_string()
 }
 
+pub(crate) fn default_test_server_border_color() -> String {
+    crate::common::constants::defaults::border::TEST_SERVER_COLOR.to_string()
+}
+
+pub(crate) fn default_exclude_test_servers() -> bool {
+    crate::common::constants::defaults::behavior::EXCLUDE_TEST_SERVERS_FROM_CYCLE
+}
+
 pub(crate) fn default_text_font_family() -> String {
     // Try to detect best default TrueType font, but don't fail config creation
     match crate::daemon::select_best_default_font() {
@@ -364,8 +1197,11 @@ fn default_profiles() -> Vec<Profile> {
         profile_name: crate::common::constants::defaults::behavior::PROFILE_NAME.to_string(),
         profile_description: crate::common::constants::defaults::behavior::PROFILE_DESCRIPTION
             .to_string(),
+        profile_locked: false,
         thumbnail_default_width: default_thumbnail_width(),
         thumbnail_default_height: default_thumbnail_height(),
+        thumbnail_hidpi_scaling: default_hidpi_scaling(),
+        thumbnail_monitor_scales: HashMap::new(),
         thumbnail_enabled: default_thumbnail_enabled(),
         thumbnail_opacity: crate::common::constants::defaults::thumbnail::OPACITY_PERCENT,
         thumbnail_active_border: crate::common::constants::defaults::border::ENABLED,
@@ -375,33 +1211,108 @@ fn default_profiles() -> Vec<Profile> {
         thumbnail_inactive_border: default_inactive_border_enabled(),
         thumbnail_inactive_border_size: crate::common::constants::defaults::border::SIZE,
         thumbnail_inactive_border_color: default_inactive_border_color(),
+        thumbnail_test_server_border_color: default_test_server_border_color(),
+        thumbnail_border_padding: 0,
+        thumbnail_border_glow: 0,
+        thumbnail_active_border_style: BorderStyle::default(),
+        thumbnail_inactive_border_style: BorderStyle::default(),
+        thumbnail_skipped_border_style: None,
+        thumbnail_skipped_border_size: None,
+        thumbnail_minimized_border_style: None,
+        thumbnail_minimized_border_size: None,
+        thumbnail_unfocused_effect: UnfocusedEffect::default(),
+        thumbnail_unfocused_tint_color: default_unfocused_tint_color(),
+        thumbnail_shape: crate::common::types::ThumbnailShape::default(),
+        thumbnail_corner_radius: default_corner_radius(),
+        thumbnail_icon_size: default_icon_size(),
+        thumbnail_cycle_badge: false,
+        thumbnail_cycle_badge_corner: BadgeCorner::default(),
+        thumbnail_cycle_badge_size: default_cycle_badge_size(),
+        thumbnail_status_badges: false,
+        thumbnail_status_badge_corner: default_status_badge_corner(),
+        thumbnail_status_badge_size: default_status_badge_size(),
         thumbnail_text_size: crate::common::constants::defaults::text::SIZE,
         thumbnail_text_x: crate::common::constants::defaults::text::OFFSET_X,
         thumbnail_text_y: crate::common::constants::defaults::text::OFFSET_Y,
         thumbnail_text_font: default_text_font_family(),
         thumbnail_text_color: crate::common::constants::defaults::text::COLOR.to_string(),
+        thumbnail_text_effect: TextEffect::default(),
+        thumbnail_text_effect_color: default_text_effect_color(),
+        thumbnail_text_shadow_x: default_text_shadow_offset(),
+        thumbnail_text_shadow_y: default_text_shadow_offset(),
+        thumbnail_text_outline_width: default_text_outline_width(),
+        thumbnail_show_notes: crate::common::constants::defaults::text::SHOW_NOTES,
+        thumbnail_alias_template: String::new(),
         thumbnail_auto_save_position: default_auto_save_thumbnail_positions(),
         thumbnail_snap_threshold: default_snap_threshold(),
         thumbnail_hide_not_focused:
             crate::common::constants::defaults::behavior::HIDE_WHEN_NO_FOCUS,
         thumbnail_preserve_position_on_swap: default_preserve_thumbnail_position_on_swap(),
+        thumbnail_capture_backend: CaptureBackendType::default(),
+        thumbnail_scale_filter: ScaleFilter::default(),
+        thumbnail_vsync: false,
+        thumbnail_click_passthrough: false,
+        thumbnail_hover_zoom: default_hover_zoom(),
+        thumbnail_hover_zoom_anchor: HoverZoomAnchor::default(),
+        thumbnail_hover_zoom_delay_ms: 0,
+        thumbnail_hover_tooltip: false,
+        thumbnail_scroll_resize_step: 0,
+        thumbnail_scroll_resize_lock_aspect: true,
+        thumbnail_hide_fade_ms: 0,
+        thumbnail_hide_exempt_classes: Vec::new(),
+        timing_focus_loss_delay_ms: default_focus_loss_delay_ms(),
+        timing_position_debounce_ms: default_position_debounce_ms(),
+        timing_auto_save_delay_ms: default_auto_save_delay_ms(),
+        timing_damage_frame_ms: default_damage_frame_ms(),
+        timing_identity_retry_interval_ms: default_identity_retry_interval_ms(),
+        timing_identity_retry_window_ms: default_identity_retry_window_ms(),
         client_minimize_on_switch:
             crate::common::constants::defaults::behavior::MINIMIZE_CLIENTS_ON_SWITCH,
         client_minimize_show_overlay: false, // Default: off (clean minimized look)
+        client_minimize_alt_restore: false,
+        client_minimize_freeze_frame: false,
+        client_minimize_text: default_minimized_text(),
+        client_minimize_background: default_minimized_background(),
+        client_minimize_image: MinimizedImage::default(),
+        client_pointer_warp: PointerWarpMode::default(),
+        client_switch_osd: SwitchOsdMode::default(),
+        client_switch_speech: false,
         hotkey_backend: default_hotkey_backend(), // Default: X11 (secure, no permissions)
         hotkey_input_device: None, // Default: no device selected (only used by evdev backend)
         hotkey_logged_out_cycle: false, // Default: off
-        hotkey_require_eve_focus:
-            crate::common::constants::defaults::behavior::HOTKEY_REQUIRE_EVE_FOCUS,
+        hotkey_focus_context: HotkeyFocusContext::default(),
+        hotkey_focus_sources: Vec::new(),
+        hotkey_blocked_classes: Vec::new(),
+        hotkey_cycle_exclude_test_servers: default_exclude_test_servers(),
         hotkey_cycle_reset_index: false,
         hotkey_profile_switch: None,
         hotkey_toggle_skip: None,     // User must configure
         hotkey_toggle_previews: None, // User must configure
+        hotkey_overview: None,        // User must configure
+        hotkey_flip: None,            // User must configure
+        hotkey_peek: None,            // User must configure
+        hotkey_peek_scale: default_peek_scale(),
+        hotkey_reveal: None, // User must configure
+        hotkey_panic: None,  // User must configure
+        hotkey_panic_decoy_class: String::new(),
+        hotkey_pause: None,   // User must configure
+        hotkey_suspend: None, // User must configure
+        hotkey_suspend_secs: default_hotkey_suspend_secs(),
         cycle_groups: vec![CycleGroup::default_group()],
         character_hotkeys: HashMap::new(),
         character_thumbnails: HashMap::new(),
         custom_source_thumbnails: HashMap::new(),
+        custom_source_geometry: Vec::new(),
+        accounts: Vec::new(),
+        prefix_profile_rules: Vec::new(),
+        visibility_schedule: Vec::new(),
+        thumbnail_layouts: Vec::new(),
+        session_snapshots: Vec::new(),
+        startup_actions: StartupActions::default(),
+        launch_order: Vec::new(),
+        archived_characters: HashMap::new(),
         custom_windows: Vec::new(),
+        client_detection_rules: default_client_detection_rules(),
     }]
 }
 
@@ -414,6 +1325,14 @@ impl Default for GlobalSettings {
             backup_enabled: default_backup_enabled(),
             backup_interval_days: default_backup_interval_days(),
             backup_retention_count: default_backup_retention_count(),
+            metrics_port: 0,
+            theme: ManagerTheme::default(),
+            accent_color: String::new(),
+            ui_scale: default_ui_scale(),
+            ui_font_size: default_ui_font_size(),
+            ui_density: UiDensity::default(),
+            restore_last_focus: default_restore_last_focus(),
+            last_focused_character: String::new(),
         }
     }
 }
@@ -427,41 +1346,243 @@ impl Profile {
         profile
     }
 
-    /// Update thumbnail position/dimensions if changed.
-    /// Returns true if the configuration was modified, false otherwise.
-    pub fn update_thumbnail_position(
-        &mut self,
-        name: &str,
-        x: i16,
-        y: i16,
-        width: u16,
-        height: u16,
-        is_custom: bool,
-    ) -> bool {
-        let map = if is_custom {
-            &mut self.custom_source_thumbnails
-        } else {
-            &mut self.character_thumbnails
-        };
+    /// Whether thumbnail moves and resizes are written to this profile as they happen
+    pub fn persists_positions(&self) -> bool {
+        self.thumbnail_auto_save_position && !self.profile_locked
+    }
 
-        if let Some(existing) = map.get_mut(name) {
-            // Check if anything actually changed
-            if existing.x == x
-                && existing.y == y
-                && existing.dimensions.width == width
-                && existing.dimensions.height == height
-            {
-                // No change
-                return false;
-            }
+    /// Whether a visibility schedule rule hides previews at the given local time
+    pub fn schedule_hides_previews<T: chrono::Datelike + chrono::Timelike>(&self, now: &T) -> bool {
+        let minute = (now.hour() * 60 + now.minute()) as u16;
+        self.visibility_schedule
+            .iter()
+            .any(|rule| rule.hides_at(now.weekday(), minute))
+    }
 
-            // Update existing entry
-            existing.x = x;
-            existing.y = y;
-            existing.dimensions.width = width;
-            existing.dimensions.height = height;
-            true
-        } else {
+    /// Snapshot of the saved thumbnail positions of every character as a named layout
+    pub fn capture_layout(&self, name: &str) -> ThumbnailLayout {
+        ThumbnailLayout {
+            name: name.to_string(),
+            thumbnails: self
+                .character_thumbnails
+                .iter()
+                .map(|(character, settings)| {
+                    (
+                        character.clone(),
+                        LayoutSlot {
+                            x: settings.x,
+                            y: settings.y,
+                            width: settings.dimensions.width,
+                            height: settings.dimensions.height,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Layout with this name, if saved
+    pub fn layout(&self, name: &str) -> Option<&ThumbnailLayout> {
+        self.thumbnail_layouts.iter().find(|l| l.name == name)
+    }
+
+    /// Whether focusing a window of this WM_CLASS keeps thumbnails shown (case-insensitive)
+    pub fn is_hide_exempt_class(&self, class: &str) -> bool {
+        self.thumbnail_hide_exempt_classes
+            .iter()
+            .map(|c| c.trim())
+            .any(|c| !c.is_empty() && c.eq_ignore_ascii_case(class))
+    }
+
+    /// Whether focusing the client or custom source `name` lets hotkeys fire
+    pub fn hotkey_focus_allows(&self, name: &str, is_source: bool) -> bool {
+        match self.hotkey_focus_context {
+            HotkeyFocusContext::Always | HotkeyFocusContext::Clients => true,
+            HotkeyFocusContext::Eve => !is_source,
+            HotkeyFocusContext::Sources => {
+                is_source && self.hotkey_focus_sources.iter().any(|s| s == name)
+            }
+        }
+    }
+
+    /// Whether focusing a window of this WM_CLASS disables hotkeys (case-insensitive)
+    pub fn is_hotkey_blocked_class(&self, class: &str) -> bool {
+        self.hotkey_blocked_classes
+            .iter()
+            .map(|c| c.trim())
+            .any(|c| !c.is_empty() && c.eq_ignore_ascii_case(class))
+    }
+
+    /// Every hotkey bound in this profile, labelled with the action it triggers
+    pub fn hotkey_assignments(&self) -> Vec<(String, &crate::config::HotkeyBinding)> {
+        let singles = [
+            ("Switch to Profile", &self.hotkey_profile_switch),
+            ("Toggle Skip", &self.hotkey_toggle_skip),
+            ("Toggle Previews", &self.hotkey_toggle_previews),
+            ("Overview", &self.hotkey_overview),
+            ("Flip to Previous", &self.hotkey_flip),
+            ("Peek", &self.hotkey_peek),
+            ("Reveal", &self.hotkey_reveal),
+            ("Panic", &self.hotkey_panic),
+            ("Pause", &self.hotkey_pause),
+            ("Suspend", &self.hotkey_suspend),
+        ];
+        let mut assignments: Vec<_> = singles
+            .into_iter()
+            .filter_map(|(label, binding)| binding.as_ref().map(|b| (label.to_string(), b)))
+            .collect();
+        for group in &self.cycle_groups {
+            if let Some(binding) = &group.hotkey_forward {
+                assignments.push((format!("Cycle Group '{}' Forward", group.name), binding));
+            }
+            if let Some(binding) = &group.hotkey_backward {
+                assignments.push((format!("Cycle Group '{}' Backward", group.name), binding));
+            }
+        }
+        let mut characters: Vec<_> = self.character_hotkeys.iter().collect();
+        characters.sort_by_key(|(name, _)| name.as_str());
+        for (name, binding) in characters {
+            assignments.push((format!("Character: {}", name), binding));
+        }
+        for rule in &self.custom_windows {
+            if let Some(binding) = &rule.hotkey {
+                assignments.push((format!("Custom Source: {}", rule.alias), binding));
+            }
+        }
+        assignments
+    }
+
+    /// Actions other than the one labelled `except` (see `hotkey_assignments`) bound to the same
+    /// keys as `binding`
+    pub fn hotkey_conflicts(
+        &self,
+        binding: &crate::config::HotkeyBinding,
+        except: &str,
+    ) -> Vec<String> {
+        self.hotkey_assignments()
+            .into_iter()
+            .filter(|(label, bound)| label != except && bound.same_keys(binding))
+            .map(|(label, _)| label)
+            .collect()
+    }
+
+    /// Why `alias` can't be used for the custom source at `rule_index` (None = it can; pass
+    /// None for a rule not added yet). Aliases share the thumbnail namespace with character
+    /// names, so they must be unique among sources and not name a known character.
+    pub fn alias_conflict(&self, alias: &str, rule_index: Option<usize>) -> Option<String> {
+        if alias.trim().is_empty() {
+            return Some("Display name is required".to_string());
+        }
+        let taken_by_source = self
+            .custom_windows
+            .iter()
+            .enumerate()
+            .any(|(i, rule)| Some(i) != rule_index && rule.alias == alias);
+        if taken_by_source {
+            return Some(format!("Another source is already named \"{}\"", alias));
+        }
+        if self.is_known_character(alias) {
+            return Some(format!("\"{}\" is the name of a character", alias));
+        }
+        None
+    }
+
+    /// `alias` with a " (2)", " (3)", ... suffix if needed to avoid every conflict
+    pub fn unique_alias(&self, alias: &str, rule_index: Option<usize>) -> String {
+        let alias = alias.trim();
+        if self.alias_conflict(alias, rule_index).is_none() {
+            return alias.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} ({})", alias, n))
+            .find(|candidate| self.alias_conflict(candidate, rule_index).is_none())
+            .expect("unbounded suffix search always finds a free alias")
+    }
+
+    /// Id for a newly added custom source rule
+    pub fn next_rule_id(&self) -> u32 {
+        self.custom_windows.iter().map(|r| r.id).max().unwrap_or(0) + 1
+    }
+
+    /// Gives rules from older configs an id and seeds the first instance's geometry from
+    /// the alias-keyed `custom_source_thumbnails` entry they used to share
+    pub fn migrate_source_geometry(&mut self) {
+        let mut seen = HashSet::new();
+        for idx in 0..self.custom_windows.len() {
+            let id = self.custom_windows[idx].id;
+            if id == 0 || !seen.insert(id) {
+                let id = self.next_rule_id();
+                self.custom_windows[idx].id = id;
+                seen.insert(id);
+            }
+        }
+
+        for rule in &self.custom_windows {
+            let Some(settings) = self.custom_source_thumbnails.get(&rule.alias) else {
+                continue;
+            };
+            if SourceGeometry::find(&self.custom_source_geometry, rule.id, 0).is_none() {
+                self.custom_source_geometry.push(SourceGeometry {
+                    rule_id: rule.id,
+                    instance: 0,
+                    slot: LayoutSlot {
+                        x: settings.x,
+                        y: settings.y,
+                        width: settings.dimensions.width,
+                        height: settings.dimensions.height,
+                    },
+                });
+            }
+        }
+    }
+
+    /// Whether `name` is a character (one with saved settings or in a cycle group as such)
+    fn is_known_character(&self, name: &str) -> bool {
+        self.character_thumbnails.contains_key(name)
+            || self.archived_characters.contains_key(name)
+            || self.cycle_groups.iter().any(|group| {
+                group
+                    .cycle_list
+                    .iter()
+                    .any(|slot| matches!(slot, CycleSlot::Eve(n) if n == name))
+            })
+    }
+
+    /// Update thumbnail position/dimensions if changed.
+    /// Returns true if the configuration was modified, false otherwise.
+    pub fn update_thumbnail_position(
+        &mut self,
+        name: &str,
+        x: i16,
+        y: i16,
+        width: u16,
+        height: u16,
+        is_custom: bool,
+    ) -> bool {
+        let map = if is_custom {
+            &mut self.custom_source_thumbnails
+        } else {
+            &mut self.character_thumbnails
+        };
+
+        if let Some(existing) = map.get_mut(name) {
+            // Check if anything actually changed
+            if existing.x == x
+                && existing.y == y
+                && existing.dimensions.width == width
+                && existing.dimensions.height == height
+            {
+                // No change
+                return false;
+            }
+
+            // Update existing entry
+            existing.x = x;
+            existing.y = y;
+            existing.dimensions.width = width;
+            existing.dimensions.height = height;
+            true
+        } else {
             // New entry - always a change
             map.insert(
                 name.to_string(),
@@ -470,6 +1591,233 @@ impl Profile {
             true
         }
     }
+
+    /// Display name of a character or custom source (alias template applied)
+    pub fn display_name(&self, name: &str) -> String {
+        crate::common::types::alias::display_name(
+            &self.thumbnail_alias_template,
+            self.settings_for(name),
+            name,
+        )
+    }
+
+    /// Sorts character names for display: ordering keys first, then by display name
+    pub fn sort_for_display(&self, names: &mut [String]) {
+        let mut keyed: Vec<(Option<i32>, String, String)> = names
+            .iter()
+            .map(|name| {
+                let order = self.settings_for(name).and_then(|s| s.display_order);
+                (order, self.display_name(name), name.clone())
+            })
+            .collect();
+        keyed.sort_by(|a, b| {
+            crate::common::types::alias::compare_for_display((a.0, &a.1), (b.0, &b.1))
+        });
+        for (slot, (_, _, name)) in names.iter_mut().zip(keyed) {
+            *slot = name;
+        }
+    }
+
+    fn settings_for(&self, name: &str) -> Option<&CharacterSettings> {
+        self.character_thumbnails
+            .get(name)
+            .or_else(|| self.custom_source_thumbnails.get(name))
+    }
+
+    /// Insert a slot into a cycle group at `index` (clamped to the end of the list).
+    /// If the slot is already in that group it is moved there instead of duplicated.
+    /// Returns true if the group was modified.
+    pub fn insert_cycle_slot(&mut self, group: usize, slot: CycleSlot, mut index: usize) -> bool {
+        let Some(group) = self.cycle_groups.get_mut(group) else {
+            return false;
+        };
+
+        if let Some(existing) = group.cycle_list.iter().position(|s| *s == slot) {
+            if existing < index {
+                index -= 1;
+            }
+            if existing == index {
+                return false;
+            }
+            group.cycle_list.remove(existing);
+        }
+
+        let index = index.min(group.cycle_list.len());
+        group.cycle_list.insert(index, slot);
+        true
+    }
+
+    /// Pre-create thumbnails for characters that are not known yet, laid out in a grid after
+    /// the existing ones, and optionally append them to a cycle group and give characters
+    /// without a hotkey the next free Ctrl+F-key.
+    /// Returns the number of characters that were new to the profile.
+    pub fn import_characters(
+        &mut self,
+        names: &[String],
+        cycle_group: Option<usize>,
+        assign_hotkeys: bool,
+    ) -> usize {
+        const COLUMNS: usize = 4;
+        const GAP: i32 = 10;
+
+        let width = self.thumbnail_default_width;
+        let height = self.thumbnail_default_height;
+        let mut added = 0;
+
+        for name in names {
+            if name.is_empty() {
+                continue;
+            }
+            if !self.character_thumbnails.contains_key(name) {
+                let cell = self.character_thumbnails.len();
+                let x = (cell % COLUMNS) as i32 * (i32::from(width) + GAP) + GAP;
+                let y = (cell / COLUMNS) as i32 * (i32::from(height) + GAP) + GAP;
+                self.character_thumbnails.insert(
+                    name.clone(),
+                    CharacterSettings::new(
+                        x.clamp(0, i32::from(i16::MAX)) as i16,
+                        y.clamp(0, i32::from(i16::MAX)) as i16,
+                        width,
+                        height,
+                    ),
+                );
+                self.archived_characters.remove(name);
+                added += 1;
+            }
+            if let Some(group) = cycle_group.and_then(|g| self.cycle_groups.get_mut(g)) {
+                let slot = CycleSlot::Eve(name.clone());
+                if !group.cycle_list.contains(&slot) {
+                    group.cycle_list.push(slot);
+                }
+            }
+            if assign_hotkeys
+                && !self.character_hotkeys.contains_key(name)
+                && let Some(binding) = self.next_free_import_hotkey()
+            {
+                self.character_hotkeys.insert(name.clone(), binding);
+            }
+        }
+        added
+    }
+
+    /// First Ctrl+F-key not bound to anything in this profile
+    fn next_free_import_hotkey(&self) -> Option<crate::config::HotkeyBinding> {
+        let assigned = self.hotkey_assignments();
+        crate::common::constants::input::IMPORT_HOTKEY_KEYS
+            .iter()
+            .map(|&key| crate::config::HotkeyBinding::new(key, true, false, false, false))
+            .find(|candidate| !assigned.iter().any(|(_, bound)| bound.same_keys(candidate)))
+    }
+
+    /// Index of the account owning a character
+    pub fn account_of(&self, name: &str) -> Option<usize> {
+        self.accounts
+            .iter()
+            .position(|account| account.characters.iter().any(|c| c == name))
+    }
+
+    /// Move a character into an account (or out of all accounts with `None`).
+    /// Returns true if membership changed.
+    pub fn set_character_account(&mut self, name: &str, account: Option<usize>) -> bool {
+        let current = self.account_of(name);
+        if current == account || account.is_some_and(|idx| idx >= self.accounts.len()) {
+            return false;
+        }
+        if let Some(idx) = current {
+            self.accounts[idx].characters.retain(|c| c != name);
+        }
+        if let Some(idx) = account {
+            self.accounts[idx].characters.push(name.to_string());
+        }
+        true
+    }
+
+    /// Splits `names` (already sorted for display) by owning account: one entry per account in
+    /// configuration order, followed by characters without an account (`None`).
+    pub fn group_by_account(&self, names: Vec<String>) -> Vec<(Option<usize>, Vec<String>)> {
+        let mut groups: Vec<(Option<usize>, Vec<String>)> = (0..self.accounts.len())
+            .map(|idx| (Some(idx), Vec::new()))
+            .collect();
+        let mut unassigned = Vec::new();
+        for name in names {
+            match self.account_of(&name) {
+                Some(idx) => groups[idx].1.push(name),
+                None => unassigned.push(name),
+            }
+        }
+        groups.push((None, unassigned));
+        groups
+    }
+
+    /// Remove a character with its hotkey, cycle group and account entries.
+    /// Returns the removed settings, if the character was known.
+    pub fn remove_character(&mut self, name: &str) -> Option<CharacterSettings> {
+        self.character_hotkeys.remove(name);
+        for account in &mut self.accounts {
+            account.characters.retain(|c| c != name);
+        }
+        for group in &mut self.cycle_groups {
+            group.cycle_list.retain(|slot| match slot {
+                CycleSlot::Eve(n) | CycleSlot::Source(n) => n != name,
+            });
+        }
+        self.character_thumbnails.remove(name)
+    }
+
+    /// Move a character into the archive. Its settings (position, alias, notes) are kept
+    /// for a later restore; the hotkey, cycle group and account entries are dropped.
+    pub fn archive_character(&mut self, name: &str) -> bool {
+        match self.remove_character(name) {
+            Some(settings) => {
+                self.archived_characters.insert(name.to_string(), settings);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move an archived character back to the active character list
+    pub fn restore_character(&mut self, name: &str) -> bool {
+        match self.archived_characters.remove(name) {
+            Some(settings) => {
+                self.character_thumbnails
+                    .entry(name.to_string())
+                    .or_insert(settings);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move a slot from one cycle group to `to_index` in another (or the same) group.
+    /// Returns true if any group was modified.
+    pub fn move_cycle_slot(
+        &mut self,
+        from_group: usize,
+        from_index: usize,
+        to_group: usize,
+        to_index: usize,
+    ) -> bool {
+        if to_group >= self.cycle_groups.len() {
+            return false;
+        }
+        let Some(slot) = self
+            .cycle_groups
+            .get(from_group)
+            .and_then(|g| g.cycle_list.get(from_index))
+            .cloned()
+        else {
+            return false;
+        };
+
+        if from_group == to_group {
+            return self.insert_cycle_slot(to_group, slot, to_index);
+        }
+
+        self.cycle_groups[from_group].cycle_list.remove(from_index);
+        self.insert_cycle_slot(to_group, slot, to_index);
+        true
+    }
 }
 
 impl Default for Profile {
@@ -706,6 +2054,315 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_visibility_schedule_rule() {
+        use chrono::Weekday;
+
+        // Default: weekdays 09:00-17:00
+        let work = VisibilityScheduleRule::default();
+        assert!(work.hides_at(Weekday::Mon, 9 * 60));
+        assert!(work.hides_at(Weekday::Fri, 17 * 60 - 1));
+        assert!(!work.hides_at(Weekday::Fri, 17 * 60));
+        assert!(!work.hides_at(Weekday::Wed, 8 * 60 + 59));
+        assert!(!work.hides_at(Weekday::Sat, 12 * 60));
+
+        // Friday 22:00 to 02:00 reaches into Saturday morning only
+        let night = VisibilityScheduleRule {
+            days: [false, false, false, false, true, false, false],
+            start_minute: 22 * 60,
+            end_minute: 2 * 60,
+        };
+        assert!(night.hides_at(Weekday::Fri, 23 * 60));
+        assert!(night.hides_at(Weekday::Sat, 60));
+        assert!(!night.hides_at(Weekday::Sat, 2 * 60));
+        assert!(!night.hides_at(Weekday::Fri, 60));
+
+        // Equal start and end covers the whole day
+        let all_day = VisibilityScheduleRule {
+            days: [false, false, false, false, false, false, true],
+            start_minute: 0,
+            end_minute: 0,
+        };
+        assert!(all_day.hides_at(Weekday::Sun, 0));
+        assert!(all_day.hides_at(Weekday::Sun, 24 * 60 - 1));
+        assert!(!all_day.hides_at(Weekday::Mon, 0));
+    }
+
+    #[test]
+    fn test_schedule_hides_previews() {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        // 2026-10-14 is a Wednesday
+        let noon = chrono::NaiveDate::from_ymd_opt(2026, 10, 14)
+            .unwrap()
+            .and_hms_opt(12, 30, 0)
+            .unwrap();
+        assert!(!profile.schedule_hides_previews(&noon));
+
+        profile
+            .visibility_schedule
+            .push(VisibilityScheduleRule::default());
+        assert!(profile.schedule_hides_previews(&noon));
+    }
+
+    #[test]
+    fn test_is_hide_exempt_class() {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        assert!(!profile.is_hide_exempt_class("discord"));
+
+        profile.thumbnail_hide_exempt_classes =
+            vec![" Discord ".to_string(), String::new(), "Mumble".to_string()];
+        assert!(profile.is_hide_exempt_class("discord"));
+        assert!(profile.is_hide_exempt_class("MUMBLE"));
+        assert!(!profile.is_hide_exempt_class("firefox"));
+        assert!(!profile.is_hide_exempt_class(""));
+    }
+
+    #[test]
+    fn test_hide_exempt_classes_survive_ipc() {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        profile.thumbnail_hide_fade_ms = 250;
+        profile.thumbnail_hide_exempt_classes = vec!["Discord".to_string()];
+        profile.thumbnail_preserve_position_on_swap = false;
+
+        // IPC uses the binary layout, which must list fields in declaration order
+        let (tx, rx) = ipc_channel::ipc::channel().unwrap();
+        tx.send(profile).unwrap();
+        let received: Profile = rx.recv().unwrap();
+        assert_eq!(received.thumbnail_hide_fade_ms, 250);
+        assert_eq!(
+            received.thumbnail_hide_exempt_classes,
+            vec!["Discord".to_string()]
+        );
+        assert!(!received.thumbnail_preserve_position_on_swap);
+    }
+
+    #[test]
+    fn test_hotkey_focus_allows() {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        assert!(profile.hotkey_focus_allows("Alice", false));
+        assert!(profile.hotkey_focus_allows("Browser", true));
+
+        profile.hotkey_focus_context = HotkeyFocusContext::Eve;
+        assert!(profile.hotkey_focus_allows("Alice", false));
+        assert!(!profile.hotkey_focus_allows("Browser", true));
+
+        profile.hotkey_focus_context = HotkeyFocusContext::Sources;
+        profile.hotkey_focus_sources = vec!["Browser".to_string()];
+        assert!(!profile.hotkey_focus_allows("Alice", false));
+        assert!(profile.hotkey_focus_allows("Browser", true));
+        assert!(!profile.hotkey_focus_allows("Notes", true));
+
+        profile.hotkey_blocked_classes = vec!["firefox".to_string()];
+        assert!(profile.is_hotkey_blocked_class("Firefox"));
+        assert!(!profile.is_hotkey_blocked_class("discord"));
+    }
+
+    #[test]
+    fn test_custom_window_rule_matching() {
+        let mut rule: CustomWindowRule =
+            serde_json::from_str(r#"{"title_pattern": "Discord", "alias": "Comms"}"#).unwrap();
+        assert!(rule.matches("#fleet - discord", "anything"));
+        assert!(rule.mismatch_reason("Firefox", "firefox").is_some());
+
+        rule.class_pattern = Some("vesktop".to_string());
+        assert!(rule.matches("Discord", "Vesktop"));
+        assert!(!rule.matches("Discord", "Firefox"));
+
+        rule.title_pattern = None;
+        rule.class_pattern = None;
+        assert!(!rule.matches("Discord", "Vesktop"));
+    }
+
+    #[test]
+    fn test_hotkey_conflicts() {
+        use crate::config::HotkeyBinding;
+
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        let f1 = HotkeyBinding::new(59, false, false, false, false);
+        let mut f1_keyboard = f1.clone();
+        f1_keyboard.source_devices = vec!["usb-Keyboard-event-kbd".to_string()];
+        profile.hotkey_pause = Some(f1.clone());
+        profile.cycle_groups[0].hotkey_forward = Some(f1_keyboard);
+        profile.character_hotkeys.insert(
+            "Pilot".to_string(),
+            HotkeyBinding::new(59, true, false, false, false),
+        );
+
+        let group = profile.cycle_groups[0].name.clone();
+        // Source devices don't matter, modifiers do
+        assert_eq!(
+            profile.hotkey_conflicts(&f1, "Overview"),
+            vec![
+                "Pause".to_string(),
+                format!("Cycle Group '{}' Forward", group)
+            ]
+        );
+        // The action being rebound doesn't conflict with itself
+        assert_eq!(
+            profile.hotkey_conflicts(&f1, "Pause"),
+            vec![format!("Cycle Group '{}' Forward", group)]
+        );
+        assert!(
+            profile
+                .hotkey_conflicts(&HotkeyBinding::new(60, false, false, false, false), "Pause")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_alias_conflict() {
+        let mut profile = Profile::default();
+        profile
+            .character_thumbnails
+            .insert("Pilot".to_string(), CharacterSettings::new(0, 0, 0, 0));
+        profile.custom_windows.push(
+            serde_json::from_str(r#"{"class_pattern": "discord", "alias": "Comms"}"#).unwrap(),
+        );
+
+        assert!(profile.alias_conflict("", None).is_some());
+        assert!(profile.alias_conflict("Pilot", None).is_some());
+        assert!(profile.alias_conflict("Comms", None).is_some());
+        // A rule doesn't conflict with itself
+        assert!(profile.alias_conflict("Comms", Some(0)).is_none());
+        assert!(profile.alias_conflict("Browser", None).is_none());
+
+        assert_eq!(profile.unique_alias("Pilot", None), "Pilot (2)");
+        assert_eq!(profile.unique_alias("Browser", None), "Browser");
+    }
+
+    #[test]
+    fn test_migrate_source_geometry() {
+        let mut profile = Profile::default();
+        profile
+            .custom_source_thumbnails
+            .insert("Comms".to_string(), CharacterSettings::new(5, 6, 300, 200));
+        for alias in ["Comms", "Browser"] {
+            let mut rule: CustomWindowRule =
+                serde_json::from_str(r#"{"class_pattern": "x", "alias": ""}"#).unwrap();
+            rule.alias = alias.to_string();
+            profile.custom_windows.push(rule);
+        }
+
+        let json = serde_json::to_string(&profile).unwrap();
+        let mut loaded: Profile = serde_json::from_str(&json).unwrap();
+
+        let ids: Vec<u32> = loaded.custom_windows.iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(loaded.next_rule_id(), 3);
+        let slot = LayoutSlot {
+            x: 5,
+            y: 6,
+            width: 300,
+            height: 200,
+        };
+        assert_eq!(
+            SourceGeometry::find(&loaded.custom_source_geometry, 1, 0),
+            Some(slot)
+        );
+        assert_eq!(
+            SourceGeometry::find(&loaded.custom_source_geometry, 2, 0),
+            None
+        );
+
+        // A second instance gets its own slot; re-saving the same geometry is a no-op
+        let moved = LayoutSlot { x: 400, ..slot };
+        assert!(SourceGeometry::upsert(
+            &mut loaded.custom_source_geometry,
+            1,
+            1,
+            moved
+        ));
+        assert!(!SourceGeometry::upsert(
+            &mut loaded.custom_source_geometry,
+            1,
+            1,
+            moved
+        ));
+        assert_eq!(
+            SourceGeometry::find(&loaded.custom_source_geometry, 1, 0),
+            Some(slot)
+        );
+
+        // Renaming the rule keeps its geometry
+        loaded.custom_windows[0].alias = "Voice".to_string();
+        let json = serde_json::to_string(&loaded).unwrap();
+        let reloaded: Profile = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.custom_windows[0].id, 1);
+        assert_eq!(
+            SourceGeometry::find(&reloaded.custom_source_geometry, 1, 1),
+            Some(moved)
+        );
+    }
+
+    #[test]
+    fn test_load_disambiguates_source_aliases() {
+        let mut profile = Profile::default();
+        profile
+            .character_thumbnails
+            .insert("Pilot".to_string(), CharacterSettings::new(1, 1, 100, 100));
+        profile
+            .custom_source_thumbnails
+            .insert("Pilot".to_string(), CharacterSettings::new(2, 2, 200, 200));
+        let rule: CustomWindowRule =
+            serde_json::from_str(r#"{"class_pattern": "discord", "alias": "Pilot"}"#).unwrap();
+        profile.custom_windows = vec![rule.clone(), rule];
+        profile.cycle_groups[0].cycle_list = vec![
+            CycleSlot::Eve("Pilot".to_string()),
+            CycleSlot::Source("Pilot".to_string()),
+        ];
+
+        let json = serde_json::to_string(&profile).unwrap();
+        let loaded: Profile = serde_json::from_str(&json).unwrap();
+
+        let aliases: Vec<&str> = loaded
+            .custom_windows
+            .iter()
+            .map(|r| r.alias.as_str())
+            .collect();
+        assert_eq!(aliases, vec!["Pilot (2)", "Pilot (3)"]);
+        // The character keeps its settings and slot; the source's follow its new name
+        assert_eq!(loaded.character_thumbnails["Pilot"].x, 1);
+        assert_eq!(loaded.custom_source_thumbnails["Pilot (2)"].x, 2);
+        assert_eq!(
+            loaded.cycle_groups[0].cycle_list,
+            vec![
+                CycleSlot::Eve("Pilot".to_string()),
+                CycleSlot::Source("Pilot (2)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_backend_serialization() {
+        let profile = Profile::default_with_name("Test".to_string(), String::new());
+        assert_eq!(profile.thumbnail_capture_backend, CaptureBackendType::Auto);
+
+        assert_eq!(
+            serde_json::to_string(&CaptureBackendType::Screenshot).unwrap(),
+            "\"screenshot\""
+        );
+        let forced: CaptureBackendType = serde_json::from_str("\"composite\"").unwrap();
+        assert_eq!(forced, CaptureBackendType::Composite);
+        let gpu: CaptureBackendType = serde_json::from_str("\"gpu\"").unwrap();
+        assert_eq!(gpu, CaptureBackendType::Gpu);
+        let shm: CaptureBackendType = serde_json::from_str("\"shm\"").unwrap();
+        assert_eq!(shm, CaptureBackendType::Shm);
+    }
+
+    #[test]
+    fn test_scale_filter_serialization() {
+        let profile = Profile::default_with_name("Test".to_string(), String::new());
+        assert_eq!(profile.thumbnail_scale_filter, ScaleFilter::Bilinear);
+
+        let best: ScaleFilter = serde_json::from_str("\"best\"").unwrap();
+        assert_eq!(best, ScaleFilter::Best);
+        assert_eq!(
+            serde_json::to_string(&ScaleFilter::Nearest).unwrap(),
+            "\"nearest\""
+        );
+        assert_eq!(ScaleFilter::Good.render_name(), "good");
+    }
+
     #[test]
     fn test_profile_with_hotkeys() {
         let mut profile = Profile::default_with_name("Hotkey Test".to_string(), String::new());
@@ -797,6 +2454,53 @@ mod tests {
         assert!(group.hotkey_backward.is_some());
     }
 
+    #[test]
+    fn test_migration_legacy_require_eve_focus() {
+        let default_profile = Profile::default_with_name("Legacy Test".to_string(), String::new());
+        let mut json_value = serde_json::to_value(&default_profile).unwrap();
+        let obj = json_value.as_object_mut().unwrap();
+        obj.remove("hotkey_focus_context");
+
+        obj.insert(
+            "hotkey_require_eve_focus".to_string(),
+            serde_json::json!(true),
+        );
+        let profile: Profile = serde_json::from_value(json_value.clone()).unwrap();
+        assert_eq!(profile.hotkey_focus_context, HotkeyFocusContext::Clients);
+
+        json_value.as_object_mut().unwrap().insert(
+            "hotkey_require_eve_focus".to_string(),
+            serde_json::json!(false),
+        );
+        let profile: Profile = serde_json::from_value(json_value).unwrap();
+        assert_eq!(profile.hotkey_focus_context, HotkeyFocusContext::Always);
+    }
+
+    #[test]
+    fn test_migration_legacy_hide_fade_out() {
+        let default_profile = Profile::default_with_name("Legacy Test".to_string(), String::new());
+        let mut json_value = serde_json::to_value(&default_profile).unwrap();
+        let obj = json_value.as_object_mut().unwrap();
+        obj.remove("thumbnail_hide_fade_ms");
+
+        obj.insert(
+            "thumbnail_hide_fade_out".to_string(),
+            serde_json::json!(true),
+        );
+        let profile: Profile = serde_json::from_value(json_value.clone()).unwrap();
+        assert_eq!(
+            profile.thumbnail_hide_fade_ms,
+            crate::common::constants::defaults::timing::FOCUS_LOSS_FADE_MS
+        );
+
+        json_value.as_object_mut().unwrap().insert(
+            "thumbnail_hide_fade_out".to_string(),
+            serde_json::json!(false),
+        );
+        let profile: Profile = serde_json::from_value(json_value).unwrap();
+        assert_eq!(profile.thumbnail_hide_fade_ms, 0);
+    }
+
     #[test]
     fn test_filesystem_roundtrip() {
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
@@ -832,4 +2536,318 @@ mod tests {
             crate::common::constants::defaults::behavior::PROFILE_NAME
         );
     }
+
+    fn profile_with_groups() -> Profile {
+        Profile {
+            cycle_groups: vec![
+                CycleGroup {
+                    name: "G1".to_string(),
+                    cycle_list: vec![
+                        CycleSlot::Eve("A".to_string()),
+                        CycleSlot::Eve("B".to_string()),
+                        CycleSlot::Eve("C".to_string()),
+                    ],
+                    hotkey_forward: None,
+                    hotkey_backward: None,
+                },
+                CycleGroup {
+                    name: "G2".to_string(),
+                    cycle_list: vec![CycleSlot::Eve("D".to_string())],
+                    hotkey_forward: None,
+                    hotkey_backward: None,
+                },
+            ],
+            ..Profile::default()
+        }
+    }
+
+    fn names(group: &CycleGroup) -> Vec<&str> {
+        group
+            .cycle_list
+            .iter()
+            .map(|s| match s {
+                CycleSlot::Eve(n) | CycleSlot::Source(n) => n.as_str(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_move_cycle_slot_within_group() {
+        let mut profile = profile_with_groups();
+
+        // Drop A below C
+        assert!(profile.move_cycle_slot(0, 0, 0, 3));
+        assert_eq!(names(&profile.cycle_groups[0]), ["B", "C", "A"]);
+
+        // Dropping onto its own position is a no-op
+        assert!(!profile.move_cycle_slot(0, 1, 0, 1));
+    }
+
+    #[test]
+    fn test_move_cycle_slot_between_groups() {
+        let mut profile = profile_with_groups();
+
+        assert!(profile.move_cycle_slot(0, 1, 1, 0));
+        assert_eq!(names(&profile.cycle_groups[0]), ["A", "C"]);
+        assert_eq!(names(&profile.cycle_groups[1]), ["B", "D"]);
+
+        // Invalid indices leave everything untouched
+        assert!(!profile.move_cycle_slot(0, 9, 1, 0));
+        assert!(!profile.move_cycle_slot(0, 0, 5, 0));
+    }
+
+    #[test]
+    fn test_insert_cycle_slot_does_not_duplicate() {
+        let mut profile = profile_with_groups();
+
+        assert!(profile.insert_cycle_slot(1, CycleSlot::Eve("A".to_string()), usize::MAX));
+        assert_eq!(names(&profile.cycle_groups[1]), ["D", "A"]);
+
+        // Inserting an existing slot moves it
+        assert!(profile.insert_cycle_slot(1, CycleSlot::Eve("A".to_string()), 0));
+        assert_eq!(names(&profile.cycle_groups[1]), ["A", "D"]);
+        assert!(!profile.insert_cycle_slot(1, CycleSlot::Eve("A".to_string()), 0));
+    }
+
+    fn client(name: &str, x: i16, y: i16) -> ClientGeometry {
+        ClientGeometry {
+            name: name.to_string(),
+            is_custom: false,
+            x,
+            y,
+            width: 200,
+            height: 100,
+        }
+    }
+
+    #[test]
+    fn test_add_active_clients_skips_existing() {
+        let mut profile = profile_with_groups();
+        let clients = [client("E", 300, 0), client("A", 0, 0), client("F", 0, 200)];
+
+        assert_eq!(profile.cycle_groups[0].add_active_clients(&clients), 2);
+        assert_eq!(names(&profile.cycle_groups[0]), ["A", "B", "C", "E", "F"]);
+        assert_eq!(profile.cycle_groups[0].add_active_clients(&clients), 0);
+    }
+
+    #[test]
+    fn test_remove_inactive_matches_slot_kind() {
+        let mut profile = profile_with_groups();
+        let mut source = client("B", 0, 0);
+        source.is_custom = true;
+
+        // A custom source named "B" does not keep the EVE slot "B" alive
+        let removed = profile.cycle_groups[0].remove_inactive(&[client("A", 0, 0), source]);
+        assert_eq!(removed, 2);
+        assert_eq!(names(&profile.cycle_groups[0]), ["A"]);
+    }
+
+    #[test]
+    fn test_sort_by_layout_reading_order() {
+        let mut profile = profile_with_groups();
+        profile.cycle_groups[0]
+            .cycle_list
+            .push(CycleSlot::Eve("Offline".to_string()));
+
+        // C and A share a row despite a small vertical offset, B sits below
+        let clients = [client("A", 400, 10), client("B", 0, 300), client("C", 0, 0)];
+
+        assert!(profile.cycle_groups[0].sort_by_layout(&clients));
+        assert_eq!(names(&profile.cycle_groups[0]), ["C", "A", "B", "Offline"]);
+        assert!(!profile.cycle_groups[0].sort_by_layout(&clients));
+    }
+
+    #[test]
+    fn test_capture_layout() {
+        let mut profile = Profile::default();
+        profile
+            .character_thumbnails
+            .insert("A".to_string(), CharacterSettings::new(10, 20, 300, 200));
+        profile
+            .thumbnail_layouts
+            .push(profile.capture_layout("Mining"));
+
+        let layout = profile.layout("Mining").unwrap();
+        assert_eq!(
+            layout.thumbnails["A"],
+            LayoutSlot {
+                x: 10,
+                y: 20,
+                width: 300,
+                height: 200
+            }
+        );
+        assert!(profile.layout("PvP").is_none());
+    }
+
+    #[test]
+    fn test_session_snapshot_serialization() {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        profile.session_snapshots.push(SessionSnapshot {
+            name: "Fleet".to_string(),
+            saved_at: 1_700_000_000,
+            clients: vec![ClientSnapshot {
+                name: "A".to_string(),
+                window: LayoutSlot {
+                    x: 0,
+                    y: 0,
+                    width: 1920,
+                    height: 1080,
+                },
+                minimized: true,
+                thumbnail: None,
+            }],
+            focused: Some("A".to_string()),
+        });
+
+        let json = serde_json::to_string(&profile).unwrap();
+        let deserialized: Profile = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.session_snapshots, profile.session_snapshots);
+    }
+
+    #[test]
+    fn test_sort_for_display_uses_order_and_alias() {
+        let mut profile = Profile::default();
+        for name in ["Charlie", "Alpha", "Bravo"] {
+            profile
+                .character_thumbnails
+                .insert(name.to_string(), CharacterSettings::new(0, 0, 0, 0));
+        }
+        profile
+            .character_thumbnails
+            .get_mut("Charlie")
+            .unwrap()
+            .display_order = Some(1);
+        profile.character_thumbnails.get_mut("Bravo").unwrap().alias = Some("Aardvark".to_string());
+
+        let mut names = vec![
+            "Alpha".to_string(),
+            "Bravo".to_string(),
+            "Charlie".to_string(),
+        ];
+        profile.sort_for_display(&mut names);
+        assert_eq!(names, ["Charlie", "Bravo", "Alpha"]);
+        assert_eq!(profile.display_name("Bravo"), "Aardvark");
+    }
+
+    #[test]
+    fn test_archive_and_restore_character() {
+        let mut profile = profile_with_groups();
+        profile
+            .character_thumbnails
+            .insert("A".to_string(), CharacterSettings::new(10, 20, 0, 0));
+
+        assert!(profile.archive_character("A"));
+        assert!(!profile.character_thumbnails.contains_key("A"));
+        assert!(
+            !profile.cycle_groups[0]
+                .cycle_list
+                .contains(&CycleSlot::Eve("A".to_string()))
+        );
+        assert!(!profile.archive_character("A"));
+
+        assert!(profile.restore_character("A"));
+        assert_eq!(profile.character_thumbnails["A"].x, 10);
+        assert!(profile.archived_characters.is_empty());
+    }
+
+    #[test]
+    fn test_import_characters() {
+        let mut profile = profile_with_groups();
+        profile
+            .character_thumbnails
+            .insert("A".to_string(), CharacterSettings::new(0, 0, 0, 0));
+
+        let names = vec!["A".to_string(), "New".to_string(), String::new()];
+        assert_eq!(profile.import_characters(&names, Some(1), false), 1);
+        assert!(profile.character_hotkeys.is_empty());
+
+        let new = &profile.character_thumbnails["New"];
+        assert_eq!(
+            new.x,
+            i16::try_from(profile.thumbnail_default_width).unwrap() + 20
+        );
+        assert_eq!(new.dimensions.width, profile.thumbnail_default_width);
+        assert!(
+            profile.cycle_groups[1]
+                .cycle_list
+                .contains(&CycleSlot::Eve("New".to_string()))
+        );
+        assert!(
+            profile.cycle_groups[1]
+                .cycle_list
+                .contains(&CycleSlot::Eve("A".to_string()))
+        );
+
+        // Importing again is a no-op
+        assert_eq!(profile.import_characters(&names, Some(1), false), 0);
+        assert_eq!(profile.cycle_groups[1].cycle_list.len(), 3);
+    }
+
+    #[test]
+    fn test_import_characters_assigns_free_hotkeys() {
+        use crate::config::HotkeyBinding;
+
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        let ctrl_f1 = HotkeyBinding::new(59, true, false, false, false);
+        profile.hotkey_overview = Some(ctrl_f1.clone());
+        profile.character_hotkeys.insert(
+            "Bound".to_string(),
+            HotkeyBinding::new(5, false, false, false, false),
+        );
+
+        let names = vec!["Bound".to_string(), "One".to_string(), "Two".to_string()];
+        assert_eq!(profile.import_characters(&names, None, true), 3);
+        assert_eq!(profile.character_hotkeys["Bound"].key_code, 5);
+        assert!(
+            profile.character_hotkeys["One"]
+                .same_keys(&HotkeyBinding::new(60, true, false, false, false))
+        );
+        assert!(
+            profile.character_hotkeys["Two"]
+                .same_keys(&HotkeyBinding::new(61, true, false, false, false))
+        );
+    }
+
+    #[test]
+    fn test_character_account_assignment() {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        profile.accounts = vec![
+            Account {
+                name: "Main".to_string(),
+                ..Default::default()
+            },
+            Account {
+                name: "Alts".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        assert!(profile.set_character_account("Jane", Some(0)));
+        assert!(profile.set_character_account("Bob", Some(1)));
+        assert!(!profile.set_character_account("Bob", Some(1)));
+        assert!(!profile.set_character_account("Bob", Some(5)));
+
+        // Moving between accounts keeps a single membership
+        assert!(profile.set_character_account("Jane", Some(1)));
+        assert_eq!(profile.account_of("Jane"), Some(1));
+        assert!(profile.accounts[0].characters.is_empty());
+
+        let groups = profile.group_by_account(vec![
+            "Bob".to_string(),
+            "Jane".to_string(),
+            "Solo".to_string(),
+        ]);
+        assert_eq!(
+            groups,
+            vec![
+                (Some(0), vec![]),
+                (Some(1), vec!["Bob".to_string(), "Jane".to_string()]),
+                (None, vec!["Solo".to_string()]),
+            ]
+        );
+
+        profile.remove_character("Jane");
+        assert_eq!(profile.account_of("Jane"), None);
+    }
 }
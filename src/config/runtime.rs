@@ -11,6 +11,7 @@ use x11rb::protocol::render::Color;
 
 use crate::common::color::{HexColor, Opacity};
 use crate::common::types::{CharacterSettings, Position, TextOffset};
+use crate::config::profile::{LayoutSlot, SourceGeometry};
 
 /// Snapshot of display settings for the renderer.
 #[derive(Debug, Clone)]
@@ -21,7 +22,16 @@ pub struct DisplayConfig {
     pub active_border_color: Color,
     pub text_offset: TextOffset,
     pub text_color: u32,
+    /// Outline or drop shadow behind labels, drawn in `text_effect_color` (ARGB)
+    pub text_effect: crate::config::TextEffect,
+    pub text_effect_color: u32,
+    pub text_shadow_offset: TextOffset,
+    pub text_outline_width: u16,
     pub hide_when_no_focus: bool,
+    /// Fade duration when hiding on focus loss and revealing on focus gain (None = instant)
+    pub hide_fade: Option<std::time::Duration>,
+    /// Minimum time between damage repaints of one live thumbnail
+    pub damage_frame: std::time::Duration,
     pub inactive_border_enabled: bool,
 
     /// Map of character name -> settings (overrides, aliases, etc)
@@ -29,7 +39,90 @@ pub struct DisplayConfig {
         std::collections::HashMap<String, crate::common::types::CharacterSettings>,
     pub inactive_border_color: Color,
     pub inactive_border_size: u16,
+    /// Border for unfocused Singularity/Thunderdome/Duality clients
+    pub test_server_border_color: Color,
+    /// Gap between the border and the client content
+    pub border_padding: u16,
+    /// Width of the glow fading inward from the border (0 = off)
+    pub border_glow: u16,
+    /// Border line styles of focused and unfocused thumbnails
+    pub active_border_style: crate::config::BorderStyle,
+    pub inactive_border_style: crate::config::BorderStyle,
+    /// Border style and width of skipped and minimized clients (None = as unfocused)
+    pub skipped_border_style: Option<crate::config::BorderStyle>,
+    pub skipped_border_size: Option<u16>,
+    pub minimized_border_style: Option<crate::config::BorderStyle>,
+    pub minimized_border_size: Option<u16>,
+    /// Recoloring of unfocused thumbnails' content
+    pub unfocused_effect: crate::config::UnfocusedEffect,
+    /// Tint for `UnfocusedEffect::Tint` (premultiplied, ready for XRender)
+    pub unfocused_tint: Color,
     pub minimized_overlay_enabled: bool,
+    /// Minimized clients keep showing their last frame, dimmed, instead of a black preview
+    pub minimized_freeze_frame: bool,
+    /// Text, fill color and image of the minimized placeholder
+    pub minimized_text: String,
+    pub minimized_background: Color,
+    pub minimized_image: crate::config::MinimizedImage,
+    /// Render character notes below the name even when not hovered
+    pub show_notes: bool,
+    /// Alias template for characters without their own alias
+    pub alias_template: String,
+    /// Character name -> account label tag, drawn before the name
+    pub account_tags: HashMap<String, String>,
+    /// Default thumbnail outline (per-character overrides live in `character_settings`)
+    pub shape: crate::common::types::ThumbnailShape,
+    pub corner_radius: u16,
+    /// Size of per-character corner icons in pixels
+    pub icon_size: u16,
+    /// Cycle-order badge height in pixels (0 = badges off) and the corner it sits in
+    pub cycle_badge_size: u16,
+    pub cycle_badge_corner: crate::config::BadgeCorner,
+    /// Name -> 1-based position in the first cycle group listing it
+    pub cycle_positions: HashMap<String, usize>,
+    /// Status badge icon size in pixels (0 = badges off) and the corner they line up from
+    pub status_badge_size: u16,
+    pub status_badge_corner: crate::config::BadgeCorner,
+    /// Characters with a per-character hotkey, shown with the hotkey status badge
+    pub hotkey_bound: std::collections::HashSet<String>,
+    /// Requested thumbnail capture backend (resolved per thumbnail by the daemon)
+    pub capture_backend: crate::config::CaptureBackendType,
+    /// Filter for scaling clients down (Composite capture)
+    pub scale_filter: crate::config::ScaleFilter,
+    /// Present repaints at vblank instead of copying them to the window as soon as they are drawn
+    pub vsync: bool,
+}
+
+impl DisplayConfig {
+    /// Whether this character's thumbnail is exempt from hide-when-no-focus
+    pub fn keeps_visible_without_focus(&self, character_name: &str) -> bool {
+        self.character_settings
+            .get(character_name)
+            .is_some_and(|s| s.keep_visible_without_focus)
+    }
+
+    /// Whether this character's thumbnail is exempt from hiding all previews (toggle, panic,
+    /// visibility schedule)
+    pub fn always_visible(&self, character_name: &str) -> bool {
+        self.character_settings
+            .get(character_name)
+            .is_some_and(|s| s.always_visible)
+    }
+
+    /// Timelapse interval for this character, if it is being recorded
+    pub fn timelapse_interval(&self, character_name: &str) -> Option<std::time::Duration> {
+        self.character_settings
+            .get(character_name)
+            .and_then(|s| s.timelapse_interval_secs)
+            .map(|secs| std::time::Duration::from_secs(u64::from(secs.max(1))))
+    }
+
+    /// Stacking priority of this character's thumbnail (0 when unset)
+    pub fn stack_priority(&self, character_name: &str) -> i32 {
+        self.character_settings
+            .get(character_name)
+            .map_or(0, |s| s.stack_priority)
+    }
 }
 use serde::{Deserialize, Serialize};
 
@@ -42,18 +135,113 @@ pub struct DaemonConfig {
     pub character_thumbnails: HashMap<String, CharacterSettings>,
     /// Active custom source settings
     pub custom_source_thumbnails: HashMap<String, CharacterSettings>,
+    /// Per-instance custom source geometry (runtime overrides of the profile's)
+    pub custom_source_geometry: Vec<SourceGeometry>,
     /// Flattened map of hotkey bindings to profile names
     pub profile_hotkeys: HashMap<crate::config::HotkeyBinding, String>,
     // Ephemeral state: used to temporarily hide previews via hotkey
     pub runtime_hidden: bool,
+    /// Ephemeral state: cycle group cycled last, whose order the cycle badges show
+    #[serde(skip)]
+    pub active_cycle_group: Option<String>,
+    /// Ephemeral state: run the profile's startup actions on start. Set for Managers before
+    /// `ConfigMessage::RunStartupActions`, which expect them on every start.
+    #[serde(skip)]
+    pub run_startup_actions: bool,
 }
 
 impl DaemonConfig {
-    /// Get default thumbnail dimensions from profile settings
-    pub fn default_thumbnail_size(&self, _screen_width: u16, _screen_height: u16) -> (u16, u16) {
+    /// Config for one profile without runtime state, for drawing previews outside the daemon
+    pub fn for_profile(profile: crate::config::profile::Profile) -> Self {
+        Self {
+            profile,
+            character_thumbnails: HashMap::new(),
+            custom_source_thumbnails: HashMap::new(),
+            custom_source_geometry: Vec::new(),
+            profile_hotkeys: HashMap::new(),
+            runtime_hidden: false,
+            active_cycle_group: None,
+            run_startup_actions: false,
+        }
+    }
+
+    /// Default thumbnail dimensions for a monitor: the profile's default size, scaled by the
+    /// monitor's HiDPI scale (when enabled) and its per-monitor multiplier
+    pub fn default_thumbnail_size(&self, monitor_name: Option<&str>, dpi_scale: f32) -> (u16, u16) {
+        use crate::common::constants::defaults::thumbnail;
+
+        let dpi_scale = if self.profile.thumbnail_hidpi_scaling {
+            dpi_scale
+        } else {
+            1.0
+        };
+        let multiplier = monitor_name
+            .and_then(|name| self.profile.thumbnail_monitor_scales.get(name))
+            .map_or(1.0, |m| {
+                m.clamp(thumbnail::MONITOR_SCALE_MIN, thumbnail::MONITOR_SCALE_MAX)
+            });
+        let scale = |size: u16, min: u16, max: u16| {
+            (f32::from(size) * dpi_scale * multiplier)
+                .round()
+                .clamp(f32::from(min), f32::from(max)) as u16
+        };
         (
-            self.profile.thumbnail_default_width,
-            self.profile.thumbnail_default_height,
+            scale(
+                self.profile.thumbnail_default_width,
+                thumbnail::MIN_WIDTH,
+                thumbnail::MAX_WIDTH,
+            ),
+            scale(
+                self.profile.thumbnail_default_height,
+                thumbnail::MIN_HEIGHT,
+                thumbnail::MAX_HEIGHT,
+            ),
+        )
+    }
+
+    /// Geometry of a custom source instance: runtime changes first, then the profile's
+    pub fn source_geometry(&self, rule_id: u32, instance: u32) -> Option<LayoutSlot> {
+        SourceGeometry::find(&self.custom_source_geometry, rule_id, instance).or_else(|| {
+            SourceGeometry::find(&self.profile.custom_source_geometry, rule_id, instance)
+        })
+    }
+
+    /// Hysteresis before thumbnails hide after EVE loses focus
+    pub fn focus_loss_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.profile
+                .timing_focus_loss_delay_ms
+                .min(crate::common::constants::defaults::timing::FOCUS_LOSS_DELAY_MAX_MS)
+                .into(),
+        )
+    }
+
+    /// Quiet period before thumbnail position updates are reported to the Manager
+    pub fn position_debounce(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.profile.timing_position_debounce_ms.into())
+    }
+
+    /// Interval between re-checks of windows mapped before their title identified them
+    pub fn identity_retry_interval(&self) -> std::time::Duration {
+        use crate::common::constants::defaults::timing;
+        std::time::Duration::from_millis(
+            self.profile
+                .timing_identity_retry_interval_ms
+                .clamp(
+                    timing::IDENTITY_RETRY_INTERVAL_MIN_MS,
+                    timing::IDENTITY_RETRY_INTERVAL_MAX_MS,
+                )
+                .into(),
+        )
+    }
+
+    /// How long after mapping an unidentified window keeps being re-checked
+    pub fn identity_retry_window(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.profile
+                .timing_identity_retry_window_ms
+                .min(crate::common::constants::defaults::timing::IDENTITY_RETRY_WINDOW_MAX_MS)
+                .into(),
         )
     }
 
@@ -73,6 +261,13 @@ impl DaemonConfig {
                 HexColor::from_argb32(0xFF_FF_FF_FF).argb32()
             });
 
+        let text_effect_color = HexColor::parse(&self.profile.thumbnail_text_effect_color)
+            .map(|c| c.argb32())
+            .unwrap_or_else(|| {
+                error!(text_effect_color = %self.profile.thumbnail_text_effect_color, "Invalid text_effect_color hex, using default");
+                HexColor::from_argb32(0xCC_00_00_00).argb32()
+            });
+
         let inactive_border_color = HexColor::parse(&self.profile.thumbnail_inactive_border_color)
             .map(|c| c.to_x11_color())
             .unwrap_or_else(|| {
@@ -80,6 +275,28 @@ impl DaemonConfig {
                 HexColor::from_argb32(0x00000000).to_x11_color()
             });
 
+        let minimized_background = HexColor::parse(&self.profile.client_minimize_background)
+            .map(|c| c.to_x11_color())
+            .unwrap_or_else(|| {
+                error!(minimized_background = %self.profile.client_minimize_background, "Invalid minimized background hex, using default");
+                HexColor::from_argb32(0xFF000000).to_x11_color()
+            });
+
+        let test_server_border_color =
+            HexColor::parse(&self.profile.thumbnail_test_server_border_color)
+                .map(|c| c.to_x11_color())
+                .unwrap_or_else(|| {
+                    error!(test_server_border_color = %self.profile.thumbnail_test_server_border_color, "Invalid test_server_border_color hex, using default");
+                    HexColor::from_argb32(0xFFFF8C00).to_x11_color()
+                });
+
+        let unfocused_tint = HexColor::parse(&self.profile.thumbnail_unfocused_tint_color)
+            .map(|c| c.to_premultiplied_x11_color())
+            .unwrap_or_else(|| {
+                error!(unfocused_tint_color = %self.profile.thumbnail_unfocused_tint_color, "Invalid unfocused_tint_color hex, using default");
+                HexColor::from_argb32(0x66000000).to_premultiplied_x11_color()
+            });
+
         let opacity = Opacity::from_percent(self.profile.thumbnail_opacity).to_argb32();
 
         let mut character_settings = self.profile.character_thumbnails.clone();
@@ -136,13 +353,56 @@ impl DaemonConfig {
                         override_active_border_size: rule.active_border_size,
                         override_inactive_border_size: rule.inactive_border_size,
                         override_text_color: rule.text_color.clone(),
+                        override_text_font: None,
+                        override_text_size: None,
                         preview_mode: rule.preview_mode.clone().unwrap_or_default(),
                         exempt_from_minimize: rule.exempt_from_minimize,
                         override_render_preview: rule.override_render_preview,
+                        keep_visible_without_focus: false,
+                        always_visible: false,
+                        override_shape: None,
+                        corner_icon: crate::common::types::CornerIcon::default(),
+                        display_order: None,
+                        stack_priority: 0,
+                        timelapse_interval_secs: None,
+                        crop: None,
                     }
                 });
         }
 
+        // 3. Account defaults fill in whatever a character doesn't override itself
+        let mut account_tags = HashMap::new();
+        for account in &self.profile.accounts {
+            for name in &account.characters {
+                if let Some(settings) = character_settings.get_mut(name) {
+                    if settings.override_inactive_border_color.is_none() {
+                        settings.override_inactive_border_color = account.color.clone();
+                    }
+                    settings.exempt_from_minimize |= account.exempt_from_minimize;
+                }
+                if let Some(tag) = account.label_tag() {
+                    account_tags.insert(name.clone(), tag.to_string());
+                }
+            }
+        }
+
+        // Badges number the group cycled last (the first group until one has been used)
+        let badge_group = self
+            .active_cycle_group
+            .as_deref()
+            .and_then(|name| self.profile.cycle_groups.iter().find(|g| g.name == name))
+            .or_else(|| self.profile.cycle_groups.first());
+        let mut cycle_positions = HashMap::new();
+        for (idx, slot) in badge_group
+            .iter()
+            .flat_map(|g| g.cycle_list.iter())
+            .enumerate()
+        {
+            let (crate::config::profile::CycleSlot::Eve(name)
+            | crate::config::profile::CycleSlot::Source(name)) = slot;
+            cycle_positions.entry(name.clone()).or_insert(idx + 1);
+        }
+
         DisplayConfig {
             enabled: self.profile.thumbnail_enabled,
             opacity,
@@ -157,7 +417,23 @@ impl DaemonConfig {
                 self.profile.thumbnail_text_y,
             ),
             text_color,
+            text_effect: self.profile.thumbnail_text_effect,
+            text_effect_color,
+            text_shadow_offset: TextOffset::from_border_edge(
+                self.profile.thumbnail_text_shadow_x,
+                self.profile.thumbnail_text_shadow_y,
+            ),
+            text_outline_width: self.profile.thumbnail_text_outline_width,
             hide_when_no_focus: self.profile.thumbnail_hide_not_focused,
+            hide_fade: (self.profile.thumbnail_hide_fade_ms > 0).then(|| {
+                std::time::Duration::from_millis(self.profile.thumbnail_hide_fade_ms.into())
+            }),
+            damage_frame: std::time::Duration::from_millis(
+                self.profile
+                    .timing_damage_frame_ms
+                    .min(crate::common::constants::defaults::timing::DAMAGE_FRAME_MAX_MS)
+                    .into(),
+            ),
             inactive_border_enabled: self.profile.thumbnail_inactive_border,
             inactive_border_color,
             inactive_border_size: if self.profile.thumbnail_inactive_border {
@@ -165,7 +441,45 @@ impl DaemonConfig {
             } else {
                 0
             },
+            test_server_border_color,
+            border_padding: self.profile.thumbnail_border_padding,
+            border_glow: self.profile.thumbnail_border_glow,
+            active_border_style: self.profile.thumbnail_active_border_style,
+            inactive_border_style: self.profile.thumbnail_inactive_border_style,
+            skipped_border_style: self.profile.thumbnail_skipped_border_style,
+            skipped_border_size: self.profile.thumbnail_skipped_border_size,
+            minimized_border_style: self.profile.thumbnail_minimized_border_style,
+            minimized_border_size: self.profile.thumbnail_minimized_border_size,
+            unfocused_effect: self.profile.thumbnail_unfocused_effect,
+            unfocused_tint,
             minimized_overlay_enabled: self.profile.client_minimize_show_overlay,
+            minimized_freeze_frame: self.profile.client_minimize_freeze_frame,
+            minimized_text: self.profile.client_minimize_text.clone(),
+            minimized_background,
+            minimized_image: self.profile.client_minimize_image.clone(),
+            show_notes: self.profile.thumbnail_show_notes,
+            alias_template: self.profile.thumbnail_alias_template.clone(),
+            account_tags,
+            shape: self.profile.thumbnail_shape,
+            corner_radius: self.profile.thumbnail_corner_radius,
+            icon_size: self.profile.thumbnail_icon_size,
+            cycle_badge_size: if self.profile.thumbnail_cycle_badge {
+                self.profile.thumbnail_cycle_badge_size
+            } else {
+                0
+            },
+            cycle_badge_corner: self.profile.thumbnail_cycle_badge_corner,
+            cycle_positions,
+            status_badge_size: if self.profile.thumbnail_status_badges {
+                self.profile.thumbnail_status_badge_size
+            } else {
+                0
+            },
+            status_badge_corner: self.profile.thumbnail_status_badge_corner,
+            hotkey_bound: self.profile.character_hotkeys.keys().cloned().collect(),
+            capture_backend: self.profile.thumbnail_capture_backend,
+            scale_filter: self.profile.thumbnail_scale_filter,
+            vsync: self.profile.thumbnail_vsync,
             character_settings,
         }
     }
@@ -234,6 +548,18 @@ impl DaemonConfig {
                             disk_settings.override_inactive_border_size;
                         mem_settings.override_text_color =
                             disk_settings.override_text_color.clone();
+                        mem_settings.override_text_font = disk_settings.override_text_font.clone();
+                        mem_settings.override_text_size = disk_settings.override_text_size;
+                        mem_settings.override_shape = disk_settings.override_shape;
+                        mem_settings.keep_visible_without_focus =
+                            disk_settings.keep_visible_without_focus;
+                        mem_settings.always_visible = disk_settings.always_visible;
+                        mem_settings.corner_icon = disk_settings.corner_icon.clone();
+                        mem_settings.display_order = disk_settings.display_order;
+                        mem_settings.stack_priority = disk_settings.stack_priority;
+                        mem_settings.timelapse_interval_secs =
+                            disk_settings.timelapse_interval_secs;
+                        mem_settings.crop = disk_settings.crop;
                     })
                     .or_insert_with(|| disk_settings.clone());
             }
@@ -278,8 +604,11 @@ mod tests {
             profile: Profile {
                 profile_name: "Test Profile".to_string(),
                 profile_description: String::new(),
+                profile_locked: false,
                 thumbnail_default_width: 480,
                 thumbnail_default_height: 270,
+                thumbnail_hidpi_scaling: true,
+                thumbnail_monitor_scales: HashMap::new(),
                 thumbnail_opacity: opacity_percent,
                 thumbnail_active_border: border_size > 0, // In tests, valid size > 0 implies enabled
                 thumbnail_active_border_size: border_size,
@@ -287,36 +616,115 @@ mod tests {
                 thumbnail_inactive_border: false,
                 thumbnail_inactive_border_size: 0,
                 thumbnail_inactive_border_color: "#00000000".to_string(),
+                thumbnail_test_server_border_color: "#FF8C00".to_string(),
                 thumbnail_text_size: 18,
                 thumbnail_text_x: text_x,
                 thumbnail_text_y: text_y,
                 thumbnail_text_color: text_color.to_string(),
+                thumbnail_text_effect: crate::config::TextEffect::None,
+                thumbnail_text_effect_color: "#CC000000".to_string(),
+                thumbnail_text_shadow_x: 2,
+                thumbnail_text_shadow_y: 2,
+                thumbnail_text_outline_width: 1,
                 thumbnail_text_font: String::new(),
                 thumbnail_auto_save_position: false,
                 thumbnail_snap_threshold: snap_threshold,
                 thumbnail_hide_not_focused: hide_when_no_focus,
                 thumbnail_preserve_position_on_swap: false,
+                timing_focus_loss_delay_ms: 100,
+                timing_position_debounce_ms: 250,
+                timing_auto_save_delay_ms: 1000,
+                timing_damage_frame_ms: 16,
+                timing_identity_retry_interval_ms: 500,
+                timing_identity_retry_window_ms: 5000,
+                thumbnail_capture_backend: crate::config::CaptureBackendType::Auto,
+                thumbnail_scale_filter: crate::config::ScaleFilter::Bilinear,
+                thumbnail_vsync: false,
+                thumbnail_click_passthrough: false,
+                thumbnail_hover_zoom: 1.0,
+                thumbnail_hover_zoom_anchor: crate::config::HoverZoomAnchor::Center,
+                thumbnail_hover_zoom_delay_ms: 0,
+                thumbnail_hover_tooltip: false,
+                thumbnail_scroll_resize_step: 0,
+                thumbnail_scroll_resize_lock_aspect: true,
+                thumbnail_hide_fade_ms: 0,
+                thumbnail_hide_exempt_classes: Vec::new(),
                 client_minimize_on_switch: false,
                 hotkey_input_device: None,
                 hotkey_logged_out_cycle: false,
-                hotkey_require_eve_focus: true,
+                hotkey_focus_context: crate::config::HotkeyFocusContext::Clients,
+                hotkey_focus_sources: Vec::new(),
+                hotkey_blocked_classes: Vec::new(),
                 hotkey_cycle_reset_index: false,
+                hotkey_cycle_exclude_test_servers: true,
                 cycle_groups: vec![crate::config::profile::CycleGroup::default_group()],
                 custom_windows: Vec::new(),
+                client_detection_rules: crate::config::profile::default_client_detection_rules(),
                 character_hotkeys: HashMap::new(),
                 hotkey_backend: crate::config::HotkeyBackendType::X11,
                 thumbnail_enabled: true,
                 character_thumbnails: HashMap::new(),
                 custom_source_thumbnails: HashMap::new(),
+                custom_source_geometry: Vec::new(),
+                archived_characters: HashMap::new(),
+                accounts: Vec::new(),
+                prefix_profile_rules: Vec::new(),
+                visibility_schedule: Vec::new(),
+                thumbnail_layouts: Vec::new(),
+                session_snapshots: Vec::new(),
+                startup_actions: crate::config::profile::StartupActions::default(),
+                launch_order: Vec::new(),
                 hotkey_profile_switch: None,
                 hotkey_toggle_skip: None,
                 hotkey_toggle_previews: None,
+                hotkey_overview: None,
+                hotkey_flip: None,
+                hotkey_peek: None,
+                hotkey_peek_scale: 2.0,
+                hotkey_reveal: None,
+                hotkey_panic: None,
+                hotkey_panic_decoy_class: String::new(),
+                hotkey_pause: None,
+                hotkey_suspend: None,
+                hotkey_suspend_secs: 10,
                 client_minimize_show_overlay: false,
+                client_minimize_alt_restore: false,
+                client_minimize_freeze_frame: false,
+                client_minimize_text: "MINIMIZED".to_string(),
+                client_minimize_background: "#000000".to_string(),
+                client_minimize_image: crate::config::MinimizedImage::None,
+                client_pointer_warp: crate::config::PointerWarpMode::Off,
+                client_switch_osd: crate::config::SwitchOsdMode::Off,
+                client_switch_speech: false,
+                thumbnail_show_notes: false,
+                thumbnail_alias_template: String::new(),
+                thumbnail_shape: crate::common::types::ThumbnailShape::Rectangle,
+                thumbnail_border_padding: 0,
+                thumbnail_border_glow: 0,
+                thumbnail_active_border_style: crate::config::BorderStyle::Solid,
+                thumbnail_inactive_border_style: crate::config::BorderStyle::Solid,
+                thumbnail_skipped_border_style: None,
+                thumbnail_skipped_border_size: None,
+                thumbnail_minimized_border_style: None,
+                thumbnail_minimized_border_size: None,
+                thumbnail_unfocused_effect: crate::config::UnfocusedEffect::None,
+                thumbnail_unfocused_tint_color: "#66000000".to_string(),
+                thumbnail_corner_radius: 12,
+                thumbnail_icon_size: 32,
+                thumbnail_cycle_badge: false,
+                thumbnail_cycle_badge_corner: crate::config::BadgeCorner::default(),
+                thumbnail_cycle_badge_size: 20,
+                thumbnail_status_badges: false,
+                thumbnail_status_badge_corner: crate::config::BadgeCorner::BottomLeft,
+                thumbnail_status_badge_size: 14,
             },
             character_thumbnails: HashMap::new(),
             custom_source_thumbnails: HashMap::new(),
+            custom_source_geometry: Vec::new(),
             profile_hotkeys: HashMap::new(),
             runtime_hidden: false,
+            active_cycle_group: None,
+            run_startup_actions: false,
         }
     }
 
@@ -335,6 +743,175 @@ mod tests {
         assert_eq!(config.active_border_color.blue, 0);
         assert_eq!(config.active_border_color.alpha, 65535);
         assert!(!config.minimized_overlay_enabled);
+        assert!(!config.show_notes);
+    }
+
+    #[test]
+    fn test_build_display_config_show_notes() {
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", false, 20);
+        state.profile.thumbnail_show_notes = true;
+
+        let config = state.build_display_config();
+        assert!(config.show_notes);
+    }
+
+    #[test]
+    fn test_cycle_positions_follow_active_group() {
+        use crate::config::profile::{CycleGroup, CycleSlot};
+
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", false, 20);
+        let group = |name: &str, members: &[&str]| CycleGroup {
+            name: name.to_string(),
+            cycle_list: members
+                .iter()
+                .map(|m| CycleSlot::Eve(m.to_string()))
+                .collect(),
+            ..CycleGroup::default_group()
+        };
+        state.profile.cycle_groups = vec![group("Main", &["A", "B"]), group("Alts", &["B", "C"])];
+
+        // Until a group is cycled, the first one is numbered
+        let config = state.build_display_config();
+        assert_eq!(config.cycle_positions.get("B"), Some(&2));
+        assert_eq!(config.cycle_positions.get("C"), None);
+
+        state.active_cycle_group = Some("Alts".to_string());
+        let config = state.build_display_config();
+        assert_eq!(config.cycle_positions.get("A"), None);
+        assert_eq!(config.cycle_positions.get("B"), Some(&1));
+        assert_eq!(config.cycle_positions.get("C"), Some(&2));
+    }
+
+    #[test]
+    fn test_default_thumbnail_size_scales_per_monitor() {
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", false, 20);
+        assert_eq!(state.default_thumbnail_size(None, 1.0), (480, 270));
+        assert_eq!(state.default_thumbnail_size(Some("DP-1"), 1.5), (720, 405));
+
+        state
+            .profile
+            .thumbnail_monitor_scales
+            .insert("DP-1".to_string(), 0.5);
+        assert_eq!(state.default_thumbnail_size(Some("DP-1"), 2.0), (480, 270));
+        assert_eq!(
+            state.default_thumbnail_size(Some("HDMI-1"), 1.0),
+            (480, 270)
+        );
+
+        state.profile.thumbnail_hidpi_scaling = false;
+        assert_eq!(state.default_thumbnail_size(Some("DP-1"), 2.0), (240, 135));
+        // Clamped to the thumbnail size limits
+        assert_eq!(state.default_thumbnail_size(None, 10.0), (480, 270));
+        state.profile.thumbnail_hidpi_scaling = true;
+        assert_eq!(state.default_thumbnail_size(None, 10.0).0, 2000);
+    }
+
+    #[test]
+    fn test_keeps_visible_without_focus() {
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", true, 20);
+        let mut scout = CharacterSettings::new(0, 0, 100, 100);
+        scout.keep_visible_without_focus = true;
+        state
+            .profile
+            .character_thumbnails
+            .insert("Scout".to_string(), scout);
+        state
+            .profile
+            .character_thumbnails
+            .insert("Jane".to_string(), CharacterSettings::new(0, 0, 100, 100));
+
+        let config = state.build_display_config();
+        assert!(config.keeps_visible_without_focus("Scout"));
+        assert!(!config.keeps_visible_without_focus("Jane"));
+        assert!(!config.keeps_visible_without_focus("Unknown"));
+    }
+
+    #[test]
+    fn test_always_visible() {
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", true, 20);
+        let mut scout = CharacterSettings::new(0, 0, 100, 100);
+        scout.always_visible = true;
+        state
+            .profile
+            .character_thumbnails
+            .insert("Scout".to_string(), scout);
+
+        let config = state.build_display_config();
+        assert!(config.always_visible("Scout"));
+        assert!(!config.keeps_visible_without_focus("Scout"));
+        assert!(!config.always_visible("Unknown"));
+    }
+
+    #[test]
+    fn test_timing_overrides_are_bounded() {
+        use crate::common::constants::defaults::timing;
+        use std::time::Duration;
+
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", true, 20);
+        assert_eq!(state.identity_retry_interval(), Duration::from_millis(500));
+        assert_eq!(
+            state.build_display_config().damage_frame,
+            Duration::from_millis(16)
+        );
+
+        // Out-of-range values are pulled back (a zero tokio interval panics)
+        state.profile.timing_identity_retry_interval_ms = 0;
+        state.profile.timing_identity_retry_window_ms = u32::MAX;
+        state.profile.timing_damage_frame_ms = u32::MAX;
+        assert_eq!(
+            state.identity_retry_interval(),
+            Duration::from_millis(timing::IDENTITY_RETRY_INTERVAL_MIN_MS.into())
+        );
+        assert_eq!(
+            state.identity_retry_window(),
+            Duration::from_millis(timing::IDENTITY_RETRY_WINDOW_MAX_MS.into())
+        );
+        assert_eq!(
+            state.build_display_config().damage_frame,
+            Duration::from_millis(timing::DAMAGE_FRAME_MAX_MS.into())
+        );
+    }
+
+    #[test]
+    fn test_build_display_config_account_defaults() {
+        let mut state = test_config(75, 3, "#FF00FF00", 15, 25, "#FFFFFFFF", false, 20);
+        let mut own_color = CharacterSettings::new(0, 0, 100, 100);
+        own_color.override_inactive_border_color = Some("#00FF00".to_string());
+        state
+            .profile
+            .character_thumbnails
+            .insert("Jane".to_string(), CharacterSettings::new(0, 0, 100, 100));
+        state
+            .profile
+            .character_thumbnails
+            .insert("Bob".to_string(), own_color);
+        state
+            .profile
+            .accounts
+            .push(crate::config::profile::Account {
+                name: "Main".to_string(),
+                characters: vec!["Jane".to_string(), "Bob".to_string()],
+                color: Some("#0000FF".to_string()),
+                tag: Some(" A1 ".to_string()),
+                exempt_from_minimize: true,
+            });
+
+        let config = state.build_display_config();
+        let jane = &config.character_settings["Jane"];
+        let bob = &config.character_settings["Bob"];
+        assert_eq!(
+            jane.override_inactive_border_color.as_deref(),
+            Some("#0000FF")
+        );
+        assert_eq!(
+            bob.override_inactive_border_color.as_deref(),
+            Some("#00FF00")
+        );
+        assert!(jane.exempt_from_minimize && bob.exempt_from_minimize);
+        assert_eq!(
+            config.account_tags.get("Jane").map(String::as_str),
+            Some("A1")
+        );
     }
 
     #[test]
@@ -0,0 +1,305 @@
+//! Privacy-scrubbed configuration export
+//!
+//! Produces a copy of the configuration that is safe to paste into a bug report: character
+//! and account names become numbered placeholders (in the fields that hold names, and as whole
+//! words in notes, aliases, title patterns and launch commands), portrait character IDs are
+//! zeroed and file paths are replaced. Field names and other values are left alone, so a
+//! character called "x" or "center" cannot break the exported config.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use tracing::info;
+
+use crate::config::profile::{Config, CycleSlot};
+
+/// Replacement for anything that looks like a file path
+const PATH_PLACEHOLDER: &str = "<path>";
+
+/// Maps keyed by character name
+const NAME_KEYED_MAPS: &[&str] = &[
+    "character_thumbnails",
+    "archived_characters",
+    "character_hotkeys",
+    "thumbnails",
+];
+
+/// Fields whose value is a character or account name (or a list of them)
+const NAME_FIELDS: &[&str] = &[
+    "last_focused_character",
+    "focus_character",
+    "minimize_all_except",
+    "focused",
+    "character",
+    "characters",
+    "name",
+    "eve",
+];
+
+/// Free-text fields that may mention names among other words
+const TEXT_FIELDS: &[&str] = &[
+    "notes",
+    "alias",
+    "thumbnail_alias_template",
+    "title_pattern",
+    "command",
+];
+
+/// Every character and account name mentioned in the config, paired with its placeholder.
+/// Longest names come first so a name containing another is replaced whole.
+fn placeholders(config: &Config) -> Vec<(String, String)> {
+    let mut characters = BTreeSet::new();
+    let mut accounts = BTreeSet::new();
+    characters.insert(config.global.last_focused_character.clone());
+
+    for profile in &config.profiles {
+        characters.extend(profile.character_thumbnails.keys().cloned());
+        characters.extend(profile.archived_characters.keys().cloned());
+        characters.extend(profile.character_hotkeys.keys().cloned());
+        for group in &profile.cycle_groups {
+            characters.extend(group.cycle_list.iter().filter_map(|slot| match slot {
+                CycleSlot::Eve(name) => Some(name.clone()),
+                CycleSlot::Source(_) => None,
+            }));
+        }
+        for account in &profile.accounts {
+            accounts.insert(account.name.clone());
+            characters.extend(account.characters.iter().cloned());
+        }
+        characters.extend(profile.launch_order.iter().map(|e| e.character.clone()));
+        characters.insert(profile.startup_actions.focus_character.clone());
+        for layout in &profile.thumbnail_layouts {
+            characters.extend(layout.thumbnails.keys().cloned());
+        }
+        for snapshot in &profile.session_snapshots {
+            characters.extend(snapshot.clients.iter().map(|c| c.name.clone()));
+            characters.extend(snapshot.focused.clone());
+        }
+    }
+
+    let numbered = |names: BTreeSet<String>, label: &str| {
+        names
+            .into_iter()
+            .filter(|name| !name.trim().is_empty())
+            .enumerate()
+            .map(|(i, name)| (name, format!("{} {}", label, i + 1)))
+            .collect::<Vec<_>>()
+    };
+    let mut replacements = numbered(characters, "Character");
+    replacements.extend(numbered(accounts, "Account"));
+    replacements.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    replacements
+}
+
+/// Whether a whitespace-separated word is an absolute or home-relative path
+fn is_path(word: &str) -> bool {
+    let word = word.trim_matches(|c| c == '"' || c == '\'');
+    word.starts_with('/')
+        || word.starts_with("~/")
+        || word.as_bytes().get(1..3) == Some(b":\\")
+        || word.starts_with("\\\\")
+}
+
+/// Placeholder for `text` if it is exactly a known name
+fn replace_name(text: &str, replacements: &[(String, String)]) -> Option<String> {
+    replacements
+        .iter()
+        .find(|(name, _)| name == text)
+        .map(|(_, placeholder)| placeholder.clone())
+}
+
+/// Replaces names that appear in `text` as whole words (not inside longer words)
+fn replace_words(text: &str, replacements: &[(String, String)]) -> String {
+    let is_word_char = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut text = text.to_string();
+    for (name, placeholder) in replacements {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = 0;
+        for (start, _) in text.match_indices(name.as_str()) {
+            let end = start + name.len();
+            if is_word_char(text[..start].chars().next_back())
+                || is_word_char(text[end..].chars().next())
+            {
+                continue;
+            }
+            out.push_str(&text[rest..start]);
+            out.push_str(placeholder);
+            rest = end;
+        }
+        out.push_str(&text[rest..]);
+        text = out;
+    }
+    text
+}
+
+fn scrub_paths(text: &str) -> String {
+    let mut text = text.to_string();
+    if text.split_whitespace().any(is_path) {
+        text = text
+            .split(' ')
+            .map(|word| {
+                if is_path(word) {
+                    PATH_PLACEHOLDER
+                } else {
+                    word
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    text
+}
+
+/// Scrubs `value`, the value of `field` (list items inherit the field of their list)
+fn scrub_value(value: &mut Value, field: &str, replacements: &[(String, String)]) {
+    match value {
+        Value::String(text) => {
+            if NAME_FIELDS.contains(&field) {
+                if let Some(placeholder) = replace_name(text, replacements) {
+                    *text = placeholder;
+                }
+            } else if TEXT_FIELDS.contains(&field) {
+                *text = replace_words(text, replacements);
+            }
+            *text = scrub_paths(text);
+        }
+        Value::Array(items) => {
+            for item in items {
+                scrub_value(item, field, replacements);
+            }
+        }
+        Value::Object(map) => {
+            let name_keyed = NAME_KEYED_MAPS.contains(&field);
+            let entries = std::mem::take(map);
+            for (key, mut item) in entries {
+                if name_keyed {
+                    // Entries are keyed by name, so the key says nothing about the value
+                    scrub_value(&mut item, "", replacements);
+                    let key = replace_name(&key, replacements).unwrap_or(key);
+                    map.insert(key, item);
+                    continue;
+                }
+                if key == "character_id" && item.is_u64() {
+                    item = Value::from(0);
+                } else {
+                    scrub_value(&mut item, &key, replacements);
+                }
+                map.insert(key, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Config as pretty JSON with names, portrait IDs and file paths scrubbed
+pub fn scrubbed_json(config: &Config) -> Result<String> {
+    let mut value = serde_json::to_value(config).context("Failed to serialize config")?;
+    scrub_value(&mut value, "", &placeholders(config));
+    serde_json::to_string_pretty(&value).context("Failed to serialize scrubbed config")
+}
+
+/// Writes a scrubbed copy of the saved configuration to the exports directory next to it
+pub fn export_scrubbed(config_path_override: Option<&Path>) -> Result<PathBuf> {
+    let config_path = config_path_override
+        .map(Path::to_path_buf)
+        .unwrap_or_else(Config::path);
+    let config = Config::load_from(&config_path)?;
+
+    let mut export_dir = config_path;
+    export_dir.pop();
+    export_dir.push(crate::common::constants::config::export::SUBDIR);
+    fs::create_dir_all(&export_dir).context("Failed to create export directory")?;
+
+    let datetime: chrono::DateTime<chrono::Local> = std::time::SystemTime::now().into();
+    let export_path = export_dir.join(format!(
+        "scrubbed_config_{}.json",
+        datetime.format("%Y%m%d_%H%M%S")
+    ));
+    fs::write(&export_path, scrubbed_json(&config)?)
+        .with_context(|| format!("Failed to write {:?}", export_path))?;
+
+    info!(path = ?export_path, "Exported scrubbed config");
+    Ok(export_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{CharacterSettings, CornerIcon};
+
+    #[test]
+    fn test_scrubbed_config_hides_names_and_paths() {
+        let mut config = Config::default();
+        config.global.last_focused_character = "Alt Pilot".to_string();
+        let profile = &mut config.profiles[0];
+        let mut settings = CharacterSettings::new(10, 20, 300, 200);
+        settings.notes = Some("Hauler for Main Pilot".to_string());
+        settings.corner_icon = CornerIcon::Portrait {
+            character_id: 90000001,
+        };
+        profile
+            .character_thumbnails
+            .insert("Main Pilot".to_string(), settings.clone());
+        settings.corner_icon = CornerIcon::Custom {
+            path: "/home/someone/icons/alt.png".to_string(),
+        };
+        profile
+            .character_thumbnails
+            .insert("Alt Pilot".to_string(), settings);
+        profile.hotkey_input_device = Some("/dev/input/event3".to_string());
+
+        let json = scrubbed_json(&config).unwrap();
+        for secret in [
+            "Main Pilot",
+            "Alt Pilot",
+            "90000001",
+            "/home/someone",
+            "/dev/input",
+        ] {
+            assert!(!json.contains(secret), "{secret} leaked");
+        }
+        assert!(json.contains("Hauler for Character 2"));
+
+        // Still a loadable config with the same shape
+        let scrubbed: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(scrubbed.profiles[0].character_thumbnails.len(), 2);
+        assert_eq!(scrubbed.global.last_focused_character, "Character 1");
+    }
+
+    #[test]
+    fn test_scrub_names_colliding_with_fields() {
+        let mut config = Config::default();
+        let profile = &mut config.profiles[0];
+        profile.thumbnail_hover_zoom_anchor = crate::config::HoverZoomAnchor::TopLeft;
+        let mut settings = CharacterSettings::new(10, 20, 300, 200);
+        settings.notes = Some("x scouts, Xenia boxes".to_string());
+        profile
+            .character_thumbnails
+            .insert("x".to_string(), settings.clone());
+        settings.notes = None;
+        profile
+            .character_thumbnails
+            .insert("top_left".to_string(), settings);
+
+        let json = scrubbed_json(&config).unwrap();
+        let scrubbed: Config = serde_json::from_str(&json).unwrap();
+        let profile = &scrubbed.profiles[0];
+        assert_eq!(
+            profile.thumbnail_hover_zoom_anchor,
+            crate::config::HoverZoomAnchor::TopLeft
+        );
+        assert!(!profile.character_thumbnails.contains_key("x"));
+        assert!(!profile.character_thumbnails.contains_key("top_left"));
+
+        // Placeholders are numbered in name order
+        let scout = &profile.character_thumbnails["Character 2"];
+        assert_eq!((scout.x, scout.y), (10, 20));
+        assert_eq!(
+            scout.notes.as_deref(),
+            Some("Character 2 scouts, Xenia boxes")
+        );
+    }
+}
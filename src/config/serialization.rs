@@ -1,14 +1,26 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
-use crate::common::types::CharacterSettings;
+use crate::common::types::{CharacterSettings, ClientDetectionRule};
 use crate::config::profile::{
-    CustomWindowRule, CycleGroup, HotkeyBackendType, Profile,
-    default_auto_save_thumbnail_positions, default_border_enabled, default_border_size,
-    default_hotkey_backend, default_inactive_border_color, default_inactive_border_enabled,
-    default_preserve_thumbnail_position_on_swap, default_profile_name, default_snap_threshold,
-    default_text_font_family, default_thumbnail_enabled, default_thumbnail_height,
-    default_thumbnail_width,
+    Account, BadgeCorner, BorderStyle, CaptureBackendType, CustomWindowRule, CycleGroup,
+    HotkeyBackendType, HotkeyFocusContext, HoverZoomAnchor, LaunchEntry, MinimizedImage,
+    PointerWarpMode, PrefixProfileRule, Profile, ScaleFilter, SessionSnapshot, SourceGeometry,
+    StartupActions, SwitchOsdMode, TextEffect, ThumbnailLayout, UnfocusedEffect,
+    VisibilityScheduleRule, default_auto_save_delay_ms, default_auto_save_thumbnail_positions,
+    default_border_enabled, default_border_size, default_client_detection_rules,
+    default_corner_radius, default_cycle_badge_size, default_damage_frame_ms,
+    default_exclude_test_servers, default_focus_loss_delay_ms, default_hidpi_scaling,
+    default_hotkey_backend, default_hotkey_suspend_secs, default_hover_zoom, default_icon_size,
+    default_identity_retry_interval_ms, default_identity_retry_window_ms,
+    default_inactive_border_color, default_inactive_border_enabled, default_minimized_background,
+    default_minimized_text, default_peek_scale, default_position_debounce_ms,
+    default_preserve_thumbnail_position_on_swap, default_profile_name,
+    default_scroll_resize_lock_aspect, default_snap_threshold, default_status_badge_corner,
+    default_status_badge_size, default_test_server_border_color, default_text_effect_color,
+    default_text_font_family, default_text_outline_width, default_text_shadow_offset,
+    default_thumbnail_enabled, default_thumbnail_height, default_thumbnail_width,
+    default_unfocused_tint_color,
 };
 
 /// Helper struct for migration during deserialization
@@ -18,10 +30,16 @@ struct ProfileHelper {
     profile_name: String,
     #[serde(default)]
     profile_description: String,
+    #[serde(default)]
+    profile_locked: bool,
     #[serde(default = "default_thumbnail_width")]
     thumbnail_default_width: u16,
     #[serde(default = "default_thumbnail_height")]
     thumbnail_default_height: u16,
+    #[serde(default = "default_hidpi_scaling")]
+    thumbnail_hidpi_scaling: bool,
+    #[serde(default)]
+    thumbnail_monitor_scales: HashMap<String, f32>,
     #[serde(default = "default_thumbnail_enabled")]
     thumbnail_enabled: bool,
     thumbnail_opacity: u8,
@@ -40,12 +58,66 @@ struct ProfileHelper {
     thumbnail_inactive_border_size: u16,
     #[serde(default = "default_inactive_border_color")]
     thumbnail_inactive_border_color: String,
+    #[serde(default = "default_test_server_border_color")]
+    thumbnail_test_server_border_color: String,
+    #[serde(default)]
+    thumbnail_border_padding: u16,
+    #[serde(default)]
+    thumbnail_border_glow: u16,
+    #[serde(default)]
+    thumbnail_active_border_style: BorderStyle,
+    #[serde(default)]
+    thumbnail_inactive_border_style: BorderStyle,
+    #[serde(default)]
+    thumbnail_skipped_border_style: Option<BorderStyle>,
+    #[serde(default)]
+    thumbnail_skipped_border_size: Option<u16>,
+    #[serde(default)]
+    thumbnail_minimized_border_style: Option<BorderStyle>,
+    #[serde(default)]
+    thumbnail_minimized_border_size: Option<u16>,
+    #[serde(default)]
+    thumbnail_unfocused_effect: UnfocusedEffect,
+    #[serde(default = "default_unfocused_tint_color")]
+    thumbnail_unfocused_tint_color: String,
+    #[serde(default)]
+    thumbnail_shape: crate::common::types::ThumbnailShape,
+    #[serde(default = "default_corner_radius")]
+    thumbnail_corner_radius: u16,
+    #[serde(default = "default_icon_size")]
+    thumbnail_icon_size: u16,
+    #[serde(default)]
+    thumbnail_cycle_badge: bool,
+    #[serde(default)]
+    thumbnail_cycle_badge_corner: BadgeCorner,
+    #[serde(default = "default_cycle_badge_size")]
+    thumbnail_cycle_badge_size: u16,
+    #[serde(default)]
+    thumbnail_status_badges: bool,
+    #[serde(default = "default_status_badge_corner")]
+    thumbnail_status_badge_corner: BadgeCorner,
+    #[serde(default = "default_status_badge_size")]
+    thumbnail_status_badge_size: u16,
     thumbnail_text_size: u16,
     thumbnail_text_x: i16,
     thumbnail_text_y: i16,
     #[serde(default = "default_text_font_family")]
     thumbnail_text_font: String,
     thumbnail_text_color: String,
+    #[serde(default)]
+    thumbnail_text_effect: TextEffect,
+    #[serde(default = "default_text_effect_color")]
+    thumbnail_text_effect_color: String,
+    #[serde(default = "default_text_shadow_offset")]
+    thumbnail_text_shadow_x: i16,
+    #[serde(default = "default_text_shadow_offset")]
+    thumbnail_text_shadow_y: i16,
+    #[serde(default = "default_text_outline_width")]
+    thumbnail_text_outline_width: u16,
+    #[serde(default)]
+    thumbnail_show_notes: bool,
+    #[serde(default)]
+    thumbnail_alias_template: String,
     #[serde(default = "default_auto_save_thumbnail_positions")]
     thumbnail_auto_save_position: bool,
     #[serde(default = "default_snap_threshold")]
@@ -55,9 +127,61 @@ struct ProfileHelper {
     #[serde(default = "default_preserve_thumbnail_position_on_swap")]
     thumbnail_preserve_position_on_swap: bool,
     #[serde(default)]
+    thumbnail_capture_backend: CaptureBackendType,
+    #[serde(default)]
+    thumbnail_scale_filter: ScaleFilter,
+    #[serde(default)]
+    thumbnail_vsync: bool,
+    #[serde(default)]
+    thumbnail_click_passthrough: bool,
+    #[serde(default = "default_hover_zoom")]
+    thumbnail_hover_zoom: f32,
+    #[serde(default)]
+    thumbnail_hover_zoom_anchor: HoverZoomAnchor,
+    #[serde(default)]
+    thumbnail_hover_zoom_delay_ms: u32,
+    #[serde(default)]
+    thumbnail_hover_tooltip: bool,
+    #[serde(default)]
+    thumbnail_scroll_resize_step: u16,
+    #[serde(default = "default_scroll_resize_lock_aspect")]
+    thumbnail_scroll_resize_lock_aspect: bool,
+    #[serde(default)]
+    thumbnail_hide_fade_ms: Option<u32>,
+    #[serde(default)]
+    thumbnail_hide_exempt_classes: Vec<String>,
+    #[serde(default = "default_focus_loss_delay_ms")]
+    timing_focus_loss_delay_ms: u32,
+    #[serde(default = "default_position_debounce_ms")]
+    timing_position_debounce_ms: u32,
+    #[serde(default = "default_auto_save_delay_ms")]
+    timing_auto_save_delay_ms: u32,
+    #[serde(default = "default_damage_frame_ms")]
+    timing_damage_frame_ms: u32,
+    #[serde(default = "default_identity_retry_interval_ms")]
+    timing_identity_retry_interval_ms: u32,
+    #[serde(default = "default_identity_retry_window_ms")]
+    timing_identity_retry_window_ms: u32,
+    #[serde(default)]
     client_minimize_on_switch: bool,
     #[serde(default)]
     client_minimize_show_overlay: bool,
+    #[serde(default)]
+    client_minimize_alt_restore: bool,
+    #[serde(default)]
+    client_minimize_freeze_frame: bool,
+    #[serde(default = "default_minimized_text")]
+    client_minimize_text: String,
+    #[serde(default = "default_minimized_background")]
+    client_minimize_background: String,
+    #[serde(default)]
+    client_minimize_image: MinimizedImage,
+    #[serde(default)]
+    client_pointer_warp: PointerWarpMode,
+    #[serde(default)]
+    client_switch_osd: SwitchOsdMode,
+    #[serde(default)]
+    client_switch_speech: bool,
     #[serde(default = "default_hotkey_backend")]
     hotkey_backend: HotkeyBackendType,
     #[serde(default)]
@@ -65,7 +189,13 @@ struct ProfileHelper {
     #[serde(default)]
     hotkey_logged_out_cycle: bool,
     #[serde(default)]
-    hotkey_require_eve_focus: bool,
+    hotkey_focus_context: Option<HotkeyFocusContext>,
+    #[serde(default)]
+    hotkey_focus_sources: Vec<String>,
+    #[serde(default)]
+    hotkey_blocked_classes: Vec<String>,
+    #[serde(default = "default_exclude_test_servers")]
+    hotkey_cycle_exclude_test_servers: bool,
     #[serde(default)]
     hotkey_cycle_reset_index: bool,
     #[serde(default)]
@@ -75,6 +205,26 @@ struct ProfileHelper {
     #[serde(default)]
     hotkey_toggle_previews: Option<crate::config::HotkeyBinding>,
     #[serde(default)]
+    hotkey_overview: Option<crate::config::HotkeyBinding>,
+    #[serde(default)]
+    hotkey_flip: Option<crate::config::HotkeyBinding>,
+    #[serde(default)]
+    hotkey_peek: Option<crate::config::HotkeyBinding>,
+    #[serde(default = "default_peek_scale")]
+    hotkey_peek_scale: f32,
+    #[serde(default)]
+    hotkey_reveal: Option<crate::config::HotkeyBinding>,
+    #[serde(default)]
+    hotkey_panic: Option<crate::config::HotkeyBinding>,
+    #[serde(default)]
+    hotkey_panic_decoy_class: String,
+    #[serde(default)]
+    hotkey_pause: Option<crate::config::HotkeyBinding>,
+    #[serde(default)]
+    hotkey_suspend: Option<crate::config::HotkeyBinding>,
+    #[serde(default = "default_hotkey_suspend_secs")]
+    hotkey_suspend_secs: u32,
+    #[serde(default)]
     character_hotkeys: HashMap<String, crate::config::HotkeyBinding>,
     #[serde(default)]
     character_thumbnails: HashMap<String, CharacterSettings>,
@@ -82,7 +232,27 @@ struct ProfileHelper {
     #[serde(default)]
     custom_source_thumbnails: HashMap<String, CharacterSettings>,
     #[serde(default)]
+    custom_source_geometry: Vec<SourceGeometry>,
+    #[serde(default)]
+    accounts: Vec<Account>,
+    #[serde(default)]
+    prefix_profile_rules: Vec<PrefixProfileRule>,
+    #[serde(default)]
+    visibility_schedule: Vec<VisibilityScheduleRule>,
+    #[serde(default)]
+    thumbnail_layouts: Vec<ThumbnailLayout>,
+    #[serde(default)]
+    session_snapshots: Vec<SessionSnapshot>,
+    #[serde(default)]
+    startup_actions: StartupActions,
+    #[serde(default)]
+    launch_order: Vec<LaunchEntry>,
+    #[serde(default)]
+    archived_characters: HashMap<String, CharacterSettings>,
+    #[serde(default)]
     custom_windows: Vec<CustomWindowRule>,
+    #[serde(default = "default_client_detection_rules")]
+    client_detection_rules: Vec<ClientDetectionRule>,
 
     // New field
     #[serde(default)]
@@ -95,6 +265,66 @@ struct ProfileHelper {
     hotkey_cycle_backward: Option<crate::config::HotkeyBinding>,
     #[serde(default)]
     hotkey_cycle_group: Vec<String>,
+    #[serde(default)]
+    hotkey_require_eve_focus: bool,
+    #[serde(default)]
+    thumbnail_hide_fade_out: bool,
+}
+
+/// Renames custom sources whose alias is already taken, by an earlier source or by a
+/// character (an alias with entries in both thumbnail maps). Legacy configs kept sources in
+/// `character_thumbnails`, so an alias found only there is a misplaced source, not a clash.
+fn disambiguate_source_aliases(
+    rules: &mut [CustomWindowRule],
+    character_thumbnails: &HashMap<String, CharacterSettings>,
+    custom_source_thumbnails: &mut HashMap<String, CharacterSettings>,
+    cycle_groups: &mut [CycleGroup],
+) {
+    let mut seen: Vec<String> = Vec::new();
+    for idx in 0..rules.len() {
+        let alias = rules[idx].alias.clone();
+        let duplicate = seen.contains(&alias);
+        let shadows_character = character_thumbnails.contains_key(&alias)
+            && custom_source_thumbnails.contains_key(&alias);
+        if !duplicate && !shadows_character {
+            seen.push(alias);
+            continue;
+        }
+
+        let taken = |name: &str| {
+            rules.iter().any(|r| r.alias == name)
+                || character_thumbnails.contains_key(name)
+                || custom_source_thumbnails.contains_key(name)
+        };
+        let renamed = (2..)
+            .map(|n| format!("{} ({})", alias, n))
+            .find(|candidate| !taken(candidate))
+            .expect("unbounded suffix search always finds a free alias");
+        tracing::warn!(
+            alias = %alias,
+            renamed = %renamed,
+            "Custom source alias collides with another name, renaming"
+        );
+
+        // A duplicate leaves the old name's settings and cycle slots with the first source
+        if !duplicate {
+            if let Some(settings) = custom_source_thumbnails.remove(&alias) {
+                custom_source_thumbnails.insert(renamed.clone(), settings);
+            }
+            for slot in cycle_groups
+                .iter_mut()
+                .flat_map(|g| g.cycle_list.iter_mut())
+            {
+                if matches!(slot, crate::config::profile::CycleSlot::Source(n) if *n == alias) {
+                    *slot = crate::config::profile::CycleSlot::Source(renamed.clone());
+                }
+            }
+        }
+        rules[idx].alias = renamed.clone();
+        // The old name now belongs to the character, so later sources using it clash too
+        seen.push(alias);
+        seen.push(renamed);
+    }
 }
 
 impl From<ProfileHelper> for Profile {
@@ -134,12 +364,15 @@ impl From<ProfileHelper> for Profile {
         // Enforce separation: Ensure no custom sources remain in character_thumbnails
         let mut character_thumbnails = helper.character_thumbnails;
         let mut custom_source_thumbnails = helper.custom_source_thumbnails;
+        let mut custom_windows = helper.custom_windows;
+        disambiguate_source_aliases(
+            &mut custom_windows,
+            &character_thumbnails,
+            &mut custom_source_thumbnails,
+            &mut cycle_groups,
+        );
 
-        let custom_aliases: Vec<String> = helper
-            .custom_windows
-            .iter()
-            .map(|w| w.alias.clone())
-            .collect();
+        let custom_aliases: Vec<String> = custom_windows.iter().map(|w| w.alias.clone()).collect();
 
         // Move any entry that matches a custom alias to the correct map
         let keys_to_move: Vec<String> = character_thumbnails
@@ -166,11 +399,14 @@ impl From<ProfileHelper> for Profile {
             }
         }
 
-        Profile {
+        let mut profile = Profile {
             profile_name: helper.profile_name,
             profile_description: helper.profile_description,
+            profile_locked: helper.profile_locked,
             thumbnail_default_width: helper.thumbnail_default_width,
             thumbnail_default_height: helper.thumbnail_default_height,
+            thumbnail_hidpi_scaling: helper.thumbnail_hidpi_scaling,
+            thumbnail_monitor_scales: helper.thumbnail_monitor_scales,
             thumbnail_enabled: helper.thumbnail_enabled,
             thumbnail_opacity: helper.thumbnail_opacity,
             thumbnail_active_border: helper.thumbnail_active_border,
@@ -179,31 +415,123 @@ impl From<ProfileHelper> for Profile {
             thumbnail_inactive_border: helper.thumbnail_inactive_border,
             thumbnail_inactive_border_size: helper.thumbnail_inactive_border_size,
             thumbnail_inactive_border_color: helper.thumbnail_inactive_border_color,
+            thumbnail_test_server_border_color: helper.thumbnail_test_server_border_color,
+            thumbnail_border_padding: helper.thumbnail_border_padding,
+            thumbnail_border_glow: helper.thumbnail_border_glow,
+            thumbnail_active_border_style: helper.thumbnail_active_border_style,
+            thumbnail_inactive_border_style: helper.thumbnail_inactive_border_style,
+            thumbnail_skipped_border_style: helper.thumbnail_skipped_border_style,
+            thumbnail_skipped_border_size: helper.thumbnail_skipped_border_size,
+            thumbnail_minimized_border_style: helper.thumbnail_minimized_border_style,
+            thumbnail_minimized_border_size: helper.thumbnail_minimized_border_size,
+            thumbnail_unfocused_effect: helper.thumbnail_unfocused_effect,
+            thumbnail_unfocused_tint_color: helper.thumbnail_unfocused_tint_color,
+            thumbnail_shape: helper.thumbnail_shape,
+            thumbnail_corner_radius: helper.thumbnail_corner_radius,
+            thumbnail_icon_size: helper.thumbnail_icon_size,
+            thumbnail_cycle_badge: helper.thumbnail_cycle_badge,
+            thumbnail_cycle_badge_corner: helper.thumbnail_cycle_badge_corner,
+            thumbnail_cycle_badge_size: helper.thumbnail_cycle_badge_size,
+            thumbnail_status_badges: helper.thumbnail_status_badges,
+            thumbnail_status_badge_corner: helper.thumbnail_status_badge_corner,
+            thumbnail_status_badge_size: helper.thumbnail_status_badge_size,
             thumbnail_text_size: helper.thumbnail_text_size,
             thumbnail_text_x: helper.thumbnail_text_x,
             thumbnail_text_y: helper.thumbnail_text_y,
             thumbnail_text_font: helper.thumbnail_text_font,
             thumbnail_text_color: helper.thumbnail_text_color,
+            thumbnail_text_effect: helper.thumbnail_text_effect,
+            thumbnail_text_effect_color: helper.thumbnail_text_effect_color,
+            thumbnail_text_shadow_x: helper.thumbnail_text_shadow_x,
+            thumbnail_text_shadow_y: helper.thumbnail_text_shadow_y,
+            thumbnail_text_outline_width: helper.thumbnail_text_outline_width,
+            thumbnail_show_notes: helper.thumbnail_show_notes,
+            thumbnail_alias_template: helper.thumbnail_alias_template,
             thumbnail_auto_save_position: helper.thumbnail_auto_save_position,
             thumbnail_snap_threshold: helper.thumbnail_snap_threshold,
             thumbnail_hide_not_focused: helper.thumbnail_hide_not_focused,
             thumbnail_preserve_position_on_swap: helper.thumbnail_preserve_position_on_swap,
+            thumbnail_capture_backend: helper.thumbnail_capture_backend,
+            thumbnail_scale_filter: helper.thumbnail_scale_filter,
+            thumbnail_vsync: helper.thumbnail_vsync,
+            thumbnail_click_passthrough: helper.thumbnail_click_passthrough,
+            thumbnail_hover_zoom: helper.thumbnail_hover_zoom,
+            thumbnail_hover_zoom_anchor: helper.thumbnail_hover_zoom_anchor,
+            thumbnail_hover_zoom_delay_ms: helper.thumbnail_hover_zoom_delay_ms,
+            thumbnail_hover_tooltip: helper.thumbnail_hover_tooltip,
+            thumbnail_scroll_resize_step: helper.thumbnail_scroll_resize_step,
+            thumbnail_scroll_resize_lock_aspect: helper.thumbnail_scroll_resize_lock_aspect,
+            // Configs from before the fade duration only had a fade-out checkbox
+            thumbnail_hide_fade_ms: helper.thumbnail_hide_fade_ms.unwrap_or(
+                if helper.thumbnail_hide_fade_out {
+                    crate::common::constants::defaults::timing::FOCUS_LOSS_FADE_MS
+                } else {
+                    0
+                },
+            ),
+            thumbnail_hide_exempt_classes: helper.thumbnail_hide_exempt_classes,
+            timing_focus_loss_delay_ms: helper.timing_focus_loss_delay_ms,
+            timing_position_debounce_ms: helper.timing_position_debounce_ms,
+            timing_auto_save_delay_ms: helper.timing_auto_save_delay_ms,
+            timing_damage_frame_ms: helper.timing_damage_frame_ms,
+            timing_identity_retry_interval_ms: helper.timing_identity_retry_interval_ms,
+            timing_identity_retry_window_ms: helper.timing_identity_retry_window_ms,
             client_minimize_on_switch: helper.client_minimize_on_switch,
             client_minimize_show_overlay: helper.client_minimize_show_overlay,
+            client_minimize_alt_restore: helper.client_minimize_alt_restore,
+            client_minimize_freeze_frame: helper.client_minimize_freeze_frame,
+            client_minimize_text: helper.client_minimize_text,
+            client_minimize_background: helper.client_minimize_background,
+            client_minimize_image: helper.client_minimize_image,
+            client_pointer_warp: helper.client_pointer_warp,
+            client_switch_osd: helper.client_switch_osd,
+            client_switch_speech: helper.client_switch_speech,
             hotkey_backend: helper.hotkey_backend,
             hotkey_input_device: helper.hotkey_input_device,
             hotkey_logged_out_cycle: helper.hotkey_logged_out_cycle,
-            hotkey_require_eve_focus: helper.hotkey_require_eve_focus,
+            // Configs from before focus contexts only had the EVE focus checkbox
+            hotkey_focus_context: helper.hotkey_focus_context.unwrap_or(
+                if helper.hotkey_require_eve_focus {
+                    HotkeyFocusContext::Clients
+                } else {
+                    HotkeyFocusContext::Always
+                },
+            ),
+            hotkey_focus_sources: helper.hotkey_focus_sources,
+            hotkey_blocked_classes: helper.hotkey_blocked_classes,
+            hotkey_cycle_exclude_test_servers: helper.hotkey_cycle_exclude_test_servers,
             hotkey_cycle_reset_index: helper.hotkey_cycle_reset_index,
             hotkey_profile_switch: helper.hotkey_profile_switch,
             hotkey_toggle_skip: helper.hotkey_toggle_skip,
             hotkey_toggle_previews: helper.hotkey_toggle_previews,
+            hotkey_overview: helper.hotkey_overview,
+            hotkey_flip: helper.hotkey_flip,
+            hotkey_peek: helper.hotkey_peek,
+            hotkey_reveal: helper.hotkey_reveal,
+            hotkey_panic: helper.hotkey_panic,
+            hotkey_panic_decoy_class: helper.hotkey_panic_decoy_class,
+            hotkey_pause: helper.hotkey_pause,
+            hotkey_suspend: helper.hotkey_suspend,
+            hotkey_suspend_secs: helper.hotkey_suspend_secs,
+            hotkey_peek_scale: helper.hotkey_peek_scale,
             cycle_groups, // Use the migrated or valid groups
             character_hotkeys: helper.character_hotkeys,
             character_thumbnails,
             custom_source_thumbnails,
-            custom_windows: helper.custom_windows,
-        }
+            custom_source_geometry: helper.custom_source_geometry,
+            accounts: helper.accounts,
+            prefix_profile_rules: helper.prefix_profile_rules,
+            visibility_schedule: helper.visibility_schedule,
+            thumbnail_layouts: helper.thumbnail_layouts,
+            session_snapshots: helper.session_snapshots,
+            startup_actions: helper.startup_actions,
+            launch_order: helper.launch_order,
+            archived_characters: helper.archived_characters,
+            custom_windows,
+            client_detection_rules: helper.client_detection_rules,
+        };
+        profile.migrate_source_geometry();
+        profile
     }
 }
 
@@ -223,10 +551,16 @@ impl<'de> Deserialize<'de> for Profile {
                 pub profile_name: String,
                 #[serde(default)]
                 pub profile_description: String,
+                #[serde(default)]
+                pub profile_locked: bool,
                 #[serde(default = "default_thumbnail_width")]
                 pub thumbnail_default_width: u16,
                 #[serde(default = "default_thumbnail_height")]
                 pub thumbnail_default_height: u16,
+                #[serde(default = "default_hidpi_scaling")]
+                pub thumbnail_hidpi_scaling: bool,
+                #[serde(default)]
+                pub thumbnail_monitor_scales: HashMap<String, f32>,
                 #[serde(default = "default_thumbnail_enabled")]
                 pub thumbnail_enabled: bool,
                 pub thumbnail_opacity: u8,
@@ -245,24 +579,130 @@ impl<'de> Deserialize<'de> for Profile {
                 pub thumbnail_inactive_border_size: u16,
                 #[serde(default = "default_inactive_border_color")]
                 pub thumbnail_inactive_border_color: String,
+                #[serde(default = "default_test_server_border_color")]
+                pub thumbnail_test_server_border_color: String,
+                #[serde(default)]
+                pub thumbnail_border_padding: u16,
+                #[serde(default)]
+                pub thumbnail_border_glow: u16,
+                #[serde(default)]
+                pub thumbnail_active_border_style: BorderStyle,
+                #[serde(default)]
+                pub thumbnail_inactive_border_style: BorderStyle,
+                #[serde(default)]
+                pub thumbnail_skipped_border_style: Option<BorderStyle>,
+                #[serde(default)]
+                pub thumbnail_skipped_border_size: Option<u16>,
+                #[serde(default)]
+                pub thumbnail_minimized_border_style: Option<BorderStyle>,
+                #[serde(default)]
+                pub thumbnail_minimized_border_size: Option<u16>,
+                #[serde(default)]
+                pub thumbnail_unfocused_effect: UnfocusedEffect,
+                #[serde(default = "default_unfocused_tint_color")]
+                pub thumbnail_unfocused_tint_color: String,
+                #[serde(default)]
+                pub thumbnail_shape: crate::common::types::ThumbnailShape,
+                #[serde(default = "default_corner_radius")]
+                pub thumbnail_corner_radius: u16,
+                #[serde(default = "default_icon_size")]
+                pub thumbnail_icon_size: u16,
+                #[serde(default)]
+                pub thumbnail_cycle_badge: bool,
+                #[serde(default)]
+                pub thumbnail_cycle_badge_corner: BadgeCorner,
+                #[serde(default = "default_cycle_badge_size")]
+                pub thumbnail_cycle_badge_size: u16,
+                #[serde(default)]
+                pub thumbnail_status_badges: bool,
+                #[serde(default = "default_status_badge_corner")]
+                pub thumbnail_status_badge_corner: BadgeCorner,
+                #[serde(default = "default_status_badge_size")]
+                pub thumbnail_status_badge_size: u16,
                 pub thumbnail_text_size: u16,
                 pub thumbnail_text_x: i16,
                 pub thumbnail_text_y: i16,
                 #[serde(default = "default_text_font_family")]
                 pub thumbnail_text_font: String,
                 pub thumbnail_text_color: String,
+                #[serde(default)]
+                pub thumbnail_text_effect: TextEffect,
+                #[serde(default = "default_text_effect_color")]
+                pub thumbnail_text_effect_color: String,
+                #[serde(default = "default_text_shadow_offset")]
+                pub thumbnail_text_shadow_x: i16,
+                #[serde(default = "default_text_shadow_offset")]
+                pub thumbnail_text_shadow_y: i16,
+                #[serde(default = "default_text_outline_width")]
+                pub thumbnail_text_outline_width: u16,
+                #[serde(default)]
+                pub thumbnail_show_notes: bool,
+                #[serde(default)]
+                pub thumbnail_alias_template: String,
                 #[serde(default = "default_auto_save_thumbnail_positions")]
                 pub thumbnail_auto_save_position: bool,
                 #[serde(default = "default_snap_threshold")]
                 pub thumbnail_snap_threshold: u16,
                 #[serde(default)]
                 pub thumbnail_hide_not_focused: bool,
+                #[serde(default)]
+                pub thumbnail_hide_fade_ms: u32,
+                #[serde(default)]
+                pub thumbnail_hide_exempt_classes: Vec<String>,
                 #[serde(default = "default_preserve_thumbnail_position_on_swap")]
                 pub thumbnail_preserve_position_on_swap: bool,
                 #[serde(default)]
+                pub thumbnail_capture_backend: CaptureBackendType,
+                #[serde(default)]
+                pub thumbnail_scale_filter: ScaleFilter,
+                #[serde(default)]
+                pub thumbnail_vsync: bool,
+                #[serde(default)]
+                pub thumbnail_click_passthrough: bool,
+                #[serde(default = "default_hover_zoom")]
+                pub thumbnail_hover_zoom: f32,
+                #[serde(default)]
+                pub thumbnail_hover_zoom_anchor: HoverZoomAnchor,
+                #[serde(default)]
+                pub thumbnail_hover_zoom_delay_ms: u32,
+                #[serde(default)]
+                pub thumbnail_hover_tooltip: bool,
+                #[serde(default)]
+                pub thumbnail_scroll_resize_step: u16,
+                #[serde(default = "default_scroll_resize_lock_aspect")]
+                pub thumbnail_scroll_resize_lock_aspect: bool,
+                #[serde(default = "default_focus_loss_delay_ms")]
+                pub timing_focus_loss_delay_ms: u32,
+                #[serde(default = "default_position_debounce_ms")]
+                pub timing_position_debounce_ms: u32,
+                #[serde(default = "default_auto_save_delay_ms")]
+                pub timing_auto_save_delay_ms: u32,
+                #[serde(default = "default_damage_frame_ms")]
+                pub timing_damage_frame_ms: u32,
+                #[serde(default = "default_identity_retry_interval_ms")]
+                pub timing_identity_retry_interval_ms: u32,
+                #[serde(default = "default_identity_retry_window_ms")]
+                pub timing_identity_retry_window_ms: u32,
+                #[serde(default)]
                 pub client_minimize_on_switch: bool,
                 #[serde(default)]
                 pub client_minimize_show_overlay: bool,
+                #[serde(default)]
+                pub client_minimize_alt_restore: bool,
+                #[serde(default)]
+                pub client_minimize_freeze_frame: bool,
+                #[serde(default = "default_minimized_text")]
+                pub client_minimize_text: String,
+                #[serde(default = "default_minimized_background")]
+                pub client_minimize_background: String,
+                #[serde(default)]
+                pub client_minimize_image: MinimizedImage,
+                #[serde(default)]
+                pub client_pointer_warp: PointerWarpMode,
+                #[serde(default)]
+                pub client_switch_osd: SwitchOsdMode,
+                #[serde(default)]
+                pub client_switch_speech: bool,
                 #[serde(default = "default_hotkey_backend")]
                 pub hotkey_backend: HotkeyBackendType,
                 #[serde(default)]
@@ -272,7 +712,13 @@ impl<'de> Deserialize<'de> for Profile {
                 #[serde(default)]
                 pub hotkey_logged_out_cycle: bool,
                 #[serde(default)]
-                pub hotkey_require_eve_focus: bool,
+                pub hotkey_focus_context: HotkeyFocusContext,
+                #[serde(default)]
+                pub hotkey_focus_sources: Vec<String>,
+                #[serde(default)]
+                pub hotkey_blocked_classes: Vec<String>,
+                #[serde(default = "default_exclude_test_servers")]
+                pub hotkey_cycle_exclude_test_servers: bool,
                 #[serde(default)]
                 pub hotkey_cycle_reset_index: bool,
                 #[serde(default)]
@@ -282,13 +728,53 @@ impl<'de> Deserialize<'de> for Profile {
                 #[serde(default)]
                 pub hotkey_toggle_previews: Option<crate::config::HotkeyBinding>,
                 #[serde(default)]
+                pub hotkey_overview: Option<crate::config::HotkeyBinding>,
+                #[serde(default)]
+                pub hotkey_flip: Option<crate::config::HotkeyBinding>,
+                #[serde(default)]
+                pub hotkey_peek: Option<crate::config::HotkeyBinding>,
+                #[serde(default = "default_peek_scale")]
+                pub hotkey_peek_scale: f32,
+                #[serde(default)]
+                pub hotkey_reveal: Option<crate::config::HotkeyBinding>,
+                #[serde(default)]
+                pub hotkey_panic: Option<crate::config::HotkeyBinding>,
+                #[serde(default)]
+                pub hotkey_panic_decoy_class: String,
+                #[serde(default)]
+                pub hotkey_pause: Option<crate::config::HotkeyBinding>,
+                #[serde(default)]
+                pub hotkey_suspend: Option<crate::config::HotkeyBinding>,
+                #[serde(default = "default_hotkey_suspend_secs")]
+                pub hotkey_suspend_secs: u32,
+                #[serde(default)]
                 pub character_hotkeys: HashMap<String, crate::config::HotkeyBinding>,
                 #[serde(default)]
                 pub character_thumbnails: HashMap<String, CharacterSettings>,
                 #[serde(default)]
                 pub custom_source_thumbnails: HashMap<String, CharacterSettings>,
                 #[serde(default)]
+                pub custom_source_geometry: Vec<SourceGeometry>,
+                #[serde(default)]
+                pub accounts: Vec<Account>,
+                #[serde(default)]
+                pub prefix_profile_rules: Vec<PrefixProfileRule>,
+                #[serde(default)]
+                pub visibility_schedule: Vec<VisibilityScheduleRule>,
+                #[serde(default)]
+                pub thumbnail_layouts: Vec<ThumbnailLayout>,
+                #[serde(default)]
+                pub session_snapshots: Vec<SessionSnapshot>,
+                #[serde(default)]
+                pub startup_actions: StartupActions,
+                #[serde(default)]
+                pub launch_order: Vec<LaunchEntry>,
+                #[serde(default)]
+                pub archived_characters: HashMap<String, CharacterSettings>,
+                #[serde(default)]
                 pub custom_windows: Vec<CustomWindowRule>,
+                #[serde(default = "default_client_detection_rules")]
+                pub client_detection_rules: Vec<ClientDetectionRule>,
             }
 
             #[derive(Deserialize)]
@@ -331,8 +817,11 @@ impl<'de> Deserialize<'de> for Profile {
             Ok(Profile {
                 profile_name: p.profile_name,
                 profile_description: p.profile_description,
+                profile_locked: p.profile_locked,
                 thumbnail_default_width: p.thumbnail_default_width,
                 thumbnail_default_height: p.thumbnail_default_height,
+                thumbnail_hidpi_scaling: p.thumbnail_hidpi_scaling,
+                thumbnail_monitor_scales: p.thumbnail_monitor_scales,
                 thumbnail_enabled: p.thumbnail_enabled,
                 thumbnail_opacity: p.thumbnail_opacity,
                 thumbnail_active_border: p.thumbnail_active_border,
@@ -341,30 +830,106 @@ impl<'de> Deserialize<'de> for Profile {
                 thumbnail_inactive_border: p.thumbnail_inactive_border,
                 thumbnail_inactive_border_size: p.thumbnail_inactive_border_size,
                 thumbnail_inactive_border_color: p.thumbnail_inactive_border_color,
+                thumbnail_test_server_border_color: p.thumbnail_test_server_border_color,
+                thumbnail_border_padding: p.thumbnail_border_padding,
+                thumbnail_border_glow: p.thumbnail_border_glow,
+                thumbnail_active_border_style: p.thumbnail_active_border_style,
+                thumbnail_inactive_border_style: p.thumbnail_inactive_border_style,
+                thumbnail_skipped_border_style: p.thumbnail_skipped_border_style,
+                thumbnail_skipped_border_size: p.thumbnail_skipped_border_size,
+                thumbnail_minimized_border_style: p.thumbnail_minimized_border_style,
+                thumbnail_minimized_border_size: p.thumbnail_minimized_border_size,
+                thumbnail_unfocused_effect: p.thumbnail_unfocused_effect,
+                thumbnail_unfocused_tint_color: p.thumbnail_unfocused_tint_color,
+                thumbnail_shape: p.thumbnail_shape,
+                thumbnail_corner_radius: p.thumbnail_corner_radius,
+                thumbnail_icon_size: p.thumbnail_icon_size,
+                thumbnail_cycle_badge: p.thumbnail_cycle_badge,
+                thumbnail_cycle_badge_corner: p.thumbnail_cycle_badge_corner,
+                thumbnail_cycle_badge_size: p.thumbnail_cycle_badge_size,
+                thumbnail_status_badges: p.thumbnail_status_badges,
+                thumbnail_status_badge_corner: p.thumbnail_status_badge_corner,
+                thumbnail_status_badge_size: p.thumbnail_status_badge_size,
                 thumbnail_text_size: p.thumbnail_text_size,
                 thumbnail_text_x: p.thumbnail_text_x,
                 thumbnail_text_y: p.thumbnail_text_y,
                 thumbnail_text_font: p.thumbnail_text_font,
                 thumbnail_text_color: p.thumbnail_text_color,
+                thumbnail_text_effect: p.thumbnail_text_effect,
+                thumbnail_text_effect_color: p.thumbnail_text_effect_color,
+                thumbnail_text_shadow_x: p.thumbnail_text_shadow_x,
+                thumbnail_text_shadow_y: p.thumbnail_text_shadow_y,
+                thumbnail_text_outline_width: p.thumbnail_text_outline_width,
+                thumbnail_show_notes: p.thumbnail_show_notes,
+                thumbnail_alias_template: p.thumbnail_alias_template,
                 thumbnail_auto_save_position: p.thumbnail_auto_save_position,
                 thumbnail_snap_threshold: p.thumbnail_snap_threshold,
                 thumbnail_hide_not_focused: p.thumbnail_hide_not_focused,
                 thumbnail_preserve_position_on_swap: p.thumbnail_preserve_position_on_swap,
+                thumbnail_capture_backend: p.thumbnail_capture_backend,
+                thumbnail_scale_filter: p.thumbnail_scale_filter,
+                thumbnail_vsync: p.thumbnail_vsync,
+                thumbnail_click_passthrough: p.thumbnail_click_passthrough,
+                thumbnail_hover_zoom: p.thumbnail_hover_zoom,
+                thumbnail_hover_zoom_anchor: p.thumbnail_hover_zoom_anchor,
+                thumbnail_hover_zoom_delay_ms: p.thumbnail_hover_zoom_delay_ms,
+                thumbnail_hover_tooltip: p.thumbnail_hover_tooltip,
+                thumbnail_scroll_resize_step: p.thumbnail_scroll_resize_step,
+                thumbnail_scroll_resize_lock_aspect: p.thumbnail_scroll_resize_lock_aspect,
+                thumbnail_hide_fade_ms: p.thumbnail_hide_fade_ms,
+                thumbnail_hide_exempt_classes: p.thumbnail_hide_exempt_classes,
+                timing_focus_loss_delay_ms: p.timing_focus_loss_delay_ms,
+                timing_position_debounce_ms: p.timing_position_debounce_ms,
+                timing_auto_save_delay_ms: p.timing_auto_save_delay_ms,
+                timing_damage_frame_ms: p.timing_damage_frame_ms,
+                timing_identity_retry_interval_ms: p.timing_identity_retry_interval_ms,
+                timing_identity_retry_window_ms: p.timing_identity_retry_window_ms,
                 client_minimize_on_switch: p.client_minimize_on_switch,
                 client_minimize_show_overlay: p.client_minimize_show_overlay,
+                client_minimize_alt_restore: p.client_minimize_alt_restore,
+                client_minimize_freeze_frame: p.client_minimize_freeze_frame,
+                client_minimize_text: p.client_minimize_text,
+                client_minimize_background: p.client_minimize_background,
+                client_minimize_image: p.client_minimize_image,
+                client_pointer_warp: p.client_pointer_warp,
+                client_switch_osd: p.client_switch_osd,
+                client_switch_speech: p.client_switch_speech,
                 hotkey_backend: p.hotkey_backend,
                 hotkey_input_device: p.hotkey_input_device,
                 cycle_groups,
                 hotkey_logged_out_cycle: p.hotkey_logged_out_cycle,
-                hotkey_require_eve_focus: p.hotkey_require_eve_focus,
+                hotkey_focus_context: p.hotkey_focus_context,
+                hotkey_focus_sources: p.hotkey_focus_sources,
+                hotkey_blocked_classes: p.hotkey_blocked_classes,
+                hotkey_cycle_exclude_test_servers: p.hotkey_cycle_exclude_test_servers,
                 hotkey_cycle_reset_index: p.hotkey_cycle_reset_index,
                 hotkey_profile_switch: p.hotkey_profile_switch,
                 hotkey_toggle_skip: p.hotkey_toggle_skip,
                 hotkey_toggle_previews: p.hotkey_toggle_previews,
+                hotkey_overview: p.hotkey_overview,
+                hotkey_flip: p.hotkey_flip,
+                hotkey_peek: p.hotkey_peek,
+                hotkey_reveal: p.hotkey_reveal,
+                hotkey_panic: p.hotkey_panic,
+                hotkey_panic_decoy_class: p.hotkey_panic_decoy_class,
+                hotkey_pause: p.hotkey_pause,
+                hotkey_suspend: p.hotkey_suspend,
+                hotkey_suspend_secs: p.hotkey_suspend_secs,
+                hotkey_peek_scale: p.hotkey_peek_scale,
                 character_hotkeys: p.character_hotkeys,
                 character_thumbnails: p.character_thumbnails,
                 custom_source_thumbnails: p.custom_source_thumbnails,
+                custom_source_geometry: p.custom_source_geometry,
+                accounts: p.accounts,
+                prefix_profile_rules: p.prefix_profile_rules,
+                visibility_schedule: p.visibility_schedule,
+                thumbnail_layouts: p.thumbnail_layouts,
+                session_snapshots: p.session_snapshots,
+                startup_actions: p.startup_actions,
+                launch_order: p.launch_order,
+                archived_characters: p.archived_characters,
                 custom_windows: p.custom_windows,
+                client_detection_rules: p.client_detection_rules,
             })
         }
     }
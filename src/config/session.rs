@@ -0,0 +1,177 @@
+//! Persistent session history
+//!
+//! Remembers when each character was last seen by the daemon so long-unused entries can be
+//! found and cleaned up. Kept in its own file next to the config so routine detections don't
+//! rewrite (and back up) the user's config.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use super::profile::Config;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionHistory {
+    /// Character name → unix timestamp (seconds) of the last detection
+    #[serde(default)]
+    pub character_last_seen: HashMap<String, u64>,
+}
+
+/// Current unix time in seconds
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl SessionHistory {
+    pub fn path() -> PathBuf {
+        Config::path().with_file_name(crate::common::constants::config::SESSION_FILENAME)
+    }
+
+    /// Load the history, falling back to an empty one if the file is missing or unreadable
+    pub fn load() -> Self {
+        let path = Self::path();
+        if !path.exists() {
+            return Self::default();
+        }
+        match Self::load_from(&path) {
+            Ok(history) => history,
+            Err(e) => {
+                warn!(error = ?e, "Ignoring unreadable session history");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session history from {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse session history from {:?}", path))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create config directory {:?}", parent))?;
+        }
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize session history")?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write session history to {:?}", path))?;
+        info!(path = ?path, "Saved session history");
+        Ok(())
+    }
+
+    /// Record a detection. Returns true if the stored timestamp changed.
+    pub fn mark_seen(&mut self, name: &str, now: u64) -> bool {
+        match self.character_last_seen.get_mut(name) {
+            Some(seen) if *seen >= now => false,
+            Some(seen) => {
+                *seen = now;
+                true
+            }
+            None => {
+                self.character_last_seen.insert(name.to_string(), now);
+                true
+            }
+        }
+    }
+
+    /// Whole days since the character was last seen (`None` if never recorded)
+    pub fn days_since_seen(&self, name: &str, now: u64) -> Option<u64> {
+        self.character_last_seen
+            .get(name)
+            .map(|seen| now.saturating_sub(*seen) / SECS_PER_DAY)
+    }
+
+    /// Start tracking characters that have no record yet.
+    ///
+    /// Characters that predate the history are counted from `now` rather than treated as
+    /// never seen, so upgrading doesn't flag every existing character as stale at once.
+    /// Returns true if anything was added.
+    pub fn track_new<'a>(&mut self, names: impl IntoIterator<Item = &'a String>, now: u64) -> bool {
+        let mut added = false;
+        for name in names {
+            if !self.character_last_seen.contains_key(name) {
+                self.character_last_seen.insert(name.clone(), now);
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// Characters from `names` not seen for at least `days` days, oldest first.
+    /// Characters without a record are never stale.
+    pub fn stale<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a String>,
+        days: u32,
+        now: u64,
+    ) -> Vec<(String, u64)> {
+        let mut stale: Vec<(String, u64)> = names
+            .into_iter()
+            .filter_map(|name| {
+                self.days_since_seen(name, now)
+                    .filter(|age| *age >= u64::from(days))
+                    .map(|age| (name.clone(), age))
+            })
+            .collect();
+        stale.sort_by_key(|(name, age)| (std::cmp::Reverse(*age), name.to_lowercase()));
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_seen_only_moves_forward() {
+        let mut history = SessionHistory::default();
+        assert!(history.mark_seen("Alpha", 100));
+        assert!(!history.mark_seen("Alpha", 50));
+        assert!(history.mark_seen("Alpha", 200));
+        assert_eq!(history.character_last_seen["Alpha"], 200);
+    }
+
+    #[test]
+    fn test_stale_characters() {
+        let now = 100 * SECS_PER_DAY;
+        let mut history = SessionHistory::default();
+        history.mark_seen("Recent", now - SECS_PER_DAY);
+        history.mark_seen("Old", now - 40 * SECS_PER_DAY);
+        history.mark_seen("Older", now - 60 * SECS_PER_DAY);
+
+        let names: Vec<String> = ["Recent", "Old", "Older", "Unknown"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let stale = history.stale(&names, 30, now);
+        assert_eq!(stale, [("Older".to_string(), 60), ("Old".to_string(), 40)]);
+    }
+
+    #[test]
+    fn test_track_new_keeps_existing_records() {
+        let mut history = SessionHistory::default();
+        history.mark_seen("Known", 10);
+
+        let names = vec!["Known".to_string(), "Legacy".to_string()];
+        assert!(history.track_new(&names, 500));
+        assert!(!history.track_new(&names, 900));
+        assert_eq!(history.character_last_seen["Known"], 10);
+        assert_eq!(history.character_last_seen["Legacy"], 500);
+    }
+}
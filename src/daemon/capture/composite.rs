@@ -0,0 +1,288 @@
+//! Composite capture: RENDER scales the redirected client's offscreen pixmap on the server
+//!
+//! Bilinear filtering only ever blends the 2x2 source pixels nearest a sample, so shrinking a
+//! client by much more than 2x skips most of its pixels and small thumbnails shimmer. For large
+//! ratios the bilinear filter therefore scales in two passes through an intermediate image, each
+//! shrinking by the square root of the full ratio. "good" and "best" leave it to the server,
+//! whose convolution filters cover every source pixel (at a higher CPU cost).
+
+use std::cell::RefCell;
+
+use anyhow::{Context, Result};
+use tracing::{debug, error, info};
+use x11rb::connection::Connection;
+use x11rb::protocol::render::{
+    ConnectionExt as RenderExt, CreatePictureAux, PictOp, Pictformat, Picture, Transform,
+};
+use x11rb::protocol::xproto::{ConnectionExt, Pixmap, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::{CaptureBackend, CaptureTarget};
+use crate::common::types::{CropRegion, Dimensions};
+use crate::config::ScaleFilter;
+use crate::x11::{AppContext, to_fixed};
+
+/// Downscale ratio (on either axis) from which bilinear scaling takes two passes
+const TWO_PASS_MIN_RATIO: f32 = 3.0;
+
+#[derive(Debug)]
+pub struct CompositeCapture<'a> {
+    conn: &'a RustConnection,
+    src_picture: Picture,
+    filter: ScaleFilter,
+    /// Where intermediate images are created, and their depth and format (the thumbnail's)
+    root: Window,
+    depth: u8,
+    format: Pictformat,
+    /// Intermediate image of the last two-pass downscale, reused while the size fits
+    intermediate: RefCell<Option<Intermediate>>,
+}
+
+/// Off-screen image between the two passes of a downscale
+#[derive(Debug)]
+struct Intermediate {
+    size: Dimensions,
+    pixmap: Pixmap,
+    picture: Picture,
+}
+
+impl<'a> CompositeCapture<'a> {
+    pub fn new(
+        ctx: &AppContext<'a>,
+        src: Window,
+        src_depth: u8,
+        filter: ScaleFilter,
+        character_name: &str,
+    ) -> Result<Self> {
+        // Determine source format based on window depth
+        let src_format = if src_depth == 32 {
+            info!(character = %character_name, depth = src_depth, format = "ARGB32", "Using ARGB format for source window");
+            ctx.formats.argb
+        } else {
+            // Default to RGB (usually 24-bit)
+            // If it's not 32 or root depth, this might still be wrong, but it covers standard cases.
+            debug!(character = %character_name, depth = src_depth, format = "RGB24", "Using RGB format for source window");
+            ctx.formats.rgb
+        };
+
+        let src_picture = ctx
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for source picture")?;
+
+        ctx.conn
+            .render_create_picture(src_picture, src, src_format, &CreatePictureAux::new())
+            .context(format!(
+                "Failed to create source picture for '{}'",
+                character_name
+            ))?;
+
+        ctx.conn
+            .render_set_picture_filter(src_picture, filter.render_name().as_bytes(), &[])
+            .context(format!(
+                "Failed to set {} filter for '{}'",
+                filter.render_name(),
+                character_name
+            ))?;
+
+        Ok(Self {
+            conn: ctx.conn,
+            src_picture,
+            filter,
+            root: ctx.screen.root,
+            depth: ctx.screen.root_depth,
+            format: ctx.formats.rgb,
+            intermediate: RefCell::new(None),
+        })
+    }
+
+    /// Draws `region` of `src` scaled to `dimensions` into `dst`
+    fn scale(
+        &self,
+        src: Picture,
+        region: CropRegion,
+        dst: Picture,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        // Maps destination pixels into the region: scale, then offset by its origin
+        let transform = Transform {
+            matrix11: to_fixed(region.width as f32 / dimensions.width as f32),
+            matrix13: to_fixed(region.x as f32),
+            matrix22: to_fixed(region.height as f32 / dimensions.height as f32),
+            matrix23: to_fixed(region.y as f32),
+            matrix33: to_fixed(1.0),
+            ..Default::default()
+        };
+        self.conn
+            .render_set_picture_transform(src, transform)
+            .context("Failed to set source transform")?;
+        self.conn
+            .render_composite(
+                PictOp::SRC,
+                src,
+                0u32,
+                dst,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                dimensions.width,
+                dimensions.height,
+            )
+            .context("Failed to composite source window")?;
+        Ok(())
+    }
+
+    /// Picture of a `size` intermediate image, recreated when the size changes
+    fn intermediate(&self, size: Dimensions) -> Result<Picture> {
+        let mut intermediate = self.intermediate.borrow_mut();
+        if let Some(current) = intermediate.as_ref()
+            && current.size == size
+        {
+            return Ok(current.picture);
+        }
+        if let Some(old) = intermediate.take() {
+            old.free(self.conn);
+        }
+
+        let pixmap = self
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for intermediate image")?;
+        self.conn
+            .create_pixmap(self.depth, pixmap, self.root, size.width, size.height)
+            .context("Failed to create intermediate image")?;
+        let picture = self
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for intermediate picture")?;
+        let created = Intermediate {
+            size,
+            pixmap,
+            picture,
+        };
+        self.conn
+            .render_create_picture(picture, pixmap, self.format, &CreatePictureAux::new())
+            .context("Failed to create intermediate picture")
+            .and_then(|_| {
+                self.conn
+                    .render_set_picture_filter(picture, b"bilinear", &[])
+                    .context("Failed to set intermediate filter")
+            })
+            .inspect_err(|_| created.free(self.conn))?;
+        *intermediate = Some(created);
+        Ok(picture)
+    }
+}
+
+impl Intermediate {
+    fn free(&self, conn: &RustConnection) {
+        if let Err(e) = conn.render_free_picture(self.picture) {
+            error!(picture = self.picture, error = %e, "Failed to free intermediate picture");
+        }
+        if let Err(e) = conn.free_pixmap(self.pixmap) {
+            error!(pixmap = self.pixmap, error = %e, "Failed to free intermediate image");
+        }
+    }
+}
+
+/// Size of the image between the passes of a two-pass downscale of `region` to `dimensions`,
+/// or None when one pass is enough
+fn two_pass_size(
+    filter: ScaleFilter,
+    region: Dimensions,
+    dimensions: Dimensions,
+) -> Option<Dimensions> {
+    if filter != ScaleFilter::Bilinear {
+        return None;
+    }
+    let ratio_x = region.width as f32 / dimensions.width.max(1) as f32;
+    let ratio_y = region.height as f32 / dimensions.height.max(1) as f32;
+    if ratio_x.max(ratio_y) < TWO_PASS_MIN_RATIO {
+        return None;
+    }
+    // Each pass shrinks by the square root of the ratio (never enlarging an axis)
+    let size = |target: u16, ratio: f32| {
+        ((target as f32 * ratio.max(1.0).sqrt()).round() as u16).max(target)
+    };
+    Some(Dimensions::new(
+        size(dimensions.width, ratio_x),
+        size(dimensions.height, ratio_y),
+    ))
+}
+
+impl CaptureBackend for CompositeCapture<'_> {
+    fn name(&self) -> &'static str {
+        "composite"
+    }
+
+    fn capture(
+        &self,
+        target: CaptureTarget,
+        _src_size: Dimensions,
+        region: CropRegion,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let Some(size) = two_pass_size(self.filter, region.dimensions(), dimensions) else {
+            return self.scale(self.src_picture, region, target.picture, dimensions);
+        };
+        let intermediate = self.intermediate(size)?;
+        self.scale(self.src_picture, region, intermediate, size)?;
+        self.scale(
+            intermediate,
+            CropRegion::full(size),
+            target.picture,
+            dimensions,
+        )
+    }
+}
+
+impl Drop for CompositeCapture<'_> {
+    fn drop(&mut self) {
+        if let Some(intermediate) = self.intermediate.get_mut().take() {
+            intermediate.free(self.conn);
+        }
+        if let Err(e) = self.conn.render_free_picture(self.src_picture) {
+            error!(
+                picture = self.src_picture,
+                error = %e,
+                "Failed to free source picture"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_pass_size() {
+        let thumbnail = Dimensions::new(240, 135);
+        // 1920x1080 -> 240x135 is 8x: two passes of ~2.83x
+        assert_eq!(
+            two_pass_size(
+                ScaleFilter::Bilinear,
+                Dimensions::new(1920, 1080),
+                thumbnail
+            ),
+            Some(Dimensions::new(679, 382))
+        );
+        // Small ratios and the other filters take one pass
+        assert_eq!(
+            two_pass_size(ScaleFilter::Bilinear, Dimensions::new(480, 270), thumbnail),
+            None
+        );
+        assert_eq!(
+            two_pass_size(ScaleFilter::Best, Dimensions::new(1920, 1080), thumbnail),
+            None
+        );
+        // A crop narrower than the thumbnail is never enlarged twice
+        assert_eq!(
+            two_pass_size(ScaleFilter::Bilinear, Dimensions::new(200, 1080), thumbnail),
+            Some(Dimensions::new(240, 382))
+        );
+    }
+}
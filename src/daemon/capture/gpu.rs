@@ -0,0 +1,609 @@
+//! GPU capture: OpenGL ES scales the client's Composite pixmap, bound as a texture
+//!
+//! With 20+ clients RENDER's bilinear downscaling of full-size clients can saturate a CPU core
+//! (software RENDER, or drivers that fall back to it). Here both the client's offscreen pixmap
+//! (Composite `NameWindowPixmap`) and a thumbnail-sized pixmap are bound as textures through
+//! EGL images (texture-from-pixmap). The GPU draws the scaled client into the thumbnail pixmap,
+//! and RENDER only copies that 1:1 into the window, so the overlay pipeline is unchanged.
+//!
+//! EGL opens its own connection to the default X display; pixmap IDs are shared server-wide.
+//! One context serves every thumbnail of the daemon thread. EGL and GL are raw C APIs, which is
+//! why this module allows `unsafe`.
+
+#![allow(unsafe_code)]
+
+use std::cell::{OnceCell, RefCell};
+use std::ffi::{CStr, c_void};
+use std::fmt;
+use std::ptr::{self, NonNull};
+use std::rc::Rc;
+
+use anyhow::{Context, Result, bail};
+use glow::HasContext;
+use glutin::api::egl::context::PossiblyCurrentContext;
+use glutin::api::egl::display::Display;
+use glutin::config::{Api, ConfigTemplateBuilder};
+use glutin::context::{ContextApi, ContextAttributesBuilder, Version};
+use glutin::display::{AsRawDisplay, GlDisplay, RawDisplay};
+use raw_window_handle::{RawDisplayHandle, XlibDisplayHandle};
+use tracing::{error, info, warn};
+use x11rb::connection::Connection;
+use x11rb::protocol::composite::{ConnectionExt as CompositeExt, Redirect};
+use x11rb::protocol::render::Pictformat;
+use x11rb::protocol::render::{ConnectionExt as RenderExt, CreatePictureAux, PictOp, Picture};
+use x11rb::protocol::xproto::{ConnectionExt, Pixmap, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::{CaptureBackend, CaptureTarget};
+use crate::common::types::{CropRegion, Dimensions};
+use crate::x11::AppContext;
+
+/// `EGL_NATIVE_PIXMAP_KHR` (EGL_KHR_image_pixmap): image target for an X pixmap
+const EGL_NATIVE_PIXMAP_KHR: u32 = 0x30B0;
+const EGL_IMAGE_PRESERVED_KHR: i32 = 0x30D2;
+const EGL_TRUE: i32 = 1;
+const EGL_NONE: i32 = 0x3038;
+const EGL_EXTENSIONS: i32 = 0x3055;
+
+/// `glEGLImageTargetTexture2DOES` (GL_OES_EGL_image)
+type ImageTargetTexture2dOes = unsafe extern "system" fn(target: u32, image: *const c_void);
+
+const VERTEX_SHADER: &str = "
+attribute vec2 position;
+// Captured part of the source: origin (xy) and size (zw) as fractions of the texture
+uniform vec4 region;
+varying vec2 uv;
+void main() {
+    // Pixmap row 0 is the top in both textures, so no flip is needed
+    uv = region.xy + (position * 0.5 + 0.5) * region.zw;
+    gl_Position = vec4(position, 0.0, 1.0);
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+uniform sampler2D source;
+varying vec2 uv;
+void main() {
+    gl_FragColor = vec4(texture2D(source, uv).rgb, 1.0);
+}
+";
+
+/// Full-target quad as a triangle strip
+const QUAD: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+
+thread_local! {
+    /// Shared GPU state, set up on first use. `None` once setup has failed.
+    static GPU: OnceCell<Option<Rc<Gpu>>> = const { OnceCell::new() };
+}
+
+/// Shared GPU state for this thread, if EGL supports texture-from-pixmap here
+fn gpu() -> Option<Rc<Gpu>> {
+    GPU.with(|gpu| {
+        gpu.get_or_init(|| match Gpu::new() {
+            Ok(gpu) => {
+                info!("GPU thumbnail capture initialized");
+                Some(Rc::new(gpu))
+            }
+            Err(e) => {
+                warn!(error = %e, "GPU thumbnail capture is unavailable");
+                None
+            }
+        })
+        .clone()
+    })
+}
+
+/// Whether the GPU backend can run (initializes it on first call)
+pub fn is_available() -> bool {
+    gpu().is_some()
+}
+
+/// Screen number of an X display name (":0.1" -> 1); EGL connects to the default display
+fn screen_number(display: &str) -> i32 {
+    display
+        .rsplit_once(':')
+        .and_then(|(_, rest)| rest.split_once('.'))
+        .and_then(|(_, screen)| screen.parse().ok())
+        .unwrap_or(0)
+}
+
+/// EGL display, current context and the scaling program
+struct Gpu {
+    display: Display,
+    _context: PossiblyCurrentContext,
+    gl: glow::Context,
+    image_target_texture: ImageTargetTexture2dOes,
+    program: glow::Program,
+    quad: glow::Buffer,
+    position: u32,
+    region: Option<glow::UniformLocation>,
+}
+
+impl fmt::Debug for Gpu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gpu").finish_non_exhaustive()
+    }
+}
+
+impl Gpu {
+    fn new() -> Result<Self> {
+        let handle = XlibDisplayHandle::new(
+            None,
+            screen_number(&std::env::var("DISPLAY").unwrap_or_default()),
+        );
+        // SAFETY: a handle without a display asks EGL to open the default X display itself
+        let display = unsafe { Display::new(RawDisplayHandle::Xlib(handle)) }
+            .context("Failed to open EGL display")?;
+
+        let RawDisplay::Egl(raw_display) = display.raw_display() else {
+            bail!("EGL display has no raw handle");
+        };
+        // SAFETY: `raw_display` is the initialized display; the returned string is static
+        let extensions = unsafe {
+            let list = display.egl().QueryString(raw_display, EGL_EXTENSIONS);
+            if list.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(list).to_string_lossy().into_owned()
+            }
+        };
+        for required in [
+            "EGL_KHR_image_pixmap",
+            "EGL_KHR_image_base",
+            "EGL_KHR_surfaceless_context",
+        ] {
+            if !extensions.split_whitespace().any(|e| e == required) {
+                bail!("EGL display lacks {required}");
+            }
+        }
+
+        let template = ConfigTemplateBuilder::new().with_api(Api::GLES2).build();
+        // SAFETY: the template only asks for GLES2 support
+        let config = unsafe { display.find_configs(template) }
+            .context("Failed to query EGL configs")?
+            .next()
+            .context("No EGL config supports OpenGL ES 2")?;
+        let attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::Gles(Some(Version::new(2, 0))))
+            .build(None);
+        // SAFETY: `config` belongs to `display`
+        let context = unsafe { display.create_context(&config, &attributes) }
+            .context("Failed to create OpenGL ES context")?
+            .make_current_surfaceless()
+            .context("Failed to make OpenGL ES context current")?;
+
+        // SAFETY: the context is current, so GL entry points resolve for it
+        let gl = unsafe {
+            glow::Context::from_loader_function_cstr(|name| display.get_proc_address(name))
+        };
+        let image_target_texture = display.get_proc_address(c"glEGLImageTargetTexture2DOES");
+        if image_target_texture.is_null() {
+            bail!("OpenGL ES lacks GL_OES_EGL_image");
+        }
+        // SAFETY: non-null pointer to glEGLImageTargetTexture2DOES, which has this signature
+        let image_target_texture = unsafe {
+            std::mem::transmute::<*const c_void, ImageTargetTexture2dOes>(image_target_texture)
+        };
+
+        // SAFETY: plain GL calls on the current context
+        let (program, quad, position, region) = unsafe {
+            let program = link_program(&gl)?;
+            let position = gl
+                .get_attrib_location(program, "position")
+                .context("Scaling shader has no position attribute")?;
+            let quad = gl.create_buffer().map_err(anyhow::Error::msg)?;
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(quad));
+            let bytes: Vec<u8> = QUAD.iter().flat_map(|v| v.to_ne_bytes()).collect();
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, &bytes, glow::STATIC_DRAW);
+            gl.use_program(Some(program));
+            gl.uniform_1_i32(gl.get_uniform_location(program, "source").as_ref(), 0);
+            let region = gl.get_uniform_location(program, "region");
+            (program, quad, position, region)
+        };
+
+        Ok(Self {
+            display,
+            _context: context,
+            gl,
+            image_target_texture,
+            program,
+            quad,
+            position,
+            region,
+        })
+    }
+
+    fn raw_display(&self) -> *const c_void {
+        match self.display.raw_display() {
+            RawDisplay::Egl(raw) => raw,
+            #[allow(unreachable_patterns)]
+            _ => ptr::null(),
+        }
+    }
+
+    /// EGL image sharing the storage of `pixmap`
+    fn create_image(&self, pixmap: Pixmap) -> Result<NonNull<c_void>> {
+        let attributes = [EGL_IMAGE_PRESERVED_KHR, EGL_TRUE, EGL_NONE];
+        let egl = self.display.egl();
+        // SAFETY: the pixmap exists (callers sync with the server first) and the attribute
+        // list is EGL_NONE-terminated
+        let image = unsafe {
+            egl.CreateImageKHR(
+                self.raw_display(),
+                ptr::null(),
+                EGL_NATIVE_PIXMAP_KHR,
+                pixmap as usize as *const c_void,
+                attributes.as_ptr(),
+            )
+        };
+        NonNull::new(image.cast_mut()).with_context(|| {
+            // SAFETY: reads this thread's last EGL error
+            let code = unsafe { egl.GetError() };
+            format!("eglCreateImageKHR failed for pixmap {pixmap} (error {code:#x})")
+        })
+    }
+
+    fn destroy_image(&self, image: NonNull<c_void>) {
+        // SAFETY: `image` was created on this display and is no longer bound
+        unsafe {
+            self.display
+                .egl()
+                .DestroyImageKHR(self.raw_display(), image.as_ptr());
+        }
+    }
+
+    /// Texture backed by `image`, filtered bilinearly like the Composite backend
+    fn texture_for(&self, image: NonNull<c_void>) -> Result<glow::Texture> {
+        // SAFETY: GL calls on the current context with a valid EGL image
+        unsafe {
+            let gl = &self.gl;
+            let texture = gl.create_texture().map_err(anyhow::Error::msg)?;
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            for (parameter, value) in [
+                (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+                (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+                (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+            ] {
+                gl.tex_parameter_i32(glow::TEXTURE_2D, parameter, value as i32);
+            }
+            (self.image_target_texture)(glow::TEXTURE_2D, image.as_ptr());
+            if gl.get_error() != glow::NO_ERROR {
+                gl.delete_texture(texture);
+                bail!("Failed to bind pixmap image to a texture");
+            }
+            Ok(texture)
+        }
+    }
+}
+
+impl Drop for Gpu {
+    fn drop(&mut self) {
+        // SAFETY: the context is still current; these objects belong to it
+        unsafe {
+            self.gl.delete_program(self.program);
+            self.gl.delete_buffer(self.quad);
+        }
+    }
+}
+
+/// Compiles and links the scaling shaders
+///
+/// # Safety
+/// `gl` must be current on this thread.
+unsafe fn link_program(gl: &glow::Context) -> Result<glow::Program> {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let program = gl.create_program().map_err(anyhow::Error::msg)?;
+        let mut shaders = Vec::new();
+        for (kind, source) in [
+            (glow::VERTEX_SHADER, VERTEX_SHADER),
+            (glow::FRAGMENT_SHADER, FRAGMENT_SHADER),
+        ] {
+            let shader = gl.create_shader(kind).map_err(anyhow::Error::msg)?;
+            gl.shader_source(shader, source);
+            gl.compile_shader(shader);
+            if !gl.get_shader_compile_status(shader) {
+                let log = gl.get_shader_info_log(shader);
+                gl.delete_shader(shader);
+                gl.delete_program(program);
+                bail!("Failed to compile scaling shader: {log}");
+            }
+            gl.attach_shader(program, shader);
+            shaders.push(shader);
+        }
+        gl.link_program(program);
+        for shader in shaders {
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+        }
+        if !gl.get_program_link_status(program) {
+            let log = gl.get_program_info_log(program);
+            gl.delete_program(program);
+            bail!("Failed to link scaling shader: {log}");
+        }
+        Ok(program)
+    }
+}
+
+/// The client's Composite pixmap, bound as a texture. Renamed whenever the client resizes.
+struct Source {
+    size: Dimensions,
+    pixmap: Pixmap,
+    image: NonNull<c_void>,
+    texture: glow::Texture,
+}
+
+/// Thumbnail-sized pixmap the GPU renders into, with its RENDER picture
+struct Target {
+    size: Dimensions,
+    pixmap: Pixmap,
+    picture: Picture,
+    image: NonNull<c_void>,
+    texture: glow::Texture,
+    framebuffer: glow::Framebuffer,
+}
+
+pub struct GpuCapture<'a> {
+    conn: &'a RustConnection,
+    gpu: Rc<Gpu>,
+    src: Window,
+    root: Window,
+    depth: u8,
+    format: Pictformat,
+    source: RefCell<Option<Source>>,
+    target: RefCell<Option<Target>>,
+}
+
+impl fmt::Debug for GpuCapture<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuCapture")
+            .field("src", &self.src)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> GpuCapture<'a> {
+    pub fn new(ctx: &AppContext<'a>, src: Window, character_name: &str) -> Result<Self> {
+        let gpu = gpu().context("GPU capture is unavailable")?;
+
+        // Keep the client rendered offscreen even without a compositing manager, so its
+        // pixmap can be named
+        ctx.conn
+            .composite_redirect_window(src, Redirect::AUTOMATIC)
+            .context(format!(
+                "Failed to redirect source window for '{}'",
+                character_name
+            ))?;
+
+        Ok(Self {
+            conn: ctx.conn,
+            gpu,
+            src,
+            root: ctx.screen.root,
+            // Thumbnail windows are created with the root depth
+            depth: ctx.screen.root_depth,
+            format: ctx.formats.rgb,
+            source: RefCell::new(None),
+            target: RefCell::new(None),
+        })
+    }
+
+    fn bind_source(&self, size: Dimensions) -> Result<Source> {
+        let pixmap = self.conn.generate_id()?;
+        // Checked, so the pixmap exists before EGL's connection refers to it
+        self.conn
+            .composite_name_window_pixmap(self.src, pixmap)?
+            .check()
+            .context("Failed to name the source window pixmap")?;
+        let image = self.gpu.create_image(pixmap).inspect_err(|_| {
+            let _ = self.conn.free_pixmap(pixmap);
+        })?;
+        let texture = self.gpu.texture_for(image).inspect_err(|_| {
+            self.gpu.destroy_image(image);
+            let _ = self.conn.free_pixmap(pixmap);
+        })?;
+        Ok(Source {
+            size,
+            pixmap,
+            image,
+            texture,
+        })
+    }
+
+    fn release_source(&self, source: Source) {
+        // SAFETY: the texture belongs to the current context
+        unsafe { self.gpu.gl.delete_texture(source.texture) };
+        self.gpu.destroy_image(source.image);
+        if let Err(e) = self.conn.free_pixmap(source.pixmap) {
+            error!(pixmap = source.pixmap, error = %e, "Failed to free source pixmap");
+        }
+    }
+
+    fn bind_target(&self, size: Dimensions) -> Result<Target> {
+        let pixmap = self.conn.generate_id()?;
+        self.conn
+            .create_pixmap(self.depth, pixmap, self.root, size.width, size.height)?
+            .check()
+            .context("Failed to create GPU target pixmap")?;
+        let picture = self.conn.generate_id()?;
+        let cleanup = |image: Option<NonNull<c_void>>| {
+            if let Some(image) = image {
+                self.gpu.destroy_image(image);
+            }
+            let _ = self.conn.render_free_picture(picture);
+            let _ = self.conn.free_pixmap(pixmap);
+        };
+        self.conn
+            .render_create_picture(picture, pixmap, self.format, &CreatePictureAux::new())
+            .inspect_err(|_| cleanup(None))
+            .context("Failed to create GPU target picture")?;
+
+        let image = self
+            .gpu
+            .create_image(pixmap)
+            .inspect_err(|_| cleanup(None))?;
+        let texture = self
+            .gpu
+            .texture_for(image)
+            .inspect_err(|_| cleanup(Some(image)))?;
+        let gl = &self.gpu.gl;
+        // SAFETY: GL calls on the current context with objects created above
+        let framebuffer = unsafe {
+            let framebuffer = gl
+                .create_framebuffer()
+                .map_err(anyhow::Error::msg)
+                .inspect_err(|_| cleanup(Some(image)))?;
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.framebuffer_texture_2d(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::TEXTURE_2D,
+                Some(texture),
+                0,
+            );
+            if gl.check_framebuffer_status(glow::FRAMEBUFFER) != glow::FRAMEBUFFER_COMPLETE {
+                gl.delete_framebuffer(framebuffer);
+                gl.delete_texture(texture);
+                cleanup(Some(image));
+                bail!("Thumbnail pixmap can't be rendered to by the GPU");
+            }
+            framebuffer
+        };
+        Ok(Target {
+            size,
+            pixmap,
+            picture,
+            image,
+            texture,
+            framebuffer,
+        })
+    }
+
+    fn release_target(&self, target: Target) {
+        // SAFETY: the framebuffer and texture belong to the current context
+        unsafe {
+            self.gpu.gl.delete_framebuffer(target.framebuffer);
+            self.gpu.gl.delete_texture(target.texture);
+        }
+        self.gpu.destroy_image(target.image);
+        if let Err(e) = self.conn.render_free_picture(target.picture) {
+            error!(picture = target.picture, error = %e, "Failed to free GPU target picture");
+        }
+        if let Err(e) = self.conn.free_pixmap(target.pixmap) {
+            error!(pixmap = target.pixmap, error = %e, "Failed to free GPU target pixmap");
+        }
+    }
+}
+
+impl CaptureBackend for GpuCapture<'_> {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn capture(
+        &self,
+        target: CaptureTarget,
+        src_size: Dimensions,
+        region: CropRegion,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let mut source = self.source.borrow_mut();
+        if source.as_ref().is_none_or(|s| s.size != src_size) {
+            if let Some(old) = source.take() {
+                self.release_source(old);
+            }
+            *source = Some(self.bind_source(src_size)?);
+        }
+        let mut scaled = self.target.borrow_mut();
+        if scaled.as_ref().is_none_or(|t| t.size != dimensions) {
+            if let Some(old) = scaled.take() {
+                self.release_target(old);
+            }
+            *scaled = Some(self.bind_target(dimensions)?);
+        }
+        let (Some(source), Some(scaled)) = (source.as_ref(), scaled.as_ref()) else {
+            return Ok(());
+        };
+
+        let gpu = &self.gpu;
+        // SAFETY: GL calls on the current context with objects owned by this backend
+        unsafe {
+            let gl = &gpu.gl;
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(scaled.framebuffer));
+            gl.viewport(0, 0, dimensions.width.into(), dimensions.height.into());
+            gl.use_program(Some(gpu.program));
+            gl.uniform_4_f32(
+                gpu.region.as_ref(),
+                f32::from(region.x) / f32::from(src_size.width),
+                f32::from(region.y) / f32::from(src_size.height),
+                f32::from(region.width) / f32::from(src_size.width),
+                f32::from(region.height) / f32::from(src_size.height),
+            );
+            gl.active_texture(glow::TEXTURE0);
+            gl.bind_texture(glow::TEXTURE_2D, Some(source.texture));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(gpu.quad));
+            gl.enable_vertex_attrib_array(gpu.position);
+            gl.vertex_attrib_pointer_f32(gpu.position, 2, glow::FLOAT, false, 0, 0);
+            gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+            // The X server reads the pixmap next, over another connection
+            gl.finish();
+        }
+
+        self.conn
+            .render_composite(
+                PictOp::SRC,
+                scaled.picture,
+                0u32,
+                target.picture,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                dimensions.width,
+                dimensions.height,
+            )
+            .context("Failed to copy GPU-scaled thumbnail")?;
+        Ok(())
+    }
+
+    fn source_changed(&self) {
+        // The named pixmap keeps showing the old contents once the window gets a new one
+        if let Some(source) = self.source.borrow_mut().take() {
+            self.release_source(source);
+        }
+    }
+}
+
+impl Drop for GpuCapture<'_> {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.take() {
+            self.release_source(source);
+        }
+        if let Some(target) = self.target.take() {
+            self.release_target(target);
+        }
+        if let Err(e) = self
+            .conn
+            .composite_unredirect_window(self.src, Redirect::AUTOMATIC)
+        {
+            error!(window = self.src, error = %e, "Failed to unredirect source window");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_number() {
+        assert_eq!(screen_number(":0"), 0);
+        assert_eq!(screen_number(":1.2"), 2);
+        assert_eq!(screen_number("host.example:0.1"), 1);
+        assert_eq!(screen_number(""), 0);
+    }
+}
@@ -0,0 +1,128 @@
+//! Thumbnail capture backends
+//!
+//! A backend copies the source client's current contents, scaled to the thumbnail size, into
+//! the thumbnail window. `ThumbnailRenderer` owns the window and calls `CaptureBackend::capture`
+//! on every repaint; which backend it gets is decided once per thumbnail by `create`, from the
+//! profile's `thumbnail_capture_backend` and what the X server supports.
+//!
+//! Adding a backend (e.g. a Wayland screencast) means implementing the trait and
+//! wiring it into `CaptureBackendType`, `is_available` and `create`. The event loop is untouched.
+
+mod composite;
+mod gpu;
+mod screenshot;
+mod shm;
+
+use anyhow::Result;
+use tracing::{debug, warn};
+use x11rb::connection::RequestConnection;
+use x11rb::protocol::render::Picture;
+use x11rb::protocol::xproto::{Drawable, Window};
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::types::{CropRegion, Dimensions};
+use crate::config::{CaptureBackendType, ScaleFilter};
+use crate::x11::AppContext;
+
+pub use composite::CompositeCapture;
+pub use gpu::GpuCapture;
+pub use screenshot::ScreenshotCapture;
+pub use shm::ShmCapture;
+
+/// Thumbnail surface a backend draws into (the off-screen back buffer of the window)
+#[derive(Debug, Clone, Copy)]
+pub struct CaptureTarget {
+    pub drawable: Drawable,
+    /// RENDER picture for `drawable`
+    pub picture: Picture,
+}
+
+/// Copies a source window's contents into a thumbnail
+pub trait CaptureBackend: std::fmt::Debug {
+    /// Short name for logs and diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Draws `region` of the source window (currently `src_size`) scaled to `dimensions` into
+    /// `target`.
+    ///
+    /// Callers have already checked that the source is mapped and larger than 1x1, and clipped
+    /// `region` to it.
+    fn capture(
+        &self,
+        target: CaptureTarget,
+        src_size: Dimensions,
+        region: CropRegion,
+        dimensions: Dimensions,
+    ) -> Result<()>;
+
+    /// Forgets anything bound to the source window's current contents. Called when the
+    /// source is mapped, unmapped or reconfigured, since each of those gives it a new pixmap.
+    fn source_changed(&self) {}
+}
+
+/// Whether the X server supports a backend (`Auto` is always available)
+pub fn is_available(conn: &RustConnection, kind: CaptureBackendType) -> bool {
+    match kind {
+        CaptureBackendType::Auto | CaptureBackendType::Screenshot => true,
+        CaptureBackendType::Composite => has_composite(conn),
+        CaptureBackendType::Gpu => has_composite(conn) && gpu::is_available(),
+        // Shared memory only works with a server on this machine
+        CaptureBackendType::Shm => {
+            !crate::x11::display_is_remote() && crate::x11::supports_attach_fd(conn)
+        }
+    }
+}
+
+fn has_composite(conn: &RustConnection) -> bool {
+    conn.extension_information(x11rb::protocol::composite::X11_EXTENSION_NAME)
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Concrete backend for a configured type: `Auto` picks the best available one, and a forced
+/// backend the server can't provide falls back to `Auto`. `Auto` never picks `Gpu`, which is
+/// opt-in.
+pub fn resolve(conn: &RustConnection, requested: CaptureBackendType) -> CaptureBackendType {
+    if requested != CaptureBackendType::Auto {
+        if is_available(conn, requested) {
+            return requested;
+        }
+        warn!(backend = ?requested, "Configured capture backend is unavailable, choosing automatically");
+    }
+    [
+        CaptureBackendType::Composite,
+        CaptureBackendType::Shm,
+        CaptureBackendType::Screenshot,
+    ]
+    .into_iter()
+    .find(|&kind| is_available(conn, kind))
+    .unwrap_or(CaptureBackendType::Screenshot)
+}
+
+/// Creates the capture backend for one thumbnail
+pub fn create<'a>(
+    ctx: &AppContext<'a>,
+    requested: CaptureBackendType,
+    filter: ScaleFilter,
+    src: Window,
+    src_depth: u8,
+    character_name: &str,
+) -> Result<Box<dyn CaptureBackend + 'a>> {
+    let backend: Box<dyn CaptureBackend + 'a> = match resolve(ctx.conn, requested) {
+        CaptureBackendType::Composite | CaptureBackendType::Auto => Box::new(
+            CompositeCapture::new(ctx, src, src_depth, filter, character_name)?,
+        ),
+        CaptureBackendType::Screenshot => Box::new(ScreenshotCapture::new(
+            ctx,
+            src,
+            src_depth,
+            filter,
+            character_name,
+        )?),
+        CaptureBackendType::Gpu => Box::new(GpuCapture::new(ctx, src, character_name)?),
+        CaptureBackendType::Shm => Box::new(ShmCapture::new(ctx, src, src_depth, character_name)?),
+    };
+    debug!(character = %character_name, backend = backend.name(), "Selected capture backend");
+    Ok(backend)
+}
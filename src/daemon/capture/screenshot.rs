@@ -0,0 +1,250 @@
+//! Screenshot capture: GetImage of the client, scaled in the daemon, PutImage into the thumbnail
+//!
+//! Every repaint moves the full client image through the daemon, so this is far slower than
+//! Composite. It exists to rule out RENDER/driver problems when debugging blank thumbnails.
+//!
+//! On a remote X connection that image would cross the network twice, so instead the client is
+//! scaled down on the server (as the Composite backend does) into a 16-bit scratch image when
+//! the server supports one, and that is composited into the thumbnail without leaving the server.
+
+use std::cell::RefCell;
+
+use anyhow::{Context, Result};
+use tracing::{debug, error};
+use x11rb::connection::Connection;
+use x11rb::protocol::render::{
+    ConnectionExt as RenderExt, CreatePictureAux, PictOp, Pictformat, Picture,
+};
+use x11rb::protocol::xproto::{ConnectionExt, CreateGCAux, Gcontext, ImageFormat, Pixmap, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::{CaptureBackend, CaptureTarget, CompositeCapture};
+use crate::common::types::{CropRegion, Dimensions};
+use crate::config::ScaleFilter;
+use crate::x11::{AppContext, capture_frame};
+
+#[derive(Debug)]
+pub struct ScreenshotCapture<'a> {
+    conn: &'a RustConnection,
+    src: Window,
+    gc: Gcontext,
+    depth: u8,
+    /// Server-side downscale used on remote X connections
+    remote: Option<RemoteScale<'a>>,
+}
+
+/// Scales the client into a reduced-depth image on the server and copies that to the thumbnail
+#[derive(Debug)]
+struct RemoteScale<'a> {
+    scaler: CompositeCapture<'a>,
+    root: Window,
+    /// Depth and format of the scratch image (16-bit when available, else the root's)
+    depth: u8,
+    format: Pictformat,
+    scratch: RefCell<Option<Scratch>>,
+}
+
+#[derive(Debug)]
+struct Scratch {
+    size: Dimensions,
+    pixmap: Pixmap,
+    picture: Picture,
+}
+
+impl<'a> ScreenshotCapture<'a> {
+    pub fn new(
+        ctx: &AppContext<'a>,
+        src: Window,
+        src_depth: u8,
+        filter: ScaleFilter,
+        character_name: &str,
+    ) -> Result<Self> {
+        let gc = ctx
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for screenshot graphics context")?;
+        ctx.conn
+            .create_gc(gc, ctx.screen.root, &CreateGCAux::new())
+            .context(format!(
+                "Failed to create screenshot graphics context for '{}'",
+                character_name
+            ))?;
+        let remote = if crate::x11::display_is_remote() {
+            RemoteScale::new(ctx, src, src_depth, filter, character_name)
+                .inspect_err(|_| {
+                    let _ = ctx.conn.free_gc(gc);
+                })
+                .map(Some)?
+        } else {
+            None
+        };
+        Ok(Self {
+            conn: ctx.conn,
+            src,
+            gc,
+            // Thumbnail windows are created with the root depth
+            depth: ctx.screen.root_depth,
+            remote,
+        })
+    }
+}
+
+impl<'a> RemoteScale<'a> {
+    fn new(
+        ctx: &AppContext<'a>,
+        src: Window,
+        src_depth: u8,
+        filter: ScaleFilter,
+        character_name: &str,
+    ) -> Result<Self> {
+        let scaler = CompositeCapture::new(ctx, src, src_depth, filter, character_name)?;
+        let (depth, format) = match ctx.formats.rgb16 {
+            Some(format) => (16, format),
+            None => (ctx.screen.root_depth, ctx.formats.rgb),
+        };
+        debug!(character = %character_name, depth = depth, "Remote display: scaling screenshots on the server");
+        Ok(Self {
+            scaler,
+            root: ctx.screen.root,
+            depth,
+            format,
+            scratch: RefCell::new(None),
+        })
+    }
+
+    /// Scales `region` of the client to `dimensions` at the scratch depth and draws it into
+    /// `target`, all on the server
+    fn capture(
+        &self,
+        conn: &RustConnection,
+        target: CaptureTarget,
+        src_size: Dimensions,
+        region: CropRegion,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let mut slot = self.scratch.borrow_mut();
+        let scratch = match slot.take() {
+            Some(current) if current.size == dimensions => current,
+            old => {
+                if let Some(old) = old {
+                    old.free(conn);
+                }
+                self.create_scratch(conn, dimensions)?
+            }
+        };
+        let scratch = slot.insert(scratch);
+
+        self.scaler.capture(
+            CaptureTarget {
+                drawable: scratch.pixmap,
+                picture: scratch.picture,
+            },
+            src_size,
+            region,
+            dimensions,
+        )?;
+        conn.render_composite(
+            PictOp::SRC,
+            scratch.picture,
+            0u32,
+            target.picture,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            dimensions.width,
+            dimensions.height,
+        )
+        .context("Failed to copy the scaled screenshot to the thumbnail")?;
+        Ok(())
+    }
+
+    fn create_scratch(&self, conn: &RustConnection, size: Dimensions) -> Result<Scratch> {
+        let pixmap = conn.generate_id()?;
+        conn.create_pixmap(self.depth, pixmap, self.root, size.width, size.height)
+            .context("Failed to create scaled screenshot image")?;
+        let picture = conn.generate_id()?;
+        if let Err(e) =
+            conn.render_create_picture(picture, pixmap, self.format, &CreatePictureAux::new())
+        {
+            let _ = conn.free_pixmap(pixmap);
+            return Err(e).context("Failed to create scaled screenshot picture");
+        }
+        Ok(Scratch {
+            size,
+            pixmap,
+            picture,
+        })
+    }
+}
+
+impl Scratch {
+    fn free(&self, conn: &RustConnection) {
+        if let Err(e) = conn.render_free_picture(self.picture) {
+            error!(picture = self.picture, error = %e, "Failed to free scaled screenshot picture");
+        }
+        if let Err(e) = conn.free_pixmap(self.pixmap) {
+            error!(pixmap = self.pixmap, error = %e, "Failed to free scaled screenshot image");
+        }
+    }
+}
+
+impl CaptureBackend for ScreenshotCapture<'_> {
+    fn name(&self) -> &'static str {
+        "screenshot"
+    }
+
+    fn capture(
+        &self,
+        target: CaptureTarget,
+        src_size: Dimensions,
+        region: CropRegion,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        if let Some(remote) = &self.remote {
+            return remote.capture(self.conn, target, src_size, region, dimensions);
+        }
+        let Some(frame) = capture_frame(self.conn, self.src)? else {
+            return Ok(());
+        };
+        let frame = frame
+            .cropped(
+                region.x as usize,
+                region.y as usize,
+                region.width as usize,
+                region.height as usize,
+            )
+            .stretched(dimensions.width as usize, dimensions.height as usize);
+        self.conn
+            .put_image(
+                ImageFormat::Z_PIXMAP,
+                target.drawable,
+                self.gc,
+                dimensions.width,
+                dimensions.height,
+                0,
+                0,
+                0,
+                self.depth,
+                &frame.to_bgrx(),
+            )
+            .context("Failed to upload screenshot to thumbnail")?;
+        Ok(())
+    }
+}
+
+impl Drop for ScreenshotCapture<'_> {
+    fn drop(&mut self) {
+        if let Some(remote) = &self.remote
+            && let Some(scratch) = remote.scratch.take()
+        {
+            scratch.free(self.conn);
+        }
+        if let Err(e) = self.conn.free_gc(self.gc) {
+            error!(gc = self.gc, error = %e, "Failed to free screenshot graphics context");
+        }
+        let _ = self.conn.flush();
+    }
+}
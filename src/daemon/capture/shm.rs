@@ -0,0 +1,104 @@
+//! MIT-SHM capture: ShmGetImage of the client into shared memory, scaled in the daemon
+//!
+//! Like the screenshot backend the pixels pass through the daemon, but they are read from and
+//! written to segments shared with the X server instead of being copied through the socket. The
+//! cropped region is read on the server, so cropping costs nothing. Local displays only.
+
+use anyhow::{Context, Result, bail};
+use tracing::error;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{ConnectionExt, CreateGCAux, Gcontext, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::{CaptureBackend, CaptureTarget};
+use crate::common::types::{CropRegion, Dimensions};
+use crate::x11::{AppContext, Frame, ShmReader, put_image};
+
+#[derive(Debug)]
+pub struct ShmCapture<'a> {
+    conn: &'a RustConnection,
+    src: Window,
+    gc: Gcontext,
+    depth: u8,
+    reader: ShmReader,
+}
+
+impl<'a> ShmCapture<'a> {
+    pub fn new(
+        ctx: &AppContext<'a>,
+        src: Window,
+        src_depth: u8,
+        character_name: &str,
+    ) -> Result<Self> {
+        // Other depths don't use the 32bpp layout `Frame::from_bgrx` reads
+        if src_depth < 24 {
+            bail!(
+                "MIT-SHM capture needs a 24 or 32-bit client, '{}' is {}-bit",
+                character_name,
+                src_depth
+            );
+        }
+        let gc = ctx
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for MIT-SHM graphics context")?;
+        ctx.conn
+            .create_gc(gc, ctx.screen.root, &CreateGCAux::new())
+            .context(format!(
+                "Failed to create MIT-SHM graphics context for '{}'",
+                character_name
+            ))?;
+        Ok(Self {
+            conn: ctx.conn,
+            src,
+            gc,
+            // Thumbnail windows are created with the root depth
+            depth: ctx.screen.root_depth,
+            reader: ShmReader::default(),
+        })
+    }
+}
+
+impl CaptureBackend for ShmCapture<'_> {
+    fn name(&self) -> &'static str {
+        "shm"
+    }
+
+    fn capture(
+        &self,
+        target: CaptureTarget,
+        _src_size: Dimensions,
+        region: CropRegion,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let data = self.reader.get_image(
+            self.conn,
+            self.src,
+            (region.x as i16, region.y as i16),
+            (region.width, region.height),
+            4,
+        )?;
+        let frame = Frame::from_bgrx(&data, region.width as usize, region.height as usize)
+            .stretched(dimensions.width as usize, dimensions.height as usize);
+        put_image(
+            self.conn,
+            target.drawable,
+            self.gc,
+            dimensions.width,
+            dimensions.height,
+            self.depth,
+            &frame.to_bgrx(),
+        )
+        .context("Failed to upload MIT-SHM capture to thumbnail")
+    }
+}
+
+impl Drop for ShmCapture<'_> {
+    fn drop(&mut self) {
+        self.reader.release(self.conn);
+        if let Err(e) = self.conn.free_gc(self.gc) {
+            error!(gc = self.gc, error = %e, "Failed to free MIT-SHM graphics context");
+        }
+        let _ = self.conn.flush();
+    }
+}
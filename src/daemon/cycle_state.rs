@@ -32,6 +32,13 @@ pub struct CycleState {
 
     /// The name of the cycle group that was last active (used for reset logic)
     last_active_group: Option<String>,
+
+    /// Windows left out of group cycling regardless of their character (test-server clients)
+    excluded_windows: HashSet<Window>,
+
+    /// Last two tracked clients to gain focus, most recent first (for the flip hotkey).
+    /// Focus on untracked windows leaves them alone.
+    focus_history: [Option<Window>; 2],
 }
 
 impl CycleState {
@@ -60,20 +67,41 @@ impl CycleState {
             active_windows: HashMap::new(),
             skipped_characters: HashSet::new(),
             last_active_group: None,
+            excluded_windows: HashSet::new(),
+            focus_history: [None, None],
         }
     }
 
     /// Register a new EVE window (called from CreateNotify)
     pub fn add_window(&mut self, character_name: String, window: Window) {
         debug!(character = %character_name, window = window, "Adding window for character");
+        // A window is tracked once by XID: drop any entry it held under a previous name
+        self.active_windows
+            .retain(|name, &mut w| w != window || *name == character_name);
         self.active_windows.insert(character_name.clone(), window);
 
         // Note: Only characters listed in the profile's `cycle_group` will be included in the cycle order.
         // We track all windows here, but `cycle_forward/backward` logic filters internally based on the config.
     }
 
+    /// Whether the window is tracked under any character name
+    pub fn tracks_window(&self, window: Window) -> bool {
+        self.active_windows.values().any(|&w| w == window)
+    }
+
+    /// Include or exclude a window from group cycling (per-character hotkeys still reach it)
+    pub fn set_window_excluded(&mut self, window: Window, excluded: bool) {
+        if excluded {
+            debug!(window = window, "Excluding window from cycle groups");
+            self.excluded_windows.insert(window);
+        } else {
+            self.excluded_windows.remove(&window);
+        }
+    }
+
     /// Remove window (called from DestroyNotify)
     pub fn remove_window(&mut self, window: Window) {
+        self.excluded_windows.remove(&window);
         // Find and remove from active_windows
         if let Some((name, _)) = self
             .active_windows
@@ -92,6 +120,13 @@ impl CycleState {
                 self.current_window = None;
             }
         }
+
+        if self.focus_history[1] == Some(window) {
+            self.focus_history[1] = None;
+        }
+        if self.focus_history[0] == Some(window) {
+            self.focus_history = [self.focus_history[1], None];
+        }
     }
 
     /// Update character name (called on login/logout)
@@ -189,16 +224,18 @@ impl CycleState {
                     }
 
                     // Check active windows first
-                    if let Some(&window) = self.active_windows.get(character_name) {
+                    if let Some(&window) = self.active_windows.get(character_name)
+                        && !self.excluded_windows.contains(&window)
+                    {
                         debug!(group = group_name, character = %character_name, index = group_state.current_index, "Cycling forward to logged-in character");
                         return Some((window, character_name.clone()));
                     }
 
                     // Check logged-out windows
                     if let Some(map) = logged_out_map
-                        && let Some((&window, _)) = map
-                            .iter()
-                            .find(|(_, last_char)| *last_char == character_name)
+                        && let Some((&window, _)) = map.iter().find(|(window, last_char)| {
+                            *last_char == character_name && !self.excluded_windows.contains(window)
+                        })
                     {
                         debug!(group = group_name, character = %character_name, index = group_state.current_index, window = window, "Cycling forward to logged-out character");
                         return Some((window, character_name.clone()));
@@ -217,6 +254,53 @@ impl CycleState {
         }
     }
 
+    /// Resolve the character a forward cycle would switch to, without advancing the cycle
+    /// Used by the peek hotkey to preview the next client
+    pub fn peek_forward(
+        &self,
+        group_name: &str,
+        logged_out_map: Option<&HashMap<Window, String>>,
+        reset_on_switch: bool,
+    ) -> Option<(Window, String)> {
+        let group_state = self.groups.get(group_name)?;
+        let len = group_state.order.len();
+        if len == 0 {
+            return None;
+        }
+
+        let start_index =
+            if reset_on_switch && self.last_active_group.as_deref() != Some(group_name) {
+                len - 1
+            } else {
+                group_state.current_index
+            };
+
+        (1..=len)
+            .map(|step| &group_state.order[(start_index + step) % len])
+            .filter(|name| !self.skipped_characters.contains(*name))
+            .find_map(|name| {
+                self.active_windows
+                    .get(name)
+                    .copied()
+                    .filter(|window| !self.excluded_windows.contains(window))
+                    .or_else(|| {
+                        logged_out_map.and_then(|map| {
+                            map.iter()
+                                .find(|(window, last_char)| {
+                                    *last_char == name && !self.excluded_windows.contains(window)
+                                })
+                                .map(|(&window, _)| window)
+                        })
+                    })
+                    .map(|window| (window, name.clone()))
+            })
+    }
+
+    /// Name of the cycle group that was used last (if any)
+    pub fn last_active_group(&self) -> Option<&str> {
+        self.last_active_group.as_deref()
+    }
+
     /// Move to previous character in specified group (backward cycle hotkey)
     pub fn cycle_backward(
         &mut self,
@@ -264,15 +348,17 @@ impl CycleState {
                         continue;
                     }
 
-                    if let Some(&window) = self.active_windows.get(character_name) {
+                    if let Some(&window) = self.active_windows.get(character_name)
+                        && !self.excluded_windows.contains(&window)
+                    {
                         debug!(group = group_name, character = %character_name, index = group_state.current_index, "Cycling backward to logged-in character");
                         return Some((window, character_name.clone()));
                     }
 
                     if let Some(map) = logged_out_map
-                        && let Some((&window, _)) = map
-                            .iter()
-                            .find(|(_, last_char)| *last_char == character_name)
+                        && let Some((&window, _)) = map.iter().find(|(window, last_char)| {
+                            *last_char == character_name && !self.excluded_windows.contains(window)
+                        })
                     {
                         debug!(group = group_name, character = %character_name, index = group_state.current_index, window = window, "Cycling backward to logged-out character");
                         return Some((window, character_name.clone()));
@@ -368,6 +454,10 @@ impl CycleState {
         // Always track the current window, even if it's not part of the cycle group
         self.current_window = Some(window);
 
+        if self.focus_history[0] != Some(window) && self.tracks_window(window) {
+            self.focus_history = [Some(window), self.focus_history[0]];
+        }
+
         if let Some((character_name, _)) = self.active_windows.iter().find(|&(_, &w)| w == window) {
             let character_name = character_name.clone();
             // This will try to update current_index if in group, but we return true regardless if found
@@ -491,6 +581,48 @@ impl CycleState {
         None
     }
 
+    /// 1-based position of `character_name` among the running, cyclable characters of its cycle
+    /// group, and how many there are. Prefers the last used group when the character is in
+    /// several; None when it is in no group or not cyclable.
+    pub fn position_in_group(&self, character_name: &str) -> Option<(usize, usize)> {
+        let contains = |group: &GroupState| group.order.iter().any(|name| name == character_name);
+        let group = self
+            .last_active_group
+            .as_deref()
+            .and_then(|name| self.groups.get(name))
+            .filter(|group| contains(group))
+            .or_else(|| {
+                let mut names: Vec<&String> = self.groups.keys().collect();
+                names.sort();
+                names
+                    .into_iter()
+                    .map(|name| &self.groups[name])
+                    .find(|group| contains(group))
+            })?;
+
+        let cyclable: Vec<&String> = group
+            .order
+            .iter()
+            .filter(|name| !self.skipped_characters.contains(*name))
+            .filter(|name| {
+                self.active_windows
+                    .get(*name)
+                    .is_some_and(|window| !self.excluded_windows.contains(window))
+            })
+            .collect();
+        let index = cyclable.iter().position(|name| *name == character_name)?;
+        Some((index + 1, cyclable.len()))
+    }
+
+    /// Client focused before the most recent one, for flipping between two characters
+    /// independent of cycle order. Returns (window, character_name).
+    pub fn flip_previous(&self) -> Option<(Window, String)> {
+        let window = self.focus_history[1]?;
+        let (name, _) = self.active_windows.iter().find(|&(_, &w)| w == window)?;
+        debug!(character = %name, window = window, "Flipping to previously focused character");
+        Some((window, name.clone()))
+    }
+
     /// Get the window ID of the currently focused window (if known)
     pub fn get_current_window(&self) -> Option<Window> {
         self.current_window
@@ -602,4 +734,178 @@ mod tests {
             Some((100, "A".to_string()))
         );
     }
+
+    #[test]
+    fn test_peek_forward_does_not_advance() {
+        use crate::config::profile::CycleGroup;
+        let group1 = CycleGroup {
+            name: "G1".to_string(),
+            cycle_list: vec![
+                crate::config::profile::CycleSlot::Eve("A".to_string()),
+                crate::config::profile::CycleSlot::Eve("B".to_string()),
+                crate::config::profile::CycleSlot::Eve("C".to_string()),
+            ],
+            hotkey_forward: None,
+            hotkey_backward: None,
+        };
+        let mut state = CycleState::new(vec![group1]);
+        state.add_window("A".to_string(), 100);
+        state.add_window("B".to_string(), 200);
+        state.add_window("C".to_string(), 300);
+        state.toggle_skip("B");
+
+        // Peeking repeatedly returns the same target, skipping B
+        assert_eq!(
+            state.peek_forward("G1", None, false),
+            Some((300, "C".to_string()))
+        );
+        assert_eq!(
+            state.peek_forward("G1", None, false),
+            Some((300, "C".to_string()))
+        );
+
+        // The real cycle lands on the peeked character
+        assert_eq!(
+            state.cycle_forward("G1", None, false),
+            Some((300, "C".to_string()))
+        );
+        assert_eq!(state.peek_forward("Missing", None, false), None);
+    }
+
+    #[test]
+    fn test_excluded_window_is_skipped() {
+        use crate::config::profile::{CycleGroup, CycleSlot};
+        let group = CycleGroup {
+            name: "G1".to_string(),
+            cycle_list: vec![
+                CycleSlot::Eve("A".to_string()),
+                CycleSlot::Eve("Sisi".to_string()),
+                CycleSlot::Eve("C".to_string()),
+            ],
+            hotkey_forward: None,
+            hotkey_backward: None,
+        };
+        let mut state = CycleState::new(vec![group]);
+        state.add_window("A".to_string(), 100);
+        state.add_window("Sisi".to_string(), 200);
+        state.add_window("C".to_string(), 300);
+        state.set_window_excluded(200, true);
+
+        assert_eq!(
+            state.peek_forward("G1", None, false),
+            Some((300, "C".to_string()))
+        );
+        assert_eq!(
+            state.cycle_forward("G1", None, false),
+            Some((300, "C".to_string()))
+        );
+        assert_eq!(
+            state.cycle_backward("G1", None, false),
+            Some((100, "A".to_string()))
+        );
+
+        state.set_window_excluded(200, false);
+        assert_eq!(
+            state.cycle_forward("G1", None, false),
+            Some((200, "Sisi".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_add_window_tracks_by_xid() {
+        let mut state = CycleState::new(Vec::new());
+        state.add_window("EVE".to_string(), 100);
+        state.add_window("A".to_string(), 100);
+        state.add_window("B".to_string(), 200);
+
+        assert!(state.tracks_window(100));
+        let mut windows: Vec<_> = state.get_active_windows().values().copied().collect();
+        windows.sort();
+        assert_eq!(windows, vec![100, 200]);
+    }
+
+    #[test]
+    fn test_remove_window_drops_it_from_cycling() {
+        use crate::config::profile::{CycleGroup, CycleSlot};
+        let group = CycleGroup {
+            name: "G1".to_string(),
+            cycle_list: vec![
+                CycleSlot::Eve("A".to_string()),
+                CycleSlot::Eve("B".to_string()),
+                CycleSlot::Eve("C".to_string()),
+            ],
+            hotkey_forward: None,
+            hotkey_backward: None,
+        };
+        let mut state = CycleState::new(vec![group]);
+        state.add_window("A".to_string(), 100);
+        state.add_window("B".to_string(), 200);
+        state.add_window("C".to_string(), 300);
+        assert!(state.set_current_by_window(200));
+
+        state.remove_window(200);
+        assert!(!state.tracks_window(200));
+        assert_eq!(state.current_window, None);
+        for _ in 0..3 {
+            let (window, _) = state.cycle_forward("G1", None, false).unwrap();
+            assert_ne!(window, 200);
+        }
+    }
+
+    #[test]
+    fn test_position_in_group() {
+        use crate::config::profile::{CycleGroup, CycleSlot};
+        let group = |name: &str, members: &[&str]| CycleGroup {
+            name: name.to_string(),
+            cycle_list: members
+                .iter()
+                .map(|m| CycleSlot::Eve(m.to_string()))
+                .collect(),
+            hotkey_forward: None,
+            hotkey_backward: None,
+        };
+        let mut state = CycleState::new(vec![
+            group("Main", &["A", "B", "C", "D"]),
+            group("Scouts", &["D", "E"]),
+        ]);
+        for (name, window) in [("A", 100), ("B", 200), ("C", 300), ("D", 400), ("E", 500)] {
+            state.add_window(name.to_string(), window);
+        }
+
+        assert_eq!(state.position_in_group("C"), Some((3, 4)));
+        // Skipped characters are not counted
+        state.toggle_skip("B");
+        assert_eq!(state.position_in_group("C"), Some((2, 3)));
+        assert_eq!(state.position_in_group("B"), None);
+        assert_eq!(state.position_in_group("Missing"), None);
+
+        // D is in both groups: the one cycled last wins
+        assert_eq!(state.position_in_group("D"), Some((3, 3)));
+        state.cycle_forward("Scouts", None, false);
+        assert_eq!(state.position_in_group("D"), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_flip_previous_follows_focus_history() {
+        let mut state = CycleState::new(Vec::new());
+        state.add_window("A".to_string(), 100);
+        state.add_window("B".to_string(), 200);
+        state.add_window("C".to_string(), 300);
+        assert_eq!(state.flip_previous(), None);
+
+        state.set_current_by_window(100);
+        state.set_current_by_window(200);
+        // Repeated focus and untracked windows don't count
+        state.set_current_by_window(200);
+        state.set_current_by_window(999);
+        assert_eq!(state.flip_previous(), Some((100, "A".to_string())));
+
+        state.set_current_by_window(100);
+        assert_eq!(state.flip_previous(), Some((200, "B".to_string())));
+
+        state.remove_window(200);
+        assert_eq!(state.flip_previous(), None);
+        state.set_current_by_window(300);
+        assert_eq!(state.flip_previous(), Some((100, "A".to_string())));
+    }
 }
@@ -4,6 +4,8 @@
 
 use anyhow::Result;
 use std::collections::HashMap;
+use std::time::Instant;
+use tracing::error;
 use x11rb::protocol::Event::{
     self, ConfigureNotify, CreateNotify, DamageNotify, DestroyNotify, PropertyNotify,
 };
@@ -14,11 +16,11 @@ use super::session_state::SessionState;
 use super::thumbnail::Thumbnail;
 use crate::config::DaemonConfig;
 
-use crate::common::ipc::DaemonMessage;
+use super::status_sender::StatusSender;
 use crate::x11::AppContext;
-use ipc_channel::ipc::IpcSender;
 
 use super::handlers;
+use super::roundtrip_audit;
 
 /// Context bundle for event handlers to reduce argument count
 pub struct EventContext<'a, 'b> {
@@ -27,16 +29,18 @@ pub struct EventContext<'a, 'b> {
     pub eve_clients: &'b mut HashMap<Window, Thumbnail<'a>>,
     pub session_state: &'b mut SessionState,
     pub cycle_state: &'b mut CycleState,
-    pub status_tx: &'b IpcSender<DaemonMessage>,
+    pub status_tx: &'b StatusSender,
     pub font_renderer: &'b crate::daemon::font::FontRenderer,
     pub display_config: &'b crate::config::DisplayConfig,
 }
 
 pub fn handle_event(ctx: &mut EventContext, event: Event) -> Result<()> {
+    let _audit = roundtrip_audit::enabled().then(|| roundtrip_audit::handling(event_name(&event)));
     match event {
         DamageNotify(event) => handlers::window::handle_damage_notify(ctx, event),
         CreateNotify(event) => handlers::window::handle_create_notify(ctx, event),
         Event::MapNotify(event) => handlers::window::handle_map_notify(ctx, event),
+        Event::UnmapNotify(event) => handlers::window::handle_unmap_notify(ctx, event),
         DestroyNotify(event) => handlers::window::handle_destroy_notify(ctx, event),
         ConfigureNotify(event) => handlers::window::handle_configure_notify(ctx, event),
         Event::FocusIn(event) => handlers::state::handle_focus_in(ctx, event),
@@ -44,9 +48,18 @@ pub fn handle_event(ctx: &mut EventContext, event: Event) -> Result<()> {
         Event::ButtonPress(event) => handlers::input::handle_button_press(ctx, event),
         Event::ButtonRelease(event) => handlers::input::handle_button_release(ctx, event),
         Event::MotionNotify(event) => handlers::input::handle_motion_notify(ctx, event),
+        Event::EnterNotify(event) => handlers::input::handle_hover_change(ctx, event.event, true),
+        Event::LeaveNotify(event) => handlers::input::handle_hover_change(ctx, event.event, false),
+        Event::VisibilityNotify(event) => handlers::window::handle_visibility_notify(ctx, event),
         PropertyNotify(event) => {
-            if event.atom == ctx.app_ctx.atoms.wm_name || event.atom == ctx.app_ctx.atoms.wm_class {
+            if event.atom == ctx.app_ctx.atoms.wm_name
+                || event.atom == ctx.app_ctx.atoms.net_wm_name
+            {
                 handlers::window::handle_identity_update(ctx, event.window)
+            } else if event.atom == ctx.app_ctx.atoms.wm_class {
+                handlers::window::handle_class_change(ctx, event.window)
+            } else if event.atom == ctx.app_ctx.atoms.epm_ignore {
+                handlers::window::handle_ignore_change(ctx, event.window)
             } else if event.atom == ctx.app_ctx.atoms.net_wm_state {
                 handlers::state::handle_net_wm_state(ctx, event.window, event.atom)
             } else {
@@ -59,6 +72,80 @@ pub fn handle_event(ctx: &mut EventContext, event: Event) -> Result<()> {
             }
             Ok(())
         }
+        Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_) => {
+            handlers::window::handle_screen_change(ctx)
+        }
+        Event::PresentIdleNotify(event) => {
+            match ctx
+                .eve_clients
+                .values()
+                .find(|t| t.window() == event.window)
+            {
+                Some(thumbnail) => thumbnail.present_idle(event.pixmap),
+                None => Ok(()),
+            }
+        }
         _ => Ok(()),
     }
 }
+
+/// Name of an event type for the round-trip audit
+fn event_name(event: &Event) -> &'static str {
+    match event {
+        DamageNotify(_) => "DamageNotify",
+        CreateNotify(_) => "CreateNotify",
+        Event::MapNotify(_) => "MapNotify",
+        Event::UnmapNotify(_) => "UnmapNotify",
+        DestroyNotify(_) => "DestroyNotify",
+        ConfigureNotify(_) => "ConfigureNotify",
+        Event::FocusIn(_) => "FocusIn",
+        Event::FocusOut(_) => "FocusOut",
+        Event::ButtonPress(_) => "ButtonPress",
+        Event::ButtonRelease(_) => "ButtonRelease",
+        Event::MotionNotify(_) => "MotionNotify",
+        Event::EnterNotify(_) => "EnterNotify",
+        Event::LeaveNotify(_) => "LeaveNotify",
+        Event::VisibilityNotify(_) => "VisibilityNotify",
+        PropertyNotify(_) => "PropertyNotify",
+        Event::ReparentNotify(_) => "ReparentNotify",
+        Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_) => "RandrNotify",
+        Event::PresentIdleNotify(_) => "PresentIdleNotify",
+        _ => "unhandled event",
+    }
+}
+
+/// Events still handled while the daemon is paused: window lifecycle and monitor layout only,
+/// so clients that start or close in the meantime are tracked correctly on resume
+pub fn handled_while_paused(event: &Event) -> bool {
+    matches!(
+        event,
+        CreateNotify(_)
+            | Event::MapNotify(_)
+            | Event::UnmapNotify(_)
+            | DestroyNotify(_)
+            | PropertyNotify(_)
+            | Event::ReparentNotify(_)
+            | Event::RandrScreenChangeNotify(_)
+            | Event::RandrNotify(_)
+            | Event::PresentIdleNotify(_)
+    )
+}
+
+/// Repaints thumbnails whose coalesced damage is due at `now`. DamageNotify only marks a
+/// thumbnail as damaged, so a burst of damage reports costs one capture per frame tick.
+pub fn repaint_damaged(
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    display_config: &crate::config::DisplayConfig,
+    font_renderer: &crate::daemon::font::FontRenderer,
+    now: Instant,
+) {
+    for thumbnail in eve_clients.values_mut() {
+        if thumbnail
+            .repaint_deadline(display_config.damage_frame)
+            .is_some_and(|due| due <= now)
+            && let Err(e) = thumbnail.update(display_config, font_renderer)
+        {
+            error!(error = %e, character = %thumbnail.character_name, "Failed to repaint damaged thumbnail");
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Font renderers for per-character label overrides
+//!
+//! Characters may override the profile's label font family and size. Each distinct
+//! (font, size) pair is loaded once and shared by every thumbnail using it; the shared
+//! renderer built from the profile is used for everyone else.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use tracing::{debug, warn};
+
+use super::discovery::select_best_default_font;
+use super::rendering::FontRenderer;
+use crate::common::constants::defaults::text::FONT_CACHE_CAPACITY;
+
+type FontKey = (String, u16);
+
+/// Loaded override renderers. Fonts that failed to load are cached as `None` so they are only
+/// reported once.
+static FONT_CACHE: LazyLock<Mutex<HashMap<FontKey, Option<Arc<FontRenderer>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Font and size a character's label should use, or `None` when `base` already matches
+fn override_key(base: &FontRenderer, font: Option<&str>, size: Option<u16>) -> Option<FontKey> {
+    let name = font.unwrap_or(base.font_name());
+    let size = size.unwrap_or(base.size().round() as u16);
+    if base.matches_config(name, size as f32) {
+        None
+    } else {
+        Some((name.to_string(), size))
+    }
+}
+
+/// Profile label font, then the best system font, for previews drawn without an X server.
+/// `None` when no TrueType font is available; such previews go unlabelled.
+pub fn profile_font(name: &str, size: f32) -> Option<FontRenderer> {
+    let configured = (!name.is_empty())
+        .then(|| FontRenderer::from_font_name(name, size).ok())
+        .flatten();
+    configured.or_else(|| {
+        select_best_default_font()
+            .and_then(|(name, path)| FontRenderer::from_path(path, name, size))
+            .inspect_err(|e| warn!(error = %e, "No font available, previews have no labels"))
+            .ok()
+    })
+}
+
+/// Returns the renderer for a label with the given overrides, loading it on first use.
+/// `None` means the shared `base` renderer should be used (no override, or it failed to load).
+pub fn label_renderer(
+    base: &FontRenderer,
+    font: Option<&str>,
+    size: Option<u16>,
+) -> Option<Arc<FontRenderer>> {
+    let key = override_key(base, font, size)?;
+
+    if let Ok(cache) = FONT_CACHE.lock()
+        && let Some(cached) = cache.get(&key)
+    {
+        return cached.clone();
+    }
+
+    let (name, size) = (&key.0, key.1 as f32);
+    let loaded = if name.is_empty() {
+        select_best_default_font()
+            .and_then(|(name, path)| FontRenderer::from_path(path, name, size))
+    } else {
+        FontRenderer::from_font_name(name, size)
+    };
+    let renderer = match loaded {
+        Ok(renderer) => {
+            debug!(font = %name, size = size, "Loaded label font override");
+            Some(Arc::new(renderer))
+        }
+        Err(e) => {
+            warn!(font = %name, size = size, error = ?e, "Failed to load label font override, using profile font");
+            None
+        }
+    };
+
+    if let Ok(mut cache) = FONT_CACHE.lock() {
+        // Overrides change rarely; start over rather than track usage
+        if cache.len() >= FONT_CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(key, renderer.clone());
+    }
+    renderer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> FontRenderer {
+        FontRenderer::X11Fallback {
+            font_id: 0,
+            font_name: "Sans".to_string(),
+            size: 22.0,
+        }
+    }
+
+    #[test]
+    fn test_override_key() {
+        let base = base();
+        assert_eq!(override_key(&base, None, None), None);
+        assert_eq!(override_key(&base, Some("Sans"), Some(22)), None);
+        assert_eq!(
+            override_key(&base, None, Some(30)),
+            Some(("Sans".to_string(), 30))
+        );
+        assert_eq!(
+            override_key(&base, Some("Mono"), None),
+            Some(("Mono".to_string(), 22))
+        );
+    }
+
+    #[test]
+    fn test_no_override_uses_base() {
+        assert!(label_renderer(&base(), Some("Sans"), None).is_none());
+    }
+}
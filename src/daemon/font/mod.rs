@@ -2,9 +2,11 @@
 //!
 //! Refactored into sub-modules for better organization.
 
+pub mod cache;
 pub mod discovery;
 pub mod rendering;
 
 // Re-export common types
+pub use cache::{label_renderer, profile_font};
 pub use discovery::{list_fonts, select_best_default_font};
-pub use rendering::FontRenderer;
+pub use rendering::{FontRenderer, TextDecoration};
@@ -16,6 +16,125 @@ pub struct RenderedText {
     pub height: usize,
     /// Little-endian ARGB (BGRA in memory): Blue, Green, Red, Alpha
     pub data: Vec<u8>,
+    /// Top-left of the text itself within the bitmap; non-zero when an outline or a shadow
+    /// reaches above or left of it. Draw at the label position minus this.
+    pub origin: (usize, usize),
+}
+
+/// Outline or drop shadow drawn behind rendered text. Colors are ARGB like the text color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDecoration {
+    #[default]
+    None,
+    /// Every glyph grown by `width` pixels in all directions
+    Outline { color: u32, width: u16 },
+    /// The glyphs again, `dx`/`dy` pixels away
+    Shadow { color: u32, dx: i16, dy: i16 },
+}
+
+impl RenderedText {
+    fn empty() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            data: Vec::new(),
+            origin: (0, 0),
+        }
+    }
+
+    /// Colors a glyph coverage mask (one byte per pixel) and draws the decoration behind it
+    fn from_coverage(
+        mask: &[u8],
+        width: usize,
+        height: usize,
+        fg_color: u32,
+        decoration: TextDecoration,
+    ) -> Self {
+        // Margins the decoration adds on each side: left, top, right, bottom
+        let (left, top, right, bottom) = match decoration {
+            TextDecoration::None => (0, 0, 0, 0),
+            TextDecoration::Outline { width, .. } => {
+                let w = width as usize;
+                (w, w, w, w)
+            }
+            TextDecoration::Shadow { dx, dy, .. } => (
+                (-dx).max(0) as usize,
+                (-dy).max(0) as usize,
+                dx.max(0) as usize,
+                dy.max(0) as usize,
+            ),
+        };
+        let (out_width, out_height) = (width + left + right, height + top + bottom);
+
+        // Glyph coverage at a bitmap position (zero outside the text)
+        let text_at = |x: isize, y: isize| -> u32 {
+            let (tx, ty) = (x - left as isize, y - top as isize);
+            if tx < 0 || ty < 0 || tx >= width as isize || ty >= height as isize {
+                0
+            } else {
+                mask[ty as usize * width + tx as usize] as u32
+            }
+        };
+        let decoration_at = |x: isize, y: isize| -> u32 {
+            match decoration {
+                TextDecoration::None => 0,
+                TextDecoration::Outline { width, .. } => {
+                    // Round brush: the strongest coverage within `width` pixels
+                    let r = width as isize;
+                    let mut coverage = 0;
+                    for oy in -r..=r {
+                        for ox in -r..=r {
+                            if ox * ox + oy * oy <= r * r + r {
+                                coverage = coverage.max(text_at(x + ox, y + oy));
+                            }
+                        }
+                    }
+                    coverage
+                }
+                TextDecoration::Shadow { dx, dy, .. } => text_at(x - dx as isize, y - dy as isize),
+            }
+        };
+        let decoration_color = match decoration {
+            TextDecoration::None => 0,
+            TextDecoration::Outline { color, .. } | TextDecoration::Shadow { color, .. } => color,
+        };
+
+        // Premultiplied BGRA of a color at a coverage
+        let premultiplied = |color: u32, coverage: u32| -> [u32; 4] {
+            let alpha = ((color >> 24) & 0xFF) * coverage / 255;
+            [
+                (color & 0xFF) * alpha / 255,
+                ((color >> 8) & 0xFF) * alpha / 255,
+                ((color >> 16) & 0xFF) * alpha / 255,
+                alpha,
+            ]
+        };
+
+        let mut data = vec![0u8; out_width * out_height * 4];
+        for y in 0..out_height {
+            for x in 0..out_width {
+                let fg = text_at(x as isize, y as isize);
+                let bg = decoration_at(x as isize, y as isize);
+                if fg == 0 && bg == 0 {
+                    continue;
+                }
+                // Text over decoration
+                let fg = premultiplied(fg_color, fg);
+                let bg = premultiplied(decoration_color, bg);
+                let idx = (y * out_width + x) * 4;
+                for c in 0..4 {
+                    data[idx + c] = (fg[c] + bg[c] * (255 - fg[3]) / 255) as u8;
+                }
+            }
+        }
+
+        Self {
+            width: out_width,
+            height: out_height,
+            data,
+            origin: (left, top),
+        }
+    }
 }
 
 /// Font renderer with TrueType (fontdue) or X11 core font fallback
@@ -160,6 +279,13 @@ impl FontRenderer {
         }
     }
 
+    /// Configured font family (empty for the system default picked by the X11 fallback)
+    pub fn font_name(&self) -> &str {
+        match self {
+            Self::Fontdue { font_name, .. } | Self::X11Fallback { font_name, .. } => font_name,
+        }
+    }
+
     /// Check if this renderer matches the given font configuration
     /// Returns true if font name and size are the same (no rebuild needed)
     pub fn matches_config(&self, font_name: &str, font_size: f32) -> bool {
@@ -177,16 +303,18 @@ impl FontRenderer {
         }
     }
 
-    /// Render text to a BGRA bitmap (X11 optimized)
-    pub fn render_text(&self, text: &str, fg_color: u32) -> Result<RenderedText> {
+    /// Render text to a BGRA bitmap (X11 optimized), with an optional outline or drop shadow
+    /// behind it. The X11 core font fallback renders nothing here.
+    pub fn render_text(
+        &self,
+        text: &str,
+        fg_color: u32,
+        decoration: TextDecoration,
+    ) -> Result<RenderedText> {
         match self {
             Self::Fontdue { font, size, .. } => {
                 if text.is_empty() {
-                    return Ok(RenderedText {
-                        width: 0,
-                        height: 0,
-                        data: Vec::new(),
-                    });
+                    return Ok(RenderedText::empty());
                 }
 
                 let mut glyphs = Vec::new();
@@ -208,21 +336,11 @@ impl FontRenderer {
                 let height = (max_ascent + max_descent) as usize;
 
                 if width == 0 || height == 0 {
-                    return Ok(RenderedText {
-                        width: 0,
-                        height: 0,
-                        data: Vec::new(),
-                    });
+                    return Ok(RenderedText::empty());
                 }
 
-                // Allocate buffer for BGRA data (4 bytes per pixel)
-                let mut data = vec![0u8; width * height * 4];
-
-                // Pre-calculate color components
-                let fg_a = (fg_color >> 24) & 0xFF;
-                let fg_r = (fg_color >> 16) & 0xFF;
-                let fg_g = (fg_color >> 8) & 0xFF;
-                let fg_b = fg_color & 0xFF;
+                // Glyph coverage, one byte per pixel
+                let mut mask = vec![0u8; width * height];
 
                 for (x_offset, metrics, bitmap) in glyphs {
                     let baseline_y = max_ascent - (metrics.height as i32 + metrics.ymin);
@@ -236,39 +354,70 @@ impl FontRenderer {
                                 continue;
                             }
 
-                            let coverage = bitmap[gy * metrics.width + gx] as u32;
-
-                            if coverage > 0 {
-                                // Integer math for performance
-                                // pixel = color * coverage / 255
-                                let alpha = (fg_a * coverage) / 255;
-                                let r = (fg_r * coverage) / 255;
-                                let g = (fg_g * coverage) / 255;
-                                let b = (fg_b * coverage) / 255;
-
-                                let idx = ((py as usize) * width + (px as usize)) * 4;
-
-                                // Write BGRA directly (Little Endian)
-                                data[idx] = b as u8;
-                                data[idx + 1] = g as u8;
-                                data[idx + 2] = r as u8;
-                                data[idx + 3] = alpha as u8;
-                            }
+                            let coverage = bitmap[gy * metrics.width + gx];
+                            let idx = (py as usize) * width + (px as usize);
+                            // Overlapping glyphs (kerning) keep the stronger coverage
+                            mask[idx] = mask[idx].max(coverage);
                         }
                     }
                 }
 
-                Ok(RenderedText {
-                    width,
-                    height,
-                    data,
-                })
+                Ok(RenderedText::from_coverage(
+                    &mask, width, height, fg_color, decoration,
+                ))
             }
-            Self::X11Fallback { .. } => Ok(RenderedText {
-                width: 0,
-                height: 0,
-                data: Vec::new(),
-            }),
+            Self::X11Fallback { .. } => Ok(RenderedText::empty()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x1 bitmap with a single fully covered pixel on the left
+    const MASK: [u8; 2] = [255, 0];
+
+    fn pixel(text: &RenderedText, x: usize, y: usize) -> [u8; 4] {
+        let idx = (y * text.width + x) * 4;
+        text.data[idx..idx + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_plain_text_is_premultiplied() {
+        let text = RenderedText::from_coverage(&MASK, 2, 1, 0xFF_FF_00_00, TextDecoration::None);
+        assert_eq!((text.width, text.height, text.origin), (2, 1, (0, 0)));
+        assert_eq!(pixel(&text, 0, 0), [0, 0, 255, 255]);
+        assert_eq!(pixel(&text, 1, 0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_outline_surrounds_text() {
+        let outline = TextDecoration::Outline {
+            color: 0xFF_00_00_FF,
+            width: 1,
+        };
+        let text = RenderedText::from_coverage(&MASK, 2, 1, 0xFF_FF_FF_FF, outline);
+        assert_eq!((text.width, text.height, text.origin), (4, 3, (1, 1)));
+        // Text on top, outline around it, nothing past the outline
+        assert_eq!(pixel(&text, 1, 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(&text, 0, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&text, 2, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&text, 3, 1), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_shadow_offsets_and_grows_bitmap() {
+        let shadow = TextDecoration::Shadow {
+            color: 0x80_00_00_00,
+            dx: -2,
+            dy: 1,
+        };
+        let text = RenderedText::from_coverage(&MASK, 2, 1, 0xFF_FF_FF_FF, shadow);
+        // Shadow reaches left, so the text moves right by two pixels
+        assert_eq!((text.width, text.height, text.origin), (4, 2, (2, 0)));
+        assert_eq!(pixel(&text, 2, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&text, 0, 1), [0, 0, 0, 128]);
+        assert_eq!(pixel(&text, 2, 1), [0, 0, 0, 0]);
+    }
+}
@@ -4,10 +4,15 @@ use x11rb::connection::Connection;
 use x11rb::protocol::xproto::*;
 
 use super::super::dispatcher::EventContext;
+use super::super::hover_zoom;
+use super::super::osd::OsdRequest;
+use super::super::overview;
 use super::super::snapping::{self, Rect};
+use super::super::stacking;
 use super::super::thumbnail::Thumbnail;
+use super::super::window_detection::record_source_geometry;
 use crate::common::constants::mouse;
-use crate::common::types::Position;
+use crate::common::types::{Dimensions, Position};
 
 /// Handle ButtonPress events - start dragging or set current character
 #[tracing::instrument(skip(ctx), fields(window = event.event))]
@@ -20,34 +25,35 @@ pub fn handle_button_press(ctx: &mut EventContext, event: ButtonPressEvent) -> R
     );
 
     // First, find which window was clicked (if any)
-    let clicked_window = ctx
-        .eve_clients
-        .iter()
-        .find(|(_, thumb)| thumb.is_hovered(event.root_x, event.root_y) && thumb.is_visible())
-        .map(|(win, _)| *win);
+    let clicked_window = zoomed_at(ctx, event.root_x, event.root_y).or_else(|| {
+        ctx.eve_clients
+            .iter()
+            .find(|(_, thumb)| thumb.is_hovered(event.root_x, event.root_y) && thumb.is_visible())
+            .map(|(win, _)| *win)
+    });
 
     let Some(clicked_window) = clicked_window else {
         return Ok(()); // No thumbnail was clicked
     };
 
+    if matches!(
+        event.detail,
+        mouse::BUTTON_SCROLL_UP | mouse::BUTTON_SCROLL_DOWN
+    ) {
+        return scroll_resize(ctx, clicked_window, event.detail == mouse::BUTTON_SCROLL_UP);
+    }
+
+    // Drags start from (and save) the thumbnail's own geometry, not the zoomed one
+    if event.detail == mouse::BUTTON_RIGHT {
+        end_hover_zoom(ctx);
+    }
+
     // For right-click drags, collect snap targets BEFORE getting mutable reference
     let snap_targets = if event.detail == mouse::BUTTON_RIGHT {
         ctx.eve_clients
             .iter()
             .filter(|(win, t)| **win != clicked_window && t.is_visible())
-            .filter_map(|(_, t)| {
-                ctx.app_ctx
-                    .conn
-                    .get_geometry(t.window())
-                    .ok()
-                    .and_then(|req| req.reply().ok())
-                    .map(|geom| Rect {
-                        x: geom.x,
-                        y: geom.y,
-                        width: t.dimensions.width,
-                        height: t.dimensions.height,
-                    })
-            })
+            .map(|(_, t)| t.rect())
             .collect()
     } else {
         Vec::new() // No snap targets needed for left-click
@@ -56,23 +62,20 @@ pub fn handle_button_press(ctx: &mut EventContext, event: ButtonPressEvent) -> R
     // Now get mutable reference to the clicked thumbnail
     if let Some(thumbnail) = ctx.eve_clients.get_mut(&clicked_window) {
         debug!(window = thumbnail.window(), character = %thumbnail.character_name, "ButtonPress on thumbnail");
-        let geom = ctx
-            .app_ctx
-            .conn
-            .get_geometry(thumbnail.window())
-            .context("Failed to send geometry query on button press")?
-            .reply()
-            .context(format!(
-                "Failed to get geometry on button press for '{}'",
-                thumbnail.character_name
-            ))?;
         thumbnail.input_state.drag_start = Position::new(event.root_x, event.root_y);
-        thumbnail.input_state.win_start = Position::new(geom.x, geom.y);
+        thumbnail.input_state.win_start = thumbnail.current_position;
 
-        // Only allow dragging with right-click
-        if event.detail == mouse::BUTTON_RIGHT {
+        // Only allow dragging with right-click (overview tiles are temporary and never dragged)
+        if event.detail == mouse::BUTTON_RIGHT && ctx.session_state.overview.is_none() {
             // Store the pre-computed snap targets
             thumbnail.input_state.snap_targets = snap_targets;
+            thumbnail.input_state.snap_monitors = ctx
+                .app_ctx
+                .monitors
+                .all()
+                .into_iter()
+                .map(Rect::from)
+                .collect();
             thumbnail.input_state.dragging = true;
             debug!(
                 window = thumbnail.window(),
@@ -80,8 +83,15 @@ pub fn handle_button_press(ctx: &mut EventContext, event: ButtonPressEvent) -> R
                 "Started dragging thumbnail with cached snap targets"
             );
         }
-        // Left-click sets current character for cycling
-        if event.detail == mouse::BUTTON_LEFT {
+        // Left-click sets current character for cycling (passthrough clicks leave it alone)
+        if event.detail == mouse::BUTTON_LEFT
+            && !is_click_passthrough(
+                ctx.daemon_config,
+                ctx.session_state,
+                event.detail,
+                event.state,
+            )
+        {
             ctx.cycle_state.set_current(&thumbnail.character_name);
             debug!(character = %thumbnail.character_name, "Set current character via click");
         }
@@ -89,11 +99,159 @@ pub fn handle_button_press(ctx: &mut EventContext, event: ButtonPressEvent) -> R
     Ok(())
 }
 
-/// Handle ButtonRelease events - focus window and save position after drag
-pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent) -> Result<()> {
+/// Grows or shrinks a thumbnail by one scroll-wheel notch and saves its new size
+fn scroll_resize(ctx: &mut EventContext, window: Window, grow: bool) -> Result<()> {
+    let profile = &ctx.daemon_config.profile;
+    let step = profile.thumbnail_scroll_resize_step;
+    // Overview tiles are temporary and never resized
+    if step == 0 || ctx.session_state.overview.is_some() {
+        return Ok(());
+    }
+    let aspect = profile.thumbnail_scroll_resize_lock_aspect.then(|| {
+        Dimensions::new(
+            profile.thumbnail_default_width,
+            profile.thumbnail_default_height,
+        )
+    });
+
+    // Resize from the thumbnail's own geometry, not the zoomed one
+    end_hover_zoom(ctx);
+    let Some(thumbnail) = ctx.eve_clients.get_mut(&window) else {
+        return Ok(());
+    };
+    if thumbnail.input_state.dragging {
+        return Ok(());
+    }
+    let size = super::super::thumbnail::scroll_resized(thumbnail.dimensions, step, grow, aspect);
+    if size == thumbnail.dimensions {
+        return Ok(());
+    }
+    thumbnail.resize(size.width, size.height)?;
+    debug!(
+        window = thumbnail.window(),
+        width = size.width,
+        height = size.height,
+        "Resized thumbnail by scroll wheel"
+    );
+    save_thumbnail_geometry(ctx.daemon_config, ctx.status_tx, thumbnail);
+    Ok(())
+}
+
+/// Stores a thumbnail's position and size in the runtime config and reports it to the Manager,
+/// which saves it
+fn save_thumbnail_geometry(
+    daemon_config: &mut crate::config::DaemonConfig,
+    status_tx: &super::super::status_sender::StatusSender,
+    thumbnail: &Thumbnail,
+) {
     use crate::common::ipc::DaemonMessage;
-    use crate::x11::minimize_window;
 
+    if thumbnail.character_name.is_empty() {
+        return;
+    }
+    let position = thumbnail.current_position;
+    let settings = crate::common::types::CharacterSettings::new(
+        position.x,
+        position.y,
+        thumbnail.dimensions.width,
+        thumbnail.dimensions.height,
+    );
+
+    if let Some(msg) = record_source_geometry(daemon_config, thumbnail, position.x, position.y) {
+        status_tx.send(msg);
+    }
+
+    let is_custom_source = thumbnail.is_custom;
+    if is_custom_source {
+        daemon_config
+            .custom_source_thumbnails
+            .insert(thumbnail.character_name.clone(), settings);
+    } else {
+        daemon_config
+            .character_thumbnails
+            .insert(thumbnail.character_name.clone(), settings);
+    }
+
+    status_tx.send(DaemonMessage::PositionChanged {
+        name: thumbnail.character_name.clone(),
+        x: position.x,
+        y: position.y,
+        width: thumbnail.dimensions.width,
+        height: thumbnail.dimensions.height,
+        is_custom: is_custom_source,
+    });
+}
+
+/// The hover-zoomed thumbnail if it is under the pointer; it covers any neighbours it overlaps
+fn zoomed_at(ctx: &EventContext, root_x: i16, root_y: i16) -> Option<Window> {
+    let (key, _) = ctx.session_state.hover_zoom.as_ref()?;
+    ctx.eve_clients
+        .get(key)
+        .filter(|thumb| thumb.is_hovered(root_x, root_y))
+        .map(|_| *key)
+}
+
+/// Ctrl+left-click is forwarded into the source window when the profile opts in
+fn is_click_passthrough(
+    daemon_config: &crate::config::DaemonConfig,
+    session_state: &crate::daemon::session_state::SessionState,
+    button: u8,
+    state: KeyButMask,
+) -> bool {
+    daemon_config.profile.thumbnail_click_passthrough
+        && button == mouse::BUTTON_LEFT
+        && state.contains(KeyButMask::CONTROL)
+        && session_state.overview.is_none()
+}
+
+/// Replays a thumbnail click at the matching point of the source window, leaving focus as is
+fn forward_click(
+    ctx: &mut EventContext,
+    clicked_key: Window,
+    event: &ButtonReleaseEvent,
+) -> Result<()> {
+    let Some(thumbnail) = ctx.eve_clients.get(&clicked_key) else {
+        return Ok(());
+    };
+    let src_geom = ctx
+        .app_ctx
+        .conn
+        .get_geometry(thumbnail.src())
+        .context("Failed to send geometry query for click passthrough")?
+        .reply()
+        .context(format!(
+            "Failed to get source geometry for click passthrough on '{}'",
+            thumbnail.character_name
+        ))?;
+    let crop = ctx
+        .display_config
+        .character_settings
+        .get(&thumbnail.character_name)
+        .and_then(|s| s.crop);
+    let point = thumbnail.source_point(
+        event.root_x,
+        event.root_y,
+        Dimensions::new(src_geom.width, src_geom.height),
+        crop,
+    );
+    debug!(character = %thumbnail.character_name, x = point.x, y = point.y, "Passing click through to source window");
+    crate::x11::send_click(
+        ctx.app_ctx.conn,
+        ctx.app_ctx.screen,
+        thumbnail.src(),
+        point.x,
+        point.y,
+        event.detail,
+        event.time,
+    )
+    .context(format!(
+        "Failed to forward click to '{}'",
+        thumbnail.character_name
+    ))
+}
+
+/// Handle ButtonRelease events - focus window and save position after drag
+pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent) -> Result<()> {
     debug!(
         x = event.root_x,
         y = event.root_y,
@@ -102,22 +260,33 @@ pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent)
     );
 
     // First pass: identify the hovered thumbnail by the EVE window key
-    let clicked_key = ctx.eve_clients
-        .iter()
-        .find(|(_, thumb)| {
-            let hovered = thumb.is_hovered(event.root_x, event.root_y);
-            if hovered {
-                debug!(window = thumb.window(), character = %thumb.character_name, "Found hovered thumbnail");
-            }
-            hovered
-        })
-        .map(|(eve_window, _)| *eve_window);
+    let clicked_key = zoomed_at(ctx, event.root_x, event.root_y).or_else(|| {
+        ctx.eve_clients
+            .iter()
+            .find(|(_, thumb)| {
+                let hovered = thumb.is_hovered(event.root_x, event.root_y);
+                if hovered {
+                    debug!(window = thumb.window(), character = %thumb.character_name, "Found hovered thumbnail");
+                }
+                hovered
+            })
+            .map(|(eve_window, _)| *eve_window)
+    });
 
     let Some(clicked_key) = clicked_key else {
         debug!("No thumbnail hovered at release position");
         return Ok(());
     };
 
+    if is_click_passthrough(
+        ctx.daemon_config,
+        ctx.session_state,
+        event.detail,
+        event.state,
+    ) {
+        return forward_click(ctx, clicked_key, &event);
+    }
+
     let mut clicked_src: Option<Window> = None;
     let is_left_click = event.detail == mouse::BUTTON_LEFT;
 
@@ -136,70 +305,30 @@ pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent)
 
             // Update cycle state and borders immediately to prevent flash
             ctx.cycle_state.set_current(&character_name);
+            ctx.session_state.osd_request = Some(OsdRequest::Switch(thumbnail.src()));
         }
 
         // Save position after drag ends (right-click release)
         if thumbnail.input_state.dragging {
-            let geom = ctx
-                .app_ctx
-                .conn
-                .get_geometry(thumbnail.window())
-                .context("Failed to send geometry query after drag")?
-                .reply()
-                .context(format!(
-                    "Failed to get geometry after drag for '{}'",
-                    thumbnail.character_name
-                ))?;
+            let position = thumbnail.current_position;
 
             ctx.session_state
-                .update_window_position(thumbnail.window(), geom.x, geom.y);
-
-            if !thumbnail.character_name.is_empty() {
-                let settings = crate::common::types::CharacterSettings::new(
-                    geom.x,
-                    geom.y,
-                    thumbnail.dimensions.width,
-                    thumbnail.dimensions.height,
-                );
-
-                // Check if this is a Custom Source
-                let is_custom_source = ctx
-                    .daemon_config
-                    .profile
-                    .custom_windows
-                    .iter()
-                    .any(|rule| rule.alias == thumbnail.character_name);
-
-                if is_custom_source {
-                    ctx.daemon_config
-                        .custom_source_thumbnails
-                        .insert(thumbnail.character_name.clone(), settings);
-                } else {
-                    ctx.daemon_config
-                        .character_thumbnails
-                        .insert(thumbnail.character_name.clone(), settings);
-                }
-
-                let _ = ctx.status_tx.send(DaemonMessage::PositionChanged {
-                    name: thumbnail.character_name.clone(),
-                    x: geom.x,
-                    y: geom.y,
-                    width: thumbnail.dimensions.width,
-                    height: thumbnail.dimensions.height,
-                    is_custom: is_custom_source,
-                });
-            }
+                .update_window_position(thumbnail.window(), position.x, position.y);
+            save_thumbnail_geometry(ctx.daemon_config, ctx.status_tx, thumbnail);
 
             debug!(
                 window = thumbnail.window(),
-                x = geom.x,
-                y = geom.y,
+                x = position.x,
+                y = position.y,
                 "Sent PositionChanged IPC message after drag"
             );
         }
 
         thumbnail.input_state.dragging = false;
+        // Placed by hand: wherever it was displaced from no longer matters
+        thumbnail.displaced_from = None;
         thumbnail.input_state.snap_targets.clear();
+        thumbnail.input_state.snap_monitors.clear();
     }
 
     // After dropping the thumbnail borrow, update borders for left-clicks
@@ -242,6 +371,15 @@ pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent)
             }
         }
 
+        // Clicking a tile in the overview also dismisses it
+        overview::exit(
+            ctx.eve_clients,
+            ctx.session_state,
+            ctx.cycle_state,
+            ctx.display_config,
+            ctx.font_renderer,
+        );
+
         // Flush X11 connection to ensure border updates are rendered immediately
         let _ = ctx.app_ctx.conn.flush();
     }
@@ -268,7 +406,7 @@ pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent)
             .map(|(w, _)| *w)
             .collect();
 
-        for window in windows_to_minimize {
+        for &window in &windows_to_minimize {
             // Clear border BEFORE minimizing to prevent stale active borders
             if let Some(thumb) = ctx.eve_clients.get_mut(&window) {
                 // Don't change state here - let the minimize handler set it to Minimized
@@ -282,16 +420,14 @@ pub fn handle_button_release(ctx: &mut EventContext, event: ButtonReleaseEvent)
                     warn!(window = window, error = %e, "Failed to clear border before minimize");
                 }
             }
-
-            if let Err(e) = minimize_window(
-                ctx.app_ctx.conn,
-                ctx.app_ctx.screen,
-                ctx.app_ctx.atoms,
-                window,
-            ) {
-                debug!(error = ?e, window = window, "Failed to minimize window");
-            }
         }
+        // Sent in paced batches and verified by the daemon's minimize timer
+        ctx.session_state.minimize_queue.schedule_switch(
+            clicked_src,
+            windows_to_minimize,
+            ctx.daemon_config.profile.client_minimize_alt_restore,
+            std::time::Instant::now(),
+        );
     }
 
     Ok(())
@@ -322,11 +458,13 @@ pub fn handle_motion_notify(ctx: &mut EventContext, event: MotionNotifyEvent) ->
         .get_mut(&dragging_window)
         .context("Dragging window not found in clients map")?;
     let snap_targets = thumbnail.input_state.snap_targets.clone();
+    let snap_monitors = thumbnail.input_state.snap_monitors.clone();
 
     handle_drag_motion(
         thumbnail,
         &event,
         &snap_targets,
+        &snap_monitors,
         thumbnail.dimensions.width,
         thumbnail.dimensions.height,
         snap_threshold,
@@ -339,11 +477,148 @@ pub fn handle_motion_notify(ctx: &mut EventContext, event: MotionNotifyEvent) ->
     Ok(())
 }
 
+/// Handle EnterNotify/LeaveNotify on thumbnail windows - toggle the notes line and hover zoom
+#[tracing::instrument(skip(ctx), fields(window = window))]
+pub fn handle_hover_change(ctx: &mut EventContext, window: Window, hovered: bool) -> Result<()> {
+    let Some((&key, thumbnail)) = ctx
+        .eve_clients
+        .iter_mut()
+        .find(|(_, t)| t.window() == window)
+    else {
+        return Ok(());
+    };
+
+    debug!(character = %thumbnail.character_name, hovered = hovered, "Thumbnail hover changed");
+    let skipped = ctx.cycle_state.is_skipped(&thumbnail.character_name);
+    thumbnail
+        .set_hovered(hovered, ctx.display_config, skipped, ctx.font_renderer)
+        .context(format!(
+            "Failed to update hover state for '{}'",
+            thumbnail.character_name
+        ))?;
+
+    if hovered {
+        ctx.session_state.hovered = Some(key);
+    } else if ctx.session_state.hovered == Some(key) {
+        ctx.session_state.hovered = None;
+    }
+
+    if hovered {
+        // With a dwell the zoom waits until the pointer has rested on the thumbnail
+        let delay = ctx.daemon_config.profile.thumbnail_hover_zoom_delay_ms;
+        if delay > 0 {
+            ctx.session_state.hover_zoom_pending = Some((
+                key,
+                std::time::Instant::now() + std::time::Duration::from_millis(delay.into()),
+            ));
+            return Ok(());
+        }
+        start_hover_zoom(ctx, key)
+    } else {
+        if ctx
+            .session_state
+            .hover_zoom_pending
+            .is_some_and(|(pending, _)| pending == key)
+        {
+            ctx.session_state.hover_zoom_pending = None;
+        }
+        if ctx
+            .session_state
+            .hover_zoom
+            .as_ref()
+            .is_some_and(|(zoomed, _)| *zoomed == key)
+        {
+            end_hover_zoom(ctx);
+        }
+        Ok(())
+    }
+}
+
+/// Zooms the thumbnail the pointer has rested on for the hover dwell
+pub fn start_pending_hover_zoom(ctx: &mut EventContext) -> Result<()> {
+    let Some((key, _)) = ctx.session_state.hover_zoom_pending.take() else {
+        return Ok(());
+    };
+    start_hover_zoom(ctx, key)
+}
+
+/// Enlarges the thumbnail of `key` if the profile has hover zoom on and nothing else is
+/// rearranging thumbnails. Off on a remote X connection, like the other animations.
+fn start_hover_zoom(ctx: &mut EventContext, key: Window) -> Result<()> {
+    let scale = ctx.daemon_config.profile.thumbnail_hover_zoom;
+    if scale <= 1.0
+        || crate::x11::display_is_remote()
+        || ctx.session_state.overview.is_some()
+        || ctx.session_state.peek.is_some()
+        || ctx.eve_clients.values().any(|t| t.input_state.dragging)
+    {
+        return Ok(());
+    }
+    // A leave we never saw (e.g. thumbnail hidden under the pointer)
+    end_hover_zoom(ctx);
+
+    let Some(thumbnail) = ctx.eve_clients.get_mut(&key) else {
+        return Ok(());
+    };
+    if !thumbnail.is_visible() {
+        return Ok(());
+    }
+    let center = Position::new(
+        thumbnail.current_position.x + (thumbnail.dimensions.width / 2) as i16,
+        thumbnail.current_position.y + (thumbnail.dimensions.height / 2) as i16,
+    );
+    let monitor = ctx
+        .app_ctx
+        .monitors
+        .at(center.x, center.y)
+        .map(Rect::from)
+        .unwrap_or(Rect {
+            x: 0,
+            y: 0,
+            width: ctx.app_ctx.screen.width_in_pixels,
+            height: ctx.app_ctx.screen.height_in_pixels,
+        });
+
+    let skipped = ctx.cycle_state.is_skipped(&thumbnail.character_name);
+    let saved = hover_zoom::start(
+        thumbnail,
+        scale,
+        ctx.daemon_config.profile.thumbnail_hover_zoom_anchor,
+        monitor,
+        ctx.display_config,
+        skipped,
+        ctx.font_renderer,
+    )
+    .context(format!(
+        "Failed to zoom hovered thumbnail for '{}'",
+        thumbnail.character_name
+    ))?;
+    debug!(character = %thumbnail.character_name, scale = scale, "Hover zoom started");
+    ctx.session_state.hover_zoom = Some((key, saved));
+    Ok(())
+}
+
+/// Restores the thumbnail enlarged by hovering (if any) and its stacking
+pub fn end_hover_zoom(ctx: &mut EventContext) {
+    if ctx.session_state.hover_zoom.is_none() {
+        return;
+    }
+    hover_zoom::end(
+        ctx.eve_clients,
+        ctx.session_state,
+        ctx.cycle_state,
+        ctx.display_config,
+        ctx.font_renderer,
+    );
+    stacking::restack(ctx.eve_clients, ctx.display_config);
+}
+
 /// Handle drag motion for a single thumbnail with snapping
 fn handle_drag_motion(
     thumbnail: &mut Thumbnail,
     event: &MotionNotifyEvent,
     snap_targets: &[Rect],
+    snap_monitors: &[Rect],
     _config_width: u16,
     _config_height: u16,
     snap_threshold: u16,
@@ -369,7 +644,7 @@ fn handle_drag_motion(
     let Position {
         x: final_x,
         y: final_y,
-    } = snapping::find_snap_position(dragged_rect, snap_targets, snap_threshold)
+    } = snapping::find_snap_position(dragged_rect, snap_targets, snap_monitors, snap_threshold)
         .unwrap_or_else(|| Position::new(new_x, new_y));
 
     trace!(
@@ -1,7 +1,9 @@
 use super::super::dispatcher::EventContext;
+use crate::common::ipc::DaemonMessage;
 use crate::common::types::ThumbnailState;
+use crate::config::PointerWarpMode;
 use anyhow::{Context, Result};
-use tracing::debug;
+use tracing::{debug, info};
 use x11rb::protocol::xproto::*;
 
 /// Handle FocusIn events - update focused state and visibility
@@ -44,14 +46,27 @@ pub fn handle_focus_in(ctx: &mut EventContext, event: FocusInEvent) -> Result<()
         debug!(window = event.event, "Synced cycle state to focused window");
     }
 
+    request_prefix_profile(ctx, event.event);
+    report_focus(ctx, event.event);
+
+    // A client the user focused again must not be minimized by an earlier switch
+    ctx.session_state.minimize_queue.cancel(event.event);
+
     // Cancel any pending hide operation since we regained focus
     if ctx.session_state.focus_loss_deadline.is_some() {
         ctx.session_state.focus_loss_deadline = None;
         debug!("Cancelled pending focus loss hide");
     }
+    if ctx.session_state.focus_loss_fade.take().is_some() {
+        for thumbnail in ctx.eve_clients.values() {
+            thumbnail.set_opacity(ctx.display_config.opacity)?;
+        }
+        debug!("Cancelled focus loss fade-out");
+    }
 
     if ctx.display_config.hide_when_no_focus && ctx.eve_clients.values().any(|x| !x.is_visible()) {
-        for thumbnail in ctx.eve_clients.values_mut() {
+        let mut revealed = Vec::new();
+        for (window, thumbnail) in ctx.eve_clients.iter_mut() {
             // Respect per-character override: don't reveal force-hidden thumbnails
             let should_render = ctx
                 .display_config
@@ -60,11 +75,16 @@ pub fn handle_focus_in(ctx: &mut EventContext, event: FocusInEvent) -> Result<()
                 .and_then(|s| s.override_render_preview)
                 .unwrap_or(ctx.display_config.enabled);
 
-            if !should_render {
+            if !should_render || thumbnail.is_visible() {
                 continue;
             }
 
             debug!(character = %thumbnail.character_name, "Revealing thumbnail due to focus change");
+            // Fading in: map fully transparent, the main loop raises the opacity
+            if crate::daemon::thumbnail::hide_fade(ctx.display_config).is_some() {
+                thumbnail.set_opacity(0)?;
+                revealed.push(*window);
+            }
             thumbnail.visibility(true).context(format!(
                 "Failed to show thumbnail '{}' on focus",
                 thumbnail.character_name
@@ -76,6 +96,10 @@ pub fn handle_focus_in(ctx: &mut EventContext, event: FocusInEvent) -> Result<()
                     thumbnail.character_name
                 ))?;
         }
+        if !revealed.is_empty() {
+            ctx.session_state.focus_gain_fade = Some((std::time::Instant::now(), revealed));
+        }
+        crate::daemon::stacking::restack(ctx.eve_clients, ctx.display_config);
     }
 
     for (window, thumbnail) in ctx.eve_clients.iter_mut() {
@@ -125,6 +149,60 @@ pub fn handle_focus_in(ctx: &mut EventContext, event: FocusInEvent) -> Result<()
     Ok(())
 }
 
+/// Tell the Manager which character has focus, so it can be focused again after a restart
+fn report_focus(ctx: &mut EventContext, window: Window) {
+    let Some(thumbnail) = ctx.eve_clients.get(&window) else {
+        return;
+    };
+    if thumbnail.is_custom || thumbnail.character_name.is_empty() {
+        return;
+    }
+    if ctx.session_state.reported_focus.as_deref() == Some(thumbnail.character_name.as_str()) {
+        return;
+    }
+    ctx.session_state.reported_focus = Some(thumbnail.character_name.clone());
+    ctx.status_tx.send(DaemonMessage::FocusChanged(
+        thumbnail.character_name.clone(),
+    ));
+}
+
+/// Ask the Manager to switch profiles if the focused client's Wine prefix has a profile rule
+fn request_prefix_profile(ctx: &mut EventContext, window: Window) {
+    let Some(prefix) = ctx
+        .session_state
+        .window_prefix
+        .get(&window)
+        .and_then(|info| info.prefix.as_deref())
+    else {
+        return;
+    };
+    let profile = &ctx.daemon_config.profile;
+    let Some(rule) = profile
+        .prefix_profile_rules
+        .iter()
+        .find(|rule| rule.matches(prefix))
+    else {
+        return;
+    };
+    if rule.profile == profile.profile_name {
+        return;
+    }
+
+    let request = (window, rule.profile.clone());
+    if ctx.session_state.prefix_switch_requested.as_ref() == Some(&request) {
+        return;
+    }
+    info!(
+        window = window,
+        prefix = %prefix,
+        target_profile = %rule.profile,
+        "Requesting profile switch for Wine prefix"
+    );
+    ctx.status_tx
+        .send(DaemonMessage::RequestProfileSwitch(rule.profile.clone()));
+    ctx.session_state.prefix_switch_requested = Some(request);
+}
+
 /// Handle FocusOut events - update focused state and visibility  
 #[tracing::instrument(skip(ctx), fields(window = event.event))]
 pub fn handle_focus_out(ctx: &mut EventContext, event: FocusOutEvent) -> Result<()> {
@@ -135,6 +213,18 @@ pub fn handle_focus_out(ctx: &mut EventContext, event: FocusOutEvent) -> Result<
 
     debug!(window = event.event, "FocusOut received");
 
+    // Keep where the pointer was in the client we're leaving, to put it back on return
+    if ctx.daemon_config.profile.client_pointer_warp == PointerWarpMode::Remembered
+        && ctx.eve_clients.contains_key(&event.event)
+        && let Err(e) = super::super::pointer_warp::remember(
+            ctx.app_ctx.conn,
+            event.event,
+            &mut ctx.session_state.pointer_positions,
+        )
+    {
+        debug!(window = event.event, error = %e, "Failed to remember pointer position");
+    }
+
     if ctx.display_config.hide_when_no_focus {
         let was_active = ctx
             .eve_clients
@@ -146,7 +236,7 @@ pub fn handle_focus_out(ctx: &mut EventContext, event: FocusOutEvent) -> Result<
             // Schedule the hide operation with a short delay (hysteresis) to allow for
             // quick focus cycling without flickering.
             ctx.session_state.focus_loss_deadline =
-                Some(std::time::Instant::now() + std::time::Duration::from_millis(100));
+                Some(std::time::Instant::now() + ctx.daemon_config.focus_loss_delay());
             debug!(
                 window = event.event,
                 "Scheduled delayed thumbnail hide due to focus loss"
@@ -1,11 +1,14 @@
 use anyhow::{Context, Result};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use x11rb::connection::Connection;
 use x11rb::protocol::damage::ConnectionExt as DamageExt;
 use x11rb::protocol::xproto::*;
 
 use super::super::dispatcher::EventContext;
-use crate::common::types::Position;
+use super::super::session_state::SessionState;
+use super::super::snapping::Rect;
+use super::super::status_sender::StatusSender;
+use crate::x11::get_window_class;
 
 /// Handle DamageNotify events - update damaged thumbnail
 pub fn handle_damage_notify(
@@ -21,12 +24,8 @@ pub fn handle_damage_notify(
         .values_mut()
         .find(|thumbnail| thumbnail.damage() == event.damage)
     {
-        thumbnail
-            .update(ctx.display_config, ctx.font_renderer)
-            .context(format!(
-                "Failed to update thumbnail for damage event (damage={})",
-                event.damage
-            ))?;
+        // The main loop repaints once per frame (or screenshot interval) for all damage collected
+        thumbnail.mark_damaged();
         ctx.app_ctx
             .conn
             .damage_subtract(event.damage, 0u32, 0u32)
@@ -34,10 +33,56 @@ pub fn handle_damage_notify(
                 "Failed to subtract damage region (damage={})",
                 event.damage
             ))?;
-        ctx.app_ctx
-            .conn
-            .flush()
-            .context("Failed to flush X11 connection after damage update")?;
+    }
+    Ok(())
+}
+
+/// Handle VisibilityNotify events - hold repaints for fully covered thumbnails.
+///
+/// Only reported without a compositing manager; composited windows always count as unobscured.
+pub fn handle_visibility_notify(
+    ctx: &mut EventContext,
+    event: VisibilityNotifyEvent,
+) -> Result<()> {
+    if let Some(thumbnail) = ctx
+        .eve_clients
+        .values_mut()
+        .find(|thumbnail| thumbnail.window() == event.window)
+    {
+        let obscured = event.state == Visibility::FULLY_OBSCURED;
+        debug!(character = %thumbnail.character_name, obscured, "Thumbnail visibility changed");
+        thumbnail.set_obscured(obscured);
+    }
+    Ok(())
+}
+
+/// Handle RandR layout changes - refresh the cached monitors and bring thumbnails left
+/// off-screen by an unplugged monitor or a resolution change back onto one
+pub fn handle_screen_change(ctx: &mut EventContext) -> Result<()> {
+    ctx.app_ctx.monitors.refresh(ctx.app_ctx.conn);
+    super::input::end_hover_zoom(ctx);
+    // Overview tiles are temporary; their saved geometry is restored as it was
+    if ctx.session_state.overview.is_some() {
+        return Ok(());
+    }
+
+    let monitors: Vec<Rect> = ctx
+        .app_ctx
+        .monitors
+        .all()
+        .into_iter()
+        .map(Rect::from)
+        .collect();
+    for thumbnail in ctx.eve_clients.values_mut() {
+        match thumbnail.fit_to_monitors(&monitors) {
+            Ok(true) => {
+                info!(character = %thumbnail.character_name, position = ?thumbnail.current_position, "Moved thumbnail after monitor layout change");
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(character = %thumbnail.character_name, error = %e, "Failed to move thumbnail after monitor layout change");
+            }
+        }
     }
     Ok(())
 }
@@ -49,7 +94,7 @@ pub fn process_detected_window(
     identity: crate::daemon::window_detection::WindowIdentity,
 ) -> Result<()> {
     use crate::common::ipc::DaemonMessage;
-    use crate::daemon::window_detection::check_and_create_window;
+    use crate::daemon::window_detection::{check_and_create_window, record_source_geometry};
 
     debug!(
         window = window,
@@ -58,8 +103,17 @@ pub fn process_detected_window(
         "Identified window for preview"
     );
     debug!(?identity, "Identity details");
+    ctx.session_state.identity_retries.remove(&window);
+    if let Ok(Some(class)) = get_window_class(ctx.app_ctx.conn, window, ctx.app_ctx.atoms) {
+        ctx.session_state.note_window_class(window, &class);
+    }
 
     ctx.cycle_state.add_window(identity.name.clone(), window);
+    ctx.cycle_state.set_window_excluded(
+        window,
+        ctx.daemon_config.profile.hotkey_cycle_exclude_test_servers
+            && ctx.session_state.window_server(window).is_test_server(),
+    );
 
     match check_and_create_window(
         ctx.app_ctx,
@@ -72,122 +126,124 @@ pub fn process_detected_window(
         Some(identity.clone()),
     ) {
         Ok(Some(mut thumbnail)) => {
-            let geom_result = ctx
-                .app_ctx
-                .conn
-                .get_geometry(thumbnail.window())
-                .map_err(anyhow::Error::from)
-                .and_then(|cookie| cookie.reply().map_err(anyhow::Error::from));
-
-            match geom_result {
-                Ok(geom) => {
-                    if !thumbnail.character_name.is_empty() {
-                        let settings = crate::common::types::CharacterSettings::new(
-                            geom.x,
-                            geom.y,
-                            thumbnail.dimensions.width,
-                            thumbnail.dimensions.height,
-                        );
+            // A thumbnail moved off a disconnected monitor keeps its saved spot
+            let position = thumbnail
+                .displaced_from
+                .unwrap_or(thumbnail.current_position);
+            if !thumbnail.character_name.is_empty() {
+                let settings = crate::common::types::CharacterSettings::new(
+                    position.x,
+                    position.y,
+                    thumbnail.dimensions.width,
+                    thumbnail.dimensions.height,
+                );
 
-                        if identity.is_eve {
-                            // Check if we already have settings for this character.
-                            // If so, update the geometry but PRESERVE the user's overrides (like preview_mode).
-                            // This fixes the issue where unminimizing a client (MapNotify) would reset it to Live mode.
-                            if let Some(existing) = ctx
-                                .daemon_config
-                                .character_thumbnails
-                                .get_mut(&thumbnail.character_name)
-                            {
-                                existing.x = settings.x;
-                                existing.y = settings.y;
-                                existing.dimensions = settings.dimensions;
-                            } else {
-                                ctx.daemon_config
-                                    .character_thumbnails
-                                    .insert(thumbnail.character_name.clone(), settings.clone());
-                            }
-                        } else {
-                            ctx.daemon_config
-                                .custom_source_thumbnails
-                                .insert(thumbnail.character_name.clone(), settings.clone());
-                        }
+                if identity.is_eve {
+                    // Check if we already have settings for this character.
+                    // If so, update the geometry but PRESERVE the user's overrides (like preview_mode).
+                    // This fixes the issue where unminimizing a client (MapNotify) would reset it to Live mode.
+                    if let Some(existing) = ctx
+                        .daemon_config
+                        .character_thumbnails
+                        .get_mut(&thumbnail.character_name)
+                    {
+                        existing.x = settings.x;
+                        existing.y = settings.y;
+                        existing.dimensions = settings.dimensions;
+                    } else {
+                        ctx.daemon_config
+                            .character_thumbnails
+                            .insert(thumbnail.character_name.clone(), settings.clone());
+                    }
+                } else {
+                    ctx.daemon_config
+                        .custom_source_thumbnails
+                        .insert(thumbnail.character_name.clone(), settings.clone());
+                    if let Some(msg) = record_source_geometry(
+                        ctx.daemon_config,
+                        &thumbnail,
+                        settings.x,
+                        settings.y,
+                    ) {
+                        ctx.status_tx.send(msg);
+                    }
+                }
 
-                        let _ = ctx.status_tx.send(DaemonMessage::PositionChanged {
-                            name: thumbnail.character_name.clone(),
-                            x: settings.x,
-                            y: settings.y,
-                            width: settings.dimensions.width,
-                            height: settings.dimensions.height,
-                            is_custom: !identity.is_eve,
-                        });
-
-                        // Only send CharacterDetected if this is a new window (avoid spam from Create+Map)
-                        if !ctx.eve_clients.contains_key(&window) {
-                            let _ = ctx.status_tx.send(DaemonMessage::CharacterDetected {
-                                name: thumbnail.character_name.clone(),
-                                is_custom: !identity.is_eve,
-                            });
-                        }
+                ctx.status_tx.debounce_position(
+                    DaemonMessage::PositionChanged {
+                        name: thumbnail.character_name.clone(),
+                        x: settings.x,
+                        y: settings.y,
+                        width: settings.dimensions.width,
+                        height: settings.dimensions.height,
+                        is_custom: !identity.is_eve,
+                    },
+                    ctx.daemon_config.position_debounce(),
+                );
+
+                // Only send CharacterDetected if this is a new window (avoid spam from Create+Map)
+                if !ctx.eve_clients.contains_key(&window) {
+                    ctx.status_tx.send(DaemonMessage::CharacterDetected {
+                        name: thumbnail.character_name.clone(),
+                        is_custom: !identity.is_eve,
+                    });
+                    send_client_prefix(
+                        ctx.status_tx,
+                        ctx.session_state,
+                        window,
+                        &thumbnail.character_name,
+                    );
+                }
 
-                        // Force initial update for custom sources as they might not emit Damage events immediately
-                        if !identity.is_eve {
-                            // 1. Attempt immediate capture
-                            if let Err(e) = thumbnail.update(ctx.display_config, ctx.font_renderer)
-                            {
-                                tracing::warn!(
-                                    "Failed to perform initial update for custom source {}: {}",
-                                    thumbnail.character_name,
-                                    e
-                                );
-                            }
+                // Force initial update for custom sources as they might not emit Damage events immediately
+                if !identity.is_eve {
+                    // 1. Attempt immediate capture
+                    if let Err(e) = thumbnail.update(ctx.display_config, ctx.font_renderer) {
+                        tracing::warn!(
+                            "Failed to perform initial update for custom source {}: {}",
+                            thumbnail.character_name,
+                            e
+                        );
+                    }
 
-                            // 2. Send synthetic Expose event to force the application to repaint
-                            // This fixes issues where apps wait for focus or interaction to paint their first frame
-                            let src_geom = ctx
-                                .app_ctx
-                                .conn
-                                .get_geometry(window)
-                                .context("Failed to get geometry for custom source expose")?
-                                .reply()
-                                .context("Failed to receive geometry reply")?;
-
-                            let expose = ExposeEvent {
-                                response_type: EXPOSE_EVENT,
-                                sequence: 0,
-                                window,
-                                x: 0,
-                                y: 0,
-                                width: src_geom.width,
-                                height: src_geom.height,
-                                count: 0,
-                            };
-
-                            if let Err(e) = ctx.app_ctx.conn.send_event(
-                                false,
-                                window,
-                                EventMask::EXPOSURE,
-                                expose,
-                            ) {
-                                tracing::warn!(
-                                    "Failed to send Expose event to {}: {}",
-                                    thumbnail.character_name,
-                                    e
-                                );
-                            }
-                            let _ = ctx.app_ctx.conn.flush();
-                        }
+                    // 2. Send synthetic Expose event to force the application to repaint
+                    // This fixes issues where apps wait for focus or interaction to paint their first frame
+                    let src_geom = ctx
+                        .app_ctx
+                        .conn
+                        .get_geometry(window)
+                        .context("Failed to get geometry for custom source expose")?
+                        .reply()
+                        .context("Failed to receive geometry reply")?;
+
+                    let expose = ExposeEvent {
+                        response_type: EXPOSE_EVENT,
+                        sequence: 0,
+                        window,
+                        x: 0,
+                        y: 0,
+                        width: src_geom.width,
+                        height: src_geom.height,
+                        count: 0,
+                    };
+
+                    if let Err(e) =
+                        ctx.app_ctx
+                            .conn
+                            .send_event(false, window, EventMask::EXPOSURE, expose)
+                    {
+                        tracing::warn!(
+                            "Failed to send Expose event to {}: {}",
+                            thumbnail.character_name,
+                            e
+                        );
                     }
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to query geometry for new thumbnail window {}: {}",
-                        thumbnail.window(),
-                        e
-                    );
+                    let _ = ctx.app_ctx.conn.flush();
                 }
             }
 
             ctx.eve_clients.insert(window, thumbnail);
+            crate::daemon::stacking::restack(ctx.eve_clients, ctx.display_config);
 
             // Check if this newly detected/mapped window is actually the focused window
             // This handles cases like unminimizing where MapNotify might race with FocusIn,
@@ -282,7 +338,7 @@ pub fn handle_create_notify(ctx: &mut EventContext, event: CreateNotifyEvent) ->
         ctx.app_ctx,
         event.window,
         ctx.session_state,
-        &ctx.daemon_config.profile.custom_windows,
+        &ctx.daemon_config.profile,
     )
     .context(format!("Failed to identify window {}", event.window))?
     {
@@ -296,22 +352,75 @@ pub fn handle_map_notify(ctx: &mut EventContext, event: MapNotifyEvent) -> Resul
     use crate::daemon::window_detection::identify_window;
 
     debug!(window = event.window, "MapNotify received");
+    if let Some(thumbnail) = ctx.eve_clients.get(&event.window) {
+        thumbnail.source_changed();
+    }
 
     if let Some(identity) = identify_window(
         ctx.app_ctx,
         event.window,
         ctx.session_state,
-        &ctx.daemon_config.profile.custom_windows,
+        &ctx.daemon_config.profile,
     )
     .context(format!("Failed to identify window {}", event.window))?
     {
         process_detected_window(ctx, event.window, identity)?;
+    } else if !event.override_redirect && !ctx.eve_clients.contains_key(&event.window) {
+        // The title may not be set yet; keep checking for a while in case no PropertyNotify follows
+        ctx.session_state
+            .queue_identity_retry(event.window, std::time::Instant::now());
+    }
+    Ok(())
+}
+
+/// Handle UnmapNotify events - the source stops having contents until it is mapped again
+pub fn handle_unmap_notify(ctx: &mut EventContext, event: UnmapNotifyEvent) -> Result<()> {
+    if let Some(thumbnail) = ctx.eve_clients.get(&event.window) {
+        debug!(window = event.window, "Tracked client unmapped");
+        thumbnail.source_changed();
+    }
+    Ok(())
+}
+
+/// Re-check windows that were mapped before they could be identified
+pub fn retry_pending_identities(ctx: &mut EventContext) -> Result<()> {
+    use crate::daemon::window_detection::identify_window;
+
+    let due = ctx.session_state.due_identity_retries(
+        std::time::Instant::now(),
+        ctx.daemon_config.identity_retry_window(),
+    );
+    for window in due {
+        let identity = identify_window(
+            ctx.app_ctx,
+            window,
+            ctx.session_state,
+            &ctx.daemon_config.profile,
+        )
+        .context(format!("Failed to re-check window {}", window));
+        match identity {
+            Ok(Some(identity)) => {
+                info!(window = window, name = %identity.name, "Identified window on retry");
+                process_detected_window(ctx, window, identity)?;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                // Most likely destroyed in the meantime
+                debug!(window = window, error = %e, "Dropping window from identity retries");
+                ctx.session_state.identity_retries.remove(&window);
+            }
+        }
     }
     Ok(())
 }
 
 /// Handle DestroyNotify events - remove destroyed window
 pub fn handle_destroy_notify(ctx: &mut EventContext, event: DestroyNotifyEvent) -> Result<()> {
+    ctx.session_state.identity_retries.remove(&event.window);
+    if let Some(panic) = ctx.session_state.panic.as_mut() {
+        panic.forget_window(event.window);
+    }
+
     let window_to_remove = if ctx.eve_clients.contains_key(&event.window) {
         Some(event.window)
     } else {
@@ -327,9 +436,7 @@ pub fn handle_destroy_notify(ctx: &mut EventContext, event: DestroyNotifyEvent)
             client_window = win,
             "DestroyNotify matched EVE client (direct or parent)"
         );
-        ctx.cycle_state.remove_window(win);
-        ctx.session_state.remove_window(win);
-        ctx.eve_clients.remove(&win);
+        untrack_window(ctx, win);
     } else {
         debug!(
             window = event.window,
@@ -339,6 +446,67 @@ pub fn handle_destroy_notify(ctx: &mut EventContext, event: DestroyNotifyEvent)
     Ok(())
 }
 
+/// Handle PropertyNotify for `_EPM_IGNORE`: setting it on a tracked window drops its thumbnail
+/// and cycle slot, removing it lets the window be identified again
+pub fn handle_ignore_change(ctx: &mut EventContext, window: Window) -> Result<()> {
+    let ignored = crate::x11::is_window_ignored(ctx.app_ctx.conn, window, ctx.app_ctx.atoms)?;
+    if !ignored {
+        return handle_identity_update(ctx, window);
+    }
+
+    ctx.session_state.identity_retries.remove(&window);
+    let tracked = ctx.eve_clients.contains_key(&window) || ctx.cycle_state.tracks_window(window);
+    if !tracked {
+        return Ok(());
+    }
+    info!(
+        window = window,
+        "Window marked _EPM_IGNORE, no longer tracking it"
+    );
+    untrack_window(ctx, window);
+    crate::x11::clear_character_marker(ctx.app_ctx.conn, ctx.app_ctx.atoms, window)
+}
+
+/// Drops a client's thumbnail and per-window state and tells the Manager it is gone
+fn untrack_window(ctx: &mut EventContext, window: Window) {
+    use crate::common::ipc::DaemonMessage;
+
+    ctx.cycle_state.remove_window(window);
+    ctx.session_state.remove_window(window);
+    if let Some(thumbnail) = ctx.eve_clients.remove(&window)
+        && !thumbnail.character_name.is_empty()
+    {
+        ctx.status_tx.send(DaemonMessage::CharacterRemoved {
+            name: thumbnail.character_name.clone(),
+            is_custom: thumbnail.is_custom,
+        });
+    }
+}
+
+/// Handle PropertyNotify for WM_CLASS. Windows are tracked by XID, so a class change on a
+/// known window (some Proton builds swap it after startup) re-evaluates the existing
+/// thumbnail instead of dropping or re-creating it.
+pub fn handle_class_change(ctx: &mut EventContext, window: Window) -> Result<()> {
+    let known = ctx.eve_clients.contains_key(&window) || ctx.cycle_state.tracks_window(window);
+    if !known {
+        return handle_identity_update(ctx, window);
+    }
+
+    let class = get_window_class(ctx.app_ctx.conn, window, ctx.app_ctx.atoms)
+        .context(format!("Failed to read WM_CLASS for window {}", window))?
+        .unwrap_or_default();
+    let Some(previous) = ctx.session_state.note_window_class(window, &class) else {
+        return Ok(());
+    };
+    info!(
+        window = window,
+        old_class = %previous,
+        new_class = %class,
+        "Tracked window changed WM_CLASS, re-evaluating"
+    );
+    handle_identity_update(ctx, window)
+}
+
 /// Handle PropertyNotify for identity changes (WM_NAME or WM_CLASS) to detect late-identifying windows
 pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<()> {
     use crate::common::ipc::DaemonMessage;
@@ -348,12 +516,16 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
     // Check if the window is already tracked
     if ctx.eve_clients.contains_key(&window) {
         // Window is tracked. Check if it's an EVE window to handle character swaps/renames.
-        if let Some(eve_window) = is_window_eve(ctx.app_ctx.conn, window, ctx.app_ctx.atoms)
-            .context(format!(
-                "Failed to check if window {} is EVE client during property change",
-                window
-            ))?
-        {
+        if let Some(eve_window) = is_window_eve(
+            ctx.app_ctx.conn,
+            window,
+            ctx.app_ctx.atoms,
+            &ctx.daemon_config.profile.client_detection_rules,
+        )
+        .context(format!(
+            "Failed to check if window {} is EVE client during property change",
+            window
+        ))? {
             // It IS an EVE window.
             // Re-borrow thumbnail mutably
             let thumbnail = ctx
@@ -367,18 +539,23 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
             if old_name == new_character_name {
                 return Ok(());
             }
+            // The old character is gone from this window (logout or character switch)
+            if !old_name.is_empty() {
+                ctx.status_tx.send(DaemonMessage::CharacterRemoved {
+                    name: old_name.clone(),
+                    is_custom: false,
+                });
+            }
+            if let Err(e) = crate::x11::set_character_marker(
+                ctx.app_ctx.conn,
+                ctx.app_ctx.atoms,
+                window,
+                new_character_name,
+            ) {
+                warn!(window = window, error = %e, "Failed to update character marker");
+            }
 
-            let geom = ctx
-                .app_ctx
-                .conn
-                .get_geometry(thumbnail.window())
-                .context("Failed to send geometry query during character change")?
-                .reply()
-                .context(format!(
-                    "Failed to get geometry during character change for window {}",
-                    thumbnail.window()
-                ))?;
-            let current_pos = Position::new(geom.x, geom.y);
+            let current_pos = thumbnail.current_position;
 
             ctx.cycle_state
                 .update_character(window, new_character_name.to_string());
@@ -398,6 +575,8 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
                 ))?;
 
             if !new_character_name.is_empty() {
+                send_client_prefix(ctx.status_tx, ctx.session_state, window, new_character_name);
+
                 let final_settings = if let Some(settings) = new_settings {
                     Some(settings)
                 } else {
@@ -438,19 +617,21 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
                         .character_thumbnails
                         .insert(new_character_name.to_string(), settings.clone());
 
-                    let _ = ctx.status_tx.send(DaemonMessage::CharacterDetected {
+                    ctx.status_tx.send(DaemonMessage::CharacterDetected {
                         name: new_character_name.to_string(),
                         is_custom: false,
                     });
-
-                    let _ = ctx.status_tx.send(DaemonMessage::PositionChanged {
-                        name: new_character_name.to_string(),
-                        x: settings.x,
-                        y: settings.y,
-                        width: settings.dimensions.width,
-                        height: settings.dimensions.height,
-                        is_custom: false, // EVE chars are never custom sources
-                    });
+                    ctx.status_tx.debounce_position(
+                        DaemonMessage::PositionChanged {
+                            name: new_character_name.to_string(),
+                            x: settings.x,
+                            y: settings.y,
+                            width: settings.dimensions.width,
+                            height: settings.dimensions.height,
+                            is_custom: false, // EVE chars are never custom sources
+                        },
+                        ctx.daemon_config.position_debounce(),
+                    );
 
                     Some(settings)
                 };
@@ -491,8 +672,9 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
                     ))?;
             }
         } else {
-            // Tracked, but not valid EVE window (likely Custom Source)
-            // Implicitly ignore property updates for custom sources to prevent re-detection loops
+            // Tracked, but not valid EVE window (likely Custom Source, or an EVE client whose
+            // class no longer matches its rule). Keep the existing thumbnail: re-creating it
+            // on property updates causes re-detection loops.
         }
     } else {
         // Window is NOT tracked. Verify and identify.
@@ -500,7 +682,7 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
             ctx.app_ctx,
             window,
             ctx.session_state,
-            &ctx.daemon_config.profile.custom_windows,
+            &ctx.daemon_config.profile,
         )
         .context(format!(
             "Failed to identify window {} during property change",
@@ -515,12 +697,23 @@ pub fn handle_identity_update(ctx: &mut EventContext, window: Window) -> Result<
 /// Handle ConfigureNotify events - update cached source dimensions
 #[tracing::instrument(skip(ctx), fields(window = event.window))]
 pub fn handle_configure_notify(ctx: &mut EventContext, event: ConfigureNotifyEvent) -> Result<()> {
+    // Our own thumbnail windows (children of the root, reported through SubstructureNotify)
+    if let Some(thumbnail) = ctx
+        .eve_clients
+        .values_mut()
+        .find(|t| t.window() == event.window)
+    {
+        thumbnail.configured(event.x, event.y, event.sequence);
+        return Ok(());
+    }
+
     if let Some(thumbnail) = ctx.eve_clients.get_mut(&event.window) {
         // NOTE: This call is effectively a no-op.
         // We stopped caching source dimensions here to fix a race condition where
         // the event loop sees valid dimensions but the X server sees 1x1/unmapped.
         // Geometry is now queried freshly in `renderer::capture()`.
         thumbnail.update_source_dimensions(event.width, event.height);
+        thumbnail.source_changed();
 
         tracing::debug!(
             window = event.window,
@@ -531,3 +724,21 @@ pub fn handle_configure_notify(ctx: &mut EventContext, event: ConfigureNotifyEve
     }
     Ok(())
 }
+
+/// Report a client's Wine prefix to the Manager (if it was detected)
+fn send_client_prefix(
+    status_tx: &StatusSender,
+    session_state: &SessionState,
+    window: Window,
+    name: &str,
+) {
+    if name.is_empty() {
+        return;
+    }
+    if let Some(info) = session_state.window_prefix.get(&window) {
+        status_tx.send(crate::common::ipc::DaemonMessage::ClientPrefix {
+            name: name.to_string(),
+            info: info.clone(),
+        });
+    }
+}
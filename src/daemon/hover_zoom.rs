@@ -0,0 +1,216 @@
+//! Zoom on hover
+//!
+//! While the pointer is over a thumbnail it is enlarged by the profile's hover zoom factor, so
+//! small thumbnails can still be read (e.g. local chat) at a glance. An optional dwell delays
+//! the zoom until the pointer has rested on the thumbnail for a while. The anchor corner (or the
+//! center) stays where it was; the rest grows away from it and is kept on the thumbnail's
+//! monitor. Leaving the thumbnail, dragging it, or anything else that rearranges thumbnails
+//! restores the geometry it had before.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+use x11rb::protocol::xproto::Window;
+
+use super::cycle_state::CycleState;
+use super::font::FontRenderer;
+use super::overview::{SavedGeometry, apply_geometry};
+use super::session_state::SessionState;
+use super::snapping::Rect;
+use super::thumbnail::Thumbnail;
+use crate::common::constants::defaults::thumbnail;
+use crate::common::types::{Dimensions, Position};
+use crate::config::{DisplayConfig, HoverZoomAnchor};
+
+/// Computes the enlarged geometry of a hovered thumbnail.
+///
+/// The thumbnail grows away from `anchor` and is then pushed back inside `monitor`. Since the
+/// enlarged thumbnail covers the original one, the pointer stays over it.
+pub fn zoom_geometry(
+    position: Position,
+    dimensions: Dimensions,
+    scale: f32,
+    anchor: HoverZoomAnchor,
+    monitor: Rect,
+) -> (Position, Dimensions) {
+    let scale = scale.max(1.0);
+    let width = ((dimensions.width as f32 * scale) as u16)
+        .min(thumbnail::MAX_WIDTH)
+        .min(monitor.width)
+        .max(dimensions.width.min(monitor.width));
+    let height = ((dimensions.height as f32 * scale) as u16)
+        .min(thumbnail::MAX_HEIGHT)
+        .min(monitor.height)
+        .max(dimensions.height.min(monitor.height));
+
+    let grow_x = width as i32 - dimensions.width as i32;
+    let grow_y = height as i32 - dimensions.height as i32;
+    let (shift_x, shift_y) = match anchor {
+        HoverZoomAnchor::Center => (grow_x / 2, grow_y / 2),
+        HoverZoomAnchor::TopLeft => (0, 0),
+        HoverZoomAnchor::TopRight => (grow_x, 0),
+        HoverZoomAnchor::BottomLeft => (0, grow_y),
+        HoverZoomAnchor::BottomRight => (grow_x, grow_y),
+    };
+
+    let min_x = monitor.x as i32;
+    let min_y = monitor.y as i32;
+    let x = (position.x as i32 - shift_x).clamp(min_x, min_x + (monitor.width - width) as i32);
+    let y = (position.y as i32 - shift_y).clamp(min_y, min_y + (monitor.height - height) as i32);
+
+    (
+        Position::new(x as i16, y as i16),
+        Dimensions::new(width, height),
+    )
+}
+
+/// Enlarges a hovered thumbnail and raises it above its neighbours.
+///
+/// Returns the geometry to restore once the pointer leaves.
+pub fn start(
+    thumbnail: &mut Thumbnail,
+    scale: f32,
+    anchor: HoverZoomAnchor,
+    monitor: Rect,
+    display_config: &DisplayConfig,
+    skipped: bool,
+    font_renderer: &FontRenderer,
+) -> Result<SavedGeometry> {
+    let saved = SavedGeometry {
+        position: thumbnail.current_position,
+        dimensions: thumbnail.dimensions,
+        visible: thumbnail.is_visible(),
+    };
+
+    let (position, dimensions) =
+        zoom_geometry(saved.position, saved.dimensions, scale, anchor, monitor);
+
+    apply_geometry(
+        thumbnail,
+        position,
+        dimensions,
+        true,
+        display_config,
+        skipped,
+        font_renderer,
+    )?;
+    thumbnail.raise().context(format!(
+        "Failed to raise zoomed thumbnail for '{}'",
+        thumbnail.character_name
+    ))?;
+    Ok(saved)
+}
+
+/// Restores the thumbnail enlarged by hovering (if any)
+pub fn end(
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    session_state: &mut SessionState,
+    cycle_state: &CycleState,
+    display_config: &DisplayConfig,
+    font_renderer: &FontRenderer,
+) {
+    let Some((window, saved)) = session_state.hover_zoom.take() else {
+        return;
+    };
+    let Some(thumbnail) = eve_clients.get_mut(&window) else {
+        return;
+    };
+
+    let skipped = cycle_state.is_skipped(&thumbnail.character_name);
+    if let Err(e) = apply_geometry(
+        thumbnail,
+        saved.position,
+        saved.dimensions,
+        saved.visible,
+        display_config,
+        skipped,
+        font_renderer,
+    ) {
+        warn!(character = %thumbnail.character_name, error = %e, "Failed to restore thumbnail after hover zoom");
+    } else {
+        debug!(character = %thumbnail.character_name, "Hover zoom ended");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn test_zoom_geometry_anchors() {
+        let position = Position::new(500, 400);
+        let size = Dimensions::new(200, 100);
+        let zoom = |anchor| zoom_geometry(position, size, 2.0, anchor, MONITOR);
+
+        let (pos, dims) = zoom(HoverZoomAnchor::Center);
+        assert_eq!(dims, Dimensions::new(400, 200));
+        assert_eq!(pos, Position::new(400, 350));
+        assert_eq!(zoom(HoverZoomAnchor::TopLeft).0, Position::new(500, 400));
+        assert_eq!(zoom(HoverZoomAnchor::TopRight).0, Position::new(300, 400));
+        assert_eq!(zoom(HoverZoomAnchor::BottomLeft).0, Position::new(500, 300));
+        assert_eq!(
+            zoom(HoverZoomAnchor::BottomRight).0,
+            Position::new(300, 300)
+        );
+    }
+
+    #[test]
+    fn test_zoom_geometry_stays_on_monitor() {
+        // Second monitor to the right of the first
+        let monitor = Rect {
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        let (pos, dims) = zoom_geometry(
+            Position::new(3100, 950),
+            Dimensions::new(100, 70),
+            3.0,
+            HoverZoomAnchor::TopLeft,
+            monitor,
+        );
+        assert_eq!(dims, Dimensions::new(300, 210));
+        assert_eq!(pos, Position::new(3200 - 300, 1024 - 210));
+
+        let (pos, _) = zoom_geometry(
+            Position::new(1930, 10),
+            Dimensions::new(100, 70),
+            3.0,
+            HoverZoomAnchor::BottomRight,
+            monitor,
+        );
+        assert_eq!(pos, Position::new(1920, 0));
+    }
+
+    #[test]
+    fn test_zoom_geometry_limits() {
+        // Never larger than the monitor, never smaller than the thumbnail
+        let (pos, dims) = zoom_geometry(
+            Position::new(100, 100),
+            Dimensions::new(1200, 700),
+            4.0,
+            HoverZoomAnchor::Center,
+            MONITOR,
+        );
+        assert_eq!(dims, Dimensions::new(1920, 1080));
+        assert_eq!(pos, Position::new(0, 0));
+
+        let (_, dims) = zoom_geometry(
+            Position::new(100, 100),
+            Dimensions::new(250, 140),
+            0.5,
+            HoverZoomAnchor::Center,
+            MONITOR,
+        );
+        assert_eq!(dims, Dimensions::new(250, 140));
+    }
+}
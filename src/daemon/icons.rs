@@ -0,0 +1,363 @@
+//! Corner icon loading and caching
+//!
+//! Loads per-character corner icons (ESI portraits or custom PNG files), scales them to the
+//! configured size and caches the result as a premultiplied BGRA bitmap ready for X11 upload.
+//! Portraits are downloaded once into the cache directory on a background thread; until the
+//! file exists the icon is simply skipped, and a failed download is only retried after
+//! `PORTRAIT_RETRY_SECS`. A finished download wakes the event loop (`portrait_downloaded`) so
+//! the thumbnails are redrawn with it.
+
+use anyhow::{Context, Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use crate::common::constants::{config, eve};
+use crate::common::types::CornerIcon;
+
+/// Square icon bitmap in little-endian premultiplied ARGB (BGRA in memory)
+#[derive(Debug)]
+pub struct IconBitmap {
+    pub size: u16,
+    pub data: Vec<u8>,
+}
+
+type IconKey = (CornerIcon, u16);
+
+/// A loaded icon, or `None` for a file that failed to load (so it is only reported once)
+#[derive(Debug, Clone)]
+struct CachedIcon {
+    bitmap: Option<Arc<IconBitmap>>,
+    /// Modification time of the file when it was loaded; a changed file is loaded again
+    modified: Option<SystemTime>,
+}
+
+/// Loaded/scaled icons
+static ICON_CACHE: LazyLock<Mutex<HashMap<IconKey, CachedIcon>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Character IDs with a portrait download already in flight
+static PENDING_PORTRAITS: LazyLock<Mutex<HashSet<u64>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// When each failed portrait download may be retried
+static FAILED_PORTRAITS: LazyLock<Mutex<HashMap<u64, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Signalled after a portrait download lands in the cache
+static PORTRAIT_READY: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Resolves once a portrait download has finished since the last call
+pub async fn portrait_downloaded() {
+    PORTRAIT_READY.notified().await;
+}
+
+/// Returns the scaled icon bitmap for `icon`, loading it on first use.
+pub fn load_icon(icon: &CornerIcon, size: u16) -> Option<Arc<IconBitmap>> {
+    if size == 0 {
+        return None;
+    }
+
+    let path = match icon {
+        CornerIcon::None => return None,
+        CornerIcon::Custom { path } => PathBuf::from(path),
+        // Not set yet in the character editor
+        CornerIcon::Portrait { character_id: 0 } => return None,
+        CornerIcon::Portrait { character_id } => portrait_path(*character_id),
+    };
+    let modified = modified_time(&path);
+
+    let key = (icon.clone(), size);
+    if let Ok(cache) = ICON_CACHE.lock()
+        && let Some(cached) = cache.get(&key)
+        && cached.modified == modified
+    {
+        return cached.bitmap.clone();
+    }
+
+    if let CornerIcon::Portrait { character_id } = icon
+        && modified.is_none()
+    {
+        // Not cached yet: fetch in the background, the event loop redraws once it lands
+        request_portrait(*character_id, &path);
+        return None;
+    }
+
+    let bitmap = match decode_png(&path).map(|(w, h, rgba)| scale_to_bgra(w, h, &rgba, size)) {
+        Ok(data) => {
+            debug!(path = %path.display(), size = size, "Loaded corner icon");
+            Some(Arc::new(IconBitmap { size, data }))
+        }
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Failed to load corner icon");
+            None
+        }
+    };
+
+    if let Ok(mut cache) = ICON_CACHE.lock() {
+        cache.insert(
+            key,
+            CachedIcon {
+                bitmap: bitmap.clone(),
+                modified,
+            },
+        );
+    }
+    bitmap
+}
+
+/// Modification time of an icon file, None if it doesn't exist (yet)
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Location of the cached portrait for a character ID
+fn portrait_path(character_id: u64) -> PathBuf {
+    let mut path = dirs::cache_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(config::APP_DIR);
+    path.push("portraits");
+    path.push(format!("{}.png", character_id));
+    path
+}
+
+/// Starts a background download of the portrait from the EVE image server.
+fn request_portrait(character_id: u64, path: &Path) {
+    if let Ok(failed) = FAILED_PORTRAITS.lock()
+        && failed
+            .get(&character_id)
+            .is_some_and(|&retry_at| Instant::now() < retry_at)
+    {
+        return;
+    }
+    let Ok(mut pending) = PENDING_PORTRAITS.lock() else {
+        return;
+    };
+    if !pending.insert(character_id) {
+        return;
+    }
+
+    if let Some(parent) = path.parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        warn!(path = %parent.display(), error = %e, "Failed to create portrait cache directory");
+        pending.remove(&character_id);
+        portrait_failed(character_id);
+        return;
+    }
+
+    let url = format!(
+        "{}/characters/{}/portrait?size={}",
+        eve::IMAGE_SERVER_URL,
+        character_id,
+        eve::PORTRAIT_FETCH_SIZE
+    );
+    info!(character_id = character_id, url = %url, "Fetching character portrait");
+
+    // Download to a temp file so a partial transfer is never picked up as the icon
+    let tmp_path = path.with_extension("png.part");
+    let final_path = path.to_path_buf();
+    drop(pending);
+    std::thread::spawn(move || {
+        let result = download(&url, &tmp_path).and_then(|()| {
+            std::fs::rename(&tmp_path, &final_path).context("Failed to move portrait into place")
+        });
+        match result {
+            Ok(()) => {
+                debug!(character_id = character_id, "Portrait downloaded");
+                PORTRAIT_READY.notify_one();
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&tmp_path);
+                warn!(character_id = character_id, error = ?e, "Portrait download failed");
+                portrait_failed(character_id);
+            }
+        }
+        if let Ok(mut pending) = PENDING_PORTRAITS.lock() {
+            pending.remove(&character_id);
+        }
+    });
+}
+
+/// Fetches `url` into the file at `path`
+fn download(url: &str, path: &Path) -> Result<()> {
+    let body = ureq::get(url)
+        .config()
+        .timeout_global(Some(Duration::from_secs(eve::PORTRAIT_TIMEOUT_SECS)))
+        .build()
+        .call()
+        .context(format!("Request to {} failed", url))?
+        .body_mut()
+        .read_to_vec()
+        .context("Failed to read portrait")?;
+    std::fs::write(path, body).context(format!("Failed to write {}", path.display()))
+}
+
+/// Holds off further downloads of a portrait for `PORTRAIT_RETRY_SECS`
+fn portrait_failed(character_id: u64) {
+    if let Ok(mut failed) = FAILED_PORTRAITS.lock() {
+        failed.insert(
+            character_id,
+            Instant::now() + Duration::from_secs(eve::PORTRAIT_RETRY_SECS),
+        );
+    }
+}
+
+/// Decodes a PNG file into RGBA8 pixels
+fn decode_png(path: &Path) -> Result<(u32, u32, Vec<u8>)> {
+    let file = File::open(path).context(format!("Failed to open icon file {}", path.display()))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .context("PNG has no output buffer size")?
+    ];
+    let info = reader.next_frame(&mut buf)?;
+    let pixels = &buf[..info.buffer_size()];
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => pixels.to_vec(),
+        png::ColorType::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|c| [c[0], c[1], c[2], 0xFF])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => pixels
+            .chunks_exact(2)
+            .flat_map(|c| [c[0], c[0], c[0], c[1]])
+            .collect(),
+        png::ColorType::Grayscale => pixels.iter().flat_map(|&v| [v, v, v, 0xFF]).collect(),
+        other => {
+            return Err(anyhow!(
+                "Unsupported icon color type {:?} (expected RGB, RGBA or grayscale)",
+                other
+            ));
+        }
+    };
+
+    Ok((info.width, info.height, rgba))
+}
+
+/// Box-filters RGBA pixels into a `size`×`size` premultiplied BGRA bitmap
+fn scale_to_bgra(width: u32, height: u32, rgba: &[u8], size: u16) -> Vec<u8> {
+    let size = size as u32;
+    let mut out = vec![0u8; (size * size * 4) as usize];
+    if width == 0 || height == 0 {
+        return out;
+    }
+
+    for dy in 0..size {
+        let y0 = dy * height / size;
+        let y1 = ((dy + 1) * height / size).max(y0 + 1).min(height);
+        for dx in 0..size {
+            let x0 = dx * width / size;
+            let x1 = ((dx + 1) * width / size).max(x0 + 1).min(width);
+
+            let (mut r, mut g, mut b, mut a, mut n) = (0u32, 0u32, 0u32, 0u32, 0u32);
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let idx = ((sy * width + sx) * 4) as usize;
+                    let pa = rgba[idx + 3] as u32;
+                    r += rgba[idx] as u32 * pa / 255;
+                    g += rgba[idx + 1] as u32 * pa / 255;
+                    b += rgba[idx + 2] as u32 * pa / 255;
+                    a += pa;
+                    n += 1;
+                }
+            }
+
+            let out_idx = ((dy * size + dx) * 4) as usize;
+            out[out_idx] = (b / n) as u8;
+            out[out_idx + 1] = (g / n) as u8;
+            out[out_idx + 2] = (r / n) as u8;
+            out[out_idx + 3] = (a / n) as u8;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_to_bgra_premultiplies_and_swizzles() {
+        // 2x2 half-transparent red
+        let rgba = [255, 0, 0, 128].repeat(4);
+        let out = scale_to_bgra(2, 2, &rgba, 1);
+
+        assert_eq!(out.len(), 4);
+        assert_eq!(out[0], 0); // B
+        assert_eq!(out[1], 0); // G
+        assert_eq!(out[2], 128); // R premultiplied
+        assert_eq!(out[3], 128); // A
+    }
+
+    #[test]
+    fn test_scale_to_bgra_upscales() {
+        let rgba = [0, 0, 255, 255];
+        let out = scale_to_bgra(1, 1, &rgba, 4);
+
+        assert_eq!(out.len(), 4 * 4 * 4);
+        assert!(out.chunks_exact(4).all(|px| px == [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_none_icon_loads_nothing() {
+        assert!(load_icon(&CornerIcon::None, 32).is_none());
+    }
+
+    #[test]
+    fn test_unset_portrait_id_loads_nothing() {
+        assert!(load_icon(&CornerIcon::Portrait { character_id: 0 }, 32).is_none());
+        assert!(
+            PENDING_PORTRAITS
+                .lock()
+                .is_ok_and(|pending| !pending.contains(&0))
+        );
+    }
+
+    #[test]
+    fn test_fixed_custom_icon_is_reloaded() {
+        let dir = std::env::temp_dir().join(format!("epm-icon-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("icon.png");
+        let icon = CornerIcon::Custom {
+            path: path.to_string_lossy().into_owned(),
+        };
+
+        std::fs::write(&path, b"not a png").unwrap();
+        assert!(load_icon(&icon, 4).is_none());
+
+        let file = File::create(&path).unwrap();
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), 1, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[0, 0, 255, 255]).unwrap();
+        writer.finish().unwrap();
+        // Same-second rewrites can keep the old mtime on coarse filesystems
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        assert!(load_icon(&icon, 4).is_some());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_portrait_path_uses_character_id() {
+        let path = portrait_path(90000001);
+        assert!(path.ends_with("portraits/90000001.png"));
+    }
+}
@@ -6,29 +6,44 @@ use std::os::fd::AsRawFd;
 use tokio::io::unix::AsyncFd;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
-use x11rb::connection::Connection;
+use x11rb::connection::{Connection, RequestConnection};
 use x11rb::protocol::damage::ConnectionExt as DamageExt;
 use x11rb::protocol::xproto::*;
 
-use crate::common::constants::eve;
-use crate::common::ipc::{BootstrapMessage, ConfigMessage, DaemonMessage};
-use crate::config::DaemonConfig;
+use crate::common::constants::{defaults, eve};
+use crate::common::ipc::{
+    BootstrapMessage, ClientGeometry, ConfigMessage, DaemonMessage, HotkeyTestResult,
+    MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, ProtocolHello, STARTUP_ACTIONS_PROTOCOL_VERSION,
+};
+use crate::config::{DaemonConfig, HotkeyFocusContext, PointerWarpMode, SwitchOsdMode};
 use crate::input::listener::{self, CycleCommand, TimestampedCommand};
-use crate::x11::{AppContext, CachedAtoms, activate_window, minimize_window, unminimize_window};
-use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
+use crate::x11::{
+    AppContext, CachedAtoms, FocusCheck, check_focus, focus_window, get_active_window,
+    minimize_window, unminimize_window,
+};
+use ipc_channel::ipc::{self, IpcError, IpcReceiver, IpcSender};
 
 use super::cycle_state::CycleState;
 use super::dispatcher::{EventContext, handle_event};
 use super::font;
+use super::hover_zoom;
+use super::osd::OsdRequest;
+use super::overview;
+use super::panic;
+use super::peek;
 use super::session_state::SessionState;
-use super::thumbnail::Thumbnail;
+use super::snapshot;
+use super::stacking;
+use super::startup;
+use super::status_sender::StatusSender;
+use super::thumbnail::{Thumbnail, fade_in_opacity, fade_out_opacity};
 
 use std::collections::HashSet;
 use std::sync::{Arc, RwLock};
 use std::thread::JoinHandle;
 use x11rb::rust_connection::RustConnection;
 
-use crate::input::backend::AllowedWindows;
+use crate::input::backend::{AllowedWindows, PausedFlag};
 
 struct HotkeyResources {
     #[allow(dead_code)]
@@ -49,6 +64,7 @@ fn initialize_x11() -> Result<(
     usize,
     CachedAtoms,
     crate::x11::CachedFormats,
+    crate::x11::CachedMonitors,
 )> {
     // Initial screen metrics are required for auto-scaling thumbnails.
     let (conn, screen_num) = x11rb::connect(None)
@@ -68,6 +84,26 @@ fn initialize_x11() -> Result<(
     conn.damage_query_version(1, 1)
         .context("Failed to query DAMAGE extension version. Is DAMAGE extension available?")?;
 
+    if !super::renderer::has_shape(&conn) {
+        warn!("X server lacks the SHAPE extension, thumbnails stay rectangular");
+    }
+
+    if crate::x11::display_is_remote() {
+        info!(
+            "Remote X display detected, live previews use screenshot refresh mode, screenshot captures are scaled on the server at 16-bit depth and animations are off"
+        );
+    }
+
+    // Thumbnails are read from offscreen storage; without it the Manager shows fallback previews
+    if conn
+        .extension_information(x11rb::protocol::composite::X11_EXTENSION_NAME)?
+        .is_none()
+    {
+        anyhow::bail!(
+            "X server lacks the COMPOSITE extension; the Manager shows fallback previews instead"
+        );
+    }
+
     conn.change_window_attributes(
         screen.root,
         &ChangeWindowAttributesAux::new().event_mask(
@@ -84,10 +120,12 @@ fn initialize_x11() -> Result<(
         .context("Failed to cache picture formats at startup")?;
     debug!("Picture formats cached");
 
+    let monitors = crate::x11::CachedMonitors::new(&conn, screen);
+
     // Note: Font renderer initialization is deferred until after config load
     // as it depends on user-configured font settings.
 
-    Ok((conn, screen_num, atoms, formats))
+    Ok((conn, screen_num, atoms, formats, monitors))
 }
 
 fn initialize_state(
@@ -114,7 +152,11 @@ fn initialize_state(
     Ok((daemon_config, config, session_state, cycle_state))
 }
 
-fn setup_hotkeys(daemon_config: &DaemonConfig, allowed_windows: AllowedWindows) -> HotkeyResources {
+fn setup_hotkeys(
+    daemon_config: &DaemonConfig,
+    allowed_windows: AllowedWindows,
+    paused: PausedFlag,
+) -> HotkeyResources {
     // Create channel for hotkey thread → main loop
     let (hotkey_tx, hotkey_rx) = mpsc::channel(32);
 
@@ -192,16 +234,30 @@ fn setup_hotkeys(daemon_config: &DaemonConfig, allowed_windows: AllowedWindows)
     let has_profile_hotkeys = !profile_hotkeys.is_empty();
     let has_skip_key = daemon_config.profile.hotkey_toggle_skip.is_some();
     let has_toggle_previews_key = daemon_config.profile.hotkey_toggle_previews.is_some();
+    let has_overview_key = daemon_config.profile.hotkey_overview.is_some();
+    let has_flip_key = daemon_config.profile.hotkey_flip.is_some();
+    let has_peek_key = daemon_config.profile.hotkey_peek.is_some();
+    let has_reveal_key = daemon_config.profile.hotkey_reveal.is_some();
+    let has_panic_key = daemon_config.profile.hotkey_panic.is_some();
+    let has_pause_key = daemon_config.profile.hotkey_pause.is_some();
+    let has_suspend_key = daemon_config.profile.hotkey_suspend.is_some();
 
     let hotkey_handle = if has_cycle_keys
         || has_character_hotkeys
         || has_profile_hotkeys
         || has_skip_key
         || has_toggle_previews_key
+        || has_overview_key
+        || has_flip_key
+        || has_peek_key
+        || has_reveal_key
+        || has_panic_key
+        || has_pause_key
+        || has_suspend_key
     {
         // Select backend based on functionality
         use crate::config::HotkeyBackendType;
-        use crate::input::backend::{HotkeyBackend, HotkeyConfiguration};
+        use crate::input::backend::HotkeyConfiguration;
 
         let hotkey_config = HotkeyConfiguration {
             cycle_hotkeys,
@@ -209,70 +265,52 @@ fn setup_hotkeys(daemon_config: &DaemonConfig, allowed_windows: AllowedWindows)
             profile_hotkeys: profile_hotkeys.clone(),
             toggle_skip_key: daemon_config.profile.hotkey_toggle_skip.clone(),
             toggle_previews_key: daemon_config.profile.hotkey_toggle_previews.clone(),
+            overview_key: daemon_config.profile.hotkey_overview.clone(),
+            flip_key: daemon_config.profile.hotkey_flip.clone(),
+            peek_key: daemon_config.profile.hotkey_peek.clone(),
+            reveal_key: daemon_config.profile.hotkey_reveal.clone(),
+            panic_key: daemon_config.profile.hotkey_panic.clone(),
+            pause_key: daemon_config.profile.hotkey_pause.clone(),
+            suspend_key: daemon_config.profile.hotkey_suspend.clone(),
+            blocked_classes: daemon_config.profile.hotkey_blocked_classes.clone(),
         };
 
-        match daemon_config.profile.hotkey_backend {
-            HotkeyBackendType::X11 => {
-                debug!("Using X11 hotkey backend");
-                match crate::input::x11_backend::X11Backend::spawn(
-                    hotkey_tx,
-                    hotkey_config,
-                    daemon_config.profile.hotkey_input_device.clone(),
-                    daemon_config.profile.hotkey_require_eve_focus,
-                    allowed_windows.clone(),
-                ) {
-                    Ok(handle) => {
-                        debug!(
-                            enabled = true,
-                            backend = "x11",
-                            has_cycle_keys = has_cycle_keys,
-                            has_character_hotkeys = has_character_hotkeys,
-                            has_profile_hotkeys = has_profile_hotkeys,
-                            has_skip_key = has_skip_key,
-                            has_toggle_previews_key = has_toggle_previews_key,
-                            "Hotkey support enabled"
-                        );
-                        Some(handle)
-                    }
-                    Err(e) => {
-                        error!(error = %e, backend = "x11", "Failed to start hotkey listener");
-                        None
-                    }
-                }
+        let requested = daemon_config.profile.hotkey_backend;
+        if requested == HotkeyBackendType::Evdev {
+            info!("Using evdev hotkey backend (requires input group membership)");
+        }
+        match listener::spawn_backend(
+            requested,
+            hotkey_tx,
+            hotkey_config,
+            daemon_config.profile.hotkey_input_device.clone(),
+            daemon_config.profile.hotkey_focus_context != HotkeyFocusContext::Always,
+            allowed_windows.clone(),
+            paused,
+        ) {
+            Ok((backend, handle)) => {
+                debug!(
+                    enabled = true,
+                    backend = ?backend,
+                    has_cycle_keys = has_cycle_keys,
+                    has_character_hotkeys = has_character_hotkeys,
+                    has_profile_hotkeys = has_profile_hotkeys,
+                    has_skip_key = has_skip_key,
+                    has_toggle_previews_key = has_toggle_previews_key,
+                    has_overview_key = has_overview_key,
+                    has_flip_key = has_flip_key,
+                    has_peek_key = has_peek_key,
+                    has_reveal_key = has_reveal_key,
+                    has_panic_key = has_panic_key,
+                    has_pause_key = has_pause_key,
+                    has_suspend_key = has_suspend_key,
+                    "Hotkey support enabled"
+                );
+                Some(handle)
             }
-            HotkeyBackendType::Evdev => {
-                info!("Using evdev hotkey backend (requires input group membership)");
-                if !crate::input::evdev_backend::EvdevBackend::is_available() {
-                    listener::print_permission_error();
-                    None
-                } else {
-                    match crate::input::evdev_backend::EvdevBackend::spawn(
-                        hotkey_tx,
-                        hotkey_config,
-                        daemon_config.profile.hotkey_input_device.clone(),
-                        daemon_config.profile.hotkey_require_eve_focus,
-                        allowed_windows.clone(),
-                    ) {
-                        Ok(handle) => {
-                            debug!(
-                                enabled = true,
-                                backend = "evdev",
-                                has_cycle_keys = has_cycle_keys,
-                                has_character_hotkeys = has_character_hotkeys,
-                                has_profile_hotkeys = has_profile_hotkeys,
-                                has_skip_key = has_skip_key,
-                                has_toggle_previews_key = has_toggle_previews_key,
-                                "Hotkey support enabled"
-                            );
-                            Some(handle)
-                        }
-                        Err(e) => {
-                            error!(error = %e, backend = "evdev", "Failed to start hotkey listener");
-                            listener::print_permission_error();
-                            None
-                        }
-                    }
-                }
+            Err(e) => {
+                error!(error = %e, "Failed to start hotkey listener");
+                None
             }
         }
     } else {
@@ -287,6 +325,135 @@ fn setup_hotkeys(daemon_config: &DaemonConfig, allowed_windows: AllowedWindows)
     }
 }
 
+/// Whether the active window belongs to an app listed in the profile's hide exemptions
+fn focused_app_is_hide_exempt(
+    conn: &RustConnection,
+    screen: &Screen,
+    atoms: &CachedAtoms,
+    profile: &crate::config::profile::Profile,
+) -> bool {
+    if profile.thumbnail_hide_exempt_classes.is_empty() {
+        return false;
+    }
+    let class = crate::x11::get_active_window(conn, screen, atoms)
+        .and_then(|active| match active {
+            Some(window) => crate::x11::get_window_class(conn, window, atoms),
+            None => Ok(None),
+        })
+        .unwrap_or_else(|e| {
+            warn!(error = %e, "Failed to read class of the focused window");
+            None
+        });
+    class.is_some_and(|class| {
+        let exempt = profile.is_hide_exempt_class(&class);
+        if exempt {
+            debug!(class = %class, "Focused app is exempt from hide-when-no-focus");
+        }
+        exempt
+    })
+}
+
+/// Windows whose focus lets hotkeys fire under the profile's focus context: the accepted
+/// clients and custom sources (including those without thumbnails) and their thumbnails
+fn hotkey_focus_windows(resources: &DaemonResources<'_>) -> HashSet<u32> {
+    let profile = &resources.config.profile;
+    let is_source = |name: &str, window: Window| match resources.eve_clients.get(&window) {
+        Some(thumbnail) => thumbnail.is_custom,
+        None => profile.custom_windows.iter().any(|rule| rule.alias == name),
+    };
+
+    let mut windows = HashSet::new();
+    let known = resources
+        .cycle
+        .get_active_windows()
+        .iter()
+        .map(|(name, &window)| (name.as_str(), window))
+        .chain(
+            resources
+                .eve_clients
+                .iter()
+                .map(|(&window, thumbnail)| (thumbnail.character_name.as_str(), window)),
+        );
+    for (name, window) in known {
+        if profile.hotkey_focus_allows(name, is_source(name, window)) {
+            windows.insert(window);
+            if let Some(thumbnail) = resources.eve_clients.get(&window) {
+                windows.insert(thumbnail.window());
+            }
+        }
+    }
+    windows
+}
+
+/// Whether the focused window lets hotkeys fire: not a blocked app, and (unless the focus
+/// context is `Always`) one of `focus_windows` or a descendant of one
+fn focus_allows_hotkeys(
+    ctx: &AppContext<'_>,
+    profile: &crate::config::profile::Profile,
+    focus_windows: &HashSet<u32>,
+) -> bool {
+    let active_window = match get_active_window(ctx.conn, ctx.screen, ctx.atoms) {
+        Ok(active) => active,
+        Err(e) => {
+            error!(error = %e, "Failed to check focused window");
+            return false;
+        }
+    };
+
+    if !profile.hotkey_blocked_classes.is_empty()
+        && let Some(window) = active_window
+        && let Ok(Some(class)) = crate::x11::get_window_class(ctx.conn, window, ctx.atoms)
+        && profile.is_hotkey_blocked_class(&class)
+    {
+        debug!(class = %class, "Hotkey ignored: focused app disables hotkeys");
+        return false;
+    }
+
+    if profile.hotkey_focus_context == HotkeyFocusContext::Always {
+        return true;
+    }
+    let Some(active_window) = active_window else {
+        return false;
+    };
+    if focus_windows.contains(&active_window) {
+        return true;
+    }
+
+    // NOTE: The active window might be a child (e.g. in Wine/Proton apps like Mod Organizer).
+    // We must walk the window hierarchy to check if any ancestor is an accepted client.
+    // Limit traversal depth to prevent infinite loops (X11 cycles) or stalls.
+    let mut current = active_window;
+    for _ in 0..10 {
+        let Some(reply) = ctx
+            .conn
+            .query_tree(current)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+        else {
+            break;
+        };
+        if focus_windows.contains(&reply.parent) {
+            debug!(
+                child = active_window,
+                parent = reply.parent,
+                "Hotkey allowed: Found accepted ancestor"
+            );
+            return true;
+        }
+        // Stop if we hit root or invalid window
+        if reply.parent == ctx.screen.root || reply.parent == 0 {
+            break;
+        }
+        current = reply.parent;
+    }
+
+    debug!(
+        active_window = active_window,
+        "Hotkey ignored: Focused window is not an accepted client or descendant"
+    );
+    false
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_event_loop(
     conn: &RustConnection,
@@ -294,47 +461,123 @@ async fn run_event_loop(
     mut display_config: crate::config::DisplayConfig,
     atoms: &CachedAtoms,
     formats: &crate::x11::CachedFormats,
+    monitors: &crate::x11::CachedMonitors,
     mut font_renderer: crate::daemon::font::FontRenderer,
     mut resources: DaemonResources<'_>,
     mut hotkey_rx: mpsc::Receiver<TimestampedCommand>,
     hotkey_groups: HashMap<crate::config::HotkeyBinding, Vec<String>>,
     mut sigusr1: tokio::signal::unix::Signal,
+    mut sigterm: tokio::signal::unix::Signal,
     config_rx: IpcReceiver<ConfigMessage>,
-    status_tx: IpcSender<DaemonMessage>,
+    status_tx: StatusSender,
     allowed_windows: AllowedWindows,
+    paused_flag: PausedFlag,
 ) -> Result<()> {
     debug!("Daemon running (async)");
 
-    // Wrap IPC receiver in something async-friendly?
-    // IpcReceiver is blocking. IPC-channel doesn't support async recv out of the box in a way that integrates with tokio::select! easily without a bridge.
-    // We should spawn a thread to bridge IPC messages to a tokio channel.
-    let (ipc_config_tx, mut ipc_config_rx_tokio) = mpsc::channel(1);
+    let mut ipc_config_rx_tokio = forward_config_messages(config_rx);
 
-    std::thread::spawn(move || {
-        while let Ok(msg) = config_rx.recv() {
-            if ipc_config_tx.blocking_send(msg).is_err() {
-                break; // Manager connection lost
-            }
-        }
-        // If config_rx fails (Manager side closed), this thread ends.
-        // We should probably explicitly terminate the daemon here if we want absolute safety.
-        error!("IPC Config channel closed - Manager process likely terminated. Exiting daemon.");
-        std::process::exit(1);
-    });
+    // Results of `TestHotkey` probes, which block on their own thread until the key is pressed
+    let (hotkey_test_tx, mut hotkey_test_rx) =
+        mpsc::channel::<(crate::config::HotkeyBinding, HotkeyTestResult)>(4);
 
     // Wrap X11 connection in AsyncFd for async polling
     // This allows us to wake up exactly when X11 has data, without busy polling
     let x11_fd = AsyncFd::new(conn.stream().as_raw_fd())
         .context("Failed to create AsyncFd for X11 connection")?;
 
+    // Newer Managers ask with `RunStartupActions` once the handshake is done
+    if resources.config.run_startup_actions {
+        run_startup_actions(
+            &mut resources,
+            &AppContext {
+                conn,
+                screen,
+                atoms,
+                formats,
+                monitors,
+            },
+            &display_config,
+            &font_renderer,
+            &status_tx,
+        );
+    }
+
+    let mut metrics = super::metrics::Sampler::new();
+
     // Heartbeat timer (3s interval) - skip missed ticks to prevent backlog
     let mut heartbeat_interval = tokio::time::interval(std::time::Duration::from_secs(3));
     heartbeat_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
+    // Visibility schedule timer (first tick fires immediately to apply the schedule on startup)
+    let mut schedule_interval = tokio::time::interval(std::time::Duration::from_secs(
+        defaults::timing::SCHEDULE_CHECK_INTERVAL_SECS,
+    ));
+    schedule_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    // Re-check timer for windows mapped before their title identified them
+    let mut identity_retry_interval =
+        tokio::time::interval(resources.config.identity_retry_interval());
+    identity_retry_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    // Event mask audit timer
+    let mut mask_audit_interval = tokio::time::interval(std::time::Duration::from_secs(
+        defaults::timing::EVENT_MASK_AUDIT_INTERVAL_SECS,
+    ));
+    mask_audit_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    // Timelapse recording timer
+    let mut timelapse_interval = tokio::time::interval(std::time::Duration::from_secs(
+        defaults::timing::TIMELAPSE_CHECK_INTERVAL_SECS,
+    ));
+    timelapse_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut timelapse = super::timelapse::Recorder::new();
+    let mut timelapse_worker = super::timelapse::Worker::new();
+
+    // Round-trip audit summaries (only ticks when the audit is enabled)
+    let mut roundtrip_audit_interval = tokio::time::interval(std::time::Duration::from_millis(
+        crate::common::constants::x11::ROUNDTRIP_AUDIT_INTERVAL_MS,
+    ));
+    roundtrip_audit_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
     // Timer for delayed thumbnail hiding (hysteresis)
     let hide_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
     tokio::pin!(hide_timer);
 
+    // Timer for the hover dwell before a thumbnail zooms
+    let hover_zoom_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(hover_zoom_timer);
+
+    // Reads back whether the last hotkey activation took effect
+    let focus_check_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(focus_check_timer);
+
+    // Timer for stepping the focus-gain fade-in
+    let fade_in_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(fade_in_timer);
+
+    // Timer for sending debounced thumbnail position updates
+    let position_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(position_timer);
+
+    // Timer for coalesced damage repaints (one per frame, or per screenshot-mode interval)
+    let repaint_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(repaint_timer);
+
+    // Timer for paced minimize-on-switch batches and their verification
+    let minimize_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(minimize_timer);
+
+    // Timer for ending a hotkey suspension
+    let suspend_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(suspend_timer);
+
+    // Switch OSD and the timer fading it out
+    let mut switch_osd = super::osd::SwitchOsd::new(conn);
+    let mut hover_tooltip = super::tooltip::HoverTooltip::new(conn);
+    let osd_timer = tokio::time::sleep(tokio::time::Duration::from_secs(86400));
+    tokio::pin!(osd_timer);
+
     loop {
         // Scope ctx to allow mutable borrow of font_renderer later
         {
@@ -344,6 +587,7 @@ async fn run_event_loop(
                 screen,
                 atoms,
                 formats,
+                monitors,
             };
 
             // Process all pending X11 events without blocking to ensure the queue is drained
@@ -353,6 +597,14 @@ async fn run_event_loop(
                 .poll_for_event()
                 .context("Failed to poll for X11 event")?
             {
+                super::metrics::record(super::metrics::Counter::Event);
+                if let x11rb::protocol::Event::Error(error) = &event {
+                    super::metrics::record_x_error(error);
+                }
+                if resources.session.paused && !super::dispatcher::handled_while_paused(&event) {
+                    continue;
+                }
+
                 // Scope the mutable borrows for event handling
                 {
                     let mut context = EventContext {
@@ -372,27 +624,35 @@ async fn run_event_loop(
                 }
             }
 
+            // Thumbnails created while paused stay hidden until resume
+            if resources.session.paused {
+                for thumbnail in resources.eve_clients.values_mut() {
+                    if let Err(e) = thumbnail.visibility(false) {
+                        warn!(character = %thumbnail.character_name, error = %e, "Failed to hide thumbnail while paused");
+                    }
+                }
+            }
+
+            // Repaint damage from this batch that is already due; the rest waits for the timer
+            if !resources.session.paused {
+                super::dispatcher::repaint_damaged(
+                    &mut resources.eve_clients,
+                    &display_config,
+                    &font_renderer,
+                    std::time::Instant::now(),
+                );
+            }
+
             // Flush any pending requests to X server
             let _ = ctx.conn.flush();
         }
 
         // Sync allowed windows with backend
-        // Include both EVE client source windows AND thumbnail windows to ensure hotkeys
+        // Include both client source windows AND thumbnail windows to ensure hotkeys
         // work when focus is on either the clients themselves or their thumbnails.
         // This is critical when thumbnails are hidden/shown or clients are minimized.
         {
-            let mut current_windows: HashSet<u32> = HashSet::new();
-
-            // allow hotkeys for all EVE client source windows known to the cycle state
-            // (including those without thumbnails/previews)
-            for src_window in resources.cycle.get_active_windows().values() {
-                current_windows.insert(*src_window);
-            }
-
-            // allow hotkeys for thumbnail overlay windows
-            for thumbnail in resources.eve_clients.values() {
-                current_windows.insert(thumbnail.window());
-            }
+            let current_windows = hotkey_focus_windows(&resources);
 
             let need_update = {
                 if let Ok(guard) = allowed_windows.read() {
@@ -411,6 +671,93 @@ async fn run_event_loop(
             }
         }
 
+        // Let the hotkey backend release its grabs while paused or suspended
+        paused_flag.store(
+            resources.session.paused
+                || resources
+                    .session
+                    .hotkeys_suspended(std::time::Instant::now()),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+
+        if let Some(request) = resources.session.osd_request.take() {
+            show_osd(
+                &mut switch_osd,
+                &AppContext {
+                    conn,
+                    screen,
+                    atoms,
+                    formats,
+                    monitors,
+                },
+                &resources,
+                request,
+                &font_renderer,
+            );
+        }
+        sync_hover_tooltip(
+            &mut hover_tooltip,
+            &AppContext {
+                conn,
+                screen,
+                atoms,
+                formats,
+                monitors,
+            },
+            &resources,
+            &display_config,
+            &font_renderer,
+        );
+        if let Some(deadline) = switch_osd.deadline() {
+            let duration = deadline
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            osd_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        if let Some(until) = resources.session.hotkeys_suspended_until {
+            let duration = until
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            suspend_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        if let Some((_, due)) = resources.session.hover_zoom_pending {
+            let duration = due
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            hover_zoom_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        if let Some(pending) = &resources.session.pending_focus {
+            let duration = pending
+                .next_check
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            focus_check_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        // Next fade-in step falls on the step grid counted from the start of the fade
+        if let Some((started, _)) = &resources.session.focus_gain_fade {
+            let step = std::time::Duration::from_millis(defaults::timing::FOCUS_LOSS_FADE_STEP_MS);
+            let steps = started.elapsed().as_millis() / step.as_millis() + 1;
+            let deadline = *started + step * steps as u32;
+            let duration = deadline
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            fade_in_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
         // Update hide timer if deadline was set or changed
         if let Some(deadline) = resources.session.focus_loss_deadline {
             // Calculate duration until deadline
@@ -429,6 +776,44 @@ async fn run_event_loop(
             );
         }
 
+        if let Some(deadline) = status_tx.position_deadline() {
+            let duration = deadline
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            position_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        let minimize_deadline = resources.session.minimize_queue.deadline();
+        if let Some(deadline) = minimize_deadline {
+            let duration = deadline
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            minimize_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        let repaint_deadline = resources
+            .eve_clients
+            .values()
+            .filter_map(|thumbnail| thumbnail.repaint_deadline(display_config.damage_frame))
+            .min();
+        if let Some(deadline) = repaint_deadline {
+            let duration = deadline
+                .checked_duration_since(std::time::Instant::now())
+                .unwrap_or(std::time::Duration::ZERO);
+            repaint_timer
+                .as_mut()
+                .reset(tokio::time::Instant::now() + duration);
+        }
+
+        let timelapse_active = display_config
+            .character_settings
+            .values()
+            .any(|s| s.timelapse_interval_secs.is_some());
+
         tokio::select! {
             biased;  // Process branches in order - prioritize hotkeys over heartbeat/IPC
 
@@ -437,6 +822,17 @@ async fn run_event_loop(
             Some(msg) = hotkey_rx.recv() => {
                  let TimestampedCommand { command, timestamp } = msg;
 
+                if resources.session.paused && !command.works_while_paused() {
+                    debug!(command = ?command, "Hotkey ignored, daemon is paused");
+                    continue;
+                }
+                if resources.session.hotkeys_suspended(std::time::Instant::now())
+                    && !command.works_while_paused()
+                {
+                    debug!(command = ?command, "Hotkey ignored, hotkeys are suspended");
+                    continue;
+                }
+
                  // Reconstruct AppContext for hotkey handling (read-only borrow)
                 let ctx = AppContext {
                     conn,
@@ -444,71 +840,13 @@ async fn run_event_loop(
 
                     atoms,
                     formats,
+                    monitors,
                 };
 
-                // NOTE: Logic gates hotkeys to only function when a tracked window has focus.
+                // NOTE: Logic gates hotkeys to the profile's focus context and blocked apps.
                 // This prevents hotkeys from firing while typing in other applications (e.g. Discord).
-                let should_process = if resources.config.profile.hotkey_require_eve_focus {
-                    match crate::x11::get_active_window(ctx.conn, ctx.screen, ctx.atoms) {
-                        Ok(Some(active_window)) => {
-                            // Check if active window is a known EVE window (thumbnail OR just identified)
-                            let is_known = resources.eve_clients.contains_key(&active_window) ||
-                                         resources.cycle.get_active_windows().values().any(|&w| w == active_window);
-
-                            if is_known {
-                                true
-                            } else {
-                                // NOTE: The active window might be a child (e.g. in Wine/Proton apps like Mod Organizer).
-                                // We must walk the window hierarchy to check if any ancestor is a tracked client.
-                                let mut current = active_window;
-                                let mut found_ancestor = false;
-
-                                // NOTE: Limit traversal depth to prevent infinite loops (X11 cycles) or stalls.
-                                for _ in 0..10 {
-                                    match ctx.conn.query_tree(current) {
-                                        Ok(cookie) => {
-                                            if let Ok(reply) = cookie.reply() {
-                                                if resources.eve_clients.contains_key(&reply.parent) ||
-                                                   resources.cycle.get_active_windows().values().any(|&w| w == reply.parent) {
-                                                    found_ancestor = true;
-                                                    debug!(
-                                                        child = active_window,
-                                                        parent = reply.parent,
-                                                        "Hotkey allowed: Found tracked ancestor"
-                                                    );
-                                                    break;
-                                                }
-                                                // Stop if we hit root or invalid window
-                                                if reply.parent == ctx.screen.root || reply.parent == 0 {
-                                                    break;
-                                                }
-                                                current = reply.parent;
-                                            } else {
-                                                break;
-                                            }
-                                        }
-                                        Err(_) => break,
-                                    }
-                                }
-
-                                if !found_ancestor {
-                                    debug!(
-                                        active_window = active_window,
-                                        "Hotkey ignored: Focused window is not a tracked client or descendant"
-                                    );
-                                }
-                                found_ancestor
-                            }
-                        }
-                        Ok(None) => false,
-                        Err(e) => {
-                            error!(error = %e, "Failed to check focused window");
-                            false
-                        }
-                    }
-                } else {
-                    true
-                };
+                let should_process = command.ignores_focus_requirement()
+                    || focus_allows_hotkeys(&ctx, &resources.config.profile, &hotkey_focus_windows(&resources));
 
                 if should_process {
                     debug!(command = ?command, "Received hotkey command");
@@ -526,7 +864,20 @@ async fn run_event_loop(
                         );
                     }
 
-                    if let Some((window, character_name)) = handle_cycle_command(&command, &mut resources, &ctx, &font_renderer, &status_tx, &hotkey_groups) {
+                    let activation = handle_cycle_command(&command, &mut resources, &ctx, &font_renderer, &status_tx, &hotkey_groups);
+
+                    // Cycle badges show the order of the group cycled last
+                    if resources.cycle.last_active_group() != resources.config.active_cycle_group.as_deref() {
+                        resources.config.active_cycle_group = resources.cycle.last_active_group().map(str::to_string);
+                        display_config = resources.config.build_display_config();
+                        if display_config.cycle_badge_size > 0 {
+                            for thumbnail in resources.eve_clients.values_mut() {
+                                let _ = thumbnail.update(&display_config, &font_renderer);
+                            }
+                        }
+                    }
+
+                    if let Some((window, character_name)) = activation {
                         let display_name = if character_name.is_empty() {
                             eve::LOGGED_OUT_DISPLAY_NAME
                         } else {
@@ -539,7 +890,7 @@ async fn run_event_loop(
                         );
 
                         // NOTE: When minimize mode is enabled, unminimize the target window FIRST
-                        // before calling activate_window. This ensures the window is restored from
+                        // before calling focus_window. This ensures the window is restored from
                         // minimized state so it can properly receive keyboard focus.
                         if resources.config.profile.client_minimize_on_switch
                             && let Err(e) = unminimize_window(ctx.conn, ctx.screen, ctx.atoms, window)
@@ -547,124 +898,154 @@ async fn run_event_loop(
                             error!(window = window, error = %e, "Failed to unminimize window before activation");
                         }
 
-                        if let Err(e) = activate_window(ctx.conn, ctx.screen, ctx.atoms, window, timestamp) {
-                            error!(window = window, error = %e, "Failed to activate window");
-                        } else {
-                            debug!(window = window, "activate_window completed successfully");
-
-                            // Set current window immediately after successful activation.
-                            // This ensures the border shows correctly during the 25ms delay before
-                            // FocusIn arrives. The FocusIn handler will confirm this later.
-                            resources.cycle.set_current_by_window(window);
+                        let warp_mode = resources.config.profile.client_pointer_warp;
+                        if warp_mode == PointerWarpMode::Remembered
+                            && let Ok(Some(previous)) = get_active_window(ctx.conn, ctx.screen, ctx.atoms)
+                            && previous != window
+                            && resources.eve_clients.contains_key(&previous)
+                            && let Err(e) = super::pointer_warp::remember(ctx.conn, previous, &mut resources.session.pointer_positions)
+                        {
+                            debug!(window = previous, error = %e, "Failed to remember pointer position");
+                        }
 
-                            // Draw active border immediately to prevent flash during delay
-                            if let Some(thumb) = resources.eve_clients.get(&window) {
-                                let display_config = resources.config.build_display_config();
-                                if let Err(e) = thumb.border(
-                                    &display_config,
-                                    true,
-                                    resources.cycle.is_skipped(&thumb.character_name),
-                                    &font_renderer,
+                        match focus_window(ctx.conn, ctx.screen, ctx.atoms, window, timestamp) {
+                            Err(e) => error!(window = window, error = %e, "Failed to activate window"),
+                            Ok(pending) => {
+                                debug!(window = window, "Activation requested");
+                                resources.session.pending_focus = Some(pending);
+
+                                if let Err(e) = super::pointer_warp::warp(
+                                    ctx.conn,
+                                    warp_mode,
+                                    window,
+                                    resources.session.pointer_positions.get(&window).copied(),
                                 ) {
-                                    warn!(window = window, error = %e, "Failed to draw initial active border");
+                                    warn!(window = window, error = %e, "Failed to warp pointer to activated client");
                                 }
-                            }
 
-                            // Clear borders from ALL other windows immediately (including minimized ones)
-                            // This ensures we don't leave stale active borders on minimized windows
-                            for (w, thumb) in resources.eve_clients.iter_mut() {
-                                if *w != window {
+                                resources.session.osd_request = Some(OsdRequest::Switch(window));
+                                if resources.config.profile.client_switch_speech {
+                                    super::speech::announce(display_name);
+                                }
+
+                                // Set current window immediately after successful activation.
+                                // This ensures the border shows correctly during the 25ms delay before
+                                // FocusIn arrives. The FocusIn handler will confirm this later.
+                                resources.cycle.set_current_by_window(window);
+
+                                // Draw active border immediately to prevent flash during delay
+                                if let Some(thumb) = resources.eve_clients.get_mut(&window) {
                                     let display_config = resources.config.build_display_config();
-                                    // Only change state for non-minimized windows
-                                    // Minimized windows should stay Minimized - calling border() on them causes
-                                    // double-rendering. Instead, re-call minimized() to properly clear and re-render.
-                                    if thumb.state.is_minimized() {
-                                        if let Err(e) = thumb.minimized(&display_config, &font_renderer) {
-                                            warn!(window = *w, error = %e, "Failed to re-render minimized window");
-                                        }
-                                    } else {
-                                        thumb.state = crate::common::types::ThumbnailState::Normal { focused: false };
-                                        if let Err(e) = thumb.border(
-                                            &display_config,
-                                            false,
-                                            resources.cycle.is_skipped(&thumb.character_name),
-                                            &font_renderer,
-                                        ) {
-                                            warn!(window = *w, error = %e, "Failed to clear border during switch");
-                                        }
+                                    if let Err(e) = thumb.border(
+                                        &display_config,
+                                        true,
+                                        resources.cycle.is_skipped(&thumb.character_name),
+                                        &font_renderer,
+                                    ) {
+                                        warn!(window = window, error = %e, "Failed to draw initial active border");
                                     }
                                 }
-                            }
 
-                            // CRITICAL: Flush X11 connection to ensure border updates are rendered
-                            // before the 25ms delay. Without this, borders may flash to wrong clients.
-                            let _ = ctx.conn.flush();
-
-                            if resources.config.profile.client_minimize_on_switch {
-                                // NOTE: Critical delay to prevent KWin focus thrashing. Without this,
-                                // KWin repeatedly redirects focus to window 2097152 (internal KWin window)
-                                // during the minimize operations, causing continuous FocusOut/FocusIn loops.
-                                // The 25ms allows KWin to fully commit to the focus transfer before we
-                                // start changing other window states.
-                                tokio::time::sleep(std::time::Duration::from_millis(25)).await;
-
-                                // Minimize all other EVE clients after successful activation.
-                                // NOTE: exempt_from_minimize for custom sources is stored in the
-                                // rule, not in daemon_config maps; build_display_config() is the
-                                // only place it is resolved into character_settings.
-                                let display_config = resources.config.build_display_config();
-                                let other_windows: Vec<Window> = resources.eve_clients
-                                    .iter()
-                                    .filter(|(w, _)| **w != window)
-                                    .filter(|(_, t)| {
-                                        !display_config
-                                            .character_settings
-                                            .get(&t.character_name)
-                                            .map(|s| s.exempt_from_minimize)
-                                            .unwrap_or(false)
-                                    })
-                                    .map(|(w, _)| *w)
-                                    .collect();
-                                for other_window in other_windows {
-                                    // Clear border on the window BEFORE minimizing it
-                                    // This prevents leaving stale active borders on minimized windows
-                                    if let Some(thumb) = resources.eve_clients.get_mut(&other_window) {
-                                        // Don't change state here - let the minimize handler set it to Minimized
-                                        // Just clear the border for now
-                                        if let Err(e) = thumb.border(
-                                            &display_config,
-                                            false,
-                                            resources.cycle.is_skipped(&thumb.character_name),
-                                            &font_renderer,
-                                        ) {
-                                            warn!(window = other_window, error = %e, "Failed to clear border before minimize");
+                                // Clear borders from ALL other windows immediately (including minimized ones)
+                                // This ensures we don't leave stale active borders on minimized windows
+                                for (w, thumb) in resources.eve_clients.iter_mut() {
+                                    if *w != window {
+                                        let display_config = resources.config.build_display_config();
+                                        // Only change state for non-minimized windows
+                                        // Minimized windows should stay Minimized - calling border() on them causes
+                                        // double-rendering. Instead, re-call minimized() to properly clear and re-render.
+                                        if thumb.state.is_minimized() {
+                                            if let Err(e) = thumb.minimized(&display_config, &font_renderer) {
+                                                warn!(window = *w, error = %e, "Failed to re-render minimized window");
+                                            }
+                                        } else {
+                                            thumb.state = crate::common::types::ThumbnailState::Normal { focused: false };
+                                            if let Err(e) = thumb.border(
+                                                &display_config,
+                                                false,
+                                                resources.cycle.is_skipped(&thumb.character_name),
+                                                &font_renderer,
+                                            ) {
+                                                warn!(window = *w, error = %e, "Failed to clear border during switch");
+                                            }
                                         }
                                     }
-                                    if let Err(e) = minimize_window(ctx.conn, ctx.screen, ctx.atoms, other_window) {
-                                        debug!(window = other_window, error = %e, "Failed to minimize window via hotkey");
-                                    }
                                 }
 
-                                // Minimize Manager GUI as well (to prevent focus stealing/clutter)
-                                // We search for "eve-preview-manager" class.
-                                // NOTE: Thumbnails are now "eve-preview-thumbnail", so this is safe/unique.
-                                let manager_window = crate::x11::get_client_list(ctx.conn, ctx.atoms)
-                                    .ok()
-                                    .and_then(|windows| {
-                                        windows.into_iter().find(|&w| {
-                                            crate::x11::get_window_class(ctx.conn, w, ctx.atoms)
-                                                .ok()
-                                                .flatten()
-                                                .map(|class| class == "eve-preview-manager")
+                                // CRITICAL: Flush X11 connection to ensure border updates are rendered
+                                // before the 25ms delay. Without this, borders may flash to wrong clients.
+                                let _ = ctx.conn.flush();
+
+                                if resources.config.profile.client_minimize_on_switch {
+                                    // NOTE: Critical delay to prevent KWin focus thrashing. Without this,
+                                    // KWin repeatedly redirects focus to window 2097152 (internal KWin window)
+                                    // during the minimize operations, causing continuous FocusOut/FocusIn loops.
+                                    // The 25ms allows KWin to fully commit to the focus transfer before we
+                                    // start changing other window states.
+                                    tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+
+                                    // Minimize all other EVE clients after successful activation.
+                                    // NOTE: exempt_from_minimize for custom sources is stored in the
+                                    // rule, not in daemon_config maps; build_display_config() is the
+                                    // only place it is resolved into character_settings.
+                                    let display_config = resources.config.build_display_config();
+                                    let other_windows: Vec<Window> = resources.eve_clients
+                                        .iter()
+                                        .filter(|(w, _)| **w != window)
+                                        .filter(|(_, t)| {
+                                            !display_config
+                                                .character_settings
+                                                .get(&t.character_name)
+                                                .map(|s| s.exempt_from_minimize)
                                                 .unwrap_or(false)
                                         })
-                                    });
+                                        .map(|(w, _)| *w)
+                                        .collect();
+                                    for &other_window in &other_windows {
+                                        // Clear border on the window BEFORE minimizing it
+                                        // This prevents leaving stale active borders on minimized windows
+                                        if let Some(thumb) = resources.eve_clients.get_mut(&other_window) {
+                                            // Don't change state here - let the minimize handler set it to Minimized
+                                            // Just clear the border for now
+                                            if let Err(e) = thumb.border(
+                                                &display_config,
+                                                false,
+                                                resources.cycle.is_skipped(&thumb.character_name),
+                                                &font_renderer,
+                                            ) {
+                                                warn!(window = other_window, error = %e, "Failed to clear border before minimize");
+                                            }
+                                        }
+                                    }
+                                    // Sent in paced batches and verified by the minimize timer
+                                    resources.session.minimize_queue.schedule_switch(
+                                        window,
+                                        other_windows,
+                                        resources.config.profile.client_minimize_alt_restore,
+                                        std::time::Instant::now(),
+                                    );
 
-                                if let Some(mgr_win) = manager_window {
-                                    if let Err(e) = minimize_window(ctx.conn, ctx.screen, ctx.atoms, mgr_win) {
-                                        debug!(window = mgr_win, error = %e, "Failed to minimize Manager GUI");
-                                    } else {
-                                        debug!("Minimized Manager GUI");
+                                    // Minimize Manager GUI as well (to prevent focus stealing/clutter)
+                                    // We search for "eve-preview-manager" class.
+                                    // NOTE: Thumbnails are now "eve-preview-thumbnail", so this is safe/unique.
+                                    let manager_window = crate::x11::get_client_list(ctx.conn, ctx.atoms)
+                                        .ok()
+                                        .and_then(|windows| {
+                                            windows.into_iter().find(|&w| {
+                                                crate::x11::get_window_class(ctx.conn, w, ctx.atoms)
+                                                    .ok()
+                                                    .flatten()
+                                                    .map(|class| class == "eve-preview-manager")
+                                                    .unwrap_or(false)
+                                            })
+                                        });
+
+                                    if let Some(mgr_win) = manager_window {
+                                        if let Err(e) = minimize_window(ctx.conn, ctx.screen, ctx.atoms, mgr_win) {
+                                            debug!(window = mgr_win, error = %e, "Failed to minimize Manager GUI");
+                                        } else {
+                                            debug!("Minimized Manager GUI");
+                                        }
                                     }
                                 }
                             }
@@ -673,7 +1054,7 @@ async fn run_event_loop(
                         warn!("No window to activate via hotkey");
                     }
                 } else {
-                    info!(hotkey_require_eve_focus = resources.config.profile.hotkey_require_eve_focus, "Hotkey ignored, EVE window not focused (hotkey_require_eve_focus enabled)");
+                    info!(context = ?resources.config.profile.hotkey_focus_context, "Hotkey ignored, focused window is outside the hotkey focus context");
                 }
 
 
@@ -700,40 +1081,379 @@ async fn run_event_loop(
             // 3. Handle Delayed Hide (Hysteresis)
             // Only process this branch if there's an active deadline
             () = &mut hide_timer, if resources.session.focus_loss_deadline.is_some() => {
+                // Clear deadline - this will disable the branch until next FocusOut (or fade step)
+                resources.session.focus_loss_deadline = None;
+
+                // A fade-in still running is cut short at full opacity
+                if let Some((_, windows)) = resources.session.focus_gain_fade.take() {
+                    for window in windows {
+                        if let Some(thumbnail) = resources.eve_clients.get(&window)
+                            && let Err(e) = thumbnail.set_opacity(display_config.opacity)
+                        {
+                            error!(error = %e, character = %thumbnail.character_name, "Failed to restore thumbnail opacity");
+                        }
+                    }
+                }
+
+                // The reveal hotkey keeps thumbnails up; releasing it reschedules the hide
+                if resources.session.reveal_active {
+                    continue;
+                }
+
+                // The overview keeps everything visible until it is dismissed
+                if resources.session.overview.is_some() {
+                    if resources.session.focus_loss_fade.take().is_some() {
+                        for thumbnail in resources.eve_clients.values() {
+                            if let Err(e) = thumbnail.set_opacity(display_config.opacity) {
+                                error!(error = %e, character = %thumbnail.character_name, "Failed to restore thumbnail opacity");
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                // Focus moved to an exempt app (e.g. comms): keep thumbnails up and check again
+                // later, since leaving that app for another one sends us no FocusOut
+                if resources.session.focus_loss_fade.is_none()
+                    && focused_app_is_hide_exempt(conn, screen, atoms, &resources.config.profile)
+                {
+                    resources.session.focus_loss_deadline = Some(
+                        std::time::Instant::now()
+                            + std::time::Duration::from_millis(defaults::timing::HIDE_EXEMPT_RECHECK_MS),
+                    );
+                    continue;
+                }
+
+                // Fading: lower the opacity step by step, then unmap once fully transparent
+                if let Some(fade) = super::thumbnail::hide_fade(&display_config) {
+                    let started = *resources
+                        .session
+                        .focus_loss_fade
+                        .get_or_insert_with(std::time::Instant::now);
+                    if let Some(opacity) = fade_out_opacity(display_config.opacity, started.elapsed(), fade) {
+                        for thumbnail in resources.eve_clients.values() {
+                            if display_config.keeps_visible_without_focus(&thumbnail.character_name) {
+                                continue;
+                            }
+                            if let Err(e) = thumbnail.set_opacity(opacity) {
+                                error!(error = %e, character = %thumbnail.character_name, "Failed to fade thumbnail on focus timeout");
+                            }
+                        }
+                        resources.session.focus_loss_deadline = Some(
+                            std::time::Instant::now()
+                                + std::time::Duration::from_millis(defaults::timing::FOCUS_LOSS_FADE_STEP_MS),
+                        );
+                        continue;
+                    }
+                }
+
                 debug!("Executing delayed thumbnail hide");
+                let faded = resources.session.focus_loss_fade.take().is_some();
                 for thumbnail in resources.eve_clients.values_mut() {
+                    if display_config.keeps_visible_without_focus(&thumbnail.character_name) {
+                        continue;
+                    }
                     if let Err(e) = thumbnail.visibility(false) {
                         error!(error = %e, character = %thumbnail.character_name, "Failed to hide thumbnail on focus timeout");
                     }
+                    // Unmapped now, so restoring the opacity is invisible until the next reveal
+                    if faded && let Err(e) = thumbnail.set_opacity(display_config.opacity) {
+                        error!(error = %e, character = %thumbnail.character_name, "Failed to restore thumbnail opacity");
+                    }
                 }
-                // Clear deadline - this will disable the branch until next FocusOut
-                resources.session.focus_loss_deadline = None;
             }
 
-            // 4. Send Heartbeat (Lower priority - can wait)
-            _ = heartbeat_interval.tick() => {
-                if let Err(e) = status_tx.send(DaemonMessage::Heartbeat) {
-                    error!(error = %e, "Failed to send heartbeat to Manager");
-                    // If we can't send heartbeat, manager might be dead.
-                    // We'll let the IPC config channel failure handle termination.
-                }
+            // Raise the opacity of thumbnails revealed on focus gain step by step
+            () = &mut fade_in_timer, if resources.session.focus_gain_fade.is_some() => {
+                let Some((started, windows)) = resources.session.focus_gain_fade.take() else {
+                    continue;
+                };
+                let opacity = super::thumbnail::hide_fade(&display_config)
+                    .and_then(|fade| fade_in_opacity(display_config.opacity, started.elapsed(), fade));
+                for window in &windows {
+                    if let Some(thumbnail) = resources.eve_clients.get(window)
+                        && let Err(e) = thumbnail.set_opacity(opacity.unwrap_or(display_config.opacity))
+                    {
+                        error!(error = %e, character = %thumbnail.character_name, "Failed to fade thumbnail in on focus");
+                    }
+                }
+                if opacity.is_some() {
+                    resources.session.focus_gain_fade = Some((started, windows));
+                }
+            }
+
+            // Grab hotkeys again once the suspension runs out
+            () = &mut suspend_timer, if resources.session.hotkeys_suspended_until.is_some() => {
+                resources.session.hotkeys_suspended_until = None;
+                info!("Hotkey suspension ended");
+                resources.session.osd_request =
+                    Some(OsdRequest::Announce("Hotkeys resumed".to_string()));
+            }
+
+            // Fade out the switch OSD
+            () = &mut osd_timer, if switch_osd.deadline().is_some() => {
+                switch_osd.tick(atoms);
+            }
+
+            // Next batch of minimize-on-switch requests, and re-checks of earlier ones
+            () = &mut minimize_timer, if minimize_deadline.is_some() => {
+                let ctx = AppContext {
+                    conn,
+                    screen,
+                    atoms,
+                    formats,
+                    monitors,
+                };
+                resources
+                    .session
+                    .minimize_queue
+                    .tick(&ctx, std::time::Instant::now());
+                let _ = conn.flush();
+            }
+
+            // Report positions once they have settled
+            () = &mut position_timer, if status_tx.position_deadline().is_some() => {
+                status_tx.flush_positions();
+            }
+
+            // Repaint damage held back until the next frame or screenshot interval
+            () = &mut repaint_timer, if repaint_deadline.is_some() && !resources.session.paused => {
+                super::dispatcher::repaint_damaged(
+                    &mut resources.eve_clients,
+                    &display_config,
+                    &font_renderer,
+                    std::time::Instant::now(),
+                );
+                if let Err(e) = conn.flush() {
+                    error!(error = %e, "Failed to flush X11 connection after damage repaint");
+                }
+            }
+
+            // Save timelapse frames of recorded clients
+            _ = timelapse_interval.tick(), if timelapse_active && !resources.session.paused => {
+                let candidates: Vec<(Window, std::time::Duration)> = resources
+                    .eve_clients
+                    .iter()
+                    .filter(|(_, t)| !t.character_name.is_empty())
+                    .filter_map(|(w, t)| {
+                        display_config
+                            .timelapse_interval(&t.character_name)
+                            .map(|interval| (*w, interval))
+                    })
+                    .collect();
+                for window in timelapse.due(std::time::Instant::now(), &candidates) {
+                    if let Some(thumbnail) = resources.eve_clients.get(&window) {
+                        timelapse_worker.record(window, &thumbnail.character_name);
+                    }
+                }
+            }
+
+            // Zoom the thumbnail the pointer has rested on
+            () = &mut hover_zoom_timer, if resources.session.hover_zoom_pending.is_some() => {
+                let ctx = AppContext {
+                    conn,
+                    screen,
+                    atoms,
+                    formats,
+                    monitors,
+                };
+                let mut context = EventContext {
+                    app_ctx: &ctx,
+                    daemon_config: &mut resources.config,
+                    eve_clients: &mut resources.eve_clients,
+                    session_state: &mut resources.session,
+                    cycle_state: &mut resources.cycle,
+
+                    status_tx: &status_tx,
+                    font_renderer: &font_renderer,
+                    display_config: &display_config,
+                };
+                if let Err(e) = super::handlers::input::start_pending_hover_zoom(&mut context) {
+                    error!(error = ?e, "Failed to zoom hovered thumbnail");
+                }
+                let _ = conn.flush();
+            }
+
+            // Verify the last hotkey activation, retrying once if the WM ignored it
+            () = &mut focus_check_timer, if resources.session.pending_focus.is_some() => {
+                if let Some(mut pending) = resources.session.pending_focus.take() {
+                    match check_focus(conn, screen, atoms, &mut pending) {
+                        Ok(FocusCheck::Waiting) => resources.session.pending_focus = Some(pending),
+                        Ok(FocusCheck::Focused) => {
+                            debug!(window = pending.window, "Activation verified");
+                        }
+                        Ok(FocusCheck::Failed) => {}
+                        Err(e) => {
+                            warn!(window = pending.window, error = %e, "Failed to verify activation");
+                        }
+                    }
+                }
+            }
+
+            // Re-check windows that mapped before their title was set
+            _ = identity_retry_interval.tick(), if !resources.session.identity_retries.is_empty() => {
+                let ctx = AppContext {
+                    conn,
+                    screen,
+                    atoms,
+                    formats,
+                    monitors,
+                };
+                let mut context = EventContext {
+                    app_ctx: &ctx,
+                    daemon_config: &mut resources.config,
+                    eve_clients: &mut resources.eve_clients,
+                    session_state: &mut resources.session,
+                    cycle_state: &mut resources.cycle,
+
+                    status_tx: &status_tx,
+                    font_renderer: &font_renderer,
+                    display_config: &display_config,
+                };
+                if let Err(e) = super::handlers::window::retry_pending_identities(&mut context) {
+                    error!(error = ?e, "Failed to re-check unidentified windows");
+                }
+                let _ = conn.flush();
+            }
+
+            // Restore event masks that tracked windows lost ("borders stopped updating")
+            _ = mask_audit_interval.tick() => {
+                let mut windows: Vec<Window> = resources.eve_clients.keys().copied().collect();
+                for window in resources.cycle.get_active_windows().values() {
+                    if !windows.contains(window) {
+                        windows.push(*window);
+                    }
+                }
+                let ctx = AppContext {
+                    conn,
+                    screen,
+                    atoms,
+                    formats,
+                    monitors,
+                };
+                match super::window_detection::audit_event_masks(&ctx, &windows) {
+                    Ok(0) => {}
+                    Ok(repaired) => {
+                        info!(repaired = repaired, "Re-applied lost event masks");
+                        let _ = conn.flush();
+                    }
+                    Err(e) => error!(error = ?e, "Event mask audit failed"),
+                }
+            }
+
+            // 4. Send Heartbeat (Lower priority - can wait)
+            _ = heartbeat_interval.tick() => {
+                // Also retries any status messages that failed to send since the last tick.
+                // If the Manager is truly gone, the IPC config channel failure handles termination.
+                status_tx.heartbeat();
+
+                let audit = crate::x11::input_guard::drain_audit();
+                if !audit.is_empty() {
+                    status_tx.send(DaemonMessage::SyntheticInput(audit));
+                }
+                status_tx.send(DaemonMessage::Metrics(metrics.sample()));
+
+                let x_errors = super::metrics::drain_x_errors();
+                if !x_errors.is_empty() {
+                    let total: u64 = x_errors.iter().map(|e| e.count).sum();
+                    let top = &x_errors[0];
+                    warn!(
+                        total = total,
+                        error = %top.error,
+                        request = %top.request,
+                        "X errors since last heartbeat"
+                    );
+                    status_tx.send(DaemonMessage::XErrors(x_errors));
+                }
+            }
+
+            _ = roundtrip_audit_interval.tick(), if super::roundtrip_audit::enabled() => {
+                let counts = super::roundtrip_audit::drain();
+                if !counts.is_empty() {
+                    info!("{}", super::roundtrip_audit::summary(&counts));
+                }
+            }
+
+            // A corner icon portrait landed in the cache: redraw the overlays that show icons
+            () = super::icons::portrait_downloaded() => {
+                let display_config = resources.config.build_display_config();
+                for thumbnail in resources.eve_clients.values_mut() {
+                    if !thumbnail.is_visible() {
+                        continue;
+                    }
+                    let result = if thumbnail.state.is_minimized() {
+                        thumbnail.minimized(&display_config, &font_renderer)
+                    } else {
+                        let focused = thumbnail.state.is_focused();
+                        let skipped = resources.cycle.is_skipped(&thumbnail.character_name);
+                        thumbnail.border(&display_config, focused, skipped, &font_renderer)
+                    };
+                    if let Err(e) = result {
+                        warn!(character = %thumbnail.character_name, error = %e, "Failed to redraw thumbnail with its portrait");
+                    }
+                }
+            }
+
+            // Show/hide previews when the visibility schedule starts or ends a hidden window.
+            // Between transitions the toggle-previews hotkey acts as a manual override.
+            _ = schedule_interval.tick() => {
+                let scheduled = resources
+                    .config
+                    .profile
+                    .schedule_hides_previews(&chrono::Local::now());
+                let previous = resources.session.schedule_hidden.replace(scheduled);
+                if previous != Some(scheduled)
+                    && (previous.is_some() || scheduled)
+                    && resources.config.runtime_hidden != scheduled
+                {
+                    info!(hidden = scheduled, "Visibility schedule changed preview visibility");
+                    resources.config.runtime_hidden = scheduled;
+                    apply_runtime_hidden(&mut resources, &font_renderer);
+                }
+            }
+
+            // Stop requested by the Manager: leave no stale character markers on the clients
+            _ = sigterm.recv() => {
+                info!("SIGTERM received - clearing character markers and exiting");
+                for &window in resources.eve_clients.keys() {
+                    if let Err(e) = crate::x11::clear_character_marker(conn, atoms, window) {
+                        debug!(window = window, error = %e, "Failed to clear character marker");
+                    }
+                }
+                let _ = conn.flush();
+                return Ok(());
             }
 
             // 4. Handle SIGUSR1 (Lower priority)
             _ = sigusr1.recv() => {
                 info!("SIGUSR1 received - config is now managed by Manager via IPC");
-                let _ = status_tx.send(DaemonMessage::Status("SIGUSR1 received: Syncing config...".to_string()));
+                status_tx.send(DaemonMessage::Status("SIGUSR1 received: Syncing config...".to_string()));
+            }
+
+            Some((binding, result)) = hotkey_test_rx.recv() => {
+                info!(binding = %binding.display_name(), result = ?result, "Hotkey test finished");
+                status_tx.send(DaemonMessage::HotkeyTested { binding, result });
             }
 
             // 5. Handle IPC Config Updates (Lower priority - expensive operation)
             Some(msg) = ipc_config_rx_tokio.recv() => {
+                super::metrics::record(super::metrics::Counter::IpcReceived);
                 match msg {
                     ConfigMessage::Full(new_config) => {
                         let new_config = *new_config; // Unbox
                         info!("Received full config update via IPC");
 
-                        // Update DaemonConfig
+                        // Update DaemonConfig (the Manager doesn't know about hotkey/schedule hiding)
+                        let runtime_hidden = resources.config.runtime_hidden;
+                        let active_cycle_group = resources.config.active_cycle_group.take();
                         resources.config = new_config;
+                        resources.config.runtime_hidden = runtime_hidden;
+                        resources.config.active_cycle_group = active_cycle_group;
+
+                        if identity_retry_interval.period() != resources.config.identity_retry_interval() {
+                            identity_retry_interval = tokio::time::interval(resources.config.identity_retry_interval());
+                            identity_retry_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                        }
 
                         // Only rebuild font renderer if font settings actually changed
                         let font_name = &resources.config.profile.thumbnail_text_font;
@@ -766,9 +1486,32 @@ async fn run_event_loop(
 
                         // Force redraw of all thumbnails with new settings
                         display_config = resources.config.build_display_config();
+
+                        // Saved overview/peek geometry may be stale under the new config, so close them
+                        end_peek(&mut resources, &display_config, &font_renderer);
+                        end_hover_zoom(&mut resources, &display_config, &font_renderer);
+                        overview::exit(
+                            &mut resources.eve_clients,
+                            &mut resources.session,
+                            &resources.cycle,
+                            &display_config,
+                            &font_renderer,
+                        );
+                        let ctx = AppContext {
+                            conn,
+                            screen,
+                            atoms,
+                            formats,
+                            monitors,
+                        };
                         for thumbnail in resources.eve_clients.values_mut() {
-                             let _ = thumbnail.update(&display_config, &font_renderer);
+                            // Capture backend, scale filter and vsync only take effect on rebuilt backends
+                            if let Err(e) = thumbnail.apply_backend_settings(&ctx, &display_config) {
+                                warn!(character = %thumbnail.character_name, error = %e, "Failed to apply capture settings");
+                            }
+                            let _ = thumbnail.update(&display_config, &font_renderer);
                         }
+                        stacking::restack(&resources.eve_clients, &display_config);
 
                         info!("Full config updated");
                     },
@@ -784,20 +1527,24 @@ async fn run_event_loop(
                             "Received ThumbnailMove delta"
                         );
 
-                        // Find the specific thumbnail by character name AND type (EVE vs custom source)
-                        let thumbnail_opt = resources.eve_clients.values_mut().find(|t| {
-                            if t.character_name != name {
-                                return false;
-                            }
+                        // Several windows can match one custom source; their geometry is kept per
+                        // instance, so a delta keyed by alias alone is ambiguous
+                        let instances = resources
+                            .eve_clients
+                            .values()
+                            .filter(|t| t.is_custom && t.character_name == name)
+                            .count();
+                        if is_custom && instances > 1 {
+                            debug!(name = %name, instances = instances, "ThumbnailMove ignored: custom source has several instances");
+                            continue;
+                        }
 
-                            // Verify this thumbnail matches the expected type
-                            // Custom sources and EVE characters can have name collisions
-                            if is_custom {
-                                resources.config.custom_source_thumbnails.contains_key(&name)
-                            } else {
-                                resources.config.character_thumbnails.contains_key(&name)
-                            }
-                        });
+                        // Find the specific thumbnail by character name AND type (EVE vs custom source)
+                        // Custom sources and EVE characters can have name collisions
+                        let thumbnail_opt = resources
+                            .eve_clients
+                            .values_mut()
+                            .find(|t| t.character_name == name && t.is_custom == is_custom);
 
                         if let Some(thumb) = thumbnail_opt {
                             // IDEMPOTENCY CHECK (Critical for performance)
@@ -835,21 +1582,254 @@ async fn run_event_loop(
                             debug!(name = %name, is_custom = is_custom, "ThumbnailMove ignored: character not tracked");
                         }
                     }
+
+                    ConfigMessage::Hello(hello) => {
+                        match ProtocolHello::current().negotiate(&hello) {
+                            Some(version) => info!(
+                                manager_version = %hello.app_version,
+                                manager_protocol = hello.protocol_version,
+                                negotiated = version,
+                                "Protocol handshake complete"
+                            ),
+                            None => {
+                                error!(
+                                    manager_version = %hello.app_version,
+                                    manager_protocol = hello.protocol_version,
+                                    daemon_protocol = PROTOCOL_VERSION,
+                                    "Manager protocol is incompatible with this daemon. Exiting."
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Incompatible Manager protocol version {}",
+                                    hello.protocol_version
+                                ));
+                            }
+                        }
+                    }
+
+                    ConfigMessage::Ping(seq) => {
+                        status_tx.send(DaemonMessage::Pong(seq));
+                    }
+
+                    ConfigMessage::RequestClientLayout => {
+                        let layout = client_layout(&resources);
+                        debug!(clients = layout.len(), "Sending client layout to Manager");
+                        status_tx.send(DaemonMessage::ClientLayout(layout));
+                    }
+
+                    ConfigMessage::CaptureFrame { character } => {
+                        let png = capture_client_png(conn, &resources, &character)
+                            .map_err(|e| format!("{:#}", e));
+                        if let Err(e) = &png {
+                            warn!(character = %character, error = %e, "Frame capture failed");
+                        }
+                        status_tx.send(DaemonMessage::Frame { character, png });
+                    }
+
+                    ConfigMessage::MatchWindows { title_pattern, class_pattern } => {
+                        let ctx = AppContext {
+                            conn,
+                            screen,
+                            atoms,
+                            formats,
+                            monitors,
+                        };
+                        let windows = super::window_detection::matching_windows(
+                            &ctx,
+                            title_pattern.as_deref(),
+                            class_pattern.as_deref(),
+                        )
+                        .unwrap_or_else(|e| {
+                            warn!(error = %e, "Failed to match windows for rule preview");
+                            Vec::new()
+                        });
+                        status_tx.send(DaemonMessage::MatchingWindows {
+                            title_pattern,
+                            class_pattern,
+                            windows,
+                        });
+                    }
+
+                    ConfigMessage::SetPaused(paused) => {
+                        set_paused(&mut resources, paused, &font_renderer, &status_tx);
+                    }
+
+                    ConfigMessage::TogglePreviews => {
+                        resources.config.runtime_hidden = !resources.config.runtime_hidden;
+                        info!(
+                            hidden = resources.config.runtime_hidden,
+                            "Toggled previews visibility from the Manager"
+                        );
+                        apply_runtime_hidden(&mut resources, &font_renderer);
+                    }
+
+                    ConfigMessage::CaptureSession => {
+                        let ctx = AppContext {
+                            conn,
+                            screen,
+                            atoms,
+                            formats,
+                            monitors,
+                        };
+                        let (clients, focused) = snapshot::capture(
+                            &ctx,
+                            resources.cycle.get_active_windows(),
+                            &client_layout(&resources),
+                        );
+                        status_tx.send(DaemonMessage::SessionCaptured { clients, focused });
+                    }
+
+                    ConfigMessage::RestoreSession { clients, focused } => {
+                        let ctx = AppContext {
+                            conn,
+                            screen,
+                            atoms,
+                            formats,
+                            monitors,
+                        };
+                        // Leave overview / peek first so the restored thumbnail geometry sticks
+                        end_peek(&mut resources, &display_config, &font_renderer);
+                        end_hover_zoom(&mut resources, &display_config, &font_renderer);
+                        overview::exit(
+                            &mut resources.eve_clients,
+                            &mut resources.session,
+                            &resources.cycle,
+                            &display_config,
+                            &font_renderer,
+                        );
+                        let windows = resources.cycle.get_active_windows().clone();
+                        let missing = snapshot::restore(
+                            &ctx,
+                            &clients,
+                            &windows,
+                            &mut resources.eve_clients,
+                            &mut resources.config,
+                            &display_config,
+                            &font_renderer,
+                            &status_tx,
+                        );
+                        if let Some(character) = focused
+                            && let Some(window) =
+                                startup::focus_character(&ctx, &windows, &character)
+                        {
+                            resources.cycle.set_current_by_window(window);
+                        }
+                        status_tx.send(DaemonMessage::SessionRestored { missing });
+                    }
+
+                    ConfigMessage::Announce(text) => {
+                        resources.session.osd_request = Some(OsdRequest::Announce(text));
+                    }
+
+                    ConfigMessage::RunStartupActions => {
+                        run_startup_actions(
+                            &mut resources,
+                            &AppContext {
+                                conn,
+                                screen,
+                                atoms,
+                                formats,
+                                monitors,
+                            },
+                            &display_config,
+                            &font_renderer,
+                            &status_tx,
+                        );
+                    }
+
+                    ConfigMessage::FocusCharacter(character) => {
+                        let ctx = AppContext {
+                            conn,
+                            screen,
+                            atoms,
+                            formats,
+                            monitors,
+                        };
+                        let windows = resources.cycle.get_active_windows().clone();
+                        if let Some(window) = startup::focus_character(&ctx, &windows, &character)
+                        {
+                            resources.cycle.set_current_by_window(window);
+                        }
+                    }
+
+                    ConfigMessage::TestHotkey(binding) => {
+                        let profile = &resources.config.profile;
+                        let bound = profile
+                            .hotkey_assignments()
+                            .iter()
+                            .any(|(_, b)| b.same_keys(&binding))
+                            || resources.config.profile_hotkeys.keys().any(|b| b.same_keys(&binding));
+                        if bound {
+                            // The running listener already holds it, so a probe grab would fail
+                            status_tx.send(DaemonMessage::HotkeyTested {
+                                binding,
+                                result: HotkeyTestResult::AlreadyBound,
+                            });
+                        } else {
+                            info!(binding = %binding.display_name(), "Testing hotkey");
+                            let backend = profile.hotkey_backend;
+                            let device_id = profile.hotkey_input_device.clone();
+                            let tx = hotkey_test_tx.clone();
+                            std::thread::spawn(move || {
+                                let result = match listener::test_binding(
+                                    backend,
+                                    &binding,
+                                    device_id.as_deref(),
+                                    std::time::Duration::from_secs(crate::common::constants::input::HOTKEY_TEST_SECS),
+                                ) {
+                                    Ok(()) => HotkeyTestResult::Received,
+                                    Err(e) => HotkeyTestResult::Failed(format!("{:#}", e)),
+                                };
+                                let _ = tx.blocking_send((binding, result));
+                            });
+                        }
+                    }
                 }
             }
         }
     }
 }
 
-pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
-    // 1. Initialize X11 connection and resources
-    let (conn, _screen_num, atoms, formats) =
-        initialize_x11().context("Failed to initialize X11")?;
+/// Bridges the blocking IPC receiver into a tokio channel. The daemon exits once the Manager
+/// side closes, since nothing could reach it anymore.
+pub(super) fn forward_config_messages(
+    config_rx: IpcReceiver<ConfigMessage>,
+) -> mpsc::Receiver<ConfigMessage> {
+    let (ipc_config_tx, ipc_config_rx_tokio) = mpsc::channel(1);
 
-    // Re-acquire screen reference from connection (x11rb::connect returns screen index)
-    let screen = &conn.setup().roots[_screen_num];
+    std::thread::spawn(move || {
+        loop {
+            match config_rx.recv() {
+                Ok(msg) => {
+                    if ipc_config_tx.blocking_send(msg).is_err() {
+                        break; // Manager connection lost
+                    }
+                }
+                // A message this build cannot decode (newer Manager); skip it rather than stalling
+                Err(IpcError::Bincode(e)) => {
+                    warn!(error = %e, "Dropping undecodable message from Manager (protocol mismatch?)");
+                }
+                Err(_) => break,
+            }
+        }
+        // If config_rx fails (Manager side closed), this thread ends.
+        // We should probably explicitly terminate the daemon here if we want absolute safety.
+        error!("IPC Config channel closed - Manager process likely terminated. Exiting daemon.");
+        std::process::exit(1);
+    });
 
-    // 2. Setup IPC and get initial config
+    ipc_config_rx_tokio
+}
+
+/// Hands our IPC channels to the Manager, announces the protocol version and waits for the
+/// initial configuration. The Manager's hello must arrive (and agree on a version) before the
+/// config, so nothing is sent to a Manager whose message layouts differ from ours.
+pub(super) fn connect_manager(
+    ipc_server_name: String,
+) -> Result<(
+    IpcReceiver<ConfigMessage>,
+    IpcSender<DaemonMessage>,
+    DaemonConfig,
+)> {
     debug!("Connecting to IPC server: {}", ipc_server_name);
     let bootstrap_sender: IpcSender<BootstrapMessage> =
         IpcSender::connect(ipc_server_name).context("Failed to connect to IPC server")?;
@@ -864,18 +1844,76 @@ pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
         .send((config_tx, status_rx))
         .context("Failed to send bootstrap message")?;
 
+    // Announce our protocol version; older Managers never read past the bootstrap tuple
+    // before sending the config, so this is safe to send unconditionally.
+    status_tx
+        .send(DaemonMessage::Hello(ProtocolHello::current()))
+        .context("Failed to send protocol hello")?;
+
     debug!("Waiting for initial configuration...");
-    let initial_config = match config_rx.recv() {
-        Ok(ConfigMessage::Full(config)) => *config,
-        Ok(ConfigMessage::ThumbnailMove { .. }) => {
-            return Err(anyhow::anyhow!(
-                "Expected Full config on startup, got ThumbnailMove"
-            ));
+    let mut negotiated = None;
+    let initial_config = loop {
+        match config_rx.recv() {
+            Ok(ConfigMessage::Full(mut config)) if negotiated.is_some() => {
+                // Managers before `RunStartupActions` expect them on every start
+                config.run_startup_actions =
+                    negotiated.is_some_and(|v| v < STARTUP_ACTIONS_PROTOCOL_VERSION);
+                break *config;
+            }
+            Ok(ConfigMessage::Full(_)) => {
+                return Err(anyhow::anyhow!(
+                    "Manager sent its config without a protocol hello (protocol {} required)",
+                    MIN_PROTOCOL_VERSION
+                ));
+            }
+            Ok(ConfigMessage::Hello(hello)) => {
+                negotiated = ProtocolHello::current().negotiate(&hello);
+                if negotiated.is_none() {
+                    return Err(anyhow::anyhow!(
+                        "Incompatible Manager protocol version {}",
+                        hello.protocol_version
+                    ));
+                }
+                debug!(
+                    protocol = hello.protocol_version,
+                    "Manager hello received before config"
+                );
+            }
+            Ok(other) => {
+                return Err(anyhow::anyhow!(
+                    "Expected Full config on startup, got {:?}",
+                    other
+                ));
+            }
+            Err(e) => return Err(anyhow::anyhow!("Failed to receive initial config: {}", e)),
         }
-        Err(e) => return Err(anyhow::anyhow!("Failed to receive initial config: {}", e)),
     };
     debug!("Received initial configuration");
 
+    Ok((config_rx, status_tx, initial_config))
+}
+
+pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
+    if super::wayland_backend::selected() {
+        return super::wayland_backend::run(ipc_server_name).await;
+    }
+
+    // 1. Initialize X11 connection and resources
+    let (conn, _screen_num, atoms, formats, monitors) = match initialize_x11() {
+        Ok(x11) => x11,
+        Err(e) if super::wayland_backend::fallback_available() => {
+            warn!(error = ?e, "X11 previews unavailable, falling back to the Wayland backend");
+            return super::wayland_backend::run(ipc_server_name).await;
+        }
+        Err(e) => return Err(e.context("Failed to initialize X11")),
+    };
+
+    // Re-acquire screen reference from connection (x11rb::connect returns screen index)
+    let screen = &conn.setup().roots[_screen_num];
+
+    // 2. Setup IPC and get initial config
+    let (config_rx, status_tx, initial_config) = connect_manager(ipc_server_name)?;
+
     // 3. Initialize State from Config
     let (mut daemon_config, config, mut session_state, mut cycle_state) =
         initialize_state(screen, initial_config).context("Failed to initialize state")?;
@@ -887,9 +1925,13 @@ pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
 
     debug!("Registered SIGUSR1 handler for manual position save");
 
+    let sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .context("Failed to register SIGTERM handler")?;
+
     // 4. Setup Hotkeys
     let allowed_windows = Arc::new(RwLock::new(HashSet::new()));
-    let hotkeys = setup_hotkeys(&daemon_config, allowed_windows.clone());
+    let paused_flag: PausedFlag = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let hotkeys = setup_hotkeys(&daemon_config, allowed_windows.clone(), paused_flag.clone());
 
     // 5. Initialize Font Renderer
     // This depends on config so it runs after config load
@@ -915,6 +1957,7 @@ pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
             screen,
             atoms: &atoms,
             formats: &formats,
+            monitors: &monitors,
         };
 
         // Initial scan for existing EVE windows
@@ -928,13 +1971,19 @@ pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
             &mut cycle_state,
         )
         .context("Failed to get initial list of EVE windows")?;
+        stacking::restack(&eve_clients, &config);
     }
 
     // Initialize border state for all windows (defaults to inactive/cleared)
     // This ensures inactive borders are drawn immediately on startup if enabled
-    let active_eve_window = crate::x11::get_active_eve_window(&conn, screen, &atoms)
-        .ok()
-        .flatten();
+    let active_eve_window = crate::x11::get_active_eve_window(
+        &conn,
+        screen,
+        &atoms,
+        &daemon_config.profile.client_detection_rules,
+    )
+    .ok()
+    .flatten();
 
     for (window, thumbnail) in eve_clients.iter_mut() {
         // Check if this window currently has focus
@@ -974,24 +2023,96 @@ pub async fn run_daemon(ipc_server_name: String) -> Result<()> {
         config.clone(),
         &atoms,
         &formats,
+        &monitors,
         font_renderer,
         resources,
         hotkeys.rx,
         hotkeys.groups,
         sigusr1,
+        sigterm,
         config_rx,
-        status_tx,
+        StatusSender::new(status_tx),
         allowed_windows,
+        paused_flag,
     )
     .await
 }
 
+/// Shows the OSD for `request` if the profile asks for it
+fn show_osd(
+    osd: &mut super::osd::SwitchOsd,
+    ctx: &AppContext,
+    resources: &DaemonResources,
+    request: OsdRequest,
+    font_renderer: &crate::daemon::font::FontRenderer,
+) {
+    let mode = resources.config.profile.client_switch_osd;
+    if mode == SwitchOsdMode::Off {
+        return;
+    }
+    let (target, text) = match request {
+        OsdRequest::Switch(window) => {
+            let Some(thumbnail) = resources.eve_clients.get(&window) else {
+                return;
+            };
+            let text = if thumbnail.character_name.is_empty() {
+                super::osd::switch_text(eve::LOGGED_OUT_DISPLAY_NAME, None)
+            } else {
+                super::osd::switch_text(
+                    &thumbnail.character_name,
+                    resources.cycle.position_in_group(&thumbnail.character_name),
+                )
+            };
+            (Some(window), text)
+        }
+        OsdRequest::Announce(text) => (None, text),
+    };
+    if let Err(e) = osd.show(ctx, mode, target, &text, font_renderer) {
+        warn!(error = %e, text = %text, "Failed to show OSD");
+    }
+}
+
+/// Shows the hover tooltip for the thumbnail under the pointer, or hides it when there is none
+/// (or the profile has tooltips off)
+fn sync_hover_tooltip(
+    tooltip: &mut super::tooltip::HoverTooltip,
+    ctx: &AppContext,
+    resources: &DaemonResources,
+    display_config: &crate::config::DisplayConfig,
+    font_renderer: &crate::daemon::font::FontRenderer,
+) {
+    let hovered = resources
+        .session
+        .hovered
+        .filter(|_| {
+            resources.config.profile.thumbnail_hover_tooltip && resources.session.overview.is_none()
+        })
+        .and_then(|window| resources.eve_clients.get(&window))
+        .filter(|thumbnail| thumbnail.is_visible() && !thumbnail.input_state.dragging);
+    let Some(thumbnail) = hovered else {
+        tooltip.hide();
+        return;
+    };
+
+    let name = &thumbnail.character_name;
+    let lines = super::tooltip::tooltip_lines(
+        name,
+        display_config.account_tags.get(name).map(String::as_str),
+        thumbnail.state.is_minimized(),
+        resources.cycle.position_in_group(name),
+    );
+    if let Err(e) = tooltip.show(ctx, lines, thumbnail.rect(), font_renderer) {
+        warn!(character = %name, error = %e, "Failed to show hover tooltip");
+        tooltip.hide();
+    }
+}
+
 fn handle_cycle_command(
     command: &CycleCommand,
     resources: &mut DaemonResources<'_>,
     ctx: &AppContext<'_>,
     font_renderer: &crate::daemon::font::FontRenderer,
-    status_tx: &IpcSender<DaemonMessage>,
+    status_tx: &StatusSender,
     hotkey_groups: &HashMap<crate::config::HotkeyBinding, Vec<String>>,
 ) -> Option<(Window, String)> {
     // Build logged-out map if feature is enabled in profile
@@ -1050,25 +2171,31 @@ fn handle_cycle_command(
 
             if let Some(profile_name) = resources.config.profile_hotkeys.get(binding) {
                 info!(target_profile = %profile_name, "Requesting profile switch via IPC");
-                if let Err(e) =
-                    status_tx.send(DaemonMessage::RequestProfileSwitch(profile_name.clone()))
-                {
-                    error!(error = %e, "Failed to send profile switch request to Manager");
-                }
+                status_tx.send(DaemonMessage::RequestProfileSwitch(profile_name.clone()));
             }
             None
         }
         CycleCommand::ToggleSkip => {
             // Identify focused window to determine which character to skip
-            let active_window = crate::x11::get_active_eve_window(ctx.conn, ctx.screen, ctx.atoms)
-                .ok()
-                .flatten();
+            let active_window = crate::x11::get_active_eve_window(
+                ctx.conn,
+                ctx.screen,
+                ctx.atoms,
+                &resources.config.profile.client_detection_rules,
+            )
+            .ok()
+            .flatten();
 
             if let Some(window) = active_window {
                 if let Some(thumbnail) = resources.eve_clients.get_mut(&window) {
                     let char_name = thumbnail.character_name.clone();
                     let is_skipped = resources.cycle.toggle_skip(&char_name);
                     info!(character = %char_name, skipped = is_skipped, "Toggled skip status");
+                    resources.session.osd_request = Some(OsdRequest::Announce(if is_skipped {
+                        format!("{} skipped in cycles", char_name)
+                    } else {
+                        format!("{} back in cycles", char_name)
+                    }));
 
                     // Force redraw of border to show/hide indicator
                     let focused = thumbnail.state.is_focused();
@@ -1092,27 +2219,427 @@ fn handle_cycle_command(
                 hidden = resources.config.runtime_hidden,
                 "Toggled previews visibility"
             );
+            resources.session.osd_request = Some(OsdRequest::Announce(
+                if resources.config.runtime_hidden {
+                    "Previews hidden"
+                } else {
+                    "Previews shown"
+                }
+                .to_string(),
+            ));
+
+            apply_runtime_hidden(resources, font_renderer);
+            None
+        }
+        CycleCommand::PeekStart => {
+            if resources.session.peek.is_some() || resources.session.overview.is_some() {
+                return None;
+            }
+
+            // Peek into the group that was cycled last, falling back to the first group
+            let group = resources
+                .cycle
+                .last_active_group()
+                .map(str::to_string)
+                .or_else(|| {
+                    resources
+                        .config
+                        .profile
+                        .cycle_groups
+                        .first()
+                        .map(|g| g.name.clone())
+                });
+            let target = group.and_then(|group| {
+                resources.cycle.peek_forward(
+                    &group,
+                    logged_out_map,
+                    resources.config.profile.hotkey_cycle_reset_index,
+                )
+            });
+
+            let Some((window, character_name)) = target else {
+                debug!("Peek hotkey pressed but there is no next character to show");
+                return None;
+            };
 
-            // Force visibility update for all known thumbnails
             let display_config = resources.config.build_display_config();
+            end_hover_zoom(resources, &display_config, font_renderer);
+            if let Some(thumbnail) = resources.eve_clients.get_mut(&window) {
+                let skipped = resources.cycle.is_skipped(&character_name);
+                match peek::start(
+                    thumbnail,
+                    resources.config.profile.hotkey_peek_scale,
+                    ctx.screen,
+                    &display_config,
+                    skipped,
+                    font_renderer,
+                ) {
+                    Ok(saved) => {
+                        info!(character = %character_name, "Peeking at next character");
+                        resources.session.peek = Some((window, saved));
+                    }
+                    Err(e) => {
+                        warn!(character = %character_name, error = %e, "Failed to enlarge thumbnail for peek");
+                    }
+                }
+            }
+            None
+        }
+        CycleCommand::PeekEnd => {
+            let display_config = resources.config.build_display_config();
+            end_peek(resources, &display_config, font_renderer);
+            None
+        }
+        CycleCommand::RevealStart => {
+            let display_config = resources.config.build_display_config();
+            if !display_config.hide_when_no_focus || resources.config.runtime_hidden {
+                return None;
+            }
+
+            // Hold off any pending or in-progress hide until the key is released
+            resources.session.focus_loss_deadline = None;
+            let faded = resources.session.focus_loss_fade.take().is_some();
+            resources.session.reveal_active = true;
+            debug!("Revealing thumbnails while the reveal hotkey is held");
+
             for thumbnail in resources.eve_clients.values_mut() {
-                // When revealing, respect per-character overrides: force-hidden thumbnails stay hidden
+                if faded && let Err(e) = thumbnail.set_opacity(display_config.opacity) {
+                    warn!(character = %thumbnail.character_name, error = %e, "Failed to restore thumbnail opacity");
+                }
+                if thumbnail.is_visible() {
+                    continue;
+                }
+
+                // Respect per-character override: don't reveal force-hidden thumbnails
                 let should_render = display_config
                     .character_settings
                     .get(&thumbnail.character_name)
                     .and_then(|s| s.override_render_preview)
                     .unwrap_or(display_config.enabled);
+                if !should_render {
+                    continue;
+                }
 
-                let target_visible = !resources.config.runtime_hidden && should_render;
-
-                if let Err(e) = thumbnail.visibility(target_visible) {
-                    warn!(character = %thumbnail.character_name, error = %e, "Failed to update visibility after toggle");
-                } else if target_visible {
-                    // Force update to ensure content is drawn if revealed
+                if let Err(e) = thumbnail.visibility(true) {
+                    warn!(character = %thumbnail.character_name, error = %e, "Failed to reveal thumbnail");
+                } else {
                     let _ = thumbnail.update(&display_config, font_renderer);
                 }
             }
+            stacking::restack(&resources.eve_clients, &display_config);
+            None
+        }
+        CycleCommand::RevealEnd => {
+            if !std::mem::take(&mut resources.session.reveal_active) {
+                return None;
+            }
+
+            // Hide again through the usual path, unless a client was focused while revealed
+            let client_focused = crate::x11::get_active_window(ctx.conn, ctx.screen, ctx.atoms)
+                .ok()
+                .flatten()
+                .is_some_and(|window| resources.eve_clients.contains_key(&window));
+            if !client_focused {
+                resources.session.focus_loss_deadline = Some(std::time::Instant::now());
+            }
+            None
+        }
+        CycleCommand::Panic => {
+            let display_config = resources.config.build_display_config();
+            end_peek(resources, &display_config, font_renderer);
+            end_hover_zoom(resources, &display_config, font_renderer);
+            overview::exit(
+                &mut resources.eve_clients,
+                &mut resources.session,
+                &resources.cycle,
+                &display_config,
+                font_renderer,
+            );
+
+            if let Some(snapshot) = resources.session.panic.take() {
+                resources.config.runtime_hidden = snapshot.runtime_hidden;
+                apply_runtime_hidden(resources, font_renderer);
+                panic::restore(ctx, &snapshot);
+            } else {
+                let mut windows: Vec<Window> = resources.eve_clients.keys().copied().collect();
+                for window in resources.cycle.get_active_windows().values() {
+                    if !windows.contains(window) {
+                        windows.push(*window);
+                    }
+                }
+                let snapshot = panic::engage(
+                    ctx,
+                    &windows,
+                    &resources.config.profile.client_detection_rules,
+                    &resources.config.profile.hotkey_panic_decoy_class,
+                    resources.config.runtime_hidden,
+                );
+                resources.config.runtime_hidden = true;
+                apply_runtime_hidden(resources, font_renderer);
+                resources.session.panic = Some(snapshot);
+            }
             None
         }
+        CycleCommand::TogglePause => {
+            set_paused(
+                resources,
+                !resources.session.paused,
+                font_renderer,
+                status_tx,
+            );
+            resources.session.osd_request = Some(OsdRequest::Announce(
+                if resources.session.paused {
+                    "Paused"
+                } else {
+                    "Resumed"
+                }
+                .to_string(),
+            ));
+            None
+        }
+        CycleCommand::SuspendHotkeys => {
+            let now = std::time::Instant::now();
+            if resources.session.hotkeys_suspended(now) {
+                resources.session.hotkeys_suspended_until = None;
+                info!("Hotkeys resumed before the suspension ran out");
+                resources.session.osd_request =
+                    Some(OsdRequest::Announce("Hotkeys resumed".to_string()));
+            } else {
+                let secs = resources.config.profile.hotkey_suspend_secs;
+                resources.session.hotkeys_suspended_until =
+                    Some(now + std::time::Duration::from_secs(secs.into()));
+                info!(seconds = secs, "Hotkeys suspended");
+                resources.session.osd_request = Some(OsdRequest::Announce(format!(
+                    "Hotkeys suspended for {}s",
+                    secs
+                )));
+            }
+            None
+        }
+        CycleCommand::FlipPrevious => resources.cycle.flip_previous(),
+        CycleCommand::ToggleOverview => {
+            let display_config = resources.config.build_display_config();
+            end_hover_zoom(resources, &display_config, font_renderer);
+            overview::toggle(
+                &mut resources.eve_clients,
+                &mut resources.session,
+                &resources.cycle,
+                ctx.screen,
+                &display_config,
+                font_renderer,
+            );
+            None
+        }
+    }
+}
+
+/// Runs the profile's startup actions (layout, minimize, focus, previews)
+fn run_startup_actions(
+    resources: &mut DaemonResources<'_>,
+    ctx: &AppContext<'_>,
+    display_config: &crate::config::DisplayConfig,
+    font_renderer: &crate::daemon::font::FontRenderer,
+    status_tx: &StatusSender,
+) {
+    let actions = resources.config.profile.startup_actions.clone();
+
+    if !actions.apply_layout.is_empty() {
+        match resources
+            .config
+            .profile
+            .layout(&actions.apply_layout)
+            .cloned()
+        {
+            Some(layout) => startup::apply_layout(
+                &layout,
+                &mut resources.eve_clients,
+                &mut resources.config,
+                display_config,
+                font_renderer,
+                status_tx,
+            ),
+            None => warn!(layout = %actions.apply_layout, "Startup layout not found"),
+        }
+    }
+
+    let windows = resources.cycle.get_active_windows().clone();
+    if !actions.minimize_all_except.is_empty() {
+        startup::minimize_all_except(
+            &mut resources.session.minimize_queue,
+            &windows,
+            &actions.minimize_all_except,
+        );
+    }
+    if !actions.focus_character.is_empty()
+        && let Some(window) = startup::focus_character(ctx, &windows, &actions.focus_character)
+    {
+        resources.cycle.set_current_by_window(window);
+    }
+
+    if let Some(show) = actions.previews {
+        resources.config.runtime_hidden = !show;
+        apply_runtime_hidden(resources, font_renderer);
+    }
+}
+
+/// Pauses or resumes the daemon and tells the Manager (which mirrors the state in its UI)
+fn set_paused(
+    resources: &mut DaemonResources<'_>,
+    paused: bool,
+    font_renderer: &crate::daemon::font::FontRenderer,
+    status_tx: &StatusSender,
+) {
+    if resources.session.paused == paused {
+        return;
+    }
+    if paused {
+        let display_config = resources.config.build_display_config();
+        end_peek(resources, &display_config, font_renderer);
+        end_hover_zoom(resources, &display_config, font_renderer);
+        overview::exit(
+            &mut resources.eve_clients,
+            &mut resources.session,
+            &resources.cycle,
+            &display_config,
+            font_renderer,
+        );
+        resources.session.reveal_active = false;
+    }
+    resources.session.paused = paused;
+    apply_runtime_hidden(resources, font_renderer);
+    info!(paused = paused, "Daemon pause state changed");
+    status_tx.send(DaemonMessage::PausedChanged(paused));
+}
+
+/// Shows or hides every thumbnail to match `runtime_hidden` (toggle hotkey / visibility schedule)
+/// and the pause state, keeping always-visible ones shown unless paused
+fn apply_runtime_hidden(
+    resources: &mut DaemonResources<'_>,
+    font_renderer: &crate::daemon::font::FontRenderer,
+) {
+    let display_config = resources.config.build_display_config();
+    for thumbnail in resources.eve_clients.values_mut() {
+        // When revealing, respect per-character overrides: force-hidden thumbnails stay hidden
+        let should_render = display_config
+            .character_settings
+            .get(&thumbnail.character_name)
+            .and_then(|s| s.override_render_preview)
+            .unwrap_or(display_config.enabled);
+
+        // Always-visible characters (e.g. a scout) survive the blanket hide
+        let hidden = resources.config.runtime_hidden
+            && !display_config.always_visible(&thumbnail.character_name);
+        let target_visible = !hidden && !resources.session.paused && should_render;
+
+        if let Err(e) = thumbnail.visibility(target_visible) {
+            warn!(character = %thumbnail.character_name, error = %e, "Failed to update visibility after toggle");
+        } else if target_visible {
+            // Force update to ensure content is drawn if revealed
+            let _ = thumbnail.update(&display_config, font_renderer);
+        }
     }
+    stacking::restack(&resources.eve_clients, &display_config);
+}
+
+/// Restores the thumbnail enlarged by the peek hotkey (if any)
+fn end_peek(
+    resources: &mut DaemonResources<'_>,
+    display_config: &crate::config::DisplayConfig,
+    font_renderer: &crate::daemon::font::FontRenderer,
+) {
+    let Some((window, saved)) = resources.session.peek.take() else {
+        return;
+    };
+    let Some(thumbnail) = resources.eve_clients.get_mut(&window) else {
+        return;
+    };
+
+    let skipped = resources.cycle.is_skipped(&thumbnail.character_name);
+    if let Err(e) = peek::end(thumbnail, saved, display_config, skipped, font_renderer) {
+        warn!(character = %thumbnail.character_name, error = %e, "Failed to restore thumbnail after peek");
+    } else {
+        debug!(character = %thumbnail.character_name, "Peek ended");
+    }
+    stacking::restack(&resources.eve_clients, display_config);
+}
+
+/// Restores the thumbnail enlarged by hovering (if any)
+fn end_hover_zoom(
+    resources: &mut DaemonResources<'_>,
+    display_config: &crate::config::DisplayConfig,
+    font_renderer: &crate::daemon::font::FontRenderer,
+) {
+    if resources.session.hover_zoom.is_none() {
+        return;
+    }
+    hover_zoom::end(
+        &mut resources.eve_clients,
+        &mut resources.session,
+        &resources.cycle,
+        display_config,
+        font_renderer,
+    );
+    stacking::restack(&resources.eve_clients, display_config);
+}
+
+/// PNG of a tracked client's current contents (served for `ConfigMessage::CaptureFrame`)
+fn capture_client_png(
+    conn: &RustConnection,
+    resources: &DaemonResources<'_>,
+    character: &str,
+) -> Result<Vec<u8>> {
+    let window = resources
+        .eve_clients
+        .iter()
+        .find(|(_, thumbnail)| thumbnail.character_name == character)
+        .map(|(window, _)| *window)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not running", character))?;
+    let frame = crate::x11::capture_frame(conn, window)?
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not visible (minimized or unmapped)", character))?;
+    frame
+        .encode_png()
+        .context(format!("Failed to encode frame of '{}'", character))
+}
+
+/// Snapshot of every named client and where its thumbnail sits on screen.
+///
+/// While the overview, a peek or a hover zoom is active, the geometry remembered before it took
+/// over is reported instead of the temporary one.
+fn client_layout(resources: &DaemonResources<'_>) -> Vec<ClientGeometry> {
+    resources
+        .eve_clients
+        .iter()
+        .filter(|(_, t)| !t.character_name.is_empty())
+        .map(|(window, thumbnail)| {
+            let saved = resources
+                .session
+                .overview
+                .as_ref()
+                .and_then(|saved| saved.get(window))
+                .or(resources
+                    .session
+                    .peek
+                    .as_ref()
+                    .filter(|(w, _)| w == window)
+                    .map(|(_, saved)| saved))
+                .or(resources
+                    .session
+                    .hover_zoom
+                    .as_ref()
+                    .filter(|(w, _)| w == window)
+                    .map(|(_, saved)| saved));
+            let (position, dimensions) = saved
+                .map(|s| (s.position, s.dimensions))
+                .unwrap_or((thumbnail.current_position, thumbnail.dimensions));
+
+            ClientGeometry {
+                name: thumbnail.character_name.clone(),
+                is_custom: thumbnail.is_custom,
+                x: position.x,
+                y: position.y,
+                width: dimensions.width,
+                height: dimensions.height,
+            }
+        })
+        .collect()
 }
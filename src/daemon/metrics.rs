@@ -0,0 +1,172 @@
+//! Lightweight internal counters reported to the Manager with each heartbeat
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+use tracing::debug;
+use x11rb::x11_utils::X11Error;
+
+use crate::common::ipc::{DaemonMetrics, XErrorCount};
+
+/// Things the daemon counts
+#[derive(Debug, Clone, Copy)]
+pub enum Counter {
+    Event,
+    Composite,
+    XError,
+    IpcReceived,
+    IpcSent,
+}
+
+static COUNTERS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+
+/// Counts one occurrence
+pub fn record(counter: Counter) {
+    COUNTERS[counter as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+fn total(counter: Counter) -> u64 {
+    COUNTERS[counter as usize].load(Ordering::Relaxed)
+}
+
+/// Asynchronous X errors since the last report, by (error kind, request)
+static X_ERRORS: LazyLock<Mutex<HashMap<(String, String), u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Counts an X error that arrived in the event queue (errors of unchecked requests)
+pub fn record_x_error(error: &X11Error) {
+    record(Counter::XError);
+    let request = match (&error.extension_name, error.request_name) {
+        (Some(extension), Some(name)) => format!("{extension}::{name}"),
+        (None, Some(name)) => name.to_string(),
+        _ => format!("opcode {}.{}", error.major_opcode, error.minor_opcode),
+    };
+    let kind = format!("{:?}", error.error_kind);
+    debug!(error = %kind, request = %request, bad_value = error.bad_value, "X error");
+    *X_ERRORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry((kind, request))
+        .or_default() += 1;
+}
+
+/// Takes the X errors recorded since the last call, most frequent first
+pub fn drain_x_errors() -> Vec<XErrorCount> {
+    let mut errors: Vec<XErrorCount> = X_ERRORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .map(|((error, request), count)| XErrorCount {
+            error,
+            request,
+            count,
+        })
+        .collect();
+    errors.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.request.cmp(&b.request))
+    });
+    errors
+}
+
+/// Turns the counters into samples with per-second rates
+pub struct Sampler {
+    started: Instant,
+    last_at: Instant,
+    last_events: u64,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            last_at: now,
+            last_events: total(Counter::Event),
+        }
+    }
+
+    pub fn sample(&mut self) -> DaemonMetrics {
+        let now = Instant::now();
+        let events = total(Counter::Event);
+        let elapsed = now.duration_since(self.last_at).as_secs_f64();
+        let events_per_sec = if elapsed > 0.0 {
+            (events - self.last_events) as f64 / elapsed
+        } else {
+            0.0
+        };
+        self.last_at = now;
+        self.last_events = events;
+
+        DaemonMetrics {
+            uptime_secs: now.duration_since(self.started).as_secs(),
+            events_total: events,
+            events_per_sec,
+            composite_ops_total: total(Counter::Composite),
+            x_errors_total: total(Counter::XError),
+            ipc_received_total: total(Counter::IpcReceived),
+            ipc_sent_total: total(Counter::IpcSent),
+            resident_memory_bytes: resident_memory_bytes(),
+        }
+    }
+}
+
+/// Resident set size from the `VmRSS` line of `/proc/self/status`
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x11rb::protocol::ErrorKind;
+
+    fn x_error(error_kind: ErrorKind, request_name: &'static str) -> X11Error {
+        X11Error {
+            error_kind,
+            error_code: 0,
+            sequence: 0,
+            bad_value: 0,
+            minor_opcode: 0,
+            major_opcode: 0,
+            extension_name: None,
+            request_name: Some(request_name),
+        }
+    }
+
+    #[test]
+    fn test_x_errors_grouped_and_drained() {
+        record_x_error(&x_error(ErrorKind::Window, "GetProperty"));
+        record_x_error(&x_error(ErrorKind::Window, "GetProperty"));
+        record_x_error(&x_error(ErrorKind::Drawable, "GetGeometry"));
+
+        let errors = drain_x_errors();
+        assert_eq!(
+            errors,
+            vec![
+                XErrorCount {
+                    error: "Window".to_string(),
+                    request: "GetProperty".to_string(),
+                    count: 2,
+                },
+                XErrorCount {
+                    error: "Drawable".to_string(),
+                    request: "GetGeometry".to_string(),
+                    count: 1,
+                },
+            ]
+        );
+        assert!(drain_x_errors().is_empty());
+    }
+}
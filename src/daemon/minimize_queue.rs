@@ -0,0 +1,221 @@
+//! Paced minimizing for minimize-on-switch
+//!
+//! Minimizing every other client at once sends a burst of ClientMessages that some window
+//! managers only partly act on, leaving clients half-minimized. Requests are sent a few at a
+//! time instead, and each client is checked again a little later and re-sent a bounded number
+//! of times until the window manager reports it minimized.
+//!
+//! The queue also remembers the last switch, so alt-restore can leave the client the user came
+//! from up when they switch back to one that was minimized on switch.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use x11rb::protocol::xproto::Window;
+
+use crate::common::constants::defaults::timing::{
+    MINIMIZE_BATCH_INTERVAL_MS, MINIMIZE_BATCH_SIZE, MINIMIZE_MAX_ATTEMPTS,
+    MINIMIZE_VERIFY_DELAY_MS,
+};
+use crate::x11::{AppContext, is_window_minimized, minimize_window};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pending {
+    window: Window,
+    /// Minimize requests sent so far
+    attempts: u8,
+    due: Instant,
+}
+
+/// Clients waiting to be minimized or to have their minimize verified
+#[derive(Debug, Default)]
+pub struct MinimizeQueue {
+    pending: VecDeque<Pending>,
+    /// No further batch before this time
+    paced_until: Option<Instant>,
+    /// Client activated by the last switch
+    switched_to: Option<Window>,
+    /// Client alt-restore left up beside `switched_to`
+    spared: Option<Window>,
+    /// Clients minimized by the last switch
+    switched_away: HashSet<Window>,
+}
+
+impl MinimizeQueue {
+    /// Queues `windows` for minimizing, replacing whatever an earlier switch left pending
+    pub fn schedule(&mut self, windows: impl IntoIterator<Item = Window>, now: Instant) {
+        self.pending.clear();
+        for window in windows {
+            if !self.pending.iter().any(|p| p.window == window) {
+                self.pending.push_back(Pending {
+                    window,
+                    attempts: 0,
+                    due: now,
+                });
+            }
+        }
+    }
+
+    /// Queues every window in `others` for minimizing after a switch to `target`.
+    ///
+    /// With `alt_restore`, switching back to a client the last switch minimized (or left up)
+    /// keeps the client switched away from unminimized.
+    pub fn schedule_switch(
+        &mut self,
+        target: Window,
+        mut others: Vec<Window>,
+        alt_restore: bool,
+        now: Instant,
+    ) {
+        let returning = self.switched_away.contains(&target) || self.spared == Some(target);
+        let spared = self
+            .switched_to
+            .filter(|&previous| alt_restore && returning && previous != target);
+        if let Some(previous) = spared {
+            debug!(
+                kept = previous,
+                "Alt-restore: keeping the previous client up"
+            );
+            others.retain(|&w| w != previous);
+        }
+
+        self.switched_to = Some(target);
+        self.spared = spared;
+        self.switched_away = others.iter().copied().collect();
+        self.schedule(others, now);
+    }
+
+    /// Drops a window that should stay visible after all (e.g. it was just activated)
+    pub fn cancel(&mut self, window: Window) {
+        self.pending.retain(|p| p.window != window);
+    }
+
+    /// When `tick` next has work to do
+    pub fn deadline(&self) -> Option<Instant> {
+        let due = self.pending.iter().map(|p| p.due).min()?;
+        Some(self.paced_until.map_or(due, |paced| due.max(paced)))
+    }
+
+    /// Removes up to one batch of due entries and holds off the next batch
+    fn take_batch(&mut self, now: Instant) -> Vec<Pending> {
+        if self.paced_until.is_some_and(|paced| now < paced) {
+            return Vec::new();
+        }
+        let mut batch = Vec::new();
+        let mut idx = 0;
+        while idx < self.pending.len() && batch.len() < MINIMIZE_BATCH_SIZE {
+            if self.pending[idx].due <= now {
+                batch.extend(self.pending.remove(idx));
+            } else {
+                idx += 1;
+            }
+        }
+        if !batch.is_empty() {
+            self.paced_until = Some(now + Duration::from_millis(MINIMIZE_BATCH_INTERVAL_MS));
+        }
+        batch
+    }
+
+    /// Sends the next batch of minimize requests and verifies earlier ones
+    pub fn tick(&mut self, ctx: &AppContext, now: Instant) {
+        let batch = self.take_batch(now);
+        if batch.is_empty() {
+            return;
+        }
+        // No check against _NET_ACTIVE_WINDOW: right after a switch the window manager often
+        // still reports the client switched away from. A client that really gets focus is
+        // dropped from the queue by FocusIn (`cancel`).
+        for entry in batch {
+            if entry.attempts > 0 {
+                if is_window_minimized(ctx.conn, entry.window, ctx.atoms).unwrap_or(true) {
+                    continue;
+                }
+                if entry.attempts >= MINIMIZE_MAX_ATTEMPTS {
+                    warn!(
+                        window = entry.window,
+                        attempts = entry.attempts,
+                        "Window manager did not minimize client, giving up"
+                    );
+                    continue;
+                }
+                debug!(
+                    window = entry.window,
+                    attempt = entry.attempts + 1,
+                    "Client not minimized yet, retrying"
+                );
+            }
+
+            if let Err(e) = minimize_window(ctx.conn, ctx.screen, ctx.atoms, entry.window) {
+                debug!(window = entry.window, error = %e, "Failed to minimize window");
+                continue;
+            }
+            self.pending.push_back(Pending {
+                attempts: entry.attempts + 1,
+                due: now + Duration::from_millis(MINIMIZE_VERIFY_DELAY_MS),
+                ..entry
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches_are_paced() {
+        let now = Instant::now();
+        let mut queue = MinimizeQueue::default();
+        let windows: Vec<Window> = (1..=MINIMIZE_BATCH_SIZE as u32 + 2).collect();
+        queue.schedule(windows.iter().copied().chain([1]), now);
+        assert_eq!(queue.pending.len(), windows.len());
+        assert_eq!(queue.deadline(), Some(now));
+
+        let first = queue.take_batch(now);
+        assert_eq!(first.len(), MINIMIZE_BATCH_SIZE);
+        assert_eq!(first[0].window, 1);
+
+        // The rest waits for the batch interval
+        let next = now + Duration::from_millis(MINIMIZE_BATCH_INTERVAL_MS);
+        assert!(queue.take_batch(now).is_empty());
+        assert_eq!(queue.deadline(), Some(next));
+        assert_eq!(queue.take_batch(next).len(), 2);
+        assert_eq!(queue.deadline(), None);
+    }
+
+    #[test]
+    fn test_alt_restore_toggles_between_two_clients() {
+        let now = Instant::now();
+        let queued = |queue: &MinimizeQueue| {
+            let mut windows: Vec<Window> = queue.pending.iter().map(|p| p.window).collect();
+            windows.sort();
+            windows
+        };
+        let mut queue = MinimizeQueue::default();
+
+        queue.schedule_switch(1, vec![2, 3], true, now);
+        assert_eq!(queued(&queue), vec![2, 3]);
+        // Back to a minimized client: the one switched away from stays up
+        queue.schedule_switch(2, vec![1, 3], true, now);
+        assert_eq!(queued(&queue), vec![3]);
+        queue.schedule_switch(1, vec![2, 3], true, now);
+        assert_eq!(queued(&queue), vec![3]);
+        // Onward to a third client: only the last one stays up
+        queue.schedule_switch(3, vec![1, 2], true, now);
+        assert_eq!(queued(&queue), vec![2]);
+
+        queue.schedule_switch(1, vec![2, 3], false, now);
+        assert_eq!(queued(&queue), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_schedule_replaces_and_cancel_drops() {
+        let now = Instant::now();
+        let mut queue = MinimizeQueue::default();
+        queue.schedule([1, 2, 3], now);
+        queue.schedule([2, 3], now);
+        queue.cancel(3);
+        let windows: Vec<Window> = queue.pending.iter().map(|p| p.window).collect();
+        assert_eq!(windows, vec![2]);
+    }
+}
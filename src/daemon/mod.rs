@@ -1,18 +1,41 @@
 //! Daemon main loop and runtime initialization
 
+mod capture;
 mod cycle_state;
 mod dispatcher;
 pub mod font;
 mod main_loop;
+mod metrics;
+mod minimize_queue;
 
 pub mod handlers;
+mod hover_zoom;
+mod icons;
+mod osd;
 mod overlay;
+mod overview;
+mod panic;
+mod peek;
+mod pointer_warp;
 mod renderer;
+pub mod roundtrip_audit;
 mod session_state;
+mod shape;
 mod snapping;
+mod snapshot;
+mod speech;
+mod stacking;
+mod startup;
+mod status_sender;
 mod thumbnail;
+mod timelapse;
+mod tooltip;
+mod wayland_backend;
 pub mod window_detection;
+mod wine_prefix;
 
 pub use crate::input::listener::list_input_devices;
 pub use font::{list_fonts, select_best_default_font};
 pub use main_loop::run_daemon;
+pub use overlay::{BorderState, EguiOverlay, OverlayBackend};
+pub use wine_prefix::detect as detect_wine_prefix;
@@ -0,0 +1,560 @@
+//! Switch feedback OSD
+//!
+//! When a client is activated by hotkey or click, a short-lived toast ("→ Character [3/7]")
+//! appears next to the pointer or in the middle of the client's monitor, then fades out. State
+//! changes with no immediate visual effect (profile switch, previews toggled, pause, hotkey
+//! suspension, skip) are announced the same way. It is an override-redirect ARGB window whose
+//! background pixmap holds the finished drawing, so the X server repaints it without our help;
+//! clicks pass through it to whatever is below.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+use x11rb::connection::Connection;
+use x11rb::protocol::render::{Color, ConnectionExt as RenderExt, CreatePictureAux, PictOp};
+use x11rb::protocol::shape::{ConnectionExt as ShapeExt, SK, SO};
+use x11rb::protocol::xproto::*;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+use super::font::{FontRenderer, TextDecoration};
+use super::snapping::{self, Rect};
+use crate::common::constants::{osd, x11};
+use crate::common::types::{Dimensions, Position};
+use crate::config::SwitchOsdMode;
+use crate::x11::AppContext;
+
+/// What the OSD is asked to show next
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OsdRequest {
+    /// Client just activated by hotkey or click
+    Switch(Window),
+    /// Confirmation of a state change, shown as is
+    Announce(String),
+}
+
+/// OSD text for a switch to `name`, at `position` (1-based index, count) in its cycle group
+pub fn switch_text(name: &str, position: Option<(usize, usize)>) -> String {
+    match position {
+        Some((index, count)) => format!("→ {} [{}/{}]", name, index, count),
+        None => format!("→ {}", name),
+    }
+}
+
+/// How long the OSD fades out: not at all on a remote X connection, where every opacity step
+/// is a network round trip
+fn fade_duration() -> Duration {
+    if crate::x11::display_is_remote() {
+        Duration::ZERO
+    } else {
+        Duration::from_millis(osd::FADE_MS)
+    }
+}
+
+/// Opacity `elapsed` after the OSD appeared, or None once it has faded out over `fade`
+fn osd_opacity(elapsed: Duration, fade: Duration) -> Option<u32> {
+    let visible = Duration::from_millis(osd::VISIBLE_MS);
+    if elapsed < visible {
+        return Some(u32::MAX);
+    }
+    let fading = elapsed - visible;
+    if fading >= fade {
+        return None;
+    }
+    let remaining = (fade - fading).as_millis() as u64;
+    Some((u64::from(u32::MAX) * remaining / fade.as_millis() as u64) as u32)
+}
+
+/// Top-left corner of an OSD of `size`: offset from the pointer, or centered on `monitor`;
+/// either way inside `monitor`
+fn osd_position(
+    mode: SwitchOsdMode,
+    pointer: Position,
+    size: Dimensions,
+    monitor: Rect,
+) -> Position {
+    match mode {
+        SwitchOsdMode::Center => snapping::spawn_position(
+            Rect {
+                x: monitor.x + (monitor.width as i16 - size.width as i16) / 2,
+                y: monitor.y + (monitor.height as i16 - size.height as i16) / 2,
+                width: size.width,
+                height: size.height,
+            },
+            size,
+            monitor,
+            0,
+        ),
+        SwitchOsdMode::Off | SwitchOsdMode::Cursor => snapping::spawn_position(
+            Rect {
+                x: pointer.x,
+                y: pointer.y,
+                width: 0,
+                height: 0,
+            },
+            size,
+            monitor,
+            osd::CURSOR_OFFSET,
+        ),
+    }
+}
+
+/// Premultiplies the color channels of an ARGB value by its alpha
+fn premultiply(argb: u32) -> u32 {
+    let alpha = argb >> 24;
+    let channel = |shift: u32| (((argb >> shift) & 0xFF) * alpha / 0xFF) << shift;
+    (alpha << 24) | channel(16) | channel(8) | channel(0)
+}
+
+/// Converts an ARGB color to a premultiplied RENDER color
+fn render_color(argb: u32) -> Color {
+    let premultiplied = premultiply(argb);
+    let channel = |shift: u32| (((premultiplied >> shift) & 0xFF) * 0x101) as u16;
+    Color {
+        red: channel(16),
+        green: channel(8),
+        blue: channel(0),
+        alpha: channel(24),
+    }
+}
+
+/// The one switch OSD; showing it again replaces the previous toast
+pub struct SwitchOsd<'a> {
+    conn: &'a RustConnection,
+    shown: Option<Shown>,
+}
+
+struct Shown {
+    window: Window,
+    colormap: Colormap,
+    since: Instant,
+}
+
+impl<'a> SwitchOsd<'a> {
+    pub fn new(conn: &'a RustConnection) -> Self {
+        Self { conn, shown: None }
+    }
+
+    /// Shows `text`, replacing any OSD still up. Centered OSDs go on the monitor of the client
+    /// `target` (a switch), else on the pointer's monitor.
+    pub fn show(
+        &mut self,
+        ctx: &AppContext,
+        mode: SwitchOsdMode,
+        target: Option<Window>,
+        text: &str,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.hide();
+        if mode == SwitchOsdMode::Off {
+            return Ok(());
+        }
+        let Some(visual) = argb_visual(ctx.screen) else {
+            warn!("X server offers no 32-bit visual, switch OSD disabled");
+            return Ok(());
+        };
+
+        let pointer = ctx.conn.query_pointer(ctx.screen.root)?.reply()?;
+        let anchor = match (mode, target) {
+            (SwitchOsdMode::Center, Some(target)) => {
+                let (origin, size) =
+                    crate::x11::get_window_geometry(ctx.conn, ctx.screen.root, target)?;
+                Position::new(
+                    origin.x.saturating_add((size.width / 2) as i16),
+                    origin.y.saturating_add((size.height / 2) as i16),
+                )
+            }
+            _ => Position::new(pointer.root_x, pointer.root_y),
+        };
+        let monitor = ctx.monitors.at(anchor.x, anchor.y).map_or(
+            Rect {
+                x: 0,
+                y: 0,
+                width: ctx.screen.width_in_pixels,
+                height: ctx.screen.height_in_pixels,
+            },
+            Rect::from,
+        );
+
+        let pixmap = ctx.conn.generate_id()?;
+        let size = match draw_panel(ctx, pixmap, &[text], font_renderer) {
+            Ok(size) => size,
+            Err(e) => {
+                let _ = ctx.conn.free_pixmap(pixmap);
+                return Err(e);
+            }
+        };
+        let position = osd_position(
+            mode,
+            Position::new(pointer.root_x, pointer.root_y),
+            size,
+            monitor,
+        );
+
+        let (window, colormap) = create_panel_window(
+            ctx,
+            visual,
+            pixmap,
+            position,
+            size,
+            b"eve-preview-osd\0eve-preview-osd\0",
+        )
+        .context("Failed to create switch OSD window")?;
+        self.shown = Some(Shown {
+            window,
+            colormap,
+            since: Instant::now(),
+        });
+        ctx.conn
+            .map_window(window)
+            .context("Failed to map switch OSD window")?;
+        ctx.conn.flush()?;
+        debug!(text = %text, x = position.x, y = position.y, "Showing switch OSD");
+        Ok(())
+    }
+
+    /// When the OSD next needs an opacity step or removal (None while hidden)
+    pub fn deadline(&self) -> Option<Instant> {
+        let since = self.shown.as_ref()?.since;
+        let visible_until = since + Duration::from_millis(osd::VISIBLE_MS);
+        let now = Instant::now();
+        Some(if now < visible_until {
+            visible_until
+        } else {
+            now + Duration::from_millis(osd::FADE_STEP_MS)
+        })
+    }
+
+    /// Fades the OSD one step, or removes it once the fade is over
+    pub fn tick(&mut self, atoms: &crate::x11::CachedAtoms) {
+        let Some(shown) = &self.shown else {
+            return;
+        };
+        match osd_opacity(shown.since.elapsed(), fade_duration()) {
+            Some(opacity) => {
+                if let Err(e) = self.conn.change_property32(
+                    PropMode::REPLACE,
+                    shown.window,
+                    atoms.net_wm_window_opacity,
+                    AtomEnum::CARDINAL,
+                    &[opacity],
+                ) {
+                    warn!(error = %e, "Failed to fade switch OSD");
+                }
+            }
+            None => self.hide(),
+        }
+        let _ = self.conn.flush();
+    }
+
+    /// Removes the OSD if it is up
+    pub fn hide(&mut self) {
+        if let Some(shown) = self.shown.take() {
+            let _ = self.conn.destroy_window(shown.window);
+            let _ = self.conn.free_colormap(shown.colormap);
+        }
+    }
+}
+
+impl Drop for SwitchOsd<'_> {
+    fn drop(&mut self) {
+        self.hide();
+        let _ = self.conn.flush();
+    }
+}
+
+/// Creates an unmapped click-through override-redirect ARGB window at `position` showing
+/// `pixmap` (which it takes over) as its background. Returns the window and its colormap.
+pub(super) fn create_panel_window(
+    ctx: &AppContext,
+    visual: Visualid,
+    pixmap: Pixmap,
+    position: Position,
+    size: Dimensions,
+    wm_class: &[u8],
+) -> Result<(Window, Colormap)> {
+    let colormap = ctx.conn.generate_id()?;
+    ctx.conn
+        .create_colormap(ColormapAlloc::NONE, colormap, ctx.screen.root, visual)?;
+    let window = ctx.conn.generate_id()?;
+    ctx.conn.create_window(
+        x11::ARGB_DEPTH,
+        window,
+        ctx.screen.root,
+        position.x,
+        position.y,
+        size.width,
+        size.height,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        visual,
+        &CreateWindowAux::new()
+            .override_redirect(x11::OVERRIDE_REDIRECT)
+            .background_pixmap(pixmap)
+            .border_pixel(0)
+            .colormap(colormap),
+    )?;
+    // The window keeps its background; our handle is no longer needed
+    ctx.conn.free_pixmap(pixmap)?;
+
+    // Empty input region: clicks land on whatever is under the panel (not without SHAPE)
+    if super::renderer::has_shape(ctx.conn) {
+        ctx.conn.shape_rectangles(
+            SO::SET,
+            SK::INPUT,
+            ClipOrdering::UNSORTED,
+            window,
+            0,
+            0,
+            &[],
+        )?;
+    }
+    ctx.conn.change_property8(
+        PropMode::REPLACE,
+        window,
+        ctx.atoms.wm_class,
+        AtomEnum::STRING,
+        wm_class,
+    )?;
+    ctx.conn.change_property32(
+        PropMode::REPLACE,
+        window,
+        ctx.atoms.net_wm_pid,
+        AtomEnum::CARDINAL,
+        &[std::process::id()],
+    )?;
+    Ok((window, colormap))
+}
+
+/// Draws `lines`, top to bottom, on the OSD background into a new ARGB pixmap `pixmap` sized to
+/// fit them; returns its size
+pub(super) fn draw_panel(
+    ctx: &AppContext,
+    pixmap: Pixmap,
+    lines: &[&str],
+    font_renderer: &FontRenderer,
+) -> Result<Dimensions> {
+    let rendered = if font_renderer.requires_direct_rendering() {
+        None
+    } else {
+        Some(
+            lines
+                .iter()
+                .map(|line| font_renderer.render_text(line, osd::TEXT_COLOR, TextDecoration::None))
+                .collect::<Result<Vec<_>>>()
+                .context("Failed to render OSD text")?,
+        )
+    };
+    // Core fonts are Latin-1 only
+    let core_lines: Vec<String> = lines.iter().map(|line| line.replace('→', "->")).collect();
+    let line_sizes = match (&rendered, font_renderer.x11_font_id()) {
+        (Some(rendered), _) => rendered
+            .iter()
+            .map(|r| Dimensions::new(r.width as u16, r.height as u16))
+            .collect(),
+        (None, Some(font)) => {
+            let mut sizes = Vec::with_capacity(core_lines.len());
+            for line in &core_lines {
+                let chars: Vec<Char2b> = line
+                    .bytes()
+                    .map(|byte| Char2b {
+                        byte1: 0,
+                        byte2: byte,
+                    })
+                    .collect();
+                let extents = ctx.conn.query_text_extents(font, &chars)?.reply()?;
+                sizes.push(Dimensions::new(
+                    extents.overall_width.max(0) as u16,
+                    (extents.font_ascent + extents.font_descent).max(0) as u16,
+                ));
+            }
+            sizes
+        }
+        (None, None) => Vec::new(),
+    };
+    let size = Dimensions::new(
+        line_sizes.iter().map(|s| s.width).max().unwrap_or(0) + osd::PADDING * 2,
+        line_sizes.iter().map(|s| s.height).sum::<u16>() + osd::PADDING * 2,
+    );
+
+    ctx.conn
+        .create_pixmap(
+            x11::ARGB_DEPTH,
+            pixmap,
+            ctx.screen.root,
+            size.width,
+            size.height,
+        )
+        .context("Failed to create OSD pixmap")?;
+    let picture = ctx.conn.generate_id()?;
+    ctx.conn
+        .render_create_picture(picture, pixmap, ctx.formats.argb, &CreatePictureAux::new())?;
+    ctx.conn.render_fill_rectangles(
+        PictOp::SRC,
+        picture,
+        render_color(osd::BACKGROUND_COLOR),
+        &[Rectangle {
+            x: 0,
+            y: 0,
+            width: size.width,
+            height: size.height,
+        }],
+    )?;
+
+    let padding = osd::PADDING as i16;
+    let gc = ctx.conn.generate_id()?;
+    ctx.conn.create_gc(gc, pixmap, &CreateGCAux::new())?;
+    match (rendered, font_renderer.x11_font_id()) {
+        (Some(rendered), _) => {
+            let mut y = padding;
+            for rendered in rendered {
+                let (width, height) = (rendered.width as u16, rendered.height as u16);
+                if width == 0 || height == 0 {
+                    y += height as i16;
+                    continue;
+                }
+                let text_pixmap = ctx.conn.generate_id()?;
+                ctx.conn
+                    .create_pixmap(x11::ARGB_DEPTH, text_pixmap, pixmap, width, height)?;
+                crate::x11::put_image(
+                    ctx.conn,
+                    text_pixmap,
+                    gc,
+                    width,
+                    height,
+                    x11::ARGB_DEPTH,
+                    &rendered.data,
+                )?;
+                let text_picture = ctx.conn.generate_id()?;
+                ctx.conn.render_create_picture(
+                    text_picture,
+                    text_pixmap,
+                    ctx.formats.argb,
+                    &CreatePictureAux::new(),
+                )?;
+                ctx.conn.render_composite(
+                    PictOp::OVER,
+                    text_picture,
+                    0u32,
+                    picture,
+                    0,
+                    0,
+                    0,
+                    0,
+                    padding,
+                    y,
+                    width,
+                    height,
+                )?;
+                ctx.conn.render_free_picture(text_picture)?;
+                ctx.conn.free_pixmap(text_pixmap)?;
+                y += height as i16;
+            }
+        }
+        (None, Some(font)) => {
+            // Pixel values of a 32-bit pixmap are premultiplied ARGB
+            ctx.conn.change_gc(
+                gc,
+                &ChangeGCAux::new()
+                    .font(font)
+                    .foreground(osd::TEXT_COLOR)
+                    .background(premultiply(osd::BACKGROUND_COLOR)),
+            )?;
+            let mut y = padding;
+            for (line, line_size) in core_lines.iter().zip(&line_sizes) {
+                ctx.conn.image_text8(
+                    pixmap,
+                    gc,
+                    padding,
+                    y + font_renderer.size() as i16,
+                    line.as_bytes(),
+                )?;
+                y += line_size.height as i16;
+            }
+        }
+        _ => {}
+    }
+    ctx.conn.free_gc(gc)?;
+    ctx.conn.render_free_picture(picture)?;
+    Ok(size)
+}
+
+/// A 32-bit TrueColor visual for translucent windows
+pub(super) fn argb_visual(screen: &Screen) -> Option<Visualid> {
+    screen
+        .allowed_depths
+        .iter()
+        .filter(|depth| depth.depth == x11::ARGB_DEPTH)
+        .flat_map(|depth| &depth.visuals)
+        .find(|visual| visual.class == VisualClass::TRUE_COLOR)
+        .map(|visual| visual.visual_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = Rect {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    #[test]
+    fn test_switch_text() {
+        assert_eq!(switch_text("Alice", Some((3, 7))), "→ Alice [3/7]");
+        assert_eq!(switch_text("Alice", None), "→ Alice");
+    }
+
+    #[test]
+    fn test_osd_opacity() {
+        let visible = Duration::from_millis(osd::VISIBLE_MS);
+        let fade = Duration::from_millis(osd::FADE_MS);
+        assert_eq!(osd_opacity(Duration::ZERO, fade), Some(u32::MAX));
+        assert_eq!(
+            osd_opacity(visible - Duration::from_millis(1), fade),
+            Some(u32::MAX)
+        );
+        assert_eq!(osd_opacity(visible + fade / 2, fade), Some(u32::MAX / 2));
+        assert_eq!(osd_opacity(visible + fade, fade), None);
+        // No fade (remote display): gone as soon as the visible time is up
+        assert_eq!(osd_opacity(visible, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_osd_position() {
+        let size = Dimensions::new(200, 50);
+        assert_eq!(
+            osd_position(
+                SwitchOsdMode::Cursor,
+                Position::new(2000, 100),
+                size,
+                MONITOR
+            ),
+            Position::new(2024, 124)
+        );
+        // Pointer near the bottom-right corner: kept on the monitor
+        assert_eq!(
+            osd_position(
+                SwitchOsdMode::Cursor,
+                Position::new(4470, 1430),
+                size,
+                MONITOR
+            ),
+            Position::new(4480 - 200, 1440 - 50)
+        );
+        assert_eq!(
+            osd_position(SwitchOsdMode::Center, Position::new(0, 0), size, MONITOR),
+            Position::new(1920 + 1180, 695)
+        );
+    }
+
+    #[test]
+    fn test_premultiply() {
+        assert_eq!(premultiply(0xFFFFFFFF), 0xFFFFFFFF);
+        assert_eq!(premultiply(0x80FF0000), 0x80800000);
+        assert_eq!(premultiply(0x00FFFFFF), 0);
+    }
+}
@@ -0,0 +1,159 @@
+//! Status badge icon atlas
+//!
+//! Each status badge is an 8x8 one-bit glyph with its own color, drawn on a translucent dark
+//! square and scaled (nearest neighbour) to the configured badge size. Scaled bitmaps are
+//! cached per (badge, size) as premultiplied BGRA ready for X11 upload, like corner icons.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::daemon::icons::IconBitmap;
+
+/// Client state shown as a small icon on the thumbnail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusBadge {
+    Minimized,
+    /// Skipped by cycle hotkeys
+    Skipped,
+    /// Has a per-character hotkey
+    HotkeyBound,
+    /// Previews a custom source rather than an EVE client
+    CustomSource,
+}
+
+impl StatusBadge {
+    /// Glyph rows, top to bottom; bit 7 is the leftmost pixel
+    fn glyph(self) -> [u8; 8] {
+        match self {
+            // Bar along the bottom: "_"
+            Self::Minimized => [
+                0b0000_0000,
+                0b0000_0000,
+                0b0000_0000,
+                0b0000_0000,
+                0b0000_0000,
+                0b0111_1110,
+                0b0111_1110,
+                0b0000_0000,
+            ],
+            // Diagonal cross
+            Self::Skipped => [
+                0b1100_0011,
+                0b1110_0111,
+                0b0111_1110,
+                0b0011_1100,
+                0b0011_1100,
+                0b0111_1110,
+                0b1110_0111,
+                0b1100_0011,
+            ],
+            // Lightning bolt
+            Self::HotkeyBound => [
+                0b0000_1110,
+                0b0001_1100,
+                0b0011_1000,
+                0b0111_1110,
+                0b0000_1100,
+                0b0001_1000,
+                0b0011_0000,
+                0b0010_0000,
+            ],
+            // Window with a title bar
+            Self::CustomSource => [
+                0b1111_1111,
+                0b1111_1111,
+                0b1000_0001,
+                0b1000_0001,
+                0b1000_0001,
+                0b1000_0001,
+                0b1111_1111,
+                0b0000_0000,
+            ],
+        }
+    }
+
+    /// Glyph color as (red, green, blue)
+    fn color(self) -> (u8, u8, u8) {
+        match self {
+            Self::Minimized => (0xB0, 0xC4, 0xDE),
+            Self::Skipped => (0xFF, 0x50, 0x50),
+            Self::HotkeyBound => (0xFF, 0xD7, 0x40),
+            Self::CustomSource => (0x40, 0xD0, 0xC0),
+        }
+    }
+}
+
+/// Alpha of the square behind each glyph
+const BACKGROUND_ALPHA: u8 = 0xC0;
+
+type BadgeKey = (StatusBadge, u16);
+
+static BADGE_CACHE: LazyLock<Mutex<HashMap<BadgeKey, Arc<IconBitmap>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the badge bitmap at `size` pixels square, rendering it on first use.
+pub fn badge_bitmap(badge: StatusBadge, size: u16) -> Option<Arc<IconBitmap>> {
+    if size == 0 {
+        return None;
+    }
+    let key = (badge, size);
+    if let Ok(cache) = BADGE_CACHE.lock()
+        && let Some(cached) = cache.get(&key)
+    {
+        return Some(cached.clone());
+    }
+
+    let bitmap = Arc::new(IconBitmap {
+        size,
+        data: render(badge, size),
+    });
+    if let Ok(mut cache) = BADGE_CACHE.lock() {
+        cache.insert(key, bitmap.clone());
+    }
+    Some(bitmap)
+}
+
+fn render(badge: StatusBadge, size: u16) -> Vec<u8> {
+    let size = usize::from(size);
+    let glyph = badge.glyph();
+    let (red, green, blue) = badge.color();
+    // One glyph pixel of padding on each side once the glyph is big enough for it
+    let pad = size / 10;
+    let inner = size - 2 * pad;
+
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let lit = x >= pad
+                && y >= pad
+                && x < pad + inner
+                && y < pad + inner
+                && glyph[(y - pad) * 8 / inner] & (0x80 >> ((x - pad) * 8 / inner)) != 0;
+            if lit {
+                data.extend_from_slice(&[blue, green, red, 0xFF]);
+            } else {
+                data.extend_from_slice(&[0, 0, 0, BACKGROUND_ALPHA]);
+            }
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_bitmap_scales_glyph() {
+        let bitmap = badge_bitmap(StatusBadge::CustomSource, 16).unwrap();
+        assert_eq!(bitmap.size, 16);
+        assert_eq!(bitmap.data.len(), 16 * 16 * 4);
+        assert!(badge_bitmap(StatusBadge::CustomSource, 0).is_none());
+
+        // Top row of the inset glyph is the title bar, its middle is the background
+        let pixel = |x: usize, y: usize| &bitmap.data[(y * 16 + x) * 4..][..4];
+        assert_eq!(pixel(0, 0), [0, 0, 0, BACKGROUND_ALPHA]);
+        assert_eq!(pixel(8, 1), [0xC0, 0xD0, 0x40, 0xFF]);
+        assert_eq!(pixel(8, 8), [0, 0, 0, BACKGROUND_ALPHA]);
+    }
+}
@@ -0,0 +1,190 @@
+//! egui overlay backend: the software layer uploaded as a texture and painted over the preview
+//!
+//! Used by the Manager's fallback previews, which are egui viewports rather than X11 windows.
+
+use std::cell::{Cell, RefCell};
+
+use anyhow::{Result, bail};
+use eframe::egui;
+
+use crate::common::types::{Dimensions, EveServer};
+use crate::config::DisplayConfig;
+use crate::daemon::font::FontRenderer;
+
+use super::{BorderState, OverlayBackend, SoftwareOverlay};
+
+pub struct EguiOverlay {
+    layer: SoftwareOverlay,
+    /// Layer as uploaded last; `None` until the first composite
+    texture: RefCell<Option<egui::TextureHandle>>,
+    /// The layer was redrawn since the last upload
+    stale: Cell<bool>,
+}
+
+impl std::fmt::Debug for EguiOverlay {
+    // `TextureHandle` has no Debug impl
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EguiOverlay")
+            .field("layer", &self.layer)
+            .field("stale", &self.stale)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EguiOverlay {
+    /// Creates a transparent overlay
+    pub fn new(dimensions: Dimensions) -> Self {
+        Self {
+            layer: SoftwareOverlay::new(dimensions),
+            texture: RefCell::new(None),
+            stale: Cell::new(true),
+        }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        self.layer.dimensions()
+    }
+
+    /// Redraws the whole overlay for `state`, see `SoftwareOverlay::draw`
+    pub fn draw(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        state: BorderState,
+        skipped: bool,
+        font_renderer: Option<&FontRenderer>,
+    ) -> Result<()> {
+        self.stale.set(true);
+        self.layer
+            .draw(config, character_name, state, skipped, font_renderer)
+    }
+}
+
+impl OverlayBackend for EguiOverlay {
+    /// Painter clipped to the preview image
+    type Target = egui::Painter;
+
+    fn name(&self) -> &'static str {
+        "egui"
+    }
+
+    fn set_notes_visible(&mut self, visible: bool) {
+        self.layer.set_notes_visible(visible);
+    }
+
+    fn set_server(&mut self, server: EveServer) {
+        self.layer.set_server(server);
+    }
+
+    fn set_custom_source(&mut self, custom: bool) {
+        self.layer.set_custom_source(custom);
+    }
+
+    fn resize(&mut self, dimensions: Dimensions) -> Result<()> {
+        self.stale.set(true);
+        self.layer.resize(dimensions)
+    }
+
+    fn clear_content_area(&self, dimensions: Dimensions, border_size: u16) -> Result<()> {
+        self.stale.set(true);
+        self.layer.clear_content_area(dimensions, border_size)
+    }
+
+    fn update_name(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        border_size: u16,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.stale.set(true);
+        self.layer.update_name(
+            config,
+            character_name,
+            dimensions,
+            border_size,
+            font_renderer,
+        )
+    }
+
+    fn draw_border(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        focused: bool,
+        skipped: bool,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.stale.set(true);
+        self.layer.draw_border(
+            config,
+            character_name,
+            dimensions,
+            focused,
+            skipped,
+            font_renderer,
+        )
+    }
+
+    fn draw_minimized(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.stale.set(true);
+        self.layer
+            .draw_minimized(config, character_name, dimensions, font_renderer)
+    }
+
+    /// Uploads the layer when it changed and paints it stretched over the painter's clip rect
+    fn composite(&self, target: &mut egui::Painter, dimensions: Dimensions) -> Result<()> {
+        if dimensions != self.layer.dimensions() {
+            bail!(
+                "Overlay is {}x{} but was composited at {}x{}",
+                self.layer.dimensions().width,
+                self.layer.dimensions().height,
+                dimensions.width,
+                dimensions.height
+            );
+        }
+        if dimensions.width == 0 || dimensions.height == 0 {
+            return Ok(());
+        }
+
+        let mut texture = self.texture.borrow_mut();
+        if self.stale.replace(false) || texture.is_none() {
+            // BGRA to the RGBA egui expects, both premultiplied
+            let rgba: Vec<u8> = self
+                .layer
+                .pixels()
+                .chunks_exact(4)
+                .flat_map(|p| [p[2], p[1], p[0], p[3]])
+                .collect();
+            let size = [
+                usize::from(dimensions.width),
+                usize::from(dimensions.height),
+            ];
+            let image = egui::ColorImage::from_rgba_premultiplied(size, &rgba);
+            match texture.as_mut() {
+                Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+                None => {
+                    *texture = Some(target.ctx().load_texture(
+                        "thumbnail_overlay",
+                        image,
+                        egui::TextureOptions::LINEAR,
+                    ))
+                }
+            }
+        }
+
+        if let Some(texture) = texture.as_ref() {
+            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+            target.image(texture.id(), target.clip_rect(), uv, egui::Color32::WHITE);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,621 @@
+//! Overlay backends for thumbnails (text, borders, indicators)
+//!
+//! `ThumbnailRenderer` decides *what* a thumbnail shows (position, label, border state, input)
+//! and an `OverlayBackend` decides *how* it is drawn: with X RENDER for X11 thumbnails, into a
+//! pixel layer for the Wayland layer-shell previews, and as an egui texture for the Manager's
+//! fallback previews. All of them lay out labels, borders and badges with the helpers below, so
+//! every integration shows the same thumbnail.
+
+mod atlas;
+mod egui_texture;
+mod software;
+mod xrender;
+
+use anyhow::Result;
+use x11rb::protocol::render::Color;
+
+use crate::common::color::HexColor;
+use crate::common::types::{CornerIcon, Dimensions, EveServer, ThumbnailShape};
+use crate::config::{BadgeCorner, BorderStyle, DisplayConfig, MinimizedImage, TextEffect};
+
+use super::font::{FontRenderer, TextDecoration};
+
+pub use atlas::StatusBadge;
+pub use egui_texture::EguiOverlay;
+pub use software::SoftwareOverlay;
+pub use xrender::OverlayRenderer;
+
+/// Draws the overlay layer of one thumbnail
+pub trait OverlayBackend: std::fmt::Debug {
+    /// What the overlay is composited onto: a RENDER picture, a pixel buffer or an egui painter
+    type Target: ?Sized;
+
+    /// Short name for logs and diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Shows the notes line regardless of `DisplayConfig::show_notes` (set while hovered).
+    /// Takes effect on the next redraw.
+    fn set_notes_visible(&mut self, visible: bool);
+
+    /// Sets the server badge/border used on the next redraw.
+    fn set_server(&mut self, server: EveServer);
+
+    /// Marks the thumbnail as previewing a custom source (status badge on the next redraw).
+    fn set_custom_source(&mut self, custom: bool);
+
+    /// Resizes the overlay layer. Its contents are undefined until the next redraw.
+    fn resize(&mut self, dimensions: Dimensions) -> Result<()>;
+
+    /// Clears the center content area (inside the border).
+    fn clear_content_area(&self, dimensions: Dimensions, border_size: u16) -> Result<()>;
+
+    /// Draws the label (and optional notes line). Does NOT clear the background first.
+    fn update_name(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        border_size: u16,
+        font_renderer: &FontRenderer,
+    ) -> Result<()>;
+
+    /// Redraws the whole overlay: skipped indicator, label, corner icon, cycle badge and status
+    /// badges, then the border.
+    fn draw_border(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        focused: bool,
+        skipped: bool,
+        font_renderer: &FontRenderer,
+    ) -> Result<()>;
+
+    /// Redraws the overlay for a minimized client.
+    fn draw_minimized(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        font_renderer: &FontRenderer,
+    ) -> Result<()>;
+
+    /// Composites the overlay on top of the thumbnail content in `target`.
+    fn composite(&self, target: &mut Self::Target, dimensions: Dimensions) -> Result<()>;
+}
+
+/// Effective border size for a character, honouring per-character overrides
+pub fn border_size(config: &DisplayConfig, character_name: &str, focused: bool) -> u16 {
+    let settings = config.character_settings.get(character_name);
+    if focused {
+        settings
+            .and_then(|s| s.override_active_border_size)
+            .unwrap_or(config.active_border_size)
+    } else {
+        settings
+            .and_then(|s| s.override_inactive_border_size)
+            .unwrap_or(config.inactive_border_size)
+    }
+}
+
+/// Which border a thumbnail shows. Focus wins over the skipped state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorderState {
+    Focused,
+    Unfocused,
+    /// Unfocused and skipped by cycling
+    Skipped,
+    Minimized,
+}
+
+impl BorderState {
+    /// State of a client that is not minimized
+    pub fn of(focused: bool, skipped: bool) -> Self {
+        match (focused, skipped) {
+            (true, _) => Self::Focused,
+            (false, true) => Self::Skipped,
+            (false, false) => Self::Unfocused,
+        }
+    }
+
+    pub fn is_focused(self) -> bool {
+        self == Self::Focused
+    }
+}
+
+/// Style and width of the border drawn for a state (width 0 = no border).
+///
+/// Skipped and minimized clients use the unfocused border unless the profile gives them their
+/// own width or style; their own width shows even with the unfocused border disabled.
+/// Unfocused test-server clients always get a border so they stand out.
+pub fn border_line(
+    config: &DisplayConfig,
+    character_name: &str,
+    state: BorderState,
+    server: EveServer,
+) -> (BorderStyle, u16) {
+    if state.is_focused() {
+        return (
+            config.active_border_style,
+            border_size(config, character_name, true),
+        );
+    }
+    let (style, size) = match state {
+        BorderState::Skipped => (config.skipped_border_style, config.skipped_border_size),
+        BorderState::Minimized => (config.minimized_border_style, config.minimized_border_size),
+        _ => (None, None),
+    };
+    let style = style.unwrap_or(config.inactive_border_style);
+    let size = match size {
+        Some(size) => size,
+        None if server.is_test_server() => match border_size(config, character_name, false) {
+            0 => config.active_border_size,
+            size => size,
+        },
+        None if config.inactive_border_enabled => border_size(config, character_name, false),
+        None => 0,
+    };
+    (style, size)
+}
+
+/// Outline of a character's thumbnail (shape and corner radius), honouring the per-character
+/// shape override. Border, padding and glow rings follow it.
+pub fn outline(config: &DisplayConfig, character_name: &str) -> (ThumbnailShape, u16) {
+    let shape = config
+        .character_settings
+        .get(character_name)
+        .and_then(|s| s.override_shape)
+        .unwrap_or(config.shape);
+    (shape, config.corner_radius)
+}
+
+/// Effective border color for a character: the test-server color for unfocused test-server
+/// clients, then per-character overrides, then the profile colors
+pub fn border_color(
+    config: &DisplayConfig,
+    character_name: &str,
+    focused: bool,
+    server: EveServer,
+) -> Color {
+    if server.is_test_server() && !focused {
+        return config.test_server_border_color;
+    }
+    config
+        .character_settings
+        .get(character_name)
+        .and_then(|s| {
+            if focused {
+                s.override_active_border_color.as_deref()
+            } else {
+                s.override_inactive_border_color.as_deref()
+            }
+        })
+        .and_then(|hex| HexColor::parse(hex).map(|c| c.to_x11_color()))
+        .unwrap_or(if focused {
+            config.active_border_color
+        } else {
+            config.inactive_border_color
+        })
+}
+
+/// Rings of the border glow, outermost first: `(inset, alpha)` with `inset` counted from the
+/// thumbnail edge and `alpha` fading linearly from the border color's alpha towards zero
+pub fn glow_rings(config: &DisplayConfig, border: u16, color: Color) -> Vec<(u16, u16)> {
+    let glow = u32::from(config.border_glow);
+    (0..glow)
+        .map(|i| {
+            let alpha = u32::from(color.alpha) * (glow - i) / (glow + 1);
+            (border + i as u16, alpha as u16)
+        })
+        .collect()
+}
+
+/// Outline or drop shadow behind labels, from the profile's text effect
+pub fn text_decoration(config: &DisplayConfig) -> TextDecoration {
+    use crate::common::constants::defaults::text;
+
+    let color = config.text_effect_color;
+    match config.text_effect {
+        TextEffect::None => TextDecoration::None,
+        TextEffect::Outline => TextDecoration::Outline {
+            color,
+            width: config.text_outline_width.clamp(1, text::OUTLINE_WIDTH_MAX),
+        },
+        TextEffect::Shadow => {
+            let clamp = |v: i16| v.clamp(-text::SHADOW_OFFSET_MAX, text::SHADOW_OFFSET_MAX);
+            TextDecoration::Shadow {
+                color,
+                dx: clamp(config.text_shadow_offset.x),
+                dy: clamp(config.text_shadow_offset.y),
+            }
+        }
+    }
+}
+
+/// Placement of a thumbnail's cycle-order badge (position relative to the thumbnail)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleBadge {
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    /// 1-based position in the character's cycle group
+    pub number: usize,
+}
+
+/// Where the cycle-order badge goes, or `None` when badges are off or the character isn't in
+/// a cycle group. The badge is square for one digit and widens for each extra digit.
+pub fn cycle_badge(
+    config: &DisplayConfig,
+    character_name: &str,
+    dimensions: Dimensions,
+) -> Option<CycleBadge> {
+    use crate::common::constants::defaults::thumbnail::ICON_MARGIN;
+
+    let number = *config.cycle_positions.get(character_name)?;
+    let height = config
+        .cycle_badge_size
+        .min(dimensions.width / 2)
+        .min(dimensions.height / 2);
+    if height == 0 {
+        return None;
+    }
+    let digits = number.to_string().len() as u16;
+    let width = height + (digits - 1) * height / 2;
+
+    let right = dimensions.width as i16 - width as i16 - ICON_MARGIN;
+    let bottom = dimensions.height as i16 - height as i16 - ICON_MARGIN;
+    let (x, y) = match config.cycle_badge_corner {
+        BadgeCorner::TopLeft => (ICON_MARGIN, ICON_MARGIN),
+        BadgeCorner::TopRight => (right, ICON_MARGIN),
+        BadgeCorner::BottomLeft => (ICON_MARGIN, bottom),
+        BadgeCorner::BottomRight => (right, bottom),
+    };
+    Some(CycleBadge {
+        x,
+        y,
+        width,
+        height,
+        number,
+    })
+}
+
+/// Status badges that apply to a thumbnail, in drawing order
+pub fn statuses(
+    config: &DisplayConfig,
+    character_name: &str,
+    state: BorderState,
+    skipped: bool,
+    custom_source: bool,
+) -> Vec<StatusBadge> {
+    let mut badges = Vec::new();
+    if state == BorderState::Minimized {
+        badges.push(StatusBadge::Minimized);
+    }
+    if skipped {
+        badges.push(StatusBadge::Skipped);
+    }
+    if !custom_source && config.hotkey_bound.contains(character_name) {
+        badges.push(StatusBadge::HotkeyBound);
+    }
+    if custom_source {
+        badges.push(StatusBadge::CustomSource);
+    }
+    badges
+}
+
+/// Top-left positions of `count` status badges and their size, lined up along the bottom or top
+/// edge from the configured corner. They start past the cycle badge when it shares the corner.
+pub fn status_badge_layout(
+    config: &DisplayConfig,
+    character_name: &str,
+    dimensions: Dimensions,
+    count: usize,
+) -> (u16, Vec<(i16, i16)>) {
+    use crate::common::constants::defaults::thumbnail::{ICON_MARGIN, STATUS_BADGE_SPACING};
+
+    let size = config
+        .status_badge_size
+        .min(dimensions.width / 4)
+        .min(dimensions.height / 4);
+    if size == 0 || count == 0 {
+        return (0, Vec::new());
+    }
+
+    let corner = config.status_badge_corner;
+    let mut offset = ICON_MARGIN;
+    if config.cycle_badge_corner == corner
+        && let Some(badge) = cycle_badge(config, character_name, dimensions)
+    {
+        offset += badge.width as i16 + STATUS_BADGE_SPACING;
+    }
+
+    let step = size as i16 + STATUS_BADGE_SPACING;
+    let right = dimensions.width as i16 - size as i16;
+    let bottom = dimensions.height as i16 - size as i16 - ICON_MARGIN;
+    let positions = (0..count as i16)
+        .map(|i| {
+            let along = offset + i * step;
+            match corner {
+                BadgeCorner::TopLeft => (along, ICON_MARGIN),
+                BadgeCorner::TopRight => (right - along, ICON_MARGIN),
+                BadgeCorner::BottomLeft => (along, bottom),
+                BadgeCorner::BottomRight => (right - along, bottom),
+            }
+        })
+        .collect();
+    (size, positions)
+}
+
+/// Icon drawn on a minimized client's thumbnail, if the profile configures one
+pub fn minimized_image(config: &DisplayConfig, character_name: &str) -> Option<CornerIcon> {
+    match &config.minimized_image {
+        MinimizedImage::None => None,
+        MinimizedImage::CornerIcon => config
+            .character_settings
+            .get(character_name)
+            .map(|s| s.corner_icon.clone())
+            .filter(|icon| *icon != CornerIcon::None),
+        MinimizedImage::Custom { path } if !path.trim().is_empty() => {
+            Some(CornerIcon::Custom { path: path.clone() })
+        }
+        MinimizedImage::Custom { .. } => None,
+    }
+}
+
+/// Minimized text as Latin-1 bytes for the core font, at most one ImageText8 request long.
+/// Characters outside Latin-1 become '?'.
+pub fn minimized_text_bytes(text: &str) -> Vec<u8> {
+    text.trim()
+        .chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+        .take(u8::MAX as usize)
+        .collect()
+}
+
+/// Label color for a character, honouring the per-character override (ARGB)
+pub fn label_color(config: &DisplayConfig, character_name: &str) -> u32 {
+    config
+        .character_settings
+        .get(character_name)
+        .and_then(|s| s.override_text_color.as_deref())
+        .and_then(HexColor::parse)
+        .map_or(config.text_color, HexColor::argb32)
+}
+
+/// Character notes joined into one line for under the label, or `None` without notes
+pub fn notes_line(config: &DisplayConfig, character_name: &str) -> Option<String> {
+    config
+        .character_settings
+        .get(character_name)
+        .and_then(|s| s.notes.as_deref())
+        .map(|notes| {
+            notes
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        })
+        .filter(|line| !line.is_empty())
+}
+
+/// Label text for a thumbnail: alias, then account tag and test-server badge prefixes
+pub fn label_text(config: &DisplayConfig, character_name: &str, server: EveServer) -> String {
+    let display_name = crate::common::types::alias::display_name(
+        &config.alias_template,
+        config.character_settings.get(character_name),
+        character_name,
+    );
+    let display_name = match config.account_tags.get(character_name) {
+        Some(tag) => format!("[{}] {}", tag, display_name),
+        None => display_name,
+    };
+    if server.is_test_server() {
+        format!("[{}] {}", server.badge(), display_name)
+    } else {
+        display_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::CharacterSettings;
+    use crate::config::DaemonConfig;
+    use crate::config::profile::Profile;
+    use std::collections::HashMap;
+
+    fn display_config() -> DisplayConfig {
+        let mut profile = Profile::default_with_name("Test".to_string(), String::new());
+        profile.thumbnail_active_border = true;
+        profile.thumbnail_active_border_size = 3;
+        profile.thumbnail_inactive_border = false;
+        let mut jane = CharacterSettings::new(0, 0, 100, 100);
+        jane.override_active_border_size = Some(7);
+        profile
+            .character_thumbnails
+            .insert("Jane".to_string(), jane);
+        DaemonConfig {
+            profile,
+            character_thumbnails: HashMap::new(),
+            custom_source_thumbnails: HashMap::new(),
+            custom_source_geometry: Vec::new(),
+            profile_hotkeys: HashMap::new(),
+            runtime_hidden: false,
+            active_cycle_group: None,
+            run_startup_actions: false,
+        }
+        .build_display_config()
+    }
+
+    #[test]
+    fn test_border_color_and_glow() {
+        let mut config = display_config();
+        config.border_glow = 3;
+        // x11rb colors don't implement PartialEq
+        let rgba = |c: Color| (c.red, c.green, c.blue, c.alpha);
+        let active = border_color(&config, "Bob", true, EveServer::Tranquility);
+        assert_eq!(rgba(active), rgba(config.active_border_color));
+        assert_eq!(
+            rgba(border_color(&config, "Bob", false, EveServer::Singularity)),
+            rgba(config.test_server_border_color)
+        );
+
+        let rings = glow_rings(&config, 2, active);
+        assert_eq!(rings.len(), 3);
+        assert_eq!(rings[0].0, 2);
+        assert!(rings.windows(2).all(|w| w[0].1 > w[1].1));
+        assert!(rings[0].1 < active.alpha);
+    }
+
+    #[test]
+    fn test_border_size_overrides() {
+        let config = display_config();
+        assert_eq!(border_size(&config, "Jane", true), 7);
+        assert_eq!(border_size(&config, "Jane", false), 0);
+        assert_eq!(border_size(&config, "Bob", true), 3);
+    }
+
+    #[test]
+    fn test_border_line_per_state() {
+        let mut config = display_config();
+        config.inactive_border_style = BorderStyle::Dashed;
+        let line = |config: &DisplayConfig, state| {
+            border_line(config, "Bob", state, EveServer::Tranquility)
+        };
+        assert_eq!(BorderState::of(true, true), BorderState::Focused);
+        assert_eq!(line(&config, BorderState::Focused), (BorderStyle::Solid, 3));
+        // Inactive border disabled: only test-server clients get one
+        assert_eq!(
+            line(&config, BorderState::Skipped),
+            (BorderStyle::Dashed, 0)
+        );
+        assert_eq!(
+            border_line(
+                &config,
+                "Bob",
+                BorderState::Unfocused,
+                EveServer::Singularity
+            ),
+            (BorderStyle::Dashed, 3)
+        );
+
+        config.skipped_border_size = Some(2);
+        config.skipped_border_style = Some(BorderStyle::Double);
+        assert_eq!(
+            line(&config, BorderState::Skipped),
+            (BorderStyle::Double, 2)
+        );
+        assert_eq!(
+            line(&config, BorderState::Minimized),
+            (BorderStyle::Dashed, 0)
+        );
+
+        config.inactive_border_enabled = true;
+        config.inactive_border_size = 1;
+        assert_eq!(
+            line(&config, BorderState::Minimized),
+            (BorderStyle::Dashed, 1)
+        );
+    }
+
+    #[test]
+    fn test_cycle_badge_placement() {
+        let mut config = display_config();
+        let dims = Dimensions::new(200, 100);
+        config.cycle_positions.insert("Bob".to_string(), 3);
+        config.cycle_positions.insert("Jane".to_string(), 12);
+        assert_eq!(cycle_badge(&config, "Bob", dims), None);
+
+        config.cycle_badge_size = 20;
+        let badge = cycle_badge(&config, "Bob", dims).unwrap();
+        assert_eq!(
+            (badge.x, badge.y, badge.width, badge.height),
+            (174, 74, 20, 20)
+        );
+        assert_eq!(badge.number, 3);
+
+        config.cycle_badge_corner = BadgeCorner::TopLeft;
+        let badge = cycle_badge(&config, "Jane", dims).unwrap();
+        assert_eq!((badge.x, badge.y, badge.width), (6, 6, 30));
+        assert_eq!(cycle_badge(&config, "Alice", dims), None);
+
+        // Never larger than half the thumbnail
+        config.cycle_badge_size = 80;
+        assert_eq!(cycle_badge(&config, "Bob", dims).unwrap().height, 50);
+    }
+
+    #[test]
+    fn test_label_text_prefixes() {
+        let mut config = display_config();
+        assert_eq!(label_text(&config, "Bob", EveServer::Tranquility), "Bob");
+
+        config
+            .account_tags
+            .insert("Bob".to_string(), "A1".to_string());
+        assert_eq!(
+            label_text(&config, "Bob", EveServer::Singularity),
+            "[SISI] [A1] Bob"
+        );
+    }
+
+    #[test]
+    fn test_status_badges_line_up_past_cycle_badge() {
+        let mut config = display_config();
+        let dims = Dimensions::new(200, 100);
+        config.hotkey_bound.insert("Bob".to_string());
+        let badges = statuses(&config, "Bob", BorderState::Minimized, false, false);
+        assert_eq!(
+            badges,
+            vec![StatusBadge::Minimized, StatusBadge::HotkeyBound]
+        );
+        assert_eq!(
+            statuses(&config, "Bob", BorderState::Focused, true, true),
+            vec![StatusBadge::Skipped, StatusBadge::CustomSource]
+        );
+        assert_eq!(status_badge_layout(&config, "Bob", dims, 2).0, 0);
+
+        config.status_badge_size = 14;
+        config.status_badge_corner = BadgeCorner::BottomRight;
+        let (size, positions) = status_badge_layout(&config, "Bob", dims, 2);
+        assert_eq!(size, 14);
+        assert_eq!(positions, vec![(180, 80), (163, 80)]);
+
+        // A cycle badge in the same corner pushes them inward
+        config.cycle_badge_size = 20;
+        config.cycle_positions.insert("Bob".to_string(), 1);
+        let (_, positions) = status_badge_layout(&config, "Bob", dims, 1);
+        assert_eq!(positions, vec![(157, 80)]);
+    }
+
+    #[test]
+    fn test_minimized_placeholder() {
+        let mut config = display_config();
+        assert_eq!(minimized_text_bytes("  Zzz  "), b"Zzz");
+        assert_eq!(minimized_text_bytes("Café ☕"), b"Caf\xe9 ?");
+        assert_eq!(minimized_text_bytes(&"x".repeat(300)).len(), 255);
+
+        assert_eq!(minimized_image(&config, "Jane"), None);
+        config.minimized_image = MinimizedImage::CornerIcon;
+        assert_eq!(minimized_image(&config, "Jane"), None);
+        config
+            .character_settings
+            .get_mut("Jane")
+            .unwrap()
+            .corner_icon = CornerIcon::Portrait { character_id: 42 };
+        assert_eq!(
+            minimized_image(&config, "Jane"),
+            Some(CornerIcon::Portrait { character_id: 42 })
+        );
+        config.minimized_image = MinimizedImage::Custom {
+            path: "/tmp/zzz.png".to_string(),
+        };
+        assert_eq!(
+            minimized_image(&config, "Bob"),
+            Some(CornerIcon::Custom {
+                path: "/tmp/zzz.png".to_string()
+            })
+        );
+    }
+}
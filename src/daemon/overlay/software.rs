@@ -0,0 +1,582 @@
+//! Software overlay backend: text and borders drawn into a premultiplied BGRA pixel layer
+//!
+//! For integrations without X RENDER. The Wayland layer-shell previews blend the layer into
+//! their `wl_shm` buffers, and the egui backend uploads it as a texture. Drawing follows the X
+//! RENDER backend step for step, from the same layout helpers.
+
+use std::cell::{Ref, RefCell};
+
+use anyhow::{Result, bail};
+use x11rb::protocol::render::Color;
+use x11rb::protocol::xproto::Rectangle;
+
+use crate::common::constants::defaults;
+use crate::common::types::{Dimensions, EveServer, ThumbnailShape};
+use crate::config::{BorderStyle, DisplayConfig};
+use crate::daemon::font::{FontRenderer, TextDecoration, label_renderer};
+
+use super::atlas::badge_bitmap;
+use super::{
+    BorderState, OverlayBackend, border_color, border_line, cycle_badge, glow_rings, label_color,
+    label_text, minimized_image, notes_line, outline, status_badge_layout, statuses,
+    text_decoration,
+};
+
+/// Skipped indicator: opaque red diagonals, as thick as the X RENDER backend's
+const SKIPPED_INDICATOR_COLOR: u32 = 0xFFFF0000;
+const SKIPPED_INDICATOR_WIDTH: usize = 3;
+
+#[derive(Debug)]
+pub struct SoftwareOverlay {
+    /// Premultiplied BGRA (the memory layout of `wl_shm` ARGB8888), `dimensions` in size
+    layer: RefCell<Vec<u8>>,
+    dimensions: Dimensions,
+    /// Show the notes line regardless of `DisplayConfig::show_notes` (set while hovered).
+    notes_visible: bool,
+    /// Server the client is connected to; test servers get a badge and their own border.
+    server: EveServer,
+    /// Previews a custom source; shown as a status badge.
+    custom_source: bool,
+}
+
+impl SoftwareOverlay {
+    /// Creates a transparent overlay
+    pub fn new(dimensions: Dimensions) -> Self {
+        Self {
+            layer: RefCell::new(vec![0; layer_len(dimensions)]),
+            dimensions,
+            notes_visible: false,
+            server: EveServer::default(),
+            custom_source: false,
+        }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// The overlay pixels, premultiplied BGRA
+    pub fn pixels(&self) -> Ref<'_, [u8]> {
+        Ref::map(self.layer.borrow(), Vec::as_slice)
+    }
+
+    /// Redraws the whole overlay for `state`. Without a font (no TrueType font on the system)
+    /// only borders and badges are drawn.
+    pub fn draw(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        state: BorderState,
+        skipped: bool,
+        font_renderer: Option<&FontRenderer>,
+    ) -> Result<()> {
+        self.layer.borrow_mut().fill(0);
+        if skipped {
+            self.draw_skipped_indicator();
+        }
+
+        let focused = state.is_focused();
+        let (style, drawn_size) = border_line(config, character_name, state, self.server);
+
+        if let Some(font_renderer) = font_renderer {
+            self.draw_label(config, character_name, font_renderer)?;
+        }
+        self.draw_corner_icon(config, character_name);
+        self.draw_cycle_badge(config, character_name, font_renderer)?;
+        self.draw_status_badges(config, character_name, state, skipped);
+
+        let outline = outline(config, character_name);
+        if config.border_padding > 0 {
+            let padding = premultiplied(defaults::border::PADDING_COLOR);
+            self.fill_ring(outline, drawn_size, config.border_padding, padding, false);
+        }
+        if drawn_size > 0 {
+            let color = border_color(config, character_name, focused, self.server);
+            for (inset, alpha) in glow_rings(config, drawn_size, color) {
+                self.fill_ring(
+                    outline,
+                    inset,
+                    1,
+                    premultiplied(Color { alpha, ..color }),
+                    true,
+                );
+            }
+            self.stroke_border(color, style, outline, drawn_size);
+        }
+        Ok(())
+    }
+
+    /// Label and, when shown, the notes line under it
+    fn draw_label(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        let settings = config.character_settings.get(character_name);
+        let text_color = label_color(config, character_name);
+        let custom_font = settings.and_then(|s| {
+            label_renderer(
+                font_renderer,
+                s.override_text_font.as_deref(),
+                s.override_text_size,
+            )
+        });
+        let font_renderer = custom_font.as_deref().unwrap_or(font_renderer);
+        let decoration = text_decoration(config);
+        let (x, y) = (config.text_offset.x, config.text_offset.y);
+
+        let label = label_text(config, character_name, self.server);
+        self.draw_text(&label, text_color, decoration, x, y, font_renderer)?;
+
+        if (config.show_notes || self.notes_visible)
+            && let Some(notes) = notes_line(config, character_name)
+        {
+            let line_height = font_renderer.size() as i16 + defaults::text::NOTES_LINE_SPACING;
+            self.draw_text(
+                &notes,
+                text_color,
+                decoration,
+                x,
+                y + line_height,
+                font_renderer,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Draws one line of text with its top-left at (x, y). Core fonts draw on an X server only,
+    /// so they leave the layer untouched.
+    fn draw_text(
+        &self,
+        text: &str,
+        color: u32,
+        decoration: TextDecoration,
+        x: i16,
+        y: i16,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        if font_renderer.requires_direct_rendering() {
+            return Ok(());
+        }
+        let rendered = font_renderer.render_text(text, color, decoration)?;
+        self.blit(
+            &rendered.data,
+            rendered.width,
+            rendered.height,
+            i32::from(x) - rendered.origin.0 as i32,
+            i32::from(y) - rendered.origin.1 as i32,
+        );
+        Ok(())
+    }
+
+    fn draw_corner_icon(&self, config: &DisplayConfig, character_name: &str) {
+        let Some(icon) = config
+            .character_settings
+            .get(character_name)
+            .map(|s| &s.corner_icon)
+        else {
+            return;
+        };
+        let size = config
+            .icon_size
+            .min(self.dimensions.width / 2)
+            .min(self.dimensions.height / 2);
+        let Some(bitmap) = crate::daemon::icons::load_icon(icon, size) else {
+            return;
+        };
+        let margin = i32::from(defaults::thumbnail::ICON_MARGIN);
+        let x = i32::from(self.dimensions.width) - i32::from(bitmap.size) - margin;
+        let size = usize::from(bitmap.size);
+        self.blit(&bitmap.data, size, size, x, margin);
+    }
+
+    fn draw_cycle_badge(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        font_renderer: Option<&FontRenderer>,
+    ) -> Result<()> {
+        let Some(badge) = cycle_badge(config, character_name, self.dimensions) else {
+            return Ok(());
+        };
+        let background = Rectangle {
+            x: badge.x,
+            y: badge.y,
+            width: badge.width,
+            height: badge.height,
+        };
+        let fill = premultiplied(defaults::thumbnail::CYCLE_BADGE_BACKGROUND);
+        self.fill_rects(&[background], fill, true);
+
+        let Some(font_renderer) = font_renderer else {
+            return Ok(());
+        };
+        // Digits at roughly 70% of the badge height, in the label color
+        let text_size = (badge.height * 7 / 10).max(1);
+        let custom_font = label_renderer(font_renderer, None, Some(text_size));
+        let font_renderer = custom_font.as_deref().unwrap_or(font_renderer);
+        if font_renderer.requires_direct_rendering() {
+            return Ok(());
+        }
+        let number = badge.number.to_string();
+        let text = font_renderer.render_text(&number, config.text_color, TextDecoration::None)?;
+        let x = i32::from(badge.x) + (i32::from(badge.width) - text.width as i32) / 2;
+        let y = i32::from(badge.y) + (i32::from(badge.height) - text.height as i32) / 2;
+        self.blit(&text.data, text.width, text.height, x, y);
+        Ok(())
+    }
+
+    fn draw_status_badges(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        state: BorderState,
+        skipped: bool,
+    ) {
+        let badges = statuses(config, character_name, state, skipped, self.custom_source);
+        let (size, positions) =
+            status_badge_layout(config, character_name, self.dimensions, badges.len());
+        for (badge, (x, y)) in badges.into_iter().zip(positions) {
+            if let Some(bitmap) = badge_bitmap(badge, size) {
+                let size = usize::from(bitmap.size);
+                self.blit(&bitmap.data, size, size, i32::from(x), i32::from(y));
+            }
+        }
+    }
+
+    /// Two red diagonals corner to corner
+    fn draw_skipped_indicator(&self) {
+        let (width, height) = (
+            usize::from(self.dimensions.width),
+            usize::from(self.dimensions.height),
+        );
+        let color = premultiplied_bgra(SKIPPED_INDICATOR_COLOR);
+        let mut layer = self.layer.borrow_mut();
+        for y in 0..height {
+            let along = y * width / height;
+            for x in [along, width - 1 - along] {
+                let start = x.saturating_sub(SKIPPED_INDICATOR_WIDTH / 2);
+                let end = (x + SKIPPED_INDICATOR_WIDTH / 2 + 1).min(width);
+                for x in start..end {
+                    let i = (y * width + x) * 4;
+                    layer[i..i + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    /// Draws a border `size` pixels wide along the outline in the given style
+    fn stroke_border(
+        &self,
+        color: Color,
+        style: BorderStyle,
+        outline: (ThumbnailShape, u16),
+        size: u16,
+    ) {
+        let fill = premultiplied(color);
+        match style {
+            BorderStyle::Dashed => {
+                let (shape, corner_radius) = outline;
+                let ring = crate::daemon::shape::ring_bands(
+                    shape,
+                    corner_radius,
+                    self.dimensions,
+                    0,
+                    size,
+                );
+                let dashes =
+                    crate::daemon::shape::dashed(&ring, self.dimensions, size, (size * 3).max(4));
+                self.fill_rects(&dashes, fill, false);
+            }
+            BorderStyle::Double if size >= 3 => {
+                let line = size / 3;
+                self.fill_ring(outline, 0, line, fill, false);
+                self.fill_ring(outline, size - line, line, fill, false);
+            }
+            BorderStyle::InnerGlow => {
+                // 1px rings fading from the full color at the edge
+                for i in 0..size {
+                    let alpha = u32::from(color.alpha) * u32::from(size - i) / u32::from(size);
+                    let ring = Color {
+                        alpha: alpha as u16,
+                        ..color
+                    };
+                    self.fill_ring(outline, i, 1, premultiplied(ring), false);
+                }
+            }
+            BorderStyle::Solid | BorderStyle::Double => {
+                self.fill_ring(outline, 0, size, fill, false);
+            }
+        }
+    }
+
+    /// Fills a ring `width` pixels wide, `inset` pixels in from the thumbnail's `outline`
+    fn fill_ring(
+        &self,
+        outline: (ThumbnailShape, u16),
+        inset: u16,
+        width: u16,
+        bgra: [u8; 4],
+        blend: bool,
+    ) {
+        let (shape, corner_radius) = outline;
+        let bands =
+            crate::daemon::shape::ring_bands(shape, corner_radius, self.dimensions, inset, width);
+        self.fill_rects(&bands, bgra, blend);
+    }
+
+    /// Replaces the pixels of `rects` with `bgra` or (with `blend`) draws it over them
+    fn fill_rects(&self, rects: &[Rectangle], bgra: [u8; 4], blend: bool) {
+        let (width, height) = (
+            i32::from(self.dimensions.width),
+            i32::from(self.dimensions.height),
+        );
+        let mut layer = self.layer.borrow_mut();
+        for rect in rects {
+            let (left, top) = (i32::from(rect.x).max(0), i32::from(rect.y).max(0));
+            let right = (i32::from(rect.x) + i32::from(rect.width)).min(width);
+            let bottom = (i32::from(rect.y) + i32::from(rect.height)).min(height);
+            for y in top..bottom {
+                for x in left..right {
+                    let i = ((y * width + x) * 4) as usize;
+                    if blend {
+                        over(&mut layer[i..i + 4], &bgra);
+                    } else {
+                        layer[i..i + 4].copy_from_slice(&bgra);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws a premultiplied BGRA bitmap over the layer with its top-left at (x, y)
+    fn blit(&self, data: &[u8], width: usize, height: usize, x: i32, y: i32) {
+        if data.len() < width * height * 4 {
+            return;
+        }
+        let (layer_w, layer_h) = (
+            i32::from(self.dimensions.width),
+            i32::from(self.dimensions.height),
+        );
+        let mut layer = self.layer.borrow_mut();
+        for row in 0..height as i32 {
+            let dst_y = y + row;
+            if !(0..layer_h).contains(&dst_y) {
+                continue;
+            }
+            for col in 0..width as i32 {
+                let dst_x = x + col;
+                if !(0..layer_w).contains(&dst_x) {
+                    continue;
+                }
+                let src = ((row * width as i32 + col) * 4) as usize;
+                let dst = ((dst_y * layer_w + dst_x) * 4) as usize;
+                over(&mut layer[dst..dst + 4], &data[src..src + 4]);
+            }
+        }
+    }
+}
+
+impl OverlayBackend for SoftwareOverlay {
+    /// Premultiplied BGRA pixels of the thumbnail, the overlay's size
+    type Target = [u8];
+
+    fn name(&self) -> &'static str {
+        "software"
+    }
+
+    fn set_notes_visible(&mut self, visible: bool) {
+        self.notes_visible = visible;
+    }
+
+    fn set_server(&mut self, server: EveServer) {
+        self.server = server;
+    }
+
+    fn set_custom_source(&mut self, custom: bool) {
+        self.custom_source = custom;
+    }
+
+    fn resize(&mut self, dimensions: Dimensions) -> Result<()> {
+        self.dimensions = dimensions;
+        *self.layer.get_mut() = vec![0; layer_len(dimensions)];
+        Ok(())
+    }
+
+    fn clear_content_area(&self, dimensions: Dimensions, border_size: u16) -> Result<()> {
+        let content = Rectangle {
+            x: border_size as i16,
+            y: border_size as i16,
+            width: dimensions.width.saturating_sub(border_size * 2),
+            height: dimensions.height.saturating_sub(border_size * 2),
+        };
+        self.fill_rects(&[content], [0; 4], false);
+        Ok(())
+    }
+
+    fn update_name(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        _dimensions: Dimensions,
+        _border_size: u16,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.draw_label(config, character_name, font_renderer)
+    }
+
+    fn draw_border(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        _dimensions: Dimensions,
+        focused: bool,
+        skipped: bool,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.draw(
+            config,
+            character_name,
+            BorderState::of(focused, skipped),
+            skipped,
+            Some(font_renderer),
+        )
+    }
+
+    /// Draws the minimized state overlay: the configured image and/or text, centered.
+    fn draw_minimized(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.draw(
+            config,
+            character_name,
+            BorderState::Minimized,
+            false,
+            Some(font_renderer),
+        )?;
+
+        let text = config.minimized_text.trim();
+        let text = if config.minimized_overlay_enabled
+            && !text.is_empty()
+            && !font_renderer.requires_direct_rendering()
+        {
+            Some(font_renderer.render_text(text, config.text_color, TextDecoration::None)?)
+        } else {
+            None
+        };
+        let text_height = text.as_ref().map_or(0, |t| t.height as u16);
+
+        let image = minimized_image(config, character_name).and_then(|icon| {
+            let size = (dimensions.width.min(dimensions.height) / 2)
+                .min(dimensions.height.saturating_sub(text_height) * 3 / 4);
+            crate::daemon::icons::load_icon(&icon, size)
+        });
+
+        // Image and text are centered as one block, the text under the image
+        let gap = if image.is_some() && text.is_some() {
+            defaults::thumbnail::ICON_MARGIN as i32
+        } else {
+            0
+        };
+        let image_size = image.as_ref().map_or(0, |bitmap| i32::from(bitmap.size));
+        let mut y = (i32::from(dimensions.height) - image_size - gap - i32::from(text_height)) / 2;
+        if let Some(bitmap) = image {
+            let x = (i32::from(dimensions.width) - image_size) / 2;
+            let size = usize::from(bitmap.size);
+            self.blit(&bitmap.data, size, size, x, y);
+            y += image_size + gap;
+        }
+        if let Some(text) = text {
+            let x = (i32::from(dimensions.width) - text.width as i32) / 2;
+            self.blit(&text.data, text.width, text.height, x, y);
+        }
+        Ok(())
+    }
+
+    fn composite(&self, target: &mut [u8], dimensions: Dimensions) -> Result<()> {
+        let layer = self.layer.borrow();
+        if dimensions != self.dimensions || target.len() != layer.len() {
+            bail!(
+                "Overlay is {}x{} but the target holds {} bytes for {}x{}",
+                self.dimensions.width,
+                self.dimensions.height,
+                target.len(),
+                dimensions.width,
+                dimensions.height
+            );
+        }
+        for (dst, src) in target.chunks_exact_mut(4).zip(layer.chunks_exact(4)) {
+            over(dst, src);
+        }
+        Ok(())
+    }
+}
+
+fn layer_len(dimensions: Dimensions) -> usize {
+    usize::from(dimensions.width) * usize::from(dimensions.height) * 4
+}
+
+/// Premultiplied `src` over `dst`: dst = src + dst * (1 - src_alpha)
+fn over(dst: &mut [u8], src: &[u8]) {
+    let inverse = 0xFF - u32::from(src[3]);
+    for c in 0..4 {
+        dst[c] = (u32::from(src[c]) + u32::from(dst[c]) * inverse / 0xFF) as u8;
+    }
+}
+
+/// RENDER color as premultiplied BGRA bytes
+fn premultiplied(color: Color) -> [u8; 4] {
+    let argb = u32::from(color.alpha >> 8) << 24
+        | u32::from(color.red >> 8) << 16
+        | u32::from(color.green >> 8) << 8
+        | u32::from(color.blue >> 8);
+    premultiplied_bgra(argb)
+}
+
+/// ARGB32 color as premultiplied BGRA bytes
+fn premultiplied_bgra(argb: u32) -> [u8; 4] {
+    let a = argb >> 24;
+    let premultiply = |shift: u32| (((argb >> shift) & 0xFF) * a / 0xFF) as u8;
+    [premultiply(0), premultiply(8), premultiply(16), a as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_premultiplied_bgra() {
+        assert_eq!(premultiplied_bgra(0xFF_FF_00_00), [0, 0, 0xFF, 0xFF]);
+        assert_eq!(premultiplied_bgra(0x80_FF_FF_FF), [0x80, 0x80, 0x80, 0x80]);
+    }
+
+    #[test]
+    fn test_composite_blends_border_over_content() {
+        let mut profile =
+            crate::config::profile::Profile::default_with_name("Test".to_string(), String::new());
+        profile.thumbnail_active_border = true;
+        profile.thumbnail_active_border_size = 2;
+        profile.thumbnail_active_border_color = "#FF00FF00".to_string();
+        let config = crate::config::DaemonConfig::for_profile(profile).build_display_config();
+
+        let dims = Dimensions::new(8, 8);
+        let overlay = SoftwareOverlay::new(dims);
+        overlay
+            .draw(&config, "Bob", BorderState::Focused, false, None)
+            .unwrap();
+
+        let mut pixels = [0x10, 0x20, 0x30, 0xFF].repeat(64);
+        overlay.composite(&mut pixels, dims).unwrap();
+        // Edge pixel takes the border color, the center keeps the content
+        assert_eq!(pixels[..4], [0, 0xFF, 0, 0xFF]);
+        let center = (4 * 8 + 4) * 4;
+        assert_eq!(pixels[center..center + 4], [0x10, 0x20, 0x30, 0xFF]);
+
+        assert!(overlay.composite(&mut [0; 16], dims).is_err());
+    }
+}
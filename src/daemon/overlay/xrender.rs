@@ -0,0 +1,1057 @@
+//! X RENDER overlay backend: text and borders drawn into an ARGB pixmap composited over the
+//! thumbnail
+
+use anyhow::{Context, Result};
+use tracing::error;
+use x11rb::connection::Connection;
+use x11rb::protocol::render::{
+    ChangePictureAux, Color, ConnectionExt as RenderExt, CreatePictureAux, PictOp, Picture,
+};
+use x11rb::protocol::xproto::{
+    Char2b, ConnectionExt as XprotoExt, CreateGCAux, Gcontext, Pixmap, Rectangle, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::constants::{defaults, x11};
+use crate::common::types::{Dimensions, EveServer, ThumbnailShape};
+use crate::config::{BorderStyle, DisplayConfig};
+
+use super::atlas::badge_bitmap;
+use super::{
+    BorderState, OverlayBackend, border_color, border_line, border_size, cycle_badge, glow_rings,
+    label_color, label_text, minimized_image, minimized_text_bytes, notes_line, outline,
+    status_badge_layout, statuses, text_decoration,
+};
+use crate::daemon::font::{FontRenderer, TextDecoration, label_renderer};
+
+#[derive(Debug)]
+/// Handles text and border overlay rendering for thumbnails.
+///
+/// This struct manages:
+/// - The overlay pixmap where text and borders are drawn.
+/// - The graphics context (GC) for X11 text rendering.
+/// - Integration with `FontRenderer` for text glyph generation.
+pub struct OverlayRenderer<'a> {
+    // === X11 Resources (private, owned) ===
+    /// Backing pixmap for the overlay layer.
+    overlay_pixmap: Pixmap,
+    /// X Render Picture wrapping the overlay pixmap.
+    overlay_picture: Picture,
+    overlay_gc: Gcontext,           // Graphics context for text rendering
+    active_border_fill: Picture,    // Solid color fill for active border
+    inactive_border_fill: Picture,  // Solid color fill for inactive border
+    skipped_indicator_gc: Gcontext, // GC for drawing skipped indicator (Red X)
+
+    // === Display State ===
+    /// Show the notes line regardless of `DisplayConfig::show_notes` (set while hovered).
+    notes_visible: bool,
+    /// Server the client is connected to; test servers get a badge and their own border.
+    server: EveServer,
+    /// Previews a custom source; shown as a status badge.
+    custom_source: bool,
+    /// Root window the overlay pixmap is created on.
+    root: Window,
+
+    // === Borrowed Dependencies ===
+    conn: &'a RustConnection,
+    formats: &'a crate::x11::CachedFormats,
+}
+
+impl<'a> OverlayRenderer<'a> {
+    /// Creates a new `OverlayRenderer`.
+    ///
+    /// # Arguments
+    /// * `conn` - X11 connection.
+    /// * `config` - Display configuration (colors, sizes).
+    /// * `formats` - X11 Render formats.
+    /// * `font_renderer` - System for rendering text glyphs (used for initial render).
+    /// * `root` - Root window ID (for pixmap creation).
+    /// * `dimensions` - Initial size of the overlay.
+    /// * `character_name` - Debug name for error logging.
+    pub fn new<'b>(
+        conn: &'a RustConnection,
+        config: &'b DisplayConfig,
+        formats: &'a crate::x11::CachedFormats,
+        font_renderer: &FontRenderer,
+        root: Window,
+        dimensions: Dimensions,
+        character_name: &str,
+    ) -> Result<Self> {
+        // Create overlay pixmap
+        let overlay_pixmap = conn
+            .generate_id()
+            .context("Failed to generate ID for overlay pixmap")?;
+        conn.create_pixmap(
+            x11::ARGB_DEPTH,
+            overlay_pixmap,
+            root,
+            dimensions.width,
+            dimensions.height,
+        )
+        .context(format!(
+            "Failed to create overlay pixmap for '{}'",
+            character_name
+        ))?;
+
+        // Create overlay picture
+        let overlay_picture = conn
+            .generate_id()
+            .context("Failed to generate ID for overlay picture")?;
+        conn.render_create_picture(
+            overlay_picture,
+            overlay_pixmap,
+            formats.argb,
+            &CreatePictureAux::new(),
+        )
+        .context(format!(
+            "Failed to create overlay picture for '{}'",
+            character_name
+        ))?;
+
+        // Create overlay GC
+        let overlay_gc = conn
+            .generate_id()
+            .context("Failed to generate ID for overlay graphics context")?;
+        conn.create_gc(
+            overlay_gc,
+            overlay_pixmap,
+            &CreateGCAux::new().foreground(config.text_color),
+        )
+        .context(format!(
+            "Failed to create graphics context for '{}'",
+            character_name
+        ))?;
+
+        // Create skipped indicator GC (Red)
+        let skipped_indicator_gc = conn
+            .generate_id()
+            .context("Failed to generate ID for skipped indicator GC")?;
+        conn.create_gc(
+            skipped_indicator_gc,
+            overlay_pixmap,
+            &CreateGCAux::new()
+                .foreground(0xFFFF0000) // Opaque Red
+                .line_width(3), // Thicker lines for visibility
+        )
+        .context(format!(
+            "Failed to create skipped indicator GC for '{}'",
+            character_name
+        ))?;
+
+        // Create active border fill
+        let active_border_fill = conn
+            .generate_id()
+            .context("Failed to generate ID for active border fill picture")?;
+        conn.render_create_solid_fill(active_border_fill, config.active_border_color)
+            .context(format!(
+                "Failed to create active border fill for '{}'",
+                character_name
+            ))?;
+
+        // Create inactive border fill
+        let inactive_border_fill = conn
+            .generate_id()
+            .context("Failed to generate ID for inactive border fill picture")?;
+        conn.render_create_solid_fill(inactive_border_fill, config.inactive_border_color)
+            .context(format!(
+                "Failed to create inactive border fill for '{}'",
+                character_name
+            ))?;
+
+        let renderer = Self {
+            overlay_pixmap,
+            overlay_picture,
+            overlay_gc,
+            active_border_fill,
+            inactive_border_fill,
+            skipped_indicator_gc,
+            notes_visible: false,
+            server: EveServer::default(),
+            custom_source: false,
+            root,
+            conn,
+            formats,
+        };
+
+        // Render initial name
+        let initial_border_size = border_size(config, character_name, false);
+        renderer
+            .clear_content_area(dimensions, initial_border_size)
+            .context(format!(
+                "Failed to clear content area for initial render of '{}'",
+                character_name
+            ))?;
+
+        renderer
+            .update_name(
+                config,
+                character_name,
+                dimensions,
+                initial_border_size,
+                font_renderer,
+            )
+            .context(format!(
+                "Failed to render initial name for '{}'",
+                character_name
+            ))?;
+
+        Ok(renderer)
+    }
+
+    /// Draws the skipped indicator (diagonal red lines)
+    pub fn draw_skipped_indicator(&self, dimensions: Dimensions) -> Result<()> {
+        let w = dimensions.width as i16;
+        let h = dimensions.height as i16;
+
+        let segments = [
+            x11rb::protocol::xproto::Segment {
+                x1: 0,
+                y1: 0,
+                x2: w,
+                y2: h,
+            },
+            x11rb::protocol::xproto::Segment {
+                x1: w,
+                y1: 0,
+                x2: 0,
+                y2: h,
+            },
+        ];
+
+        self.conn
+            .poly_segment(self.overlay_pixmap, self.skipped_indicator_gc, &segments)
+            .context("Failed to draw skipped indicator segments")?;
+
+        Ok(())
+    }
+
+    /// Fills a ring `width` pixels wide, `inset` pixels in from the thumbnail's `outline`
+    fn fill_ring(
+        &self,
+        fill: Picture,
+        op: PictOp,
+        dimensions: Dimensions,
+        outline: (ThumbnailShape, u16),
+        inset: u16,
+        width: u16,
+    ) -> Result<()> {
+        let (shape, corner_radius) = outline;
+        let bands =
+            crate::daemon::shape::ring_bands(shape, corner_radius, dimensions, inset, width);
+        self.fill_bands(fill, op, dimensions, &bands)
+    }
+
+    /// Fills `bands` of the overlay with one composite clipped to them
+    fn fill_bands(
+        &self,
+        fill: Picture,
+        op: PictOp,
+        dimensions: Dimensions,
+        bands: &[Rectangle],
+    ) -> Result<()> {
+        if bands.is_empty() {
+            return Ok(());
+        }
+        self.conn
+            .render_set_picture_clip_rectangles(self.overlay_picture, 0, 0, bands)?;
+        self.conn.render_composite(
+            op,
+            fill,
+            0u32,
+            self.overlay_picture,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            dimensions.width,
+            dimensions.height,
+        )?;
+        self.conn.render_change_picture(
+            self.overlay_picture,
+            &ChangePictureAux::new().clipmask(x11rb::NONE),
+        )?;
+        Ok(())
+    }
+
+    /// Draws a border `size` pixels wide along the outline in the given style
+    fn stroke_border(
+        &self,
+        fill: Picture,
+        color: Color,
+        style: BorderStyle,
+        dimensions: Dimensions,
+        outline: (ThumbnailShape, u16),
+        size: u16,
+    ) -> Result<()> {
+        match style {
+            BorderStyle::Dashed => {
+                let (shape, corner_radius) = outline;
+                let ring =
+                    crate::daemon::shape::ring_bands(shape, corner_radius, dimensions, 0, size);
+                let dashes =
+                    crate::daemon::shape::dashed(&ring, dimensions, size, (size * 3).max(4));
+                self.fill_bands(fill, PictOp::SRC, dimensions, &dashes)
+            }
+            BorderStyle::Double if size >= 3 => {
+                let line = size / 3;
+                self.fill_ring(fill, PictOp::SRC, dimensions, outline, 0, line)?;
+                self.fill_ring(fill, PictOp::SRC, dimensions, outline, size - line, line)
+            }
+            BorderStyle::InnerGlow => {
+                // 1px rings fading from the full color at the edge
+                for i in 0..size {
+                    let alpha = u32::from(color.alpha) * u32::from(size - i) / u32::from(size);
+                    let pid = self.conn.generate_id()?;
+                    self.conn.render_create_solid_fill(
+                        pid,
+                        Color {
+                            alpha: alpha as u16,
+                            ..color
+                        },
+                    )?;
+                    self.fill_ring(pid, PictOp::SRC, dimensions, outline, i, 1)?;
+                    self.conn.render_free_picture(pid)?;
+                }
+                Ok(())
+            }
+            BorderStyle::Solid | BorderStyle::Double => {
+                self.fill_ring(fill, PictOp::SRC, dimensions, outline, 0, size)
+            }
+        }
+    }
+
+    /// Draws the overlay content with strict Z-order:
+    /// 1. Skipped Indicator (Red X) - Bottom
+    /// 2. Text (Name), Corner Icon, Cycle Badge and Status Badges - Middle
+    /// 3. Border - Top (covers everything at edges), styled for `state`
+    fn draw_overlay(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        state: BorderState,
+        skipped: bool,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        // 1. Clear the entire overlay first (transparent background)
+        self.conn
+            .render_composite(
+                PictOp::CLEAR,
+                self.overlay_picture,
+                0u32,
+                self.overlay_picture,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                dimensions.width,
+                dimensions.height,
+            )
+            .context("Failed to clear overlay")?;
+
+        // 2. Draw skipped indicator (Red X)
+        // Drawn first so text appears on top of it
+        if skipped {
+            self.draw_skipped_indicator(dimensions)?;
+        }
+
+        // Style and width for this state; test-server and per-state rules live in `border_line`
+        let focused = state.is_focused();
+        let (style, drawn_size) = border_line(config, character_name, state, self.server);
+        let test_server_border = self.server.is_test_server() && !focused;
+        let effective_size = border_size(config, character_name, focused);
+
+        // 3. Draw Text
+        // We pass effective_size mainly if text positioning depended on it,
+        // but currently text is positioned by config offset.
+        self.update_name(
+            config,
+            character_name,
+            dimensions,
+            effective_size,
+            font_renderer,
+        )
+        .context(format!(
+            "Failed to update name overlay for '{}'",
+            character_name
+        ))?;
+
+        self.draw_corner_icon(config, character_name, dimensions)?;
+        self.draw_cycle_badge(config, character_name, dimensions, font_renderer)?;
+        self.draw_status_badges(config, character_name, dimensions, state, skipped)?;
+
+        // 4. Draw Border (Top Layer)
+        let should_draw_border = drawn_size > 0;
+
+        let outline = outline(config, character_name);
+
+        // Padding sits between the border and the content, covering the content's edge
+        if config.border_padding > 0 {
+            let pid = self.conn.generate_id()?;
+            self.conn
+                .render_create_solid_fill(pid, defaults::border::PADDING_COLOR)?;
+            self.fill_ring(
+                pid,
+                PictOp::SRC,
+                dimensions,
+                outline,
+                drawn_size,
+                config.border_padding,
+            )?;
+            self.conn.render_free_picture(pid)?;
+        }
+
+        // Glow fades inward from the border, over padding and content
+        if should_draw_border && config.border_glow > 0 {
+            let color = border_color(config, character_name, focused, self.server);
+            for (inset, alpha) in glow_rings(config, drawn_size, color) {
+                let pid = self.conn.generate_id()?;
+                self.conn
+                    .render_create_solid_fill(pid, Color { alpha, ..color })?;
+                self.fill_ring(pid, PictOp::OVER, dimensions, outline, inset, 1)?;
+                self.conn.render_free_picture(pid)?;
+            }
+        }
+
+        if should_draw_border {
+            let (fill_picture, temp_fill_id) = if test_server_border {
+                let pid = self.conn.generate_id()?;
+                self.conn
+                    .render_create_solid_fill(pid, config.test_server_border_color)?;
+                (pid, Some(pid))
+            } else if let Some(settings) = config.character_settings.get(character_name) {
+                let override_color_hex = if focused {
+                    settings.override_active_border_color.as_ref()
+                } else {
+                    settings.override_inactive_border_color.as_ref()
+                };
+
+                if let Some(hex) = override_color_hex {
+                    if let Some(color) =
+                        crate::common::color::HexColor::parse(hex).map(|c| c.to_x11_color())
+                    {
+                        let pid = self.conn.generate_id()?;
+                        self.conn.render_create_solid_fill(pid, color)?;
+                        (pid, Some(pid))
+                    } else if focused {
+                        (self.active_border_fill, None)
+                    } else {
+                        (self.inactive_border_fill, None)
+                    }
+                } else if focused {
+                    (self.active_border_fill, None)
+                } else {
+                    (self.inactive_border_fill, None)
+                }
+            } else if focused {
+                (self.active_border_fill, None)
+            } else {
+                (self.inactive_border_fill, None)
+            };
+
+            let color = border_color(config, character_name, focused, self.server);
+            self.stroke_border(fill_picture, color, style, dimensions, outline, drawn_size)?;
+
+            // Clean up temp fill
+            if let Some(pid) = temp_fill_id {
+                self.conn.render_free_picture(pid)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws a single line of text onto the overlay at the given top-left position.
+    /// The decoration needs TrueType rendering; core fonts draw plain text.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_text(
+        &self,
+        text: &str,
+        text_color: u32,
+        decoration: TextDecoration,
+        x: i16,
+        y: i16,
+        character_name: &str,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        // Render text based on font renderer type
+        if font_renderer.requires_direct_rendering() {
+            // X11 fallback: direct rendering using ImageText8
+            if let Some(font_id) = font_renderer.x11_font_id() {
+                // Create GC with font
+                let gc = self
+                    .conn
+                    .generate_id()
+                    .context("Failed to generate GC ID for X11 text")?;
+
+                // Convert ARGB color to X11 pixel value (strip alpha)
+                let fg_pixel = text_color & 0x00FFFFFF;
+
+                self.conn
+                    .create_gc(
+                        gc,
+                        self.overlay_pixmap,
+                        &CreateGCAux::new().font(font_id).foreground(fg_pixel),
+                    )
+                    .context(format!(
+                        "Failed to create GC for X11 text rendering for '{}'",
+                        character_name
+                    ))?;
+
+                // ImageText8 renders directly to drawable
+                self.conn
+                    .image_text8(
+                        self.overlay_pixmap,
+                        gc,
+                        x,
+                        y + font_renderer.size() as i16, // Baseline adjustment
+                        text.as_bytes(),
+                    )
+                    .context(format!(
+                        "Failed to render text via X11 for '{}'",
+                        character_name
+                    ))?;
+
+                self.conn.free_gc(gc)?;
+            }
+        } else {
+            // Fontdue: pre-rendered bitmap
+            let rendered = font_renderer
+                .render_text(text, text_color, decoration)
+                .context(format!(
+                    "Failed to render text '{}' with font renderer",
+                    character_name
+                ))?;
+            if rendered.width > 0 && rendered.height > 0 {
+                // rendered.data is already in BGRA format (Little Endian ARGB)
+                self.composite_bitmap(
+                    &rendered.data,
+                    rendered.width as u16,
+                    rendered.height as u16,
+                    x - rendered.origin.0 as i16,
+                    y - rendered.origin.1 as i16,
+                    character_name,
+                )
+                .context(format!(
+                    "Failed to composite text onto overlay for '{}'",
+                    character_name
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Uploads a premultiplied BGRA bitmap and composites it onto the overlay at (x, y).
+    fn composite_bitmap(
+        &self,
+        data: &[u8],
+        width: u16,
+        height: u16,
+        x: i16,
+        y: i16,
+        character_name: &str,
+    ) -> Result<()> {
+        let pixmap = self
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for bitmap pixmap")?;
+        self.conn
+            .create_pixmap(x11::ARGB_DEPTH, pixmap, self.overlay_pixmap, width, height)
+            .context(format!(
+                "Failed to create bitmap pixmap for '{}'",
+                character_name
+            ))?;
+
+        crate::x11::put_image(
+            self.conn,
+            pixmap,
+            self.overlay_gc,
+            width,
+            height,
+            x11::ARGB_DEPTH,
+            data,
+        )
+        .context(format!(
+            "Failed to upload bitmap image for '{}'",
+            character_name
+        ))?;
+
+        // Create picture for the bitmap pixmap
+        let picture = self
+            .conn
+            .generate_id()
+            .context("Failed to generate ID for bitmap picture")?;
+        self.conn
+            .render_create_picture(picture, pixmap, self.formats.argb, &CreatePictureAux::new())
+            .context(format!(
+                "Failed to create bitmap picture for '{}'",
+                character_name
+            ))?;
+
+        // Composite bitmap onto overlay
+        self.conn
+            .render_composite(
+                PictOp::OVER,
+                picture,
+                0u32,
+                self.overlay_picture,
+                0,
+                0,
+                0,
+                0,
+                x,
+                y,
+                width,
+                height,
+            )
+            .context(format!(
+                "Failed to composite bitmap onto overlay for '{}'",
+                character_name
+            ))?;
+
+        // Cleanup
+        self.conn
+            .render_free_picture(picture)
+            .context("Failed to free bitmap picture")?;
+        self.conn
+            .free_pixmap(pixmap)
+            .context("Failed to free bitmap pixmap")?;
+        Ok(())
+    }
+
+    /// Draws the per-character corner icon (portrait or custom PNG) in the top-right corner.
+    pub fn draw_corner_icon(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let Some(icon) = config
+            .character_settings
+            .get(character_name)
+            .map(|s| &s.corner_icon)
+        else {
+            return Ok(());
+        };
+
+        // Keep the icon inside the thumbnail even for tiny previews
+        let size = config
+            .icon_size
+            .min(dimensions.width / 2)
+            .min(dimensions.height / 2);
+
+        let Some(bitmap) = crate::daemon::icons::load_icon(icon, size) else {
+            return Ok(());
+        };
+
+        let margin = defaults::thumbnail::ICON_MARGIN;
+        let x = dimensions.width as i16 - bitmap.size as i16 - margin;
+        self.composite_bitmap(
+            &bitmap.data,
+            bitmap.size,
+            bitmap.size,
+            x,
+            margin,
+            character_name,
+        )
+        .context(format!(
+            "Failed to draw corner icon for '{}'",
+            character_name
+        ))
+    }
+
+    /// Draws the cycle-order badge: a translucent box with the position number centered in it.
+    fn draw_cycle_badge(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        let Some(badge) = cycle_badge(config, character_name, dimensions) else {
+            return Ok(());
+        };
+
+        self.conn
+            .render_fill_rectangles(
+                PictOp::OVER,
+                self.overlay_picture,
+                defaults::thumbnail::CYCLE_BADGE_BACKGROUND,
+                &[Rectangle {
+                    x: badge.x,
+                    y: badge.y,
+                    width: badge.width,
+                    height: badge.height,
+                }],
+            )
+            .context(format!(
+                "Failed to draw cycle badge for '{}'",
+                character_name
+            ))?;
+
+        // Digits at roughly 70% of the badge height, in the label color
+        let text_size = (badge.height * 7 / 10).max(1);
+        let custom_font = label_renderer(font_renderer, None, Some(text_size));
+        let font_renderer = custom_font.as_deref().unwrap_or(font_renderer);
+        let number = badge.number.to_string();
+        if font_renderer.requires_direct_rendering() {
+            let inset = (badge.height.saturating_sub(font_renderer.size() as u16) / 2) as i16;
+            return self.draw_text(
+                &number,
+                config.text_color,
+                TextDecoration::None,
+                badge.x + inset,
+                badge.y + inset,
+                character_name,
+                font_renderer,
+            );
+        }
+
+        let text = font_renderer.render_text(&number, config.text_color, TextDecoration::None)?;
+        if text.width == 0 || text.height == 0 {
+            return Ok(());
+        }
+        let x = badge.x + (badge.width as i16 - text.width as i16) / 2;
+        let y = badge.y + (badge.height as i16 - text.height as i16) / 2;
+        self.composite_bitmap(
+            &text.data,
+            text.width as u16,
+            text.height as u16,
+            x,
+            y,
+            character_name,
+        )
+        .context(format!(
+            "Failed to draw cycle badge number for '{}'",
+            character_name
+        ))
+    }
+
+    /// Composites the status badge icons from the atlas along the configured edge.
+    fn draw_status_badges(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        state: BorderState,
+        skipped: bool,
+    ) -> Result<()> {
+        let badges = statuses(config, character_name, state, skipped, self.custom_source);
+        let (size, positions) =
+            status_badge_layout(config, character_name, dimensions, badges.len());
+        for (badge, (x, y)) in badges.into_iter().zip(positions) {
+            let Some(bitmap) = badge_bitmap(badge, size) else {
+                continue;
+            };
+            self.composite_bitmap(&bitmap.data, bitmap.size, bitmap.size, x, y, character_name)
+                .context(format!(
+                    "Failed to draw {:?} status badge for '{}'",
+                    badge, character_name
+                ))?;
+        }
+        Ok(())
+    }
+
+    fn cleanup_overlay_resources(&self) {
+        if let Err(e) = self.conn.free_pixmap(self.overlay_pixmap) {
+            error!(pixmap = self.overlay_pixmap, error = %e, "Failed to free overlay pixmap");
+        }
+
+        if let Err(e) = self.conn.render_free_picture(self.overlay_picture) {
+            error!(picture = self.overlay_picture, error = %e, "Failed to free overlay picture");
+        }
+    }
+}
+
+impl OverlayBackend for OverlayRenderer<'_> {
+    type Target = Picture;
+
+    fn name(&self) -> &'static str {
+        "xrender"
+    }
+
+    fn set_notes_visible(&mut self, visible: bool) {
+        self.notes_visible = visible;
+    }
+
+    fn set_server(&mut self, server: EveServer) {
+        self.server = server;
+    }
+
+    fn set_custom_source(&mut self, custom: bool) {
+        self.custom_source = custom;
+    }
+
+    /// Recreates the overlay pixmap and picture at the new size.
+    fn resize(&mut self, dimensions: Dimensions) -> Result<()> {
+        // Free old resources
+        self.cleanup_overlay_resources();
+
+        // Recreate resources with new dimensions
+        let overlay_pixmap = self.conn.generate_id()?;
+        self.conn.create_pixmap(
+            x11::ARGB_DEPTH,
+            overlay_pixmap,
+            self.root,
+            dimensions.width,
+            dimensions.height,
+        )?;
+        self.overlay_pixmap = overlay_pixmap;
+
+        let overlay_picture = self.conn.generate_id()?;
+        self.conn.render_create_picture(
+            overlay_picture,
+            overlay_pixmap,
+            self.formats.argb,
+            &CreatePictureAux::new(),
+        )?;
+        self.overlay_picture = overlay_picture;
+
+        Ok(())
+    }
+
+    /// Clears the center content area (inside the border).
+    fn clear_content_area(&self, dimensions: Dimensions, border_size: u16) -> Result<()> {
+        self.conn
+            .render_composite(
+                PictOp::CLEAR,
+                self.overlay_picture,
+                0u32,
+                self.overlay_picture,
+                0,
+                0,
+                0,
+                0,
+                border_size as i16,
+                border_size as i16,
+                dimensions.width.saturating_sub(border_size * 2),
+                dimensions.height.saturating_sub(border_size * 2),
+            )
+            .context("Failed to clear content area")?;
+        Ok(())
+    }
+
+    /// Renders the character name (and optional notes line) onto the overlay.
+    ///
+    /// Handles both direct X11 text rendering (if core fonts are used) and
+    /// client-side rendering (if TrueType fonts are used via `fontdue`).
+    /// NOTE: This does NOT clear the background. You must call `clear_content_area` first.
+    fn update_name(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        _dimensions: Dimensions,
+        _border_size: u16,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        // Resolve settings overrides
+        let settings = config.character_settings.get(character_name);
+        let display_name = label_text(config, character_name, self.server);
+        let text_color = label_color(config, character_name);
+        let custom_font = settings.and_then(|s| {
+            label_renderer(
+                font_renderer,
+                s.override_text_font.as_deref(),
+                s.override_text_size,
+            )
+        });
+        let font_renderer = custom_font.as_deref().unwrap_or(font_renderer);
+
+        let decoration = text_decoration(config);
+        self.draw_text(
+            &display_name,
+            text_color,
+            decoration,
+            config.text_offset.x,
+            config.text_offset.y,
+            character_name,
+            font_renderer,
+        )?;
+
+        // Secondary label line: character notes (always, or only while hovered)
+        if config.show_notes || self.notes_visible {
+            let notes_line = notes_line(config, character_name);
+
+            if let Some(notes_line) = notes_line {
+                let line_height = font_renderer.size() as i16 + defaults::text::NOTES_LINE_SPACING;
+                self.draw_text(
+                    &notes_line,
+                    text_color,
+                    decoration,
+                    config.text_offset.x,
+                    config.text_offset.y + line_height,
+                    character_name,
+                    font_renderer,
+                )
+                .context(format!("Failed to render notes for '{}'", character_name))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_border(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        focused: bool,
+        skipped: bool,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.draw_overlay(
+            config,
+            character_name,
+            dimensions,
+            BorderState::of(focused, skipped),
+            skipped,
+            font_renderer,
+        )
+    }
+
+    /// Draws the minimized state overlay: the configured image and/or text, centered.
+    fn draw_minimized(
+        &self,
+        config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.draw_overlay(
+            config,
+            character_name,
+            dimensions,
+            BorderState::Minimized,
+            false,
+            font_renderer,
+        )
+        .context(format!(
+            "Failed to clear border for minimized window '{}'",
+            character_name
+        ))?;
+
+        let text = if config.minimized_overlay_enabled {
+            minimized_text_bytes(&config.minimized_text)
+        } else {
+            Vec::new()
+        };
+        let extents = if text.is_empty() {
+            None
+        } else {
+            Some(
+                self.conn
+                    .query_text_extents(
+                        self.overlay_gc,
+                        text.iter()
+                            .map(|&c| Char2b { byte1: 0, byte2: c })
+                            .collect::<Vec<_>>()
+                            .as_slice(),
+                    )
+                    .context("Failed to send text extents query for minimized text")?
+                    .reply()
+                    .context("Failed to get text extents for minimized text")?,
+            )
+        };
+        let text_height = extents
+            .as_ref()
+            .map_or(0, |e| (e.font_ascent + e.font_descent) as u16);
+
+        let image = minimized_image(config, character_name).and_then(|icon| {
+            let size = (dimensions.width.min(dimensions.height) / 2)
+                .min(dimensions.height.saturating_sub(text_height) * 3 / 4);
+            crate::daemon::icons::load_icon(&icon, size)
+        });
+
+        // Image and text are centered as one block, the text under the image
+        let gap = if image.is_some() && extents.is_some() {
+            defaults::thumbnail::ICON_MARGIN
+        } else {
+            0
+        };
+        let image_size = image.as_ref().map_or(0, |bitmap| bitmap.size);
+        let mut y = (dimensions.height as i16 - image_size as i16 - gap - text_height as i16) / 2;
+
+        if let Some(bitmap) = image {
+            self.composite_bitmap(
+                &bitmap.data,
+                bitmap.size,
+                bitmap.size,
+                (dimensions.width as i16 - bitmap.size as i16) / 2,
+                y,
+                character_name,
+            )
+            .context(format!(
+                "Failed to draw minimized image for '{}'",
+                character_name
+            ))?;
+            y += bitmap.size as i16 + gap;
+        }
+
+        if let Some(extents) = extents {
+            self.conn
+                .image_text8(
+                    self.overlay_pixmap,
+                    self.overlay_gc,
+                    (dimensions.width as i16 - extents.overall_width as i16) / 2,
+                    y + extents.font_ascent,
+                    &text,
+                )
+                .context(format!(
+                    "Failed to render minimized text for '{}'",
+                    character_name
+                ))?;
+        }
+        Ok(())
+    }
+
+    fn composite(&self, target: &mut Picture, dimensions: Dimensions) -> Result<()> {
+        self.conn
+            .render_composite(
+                PictOp::OVER,
+                self.overlay_picture,
+                0u32,
+                *target,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                dimensions.width,
+                dimensions.height,
+            )
+            .context("Failed to composite overlay")?;
+        Ok(())
+    }
+}
+
+impl Drop for OverlayRenderer<'_> {
+    fn drop(&mut self) {
+        self.cleanup_overlay_resources();
+
+        if let Err(e) = self.conn.free_gc(self.overlay_gc) {
+            error!(gc = self.overlay_gc, error = %e, "Failed to free GC");
+        }
+
+        if let Err(e) = self.conn.free_gc(self.skipped_indicator_gc) {
+            error!(gc = self.skipped_indicator_gc, error = %e, "Failed to free skipped indicator GC");
+        }
+
+        if let Err(e) = self.conn.render_free_picture(self.active_border_fill) {
+            error!(picture = self.active_border_fill, error = %e, "Failed to free active border fill picture");
+        }
+
+        if let Err(e) = self.conn.render_free_picture(self.inactive_border_fill) {
+            error!(
+                picture = self.inactive_border_fill,
+                error = %e,
+                "Failed to free inactive border fill picture"
+            );
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Overview (exposé) mode
+//!
+//! Temporarily tiles every thumbnail across the screen at a large size so all clients can be
+//! compared at a glance. The original geometry and visibility of each thumbnail is remembered
+//! in `SessionState` and restored when the view is dismissed (hotkey again or left-click).
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+use x11rb::protocol::xproto::{Screen, Window};
+
+use super::cycle_state::CycleState;
+use super::font::FontRenderer;
+use super::session_state::SessionState;
+use super::snapping::Rect;
+use super::stacking;
+use super::thumbnail::Thumbnail;
+use crate::common::constants::positioning;
+use crate::common::types::{Dimensions, Position};
+use crate::config::DisplayConfig;
+
+/// Geometry and visibility of a thumbnail before the overview took it over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SavedGeometry {
+    pub position: Position,
+    pub dimensions: Dimensions,
+    pub visible: bool,
+}
+
+/// Splits `area` into `count` equally sized grid cells.
+///
+/// The column count is chosen so that tiles of the given aspect ratio end up as large as possible.
+pub fn grid_cells(count: usize, area: Dimensions, aspect: f32, gap: u16) -> Vec<Rect> {
+    if count == 0 || area.width == 0 || area.height == 0 {
+        return Vec::new();
+    }
+    let aspect = if aspect > 0.0 { aspect } else { 16.0 / 9.0 };
+
+    let mut best_cols = 1;
+    let mut best_width = 0.0f32;
+    for cols in 1..=count {
+        let rows = count.div_ceil(cols);
+        let cell_w = (area.width as f32 - gap as f32 * (cols + 1) as f32) / cols as f32;
+        let cell_h = (area.height as f32 - gap as f32 * (rows + 1) as f32) / rows as f32;
+        let tile_w = cell_w.min(cell_h * aspect);
+        if tile_w > best_width {
+            best_width = tile_w;
+            best_cols = cols;
+        }
+    }
+
+    let cols = best_cols;
+    let rows = count.div_ceil(cols);
+    let cell_w = (area.width.saturating_sub(gap * (cols as u16 + 1)) / cols as u16).max(1);
+    let cell_h = (area.height.saturating_sub(gap * (rows as u16 + 1)) / rows as u16).max(1);
+
+    (0..count)
+        .map(|i| {
+            let (col, row) = ((i % cols) as u16, (i / cols) as u16);
+            Rect {
+                x: (gap + col * (cell_w + gap)) as i16,
+                y: (gap + row * (cell_h + gap)) as i16,
+                width: cell_w,
+                height: cell_h,
+            }
+        })
+        .collect()
+}
+
+/// Largest rectangle with the aspect ratio of `dimensions` centered inside `cell`
+pub fn fit_in_cell(cell: Rect, dimensions: Dimensions) -> Rect {
+    if dimensions.width == 0 || dimensions.height == 0 {
+        return cell;
+    }
+    let scale = (cell.width as f32 / dimensions.width as f32)
+        .min(cell.height as f32 / dimensions.height as f32);
+    let width = ((dimensions.width as f32 * scale) as u16).clamp(1, cell.width.max(1));
+    let height = ((dimensions.height as f32 * scale) as u16).clamp(1, cell.height.max(1));
+
+    Rect {
+        x: cell.x + ((cell.width - width) / 2) as i16,
+        y: cell.y + ((cell.height - height) / 2) as i16,
+        width,
+        height,
+    }
+}
+
+/// Toggles the overview on or off
+pub fn toggle(
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    session_state: &mut SessionState,
+    cycle_state: &CycleState,
+    screen: &Screen,
+    display_config: &DisplayConfig,
+    font_renderer: &FontRenderer,
+) {
+    if session_state.overview.is_some() {
+        exit(
+            eve_clients,
+            session_state,
+            cycle_state,
+            display_config,
+            font_renderer,
+        );
+    } else {
+        enter(
+            eve_clients,
+            session_state,
+            cycle_state,
+            screen,
+            display_config,
+            font_renderer,
+        );
+    }
+    stacking::restack(eve_clients, display_config);
+}
+
+/// Tiles all rendered thumbnails across the screen, remembering their previous geometry
+pub fn enter(
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    session_state: &mut SessionState,
+    cycle_state: &CycleState,
+    screen: &Screen,
+    display_config: &DisplayConfig,
+    font_renderer: &FontRenderer,
+) {
+    // Thumbnails force-disabled per character stay out of the overview
+    let mut windows: Vec<(Window, String)> = eve_clients
+        .iter()
+        .filter(|(_, t)| {
+            display_config
+                .character_settings
+                .get(&t.character_name)
+                .and_then(|s| s.override_render_preview)
+                .unwrap_or(display_config.enabled)
+        })
+        .map(|(w, t)| (*w, t.character_name.clone()))
+        .collect();
+
+    if windows.is_empty() {
+        info!("No thumbnails to show in overview");
+        return;
+    }
+
+    // Stable order: named characters alphabetically, logged-out clients last
+    windows.sort_by(|(wa, a), (wb, b)| {
+        (a.is_empty(), a.to_lowercase(), *wa).cmp(&(b.is_empty(), b.to_lowercase(), *wb))
+    });
+
+    let aspect = windows
+        .iter()
+        .filter_map(|(w, _)| eve_clients.get(w))
+        .map(|t| t.dimensions.width as f32 / t.dimensions.height.max(1) as f32)
+        .sum::<f32>()
+        / windows.len() as f32;
+
+    let cells = grid_cells(
+        windows.len(),
+        Dimensions::new(screen.width_in_pixels, screen.height_in_pixels),
+        aspect,
+        positioning::OVERVIEW_GAP,
+    );
+
+    let mut saved = HashMap::new();
+    for ((window, _), cell) in windows.iter().zip(cells) {
+        let Some(thumbnail) = eve_clients.get_mut(window) else {
+            continue;
+        };
+
+        saved.insert(
+            *window,
+            SavedGeometry {
+                position: thumbnail.current_position,
+                dimensions: thumbnail.dimensions,
+                visible: thumbnail.is_visible(),
+            },
+        );
+
+        let tile = fit_in_cell(cell, thumbnail.dimensions);
+        let skipped = cycle_state.is_skipped(&thumbnail.character_name);
+        if let Err(e) = apply_geometry(
+            thumbnail,
+            Position::new(tile.x, tile.y),
+            Dimensions::new(tile.width, tile.height),
+            true,
+            display_config,
+            skipped,
+            font_renderer,
+        ) {
+            warn!(character = %thumbnail.character_name, error = %e, "Failed to place thumbnail in overview");
+        }
+    }
+
+    info!(thumbnails = saved.len(), "Overview opened");
+    session_state.overview = Some(saved);
+}
+
+/// Restores every thumbnail to the geometry it had before the overview was opened
+pub fn exit(
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    session_state: &mut SessionState,
+    cycle_state: &CycleState,
+    display_config: &DisplayConfig,
+    font_renderer: &FontRenderer,
+) {
+    let Some(saved) = session_state.overview.take() else {
+        return;
+    };
+
+    for (window, geometry) in saved {
+        // Clients that closed while the overview was open are simply gone
+        let Some(thumbnail) = eve_clients.get_mut(&window) else {
+            debug!(
+                window = window,
+                "Overview client no longer exists, skipping restore"
+            );
+            continue;
+        };
+
+        let skipped = cycle_state.is_skipped(&thumbnail.character_name);
+        if let Err(e) = apply_geometry(
+            thumbnail,
+            geometry.position,
+            geometry.dimensions,
+            geometry.visible,
+            display_config,
+            skipped,
+            font_renderer,
+        ) {
+            warn!(character = %thumbnail.character_name, error = %e, "Failed to restore thumbnail after overview");
+        }
+    }
+
+    info!("Overview closed");
+}
+
+/// Moves, resizes and redraws a single thumbnail
+pub fn apply_geometry(
+    thumbnail: &mut Thumbnail,
+    position: Position,
+    dimensions: Dimensions,
+    visible: bool,
+    display_config: &DisplayConfig,
+    skipped: bool,
+    font_renderer: &FontRenderer,
+) -> Result<()> {
+    thumbnail
+        .resize(dimensions.width, dimensions.height)
+        .context(format!(
+            "Failed to resize '{}' for overview",
+            thumbnail.character_name
+        ))?;
+    thumbnail
+        .reposition(position.x, position.y)
+        .context(format!(
+            "Failed to reposition '{}' for overview",
+            thumbnail.character_name
+        ))?;
+    thumbnail.visibility(visible)?;
+
+    if !visible {
+        return Ok(());
+    }
+
+    // Resizing recreates the overlay, so the border/name must be redrawn before the content
+    if thumbnail.state.is_minimized() {
+        thumbnail.minimized(display_config, font_renderer)
+    } else {
+        thumbnail.border(
+            display_config,
+            thumbnail.state.is_focused(),
+            skipped,
+            font_renderer,
+        )?;
+        thumbnail.update(display_config, font_renderer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_cells_empty() {
+        assert!(grid_cells(0, Dimensions::new(1920, 1080), 1.78, 16).is_empty());
+    }
+
+    #[test]
+    fn test_grid_cells_single_fills_screen() {
+        let cells = grid_cells(1, Dimensions::new(1920, 1080), 16.0 / 9.0, 16);
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].x, 16);
+        assert_eq!(cells[0].y, 16);
+        assert_eq!(cells[0].width, 1920 - 32);
+        assert_eq!(cells[0].height, 1080 - 32);
+    }
+
+    #[test]
+    fn test_grid_cells_four_widescreen_is_two_by_two() {
+        let cells = grid_cells(4, Dimensions::new(1920, 1080), 16.0 / 9.0, 0);
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[1].x, 960);
+        assert_eq!(cells[1].y, 0);
+        assert_eq!(cells[2].x, 0);
+        assert_eq!(cells[2].y, 540);
+    }
+
+    #[test]
+    fn test_grid_cells_do_not_overlap() {
+        let cells = grid_cells(7, Dimensions::new(2560, 1440), 16.0 / 9.0, 24);
+        for (i, a) in cells.iter().enumerate() {
+            for b in cells.iter().skip(i + 1) {
+                let overlap_x = a.x < b.x + b.width as i16 && b.x < a.x + a.width as i16;
+                let overlap_y = a.y < b.y + b.height as i16 && b.y < a.y + a.height as i16;
+                assert!(!(overlap_x && overlap_y), "{:?} overlaps {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fit_in_cell_keeps_aspect_and_centers() {
+        let cell = Rect {
+            x: 100,
+            y: 100,
+            width: 800,
+            height: 800,
+        };
+        let tile = fit_in_cell(cell, Dimensions::new(160, 90));
+        assert_eq!(tile.width, 800);
+        assert_eq!(tile.height, 450);
+        assert_eq!(tile.x, 100);
+        assert_eq!(tile.y, 100 + 175);
+    }
+}
@@ -0,0 +1,160 @@
+//! Panic hotkey: minimizes every EVE client and focuses an optional decoy window, remembering
+//! enough to put everything back on the second press
+
+use anyhow::Result;
+use tracing::{debug, info, warn};
+use x11rb::CURRENT_TIME;
+use x11rb::protocol::xproto::Window;
+
+use crate::common::types::ClientDetectionRule;
+use crate::x11::{
+    AppContext, activate_window, get_active_window, get_client_list, get_window_class,
+    is_window_eve, is_window_minimized, minimize_window, unminimize_window,
+};
+
+/// State captured when the panic hotkey fired
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicSnapshot {
+    /// Window that had focus before the panic
+    pub focused: Option<Window>,
+    /// Clients minimized by the panic (ones already minimized are left alone on restore)
+    pub minimized: Vec<Window>,
+    /// Whether thumbnails were already hidden by the toggle hotkey
+    pub runtime_hidden: bool,
+}
+
+impl PanicSnapshot {
+    /// Drops a destroyed window so restore does not act on a dead (or reused) XID
+    pub fn forget_window(&mut self, window: Window) {
+        self.minimized.retain(|&w| w != window);
+        if self.focused == Some(window) {
+            self.focused = None;
+        }
+    }
+}
+
+/// EVE clients among `windows` the panic should minimize; clients the user had already
+/// minimized are skipped so restore leaves them minimized
+fn windows_to_minimize(
+    windows: &[Window],
+    is_eve: impl Fn(Window) -> bool,
+    is_minimized: impl Fn(Window) -> bool,
+) -> Vec<Window> {
+    windows
+        .iter()
+        .copied()
+        .filter(|&w| is_eve(w) && !is_minimized(w))
+        .collect()
+}
+
+/// Minimizes the EVE clients among `windows` and focuses the decoy (if configured)
+pub fn engage(
+    ctx: &AppContext,
+    windows: &[Window],
+    rules: &[ClientDetectionRule],
+    decoy_class: &str,
+    runtime_hidden: bool,
+) -> PanicSnapshot {
+    let focused = get_active_window(ctx.conn, ctx.screen, ctx.atoms)
+        .ok()
+        .flatten();
+
+    let targets = windows_to_minimize(
+        windows,
+        |w| matches!(is_window_eve(ctx.conn, w, ctx.atoms, rules), Ok(Some(_))),
+        |w| is_window_minimized(ctx.conn, w, ctx.atoms).unwrap_or(false),
+    );
+    let mut minimized = Vec::new();
+    for window in targets {
+        match minimize_window(ctx.conn, ctx.screen, ctx.atoms, window) {
+            Ok(()) => minimized.push(window),
+            Err(e) => warn!(window = window, error = %e, "Failed to minimize client for panic"),
+        }
+    }
+
+    match find_decoy(ctx, decoy_class) {
+        Ok(Some(decoy)) => {
+            if let Err(e) = activate_window(ctx.conn, ctx.screen, ctx.atoms, decoy, CURRENT_TIME) {
+                warn!(window = decoy, error = %e, "Failed to focus panic decoy window");
+            }
+        }
+        Ok(None) => {
+            if !decoy_class.trim().is_empty() {
+                debug!(class = %decoy_class, "No panic decoy window found");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to look up panic decoy window"),
+    }
+
+    info!(minimized = minimized.len(), "Panic engaged");
+    PanicSnapshot {
+        focused,
+        minimized,
+        runtime_hidden,
+    }
+}
+
+/// Restores the clients minimized by `engage` and re-focuses the previously focused window
+pub fn restore(ctx: &AppContext, snapshot: &PanicSnapshot) {
+    for &window in &snapshot.minimized {
+        if let Err(e) = unminimize_window(ctx.conn, ctx.screen, ctx.atoms, window) {
+            warn!(window = window, error = %e, "Failed to restore client after panic");
+        }
+    }
+    if let Some(window) = snapshot.focused
+        && let Err(e) = activate_window(ctx.conn, ctx.screen, ctx.atoms, window, CURRENT_TIME)
+    {
+        warn!(window = window, error = %e, "Failed to re-focus window after panic");
+    }
+    info!(restored = snapshot.minimized.len(), "Panic restored");
+}
+
+/// First managed window whose WM_CLASS matches `class` (case-insensitive)
+fn find_decoy(ctx: &AppContext, class: &str) -> Result<Option<Window>> {
+    let class = class.trim();
+    if class.is_empty() {
+        return Ok(None);
+    }
+    for window in get_client_list(ctx.conn, ctx.atoms)? {
+        if get_window_class(ctx.conn, window, ctx.atoms)
+            .ok()
+            .flatten()
+            .is_some_and(|c| c.eq_ignore_ascii_case(class))
+        {
+            return Ok(Some(window));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_to_minimize_skips_non_eve_and_minimized() {
+        let eve = [1, 2, 3];
+        let already_minimized = [2];
+        let targets = windows_to_minimize(
+            &[1, 2, 3, 4],
+            |w| eve.contains(&w),
+            |w| already_minimized.contains(&w),
+        );
+        assert_eq!(targets, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_forget_window_drops_destroyed_clients() {
+        let mut snapshot = PanicSnapshot {
+            focused: Some(3),
+            minimized: vec![1, 3],
+            runtime_hidden: false,
+        };
+        snapshot.forget_window(3);
+        assert_eq!(snapshot.minimized, vec![1]);
+        assert_eq!(snapshot.focused, None);
+
+        snapshot.forget_window(9);
+        assert_eq!(snapshot.minimized, vec![1]);
+    }
+}
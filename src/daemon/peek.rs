@@ -0,0 +1,164 @@
+//! Peek mode
+//!
+//! While the peek hotkey is held, the thumbnail of the next character in the cycle is enlarged
+//! in place so it can be inspected without switching clients. Releasing the key restores it.
+
+use anyhow::{Context, Result};
+use x11rb::protocol::xproto::Screen;
+
+use super::font::FontRenderer;
+use super::overview::{SavedGeometry, apply_geometry};
+use super::thumbnail::Thumbnail;
+use crate::common::constants::defaults::thumbnail;
+use crate::common::types::{Dimensions, Position};
+use crate::config::DisplayConfig;
+
+/// Computes the enlarged geometry for a peeked thumbnail.
+///
+/// The thumbnail grows around its center and is then pushed back inside the screen.
+pub fn peek_geometry(
+    position: Position,
+    dimensions: Dimensions,
+    scale: f32,
+    screen: Dimensions,
+) -> (Position, Dimensions) {
+    let scale = scale.max(1.0);
+    let width = ((dimensions.width as f32 * scale) as u16)
+        .min(thumbnail::MAX_WIDTH)
+        .min(screen.width)
+        .max(dimensions.width.min(screen.width));
+    let height = ((dimensions.height as f32 * scale) as u16)
+        .min(thumbnail::MAX_HEIGHT)
+        .min(screen.height)
+        .max(dimensions.height.min(screen.height));
+
+    let grow_x = (width as i32 - dimensions.width as i32) / 2;
+    let grow_y = (height as i32 - dimensions.height as i32) / 2;
+    let x = (position.x as i32 - grow_x).clamp(0, (screen.width - width) as i32);
+    let y = (position.y as i32 - grow_y).clamp(0, (screen.height - height) as i32);
+
+    (
+        Position::new(x as i16, y as i16),
+        Dimensions::new(width, height),
+    )
+}
+
+/// Enlarges a thumbnail and raises it above its neighbours.
+///
+/// Returns the geometry to restore once the peek ends.
+pub fn start(
+    thumbnail: &mut Thumbnail,
+    scale: f32,
+    screen: &Screen,
+    display_config: &DisplayConfig,
+    skipped: bool,
+    font_renderer: &FontRenderer,
+) -> Result<SavedGeometry> {
+    let saved = SavedGeometry {
+        position: thumbnail.current_position,
+        dimensions: thumbnail.dimensions,
+        visible: thumbnail.is_visible(),
+    };
+
+    let (position, dimensions) = peek_geometry(
+        saved.position,
+        saved.dimensions,
+        scale,
+        Dimensions::new(screen.width_in_pixels, screen.height_in_pixels),
+    );
+
+    apply_geometry(
+        thumbnail,
+        position,
+        dimensions,
+        true,
+        display_config,
+        skipped,
+        font_renderer,
+    )?;
+    thumbnail.raise().context(format!(
+        "Failed to raise peeked thumbnail for '{}'",
+        thumbnail.character_name
+    ))?;
+    Ok(saved)
+}
+
+/// Restores a peeked thumbnail to its previous geometry and visibility
+pub fn end(
+    thumbnail: &mut Thumbnail,
+    saved: SavedGeometry,
+    display_config: &DisplayConfig,
+    skipped: bool,
+    font_renderer: &FontRenderer,
+) -> Result<()> {
+    apply_geometry(
+        thumbnail,
+        saved.position,
+        saved.dimensions,
+        saved.visible,
+        display_config,
+        skipped,
+        font_renderer,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Dimensions = Dimensions {
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn test_peek_geometry_grows_around_center() {
+        let (pos, dims) = peek_geometry(
+            Position::new(500, 400),
+            Dimensions::new(250, 140),
+            2.0,
+            SCREEN,
+        );
+        assert_eq!(dims, Dimensions::new(500, 280));
+        assert_eq!(pos, Position::new(375, 330));
+    }
+
+    #[test]
+    fn test_peek_geometry_stays_on_screen() {
+        let (pos, dims) =
+            peek_geometry(Position::new(0, 0), Dimensions::new(250, 140), 2.0, SCREEN);
+        assert_eq!(dims, Dimensions::new(500, 280));
+        assert_eq!(pos, Position::new(0, 0));
+
+        let (pos, _) = peek_geometry(
+            Position::new(1800, 1000),
+            Dimensions::new(120, 80),
+            3.0,
+            SCREEN,
+        );
+        assert_eq!(pos, Position::new(1920 - 360, 1080 - 240));
+    }
+
+    #[test]
+    fn test_peek_geometry_clamps_to_screen_size() {
+        let (pos, dims) = peek_geometry(
+            Position::new(100, 100),
+            Dimensions::new(1200, 700),
+            4.0,
+            SCREEN,
+        );
+        assert_eq!(dims, Dimensions::new(1920, 1080));
+        assert_eq!(pos, Position::new(0, 0));
+    }
+
+    #[test]
+    fn test_peek_geometry_never_shrinks() {
+        let (_, dims) = peek_geometry(
+            Position::new(100, 100),
+            Dimensions::new(250, 140),
+            0.5,
+            SCREEN,
+        );
+        assert_eq!(dims, Dimensions::new(250, 140));
+    }
+}
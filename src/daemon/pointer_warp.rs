@@ -0,0 +1,118 @@
+//! Pointer warp on hotkey activation
+//!
+//! Parts of EVE's UI react to where the mouse is, so switching clients by hotkey can leave the
+//! pointer over the wrong spot (or another monitor). When enabled, the pointer is moved into
+//! the activated client: to its center, or to where it last was in that character's client.
+//! Positions are kept per client window in session state, recorded whenever focus leaves a
+//! client (hotkey, alt-tab or the window manager) while the pointer is still inside it.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{ConnectionExt, Window};
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::types::{Dimensions, Position};
+use crate::config::PointerWarpMode;
+
+/// Records where the pointer is in `window`, if it is inside it
+pub fn remember(
+    conn: &RustConnection,
+    window: Window,
+    positions: &mut HashMap<Window, Position>,
+) -> Result<()> {
+    let pointer = conn.query_pointer(window)?.reply()?;
+    let geometry = conn.get_geometry(window)?.reply()?;
+    let position = Position::new(pointer.win_x, pointer.win_y);
+    if pointer.same_screen && contains(Dimensions::new(geometry.width, geometry.height), position) {
+        positions.insert(window, position);
+    }
+    Ok(())
+}
+
+/// Where the pointer goes in a client of `size` whose last position was `remembered`, or None
+/// to leave it
+pub fn target(
+    mode: PointerWarpMode,
+    remembered: Option<Position>,
+    size: Dimensions,
+) -> Option<Position> {
+    let center = Position::new((size.width / 2) as i16, (size.height / 2) as i16);
+    match mode {
+        PointerWarpMode::Off => None,
+        PointerWarpMode::Center => Some(center),
+        PointerWarpMode::Remembered => Some(
+            remembered
+                // The client may have shrunk since
+                .filter(|&p| contains(size, p))
+                .unwrap_or(center),
+        ),
+    }
+}
+
+/// Moves the pointer into `window` as `mode` asks
+pub fn warp(
+    conn: &RustConnection,
+    mode: PointerWarpMode,
+    window: Window,
+    remembered: Option<Position>,
+) -> Result<()> {
+    if mode == PointerWarpMode::Off {
+        return Ok(());
+    }
+    let geometry = conn.get_geometry(window)?.reply()?;
+    let Some(target) = target(
+        mode,
+        remembered,
+        Dimensions::new(geometry.width, geometry.height),
+    ) else {
+        return Ok(());
+    };
+    conn.warp_pointer(x11rb::NONE, window, 0, 0, 0, 0, target.x, target.y)
+        .context(format!("Failed to warp pointer into window {}", window))?;
+    conn.flush()?;
+    Ok(())
+}
+
+fn contains(size: Dimensions, position: Position) -> bool {
+    position.x >= 0
+        && position.y >= 0
+        && (position.x as u16) < size.width
+        && (position.y as u16) < size.height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        let size = Dimensions::new(1920, 1080);
+        assert_eq!(target(PointerWarpMode::Off, None, size), None);
+        assert_eq!(
+            target(PointerWarpMode::Center, Some(Position::new(10, 10)), size),
+            Some(Position::new(960, 540))
+        );
+        // Nothing remembered yet: center
+        assert_eq!(
+            target(PointerWarpMode::Remembered, None, size),
+            Some(Position::new(960, 540))
+        );
+
+        let remembered = Some(Position::new(1500, 900));
+        assert_eq!(
+            target(PointerWarpMode::Remembered, remembered, size),
+            Some(Position::new(1500, 900))
+        );
+        // Remembered spot outside a smaller client
+        assert_eq!(
+            target(
+                PointerWarpMode::Remembered,
+                remembered,
+                Dimensions::new(1280, 720)
+            ),
+            Some(Position::new(640, 360))
+        );
+    }
+}
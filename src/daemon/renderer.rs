@@ -1,27 +1,36 @@
 //! Daemon Thumbnail Renderer
 //!
 //! Handles low-level X11 window creation, rendering, and resource management.
+//!
+//! Every frame (content, recoloring, border, label, badges) is drawn into an off-screen back
+//! buffer and reaches the window in a single copy, so focus churn or a minimized redraw never
+//! shows a half-drawn thumbnail.
+
+use std::cell::{Cell, RefCell};
 
 use anyhow::{Context, Result};
-use tracing::{debug, error, info};
-use x11rb::connection::Connection;
+use tracing::{debug, error, info, warn};
+use x11rb::connection::{Connection, RequestConnection};
 use x11rb::protocol::damage::{
     ConnectionExt as DamageExt, Damage, ReportLevel as DamageReportLevel,
 };
+use x11rb::protocol::present::{self, ConnectionExt as PresentExt};
 use x11rb::protocol::render::{
-    ConnectionExt as RenderExt, CreatePictureAux, PictOp, Picture, Transform,
+    ConnectionExt as RenderExt, CreatePictureAux, PictOp, Pictformat, Picture,
 };
+use x11rb::protocol::shape::{self, ConnectionExt as ShapeExt, SK, SO};
 use x11rb::protocol::xproto::*;
 use x11rb::rust_connection::RustConnection;
 use x11rb::wrapper::ConnectionExt as WrapperExt;
 
 use crate::common::constants::x11;
-use crate::common::types::Dimensions;
-use crate::x11::{AppContext, to_fixed};
+use crate::common::types::{CropRegion, Dimensions, EveServer, ThumbnailShape};
+use crate::x11::AppContext;
 
+use super::capture::{self, CaptureBackend, CaptureTarget};
 use super::font::FontRenderer;
-use super::overlay::OverlayRenderer;
-use crate::config::DisplayConfig;
+use super::overlay::{self, OverlayBackend, OverlayRenderer};
+use crate::config::{DisplayConfig, UnfocusedEffect};
 
 #[derive(Debug)]
 /// Handles low-level X11 window creation, rendering, and resource management.
@@ -42,13 +51,27 @@ pub struct ThumbnailRenderer<'a> {
     /// The DAMAGE extension handle used to track updates to the source window.
     pub damage: Damage,
     root: Window,
+    /// Depth and RENDER format of the thumbnail window, for recreating the back buffer
+    depth: u8,
+    format: Pictformat,
 
     // === X11 Render Resources (private, owned resources) ===
-    src_picture: Picture,
+    /// Picture everything is drawn into, backed by `back_buffer`
     dst_picture: Picture,
-
-    // === Overlay Renderer (handles text, border, pixmap) ===
-    overlay: OverlayRenderer<'a>,
+    /// Off-screen copy of the thumbnail, presented to the window once a frame is finished
+    back_buffer: BackBuffer,
+    /// Presents finished frames at vblank (Present extension) instead of copying them at once
+    presenter: Option<PresentChain>,
+    /// Copy of the last captured content (before recoloring and overlay), shown dimmed while
+    /// the client is minimized. Only kept while the profile freezes minimized frames.
+    frozen_frame: Cell<Option<Pixmap>>,
+    /// Copies the source window into `dst_picture`
+    capture: Box<dyn CaptureBackend + 'a>,
+    /// Settings `capture` and `presenter` were created with
+    backend_settings: BackendSettings,
+
+    // === Overlay Backend (handles text, border, indicators) ===
+    overlay: Box<dyn OverlayBackend<Target = Picture> + 'a>,
 
     // === Borrowed Dependencies (private, references to app context) ===
     pub conn: &'a RustConnection,
@@ -90,7 +113,10 @@ impl<'a> ThumbnailRenderer<'a> {
                         EventMask::SUBSTRUCTURE_NOTIFY
                             | EventMask::BUTTON_PRESS
                             | EventMask::BUTTON_RELEASE
-                            | EventMask::POINTER_MOTION,
+                            | EventMask::POINTER_MOTION
+                            | EventMask::ENTER_WINDOW
+                            | EventMask::LEAVE_WINDOW
+                            | EventMask::VISIBILITY_CHANGE,
                     ),
             )
             .context(format!(
@@ -185,46 +211,12 @@ impl<'a> ThumbnailRenderer<'a> {
         Ok(())
     }
 
-    /// Create render pictures and resources
+    /// Create the destination picture for the thumbnail's back buffer
     fn create_render_resources(
         ctx: &AppContext,
-        window: Window,
-        src: Window,
-        src_depth: u8,
+        drawable: Drawable,
         character_name: &str,
-    ) -> Result<(Picture, Picture)> {
-        // Determine source format based on window depth
-        let src_format = if src_depth == 32 {
-            info!(character = %character_name, depth = src_depth, format = "ARGB32", "Using ARGB format for source window");
-            ctx.formats.argb
-        } else {
-            // Default to RGB (usually 24-bit)
-            // If it's not 32 or root depth, this might still be wrong, but it covers standard cases.
-            debug!(character = %character_name, depth = src_depth, format = "RGB24", "Using RGB format for source window");
-            ctx.formats.rgb
-        };
-
-        // Source picture
-        let src_picture = ctx
-            .conn
-            .generate_id()
-            .context("Failed to generate ID for source picture")?;
-
-        ctx.conn
-            .render_create_picture(src_picture, src, src_format, &CreatePictureAux::new())
-            .context(format!(
-                "Failed to create source picture for '{}'",
-                character_name
-            ))?;
-
-        // Apply bilinear filter for smoother downscaling (better text readability)
-        ctx.conn
-            .render_set_picture_filter(src_picture, "bilinear".as_bytes(), &[])
-            .context(format!(
-                "Failed to set bilinear filter for '{}'",
-                character_name
-            ))?;
-
+    ) -> Result<Picture> {
         // Destination picture
         let dst_picture = ctx
             .conn
@@ -233,7 +225,7 @@ impl<'a> ThumbnailRenderer<'a> {
         ctx.conn
             .render_create_picture(
                 dst_picture,
-                window,
+                drawable,
                 ctx.formats.rgb,
                 &CreatePictureAux::new(),
             )
@@ -242,7 +234,7 @@ impl<'a> ThumbnailRenderer<'a> {
                 character_name
             ))?;
 
-        Ok((src_picture, dst_picture))
+        Ok(dst_picture)
     }
 
     /// Create damage tracking for source window
@@ -328,20 +320,46 @@ impl<'a> ThumbnailRenderer<'a> {
         Self::setup_window_properties(ctx, window, display_config.opacity, character_name)?;
 
         // Create rendering resources
-        let (src_picture, dst_picture) =
-            Self::create_render_resources(ctx, window, src, src_depth, character_name)?;
-
-        // Create overlay renderer
-        let overlay = OverlayRenderer::new(
+        let backend_settings = BackendSettings::from_config(display_config);
+        let presenter = create_presenter(
             ctx.conn,
-            display_config,
-            ctx.formats,
-            font_renderer,
-            ctx.screen.root,
+            window,
             dimensions,
+            display_config.vsync,
+            character_name,
+        )?;
+        let back_buffer =
+            BackBuffer::new(ctx.conn, ctx.screen.root, ctx.screen.root_depth, dimensions)?;
+        let dst_picture = Self::create_render_resources(ctx, back_buffer.pixmap, character_name)
+            .inspect_err(|_| back_buffer.free(ctx.conn))?;
+        let capture = capture::create(
+            ctx,
+            backend_settings.capture,
+            backend_settings.filter,
+            src,
+            src_depth,
             character_name,
         )?;
 
+        // Create overlay renderer
+        let overlay: Box<dyn OverlayBackend<Target = Picture> + 'a> =
+            Box::new(OverlayRenderer::new(
+                ctx.conn,
+                display_config,
+                ctx.formats,
+                font_renderer,
+                ctx.screen.root,
+                dimensions,
+                character_name,
+            )?);
+
+        debug!(
+            character = %character_name,
+            capture = capture.name(),
+            overlay = overlay.name(),
+            "Created thumbnail renderer backends"
+        );
+
         // Setup damage tracking
         let damage = Self::create_damage_tracking(ctx, src, character_name)?;
 
@@ -378,10 +396,16 @@ impl<'a> ThumbnailRenderer<'a> {
             },
             damage,
             root: ctx.screen.root,
+            depth: ctx.screen.root_depth,
+            format: ctx.formats.rgb,
 
             // X11 Render Resources
-            src_picture,
             dst_picture,
+            back_buffer,
+            presenter,
+            frozen_frame: Cell::new(None),
+            capture,
+            backend_settings,
 
             // Overlay
             overlay,
@@ -397,6 +421,26 @@ impl<'a> ThumbnailRenderer<'a> {
         Ok(renderer)
     }
 
+    /// Toggles the hover-only notes line. Takes effect on the next overlay redraw.
+    pub fn set_notes_visible(&mut self, visible: bool) {
+        self.overlay.set_notes_visible(visible);
+    }
+
+    /// Sets the server badge/border used on the next overlay redraw.
+    pub fn set_server(&mut self, server: EveServer) {
+        self.overlay.set_server(server);
+    }
+
+    /// Marks the thumbnail as previewing a custom source. Takes effect on the next overlay redraw.
+    pub fn set_custom_source(&mut self, custom: bool) {
+        self.overlay.set_custom_source(custom);
+    }
+
+    /// Tells the capture backend the source window was mapped, unmapped or reconfigured.
+    pub fn source_changed(&self) {
+        self.capture.source_changed();
+    }
+
     /// Maps the thumbnail window, making it visible on screen.
     pub fn map(&self) -> Result<()> {
         self.conn.map_window(self.window)?;
@@ -409,13 +453,32 @@ impl<'a> ThumbnailRenderer<'a> {
         Ok(())
     }
 
-    /// Captures the current content of the source window and composites it into the thumbnail.
+    /// Updates the `_NET_WM_WINDOW_OPACITY` hint (applied by the compositor).
+    pub fn set_opacity(&self, opacity: u32) -> Result<()> {
+        self.conn.change_property32(
+            PropMode::REPLACE,
+            self.window,
+            self.atoms.net_wm_window_opacity,
+            AtomEnum::CARDINAL,
+            &[opacity],
+        )?;
+        Ok(())
+    }
+
+    /// Captures the current content of the source window (or just its `crop` region) into the
+    /// thumbnail. Returns false when the source could not be captured safely and the previous
+    /// content was left in place.
     ///
-    /// This applies the necessary scaling transform to fit the source content into the thumbnail dimensions.
+    /// Shared safety checks live here; the scaling and copy are done by the capture backend.
     ///
     /// # Errors
-    /// Returns an error if X11 composite operations fail.
-    pub fn capture(&self, character_name: &str, dimensions: Dimensions) -> Result<()> {
+    /// Returns an error if the backend's X11 operations fail.
+    pub fn capture(
+        &self,
+        character_name: &str,
+        dimensions: Dimensions,
+        crop: Option<CropRegion>,
+    ) -> Result<bool> {
         // Query attributes to check map state
         let attr_cookie = self.conn.get_window_attributes(self.src)?;
         let attrs = attr_cookie.reply()?;
@@ -430,7 +493,7 @@ impl<'a> ThumbnailRenderer<'a> {
                 src_window = self.src,
                 "Skipping capture of unmapped window"
             );
-            return Ok(()); // Skip capture to prevent crash
+            return Ok(false); // Skip capture to prevent crash
         }
 
         // NOTE: Query geometry fresh every frame.
@@ -461,38 +524,67 @@ impl<'a> ThumbnailRenderer<'a> {
                 height = src_height,
                 "Skipping capture of 1x1/empty window (likely not mapped yet)"
             );
-            return Ok(());
+            return Ok(false);
         }
 
-        let transform = Transform {
-            matrix11: to_fixed(src_width as f32 / dimensions.width as f32),
-            matrix22: to_fixed(src_height as f32 / dimensions.height as f32),
-            matrix33: to_fixed(1.0),
-            ..Default::default()
-        };
-        self.conn
-            .render_set_picture_transform(self.src_picture, transform)
-            .context(format!("Failed to set transform for '{}'", character_name))?;
-        self.conn
-            .render_composite(
-                PictOp::SRC,
-                self.src_picture,
-                0u32,
-                self.dst_picture,
-                0,
-                0,
-                0,
-                0,
-                0,
-                0,
-                dimensions.width,
-                dimensions.height,
+        self.capture
+            .capture(
+                CaptureTarget {
+                    drawable: self.drawable(),
+                    picture: self.dst_picture,
+                },
+                Dimensions::new(src_width, src_height),
+                CropRegion::resolve(crop, Dimensions::new(src_width, src_height)),
+                dimensions,
             )
             .context(format!(
-                "Failed to composite source window for '{}'",
-                character_name
+                "Failed to capture source window for '{}' ({} backend)",
+                character_name,
+                self.capture.name()
             ))?;
-        Ok(())
+        Ok(true)
+    }
+
+    /// Copies the freshly captured content aside so it can be shown while minimized.
+    fn keep_frame(&self, character_name: &str, dimensions: Dimensions) -> Result<()> {
+        let pixmap = match self.frozen_frame.get() {
+            Some(pixmap) => pixmap,
+            None => {
+                let pixmap = self
+                    .conn
+                    .generate_id()
+                    .context("Failed to generate ID for frozen frame")?;
+                self.conn
+                    .create_pixmap(
+                        self.depth,
+                        pixmap,
+                        self.root,
+                        dimensions.width.max(1),
+                        dimensions.height.max(1),
+                    )
+                    .context(format!(
+                        "Failed to create frozen frame for '{}'",
+                        character_name
+                    ))?;
+                self.frozen_frame.set(Some(pixmap));
+                pixmap
+            }
+        };
+        self.back_buffer
+            .copy_to(self.conn, pixmap, dimensions)
+            .context(format!(
+                "Failed to keep the last frame for '{}'",
+                character_name
+            ))
+    }
+
+    /// Frees the frozen frame, if any
+    fn release_frame(&self) {
+        if let Some(pixmap) = self.frozen_frame.take()
+            && let Err(e) = self.conn.free_pixmap(pixmap)
+        {
+            error!(pixmap = pixmap, error = %e, "Failed to free frozen frame");
+        }
     }
 
     /// Fills the thumbnail with a static solid color.
@@ -547,7 +639,9 @@ impl<'a> ThumbnailRenderer<'a> {
 
     /// Renders the "MINIMIZED" state overlay.
     ///
-    /// Clears any existing border and draws the localized logic for minimized windows.
+    /// Clears any existing border and draws the localized logic for minimized windows. The
+    /// content is the last captured frame, dimmed, when the profile freezes minimized frames and
+    /// one was kept; otherwise it is filled with the profile's minimized background.
     pub fn minimized(
         &self,
         display_config: &DisplayConfig,
@@ -558,17 +652,50 @@ impl<'a> ThumbnailRenderer<'a> {
         self.overlay
             .draw_minimized(display_config, character_name, dimensions, font_renderer)?;
 
-        // Explicitly clear background to black using fill_static.
         // We cannot use self.update() here because it calls capture(), which correctly skips
-        // unmapped windows to prevent KWin crashes. However, for the minimized state,
-        // we WANT to clear the old content (frozen image).
-        let black = x11rb::protocol::render::Color {
-            red: 0,
-            green: 0,
-            blue: 0,
-            alpha: 0xffff,
-        };
-        self.fill_static(character_name, dimensions, black)?;
+        // unmapped windows to prevent KWin crashes. The old content in the back buffer has the
+        // previous overlay baked in, so it is replaced by the kept frame or cleared to black.
+        match self.frozen_frame.get() {
+            Some(frame) if display_config.minimized_freeze_frame => {
+                self.conn
+                    .copy_area(
+                        frame,
+                        self.drawable(),
+                        self.back_buffer.gc,
+                        0,
+                        0,
+                        0,
+                        0,
+                        dimensions.width,
+                        dimensions.height,
+                    )
+                    .context(format!(
+                        "Failed to restore frozen frame for '{}'",
+                        character_name
+                    ))?;
+                self.conn
+                    .render_fill_rectangles(
+                        PictOp::OVER,
+                        self.dst_picture,
+                        crate::common::constants::defaults::thumbnail::FROZEN_FRAME_DIM,
+                        &[Rectangle {
+                            x: 0,
+                            y: 0,
+                            width: dimensions.width,
+                            height: dimensions.height,
+                        }],
+                    )
+                    .context(format!(
+                        "Failed to dim frozen frame for '{}'",
+                        character_name
+                    ))?;
+            }
+            _ => self.fill_static(
+                character_name,
+                dimensions,
+                display_config.minimized_background,
+            )?,
+        }
 
         self.overlay(character_name, dimensions).context(format!(
             "Failed to update minimized display for '{}'",
@@ -588,9 +715,7 @@ impl<'a> ThumbnailRenderer<'a> {
         // Calculate appropriate border size to preserve the hole
         // We default to focused=false since this is usually called during initialization or generic updates
         // However, if we are focused, the next border() call will correct it.
-        let border_size = self
-            .overlay
-            .calculate_border_size(display_config, character_name, false);
+        let border_size = overlay::border_size(display_config, character_name, false);
 
         // Must clear content area explicitly now
         self.overlay
@@ -609,36 +734,168 @@ impl<'a> ThumbnailRenderer<'a> {
         )
     }
 
-    /// Composites the text/border overlay on top of the thumbnail content.
+    /// Composites the text/border overlay on top of the thumbnail content. Every draw ends
+    /// here, so this is also where the finished frame is presented.
     pub fn overlay(&self, character_name: &str, dimensions: Dimensions) -> Result<()> {
-        self.conn
-            .render_composite(
-                PictOp::OVER,
-                self.overlay.overlay_picture,
-                0u32,
-                self.dst_picture,
-                0,
-                0,
-                0,
-                0,
-                0,
-                0,
-                dimensions.width,
-                dimensions.height,
-            )
+        let mut target = self.dst_picture;
+        self.overlay
+            .composite(&mut target, dimensions)
             .context(format!(
                 "Failed to composite overlay onto destination for '{}'",
                 character_name
             ))?;
+        let presented = match &self.presenter {
+            Some(presenter) => {
+                presenter.present(self.conn, &self.back_buffer, self.window, self.depth)
+            }
+            None => self.back_buffer.copy_to(self.conn, self.window, dimensions),
+        };
+        presented.context(format!(
+            "Failed to present thumbnail for '{}'",
+            character_name
+        ))
+    }
+
+    /// The server is done with a presented pixmap (PresentIdleNotify); presents the frame
+    /// that finished while every present buffer was still in use, if any.
+    pub fn present_idle(&self, pixmap: Pixmap) -> Result<()> {
+        let Some(presenter) = &self.presenter else {
+            return Ok(());
+        };
+        if presenter.idle(pixmap) {
+            presenter
+                .present(self.conn, &self.back_buffer, self.window, self.depth)
+                .context("Failed to present a held back thumbnail frame")?;
+        }
         Ok(())
     }
 
-    /// Logic for full update cycle: capture source -> apply overlay.
-    pub fn update(&self, character_name: &str, dimensions: Dimensions) -> Result<()> {
-        self.capture(character_name, dimensions).context(format!(
-            "Failed to capture source window for '{}'",
-            character_name
-        ))?;
+    /// Recreates the capture backend and presenter when the capture backend, scale filter or
+    /// vsync setting changed since they were created
+    pub fn apply_backend_settings(
+        &mut self,
+        ctx: &AppContext<'a>,
+        display_config: &DisplayConfig,
+        character_name: &str,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let wanted = BackendSettings::from_config(display_config);
+        let current = self.backend_settings;
+        if wanted == current {
+            return Ok(());
+        }
+
+        if (wanted.capture, wanted.filter) != (current.capture, current.filter) {
+            let src_depth = self
+                .conn
+                .get_geometry(self.src)?
+                .reply()
+                .context(format!(
+                    "Failed to query source depth for '{}'",
+                    character_name
+                ))?
+                .depth;
+            self.capture = capture::create(
+                ctx,
+                wanted.capture,
+                wanted.filter,
+                self.src,
+                src_depth,
+                character_name,
+            )?;
+        }
+        if wanted.vsync != current.vsync {
+            let presenter = create_presenter(
+                self.conn,
+                self.window,
+                dimensions,
+                wanted.vsync,
+                character_name,
+            )?;
+            if let Some(old) = std::mem::replace(&mut self.presenter, presenter) {
+                old.free(self.conn);
+            }
+        }
+        self.backend_settings = wanted;
+        info!(
+            character = %character_name,
+            capture = self.capture.name(),
+            vsync = self.presenter.is_some(),
+            "Recreated thumbnail backends for new settings"
+        );
+        Ok(())
+    }
+
+    /// Drawable behind `dst_picture`
+    fn drawable(&self) -> Drawable {
+        self.back_buffer.pixmap
+    }
+
+    /// Recolors the captured content of an unfocused thumbnail
+    fn recolor(
+        &self,
+        character_name: &str,
+        dimensions: Dimensions,
+        effect: UnfocusedEffect,
+        tint: x11rb::protocol::render::Color,
+    ) -> Result<()> {
+        let (op, color) = match effect {
+            UnfocusedEffect::None => return Ok(()),
+            // Blending a gray with HSL_SATURATION takes its saturation (none) and keeps each
+            // pixel's luminosity
+            UnfocusedEffect::Grayscale => (
+                PictOp::HSL_SATURATION,
+                x11rb::protocol::render::Color {
+                    red: 0x8000,
+                    green: 0x8000,
+                    blue: 0x8000,
+                    alpha: 0xffff,
+                },
+            ),
+            UnfocusedEffect::Tint => (PictOp::OVER, tint),
+        };
+        let rect = x11rb::protocol::xproto::Rectangle {
+            x: 0,
+            y: 0,
+            width: dimensions.width,
+            height: dimensions.height,
+        };
+        self.conn
+            .render_fill_rectangles(op, self.dst_picture, color, &[rect])
+            .context(format!(
+                "Failed to recolor unfocused thumbnail for '{}'",
+                character_name
+            ))?;
+        Ok(())
+    }
+
+    /// Logic for full update cycle: capture source -> keep a copy for minimizing (`keep_frame`)
+    /// -> recolor if unfocused and captured -> apply overlay.
+    pub fn update(
+        &self,
+        character_name: &str,
+        dimensions: Dimensions,
+        crop: Option<CropRegion>,
+        effect: UnfocusedEffect,
+        tint: x11rb::protocol::render::Color,
+        keep_frame: bool,
+    ) -> Result<()> {
+        super::metrics::record(super::metrics::Counter::Composite);
+        let captured = self
+            .capture(character_name, dimensions, crop)
+            .context(format!(
+                "Failed to capture source window for '{}'",
+                character_name
+            ))?;
+        if !keep_frame {
+            self.release_frame();
+        } else if captured {
+            self.keep_frame(character_name, dimensions)?;
+        }
+        // Without a fresh capture the buffer still holds the last recolored frame
+        if captured {
+            self.recolor(character_name, dimensions, effect, tint)?;
+        }
         self.overlay(character_name, dimensions)
             .context(format!("Failed to apply overlay for '{}'", character_name))?;
         Ok(())
@@ -708,9 +965,24 @@ impl<'a> ThumbnailRenderer<'a> {
         Ok(())
     }
 
-    /// Moves the thumbnail window to a new position.
-    pub fn reposition(&mut self, character_name: &str, x: i16, y: i16) -> Result<()> {
+    /// Raises the thumbnail window above the other thumbnails.
+    pub fn raise(&self, character_name: &str) -> Result<()> {
         self.conn
+            .configure_window(
+                self.window,
+                &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
+            )
+            .context(format!(
+                "Failed to raise thumbnail for '{}' to top of stack",
+                character_name
+            ))?;
+        Ok(())
+    }
+
+    /// Moves the thumbnail window to a new position. Returns the sequence number of the move.
+    pub fn reposition(&mut self, character_name: &str, x: i16, y: i16) -> Result<u64> {
+        let sequence = self
+            .conn
             .configure_window(
                 self.window,
                 &ConfigureWindowAux::new().x(x as i32).y(y as i32),
@@ -718,12 +990,13 @@ impl<'a> ThumbnailRenderer<'a> {
             .context(format!(
                 "Failed to reposition window for '{}' to ({}, {})",
                 character_name, x, y
-            ))?;
+            ))?
+            .sequence_number();
 
         self.conn
             .flush()
             .context("Failed to flush X11 connection after reposition")?;
-        Ok(())
+        Ok(sequence)
     }
 
     /// Resizes the thumbnail window and recreates necessary render resources.
@@ -737,9 +1010,63 @@ impl<'a> ThumbnailRenderer<'a> {
             )
             .context(format!("Failed to resize window for '{}'", character_name))?;
 
+        // The back buffer has the old size; replace it and its picture. New pixmaps hold garbage,
+        // so it is cleared and the old frame carried over: a border or label redraw before the
+        // next capture must not present garbage.
+        let dimensions = Dimensions::new(width, height);
+        let resized = BackBuffer::new(self.conn, self.root, self.depth, dimensions)?;
+        let picture = self.conn.generate_id()?;
+        self.conn
+            .render_create_picture(
+                picture,
+                resized.pixmap,
+                self.format,
+                &CreatePictureAux::new(),
+            )
+            .context(format!(
+                "Failed to recreate destination picture for '{}'",
+                character_name
+            ))?;
+        let black = x11rb::protocol::render::Color {
+            red: 0,
+            green: 0,
+            blue: 0,
+            alpha: 0xffff,
+        };
+        self.conn
+            .render_fill_rectangles(
+                PictOp::SRC,
+                picture,
+                black,
+                &[Rectangle {
+                    x: 0,
+                    y: 0,
+                    width,
+                    height,
+                }],
+            )
+            .context(format!(
+                "Failed to clear resized back buffer for '{}'",
+                character_name
+            ))?;
+        self.back_buffer
+            .copy_to(self.conn, resized.pixmap, dimensions)
+            .context(format!(
+                "Failed to carry the last frame over for '{}'",
+                character_name
+            ))?;
+        std::mem::replace(&mut self.back_buffer, resized).free(self.conn);
+        if let Some(presenter) = &self.presenter {
+            presenter.resize(self.conn, dimensions);
+        }
+        // The kept frame has the old size; a new one is taken with the next capture
+        self.release_frame();
+        self.conn.render_free_picture(self.dst_picture)?;
+        self.dst_picture = picture;
+
         // Recreate overlay resources via helper
         self.overlay
-            .resize(self.root, width, height)
+            .resize(Dimensions::new(width, height))
             .context(format!(
                 "Failed to resize overlay resources for '{}'",
                 character_name
@@ -750,6 +1077,49 @@ impl<'a> ThumbnailRenderer<'a> {
             .context("Failed to flush X11 connection after resize")?;
         Ok(())
     }
+
+    /// Applies the XShape bounding region for the given shape, or clears it for rectangles.
+    /// Without the SHAPE extension every thumbnail stays a rectangle.
+    pub fn apply_shape(
+        &self,
+        character_name: &str,
+        shape: ThumbnailShape,
+        corner_radius: u16,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        if !has_shape(self.conn) {
+            return Ok(());
+        }
+        match super::shape::shape_region(shape, corner_radius, dimensions) {
+            Some(bands) => {
+                self.conn
+                    .shape_rectangles(
+                        SO::SET,
+                        SK::BOUNDING,
+                        ClipOrdering::UNSORTED,
+                        self.window,
+                        0,
+                        0,
+                        &bands,
+                    )
+                    .context(format!(
+                        "Failed to set window shape for '{}'",
+                        character_name
+                    ))?;
+            }
+            None => {
+                self.conn
+                    .shape_mask(SO::SET, SK::BOUNDING, self.window, 0, 0, x11rb::NONE)
+                    .context(format!(
+                        "Failed to clear window shape for '{}'",
+                        character_name
+                    ))?;
+            }
+        }
+
+        debug!(character = %character_name, shape = ?shape, "Applied thumbnail shape");
+        Ok(())
+    }
 }
 
 impl Drop for ThumbnailRenderer<'_> {
@@ -761,15 +1131,9 @@ impl Drop for ThumbnailRenderer<'_> {
             error!(damage = self.damage, error = %e, "Failed to destroy damage");
         }
 
-        // OverlayRenderer Drop will handle overlay resources
+        // The overlay backend's Drop will handle overlay resources
 
-        if let Err(e) = self.conn.render_free_picture(self.src_picture) {
-            error!(
-                picture = self.src_picture,
-                error = %e,
-                "Failed to free source picture"
-            );
-        }
+        // The capture backend frees its own resources when dropped
 
         if let Err(e) = self.conn.render_free_picture(self.dst_picture) {
             error!(
@@ -779,6 +1143,12 @@ impl Drop for ThumbnailRenderer<'_> {
             );
         }
 
+        self.back_buffer.free(self.conn);
+        if let Some(presenter) = &self.presenter {
+            presenter.free(self.conn);
+        }
+        self.release_frame();
+
         if let Err(e) = self.conn.destroy_window(self.window) {
             error!(
                 window = self.window,
@@ -792,3 +1162,236 @@ impl Drop for ThumbnailRenderer<'_> {
         }
     }
 }
+
+/// Capture and presentation settings a renderer's backends were created with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BackendSettings {
+    capture: crate::config::CaptureBackendType,
+    filter: crate::config::ScaleFilter,
+    vsync: bool,
+}
+
+impl BackendSettings {
+    fn from_config(display_config: &DisplayConfig) -> Self {
+        Self {
+            capture: display_config.capture_backend,
+            filter: display_config.scale_filter,
+            vsync: display_config.vsync,
+        }
+    }
+}
+
+/// Present chain for a thumbnail window when vsync is on and the server supports it
+fn create_presenter(
+    conn: &RustConnection,
+    window: Window,
+    dimensions: Dimensions,
+    vsync: bool,
+    character_name: &str,
+) -> Result<Option<PresentChain>> {
+    if !vsync {
+        return Ok(None);
+    }
+    if !has_present(conn) {
+        warn!(character = %character_name, "X server lacks the Present extension, drawing thumbnails without vsync");
+        return Ok(None);
+    }
+    Ok(Some(PresentChain::new(conn, window, dimensions)?))
+}
+
+/// Whether the X server can shape windows (the reply is cached by the connection)
+pub fn has_shape(conn: &RustConnection) -> bool {
+    conn.extension_information(shape::X11_EXTENSION_NAME)
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Whether the X server can present pixmaps to windows
+fn has_present(conn: &RustConnection) -> bool {
+    conn.extension_information(present::X11_EXTENSION_NAME)
+        .ok()
+        .flatten()
+        .is_some()
+        && conn
+            .present_query_version(1, 0)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some()
+}
+
+/// Pixmap a thumbnail is drawn into. Only finished frames reach the window: `copy_to` copies
+/// one over at once, or `PresentChain` hands a copy to the server for the next vblank.
+#[derive(Debug)]
+struct BackBuffer {
+    pixmap: Pixmap,
+    /// Graphics context for `copy_to`
+    gc: Gcontext,
+}
+
+impl BackBuffer {
+    fn new(conn: &RustConnection, root: Window, depth: u8, dimensions: Dimensions) -> Result<Self> {
+        let pixmap = conn
+            .generate_id()
+            .context("Failed to generate ID for back buffer")?;
+        conn.create_pixmap(
+            depth,
+            pixmap,
+            root,
+            dimensions.width.max(1),
+            dimensions.height.max(1),
+        )
+        .context("Failed to create thumbnail back buffer")?;
+        let gc = conn
+            .generate_id()
+            .context("Failed to generate ID for back buffer graphics context")?;
+        if let Err(e) = conn.create_gc(gc, pixmap, &CreateGCAux::new().graphics_exposures(0)) {
+            let _ = conn.free_pixmap(pixmap);
+            return Err(e).context("Failed to create back buffer graphics context");
+        }
+        Ok(Self { pixmap, gc })
+    }
+
+    /// Copies the top-left `dimensions` of the buffer to `drawable` in one request
+    fn copy_to(
+        &self,
+        conn: &RustConnection,
+        drawable: Drawable,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        conn.copy_area(
+            self.pixmap,
+            drawable,
+            self.gc,
+            0,
+            0,
+            0,
+            0,
+            dimensions.width,
+            dimensions.height,
+        )?;
+        Ok(())
+    }
+
+    fn free(&self, conn: &RustConnection) {
+        if let Err(e) = conn.free_gc(self.gc) {
+            error!(gc = self.gc, error = %e, "Failed to free back buffer graphics context");
+        }
+        if let Err(e) = conn.free_pixmap(self.pixmap) {
+            error!(pixmap = self.pixmap, error = %e, "Failed to free back buffer");
+        }
+    }
+}
+
+/// Most pixmaps a thumbnail has in flight with the Present extension
+const PRESENT_BUFFERS: usize = 2;
+
+/// Pixmaps handed to PresentPixmap. The server may read a presented pixmap until it sends
+/// PresentIdleNotify for it, so the back buffer (which the next frame is drawn into) is never
+/// presented itself: each finished frame is copied into an idle present buffer first.
+#[derive(Debug)]
+struct PresentChain {
+    size: Cell<Dimensions>,
+    /// Present buffers and whether the server still holds each one
+    buffers: RefCell<Vec<(Pixmap, bool)>>,
+    /// A frame finished while every buffer was busy; it is presented on the next idle notify
+    held_back: Cell<bool>,
+    /// Serial of the last PresentPixmap request
+    serial: Cell<u32>,
+}
+
+impl PresentChain {
+    /// Subscribes to the window's idle notifications
+    fn new(conn: &RustConnection, window: Window, size: Dimensions) -> Result<Self> {
+        let eid = conn
+            .generate_id()
+            .context("Failed to generate ID for Present events")?;
+        conn.present_select_input(eid, window, present::EventMask::IDLE_NOTIFY)
+            .context("Failed to select Present idle notifications")?;
+        Ok(Self {
+            size: Cell::new(size),
+            buffers: RefCell::new(Vec::new()),
+            held_back: Cell::new(false),
+            serial: Cell::new(0),
+        })
+    }
+
+    /// Copies the finished frame into an idle buffer and queues it for the window at the next
+    /// vblank. Unsynced presents of the same window replace each other, so frames drawn faster
+    /// than the refresh rate are dropped rather than queued.
+    fn present(
+        &self,
+        conn: &RustConnection,
+        back: &BackBuffer,
+        window: Window,
+        depth: u8,
+    ) -> Result<()> {
+        let size = self.size.get();
+        let mut buffers = self.buffers.borrow_mut();
+        let idx = match buffers.iter().position(|&(_, busy)| !busy) {
+            Some(idx) => idx,
+            None if buffers.len() < PRESENT_BUFFERS => {
+                let pixmap = conn.generate_id()?;
+                conn.create_pixmap(depth, pixmap, window, size.width.max(1), size.height.max(1))
+                    .context("Failed to create present buffer")?;
+                buffers.push((pixmap, false));
+                buffers.len() - 1
+            }
+            None => {
+                self.held_back.set(true);
+                return Ok(());
+            }
+        };
+        let pixmap = buffers[idx].0;
+        back.copy_to(conn, pixmap, size)?;
+        let serial = self.serial.get().wrapping_add(1);
+        self.serial.set(serial);
+        conn.present_pixmap(
+            window,
+            pixmap,
+            serial,
+            x11rb::NONE,
+            x11rb::NONE,
+            0,
+            0,
+            x11rb::NONE,
+            x11rb::NONE,
+            x11rb::NONE,
+            present::Option::NONE.into(),
+            0,
+            0,
+            0,
+            &[],
+        )?;
+        buffers[idx].1 = true;
+        self.held_back.set(false);
+        Ok(())
+    }
+
+    /// Marks `pixmap` reusable; true if a held back frame should be presented now
+    fn idle(&self, pixmap: Pixmap) -> bool {
+        let mut buffers = self.buffers.borrow_mut();
+        let Some(buffer) = buffers.iter_mut().find(|(p, _)| *p == pixmap) else {
+            // Freed by a resize since it was presented
+            return false;
+        };
+        buffer.1 = false;
+        self.held_back.get()
+    }
+
+    /// Frees the buffers (the server keeps busy ones alive until it is done with them); new
+    /// ones are created at `size` as frames are presented.
+    fn resize(&self, conn: &RustConnection, size: Dimensions) {
+        self.size.set(size);
+        self.free(conn);
+    }
+
+    fn free(&self, conn: &RustConnection) {
+        self.held_back.set(false);
+        for (pixmap, _) in self.buffers.borrow_mut().drain(..) {
+            if let Err(e) = conn.free_pixmap(pixmap) {
+                error!(pixmap = pixmap, error = %e, "Failed to free present buffer");
+            }
+        }
+    }
+}
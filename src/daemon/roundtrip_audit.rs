@@ -0,0 +1,134 @@
+//! X round-trip audit (debugging aid)
+//!
+//! Every `.reply()` or `.check()` on an X request stalls the event loop until the server
+//! answers. With `EVE_PREVIEW_ROUNDTRIP_AUDIT=1` in the environment, the daemon counts these
+//! round-trips per X event type being handled (anything else counts as "other") and logs a
+//! summary every second. They are picked up from the spans x11rb opens while it waits, so no
+//! call site has to be instrumented.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use tracing::Subscriber;
+use tracing::span::{Attributes, Id};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::Context;
+
+use crate::common::constants::x11::ROUNDTRIP_AUDIT_ENV;
+
+/// Spans x11rb's `RustConnection` opens while blocked on the server
+const WAIT_SPANS: [&str; 3] = [
+    "wait_for_reply",
+    "wait_for_reply_with_fds_raw",
+    "check_for_raw_error",
+];
+
+/// Round-trips made outside event handling (IPC, hotkeys, timers)
+const OUTSIDE_EVENTS: &str = "other";
+
+static ENABLED: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var(ROUNDTRIP_AUDIT_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+});
+
+/// Round-trips since the last summary, by event type
+static COUNTS: LazyLock<Mutex<HashMap<&'static str, u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    static CURRENT: Cell<&'static str> = const { Cell::new(OUTSIDE_EVENTS) };
+}
+
+/// Whether the audit was asked for in the environment
+pub fn enabled() -> bool {
+    *ENABLED
+}
+
+/// Tracing layer counting x11rb's round-trips; install it only when `enabled()`
+pub fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    Counter.with_filter(filter_fn(|metadata| {
+        metadata.is_span()
+            && metadata.target().starts_with("x11rb")
+            && WAIT_SPANS.contains(&metadata.name())
+    }))
+}
+
+struct Counter;
+
+impl<S: Subscriber> Layer<S> for Counter {
+    fn on_new_span(&self, _attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        let event = CURRENT.get();
+        *COUNTS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(event)
+            .or_default() += 1;
+    }
+}
+
+/// Attributes round-trips to the X event `name` until dropped
+pub struct EventScope {
+    previous: &'static str,
+}
+
+impl Drop for EventScope {
+    fn drop(&mut self) {
+        CURRENT.set(self.previous);
+    }
+}
+
+/// Starts attributing round-trips to the X event `name`
+pub fn handling(name: &'static str) -> EventScope {
+    EventScope {
+        previous: CURRENT.replace(name),
+    }
+}
+
+/// Takes the counts recorded since the last call, most round-trips first
+pub fn drain() -> Vec<(&'static str, u64)> {
+    let mut counts: Vec<_> = COUNTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+}
+
+/// One-line summary of drained counts, e.g. "12 round-trips: ButtonPress 9, other 3"
+pub fn summary(counts: &[(&str, u64)]) -> String {
+    let total: u64 = counts.iter().map(|(_, count)| count).sum();
+    let by_event: Vec<String> = counts
+        .iter()
+        .map(|(event, count)| format!("{event} {count}"))
+        .collect();
+    format!("{total} round-trips: {}", by_event.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_and_summary() {
+        assert_eq!(CURRENT.get(), OUTSIDE_EVENTS);
+        {
+            let _outer = handling("ButtonPress");
+            {
+                let _inner = handling("MotionNotify");
+                assert_eq!(CURRENT.get(), "MotionNotify");
+            }
+            assert_eq!(CURRENT.get(), "ButtonPress");
+        }
+        assert_eq!(CURRENT.get(), OUTSIDE_EVENTS);
+
+        assert_eq!(
+            summary(&[("ButtonPress", 9), ("other", 3)]),
+            "12 round-trips: ButtonPress 9, other 3"
+        );
+    }
+}
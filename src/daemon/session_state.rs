@@ -4,10 +4,14 @@
 //! thumbnail positions when characters log out and for position inheritance.
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 use x11rb::protocol::xproto::Window;
 
-use crate::common::types::{CharacterSettings, Position};
+use super::overview::SavedGeometry;
+use super::panic::PanicSnapshot;
+use crate::common::ipc::WinePrefixInfo;
+use crate::common::types::{CharacterSettings, EveServer, Position};
 
 /// Runtime state for position tracking
 /// Window positions are session-only (not persisted to disk)
@@ -26,6 +30,71 @@ pub struct SessionState {
     /// Deadline for hiding thumbnails after focus loss (hysteresis)
     /// Prevents flickering when cycling through clients
     pub focus_loss_deadline: Option<std::time::Instant>,
+
+    /// When the focus-loss fade-out started (`Some` while thumbnails are fading out)
+    pub focus_loss_fade: Option<std::time::Instant>,
+
+    /// When the focus-gain fade-in started and the client windows whose thumbnails it reveals
+    /// (`Some` while thumbnails are fading in)
+    pub focus_gain_fade: Option<(std::time::Instant, Vec<Window>)>,
+
+    /// True while the reveal hotkey is held (thumbnails shown despite hide-when-no-focus)
+    pub reveal_active: bool,
+
+    /// Last verdict of the visibility schedule (None until first evaluated)
+    pub schedule_hidden: Option<bool>,
+
+    /// Client window → thumbnail geometry before the overview opened (`Some` while the overview is shown)
+    pub overview: Option<HashMap<Window, SavedGeometry>>,
+
+    /// Client window currently enlarged by the peek hotkey and its geometry to restore
+    pub peek: Option<(Window, SavedGeometry)>,
+    /// Client window whose thumbnail is enlarged while hovered and its geometry to restore
+    pub hover_zoom: Option<(Window, SavedGeometry)>,
+    /// Client window whose thumbnail is under the pointer
+    pub hovered: Option<Window>,
+    /// Client window whose thumbnail the pointer rests on and when its hover zoom is due
+    pub hover_zoom_pending: Option<(Window, Instant)>,
+    /// Hotkey activation still being verified
+    pub pending_focus: Option<crate::x11::PendingFocus>,
+
+    /// Window ID → Wine prefix the client was started from (detected once per window)
+    pub window_prefix: HashMap<Window, WinePrefixInfo>,
+
+    /// Last profile switch requested by a prefix rule (window, target profile), to avoid
+    /// repeating the request on every FocusIn
+    pub prefix_switch_requested: Option<(Window, String)>,
+
+    /// Character last reported to the Manager as focused (reported once per change)
+    pub reported_focus: Option<String>,
+
+    /// Mapped but unidentified windows → when they were first queued; re-checked until
+    /// identified or the retry window passes (clients may set their title after mapping)
+    pub identity_retries: HashMap<Window, Instant>,
+
+    /// Window ID → last seen WM_CLASS (metadata only; windows are tracked by XID because
+    /// some Proton builds change their class after startup)
+    pub window_class: HashMap<Window, String>,
+
+    /// Client window → pointer position inside it when focus last left it (pointer restore)
+    pub pointer_positions: HashMap<Window, Position>,
+
+    /// What the panic hotkey changed (`Some` until pressed again to restore)
+    pub panic: Option<PanicSnapshot>,
+
+    /// Paused from the Manager or the pause hotkey: thumbnails hidden, hotkeys released and
+    /// only window lifecycle events processed
+    pub paused: bool,
+
+    /// Set by the suspend hotkey: other hotkeys are released until this time (e.g. while
+    /// typing in EVE chat)
+    pub hotkeys_suspended_until: Option<Instant>,
+
+    /// OSD still to be shown (switch by hotkey or click, or a state change announcement)
+    pub osd_request: Option<super::osd::OsdRequest>,
+
+    /// Clients still to be minimized (or verified) after minimize-on-switch
+    pub minimize_queue: super::minimize_queue::MinimizeQueue,
 }
 
 impl SessionState {
@@ -87,10 +156,65 @@ impl SessionState {
         );
     }
 
+    /// Whether the suspend hotkey is holding other hotkeys released at `now`
+    pub fn hotkeys_suspended(&self, now: Instant) -> bool {
+        self.hotkeys_suspended_until
+            .is_some_and(|until| now < until)
+    }
+
+    /// Server a client window is connected to (Tranquility unless detected otherwise)
+    pub fn window_server(&self, window: Window) -> EveServer {
+        self.window_prefix
+            .get(&window)
+            .map(|info| info.server)
+            .unwrap_or_default()
+    }
+
     /// Remove window from session tracking (called on DestroyNotify)
     pub fn remove_window(&mut self, window: Window) {
         self.window_positions.remove(&window);
         self.window_last_character.remove(&window);
+        self.window_prefix.remove(&window);
+        self.window_class.remove(&window);
+        self.pointer_positions.remove(&window);
+        self.identity_retries.remove(&window);
+        if let Some(panic) = self.panic.as_mut() {
+            panic.forget_window(window);
+        }
+        if self.hovered == Some(window) {
+            self.hovered = None;
+        }
+        if self
+            .hover_zoom_pending
+            .is_some_and(|(pending, _)| pending == window)
+        {
+            self.hover_zoom_pending = None;
+        }
+    }
+
+    /// Record a window's WM_CLASS; returns the previous class if it changed
+    pub fn note_window_class(&mut self, window: Window, class: &str) -> Option<String> {
+        match self.window_class.insert(window, class.to_string()) {
+            Some(previous) if previous != class => Some(previous),
+            _ => None,
+        }
+    }
+
+    /// Queue a mapped window whose title didn't identify it yet (keeps the first queue time)
+    pub fn queue_identity_retry(&mut self, window: Window, now: Instant) {
+        self.identity_retries.entry(window).or_insert(now);
+    }
+
+    /// Windows to re-check now; drops those queued longer than `window_len` ago
+    pub fn due_identity_retries(&mut self, now: Instant, window_len: Duration) -> Vec<Window> {
+        self.identity_retries.retain(|window, queued| {
+            let keep = now.saturating_duration_since(*queued) < window_len;
+            if !keep {
+                debug!(window = *window, "Gave up re-checking unidentified window");
+            }
+            keep
+        });
+        self.identity_retries.keys().copied().collect()
     }
 
     /// Update last known character for a window (called on character name change)
@@ -132,6 +256,27 @@ mod tests {
             window_positions: HashMap::from([(456, Position::new(300, 400))]),
             window_last_character: HashMap::new(),
             focus_loss_deadline: None,
+            focus_loss_fade: None,
+            focus_gain_fade: None,
+            reveal_active: false,
+            schedule_hidden: None,
+            overview: None,
+            peek: None,
+            hover_zoom: None,
+            hovered: None,
+            hover_zoom_pending: None,
+            pending_focus: None,
+            window_prefix: HashMap::new(),
+            prefix_switch_requested: None,
+            reported_focus: None,
+            identity_retries: HashMap::new(),
+            window_class: HashMap::new(),
+            pointer_positions: HashMap::new(),
+            panic: None,
+            paused: false,
+            hotkeys_suspended_until: None,
+            osd_request: None,
+            minimize_queue: Default::default(),
         };
         let char_positions = HashMap::new();
 
@@ -146,6 +291,27 @@ mod tests {
             window_positions: HashMap::from([(789, Position::new(500, 600))]),
             window_last_character: HashMap::new(),
             focus_loss_deadline: None,
+            focus_loss_fade: None,
+            focus_gain_fade: None,
+            reveal_active: false,
+            schedule_hidden: None,
+            overview: None,
+            peek: None,
+            hover_zoom: None,
+            hovered: None,
+            hover_zoom_pending: None,
+            pending_focus: None,
+            window_prefix: HashMap::new(),
+            prefix_switch_requested: None,
+            reported_focus: None,
+            identity_retries: HashMap::new(),
+            window_class: HashMap::new(),
+            pointer_positions: HashMap::new(),
+            panic: None,
+            paused: false,
+            hotkeys_suspended_until: None,
+            osd_request: None,
+            minimize_queue: Default::default(),
         };
         let char_positions = HashMap::new();
 
@@ -160,6 +326,27 @@ mod tests {
             window_positions: HashMap::new(),
             window_last_character: HashMap::new(),
             focus_loss_deadline: None,
+            focus_loss_fade: None,
+            focus_gain_fade: None,
+            reveal_active: false,
+            schedule_hidden: None,
+            overview: None,
+            peek: None,
+            hover_zoom: None,
+            hovered: None,
+            hover_zoom_pending: None,
+            pending_focus: None,
+            window_prefix: HashMap::new(),
+            prefix_switch_requested: None,
+            reported_focus: None,
+            identity_retries: HashMap::new(),
+            window_class: HashMap::new(),
+            pointer_positions: HashMap::new(),
+            panic: None,
+            paused: false,
+            hotkeys_suspended_until: None,
+            osd_request: None,
+            minimize_queue: Default::default(),
         };
         let char_positions = HashMap::new();
 
@@ -174,6 +361,27 @@ mod tests {
             window_positions: HashMap::from([(111, Position::new(700, 800))]),
             window_last_character: HashMap::new(),
             focus_loss_deadline: None,
+            focus_loss_fade: None,
+            focus_gain_fade: None,
+            reveal_active: false,
+            schedule_hidden: None,
+            overview: None,
+            peek: None,
+            hover_zoom: None,
+            hovered: None,
+            hover_zoom_pending: None,
+            pending_focus: None,
+            window_prefix: HashMap::new(),
+            prefix_switch_requested: None,
+            reported_focus: None,
+            identity_retries: HashMap::new(),
+            window_class: HashMap::new(),
+            pointer_positions: HashMap::new(),
+            panic: None,
+            paused: false,
+            hotkeys_suspended_until: None,
+            osd_request: None,
+            minimize_queue: Default::default(),
         };
         let char_positions = HashMap::new();
 
@@ -266,6 +474,28 @@ mod tests {
         assert_eq!(state.window_positions.len(), 0);
     }
 
+    #[test]
+    fn test_remove_window_forgets_pending_state() {
+        let mut state = SessionState::new();
+        let now = Instant::now();
+        state.update_window_position(777, 100, 200);
+        state.note_window_class(777, "exefile.exe");
+        state.queue_identity_retry(777, now);
+        state.queue_identity_retry(888, now);
+        state.hovered = Some(777);
+        state.hover_zoom_pending = Some((777, now));
+
+        state.remove_window(777);
+        assert!(state.window_positions.is_empty());
+        assert!(!state.window_class.contains_key(&777));
+        assert_eq!(state.hovered, None);
+        assert_eq!(state.hover_zoom_pending, None);
+        assert_eq!(
+            state.due_identity_retries(now, Duration::from_secs(5)),
+            vec![888]
+        );
+    }
+
     #[test]
     fn test_remove_nonexistent_window() {
         let mut state = SessionState::new();
@@ -279,4 +509,51 @@ mod tests {
             Some(&Position::new(100, 200))
         );
     }
+
+    #[test]
+    fn test_identity_retries_expire() {
+        let mut state = SessionState::new();
+        let start = Instant::now();
+        let window_len = Duration::from_secs(5);
+
+        state.queue_identity_retry(1, start);
+        state.queue_identity_retry(2, start + Duration::from_secs(3));
+        // Re-queueing keeps the original time
+        state.queue_identity_retry(1, start + Duration::from_secs(4));
+
+        let mut due = state.due_identity_retries(start + Duration::from_secs(1), window_len);
+        due.sort();
+        assert_eq!(due, vec![1, 2]);
+
+        let due = state.due_identity_retries(start + Duration::from_secs(6), window_len);
+        assert_eq!(due, vec![2]);
+
+        let due = state.due_identity_retries(start + Duration::from_secs(9), window_len);
+        assert!(due.is_empty());
+        assert!(state.identity_retries.is_empty());
+    }
+
+    #[test]
+    fn test_window_class_change() {
+        let mut state = SessionState::new();
+        assert_eq!(state.note_window_class(7, "exefile.exe"), None);
+        assert_eq!(state.note_window_class(7, "exefile.exe"), None);
+        assert_eq!(
+            state.note_window_class(7, "steam_app_8500"),
+            Some("exefile.exe".to_string())
+        );
+
+        state.remove_window(7);
+        assert!(state.window_class.is_empty());
+    }
+
+    #[test]
+    fn test_hotkeys_suspended() {
+        let now = Instant::now();
+        let mut state = SessionState::new();
+        assert!(!state.hotkeys_suspended(now));
+        state.hotkeys_suspended_until = Some(now + Duration::from_secs(10));
+        assert!(state.hotkeys_suspended(now));
+        assert!(!state.hotkeys_suspended(now + Duration::from_secs(10)));
+    }
 }
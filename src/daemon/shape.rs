@@ -0,0 +1,383 @@
+//! Thumbnail window shapes
+//!
+//! Builds XShape bounding regions for non-rectangular thumbnails (rounded corners, circle crop).
+//! The region is expressed as horizontal bands; rows with the same inset are merged.
+//!
+//! Borders, padding and glow are rings running along the same outline, so they follow the
+//! rounded corners instead of being clipped off by the window shape.
+
+use x11rb::protocol::xproto::Rectangle;
+
+use crate::common::types::{Dimensions, ThumbnailShape};
+
+/// Computes the bounding region for a thumbnail shape.
+///
+/// Returns `None` when the window should stay a plain rectangle (no shape mask).
+pub fn shape_region(
+    shape: ThumbnailShape,
+    corner_radius: u16,
+    dimensions: Dimensions,
+) -> Option<Vec<Rectangle>> {
+    let spans = row_spans(shape, corner_radius, dimensions)?;
+    let mut bands: Vec<Rectangle> = Vec::new();
+    for (row, (x, width)) in spans.into_iter().enumerate() {
+        push_band(&mut bands, x as i16, row as i16, width);
+    }
+    Some(bands)
+}
+
+/// Computes the region `width` pixels wide that runs `inset` pixels inside a shape's outline.
+///
+/// The ring lies between two nested outlines: the shape shrunk by `inset` on every side and
+/// the shape shrunk by `inset + width`, with the corner radius shrunk alike. Returns `None` for
+/// shapes that stay plain rectangles, whose rings are four strips.
+pub fn ring_region(
+    shape: ThumbnailShape,
+    corner_radius: u16,
+    dimensions: Dimensions,
+    inset: u16,
+    width: u16,
+) -> Option<Vec<Rectangle>> {
+    row_spans(shape, corner_radius, dimensions)?;
+
+    // Rows of the shape shrunk by `by` (first row, spans), or None once nothing is left
+    let nested = |by: u16| {
+        let size = Dimensions::new(
+            dimensions.width.checked_sub(by.checked_mul(2)?)?,
+            dimensions.height.checked_sub(by.checked_mul(2)?)?,
+        );
+        (size.width > 0 && size.height > 0).then(|| {
+            row_spans(shape, corner_radius.saturating_sub(by), size)
+                .unwrap_or_else(|| vec![(0, size.width); size.height as usize])
+        })
+    };
+
+    let mut bands: Vec<Rectangle> = Vec::new();
+    let Some(outer) = nested(inset) else {
+        return Some(bands);
+    };
+    let inner_by = inset.saturating_add(width);
+    let inner = nested(inner_by).unwrap_or_default();
+
+    for (row, (x, span)) in outer.into_iter().enumerate() {
+        let y = (inset as usize + row) as i16;
+        let (x, end) = (inset + x, inset + x + span);
+        let inner_span = (y as usize)
+            .checked_sub(inner_by as usize)
+            .and_then(|row| inner.get(row));
+        match inner_span {
+            Some(&(ix, ispan)) => {
+                let (ix, iend) = (inner_by + ix, inner_by + ix + ispan);
+                push_band(&mut bands, x as i16, y, ix.saturating_sub(x));
+                push_band(&mut bands, iend as i16, y, end.saturating_sub(iend));
+            }
+            None => push_band(&mut bands, x as i16, y, span),
+        }
+    }
+    Some(bands)
+}
+
+/// Bands of a ring along any outline: `ring_region` for shaped thumbnails, four strips
+/// (top, bottom, left, right) for plain rectangles.
+pub fn ring_bands(
+    shape: ThumbnailShape,
+    corner_radius: u16,
+    dimensions: Dimensions,
+    inset: u16,
+    width: u16,
+) -> Vec<Rectangle> {
+    if let Some(bands) = ring_region(shape, corner_radius, dimensions, inset, width) {
+        return bands;
+    }
+
+    let outer_w = dimensions.width.saturating_sub(inset * 2);
+    let outer_h = dimensions.height.saturating_sub(inset * 2);
+    if width == 0 || outer_w == 0 || outer_h == 0 {
+        return Vec::new();
+    }
+    let width = width.min(outer_w.div_ceil(2)).min(outer_h.div_ceil(2));
+    let side_h = outer_h.saturating_sub(width * 2);
+    let (i, b) = (inset as i16, width as i16);
+    [
+        (i, i, outer_w, width),
+        (i, i + outer_h as i16 - b, outer_w, width),
+        (i, i + b, width, side_h),
+        (i + outer_w as i16 - b, i + b, width, side_h),
+    ]
+    .into_iter()
+    .filter(|&(_, _, w, h)| w > 0 && h > 0)
+    .map(|(x, y, width, height)| Rectangle {
+        x,
+        y,
+        width,
+        height,
+    })
+    .collect()
+}
+
+/// Cuts ring bands into dashes `dash` pixels long with gaps as long.
+///
+/// Rows within `edge` pixels of the top or bottom are cut along x, the sides along y, so the
+/// dashes run along the ring.
+pub fn dashed(bands: &[Rectangle], dimensions: Dimensions, edge: u16, dash: u16) -> Vec<Rectangle> {
+    let dash = i32::from(dash.max(1));
+    let edge = i32::from(edge);
+    let bottom = (i32::from(dimensions.height) - edge).max(edge);
+    // Sub-ranges of [start, end) lying on a dash
+    let dashes = |start: i32, end: i32| {
+        let mut pieces = Vec::new();
+        let mut pos = start;
+        while pos < end {
+            let stop = ((pos / dash + 1) * dash).min(end);
+            if (pos / dash) % 2 == 0 {
+                pieces.push((pos, stop - pos));
+            }
+            pos = stop;
+        }
+        pieces
+    };
+
+    let mut out = Vec::new();
+    for band in bands {
+        let (x, y) = (i32::from(band.x), i32::from(band.y));
+        let (right, lower) = (x + i32::from(band.width), y + i32::from(band.height));
+        let zones = [
+            (y, lower.min(edge), true),
+            (y.max(bottom), lower, true),
+            (y.max(edge), lower.min(bottom), false),
+        ];
+        for (top, end, along_x) in zones {
+            if top >= end {
+                continue;
+            }
+            if along_x {
+                for (px, len) in dashes(x, right) {
+                    out.push(Rectangle {
+                        x: px as i16,
+                        y: top as i16,
+                        width: len as u16,
+                        height: (end - top) as u16,
+                    });
+                }
+            } else {
+                for (py, len) in dashes(top, end) {
+                    out.push(Rectangle {
+                        x: band.x,
+                        y: py as i16,
+                        width: band.width,
+                        height: len as u16,
+                    });
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Adds a one-row band, extending an identical band ending on the row above
+fn push_band(bands: &mut Vec<Rectangle>, x: i16, y: i16, width: u16) {
+    if width == 0 {
+        return;
+    }
+    // A row has at most two bands, so a match is among the last few
+    let previous = bands
+        .iter_mut()
+        .rev()
+        .take(4)
+        .find(|band| band.x == x && band.width == width && band.y + band.height as i16 == y);
+    match previous {
+        Some(band) => band.height += 1,
+        None => bands.push(Rectangle {
+            x,
+            y,
+            width,
+            height: 1,
+        }),
+    }
+}
+
+/// First column and width of every row of a shape, or `None` for plain rectangles
+fn row_spans(
+    shape: ThumbnailShape,
+    corner_radius: u16,
+    dimensions: Dimensions,
+) -> Option<Vec<(u16, u16)>> {
+    let width = dimensions.width as f32;
+    let height = dimensions.height as f32;
+
+    let (rx, ry) = match shape {
+        ThumbnailShape::Rectangle => return None,
+        ThumbnailShape::RoundedRect => {
+            let r = (corner_radius as f32).min(width / 2.0).min(height / 2.0);
+            (r, r)
+        }
+        ThumbnailShape::Circle => (width / 2.0, height / 2.0),
+    };
+
+    if rx < 1.0 || ry < 1.0 {
+        return None;
+    }
+
+    let mut spans = Vec::with_capacity(dimensions.height as usize);
+    for row in 0..dimensions.height {
+        let center_y = row as f32 + 0.5;
+        let dy = if center_y < ry {
+            ry - center_y
+        } else if center_y > height - ry {
+            center_y - (height - ry)
+        } else {
+            0.0
+        };
+
+        let inset = if dy > 0.0 {
+            let t = (dy / ry).min(1.0);
+            (rx - rx * (1.0 - t * t).sqrt()).round() as u16
+        } else {
+            0
+        };
+        spans.push((inset, dimensions.width.saturating_sub(inset * 2)));
+    }
+
+    Some(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covered_rows(bands: &[Rectangle]) -> u32 {
+        bands.iter().map(|b| b.height as u32).sum()
+    }
+
+    #[test]
+    fn test_rectangle_has_no_region() {
+        assert!(shape_region(ThumbnailShape::Rectangle, 12, Dimensions::new(250, 140)).is_none());
+    }
+
+    #[test]
+    fn test_zero_radius_has_no_region() {
+        assert!(shape_region(ThumbnailShape::RoundedRect, 0, Dimensions::new(250, 140)).is_none());
+    }
+
+    #[test]
+    fn test_rounded_rect_corners_are_inset() {
+        let dims = Dimensions::new(250, 140);
+        let bands = shape_region(ThumbnailShape::RoundedRect, 12, dims).unwrap();
+
+        assert_eq!(covered_rows(&bands), 140);
+        // Top and bottom rows are inset, middle band spans the full width
+        assert!(bands.first().unwrap().x > 0);
+        assert!(bands.last().unwrap().x > 0);
+        assert!(
+            bands
+                .iter()
+                .any(|b| b.x == 0 && b.width == 250 && b.height >= 140 - 24)
+        );
+    }
+
+    #[test]
+    fn test_rounded_rect_is_symmetric() {
+        let bands =
+            shape_region(ThumbnailShape::RoundedRect, 10, Dimensions::new(100, 60)).unwrap();
+        let first = bands.first().unwrap();
+        let last = bands.last().unwrap();
+        assert_eq!(first.x, last.x);
+        assert_eq!(first.width, last.width);
+    }
+
+    #[test]
+    fn test_ring_follows_rounded_corners() {
+        let dims = Dimensions::new(100, 60);
+        assert!(ring_region(ThumbnailShape::Rectangle, 10, dims, 0, 3).is_none());
+
+        let ring = ring_region(ThumbnailShape::RoundedRect, 10, dims, 0, 3).unwrap();
+        let shape = shape_region(ThumbnailShape::RoundedRect, 10, dims).unwrap();
+        // The outer edge is the window shape: the top row matches, and the middle rows are
+        // 3 pixels on each side
+        assert_eq!(
+            (ring[0].x, ring[0].y, ring[0].width),
+            (shape[0].x, 0, shape[0].width)
+        );
+        let middle: Vec<_> = ring
+            .iter()
+            .filter(|b| b.y <= 30 && b.y + b.height as i16 > 30)
+            .collect();
+        assert_eq!(middle.len(), 2);
+        assert_eq!((middle[0].x, middle[0].width), (0, 3));
+        assert_eq!((middle[1].x, middle[1].width), (97, 3));
+
+        // Every pixel of the ring is inside the shape
+        let inside = |x: i16, y: i16| {
+            shape.iter().any(|b| {
+                y >= b.y && y < b.y + b.height as i16 && x >= b.x && x < b.x + b.width as i16
+            })
+        };
+        for band in &ring {
+            assert!(inside(band.x, band.y) && inside(band.x + band.width as i16 - 1, band.y));
+        }
+    }
+
+    #[test]
+    fn test_ring_inset_and_too_thick() {
+        let dims = Dimensions::new(40, 20);
+        let ring = ring_region(ThumbnailShape::Circle, 0, dims, 2, 1).unwrap();
+        assert!(ring.iter().all(|b| b.y >= 2 && b.y + b.height as i16 <= 18));
+        assert!(ring.iter().all(|b| b.x >= 2 && b.x + b.width as i16 <= 38));
+
+        // A ring wider than half the shape fills it
+        let filled = ring_region(ThumbnailShape::Circle, 0, dims, 0, 50).unwrap();
+        assert_eq!(covered_rows(&filled), 20);
+        assert!(
+            ring_region(ThumbnailShape::Circle, 0, dims, 10, 1)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_rectangle_ring_is_four_strips() {
+        let dims = Dimensions::new(100, 60);
+        let strips = ring_bands(ThumbnailShape::Rectangle, 0, dims, 2, 3);
+        assert_eq!(strips.len(), 4);
+        assert_eq!((strips[0].x, strips[0].y, strips[0].width), (2, 2, 96));
+        assert_eq!((strips[3].x, strips[3].height), (95, 50));
+        assert!(ring_bands(ThumbnailShape::Rectangle, 0, dims, 30, 3).is_empty());
+    }
+
+    #[test]
+    fn test_dashes_alternate_along_the_ring() {
+        let dims = Dimensions::new(100, 60);
+        let ring = ring_bands(ThumbnailShape::Rectangle, 0, dims, 0, 2);
+        let dashes = dashed(&ring, dims, 2, 6);
+
+        let top: Vec<_> = dashes.iter().filter(|d| d.y == 0).collect();
+        assert_eq!(top.len(), 9);
+        assert!(top.iter().all(|d| d.x % 12 == 0 && d.height == 2));
+        // The sides are cut along y, inside the top and bottom edges
+        let left: Vec<_> = dashes
+            .iter()
+            .filter(|d| d.x == 0 && d.y > 0 && d.y < 58)
+            .collect();
+        assert!(
+            left.iter()
+                .all(|d| d.width == 2 && d.y >= 2 && d.y + d.height as i16 <= 58)
+        );
+        assert!(left.iter().all(|d| (d.y / 6) % 2 == 0));
+        let covered: u32 = dashes
+            .iter()
+            .map(|d| d.width as u32 * d.height as u32)
+            .sum();
+        let ring_area: u32 = ring.iter().map(|d| d.width as u32 * d.height as u32).sum();
+        assert!(covered > ring_area / 3 && covered < ring_area * 2 / 3);
+    }
+
+    #[test]
+    fn test_circle_widest_at_center() {
+        let bands = shape_region(ThumbnailShape::Circle, 0, Dimensions::new(100, 100)).unwrap();
+        assert_eq!(covered_rows(&bands), 100);
+
+        let widest = bands.iter().map(|b| b.width).max().unwrap();
+        assert!(widest >= 98);
+        assert!(bands.first().unwrap().width < 30);
+    }
+}
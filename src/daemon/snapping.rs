@@ -1,11 +1,14 @@
-//! Thumbnail edge snapping
+//! Thumbnail edge snapping and placement
 //!
-//! Calculates snap positions when dragging thumbnails near other thumbnails.
+//! Calculates snap positions when dragging thumbnails near other thumbnails or monitor edges.
 //! Supports edge-to-edge and alignment snapping within a configurable threshold.
+//! New thumbnails without a saved position are placed on the monitor their client is on, and
+//! thumbnails left off-screen by a monitor layout change are moved onto the nearest monitor.
 
-use crate::common::types::Position;
+use crate::common::types::{Dimensions, Position};
+use crate::x11::Monitor;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rect {
     pub x: i16,
     pub y: i16,
@@ -31,6 +34,24 @@ impl Rect {
         // Use saturating_add to prevent overflow when y + height > i16::MAX
         self.y.saturating_add(self.height as i16)
     }
+
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.left() < other.right()
+            && other.left() < self.right()
+            && self.top() < other.bottom()
+            && other.top() < self.bottom()
+    }
+}
+
+impl From<Monitor> for Rect {
+    fn from(monitor: Monitor) -> Self {
+        Self {
+            x: monitor.x,
+            y: monitor.y,
+            width: monitor.width,
+            height: monitor.height,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -41,7 +62,12 @@ struct SnapCandidate {
 
 /// Find the best snap position for a dragged thumbnail
 /// Returns position if snapping should occur, None otherwise
-pub fn find_snap_position(dragged: Rect, others: &[Rect], threshold: u16) -> Option<Position> {
+pub fn find_snap_position(
+    dragged: Rect,
+    others: &[Rect],
+    monitors: &[Rect],
+    threshold: u16,
+) -> Option<Position> {
     if threshold == 0 {
         return None; // Snapping disabled
     }
@@ -84,6 +110,14 @@ pub fn find_snap_position(dragged: Rect, others: &[Rect], threshold: u16) -> Opt
         }
     }
 
+    // Monitor snapping: edges of every monitor the thumbnail is on, from the inside
+    for monitor in monitors.iter().filter(|m| dragged.overlaps(m)) {
+        check_snap(&mut best_x, dragged.left(), monitor.left(), threshold);
+        check_snap(&mut best_x, dragged.right(), monitor.right(), threshold);
+        check_snap(&mut best_y, dragged.top(), monitor.top(), threshold);
+        check_snap(&mut best_y, dragged.bottom(), monitor.bottom(), threshold);
+    }
+
     // Apply snaps if found
     let snap_x = best_x.map(|s| dragged.x + s.offset);
     let snap_y = best_y.map(|s| dragged.y + s.offset);
@@ -96,6 +130,53 @@ pub fn find_snap_position(dragged: Rect, others: &[Rect], threshold: u16) -> Opt
     }
 }
 
+/// Position for a new thumbnail of `size` whose client covers `client`: `offset` in from the
+/// client's top-left corner, kept inside `monitor`
+pub fn spawn_position(client: Rect, size: Dimensions, monitor: Rect, offset: i16) -> Position {
+    let clamp = |value: i16, start: i16, end: i16, length: u16| {
+        let last = end.saturating_sub(length as i16).max(start);
+        value.clamp(start, last)
+    };
+    Position::new(
+        clamp(
+            client.x.saturating_add(offset),
+            monitor.left(),
+            monitor.right(),
+            size.width,
+        ),
+        clamp(
+            client.y.saturating_add(offset),
+            monitor.top(),
+            monitor.bottom(),
+            size.height,
+        ),
+    )
+}
+
+/// Where to move a thumbnail at `rect` whose center is on none of `monitors`: inside the
+/// nearest one. None when it is still on-screen (or there are no monitors).
+pub fn rescue_position(rect: Rect, monitors: &[Rect]) -> Option<Position> {
+    let center_x = rect.x as i32 + rect.width as i32 / 2;
+    let center_y = rect.y as i32 + rect.height as i32 / 2;
+    let distance = |monitor: &Rect| {
+        let dx = (monitor.left() as i32 - center_x).max(center_x - monitor.right() as i32 + 1);
+        let dy = (monitor.top() as i32 - center_y).max(center_y - monitor.bottom() as i32 + 1);
+        let (dx, dy) = (dx.max(0) as i64, dy.max(0) as i64);
+        dx * dx + dy * dy
+    };
+
+    let nearest = monitors.iter().min_by_key(|m| distance(m))?;
+    if distance(nearest) == 0 {
+        return None;
+    }
+    Some(spawn_position(
+        rect,
+        Dimensions::new(rect.width, rect.height),
+        *nearest,
+        0,
+    ))
+}
+
 fn check_snap(best: &mut Option<SnapCandidate>, edge: i16, target: i16, threshold: i16) {
     let distance = (edge - target).abs();
     if distance <= threshold {
@@ -132,7 +213,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 0);
+        let result = find_snap_position(dragged, &[other], &[], 0);
         assert_eq!(result, None);
     }
 
@@ -151,7 +232,7 @@ mod tests {
             height: 50,
         };
         // Dragged right edge at 150, other left at 160 - distance 10, within threshold 15
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(110, 100))); // Snapped: dragged.x moves by 10
     }
 
@@ -170,7 +251,7 @@ mod tests {
             height: 50,
         };
         // Dragged left edge at 100, other right at 90 - distance 10
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(90, 100))); // Snapped: dragged.x moves to 90
     }
 
@@ -189,7 +270,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(100, 100))); // X aligned to 100
     }
 
@@ -209,7 +290,7 @@ mod tests {
             height: 50,
         };
         // Dragged right: 150, other right: 145, distance 5
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(95, 100))); // X moves by -5
     }
 
@@ -227,7 +308,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(100, 110))); // Y snapped
     }
 
@@ -245,7 +326,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(100, 90))); // Y snapped
     }
 
@@ -264,7 +345,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(100, 100))); // Y aligned
     }
 
@@ -282,7 +363,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, Some(Position::new(100, 100))); // Both X and Y snap
     }
 
@@ -300,7 +381,7 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[other], 15);
+        let result = find_snap_position(dragged, &[other], &[], 15);
         assert_eq!(result, None); // Too far to snap
     }
 
@@ -324,7 +405,7 @@ mod tests {
             width: 50,
             height: 50,
         }; // 15 pixels away
-        let result = find_snap_position(dragged, &[close, far], 20);
+        let result = find_snap_position(dragged, &[close, far], &[], 20);
         assert_eq!(result, Some(Position::new(105, 100))); // Snaps to closer one
     }
 
@@ -350,7 +431,152 @@ mod tests {
             width: 50,
             height: 50,
         };
-        let result = find_snap_position(dragged, &[snap_x, snap_y], 15);
+        let result = find_snap_position(dragged, &[snap_x, snap_y], &[], 15);
         assert_eq!(result, Some(Position::new(100, 200))); // X from first, Y from second
     }
+
+    #[test]
+    fn test_snap_to_monitor_edges() {
+        let monitors = [
+            Rect {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            },
+            Rect {
+                x: 1920,
+                y: 0,
+                width: 2560,
+                height: 1440,
+            },
+        ];
+        // Right edge 1910, 10px from the first monitor's right edge
+        let dragged = Rect {
+            x: 1660,
+            y: 500,
+            width: 250,
+            height: 140,
+        };
+        let result = find_snap_position(dragged, &[], &monitors, 15);
+        assert_eq!(result, Some(Position::new(1670, 500)));
+
+        // Bottom edge 1430 on the second monitor, which is taller than the first
+        let dragged = Rect {
+            x: 2500,
+            y: 1290,
+            width: 250,
+            height: 140,
+        };
+        let result = find_snap_position(dragged, &[], &monitors, 15);
+        assert_eq!(result, Some(Position::new(2500, 1300)));
+
+        // The first monitor's bottom edge is not a target away from it
+        let dragged = Rect {
+            x: 2500,
+            y: 935,
+            width: 250,
+            height: 140,
+        };
+        assert_eq!(find_snap_position(dragged, &[], &monitors, 15), None);
+    }
+
+    #[test]
+    fn test_spawn_position() {
+        let monitor = Rect {
+            x: 1920,
+            y: 0,
+            width: 2560,
+            height: 1440,
+        };
+        let size = Dimensions::new(250, 140);
+        let client = Rect {
+            x: 1920,
+            y: 0,
+            width: 2560,
+            height: 1440,
+        };
+        assert_eq!(
+            spawn_position(client, size, monitor, 20),
+            Position::new(1940, 20)
+        );
+
+        // Client hanging off the monitor's bottom-right corner
+        let client = Rect {
+            x: 4400,
+            y: 1400,
+            width: 1280,
+            height: 720,
+        };
+        assert_eq!(
+            spawn_position(client, size, monitor, 20),
+            Position::new(4480 - 250, 1440 - 140)
+        );
+
+        // Thumbnail larger than the monitor stays at its top-left corner
+        let small = Rect {
+            x: 0,
+            y: 0,
+            width: 200,
+            height: 100,
+        };
+        assert_eq!(spawn_position(small, size, small, 20), Position::new(0, 0));
+    }
+
+    #[test]
+    fn test_rescue_position() {
+        let monitors = [
+            Rect {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            },
+            Rect {
+                x: 1920,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            },
+        ];
+        let on_screen = Rect {
+            x: 1900,
+            y: 500,
+            width: 250,
+            height: 140,
+        };
+        assert_eq!(rescue_position(on_screen, &monitors), None);
+        // Partly over the bottom edge, center still visible
+        let edge = Rect {
+            x: 100,
+            y: 1000,
+            width: 250,
+            height: 140,
+        };
+        assert_eq!(rescue_position(edge, &monitors), None);
+
+        // Left behind on an unplugged monitor to the left
+        let stranded = Rect {
+            x: -1500,
+            y: 300,
+            width: 250,
+            height: 140,
+        };
+        assert_eq!(
+            rescue_position(stranded, &monitors),
+            Some(Position::new(0, 300))
+        );
+        // Below a monitor whose resolution shrank
+        let stranded = Rect {
+            x: 2500,
+            y: 1300,
+            width: 250,
+            height: 140,
+        };
+        assert_eq!(
+            rescue_position(stranded, &monitors),
+            Some(Position::new(2500, 1080 - 140))
+        );
+        assert_eq!(rescue_position(stranded, &[]), None);
+    }
 }
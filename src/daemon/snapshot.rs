@@ -0,0 +1,141 @@
+//! Session snapshots: capturing and restoring client geometry, minimized state, focus and
+//! thumbnail placement
+
+use std::collections::HashMap;
+use tracing::{info, warn};
+use x11rb::protocol::xproto::Window;
+
+use super::font::FontRenderer;
+use super::startup;
+use super::status_sender::StatusSender;
+use super::thumbnail::Thumbnail;
+use crate::common::ipc::{ClientGeometry, ClientSnapshot};
+use crate::common::types::{Dimensions, Position};
+use crate::config::profile::{LayoutSlot, ThumbnailLayout};
+use crate::config::{DaemonConfig, DisplayConfig};
+use crate::x11::{
+    AppContext, get_active_window, get_window_geometry, is_window_minimized, minimize_window,
+    move_resize_window, unminimize_window,
+};
+
+/// Records every running character's client; returns the clients and the focused character
+pub fn capture(
+    ctx: &AppContext,
+    windows: &HashMap<String, Window>,
+    thumbnails: &[ClientGeometry],
+) -> (Vec<ClientSnapshot>, Option<String>) {
+    let mut clients = Vec::new();
+    for (name, &window) in windows {
+        if name.is_empty() {
+            continue;
+        }
+        let (position, dimensions) = match get_window_geometry(ctx.conn, ctx.screen.root, window) {
+            Ok(geometry) => geometry,
+            Err(e) => {
+                warn!(character = %name, error = %e, "Failed to read client geometry");
+                continue;
+            }
+        };
+        let minimized = is_window_minimized(ctx.conn, window, ctx.atoms).unwrap_or(false);
+        let thumbnail = thumbnails
+            .iter()
+            .find(|t| !t.is_custom && &t.name == name)
+            .map(|t| LayoutSlot {
+                x: t.x,
+                y: t.y,
+                width: t.width,
+                height: t.height,
+            });
+
+        clients.push(ClientSnapshot {
+            name: name.clone(),
+            window: LayoutSlot {
+                x: position.x,
+                y: position.y,
+                width: dimensions.width,
+                height: dimensions.height,
+            },
+            minimized,
+            thumbnail,
+        });
+    }
+    clients.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let active = get_active_window(ctx.conn, ctx.screen, ctx.atoms)
+        .ok()
+        .flatten();
+    let focused = active.and_then(|active| {
+        windows
+            .iter()
+            .find(|&(_, &window)| window == active)
+            .map(|(name, _)| name.clone())
+    });
+
+    info!(clients = clients.len(), focused = ?focused, "Captured session snapshot");
+    (clients, focused)
+}
+
+/// Puts the running clients and their thumbnails back as saved. Returns the characters from
+/// the snapshot that aren't running.
+#[allow(clippy::too_many_arguments)]
+pub fn restore(
+    ctx: &AppContext,
+    clients: &[ClientSnapshot],
+    windows: &HashMap<String, Window>,
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    daemon_config: &mut DaemonConfig,
+    display_config: &DisplayConfig,
+    font_renderer: &FontRenderer,
+    status_tx: &StatusSender,
+) -> Vec<String> {
+    let mut missing = Vec::new();
+    for client in clients {
+        let Some(&window) = windows.get(&client.name) else {
+            missing.push(client.name.clone());
+            continue;
+        };
+        let slot = client.window;
+        if let Err(e) = move_resize_window(
+            ctx.conn,
+            ctx.screen,
+            ctx.atoms,
+            window,
+            Position::new(slot.x, slot.y),
+            Dimensions::new(slot.width, slot.height),
+        ) {
+            warn!(character = %client.name, error = %e, "Failed to restore client geometry");
+        }
+
+        let result = if client.minimized {
+            minimize_window(ctx.conn, ctx.screen, ctx.atoms, window)
+        } else {
+            unminimize_window(ctx.conn, ctx.screen, ctx.atoms, window)
+        };
+        if let Err(e) = result {
+            warn!(character = %client.name, error = %e, "Failed to restore minimized state");
+        }
+    }
+
+    let layout = ThumbnailLayout {
+        name: "session snapshot".to_string(),
+        thumbnails: clients
+            .iter()
+            .filter_map(|c| c.thumbnail.map(|slot| (c.name.clone(), slot)))
+            .collect(),
+    };
+    startup::apply_layout(
+        &layout,
+        eve_clients,
+        daemon_config,
+        display_config,
+        font_renderer,
+        status_tx,
+    );
+
+    info!(
+        restored = clients.len() - missing.len(),
+        missing = missing.len(),
+        "Restored session snapshot"
+    );
+    missing
+}
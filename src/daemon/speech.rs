@@ -0,0 +1,160 @@
+//! Spoken switch announcements
+//!
+//! For players who rely on audio confirmation rather than border colors, the character a
+//! hotkey switched to is spoken aloud. Speech goes through speech-dispatcher (`spd-say`), the
+//! service screen readers such as Orca speak through, so it uses the user's voice settings;
+//! `espeak-ng` or `espeak` is used when speech-dispatcher is not installed. Each announcement
+//! interrupts the previous one so fast cycling never queues up a backlog.
+
+use std::io;
+use std::process::{Child, Command, Stdio};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+/// Speech programs in order of preference, with the arguments placed before the text
+const SPEAKERS: &[(&str, &[&str])] = &[
+    // "message" priority replaces any earlier message still being spoken
+    ("spd-say", &["--priority", "message", "--"]),
+    ("espeak-ng", &["--"]),
+    ("espeak", &["--"]),
+];
+
+/// How often a finished announcement is checked for so it does not linger as a zombie
+const REAP_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Speaker {
+    /// Index into `SPEAKERS` of the first program that could be started
+    program: Option<usize>,
+    /// Announcement still being spoken (or at least not reaped yet)
+    current: Option<Child>,
+}
+
+static SPEAKER: LazyLock<Mutex<Speaker>> = LazyLock::new(|| {
+    Mutex::new(Speaker {
+        program: None,
+        current: None,
+    })
+});
+
+/// Speaks `text`, cutting off the previous announcement
+pub fn announce(text: &str) {
+    let Ok(mut speaker) = SPEAKER.lock() else {
+        return;
+    };
+    if let Some(mut previous) = speaker.current.take() {
+        let _ = previous.kill();
+        let _ = previous.wait();
+    }
+
+    let spawned = first_speaker(speaker.program, |program, args| {
+        Command::new(program)
+            .args(args)
+            .arg(text)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    });
+    let Some((idx, child)) = spawned else {
+        // Nothing to speak with; start over from the top should one get installed
+        speaker.program = None;
+        warn!("No speech program found (install speech-dispatcher or espeak-ng)");
+        return;
+    };
+    if speaker.program != Some(idx) {
+        debug!(program = %SPEAKERS[idx].0, "Speaking switch announcements");
+    }
+    speaker.program = Some(idx);
+    let pid = child.id();
+    speaker.current = Some(child);
+    drop(speaker);
+    spawn_reaper(pid);
+}
+
+/// Starts the first speech program that launches, beginning at the one that worked last time
+fn first_speaker<T>(
+    last: Option<usize>,
+    mut spawn: impl FnMut(&str, &[&str]) -> io::Result<T>,
+) -> Option<(usize, T)> {
+    let start = last.unwrap_or(0);
+    SPEAKERS
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find_map(|(idx, (program, args))| match spawn(program, args) {
+            Ok(spawned) => Some((idx, spawned)),
+            Err(e) => {
+                debug!(program = %program, error = %e, "Speech program unavailable");
+                None
+            }
+        })
+}
+
+/// Waits for announcement `pid` to finish on a detached thread, reaping it once it exits.
+/// The child stays in `SPEAKER` meanwhile so the next announcement can still cut it off.
+fn spawn_reaper(pid: u32) {
+    let spawned = thread::Builder::new()
+        .name("speech-reaper".to_string())
+        .spawn(move || {
+            loop {
+                thread::sleep(REAP_INTERVAL);
+                let Ok(mut speaker) = SPEAKER.lock() else {
+                    return;
+                };
+                let Some(child) = speaker.current.as_mut().filter(|c| c.id() == pid) else {
+                    // Replaced (and reaped) by a newer announcement
+                    return;
+                };
+                if !matches!(child.try_wait(), Ok(None)) {
+                    speaker.current = None;
+                    return;
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        debug!(error = %e, "Failed to start speech reaper thread");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unavailable() -> io::Error {
+        io::Error::from(io::ErrorKind::NotFound)
+    }
+
+    #[test]
+    fn test_first_speaker_falls_back_in_order() {
+        let mut tried = Vec::new();
+        let picked = first_speaker(None, |program, _| {
+            tried.push(program.to_string());
+            if program == "espeak" {
+                Ok(())
+            } else {
+                Err(unavailable())
+            }
+        });
+        assert_eq!(picked, Some((2, ())));
+        assert_eq!(tried, vec!["spd-say", "espeak-ng", "espeak"]);
+    }
+
+    #[test]
+    fn test_first_speaker_resumes_from_last_working() {
+        let mut tried = Vec::new();
+        let picked = first_speaker(Some(1), |program, _| {
+            tried.push(program.to_string());
+            Ok(())
+        });
+        assert_eq!(picked, Some((1, ())));
+        assert_eq!(tried, vec!["espeak-ng"]);
+    }
+
+    #[test]
+    fn test_first_speaker_none_available() {
+        assert_eq!(first_speaker::<()>(None, |_, _| Err(unavailable())), None);
+    }
+}
@@ -0,0 +1,49 @@
+//! Z-order of overlapping thumbnails by per-character stack priority
+
+use std::collections::HashMap;
+use tracing::{debug, warn};
+use x11rb::protocol::xproto::Window;
+
+use super::thumbnail::Thumbnail;
+use crate::config::DisplayConfig;
+
+/// Bottom-to-top raise order: ascending priority, ties broken by window ID for stability
+pub fn stacking_order(mut entries: Vec<(Window, i32)>) -> Vec<Window> {
+    entries.sort_by_key(|&(window, priority)| (priority, window));
+    entries.into_iter().map(|(window, _)| window).collect()
+}
+
+/// Raises visible thumbnails in priority order so higher-priority ones end up on top.
+///
+/// Does nothing unless some visible thumbnail has a non-zero priority, leaving the
+/// stacking untouched for users who never set one.
+pub fn restack(eve_clients: &HashMap<Window, Thumbnail>, display_config: &DisplayConfig) {
+    let entries: Vec<(Window, i32)> = eve_clients
+        .iter()
+        .filter(|(_, t)| t.is_visible())
+        .map(|(w, t)| (*w, display_config.stack_priority(&t.character_name)))
+        .collect();
+    if entries.iter().all(|&(_, priority)| priority == 0) {
+        return;
+    }
+
+    for window in stacking_order(entries) {
+        if let Some(thumbnail) = eve_clients.get(&window)
+            && let Err(e) = thumbnail.raise()
+        {
+            warn!(character = %thumbnail.character_name, error = %e, "Failed to restack thumbnail");
+        }
+    }
+    debug!("Restacked thumbnails by priority");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stacking_order() {
+        let order = stacking_order(vec![(30, 5), (10, 0), (20, -1), (5, 0)]);
+        assert_eq!(order, vec![20, 5, 10, 30]);
+    }
+}
@@ -0,0 +1,92 @@
+//! Per-profile startup actions, run once the initial window scan is done
+
+use std::collections::HashMap;
+use tracing::{info, warn};
+use x11rb::CURRENT_TIME;
+use x11rb::protocol::xproto::Window;
+
+use super::font::FontRenderer;
+use super::minimize_queue::MinimizeQueue;
+use super::status_sender::StatusSender;
+use super::thumbnail::Thumbnail;
+use crate::common::ipc::DaemonMessage;
+use crate::config::profile::ThumbnailLayout;
+use crate::config::{DaemonConfig, DisplayConfig};
+use crate::x11::{AppContext, activate_window};
+
+/// Moves and resizes the thumbnails of every running character listed in `layout`
+pub fn apply_layout(
+    layout: &ThumbnailLayout,
+    eve_clients: &mut HashMap<Window, Thumbnail>,
+    daemon_config: &mut DaemonConfig,
+    display_config: &DisplayConfig,
+    font_renderer: &FontRenderer,
+    status_tx: &StatusSender,
+) {
+    let mut applied = 0;
+    for thumbnail in eve_clients.values_mut() {
+        let Some(slot) = layout.thumbnails.get(&thumbnail.character_name) else {
+            continue;
+        };
+        if let Err(e) = thumbnail
+            .resize(slot.width, slot.height)
+            .and_then(|()| thumbnail.reposition(slot.x, slot.y))
+        {
+            warn!(character = %thumbnail.character_name, error = %e, "Failed to apply layout slot");
+            continue;
+        }
+        let _ = thumbnail.update(display_config, font_renderer);
+
+        if let Some(settings) = daemon_config
+            .character_thumbnails
+            .get_mut(&thumbnail.character_name)
+        {
+            settings.x = slot.x;
+            settings.y = slot.y;
+            settings.dimensions.width = slot.width;
+            settings.dimensions.height = slot.height;
+        }
+        status_tx.send(DaemonMessage::PositionChanged {
+            name: thumbnail.character_name.clone(),
+            x: slot.x,
+            y: slot.y,
+            width: slot.width,
+            height: slot.height,
+            is_custom: false,
+        });
+        applied += 1;
+    }
+    info!(layout = %layout.name, thumbnails = applied, "Applied startup layout");
+}
+
+/// Queues every tracked client except the one running `keep` for minimizing
+pub fn minimize_all_except(
+    queue: &mut MinimizeQueue,
+    windows: &HashMap<String, Window>,
+    keep: &str,
+) {
+    let others = windows
+        .iter()
+        .filter(|(name, _)| *name != keep)
+        .map(|(_, &window)| window);
+    queue.schedule(others, std::time::Instant::now());
+    info!(kept = %keep, "Minimizing clients on startup");
+}
+
+/// Focuses the client running `character`; returns its window when found
+pub fn focus_character(
+    ctx: &AppContext,
+    windows: &HashMap<String, Window>,
+    character: &str,
+) -> Option<Window> {
+    let Some(&window) = windows.get(character) else {
+        warn!(character = %character, "Startup focus target is not running");
+        return None;
+    };
+    if let Err(e) = activate_window(ctx.conn, ctx.screen, ctx.atoms, window, CURRENT_TIME) {
+        warn!(character = %character, error = %e, "Failed to focus client on startup");
+        return None;
+    }
+    info!(character = %character, "Focused client on startup");
+    Some(window)
+}
@@ -0,0 +1,299 @@
+//! Buffered Daemon → Manager status channel
+//!
+//! A failed send on the status channel (Manager busy reconnecting or briefly unreachable) would
+//! otherwise lose the message for good. Failed messages are queued and retried in order on the
+//! next heartbeat tick instead.
+//!
+//! Intermediate `PositionChanged` updates can also be debounced here: only the latest position
+//! per thumbnail is sent once updates have stopped for the profile's position debounce.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::common::ipc::DaemonMessage;
+use ipc_channel::ipc::IpcSender;
+
+/// Maximum number of undelivered status messages kept while the Manager is unreachable
+const MAX_BACKLOG: usize = 256;
+
+/// FIFO of undelivered messages with a fixed capacity (oldest entries are dropped first)
+#[derive(Debug)]
+struct Backlog<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> Backlog<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Queues a message, returning the one that had to be dropped to make room (if any)
+    fn push(&mut self, msg: T) -> Option<T> {
+        let dropped = if self.queue.len() >= self.capacity {
+            self.queue.pop_front()
+        } else {
+            None
+        };
+        self.queue.push_back(msg);
+        dropped
+    }
+
+    /// Sends queued messages in order until one fails; that one is kept at the front.
+    ///
+    /// Returns the number of messages delivered.
+    fn drain_with<E>(&mut self, mut send: impl FnMut(T) -> Result<(), (T, E)>) -> usize {
+        let mut delivered = 0;
+        while let Some(msg) = self.queue.pop_front() {
+            match send(msg) {
+                Ok(()) => delivered += 1,
+                Err((msg, _)) => {
+                    self.queue.push_front(msg);
+                    break;
+                }
+            }
+        }
+        delivered
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// Identifies the thumbnail a `PositionChanged` message belongs to
+fn position_key(msg: &DaemonMessage) -> Option<(&str, bool)> {
+    match msg {
+        DaemonMessage::PositionChanged {
+            name, is_custom, ..
+        } => Some((name.as_str(), *is_custom)),
+        _ => None,
+    }
+}
+
+/// Latest unsent position per thumbnail and when they are due
+#[derive(Debug, Default)]
+struct PendingPositions {
+    latest: Vec<DaemonMessage>,
+    deadline: Option<Instant>,
+}
+
+impl PendingPositions {
+    /// Replaces any pending update for the same thumbnail and pushes the deadline back
+    fn debounce(&mut self, msg: DaemonMessage, delay: Duration) {
+        self.remove(position_key(&msg));
+        self.latest.push(msg);
+        self.deadline = Some(Instant::now() + delay);
+    }
+
+    /// Drops the pending update for a thumbnail (superseded by a final position)
+    fn remove(&mut self, key: Option<(&str, bool)>) {
+        if key.is_some() {
+            self.latest.retain(|m| position_key(m) != key);
+        }
+        if self.latest.is_empty() {
+            self.deadline = None;
+        }
+    }
+
+    fn take(&mut self) -> Vec<DaemonMessage> {
+        self.deadline = None;
+        std::mem::take(&mut self.latest)
+    }
+}
+
+/// Status channel to the Manager that retries failed sends instead of dropping them
+pub struct StatusSender {
+    tx: IpcSender<DaemonMessage>,
+    backlog: Mutex<Backlog<DaemonMessage>>,
+    positions: Mutex<PendingPositions>,
+}
+
+impl StatusSender {
+    pub fn new(tx: IpcSender<DaemonMessage>) -> Self {
+        Self {
+            tx,
+            backlog: Mutex::new(Backlog::new(MAX_BACKLOG)),
+            positions: Mutex::new(PendingPositions::default()),
+        }
+    }
+
+    /// Queues an intermediate `PositionChanged`; only the latest one per thumbnail is sent,
+    /// once no further updates arrived for `quiet`
+    pub fn debounce_position(&self, msg: DaemonMessage, quiet: Duration) {
+        self.positions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .debounce(msg, quiet);
+    }
+
+    /// When the debounced positions are due, if any are pending
+    pub fn position_deadline(&self) -> Option<Instant> {
+        self.positions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .deadline
+    }
+
+    /// Sends all debounced positions now
+    pub fn flush_positions(&self) {
+        let pending = self
+            .positions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if !pending.is_empty() {
+            debug!(count = pending.len(), "Sending debounced position updates");
+        }
+        for msg in pending {
+            self.send(msg);
+        }
+    }
+
+    /// Sends a message, queueing it for retry if the channel is currently failing.
+    ///
+    /// Messages are always delivered in order: while a backlog exists, new messages queue behind it.
+    /// A `PositionChanged` sent this way is final and supersedes any debounced one for the same thumbnail.
+    pub fn send(&self, msg: DaemonMessage) {
+        self.positions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(position_key(&msg));
+
+        let mut backlog = self.backlog.lock().unwrap_or_else(|e| e.into_inner());
+
+        if backlog.is_empty() {
+            match self.transmit(&msg) {
+                Ok(()) => return,
+                Err(e) => {
+                    warn!(error = %e, "Status message to Manager failed, buffering for retry")
+                }
+            }
+        }
+
+        if let Some(dropped) = backlog.push(msg) {
+            warn!(dropped = ?dropped, "Status backlog full, dropping oldest message");
+        }
+    }
+
+    /// Retries the backlog, then sends a heartbeat.
+    ///
+    /// Heartbeats are never buffered: a stale one would only mislead the Manager's health check.
+    pub fn heartbeat(&self) {
+        self.flush();
+        if !self
+            .backlog
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+        {
+            return;
+        }
+        if let Err(e) = self.transmit(&DaemonMessage::Heartbeat) {
+            warn!(error = %e, "Failed to send heartbeat to Manager");
+        }
+    }
+
+    /// Sends one message on the channel, counting it for the metrics
+    fn transmit(&self, msg: &DaemonMessage) -> Result<(), ipc_channel::Error> {
+        self.tx.send(msg.clone())?;
+        super::metrics::record(super::metrics::Counter::IpcSent);
+        Ok(())
+    }
+
+    /// Retries queued messages in order
+    pub fn flush(&self) {
+        let mut backlog = self.backlog.lock().unwrap_or_else(|e| e.into_inner());
+        if backlog.is_empty() {
+            return;
+        }
+        let delivered = backlog.drain_with(|msg| self.transmit(&msg).map_err(|e| (msg, e)));
+        debug!(
+            delivered = delivered,
+            remaining = backlog.len(),
+            "Flushed buffered status messages"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backlog_drops_oldest_when_full() {
+        let mut backlog = Backlog::new(2);
+        assert_eq!(backlog.push(1), None);
+        assert_eq!(backlog.push(2), None);
+        assert_eq!(backlog.push(3), Some(1));
+        assert_eq!(backlog.queue, [2, 3]);
+    }
+
+    #[test]
+    fn test_backlog_drain_stops_at_first_failure() {
+        let mut backlog = Backlog::new(8);
+        for i in 1..=4 {
+            backlog.push(i);
+        }
+
+        let mut sent = Vec::new();
+        let delivered = backlog.drain_with(|msg| {
+            if msg == 3 {
+                Err((msg, ()))
+            } else {
+                sent.push(msg);
+                Ok(())
+            }
+        });
+
+        assert_eq!(delivered, 2);
+        assert_eq!(sent, [1, 2]);
+        assert_eq!(backlog.queue, [3, 4]);
+    }
+
+    fn position(name: &str, x: i16) -> DaemonMessage {
+        DaemonMessage::PositionChanged {
+            name: name.to_string(),
+            x,
+            y: 0,
+            width: 100,
+            height: 100,
+            is_custom: false,
+        }
+    }
+
+    #[test]
+    fn test_pending_positions_keep_latest_per_thumbnail() {
+        let mut pending = PendingPositions::default();
+        pending.debounce(position("A", 1), Duration::from_millis(10));
+        pending.debounce(position("B", 1), Duration::from_millis(10));
+        pending.debounce(position("A", 2), Duration::from_millis(10));
+        assert!(pending.deadline.is_some());
+
+        let sent = pending.take();
+        assert_eq!(sent.len(), 2);
+        assert!(sent.iter().any(
+            |m| matches!(m, DaemonMessage::PositionChanged { name, x: 2, .. } if name == "A")
+        ));
+        assert!(pending.deadline.is_none());
+    }
+
+    #[test]
+    fn test_final_position_supersedes_pending() {
+        let mut pending = PendingPositions::default();
+        pending.debounce(position("A", 1), Duration::from_millis(10));
+
+        pending.remove(position_key(&position("A", 5)));
+        assert!(pending.latest.is_empty());
+        assert!(pending.deadline.is_none());
+    }
+}
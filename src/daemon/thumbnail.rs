@@ -3,19 +3,23 @@
 //! Creates and manages X11 overlay windows that display scaled previews of EVE clients.
 //! High-level logic that delegates rendering to `renderer::ThumbnailRenderer`.
 
+use std::time::{Duration, Instant};
+
 use anyhow::{Context, Result};
-use tracing::debug;
+use tracing::{debug, info};
 use x11rb::protocol::damage::Damage;
-use x11rb::protocol::xproto::{ConnectionExt, Window};
+use x11rb::protocol::xproto::{ConnectionExt, Pixmap, Window};
 
-use crate::common::constants::positioning;
-use crate::common::types::{Dimensions, Position, ThumbnailState};
-use crate::config::DisplayConfig;
+use crate::common::constants::{defaults, positioning};
+use crate::common::types::{
+    CropRegion, Dimensions, EveServer, Position, PreviewMode, ThumbnailShape, ThumbnailState,
+};
+use crate::config::{DisplayConfig, UnfocusedEffect};
 use crate::x11::AppContext;
 
 use super::font::FontRenderer;
 use super::renderer::ThumbnailRenderer;
-use super::snapping::Rect;
+use super::snapping::{self, Rect};
 
 #[derive(Debug, Default)]
 pub struct InputState {
@@ -23,6 +27,27 @@ pub struct InputState {
     pub drag_start: Position,
     pub win_start: Position,
     pub snap_targets: Vec<Rect>, // Cached snap targets computed when drag starts
+    pub snap_monitors: Vec<Rect>, // Monitor layout when the drag started
+}
+
+/// Live previews become screenshot previews on a remote X connection, where every damage
+/// event and repaint is a network round trip. Explicit modes are kept as configured.
+fn effective_preview_mode(mode: PreviewMode) -> PreviewMode {
+    if mode == PreviewMode::Live && crate::x11::display_is_remote() {
+        PreviewMode::Snapshot {
+            interval_secs: defaults::thumbnail::SNAPSHOT_INTERVAL_SECS,
+        }
+    } else {
+        mode
+    }
+}
+
+/// Fade of hide-when-no-focus. Off on a remote X connection, where every opacity step is a
+/// network round trip.
+pub fn hide_fade(display_config: &DisplayConfig) -> Option<Duration> {
+    display_config
+        .hide_fade
+        .filter(|_| !crate::x11::display_is_remote())
 }
 
 #[derive(Debug)]
@@ -37,6 +62,11 @@ pub struct InputState {
 pub struct Thumbnail<'a> {
     // === Application State (public, frequently accessed) ===
     pub character_name: String,
+    /// Previews a custom source rather than an EVE client; its settings live in
+    /// `custom_source_thumbnails` even if a character shares the name
+    pub is_custom: bool,
+    /// (rule id, instance number) of a custom source, keying its saved geometry
+    pub source_instance: Option<(u32, u32)>,
     pub state: ThumbnailState,
     pub hidden: bool, // Tracks if hidden by "hide_when_no_focus"
     pub input_state: InputState,
@@ -45,7 +75,27 @@ pub struct Thumbnail<'a> {
     // === Geometry (public, immutable after creation) ===
     pub dimensions: Dimensions,
 
-    pub current_position: Position, // Cached position for hit testing
+    /// Cached window position (hit testing, drags), kept in sync through ConfigureNotify
+    pub current_position: Position,
+    /// Where the thumbnail belongs while it is moved off a monitor that is gone; it goes back
+    /// once that spot is on-screen again
+    pub displaced_from: Option<Position>,
+
+    /// Sequence number of our latest move; ConfigureNotify events from before it are stale
+    move_sequence: Option<u64>,
+
+    /// Shape and corner radius currently applied to the window (None = not yet applied)
+    applied_shape: Option<(ThumbnailShape, u16)>,
+
+    /// When source content was last captured (paces damage repaints)
+    last_capture: Option<Instant>,
+    /// Damage arrived since the last repaint; painted once `repaint_deadline` passes
+    capture_pending: bool,
+    /// Recoloring of the content on screen (None = no captured content yet)
+    painted_effect: Option<UnfocusedEffect>,
+    /// Thumbnail window is fully covered (e.g. by a full-screen client); damage is held until
+    /// it shows again
+    obscured: bool,
 
     // === Backend ===
     renderer: ThumbnailRenderer<'a>,
@@ -95,13 +145,30 @@ impl<'a> Thumbnail<'a> {
                 src, character_name
             ))?;
 
-        // Use saved position OR top-left of EVE window with 20px padding
-        let Position { x, y } = position.unwrap_or_else(|| {
-            Position::new(
-                src_geom.x + positioning::DEFAULT_SPAWN_OFFSET,
-                src_geom.y + positioning::DEFAULT_SPAWN_OFFSET,
-            )
-        });
+        // Use saved position OR top-left of EVE window with 20px padding, on its monitor
+        let Position { x, y } = match position {
+            Some(position) => position,
+            None => spawn_position(ctx, src, dimensions).context(format!(
+                "Failed to place thumbnail for '{}' (window {})",
+                character_name, src
+            ))?,
+        };
+        // A saved position can be on a monitor that is no longer connected
+        let monitors: Vec<Rect> = ctx.monitors.all().into_iter().map(Rect::from).collect();
+        let rescued = snapping::rescue_position(
+            Rect {
+                x,
+                y,
+                width: dimensions.width,
+                height: dimensions.height,
+            },
+            &monitors,
+        );
+        if let Some(to) = rescued {
+            info!(character = %character_name, x, y, to = ?to, "Saved thumbnail position is off-screen, moving it onto a monitor");
+        }
+        let displaced_from = rescued.map(|_| Position::new(x, y));
+        let Position { x, y } = rescued.unwrap_or(Position::new(x, y));
         debug!(
             character = %character_name,
             x = x,
@@ -125,12 +192,21 @@ impl<'a> Thumbnail<'a> {
 
         Ok(Self {
             character_name,
+            is_custom: false,
+            source_instance: None,
             state: ThumbnailState::default(),
             hidden: false,
             input_state: InputState::default(),
-            preview_mode,
+            preview_mode: effective_preview_mode(preview_mode),
             dimensions,
             current_position: Position::new(x, y),
+            displaced_from,
+            move_sequence: None,
+            applied_shape: None,
+            last_capture: None,
+            capture_pending: false,
+            painted_effect: None,
+            obscured: false,
             renderer,
         })
     }
@@ -192,6 +268,16 @@ impl<'a> Thumbnail<'a> {
         Ok(())
     }
 
+    /// Sets the window opacity (ARGB32 alpha, as in `DisplayConfig::opacity`).
+    ///
+    /// Used to fade thumbnails out on focus loss; restore with the configured opacity.
+    pub fn set_opacity(&self, opacity: u32) -> Result<()> {
+        self.renderer.set_opacity(opacity).context(format!(
+            "Failed to set opacity for '{}'",
+            self.character_name
+        ))
+    }
+
     /// Requests focus for the source EVE client.
     ///
     /// # Arguments
@@ -212,14 +298,94 @@ impl<'a> Thumbnail<'a> {
         // No-op
     }
 
+    /// Reuses a present buffer the server is done with (PresentIdleNotify)
+    pub fn present_idle(&self, pixmap: Pixmap) -> Result<()> {
+        self.renderer.present_idle(pixmap)
+    }
+
+    /// Recreates the capture backend and presenter after their settings changed (full config
+    /// updates); the next `update` draws with them
+    pub fn apply_backend_settings(
+        &mut self,
+        ctx: &AppContext<'a>,
+        display_config: &DisplayConfig,
+    ) -> Result<()> {
+        self.renderer.apply_backend_settings(
+            ctx,
+            display_config,
+            &self.character_name,
+            self.dimensions,
+        )
+    }
+
+    /// Drops capture state bound to the source's old pixmap (on Map/Unmap/ConfigureNotify)
+    pub fn source_changed(&self) {
+        self.renderer.source_changed();
+    }
+
+    /// Raises the thumbnail window above overlapping thumbnails.
+    pub fn raise(&self) -> Result<()> {
+        self.renderer.raise(&self.character_name)
+    }
+
     /// Moves the thumbnail to a new position updates the cached state.
     pub fn reposition(&mut self, x: i16, y: i16) -> Result<()> {
-        self.renderer.reposition(&self.character_name, x, y)?;
+        self.move_sequence = Some(self.renderer.reposition(&self.character_name, x, y)?);
         // Update cached position
         self.current_position = Position::new(x, y);
         Ok(())
     }
 
+    /// Syncs the cached position with a ConfigureNotify of the thumbnail window.
+    ///
+    /// Events the server generated before our latest move are skipped: mid-drag they would
+    /// pull the cache back to where the thumbnail was a few motion events ago.
+    pub fn configured(&mut self, x: i16, y: i16, sequence: u16) {
+        if self
+            .move_sequence
+            .is_some_and(|moved| sequence_precedes(sequence, moved))
+        {
+            return;
+        }
+        self.current_position = Position::new(x, y);
+    }
+
+    /// Cached on-screen geometry of the thumbnail window (no server round-trip)
+    pub fn rect(&self) -> Rect {
+        Rect {
+            x: self.current_position.x,
+            y: self.current_position.y,
+            width: self.dimensions.width,
+            height: self.dimensions.height,
+        }
+    }
+
+    /// Moves the thumbnail onto the nearest of `monitors` when it is off-screen, or back to
+    /// where it was displaced from once that spot is on-screen again. Returns whether it moved.
+    pub fn fit_to_monitors(&mut self, monitors: &[Rect]) -> Result<bool> {
+        let rect_at = |position: Position| Rect {
+            x: position.x,
+            y: position.y,
+            width: self.dimensions.width,
+            height: self.dimensions.height,
+        };
+        if let Some(home) = self.displaced_from
+            && snapping::rescue_position(rect_at(home), monitors).is_none()
+        {
+            self.displaced_from = None;
+            self.reposition(home.x, home.y)?;
+            return Ok(true);
+        }
+
+        let current = self.current_position;
+        let Some(target) = snapping::rescue_position(rect_at(current), monitors) else {
+            return Ok(false);
+        };
+        self.displaced_from.get_or_insert(current);
+        self.reposition(target.x, target.y)?;
+        Ok(true)
+    }
+
     /// Resizes the thumbnail.
     ///
     /// Only performs X11 resize if the dimensions have actually changed.
@@ -239,17 +405,36 @@ impl<'a> Thumbnail<'a> {
 
         self.dimensions = crate::common::types::Dimensions::new(width, height);
         self.renderer.resize(&self.character_name, width, height)?;
+
+        // Shape region depends on size, so rebuild it for the new dimensions
+        if let Some((shape, corner_radius)) = self.applied_shape {
+            self.renderer.apply_shape(
+                &self.character_name,
+                shape,
+                corner_radius,
+                self.dimensions,
+            )?;
+        }
         Ok(())
     }
 
     /// Updates the thumbnail border based on focus state.
+    ///
+    /// Content recolored for the other focus state is repainted right away.
     pub fn border(
-        &self,
+        &mut self,
         display_config: &DisplayConfig,
         focused: bool,
         skipped: bool,
         font_renderer: &FontRenderer,
     ) -> Result<()> {
+        if self
+            .painted_effect
+            .is_some_and(|painted| painted != unfocused_effect(display_config, focused))
+        {
+            self.capture_pending = true;
+            self.last_capture = None;
+        }
         self.renderer.border(
             display_config,
             &self.character_name,
@@ -280,12 +465,86 @@ impl<'a> Thumbnail<'a> {
         Ok(())
     }
 
+    /// Applies the window shape if it differs from the one currently set.
+    fn apply_shape(&mut self, shape: ThumbnailShape, corner_radius: u16) -> Result<()> {
+        if self.applied_shape == Some((shape, corner_radius)) {
+            return Ok(());
+        }
+        // A new window is already rectangular; only a previous shape needs clearing
+        if self.applied_shape.is_none() && shape == ThumbnailShape::Rectangle {
+            return Ok(());
+        }
+
+        self.renderer
+            .apply_shape(&self.character_name, shape, corner_radius, self.dimensions)
+            .context(format!(
+                "Failed to apply shape for '{}'",
+                self.character_name
+            ))?;
+        self.applied_shape = Some((shape, corner_radius));
+        Ok(())
+    }
+
+    /// Tags the thumbnail with the server its client is connected to.
+    pub fn set_server(&mut self, server: EveServer) {
+        self.renderer.set_server(server);
+    }
+
+    /// Marks the thumbnail as previewing a custom source rather than an EVE client.
+    pub fn set_custom_source(&mut self, custom: bool) {
+        self.is_custom = custom;
+        self.renderer.set_custom_source(custom);
+    }
+
+    /// Shows or hides the notes line while the pointer is over the thumbnail.
+    ///
+    /// Only redraws when the character actually has notes and they are not
+    /// already shown permanently via `DisplayConfig::show_notes`.
+    pub fn set_hovered(
+        &mut self,
+        hovered: bool,
+        display_config: &DisplayConfig,
+        skipped: bool,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        self.renderer.set_notes_visible(hovered);
+
+        let has_notes = display_config
+            .character_settings
+            .get(&self.character_name)
+            .and_then(|s| s.notes.as_deref())
+            .is_some_and(|n| !n.trim().is_empty());
+
+        if !has_notes || display_config.show_notes || !self.is_visible() {
+            return Ok(());
+        }
+
+        if self.state.is_minimized() {
+            self.renderer.minimized(
+                display_config,
+                &self.character_name,
+                self.dimensions,
+                font_renderer,
+            )
+        } else {
+            self.border(
+                display_config,
+                self.state.is_focused(),
+                skipped,
+                font_renderer,
+            )
+        }
+    }
+
     /// Triggers a repaint of the thumbnail content and overlay.
     pub fn update(
         &mut self,
         display_config: &DisplayConfig,
         font_renderer: &FontRenderer,
     ) -> Result<()> {
+        // Any repaint covers the damage collected so far
+        self.capture_pending = false;
+
         // Resolve per-character preview visibility override against the global setting.
         // override_render_preview: None = use global, Some(true) = force on, Some(false) = force off
         let should_render = display_config
@@ -300,6 +559,13 @@ impl<'a> Thumbnail<'a> {
             return Ok(());
         }
 
+        let settings = display_config.character_settings.get(&self.character_name);
+        let shape = settings
+            .and_then(|s| s.override_shape)
+            .unwrap_or(display_config.shape);
+        let crop = settings.and_then(|s| s.crop);
+        self.apply_shape(shape, display_config.corner_radius)?;
+
         if !self.is_visible() {
             return Ok(());
         }
@@ -314,9 +580,19 @@ impl<'a> Thumbnail<'a> {
                 )?;
             }
             _ => match &self.preview_mode {
-                crate::common::types::PreviewMode::Live => {
-                    self.renderer
-                        .update(&self.character_name, self.dimensions)?;
+                crate::common::types::PreviewMode::Live
+                | crate::common::types::PreviewMode::Snapshot { .. } => {
+                    let effect = unfocused_effect(display_config, self.state.is_focused());
+                    self.renderer.update(
+                        &self.character_name,
+                        self.dimensions,
+                        crop,
+                        effect,
+                        display_config.unfocused_tint,
+                        display_config.minimized_freeze_frame,
+                    )?;
+                    self.last_capture = Some(Instant::now());
+                    self.painted_effect = Some(effect);
                 }
                 crate::common::types::PreviewMode::Static { color } => {
                     // ... color parsing ...
@@ -338,6 +614,31 @@ impl<'a> Thumbnail<'a> {
         Ok(())
     }
 
+    /// Records damage to the source window. Damage is coalesced: everything reported within
+    /// one frame (or one screenshot-mode interval) is painted by a single capture once
+    /// `repaint_deadline` passes.
+    pub fn mark_damaged(&mut self) {
+        self.capture_pending = true;
+    }
+
+    /// When pending damage should be repainted (None = nothing pending). Live previews repaint
+    /// at most once per `frame`.
+    pub fn repaint_deadline(&self, frame: Duration) -> Option<Instant> {
+        let interval = self.preview_mode.snapshot_interval().unwrap_or(frame);
+        (self.capture_pending && !self.obscured)
+            .then(|| repaint_due(self.last_capture, interval, Instant::now()))
+    }
+
+    /// Records whether the thumbnail window is fully covered. Nothing on screen would show a
+    /// repaint meanwhile, so damage waits until it is uncovered and is painted then.
+    pub fn set_obscured(&mut self, obscured: bool) {
+        if self.obscured && !obscured {
+            // Covered contents may not have been kept; repaint even without new damage
+            self.capture_pending = true;
+        }
+        self.obscured = obscured;
+    }
+
     // focus, reposition, resize unchanged
 
     /// Called when character name changes (e.g. login detection update).
@@ -364,7 +665,7 @@ impl<'a> Thumbnail<'a> {
                     self.character_name
                 ))?;
 
-            self.preview_mode = settings.preview_mode;
+            self.preview_mode = effective_preview_mode(settings.preview_mode);
         }
 
         // Force update of name (and implicit repaint if visible)
@@ -396,4 +697,227 @@ impl<'a> Thumbnail<'a> {
             && y >= self.current_position.y
             && y <= self.current_position.y + self.dimensions.height as i16
     }
+
+    /// Maps a root-window point on the thumbnail to the matching point in the source window
+    /// (the preview, or its `crop` region, is stretched over the full thumbnail, so each axis
+    /// scales independently).
+    pub fn source_point(
+        &self,
+        root_x: i16,
+        root_y: i16,
+        src_size: Dimensions,
+        crop: Option<CropRegion>,
+    ) -> Position {
+        let region = CropRegion::resolve(crop, src_size);
+        let point = scale_point(
+            Position::new(
+                root_x - self.current_position.x,
+                root_y - self.current_position.y,
+            ),
+            self.dimensions,
+            region.dimensions(),
+        );
+        Position::new(point.x + region.x as i16, point.y + region.y as i16)
+    }
+}
+
+/// Opacity `elapsed` into a `total`-long focus-loss fade starting from `base`, or None once the
+/// fade is over
+pub fn fade_out_opacity(base: u32, elapsed: Duration, total: Duration) -> Option<u32> {
+    let total = total.as_millis();
+    let remaining = total.checked_sub(elapsed.as_millis()).filter(|&r| r > 0)?;
+    Some((u128::from(base) * remaining / total) as u32)
+}
+
+/// Opacity `elapsed` into a `total`-long focus-gain fade rising to `base`, or None once the fade
+/// is over
+pub fn fade_in_opacity(base: u32, elapsed: Duration, total: Duration) -> Option<u32> {
+    let total = total.as_millis();
+    let elapsed = elapsed.as_millis();
+    if elapsed >= total {
+        return None;
+    }
+    Some((u128::from(base) * elapsed / total) as u32)
+}
+
+/// Size of a thumbnail after one scroll-wheel notch: `step` pixels wider (or narrower), and
+/// either `step` taller or shaped like `aspect`, within the thumbnail size limits
+pub fn scroll_resized(
+    current: Dimensions,
+    step: u16,
+    grow: bool,
+    aspect: Option<Dimensions>,
+) -> Dimensions {
+    use defaults::thumbnail::{MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH};
+
+    let adjust = |size: u16| {
+        if grow {
+            size.saturating_add(step)
+        } else {
+            size.saturating_sub(step)
+        }
+    };
+    let width = adjust(current.width).clamp(MIN_WIDTH, MAX_WIDTH);
+    let height = match aspect {
+        Some(aspect) if aspect.width > 0 => (u32::from(width) * u32::from(aspect.height)
+            / u32::from(aspect.width))
+        .min(u32::from(MAX_HEIGHT)) as u16,
+        _ => adjust(current.height),
+    };
+    Dimensions::new(width, height.clamp(MIN_HEIGHT, MAX_HEIGHT))
+}
+
+/// Default position for a thumbnail of `size` whose client is `src`, on the monitor holding the
+/// client's center
+fn spawn_position(ctx: &AppContext, src: Window, size: Dimensions) -> Result<Position> {
+    let (origin, client_size) = crate::x11::get_window_geometry(ctx.conn, ctx.screen.root, src)?;
+    let client = Rect {
+        x: origin.x,
+        y: origin.y,
+        width: client_size.width,
+        height: client_size.height,
+    };
+    let center_x = client.x.saturating_add((client.width / 2) as i16);
+    let center_y = client.y.saturating_add((client.height / 2) as i16);
+    let monitor = ctx
+        .monitors
+        .at(center_x, center_y)
+        .map_or(client, Rect::from);
+    Ok(snapping::spawn_position(
+        client,
+        size,
+        monitor,
+        positioning::DEFAULT_SPAWN_OFFSET,
+    ))
+}
+
+/// Recoloring for a thumbnail in the given focus state
+fn unfocused_effect(display_config: &DisplayConfig, focused: bool) -> UnfocusedEffect {
+    if focused {
+        UnfocusedEffect::None
+    } else {
+        display_config.unfocused_effect
+    }
+}
+
+/// Earliest repaint after a capture at `last_capture`, at least `interval` apart
+fn repaint_due(last_capture: Option<Instant>, interval: Duration, now: Instant) -> Instant {
+    last_capture.map_or(now, |taken| (taken + interval).max(now))
+}
+
+/// Scales a point from a `from`-sized area to a `to`-sized one, clamped inside `to`
+fn scale_point(point: Position, from: Dimensions, to: Dimensions) -> Position {
+    let scale = |v: i16, from: u16, to: u16| {
+        let scaled = i32::from(v) * i32::from(to) / i32::from(from.max(1));
+        scaled.clamp(0, i32::from(to.saturating_sub(1))) as i16
+    };
+    Position::new(
+        scale(point.x, from.width, to.width),
+        scale(point.y, from.height, to.height),
+    )
+}
+
+/// Whether an event carrying the 16-bit `sequence` was generated before request `request`
+fn sequence_precedes(sequence: u16, request: u64) -> bool {
+    (sequence.wrapping_sub(request as u16) as i16) < 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_precedes() {
+        assert!(sequence_precedes(99, 100));
+        assert!(!sequence_precedes(100, 100));
+        assert!(!sequence_precedes(101, 100));
+        // Across the 16-bit wrap of event sequence numbers
+        assert!(sequence_precedes(0xFFFF, 0x1_0002));
+        assert!(!sequence_precedes(0x0001, 0xFFFF));
+    }
+
+    #[test]
+    fn test_scroll_resized() {
+        let current = Dimensions::new(300, 200);
+        assert_eq!(
+            scroll_resized(current, 20, true, None),
+            Dimensions::new(320, 220)
+        );
+        assert_eq!(
+            scroll_resized(current, 20, false, None),
+            Dimensions::new(280, 180)
+        );
+        // Locked to 16:9, whatever the thumbnail's own shape
+        assert_eq!(
+            scroll_resized(current, 20, true, Some(Dimensions::new(480, 270))),
+            Dimensions::new(320, 180)
+        );
+        // Never below the minimum size
+        assert_eq!(
+            scroll_resized(Dimensions::new(30, 30), 20, false, None),
+            Dimensions::new(
+                defaults::thumbnail::MIN_WIDTH,
+                defaults::thumbnail::MIN_HEIGHT
+            )
+        );
+    }
+
+    #[test]
+    fn test_scale_point_to_source() {
+        let thumb = Dimensions::new(480, 270);
+        let src = Dimensions::new(1920, 1080);
+        assert_eq!(
+            scale_point(Position::new(240, 135), thumb, src),
+            Position::new(960, 540)
+        );
+        // The hover test is inclusive of the far edge, which must stay inside the source
+        assert_eq!(
+            scale_point(Position::new(480, 270), thumb, src),
+            Position::new(1919, 1079)
+        );
+    }
+
+    #[test]
+    fn test_fade_opacity() {
+        let total = Duration::from_millis(250);
+        assert_eq!(
+            fade_out_opacity(0xFF000000, Duration::ZERO, total),
+            Some(0xFF000000)
+        );
+        assert_eq!(
+            fade_out_opacity(0xFF000000, total / 2, total),
+            Some(0x7F800000)
+        );
+        assert_eq!(fade_out_opacity(0xFF000000, total, total), None);
+        assert_eq!(fade_out_opacity(0xFF000000, total * 2, total), None);
+
+        assert_eq!(fade_in_opacity(0xFF000000, Duration::ZERO, total), Some(0));
+        assert_eq!(
+            fade_in_opacity(0xFF000000, total / 2, total),
+            Some(0x7F800000)
+        );
+        assert_eq!(fade_in_opacity(0xFF000000, total, total), None);
+
+        // A zero duration means no fade at all
+        assert_eq!(
+            fade_out_opacity(0xFF000000, Duration::ZERO, Duration::ZERO),
+            None
+        );
+        assert_eq!(
+            fade_in_opacity(0xFF000000, Duration::ZERO, Duration::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn test_repaint_due() {
+        let now = Instant::now();
+        let frame = Duration::from_millis(defaults::timing::DAMAGE_FRAME_MS.into());
+        // Never captured: repaint now
+        assert_eq!(repaint_due(None, frame, now), now);
+        // Captured within this frame: wait for the next one
+        assert_eq!(repaint_due(Some(now), frame, now), now + frame);
+        // Captured long ago: repaint now, not in the past
+        assert_eq!(repaint_due(now.checked_sub(frame * 4), frame, now), now);
+    }
 }
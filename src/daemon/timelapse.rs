@@ -0,0 +1,248 @@
+//! Timelapse recording: saves a PNG of chosen clients every N seconds (e.g. to review what
+//! an AFK character saw). Frames land in `<data dir>/eve-preview-manager/timelapse/<character>/`,
+//! which keeps at most `TIMELAPSE_MAX_FRAMES` of the newest frames.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use x11rb::protocol::xproto::Window;
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::constants::{config, defaults};
+
+/// Tracks when each recorded client last had a frame saved
+#[derive(Debug, Default)]
+pub struct Recorder {
+    last_frame: HashMap<Window, Instant>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clients whose interval has passed (marked as captured at `now`); windows no longer
+    /// among `candidates` are forgotten so re-enabling starts with an immediate frame
+    pub fn due(&mut self, now: Instant, candidates: &[(Window, Duration)]) -> Vec<Window> {
+        self.last_frame
+            .retain(|window, _| candidates.iter().any(|(w, _)| w == window));
+
+        let mut due = Vec::new();
+        for &(window, interval) in candidates {
+            let ready = self
+                .last_frame
+                .get(&window)
+                .is_none_or(|&last| now.duration_since(last) >= interval);
+            if ready {
+                self.last_frame.insert(window, now);
+                due.push(window);
+            }
+        }
+        due
+    }
+}
+
+/// Base directory for timelapse frames
+pub fn output_dir() -> PathBuf {
+    let mut path = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(config::APP_DIR);
+    path.push("timelapse");
+    path
+}
+
+/// File for a frame of `character` taken at `time`
+pub fn frame_path(base: &Path, character: &str, time: chrono::DateTime<chrono::Local>) -> PathBuf {
+    let folder: String = character
+        .chars()
+        .map(|c| if c == '/' || c == '\\' { '_' } else { c })
+        .collect();
+    base.join(folder)
+        .join(format!("{}.png", time.format("%Y-%m-%d_%H-%M-%S")))
+}
+
+/// Captures and saves frames on a background thread with its own X connection, so neither
+/// GetImage nor PNG encoding blocks the event loop
+#[derive(Debug, Default)]
+pub struct Worker {
+    requests: Option<mpsc::Sender<(Window, String)>>,
+}
+
+impl Worker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a frame of `window`; the thread is started on first use
+    pub fn record(&mut self, window: Window, character: &str) {
+        if self.requests.is_none() {
+            self.requests = spawn_worker();
+        }
+        let Some(requests) = &self.requests else {
+            return;
+        };
+        if requests.send((window, character.to_string())).is_err() {
+            warn!("Timelapse worker stopped, restarting on the next frame");
+            self.requests = None;
+        }
+    }
+}
+
+fn spawn_worker() -> Option<mpsc::Sender<(Window, String)>> {
+    let conn = match x11rb::connect(None) {
+        Ok((conn, _)) => conn,
+        Err(e) => {
+            warn!(error = %e, "Failed to open X11 connection for timelapse frames");
+            return None;
+        }
+    };
+    let (tx, rx) = mpsc::channel::<(Window, String)>();
+    let spawned = std::thread::Builder::new()
+        .name("timelapse".to_string())
+        .spawn(move || {
+            // Ends when the daemon drops the Worker
+            for (window, character) in rx {
+                record_frame(&conn, window, &character);
+            }
+        });
+    match spawned {
+        Ok(_) => Some(tx),
+        Err(e) => {
+            warn!(error = %e, "Failed to spawn timelapse thread");
+            None
+        }
+    }
+}
+
+/// Captures the client, writes the frame and drops the oldest frames past the retention
+/// cap. Minimized or unmapped clients are skipped.
+fn record_frame(conn: &RustConnection, window: Window, character: &str) {
+    let frame = match crate::x11::capture_frame(conn, window) {
+        Ok(Some(frame)) => frame,
+        Ok(None) => {
+            debug!(character = %character, "Client not visible, skipping timelapse frame");
+            return;
+        }
+        Err(e) => {
+            warn!(character = %character, error = %e, "Failed to capture timelapse frame");
+            return;
+        }
+    };
+
+    let path = frame_path(&output_dir(), character, chrono::Local::now());
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| frame.encode_png())
+        .and_then(|png| std::fs::write(&path, png).map_err(anyhow::Error::from));
+    match result {
+        Ok(()) => {
+            debug!(character = %character, path = %path.display(), "Saved timelapse frame")
+        }
+        Err(e) => {
+            warn!(character = %character, path = %path.display(), error = %e, "Failed to save timelapse frame");
+            return;
+        }
+    }
+
+    if let Some(dir) = path.parent()
+        && let Err(e) = prune_frames(dir, defaults::timing::TIMELAPSE_MAX_FRAMES)
+    {
+        warn!(character = %character, error = %e, "Failed to prune timelapse frames");
+    }
+}
+
+/// Deletes the oldest frames in `dir` until at most `max_frames` remain (frame names sort
+/// chronologically)
+pub fn prune_frames(dir: &Path, max_frames: usize) -> std::io::Result<()> {
+    let mut frames: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .collect();
+    if frames.len() <= max_frames {
+        return Ok(());
+    }
+    frames.sort();
+    let excess = frames.len() - max_frames;
+    for path in &frames[..excess] {
+        std::fs::remove_file(path)?;
+    }
+    debug!(dir = %dir.display(), removed = excess, "Pruned old timelapse frames");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_recorder_due() {
+        let mut recorder = Recorder::new();
+        let start = Instant::now();
+        let candidates = [(1, Duration::from_secs(10)), (2, Duration::from_secs(30))];
+
+        assert_eq!(recorder.due(start, &candidates), vec![1, 2]);
+        assert!(
+            recorder
+                .due(start + Duration::from_secs(5), &candidates)
+                .is_empty()
+        );
+        assert_eq!(
+            recorder.due(start + Duration::from_secs(10), &candidates),
+            vec![1]
+        );
+
+        // Dropping a client forgets it; adding it back captures right away
+        assert!(
+            recorder
+                .due(start + Duration::from_secs(12), &candidates[..1])
+                .is_empty()
+        );
+        assert_eq!(
+            recorder.due(start + Duration::from_secs(13), &candidates),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_frame_path() {
+        let time = chrono::Local
+            .with_ymd_and_hms(2026, 10, 15, 9, 5, 3)
+            .unwrap();
+        assert_eq!(
+            frame_path(Path::new("/tmp/tl"), "Some/Pilot", time),
+            PathBuf::from("/tmp/tl/Some_Pilot/2026-10-15_09-05-03.png")
+        );
+    }
+
+    #[test]
+    fn test_prune_frames_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "2026-10-15_09-05-03.png",
+            "2026-10-15_09-04-33.png",
+            "2026-10-15_09-05-33.png",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+
+        prune_frames(dir.path(), 2).unwrap();
+        let mut left: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![
+                "2026-10-15_09-05-03.png",
+                "2026-10-15_09-05-33.png",
+                "notes.txt"
+            ]
+        );
+    }
+}
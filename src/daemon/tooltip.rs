@@ -0,0 +1,212 @@
+//! Hover tooltip
+//!
+//! While the pointer is over a thumbnail, a small panel next to it lists what the thumbnail
+//! may not show itself: the full character name, account tag, minimized state and cycle
+//! position. Handy with labels off or tiny thumbnails. It is drawn like the switch OSD (and is
+//! just as click-through) and only rebuilt when its lines or the thumbnail's geometry change.
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::*;
+use x11rb::rust_connection::RustConnection;
+
+use super::font::FontRenderer;
+use super::osd::{argb_visual, create_panel_window, draw_panel};
+use super::snapping::Rect;
+use crate::common::constants::{eve, osd};
+use crate::common::types::{Dimensions, Position};
+use crate::x11::AppContext;
+
+/// Tooltip lines for a client, top to bottom
+pub fn tooltip_lines(
+    name: &str,
+    account_tag: Option<&str>,
+    minimized: bool,
+    position: Option<(usize, usize)>,
+) -> Vec<String> {
+    let mut lines = vec![if name.is_empty() {
+        eve::LOGGED_OUT_DISPLAY_NAME.to_string()
+    } else {
+        name.to_string()
+    }];
+    if let Some(tag) = account_tag {
+        lines.push(format!("Account: {}", tag));
+    }
+    if minimized {
+        lines.push("Minimized".to_string());
+    }
+    if let Some((index, count)) = position {
+        lines.push(format!("Cycle: {}/{}", index, count));
+    }
+    lines
+}
+
+/// Top-left corner of a tooltip of `size`: below `thumbnail`, or above it when there is no room
+/// below, and inside `monitor`
+fn tooltip_position(thumbnail: Rect, size: Dimensions, monitor: Rect) -> Position {
+    let gap = i32::from(osd::TOOLTIP_GAP);
+    let monitor_bottom = i32::from(monitor.y) + i32::from(monitor.height);
+    let below = i32::from(thumbnail.y) + i32::from(thumbnail.height) + gap;
+    let y = if below + i32::from(size.height) <= monitor_bottom {
+        below
+    } else {
+        i32::from(thumbnail.y) - gap - i32::from(size.height)
+    };
+    let max_x = i32::from(monitor.x) + i32::from(monitor.width) - i32::from(size.width);
+    let x = i32::from(thumbnail.x).min(max_x).max(i32::from(monitor.x));
+    Position::new(x as i16, y.max(i32::from(monitor.y)) as i16)
+}
+
+/// The one hover tooltip
+pub struct HoverTooltip<'a> {
+    conn: &'a RustConnection,
+    shown: Option<Shown>,
+}
+
+struct Shown {
+    window: Window,
+    colormap: Colormap,
+    lines: Vec<String>,
+    thumbnail: Rect,
+}
+
+impl<'a> HoverTooltip<'a> {
+    pub fn new(conn: &'a RustConnection) -> Self {
+        Self { conn, shown: None }
+    }
+
+    /// Shows `lines` next to `thumbnail`, replacing the current tooltip unless it already shows
+    /// these lines
+    pub fn show(
+        &mut self,
+        ctx: &AppContext,
+        lines: Vec<String>,
+        thumbnail: Rect,
+        font_renderer: &FontRenderer,
+    ) -> Result<()> {
+        if self
+            .shown
+            .as_ref()
+            .is_some_and(|shown| shown.lines == lines && shown.thumbnail == thumbnail)
+        {
+            return Ok(());
+        }
+        self.hide();
+        let Some(visual) = argb_visual(ctx.screen) else {
+            warn!("X server offers no 32-bit visual, hover tooltip disabled");
+            return Ok(());
+        };
+
+        let center_x = thumbnail.x.saturating_add((thumbnail.width / 2) as i16);
+        let center_y = thumbnail.y.saturating_add((thumbnail.height / 2) as i16);
+        let monitor = ctx.monitors.at(center_x, center_y).map_or(
+            Rect {
+                x: 0,
+                y: 0,
+                width: ctx.screen.width_in_pixels,
+                height: ctx.screen.height_in_pixels,
+            },
+            Rect::from,
+        );
+
+        let pixmap = ctx.conn.generate_id()?;
+        let line_refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let size = match draw_panel(ctx, pixmap, &line_refs, font_renderer) {
+            Ok(size) => size,
+            Err(e) => {
+                let _ = ctx.conn.free_pixmap(pixmap);
+                return Err(e);
+            }
+        };
+        let position = tooltip_position(thumbnail, size, monitor);
+
+        let (window, colormap) = create_panel_window(
+            ctx,
+            visual,
+            pixmap,
+            position,
+            size,
+            b"eve-preview-tooltip\0eve-preview-tooltip\0",
+        )
+        .context("Failed to create hover tooltip window")?;
+        self.shown = Some(Shown {
+            window,
+            colormap,
+            lines,
+            thumbnail,
+        });
+        ctx.conn
+            .map_window(window)
+            .context("Failed to map hover tooltip window")?;
+        ctx.conn.flush()?;
+        debug!(x = position.x, y = position.y, "Showing hover tooltip");
+        Ok(())
+    }
+
+    /// Removes the tooltip if it is up
+    pub fn hide(&mut self) {
+        if let Some(shown) = self.shown.take() {
+            let _ = self.conn.destroy_window(shown.window);
+            let _ = self.conn.free_colormap(shown.colormap);
+            let _ = self.conn.flush();
+        }
+    }
+}
+
+impl Drop for HoverTooltip<'_> {
+    fn drop(&mut self) {
+        self.hide();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn test_tooltip_lines() {
+        assert_eq!(
+            tooltip_lines("Alice", Some("Main"), true, Some((2, 5))),
+            vec!["Alice", "Account: Main", "Minimized", "Cycle: 2/5"]
+        );
+        assert_eq!(
+            tooltip_lines("", None, false, None),
+            vec![eve::LOGGED_OUT_DISPLAY_NAME]
+        );
+    }
+
+    #[test]
+    fn test_tooltip_position() {
+        let size = Dimensions::new(150, 60);
+        let thumbnail = Rect {
+            x: 100,
+            y: 100,
+            width: 240,
+            height: 135,
+        };
+        assert_eq!(
+            tooltip_position(thumbnail, size, MONITOR),
+            Position::new(100, 235 + osd::TOOLTIP_GAP)
+        );
+
+        // No room below or to the right: above the thumbnail, pulled back onto the monitor
+        let thumbnail = Rect {
+            x: 1850,
+            y: 1000,
+            width: 70,
+            height: 60,
+        };
+        assert_eq!(
+            tooltip_position(thumbnail, size, MONITOR),
+            Position::new(1920 - 150, 1000 - osd::TOOLTIP_GAP - 60)
+        );
+    }
+}
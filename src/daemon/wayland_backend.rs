@@ -0,0 +1,1179 @@
+//! Daemon backend for Wayland compositors where X11 overlay windows can't show previews
+//!
+//! Runs instead of the X11 event loop when forced with `EVE_PREVIEW_BACKEND=wayland`, or as a
+//! fallback when the X11 setup fails (`fallback_available()`). It covers the preview basics: one
+//! live preview per logged-in character at its saved position and size, labelled and bordered
+//! like X11 thumbnails, left click to focus the client (through XWayland, which EVE runs on)
+//! and right drag to move. Hotkeys, cycling, overview, peek and the other X11 features stay
+//! unavailable on this backend.
+
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::os::fd::AsRawFd;
+use std::time::Duration;
+use tokio::io::unix::AsyncFd;
+use tracing::{debug, info, warn};
+use wayland_client::backend::{ObjectId, WaylandError};
+use wayland_client::protocol::wl_shm::Format;
+use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_client::{Connection, Proxy, QueueHandle};
+use wayland_protocols::ext::image_capture_source::v1::client::ext_image_capture_source_v1::ExtImageCaptureSourceV1;
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_frame_v1::ExtImageCopyCaptureFrameV1;
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_manager_v1::Options;
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_session_v1::ExtImageCopyCaptureSessionV1;
+use wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1::Layer;
+use wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::{
+    Anchor, KeyboardInteractivity, ZwlrLayerSurfaceV1,
+};
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_frame_v1::ZwlrScreencopyFrameV1;
+use x11rb::CURRENT_TIME;
+use x11rb::connection::Connection as _;
+use x11rb::rust_connection::RustConnection;
+
+use super::font::{FontRenderer, profile_font};
+use super::main_loop::{connect_manager, forward_config_messages};
+use super::overlay::{BorderState, OverlayBackend, SoftwareOverlay};
+use super::status_sender::StatusSender;
+use crate::common::constants::wayland::*;
+use crate::common::ipc::{
+    ClientGeometry, ConfigMessage, DaemonMessage, HotkeyTestResult, ProtocolHello,
+};
+use crate::common::types::{
+    CharacterSettings, Dimensions, EveWindowType, Position, identify_client,
+};
+use crate::config::{DaemonConfig, DisplayConfig};
+use crate::wayland::{
+    CaptureConstraints, CaptureGlobals, Globals, ShmBuffer, WaylandEvent, WaylandState,
+    bind_outputs, compositor_supported, session_is_wayland,
+};
+use crate::x11::{
+    CachedAtoms, activate_window, get_active_eve_window, get_client_list, get_window_geometry,
+};
+
+/// Whether `EVE_PREVIEW_BACKEND` forces this backend over the X11 one
+pub fn selected() -> bool {
+    std::env::var(BACKEND_ENV).as_deref() == Ok("wayland")
+}
+
+/// Whether to fall back to this backend after the X11 setup failed: on Wayland sessions whose
+/// compositor offers the protocols it needs, unless `EVE_PREVIEW_BACKEND` forces X11
+pub fn fallback_available() -> bool {
+    std::env::var(BACKEND_ENV).as_deref() != Ok("x11")
+        && session_is_wayland()
+        && compositor_supported()
+}
+
+pub async fn run(ipc_server_name: String) -> Result<()> {
+    let conn = Connection::connect_to_env()
+        .context("Failed to connect to the Wayland compositor. Is WAYLAND_DISPLAY set?")?;
+    let (globals, mut queue) = wayland_client::globals::registry_queue_init::<WaylandState>(&conn)
+        .context("Failed to read Wayland globals")?;
+    let qh = queue.handle();
+    let wl = Globals::bind(&globals, &qh)?;
+    bind_outputs(&globals, &qh);
+
+    let mut state = WaylandState::default();
+    // Output geometry and the toplevels that already exist
+    queue
+        .roundtrip(&mut state)
+        .context("Initial Wayland roundtrip failed")?;
+
+    let (config_rx, status_tx, config) = connect_manager(ipc_server_name)?;
+    let status_tx = StatusSender::new(status_tx);
+    let mut config_rx = forward_config_messages(config_rx);
+
+    let mut backend = Backend::new(wl, qh, config, status_tx);
+    let region_capture = matches!(backend.wl.capture, CaptureGlobals::Region(_));
+    if region_capture && backend.xwayland.is_none() {
+        bail!("wlr-screencopy capture locates clients through XWayland, which is unreachable");
+    }
+    info!(
+        outputs = state.outputs.len(),
+        toplevels = state.toplevels.len(),
+        xwayland = backend.xwayland.is_some(),
+        region_capture = region_capture,
+        "Wayland preview backend running"
+    );
+
+    let wayland_fd = AsyncFd::new(conn.backend().poll_fd().as_raw_fd())
+        .context("Failed to create AsyncFd for the Wayland connection")?;
+
+    let mut capture_interval = tokio::time::interval(Duration::from_millis(CAPTURE_INTERVAL_MS));
+    capture_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut heartbeat_interval = tokio::time::interval(Duration::from_secs(3));
+    heartbeat_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        queue
+            .dispatch_pending(&mut state)
+            .context("Failed to dispatch Wayland events")?;
+        backend.handle_events(&mut state);
+        queue
+            .flush()
+            .context("Failed to flush the Wayland connection")?;
+
+        let Some(guard) = queue.prepare_read() else {
+            continue;
+        };
+
+        tokio::select! {
+            ready = wayland_fd.readable() => {
+                let mut ready = ready.context("Wayland connection poll failed")?;
+                match guard.read() {
+                    Ok(_) => {}
+                    Err(WaylandError::Io(e)) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        ready.clear_ready();
+                    }
+                    Err(e) => return Err(e).context("Lost connection to the Wayland compositor"),
+                }
+            }
+
+            Some(message) = config_rx.recv() => {
+                drop(guard);
+                backend.handle_message(message, &state);
+            }
+
+            _ = capture_interval.tick() => {
+                drop(guard);
+                backend.tick(&state);
+            }
+
+            _ = heartbeat_interval.tick() => {
+                drop(guard);
+                backend.status_tx.heartbeat();
+            }
+        }
+    }
+}
+
+/// Layer surface of a shown preview
+struct PreviewSurface {
+    surface: WlSurface,
+    layer: ZwlrLayerSurfaceV1,
+    configured: bool,
+    buffer: Option<ShmBuffer>,
+}
+
+impl Drop for PreviewSurface {
+    fn drop(&mut self) {
+        self.layer.destroy();
+        self.surface.destroy();
+    }
+}
+
+/// Capture of one client
+enum Capture {
+    Toplevel(ToplevelCapture),
+    Region(RegionCapture),
+}
+
+/// `ext-image-copy-capture` session of one toplevel
+struct ToplevelCapture {
+    source: ExtImageCaptureSourceV1,
+    session: ExtImageCopyCaptureSessionV1,
+    buffer: Option<ShmBuffer>,
+    frame: Option<ExtImageCopyCaptureFrameV1>,
+}
+
+impl Drop for ToplevelCapture {
+    fn drop(&mut self) {
+        if let Some(frame) = self.frame.take() {
+            frame.destroy();
+        }
+        self.session.destroy();
+        self.source.destroy();
+    }
+}
+
+/// `wlr-screencopy` of the output region covered by a client's XWayland window
+#[derive(Default)]
+struct RegionCapture {
+    /// Looked up on the first capture and again after a failed one
+    window: Option<u32>,
+    buffer: Option<ShmBuffer>,
+    frame: Option<ZwlrScreencopyFrameV1>,
+}
+
+impl Drop for RegionCapture {
+    fn drop(&mut self) {
+        if let Some(frame) = self.frame.take() {
+            frame.destroy();
+        }
+    }
+}
+
+struct Preview {
+    character: String,
+    position: Position,
+    dimensions: Dimensions,
+    surface: Option<PreviewSurface>,
+    capture: Option<Capture>,
+    /// Latest frame scaled to `dimensions` (BGRA), without label and border
+    content: Vec<u8>,
+    /// Label and border, drawn over `content`
+    overlay: SoftwareOverlay,
+}
+
+/// Pointer over a preview; `drag` is where the right button was pressed (surface coordinates)
+#[derive(Default)]
+struct PointerState {
+    over: Option<ObjectId>,
+    x: f64,
+    y: f64,
+    drag: Option<(f64, f64)>,
+}
+
+/// XWayland connection used to focus clients and to tell which one has focus
+struct XWayland {
+    conn: RustConnection,
+    screen_num: usize,
+    atoms: CachedAtoms,
+}
+
+impl XWayland {
+    fn connect() -> Option<Self> {
+        let (conn, screen_num) = x11rb::connect(None)
+            .inspect_err(
+                |e| warn!(error = %e, "XWayland unreachable, previews can't focus clients"),
+            )
+            .ok()?;
+        let atoms = CachedAtoms::new(&conn)
+            .inspect_err(|e| warn!(error = %e, "Failed to cache XWayland atoms"))
+            .ok()?;
+        Some(Self {
+            conn,
+            screen_num,
+            atoms,
+        })
+    }
+
+    fn character_of(&self, window: u32, config: &DaemonConfig) -> Option<String> {
+        match crate::x11::is_window_eve(
+            &self.conn,
+            window,
+            &self.atoms,
+            &config.profile.client_detection_rules,
+        ) {
+            Ok(Some(EveWindowType::LoggedIn(name))) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn focused_character(&self, config: &DaemonConfig) -> Option<String> {
+        let screen = &self.conn.setup().roots[self.screen_num];
+        let window = get_active_eve_window(
+            &self.conn,
+            screen,
+            &self.atoms,
+            &config.profile.client_detection_rules,
+        )
+        .ok()
+        .flatten()?;
+        self.character_of(window, config)
+    }
+
+    fn window_of(&self, character: &str, config: &DaemonConfig) -> Option<u32> {
+        get_client_list(&self.conn, &self.atoms)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|&w| self.character_of(w, config).as_deref() == Some(character))
+    }
+
+    /// Root coordinates and size of a client window
+    fn geometry(&self, window: u32) -> Option<(Position, Dimensions)> {
+        let root = self.conn.setup().roots[self.screen_num].root;
+        get_window_geometry(&self.conn, root, window)
+            .inspect_err(|e| debug!(window = window, error = %e, "Failed to read client geometry"))
+            .ok()
+    }
+
+    fn focus(&self, character: &str, config: &DaemonConfig) {
+        let screen = &self.conn.setup().roots[self.screen_num];
+        let Some(window) = self.window_of(character, config) else {
+            warn!(character = %character, "No XWayland window found for character");
+            return;
+        };
+        if let Err(e) = activate_window(&self.conn, screen, &self.atoms, window, CURRENT_TIME) {
+            warn!(character = %character, error = %e, "Failed to focus client");
+        }
+    }
+}
+
+struct Backend {
+    wl: Globals,
+    qh: QueueHandle<WaylandState>,
+    config: DaemonConfig,
+    display_config: DisplayConfig,
+    font_renderer: Option<FontRenderer>,
+    status_tx: StatusSender,
+    xwayland: Option<XWayland>,
+    /// Keyed by toplevel handle
+    previews: HashMap<ObjectId, Preview>,
+    focused: Option<String>,
+    paused: bool,
+    pointer: PointerState,
+}
+
+impl Backend {
+    fn new(
+        wl: Globals,
+        qh: QueueHandle<WaylandState>,
+        config: DaemonConfig,
+        status_tx: StatusSender,
+    ) -> Self {
+        Self {
+            wl,
+            qh,
+            display_config: config.build_display_config(),
+            font_renderer: load_font(&config),
+            config,
+            status_tx,
+            xwayland: XWayland::connect(),
+            previews: HashMap::new(),
+            focused: None,
+            paused: false,
+            pointer: PointerState::default(),
+        }
+    }
+
+    fn handle_events(&mut self, state: &mut WaylandState) {
+        for event in std::mem::take(&mut state.events) {
+            match event {
+                WaylandEvent::ToplevelUpdated(id) => self.toplevel_updated(id, state),
+                WaylandEvent::ToplevelClosed(id) => {
+                    if let Some(preview) = self.previews.remove(&id) {
+                        info!(character = %preview.character, "Client closed");
+                    }
+                }
+                WaylandEvent::LayerConfigured(layer) => {
+                    let Some(id) = self.preview_with(|s| s.layer.id() == layer) else {
+                        continue;
+                    };
+                    let Some(preview) = self.previews.get_mut(&id) else {
+                        continue;
+                    };
+                    if let Some(surface) = preview.surface.as_mut() {
+                        surface.configured = true;
+                    }
+                    self.redraw(&id);
+                }
+                WaylandEvent::LayerClosed(layer) => {
+                    let Some(id) = self.preview_with(|s| s.layer.id() == layer) else {
+                        continue;
+                    };
+                    if let Some(preview) = self.previews.get_mut(&id) {
+                        preview.surface = None;
+                    }
+                }
+                WaylandEvent::PointerEnter { surface, x, y } => {
+                    self.pointer.over = self.preview_with(|s| s.surface.id() == surface);
+                    self.pointer.x = x;
+                    self.pointer.y = y;
+                }
+                WaylandEvent::PointerLeave => {
+                    if self.pointer.drag.is_none() {
+                        self.pointer.over = None;
+                    }
+                }
+                WaylandEvent::PointerMotion { x, y } => {
+                    self.pointer.x = x;
+                    self.pointer.y = y;
+                    self.drag_motion(state);
+                }
+                WaylandEvent::PointerButton { button, pressed } => self.button(button, pressed),
+                WaylandEvent::CaptureReady(session, constraints) => {
+                    self.capture_ready(&session, constraints);
+                }
+                WaylandEvent::CaptureStopped(session) => {
+                    let Some(id) = self.preview_with_session(&session) else {
+                        continue;
+                    };
+                    if let Some(preview) = self.previews.get_mut(&id) {
+                        preview.capture = None;
+                    }
+                }
+                WaylandEvent::FrameReady(session) => self.frame_ready(&session),
+                WaylandEvent::FrameFailed(session) => {
+                    let Some(id) = self.preview_with_session(&session) else {
+                        continue;
+                    };
+                    if let Some(preview) = self.previews.get_mut(&id)
+                        && let Some(Capture::Toplevel(capture)) = preview.capture.as_mut()
+                        && let Some(frame) = capture.frame.take()
+                    {
+                        frame.destroy();
+                    }
+                }
+                WaylandEvent::RegionBuffer(id, constraints) => {
+                    self.region_buffer(&id, constraints);
+                }
+                WaylandEvent::RegionReady { toplevel, y_invert } => {
+                    self.region_ready(&toplevel, y_invert);
+                }
+                WaylandEvent::RegionFailed(id) => {
+                    if let Some(preview) = self.previews.get_mut(&id)
+                        && let Some(Capture::Region(capture)) = preview.capture.as_mut()
+                    {
+                        if let Some(frame) = capture.frame.take() {
+                            frame.destroy();
+                        }
+                        // The client may have been replaced by a new window
+                        capture.window = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn preview_with(&self, matches: impl Fn(&PreviewSurface) -> bool) -> Option<ObjectId> {
+        self.previews
+            .iter()
+            .find(|(_, p)| p.surface.as_ref().is_some_and(&matches))
+            .map(|(id, _)| id.clone())
+    }
+
+    fn preview_with_session(&self, session: &ObjectId) -> Option<ObjectId> {
+        self.previews
+            .iter()
+            .find(|(_, p)| {
+                matches!(&p.capture, Some(Capture::Toplevel(c)) if &c.session.id() == session)
+            })
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Starts, renames or drops the preview of a toplevel after its title changed
+    fn toplevel_updated(&mut self, id: ObjectId, state: &WaylandState) {
+        let Some(toplevel) = state.toplevels.get(&id) else {
+            return;
+        };
+        let character = match identify_client(
+            &self.config.profile.client_detection_rules,
+            &toplevel.title,
+            Some(&toplevel.app_id),
+        ) {
+            Some(EveWindowType::LoggedIn(name)) => name,
+            _ => {
+                if let Some(preview) = self.previews.remove(&id) {
+                    info!(character = %preview.character, "Client logged out");
+                }
+                return;
+            }
+        };
+
+        if let Some(preview) = self.previews.get(&id) {
+            if preview.character == character {
+                return;
+            }
+            self.previews.remove(&id);
+        }
+
+        info!(character = %character, app_id = %toplevel.app_id, "Client detected");
+        let settings = self.settings_for(&character);
+        let capture = match &self.wl.capture {
+            CaptureGlobals::Toplevel {
+                sources,
+                image_copy,
+            } => {
+                let source = sources.create_source(&toplevel.handle, &self.qh, ());
+                let session = image_copy.create_session(&source, Options::empty(), &self.qh, ());
+                Capture::Toplevel(ToplevelCapture {
+                    source,
+                    session,
+                    buffer: None,
+                    frame: None,
+                })
+            }
+            CaptureGlobals::Region(_) => Capture::Region(RegionCapture::default()),
+        };
+
+        let preview = Preview {
+            character: character.clone(),
+            position: settings.position(),
+            dimensions: settings.dimensions,
+            surface: None,
+            capture: Some(capture),
+            content: Vec::new(),
+            overlay: SoftwareOverlay::new(settings.dimensions),
+        };
+        self.previews.insert(id, preview);
+        self.config
+            .character_thumbnails
+            .entry(character.clone())
+            .or_insert(settings.clone());
+
+        self.status_tx.send(DaemonMessage::CharacterDetected {
+            name: character.clone(),
+            is_custom: false,
+        });
+        self.status_tx.send(DaemonMessage::PositionChanged {
+            name: character,
+            x: settings.x,
+            y: settings.y,
+            width: settings.dimensions.width,
+            height: settings.dimensions.height,
+            is_custom: false,
+        });
+        self.sync_visibility(state);
+    }
+
+    /// Saved geometry of a character (session changes, then the profile), or a default slot
+    fn settings_for(&self, character: &str) -> CharacterSettings {
+        if let Some(settings) = self
+            .config
+            .character_thumbnails
+            .get(character)
+            .or_else(|| self.config.profile.character_thumbnails.get(character))
+            .filter(|s| s.dimensions.width > 0 && s.dimensions.height > 0)
+        {
+            return settings.clone();
+        }
+        let (width, height) = self.config.default_thumbnail_size(None, 1.0);
+        // Cascade new previews so they don't all land on the same spot
+        let offset = 20 + 30 * self.previews.len() as i16;
+        CharacterSettings::new(offset, offset, width, height)
+    }
+
+    /// Shows or hides every preview according to pause, runtime toggle and focus settings
+    fn sync_visibility(&mut self, state: &WaylandState) {
+        let hidden = self.paused || !self.display_config.enabled;
+        let ids: Vec<ObjectId> = self.previews.keys().cloned().collect();
+        for id in ids {
+            let Some(preview) = self.previews.get(&id) else {
+                continue;
+            };
+            let visible = !hidden
+                && (!self.config.runtime_hidden
+                    || self.display_config.always_visible(&preview.character))
+                && (!self.display_config.hide_when_no_focus
+                    || self.focused.is_some()
+                    || self
+                        .display_config
+                        .keeps_visible_without_focus(&preview.character));
+            match (visible, preview.surface.is_some()) {
+                (true, false) => self.show(&id, state),
+                (false, true) => {
+                    if let Some(preview) = self.previews.get_mut(&id) {
+                        preview.surface = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn show(&mut self, id: &ObjectId, state: &WaylandState) {
+        let Some(preview) = self.previews.get_mut(id) else {
+            return;
+        };
+        let (x, y) = (preview.position.x as i32, preview.position.y as i32);
+        let output = state.output_at(x, y);
+
+        let surface = self.wl.compositor.create_surface(&self.qh, ());
+        let layer = self.wl.layer_shell.get_layer_surface(
+            &surface,
+            output.map(|o| &o.output),
+            Layer::Overlay,
+            LAYER_NAMESPACE.to_string(),
+            &self.qh,
+            (),
+        );
+        layer.set_anchor(Anchor::Top | Anchor::Left);
+        layer.set_exclusive_zone(-1);
+        layer.set_keyboard_interactivity(KeyboardInteractivity::None);
+        layer.set_size(
+            preview.dimensions.width.into(),
+            preview.dimensions.height.into(),
+        );
+        let (origin_x, origin_y) = output.map(|o| (o.x, o.y)).unwrap_or_default();
+        layer.set_margin(y - origin_y, 0, 0, x - origin_x);
+        // The first commit carries no buffer; drawing starts once the compositor configures it
+        surface.commit();
+
+        preview.surface = Some(PreviewSurface {
+            surface,
+            layer,
+            configured: false,
+            buffer: None,
+        });
+    }
+
+    /// Moves or resizes a preview's surface to its current geometry
+    fn apply_geometry(&mut self, id: &ObjectId, state: &WaylandState) {
+        let Some(preview) = self.previews.get_mut(id) else {
+            return;
+        };
+        let Some(surface) = preview.surface.as_ref() else {
+            return;
+        };
+        let (x, y) = (preview.position.x as i32, preview.position.y as i32);
+        let (origin_x, origin_y) = state
+            .output_at(x, y)
+            .map(|o| (o.x, o.y))
+            .unwrap_or_default();
+        surface.layer.set_margin(y - origin_y, 0, 0, x - origin_x);
+        surface.layer.set_size(
+            preview.dimensions.width.into(),
+            preview.dimensions.height.into(),
+        );
+        surface.surface.commit();
+        self.redraw(id);
+    }
+
+    fn capture_ready(&mut self, session: &ObjectId, constraints: CaptureConstraints) {
+        let Some(id) = self.preview_with_session(session) else {
+            return;
+        };
+        let Some(preview) = self.previews.get_mut(&id) else {
+            return;
+        };
+        let Some(Capture::Toplevel(capture)) = preview.capture.as_mut() else {
+            return;
+        };
+        let Some(format) = [Format::Argb8888, Format::Xrgb8888]
+            .into_iter()
+            .find(|f| constraints.formats.contains(f))
+        else {
+            warn!(character = %preview.character, formats = ?constraints.formats, "No supported shm format for capture");
+            return;
+        };
+        if capture.buffer.as_ref().is_some_and(|b| {
+            b.width == constraints.width && b.height == constraints.height && b.format == format
+        }) {
+            return;
+        }
+        capture.buffer = ShmBuffer::new(
+            &self.wl.shm,
+            &self.qh,
+            constraints.width,
+            constraints.height,
+            format,
+        )
+        .inspect_err(|e| warn!(character = %preview.character, error = %e, "Failed to allocate capture buffer"))
+        .ok();
+    }
+
+    /// Requests the next frame of every shown preview and refreshes focus
+    fn tick(&mut self, state: &WaylandState) {
+        if let Some(xwayland) = &self.xwayland {
+            let focused = xwayland.focused_character(&self.config);
+            if focused != self.focused {
+                if let Some(name) = &focused {
+                    self.status_tx
+                        .send(DaemonMessage::FocusChanged(name.clone()));
+                }
+                self.focused = focused;
+                self.sync_visibility(state);
+                let ids: Vec<ObjectId> = self.previews.keys().cloned().collect();
+                for id in ids {
+                    self.redraw(&id);
+                }
+            }
+        }
+
+        for (id, preview) in self.previews.iter_mut() {
+            if preview.surface.is_none() {
+                continue;
+            }
+            match preview.capture.as_mut() {
+                Some(Capture::Toplevel(capture)) => {
+                    let (Some(buffer), None) = (&capture.buffer, &capture.frame) else {
+                        continue;
+                    };
+                    let frame = capture.session.create_frame(&self.qh, capture.session.id());
+                    frame.attach_buffer(buffer.buffer());
+                    frame.damage_buffer(0, 0, buffer.width as i32, buffer.height as i32);
+                    frame.capture();
+                    capture.frame = Some(frame);
+                }
+                Some(Capture::Region(capture)) => {
+                    let (CaptureGlobals::Region(screencopy), Some(xwayland)) =
+                        (&self.wl.capture, &self.xwayland)
+                    else {
+                        continue;
+                    };
+                    if capture.frame.is_some() {
+                        continue;
+                    }
+                    if capture.window.is_none() {
+                        capture.window = xwayland.window_of(&preview.character, &self.config);
+                    }
+                    let Some((position, dimensions)) =
+                        capture.window.and_then(|w| xwayland.geometry(w))
+                    else {
+                        capture.window = None;
+                        continue;
+                    };
+                    let (x, y) = (position.x.into(), position.y.into());
+                    let Some(output) = state.output_at(x, y) else {
+                        continue;
+                    };
+                    let scale = output.scale.max(1);
+                    let Some((x, y, width, height)) = clip_to_output(
+                        (x, y, dimensions.width.into(), dimensions.height.into()),
+                        (
+                            output.x,
+                            output.y,
+                            output.mode_width / scale,
+                            output.mode_height / scale,
+                        ),
+                    ) else {
+                        continue;
+                    };
+                    capture.frame = Some(screencopy.capture_output_region(
+                        0,
+                        &output.output,
+                        x,
+                        y,
+                        width,
+                        height,
+                        &self.qh,
+                        id.clone(),
+                    ));
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Copies a pending region frame into a buffer matching what the compositor asked for
+    fn region_buffer(&mut self, id: &ObjectId, constraints: CaptureConstraints) {
+        let Some(preview) = self.previews.get_mut(id) else {
+            return;
+        };
+        let Some(Capture::Region(capture)) = preview.capture.as_mut() else {
+            return;
+        };
+        let Some(frame) = capture.frame.take() else {
+            return;
+        };
+        let Some(format) = [Format::Argb8888, Format::Xrgb8888]
+            .into_iter()
+            .find(|f| constraints.formats.contains(f))
+        else {
+            warn!(character = %preview.character, formats = ?constraints.formats, "No supported shm format for region capture");
+            frame.destroy();
+            return;
+        };
+        if capture.buffer.as_ref().is_none_or(|b| {
+            b.width != constraints.width || b.height != constraints.height || b.format != format
+        }) {
+            match ShmBuffer::new(
+                &self.wl.shm,
+                &self.qh,
+                constraints.width,
+                constraints.height,
+                format,
+            ) {
+                Ok(buffer) => capture.buffer = Some(buffer),
+                Err(e) => {
+                    warn!(character = %preview.character, error = %e, "Failed to allocate capture buffer");
+                    frame.destroy();
+                    return;
+                }
+            }
+        }
+        if let Some(buffer) = &capture.buffer {
+            frame.copy(buffer.buffer());
+        }
+        capture.frame = Some(frame);
+    }
+
+    fn region_ready(&mut self, id: &ObjectId, y_invert: bool) {
+        let Some(preview) = self.previews.get_mut(id) else {
+            return;
+        };
+        let Some(Capture::Region(capture)) = preview.capture.as_mut() else {
+            return;
+        };
+        if let Some(frame) = capture.frame.take() {
+            frame.destroy();
+        }
+        let Some(buffer) = &capture.buffer else {
+            return;
+        };
+        let mut pixels = Vec::new();
+        if let Err(e) = buffer.read(&mut pixels) {
+            warn!(character = %preview.character, error = %e, "Failed to read captured region");
+            return;
+        }
+        let row = buffer.width as usize * 4;
+        if y_invert && row > 0 {
+            pixels = pixels.chunks_exact(row).rev().flatten().copied().collect();
+        }
+        preview.content = scale(
+            &pixels,
+            buffer.width as usize,
+            buffer.height as usize,
+            preview.dimensions,
+        );
+        self.redraw(id);
+    }
+
+    fn frame_ready(&mut self, session: &ObjectId) {
+        let Some(id) = self.preview_with_session(session) else {
+            return;
+        };
+        let Some(preview) = self.previews.get_mut(&id) else {
+            return;
+        };
+        let Some(Capture::Toplevel(capture)) = preview.capture.as_mut() else {
+            return;
+        };
+        if let Some(frame) = capture.frame.take() {
+            frame.destroy();
+        }
+        let Some(buffer) = &capture.buffer else {
+            return;
+        };
+        let mut pixels = Vec::new();
+        if let Err(e) = buffer.read(&mut pixels) {
+            warn!(character = %preview.character, error = %e, "Failed to read captured frame");
+            return;
+        }
+        preview.content = scale(
+            &pixels,
+            buffer.width as usize,
+            buffer.height as usize,
+            preview.dimensions,
+        );
+        self.redraw(&id);
+    }
+
+    /// Draws content, border and label into the preview's buffer and commits it
+    fn redraw(&mut self, id: &ObjectId) {
+        let Some(preview) = self.previews.get_mut(id) else {
+            return;
+        };
+        let Some(surface) = preview.surface.as_mut() else {
+            return;
+        };
+        if !surface.configured || surface.buffer.as_ref().is_some_and(|b| b.is_busy()) {
+            return;
+        }
+        let (width, height) = (
+            preview.dimensions.width as u32,
+            preview.dimensions.height as u32,
+        );
+        if width == 0 || height == 0 {
+            return;
+        }
+        if surface
+            .buffer
+            .as_ref()
+            .is_none_or(|b| b.width != width || b.height != height)
+        {
+            match ShmBuffer::new(&self.wl.shm, &self.qh, width, height, Format::Argb8888) {
+                Ok(buffer) => surface.buffer = Some(buffer),
+                Err(e) => {
+                    warn!(character = %preview.character, error = %e, "Failed to allocate preview buffer");
+                    return;
+                }
+            }
+        }
+
+        if preview.overlay.dimensions() != preview.dimensions
+            && let Err(e) = preview.overlay.resize(preview.dimensions)
+        {
+            warn!(character = %preview.character, error = %e, "Failed to resize preview overlay");
+            return;
+        }
+        let focused = self.focused.as_deref() == Some(preview.character.as_str());
+        let pixels = compose(
+            &preview.overlay,
+            &preview.character,
+            preview.dimensions,
+            &preview.content,
+            focused,
+            &self.display_config,
+            self.font_renderer.as_ref(),
+        );
+        let Some(buffer) = surface.buffer.as_ref() else {
+            return;
+        };
+        if let Err(e) = buffer.write(&pixels) {
+            warn!(character = %preview.character, error = %e, "Failed to update preview");
+            return;
+        }
+        surface.surface.attach(Some(buffer.buffer()), 0, 0);
+        surface
+            .surface
+            .damage_buffer(0, 0, width as i32, height as i32);
+        surface.surface.commit();
+    }
+
+    fn button(&mut self, button: u32, pressed: bool) {
+        let Some(id) = self.pointer.over.clone() else {
+            return;
+        };
+        match (button, pressed) {
+            (BTN_LEFT, true) => {
+                let Some(preview) = self.previews.get(&id) else {
+                    return;
+                };
+                match &self.xwayland {
+                    Some(xwayland) => xwayland.focus(&preview.character, &self.config),
+                    None => debug!(character = %preview.character, "Can't focus without XWayland"),
+                }
+            }
+            (BTN_RIGHT, true) => self.pointer.drag = Some((self.pointer.x, self.pointer.y)),
+            (BTN_RIGHT, false) if self.pointer.drag.take().is_some() => self.drag_end(&id),
+            _ => {}
+        }
+    }
+
+    fn drag_motion(&mut self, state: &WaylandState) {
+        let (Some((start_x, start_y)), Some(id)) = (self.pointer.drag, self.pointer.over.clone())
+        else {
+            return;
+        };
+        let (dx, dy) = (
+            (self.pointer.x - start_x) as i16,
+            (self.pointer.y - start_y) as i16,
+        );
+        if dx == 0 && dy == 0 {
+            return;
+        }
+        if let Some(preview) = self.previews.get_mut(&id) {
+            preview.position.x = preview.position.x.saturating_add(dx);
+            preview.position.y = preview.position.y.saturating_add(dy);
+        }
+        self.apply_geometry(&id, state);
+    }
+
+    /// Stores a dragged preview's position and reports it to the Manager
+    fn drag_end(&mut self, id: &ObjectId) {
+        let Some(preview) = self.previews.get(id) else {
+            return;
+        };
+        if let Some(settings) = self.config.character_thumbnails.get_mut(&preview.character) {
+            settings.x = preview.position.x;
+            settings.y = preview.position.y;
+        }
+        self.status_tx.send(DaemonMessage::PositionChanged {
+            name: preview.character.clone(),
+            x: preview.position.x,
+            y: preview.position.y,
+            width: preview.dimensions.width,
+            height: preview.dimensions.height,
+            is_custom: false,
+        });
+    }
+
+    fn handle_message(&mut self, message: ConfigMessage, state: &WaylandState) {
+        match message {
+            ConfigMessage::Full(config) => {
+                info!("Received full config update via IPC");
+                let runtime_hidden = self.config.runtime_hidden;
+                self.config = *config;
+                self.config.runtime_hidden = runtime_hidden;
+                self.display_config = self.config.build_display_config();
+                self.font_renderer = load_font(&self.config);
+
+                let ids: Vec<ObjectId> = self.previews.keys().cloned().collect();
+                for id in ids {
+                    let Some(character) = self.previews.get(&id).map(|p| p.character.clone())
+                    else {
+                        continue;
+                    };
+                    let settings = self.settings_for(&character);
+                    let Some(preview) = self.previews.get_mut(&id) else {
+                        continue;
+                    };
+                    preview.position = settings.position();
+                    preview.dimensions = settings.dimensions;
+                    self.apply_geometry(&id, state);
+                }
+                self.sync_visibility(state);
+            }
+            ConfigMessage::ThumbnailMove {
+                name,
+                is_custom: false,
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let id = self
+                    .previews
+                    .iter()
+                    .find(|(_, p)| p.character == name)
+                    .map(|(id, _)| id.clone());
+                if let Some(settings) = self.config.character_thumbnails.get_mut(&name) {
+                    settings.x = x;
+                    settings.y = y;
+                    settings.dimensions = Dimensions::new(width, height);
+                }
+                if let Some(id) = id
+                    && let Some(preview) = self.previews.get_mut(&id)
+                {
+                    let geometry = (Position::new(x, y), Dimensions::new(width, height));
+                    if (preview.position, preview.dimensions) != geometry {
+                        (preview.position, preview.dimensions) = geometry;
+                        self.apply_geometry(&id, state);
+                    }
+                }
+            }
+            ConfigMessage::Hello(hello) => match ProtocolHello::current().negotiate(&hello) {
+                Some(version) => info!(
+                    manager_version = %hello.app_version,
+                    negotiated = version,
+                    "Protocol handshake complete"
+                ),
+                None => warn!(
+                    manager_protocol = hello.protocol_version,
+                    "Manager protocol is incompatible"
+                ),
+            },
+            ConfigMessage::Ping(seq) => self.status_tx.send(DaemonMessage::Pong(seq)),
+            ConfigMessage::RequestClientLayout => {
+                let layout = self
+                    .previews
+                    .values()
+                    .map(|p| ClientGeometry {
+                        name: p.character.clone(),
+                        is_custom: false,
+                        x: p.position.x,
+                        y: p.position.y,
+                        width: p.dimensions.width,
+                        height: p.dimensions.height,
+                    })
+                    .collect();
+                self.status_tx.send(DaemonMessage::ClientLayout(layout));
+            }
+            ConfigMessage::TogglePreviews => {
+                self.config.runtime_hidden = !self.config.runtime_hidden;
+                self.sync_visibility(state);
+            }
+            ConfigMessage::SetPaused(paused) => {
+                self.paused = paused;
+                info!(paused = paused, "Pause state changed");
+                self.status_tx.send(DaemonMessage::PausedChanged(paused));
+                self.sync_visibility(state);
+            }
+            ConfigMessage::FocusCharacter(character) => {
+                if let Some(xwayland) = &self.xwayland {
+                    xwayland.focus(&character, &self.config);
+                }
+            }
+            ConfigMessage::TestHotkey(binding) => {
+                self.status_tx.send(DaemonMessage::HotkeyTested {
+                    binding,
+                    result: HotkeyTestResult::Failed(
+                        "The Wayland backend has no hotkeys".to_string(),
+                    ),
+                });
+            }
+            other => debug!(message = ?other, "Message not supported by the Wayland backend"),
+        }
+    }
+}
+
+/// Configured font, then the best system font; without any, previews go unlabelled
+fn load_font(config: &DaemonConfig) -> Option<FontRenderer> {
+    profile_font(
+        &config.profile.thumbnail_text_font,
+        config.profile.thumbnail_text_size as f32,
+    )
+}
+
+/// Part of `rect` on `output` (both `(x, y, width, height)` in compositor coordinates),
+/// relative to the output's origin
+fn clip_to_output(
+    rect: (i32, i32, i32, i32),
+    output: (i32, i32, i32, i32),
+) -> Option<(i32, i32, i32, i32)> {
+    let left = rect.0.max(output.0);
+    let top = rect.1.max(output.1);
+    let right = (rect.0 + rect.2).min(output.0 + output.2);
+    let bottom = (rect.1 + rect.3).min(output.1 + output.3);
+    (right > left && bottom > top)
+        .then(|| (left - output.0, top - output.1, right - left, bottom - top))
+}
+
+/// Nearest-neighbour scale of a BGRA frame to `dimensions`
+fn scale(pixels: &[u8], width: usize, height: usize, dimensions: Dimensions) -> Vec<u8> {
+    let (out_w, out_h) = (dimensions.width as usize, dimensions.height as usize);
+    let mut out = vec![0u8; out_w * out_h * 4];
+    if width == 0 || height == 0 || pixels.len() < width * height * 4 {
+        return out;
+    }
+    for y in 0..out_h {
+        let src_row = (y * height / out_h) * width;
+        for x in 0..out_w {
+            let src = (src_row + x * width / out_w) * 4;
+            let dst = (y * out_w + x) * 4;
+            out[dst..dst + 3].copy_from_slice(&pixels[src..src + 3]);
+            // Captures are opaque; Xrgb8888 leaves the alpha byte undefined
+            out[dst + 3] = 0xFF;
+        }
+    }
+    out
+}
+
+/// Preview pixels: content at the configured opacity, then the overlay (border and label)
+fn compose(
+    overlay: &SoftwareOverlay,
+    character: &str,
+    dimensions: Dimensions,
+    content: &[u8],
+    focused: bool,
+    config: &DisplayConfig,
+    font_renderer: Option<&FontRenderer>,
+) -> Vec<u8> {
+    let (width, height) = (dimensions.width as usize, dimensions.height as usize);
+    let mut pixels = if content.len() == width * height * 4 {
+        content.to_vec()
+    } else {
+        // Nothing captured yet
+        [0, 0, 0, 0xFF].repeat(width * height)
+    };
+
+    let alpha = config.opacity >> 24;
+    if alpha < 0xFF {
+        for channel in pixels.iter_mut() {
+            *channel = (*channel as u32 * alpha / 0xFF) as u8;
+        }
+    }
+
+    let state = BorderState::of(focused, false);
+    if let Err(e) = overlay
+        .draw(config, character, state, false, font_renderer)
+        .and_then(|()| overlay.composite(&mut pixels, dimensions))
+    {
+        warn!(character = %character, error = %e, "Failed to draw preview overlay");
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_nearest_neighbour() {
+        // 2x1 frame: blue pixel, then red pixel (BGRA)
+        let frame = [0xFF, 0, 0, 0, 0, 0, 0xFF, 0];
+        let scaled = scale(&frame, 2, 1, Dimensions::new(4, 1));
+        assert_eq!(
+            scaled,
+            [
+                0xFF, 0, 0, 0xFF, 0xFF, 0, 0, 0xFF, 0, 0, 0xFF, 0xFF, 0, 0, 0xFF, 0xFF
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clip_to_output() {
+        let output = (1920, 0, 1920, 1080);
+        assert_eq!(
+            clip_to_output((2000, 100, 800, 600), output),
+            Some((80, 100, 800, 600))
+        );
+        // Straddling the left edge keeps only the part on this output
+        assert_eq!(
+            clip_to_output((1800, -50, 400, 300), output),
+            Some((0, 0, 280, 250))
+        );
+        assert_eq!(clip_to_output((0, 0, 800, 600), output), None);
+    }
+}
@@ -1,21 +1,64 @@
 //! EVE window detection and thumbnail creation logic
 
 use anyhow::{Context, Result};
-use tracing::debug;
+use tracing::{debug, warn};
 use x11rb::connection::Connection;
 use x11rb::protocol::xproto::*;
 
 use crate::common::constants;
-use crate::common::types::Dimensions;
+use crate::common::ipc::{DaemonMessage, WindowMatch};
+use crate::common::types::{ClientDetectionRule, Dimensions, Position};
 use crate::config::DaemonConfig;
 use crate::config::DisplayConfig;
-use crate::config::profile::CustomWindowRule;
-use crate::x11::{AppContext, get_window_class, is_window_eve, is_window_minimized};
+use crate::config::profile::{CustomWindowRule, LayoutSlot, Profile, SourceGeometry};
+use crate::x11::{
+    AppContext, get_client_list, get_window_class, is_window_eve, is_window_minimized,
+};
 use std::collections::HashMap;
 
 use super::session_state::SessionState;
 use super::thumbnail::Thumbnail;
 
+/// Events the daemon selects on every tracked client window
+fn client_event_mask() -> EventMask {
+    EventMask::PROPERTY_CHANGE | EventMask::FOCUS_CHANGE | EventMask::STRUCTURE_NOTIFY
+}
+
+/// Re-applies the client event mask on tracked windows that lost part of it (a title probe on
+/// a window that no longer matches, or a client resetting its attributes). Without it borders
+/// and name updates silently stop. Returns how many windows were repaired.
+pub fn audit_event_masks(ctx: &AppContext, windows: &[Window]) -> Result<usize> {
+    // Pipeline the queries, then check the replies
+    let cookies = windows
+        .iter()
+        .map(|&window| (window, ctx.conn.get_window_attributes(window)))
+        .collect::<Vec<_>>();
+
+    let mut repaired = 0;
+    for (window, cookie) in cookies {
+        let Some(attrs) = cookie.ok().and_then(|c| c.reply().ok()) else {
+            // Destroyed in the meantime; DestroyNotify cleans it up
+            continue;
+        };
+        if attrs.your_event_mask.contains(client_event_mask()) {
+            continue;
+        }
+        warn!(
+            window = window,
+            mask = ?attrs.your_event_mask,
+            "Tracked window lost its event mask, re-applying"
+        );
+        ctx.conn
+            .change_window_attributes(
+                window,
+                &ChangeWindowAttributesAux::new().event_mask(client_event_mask()),
+            )
+            .context(format!("Failed to restore event mask on window {}", window))?;
+        repaired += 1;
+    }
+    Ok(repaired)
+}
+
 /// Check if a window is an EVE client and return its character name
 /// Returns Some(character_name) for EVE windows, None for non-EVE windows
 #[derive(Debug, Clone)]
@@ -30,10 +73,22 @@ pub fn identify_window(
     ctx: &AppContext,
     window: Window,
     state: &mut SessionState,
-    custom_rules: &[CustomWindowRule],
+    profile: &Profile,
 ) -> Result<Option<WindowIdentity>> {
+    // Windows the user marked _EPM_IGNORE stay untracked; watch for the mark going away
+    if crate::x11::is_window_ignored(ctx.conn, window, ctx.atoms)? {
+        ctx.conn.change_window_attributes(
+            window,
+            &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )?;
+        debug!(window = window, "Window marked _EPM_IGNORE, not tracking");
+        return Ok(None);
+    }
+
     // Check for EVE Client identity first (Standard/Steam/Wine) using robust detection
-    if let Some(eve_window) = check_eve_window_internal(ctx, window, state)? {
+    if let Some(eve_window) =
+        check_eve_window_internal(ctx, window, state, &profile.client_detection_rules)?
+    {
         let name = eve_window;
         return Ok(Some(WindowIdentity {
             name,
@@ -43,15 +98,39 @@ pub fn identify_window(
     }
 
     // 2. Check Custom Rules
-    // Get window properties once to avoid repeated round-trips
-    let wm_name_cookie =
-        ctx.conn
-            .get_property(false, window, ctx.atoms.wm_name, AtomEnum::STRING, 0, 1024)?;
-
     let wm_class = get_window_class(ctx.conn, window, ctx.atoms)
         .ok()
         .flatten()
         .unwrap_or_default();
+    let wm_name = window_title(ctx, window)?;
+
+    for rule in &profile.custom_windows {
+        if rule.matches(&wm_name, &wm_class) {
+            debug!(
+                window = window,
+                alias = %rule.alias,
+                title = %wm_name,
+                class = %wm_class,
+                "Identified Custom Source"
+            );
+            state.update_last_character(window, &rule.alias);
+            return Ok(Some(WindowIdentity {
+                name: rule.alias.clone(),
+                is_eve: false,
+                rule: Some(rule.clone()),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Title of a window for custom rule matching (WM_NAME, then _NET_WM_NAME, then
+/// _NET_WM_VISIBLE_NAME)
+fn window_title(ctx: &AppContext, window: Window) -> Result<String> {
+    let wm_name_cookie =
+        ctx.conn
+            .get_property(false, window, ctx.atoms.wm_name, AtomEnum::STRING, 0, 1024)?;
 
     // Get WM_NAME (Legacy)
     let wm_name_legacy = if let Ok(reply) = wm_name_cookie.reply() {
@@ -69,14 +148,16 @@ pub fn identify_window(
     //
     // SAFETY: We use AtomEnum::ANY to accept any property type (UTF8_STRING, STRING, COMPOUND_TEXT).
     // Restricting to UTF8_STRING caused false negatives for valid windows.
-    let wm_name = if !wm_name_legacy.is_empty() {
-        wm_name_legacy.clone()
-    } else {
-        // Try _NET_WM_NAME (Any Type)
-        let net_name = if let Ok(cookie) = ctx.conn.get_property(
+    if !wm_name_legacy.is_empty() {
+        return Ok(wm_name_legacy);
+    }
+
+    // Try _NET_WM_NAME, then _NET_WM_VISIBLE_NAME (Any Type)
+    for atom in [ctx.atoms.net_wm_name, ctx.atoms.net_wm_visible_name] {
+        let name = if let Ok(cookie) = ctx.conn.get_property(
             false,
             window,
-            ctx.atoms.net_wm_name,
+            atom,
             AtomEnum::ANY, // Accept any type (UTF8_STRING, STRING, COMPOUND_TEXT)
             0,
             1024,
@@ -89,81 +170,37 @@ pub fn identify_window(
         } else {
             String::new()
         };
-
-        if !net_name.is_empty() {
-            net_name
-        } else {
-            // Try _NET_WM_VISIBLE_NAME (Any Type)
-            if let Ok(cookie) = ctx.conn.get_property(
-                false,
-                window,
-                ctx.atoms.net_wm_visible_name,
-                AtomEnum::ANY,
-                0,
-                1024,
-            ) {
-                cookie
-                    .reply()
-                    .ok()
-                    .map(|r| String::from_utf8_lossy(&r.value).to_string())
-                    .unwrap_or_default()
-            } else {
-                String::new()
-            }
-        }
-    };
-
-    for rule in custom_rules {
-        // Validation: If a pattern (title/class) is defined in the rule,
-        // it acts as a strict filter that MUST match the window.
-        let matches_title = rule
-            .title_pattern
-            .as_ref()
-            .map(|p| wm_name.to_lowercase().contains(&p.to_lowercase()))
-            .unwrap_or(false);
-
-        let matches_class = rule
-            .class_pattern
-            .as_ref()
-            .map(|p| wm_class.to_lowercase().contains(&p.to_lowercase()))
-            .unwrap_or(false); // If rule has class pattern, it MUST match
-
-        // Logic: Rule matches if...
-        // - Title defined AND matches (AND Class is None OR matches)
-        // - Class defined AND matches (AND Title is None OR matches)
-        // Essentially, whatever criteria are defined must be satisfied.
-
-        let mut matched = true;
-
-        if rule.title_pattern.is_some() && !matches_title {
-            matched = false;
-        }
-        if rule.class_pattern.is_some() && !matches_class {
-            matched = false;
-        }
-        // If neither is defined, it's a catch-all? No, Manager enforces at least one.
-        if rule.title_pattern.is_none() && rule.class_pattern.is_none() {
-            matched = false;
+        if !name.is_empty() {
+            return Ok(name);
         }
+    }
+    Ok(String::new())
+}
 
-        if matched {
-            debug!(
-                window = window,
-                alias = %rule.alias,
-                title = %wm_name,
-                class = %wm_class,
-                "Identified Custom Source"
-            );
-            state.update_last_character(window, &rule.alias);
-            return Ok(Some(WindowIdentity {
-                name: rule.alias.clone(),
-                is_eve: false,
-                rule: Some(rule.clone()),
-            }));
+/// Managed windows a custom rule with these patterns would match (for live rule previews)
+pub fn matching_windows(
+    ctx: &AppContext,
+    title_pattern: Option<&str>,
+    class_pattern: Option<&str>,
+) -> Result<Vec<WindowMatch>> {
+    let mut matches = Vec::new();
+    for window in get_client_list(ctx.conn, ctx.atoms)? {
+        let class = get_window_class(ctx.conn, window, ctx.atoms)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let title = window_title(ctx, window).unwrap_or_default();
+        if CustomWindowRule::pattern_mismatch(title_pattern, class_pattern, &title, &class)
+            .is_none()
+        {
+            matches.push(WindowMatch {
+                window,
+                title,
+                class,
+            });
         }
     }
-
-    Ok(None)
+    Ok(matches)
 }
 
 /// Internal helper to check EVE specifics (extracted from original check_eve_window)
@@ -171,6 +208,7 @@ fn check_eve_window_internal(
     ctx: &AppContext,
     window: Window,
     state: &mut SessionState,
+    rules: &[ClientDetectionRule],
 ) -> Result<Option<String>> {
     // 1. Get PID (Optimization to skip own windows)
     let pid_atom = ctx.atoms.net_wm_pid;
@@ -204,7 +242,7 @@ fn check_eve_window_internal(
         &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
     )?;
 
-    if let Some(eve_window) = is_window_eve(ctx.conn, window, ctx.atoms)? {
+    if let Some(eve_window) = is_window_eve(ctx.conn, window, ctx.atoms, rules)? {
         let character_name = eve_window.character_name().to_string();
 
         debug!(
@@ -213,12 +251,23 @@ fn check_eve_window_internal(
             "Confirmed EVE Client"
         );
         state.update_last_character(window, &character_name);
+        if let Err(e) =
+            crate::x11::set_character_marker(ctx.conn, ctx.atoms, window, &character_name)
+        {
+            warn!(window = window, error = %e, "Failed to publish character marker");
+        }
+
+        if let Some(pid) = pid
+            && !state.window_prefix.contains_key(&window)
+            && let Some(info) = super::wine_prefix::detect(pid)
+        {
+            debug!(window = window, prefix = ?info.prefix, proton = ?info.proton, server = ?info.server, "Detected Wine prefix");
+            state.window_prefix.insert(window, info);
+        }
 
         ctx.conn.change_window_attributes(
             window,
-            &ChangeWindowAttributesAux::new().event_mask(
-                EventMask::PROPERTY_CHANGE | EventMask::FOCUS_CHANGE | EventMask::STRUCTURE_NOTIFY,
-            ),
+            &ChangeWindowAttributesAux::new().event_mask(client_event_mask()),
         )?;
 
         Ok(Some(character_name))
@@ -242,7 +291,7 @@ pub fn check_and_create_window<'a>(
     let identity = if let Some(id) = known_identity {
         id
     } else {
-        match identify_window(ctx, window, state, &daemon_config.profile.custom_windows)? {
+        match identify_window(ctx, window, state, &daemon_config.profile)? {
             Some(id) => id,
             None => return Ok(None),
         }
@@ -267,9 +316,7 @@ pub fn check_and_create_window<'a>(
         // - STRUCTURE_NOTIFY: To detect destruction/unmapping
         ctx.conn.change_window_attributes(
             window,
-            &ChangeWindowAttributesAux::new().event_mask(
-                EventMask::PROPERTY_CHANGE | EventMask::FOCUS_CHANGE | EventMask::STRUCTURE_NOTIFY,
-            ),
+            &ChangeWindowAttributesAux::new().event_mask(client_event_mask()),
         )?;
 
         // Gather info for filtering and logging
@@ -363,6 +410,16 @@ pub fn check_and_create_window<'a>(
 
     let character_name = identity.name;
 
+    // Each instance of a custom source keeps its own geometry, keyed by rule id
+    let source_instance = identity.rule.as_ref().map(|rule| {
+        (
+            rule.id,
+            source_instance_index(existing_thumbnails, window, rule.id),
+        )
+    });
+    let saved_geometry = source_instance
+        .and_then(|(rule_id, instance)| daemon_config.source_geometry(rule_id, instance));
+
     // Get saved position and dimensions
     // Determine which map to query based on identity type
     let settings_map = if identity.is_eve {
@@ -380,7 +437,12 @@ pub fn check_and_create_window<'a>(
     // Priority 1: Runtime Settings (active session changes)
     // Priority 2: Profile Settings (saved on disk)
     // Priority 3: Inheritance / Session State
-    let position = if let Some(settings) = settings_map.get(&character_name) {
+    let position = if let Some(slot) = saved_geometry {
+        Some(Position::new(slot.x, slot.y))
+    } else if source_instance.is_some_and(|(_, instance)| instance > 0) {
+        // Further instances spawn next to their window rather than on top of the first
+        None
+    } else if let Some(settings) = settings_map.get(&character_name) {
         Some(settings.position())
     } else if let Some(settings) = profile_map.get(&character_name) {
         Some(settings.position())
@@ -418,9 +480,7 @@ pub fn check_and_create_window<'a>(
             Dimensions::new(rule.default_width, rule.default_height)
         } else if settings.dimensions.width == 0 || settings.dimensions.height == 0 {
             // Auto-detect EVE default if saved dims are invalid
-            let (w, h) = daemon_config
-                .default_thumbnail_size(ctx.screen.width_in_pixels, ctx.screen.height_in_pixels);
-            Dimensions::new(w, h)
+            default_dimensions(ctx, daemon_config, window, position)
         } else {
             settings.dimensions
         };
@@ -443,15 +503,17 @@ pub fn check_and_create_window<'a>(
             )
         } else {
             // Auto-detect EVE default
-            let (w, h) = daemon_config
-                .default_thumbnail_size(ctx.screen.width_in_pixels, ctx.screen.height_in_pixels);
             (
-                Dimensions::new(w, h),
+                default_dimensions(ctx, daemon_config, window, position),
                 crate::common::types::PreviewMode::default(),
             )
         }
     };
 
+    let dimensions = saved_geometry
+        .filter(|slot| slot.width > 0 && slot.height > 0)
+        .map_or(dimensions, |slot| Dimensions::new(slot.width, slot.height));
+
     let mut thumbnail = Thumbnail::new(
         ctx,
         character_name.clone(),
@@ -466,6 +528,10 @@ pub fn check_and_create_window<'a>(
         "Failed to create thumbnail for '{}' (window {})",
         character_name, window
     ))?;
+    thumbnail.set_custom_source(!identity.is_eve);
+    thumbnail.source_instance = source_instance;
+    let server = state.window_server(window);
+    thumbnail.set_server(server);
 
     // Check minimized state
     // Check minimized state
@@ -473,6 +539,9 @@ pub fn check_and_create_window<'a>(
 
     if is_minimized {
         thumbnail.minimized(display_config, font_renderer)?;
+    } else if server.is_test_server() {
+        // The initial overlay was drawn before the server was known; redraw badge and border
+        thumbnail.border(display_config, false, false, font_renderer)?;
     } else {
         // NOTE: We rely on standard X11 Damage events to trigger the first update naturally.
         // Forcing an update here caused issues with fleeting windows.
@@ -487,6 +556,87 @@ pub fn check_and_create_window<'a>(
     Ok(Some(thumbnail))
 }
 
+/// Default thumbnail size for the monitor the thumbnail lands on: the one holding its saved
+/// position, else the one holding the client's center (where new thumbnails spawn)
+fn default_dimensions(
+    ctx: &AppContext,
+    daemon_config: &DaemonConfig,
+    window: Window,
+    position: Option<Position>,
+) -> Dimensions {
+    let point = position.map(|p| (p.x, p.y)).or_else(|| {
+        crate::x11::get_window_geometry(ctx.conn, ctx.screen.root, window)
+            .ok()
+            .map(|(origin, size)| {
+                (
+                    origin.x.saturating_add((size.width / 2) as i16),
+                    origin.y.saturating_add((size.height / 2) as i16),
+                )
+            })
+    });
+    let monitor = point.and_then(|(x, y)| ctx.monitors.at(x, y));
+    let name = monitor.and_then(|m| ctx.monitors.name(&m));
+    let dpi_scale = monitor.map_or(1.0, |m| m.dpi_scale());
+    let (width, height) = daemon_config.default_thumbnail_size(name.as_deref(), dpi_scale);
+    Dimensions::new(width, height)
+}
+
+/// Instance number for a window of custom source `rule_id`: the one it already had, else the
+/// lowest not taken by another window of the same rule
+fn source_instance_index(
+    existing_thumbnails: &HashMap<Window, Thumbnail>,
+    window: Window,
+    rule_id: u32,
+) -> u32 {
+    if let Some((id, instance)) = existing_thumbnails
+        .get(&window)
+        .and_then(|t| t.source_instance)
+        && id == rule_id
+    {
+        return instance;
+    }
+    (0..)
+        .find(|&instance| {
+            !existing_thumbnails
+                .values()
+                .any(|t| t.source_instance == Some((rule_id, instance)))
+        })
+        .expect("unbounded instance search always finds a free index")
+}
+
+/// Records the geometry of a custom source instance at `(x, y)`; returns the message that
+/// reports it to the Manager when it changed (None for EVE clients)
+pub fn record_source_geometry(
+    daemon_config: &mut DaemonConfig,
+    thumbnail: &Thumbnail,
+    x: i16,
+    y: i16,
+) -> Option<DaemonMessage> {
+    let (rule_id, instance) = thumbnail.source_instance?;
+    let slot = LayoutSlot {
+        x,
+        y,
+        width: thumbnail.dimensions.width,
+        height: thumbnail.dimensions.height,
+    };
+    if !SourceGeometry::upsert(
+        &mut daemon_config.custom_source_geometry,
+        rule_id,
+        instance,
+        slot,
+    ) {
+        return None;
+    }
+    Some(DaemonMessage::SourceGeometryChanged {
+        rule_id,
+        instance,
+        x,
+        y,
+        width: slot.width,
+        height: slot.height,
+    })
+}
+
 /// Initial scan for existing EVE windows to populate thumbnails
 use super::cycle_state::CycleState;
 
@@ -510,7 +660,7 @@ pub fn scan_eve_windows<'a>(
     for w in windows {
         // 1. Identify valid windows (EVE or Custom Source)
         // We use identify_window directly so we can track them even if no thumbnail is created
-        let identity = match identify_window(ctx, w, state, &daemon_config.profile.custom_windows) {
+        let identity = match identify_window(ctx, w, state, &daemon_config.profile) {
             Ok(Some(id)) => id,
             Ok(None) => continue, // Not a relevant window
             Err(e) => {
@@ -521,6 +671,11 @@ pub fn scan_eve_windows<'a>(
 
         // Register identified window with CycleState
         cycle_state.add_window(identity.name.clone(), w);
+        cycle_state.set_window_excluded(
+            w,
+            daemon_config.profile.hotkey_cycle_exclude_test_servers
+                && state.window_server(w).is_test_server(),
+        );
 
         // 2. Try to create thumbnail
         match check_and_create_window(
@@ -535,69 +690,46 @@ pub fn scan_eve_windows<'a>(
         ) {
             Ok(Some(eve)) => {
                 // Save initial position and dimensions (important for first-time characters)
-                // Query geometry to get actual position from X11
-                // We handle geometry query errors safely too, just in case
-                let geom_result = ctx
-                    .conn
-                    .get_geometry(eve.window())
-                    .map_err(anyhow::Error::from)
-                    .and_then(|cookie| cookie.reply().map_err(anyhow::Error::from));
-
-                match geom_result {
-                    Ok(geom) => {
-                        // Update character_thumbnails in memory (skip logged-out clients with empty name)
-                        if !eve.character_name.is_empty() {
-                            let settings = crate::common::types::CharacterSettings::new(
-                                geom.x,
-                                geom.y,
-                                eve.dimensions.width,
-                                eve.dimensions.height,
-                            );
-
-                            // Route settings to the correct map based on whether this alias matches a Custom Rule.
-                            // This ensures separation even if originally detected as a generic client.
-                            let is_custom_alias = daemon_config
-                                .profile
-                                .custom_windows
-                                .iter()
-                                .any(|r| r.alias == eve.character_name);
-
-                            if is_custom_alias {
-                                // NOTE: specific check to preserve existing overrides (like preview_mode)
-                                // if they were already loaded from the profile config key.
-                                if let Some(existing) = daemon_config
-                                    .custom_source_thumbnails
-                                    .get_mut(&eve.character_name)
-                                {
-                                    existing.x = settings.x;
-                                    existing.y = settings.y;
-                                    existing.dimensions = settings.dimensions;
-                                } else {
-                                    daemon_config
-                                        .custom_source_thumbnails
-                                        .insert(eve.character_name.clone(), settings);
-                                }
-                            } else if let Some(existing) = daemon_config
-                                .character_thumbnails
-                                .get_mut(&eve.character_name)
-                            {
-                                existing.x = settings.x;
-                                existing.y = settings.y;
-                                existing.dimensions = settings.dimensions;
-                            } else {
-                                daemon_config
-                                    .character_thumbnails
-                                    .insert(eve.character_name.clone(), settings);
-                            }
+                let position = eve.current_position;
+                // Update character_thumbnails in memory (skip logged-out clients with empty name)
+                if !eve.character_name.is_empty() {
+                    let settings = crate::common::types::CharacterSettings::new(
+                        position.x,
+                        position.y,
+                        eve.dimensions.width,
+                        eve.dimensions.height,
+                    );
+
+                    record_source_geometry(daemon_config, &eve, position.x, position.y);
+
+                    // Route settings to the map of the thumbnail's own kind, so a source
+                    // never overwrites a character that happens to share its name
+                    if eve.is_custom {
+                        // NOTE: specific check to preserve existing overrides (like preview_mode)
+                        // if they were already loaded from the profile config key.
+                        if let Some(existing) = daemon_config
+                            .custom_source_thumbnails
+                            .get_mut(&eve.character_name)
+                        {
+                            existing.x = settings.x;
+                            existing.y = settings.y;
+                            existing.dimensions = settings.dimensions;
+                        } else {
+                            daemon_config
+                                .custom_source_thumbnails
+                                .insert(eve.character_name.clone(), settings);
                         }
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to query geometry for new thumbnail window {}: {}",
-                            eve.window(),
-                            e
-                        );
-                        // Continue anyway, we just won't update the saved position
+                    } else if let Some(existing) = daemon_config
+                        .character_thumbnails
+                        .get_mut(&eve.character_name)
+                    {
+                        existing.x = settings.x;
+                        existing.y = settings.y;
+                        existing.dimensions = settings.dimensions;
+                    } else {
+                        daemon_config
+                            .character_thumbnails
+                            .insert(eve.character_name.clone(), settings);
                     }
                 }
 
@@ -0,0 +1,136 @@
+//! Wine prefix detection for EVE clients
+//!
+//! Reads the client's process environment to find out which Wine prefix, Proton build and
+//! Steam library it was started from, and which server it connects to. Proton exports
+//! `STEAM_COMPAT_*` variables alongside `WINEPREFIX`; plain Wine launches usually only set
+//! `WINEPREFIX` (or nothing, meaning `~/.wine`).
+
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::debug;
+
+use crate::common::ipc::WinePrefixInfo;
+use crate::common::types::EveServer;
+
+/// Detect the Wine environment of process `pid`. Returns None for non-Wine processes or if
+/// the environment cannot be read (e.g. the process belongs to another user).
+pub fn detect(pid: u32) -> Option<WinePrefixInfo> {
+    let environ = std::fs::read(format!("/proc/{}/environ", pid))
+        .map_err(|e| debug!(pid = pid, error = %e, "Cannot read process environment"))
+        .ok()?;
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    from_process(&environ, &cmdline)
+}
+
+/// Parse NUL-separated `/proc/<pid>/environ` and `/proc/<pid>/cmdline` blobs
+fn from_process(environ: &[u8], cmdline: &[u8]) -> Option<WinePrefixInfo> {
+    let vars: HashMap<&str, &str> = nul_separated(environ)
+        .filter_map(|kv| kv.split_once('='))
+        .collect();
+
+    let compat_data = vars
+        .get("STEAM_COMPAT_DATA_PATH")
+        .map(|d| d.trim_end_matches('/'));
+    let is_wine = compat_data.is_some() || vars.keys().any(|k| k.starts_with("WINE"));
+    if !is_wine {
+        return None;
+    }
+
+    let prefix = vars
+        .get("WINEPREFIX")
+        .map(|p| p.trim_end_matches('/').to_string())
+        .or_else(|| compat_data.map(|d| format!("{}/pfx", d)))
+        .or_else(|| vars.get("HOME").map(|home| format!("{}/.wine", home)));
+
+    let proton = vars.get("STEAM_COMPAT_TOOL_PATHS").and_then(|paths| {
+        paths.split(':').find_map(|path| {
+            let name = Path::new(path).file_name()?.to_str()?;
+            name.to_lowercase()
+                .contains("proton")
+                .then(|| name.to_string())
+        })
+    });
+
+    let steam_library = compat_data
+        .and_then(|d| d.split_once("/steamapps/"))
+        .map(|(library, _)| library.to_string());
+
+    // Explicit launch arguments win over naming conventions of the install location
+    let server = EveServer::from_launch_args(nul_separated(cmdline))
+        .or_else(|| {
+            vars.get("STEAM_COMPAT_INSTALL_PATH")
+                .and_then(|p| EveServer::from_path(p))
+        })
+        .or_else(|| prefix.as_deref().and_then(EveServer::from_path))
+        .unwrap_or_default();
+
+    Some(WinePrefixInfo {
+        prefix,
+        proton,
+        steam_library,
+        server,
+    })
+}
+
+fn nul_separated(bytes: &[u8]) -> impl Iterator<Item = &str> {
+    bytes
+        .split(|b| *b == 0)
+        .filter_map(|s| std::str::from_utf8(s).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environ(vars: &[&str]) -> Vec<u8> {
+        vars.join("\0").into_bytes()
+    }
+
+    #[test]
+    fn test_proton_environment() {
+        let info = from_process(&environ(&[
+            "HOME=/home/pilot",
+            "STEAM_COMPAT_DATA_PATH=/mnt/games/SteamLibrary/steamapps/compatdata/8500/",
+            "STEAM_COMPAT_TOOL_PATHS=/mnt/games/SteamLibrary/steamapps/common/Proton 9.0 (Beta):/x/SteamLinuxRuntime_sniper",
+        ]), b"")
+        .unwrap();
+
+        assert_eq!(
+            info.prefix.as_deref(),
+            Some("/mnt/games/SteamLibrary/steamapps/compatdata/8500/pfx")
+        );
+        assert_eq!(info.proton.as_deref(), Some("Proton 9.0 (Beta)"));
+        assert_eq!(
+            info.steam_library.as_deref(),
+            Some("/mnt/games/SteamLibrary")
+        );
+        assert_eq!(info.server, EveServer::Tranquility);
+    }
+
+    #[test]
+    fn test_plain_wine_environment() {
+        let info = from_process(
+            &environ(&["HOME=/home/pilot", "WINEPREFIX=/home/pilot/eve-sisi/"]),
+            b"",
+        )
+        .unwrap();
+        assert_eq!(info.prefix.as_deref(), Some("/home/pilot/eve-sisi"));
+        assert_eq!(info.proton, None);
+        assert_eq!(info.server, EveServer::Singularity);
+
+        // Wine without WINEPREFIX uses the default prefix
+        let cmdline = environ(&["C:\\EVE\\bin64\\exefile.exe", "/server:thunderdome"]);
+        let info =
+            from_process(&environ(&["HOME=/home/pilot", "WINEDEBUG=-all"]), &cmdline).unwrap();
+        assert_eq!(info.prefix.as_deref(), Some("/home/pilot/.wine"));
+        assert_eq!(info.server, EveServer::Thunderdome);
+    }
+
+    #[test]
+    fn test_native_process_is_not_wine() {
+        assert_eq!(
+            from_process(&environ(&["HOME=/home/pilot", "PATH=/usr/bin"]), b""),
+            None
+        );
+    }
+}
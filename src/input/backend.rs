@@ -4,6 +4,7 @@
 //! Currently supports:
 //! - X11 XGrabKey (default, secure, no permissions)
 //! - evdev raw input (optional, requires input group)
+//! - xdg-desktop-portal GlobalShortcuts (Wayland compositors)
 
 use anyhow::Result;
 use std::thread::JoinHandle;
@@ -31,18 +32,70 @@ pub struct BackendCapabilities {
 }
 
 /// Configuration for hotkey bindings
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct HotkeyConfiguration {
     pub cycle_hotkeys: Vec<(crate::input::listener::CycleCommand, HotkeyBinding)>,
     pub character_hotkeys: Vec<HotkeyBinding>,
     pub profile_hotkeys: Vec<HotkeyBinding>,
     pub toggle_skip_key: Option<HotkeyBinding>,
     pub toggle_previews_key: Option<HotkeyBinding>,
+    pub overview_key: Option<HotkeyBinding>,
+    pub flip_key: Option<HotkeyBinding>,
+    pub peek_key: Option<HotkeyBinding>,
+    pub reveal_key: Option<HotkeyBinding>,
+    pub panic_key: Option<HotkeyBinding>,
+    pub pause_key: Option<HotkeyBinding>,
+    pub suspend_key: Option<HotkeyBinding>,
+    /// WM_CLASSes that disable hotkeys while focused (`Profile::hotkey_blocked_classes`)
+    pub blocked_classes: Vec<String>,
+}
+
+impl HotkeyConfiguration {
+    /// Every configured binding: cycle, character and profile hotkeys and the single keys.
+    /// Destructures `self` so a newly added hotkey can't be left out.
+    pub fn bindings(&self) -> impl Iterator<Item = &HotkeyBinding> {
+        let Self {
+            cycle_hotkeys,
+            character_hotkeys,
+            profile_hotkeys,
+            toggle_skip_key,
+            toggle_previews_key,
+            overview_key,
+            flip_key,
+            peek_key,
+            reveal_key,
+            panic_key,
+            pause_key,
+            suspend_key,
+            blocked_classes: _,
+        } = self;
+        let single_keys = [
+            toggle_skip_key,
+            toggle_previews_key,
+            overview_key,
+            flip_key,
+            peek_key,
+            reveal_key,
+            panic_key,
+            pause_key,
+            suspend_key,
+        ];
+        cycle_hotkeys
+            .iter()
+            .map(|(_, binding)| binding)
+            .chain(character_hotkeys)
+            .chain(profile_hotkeys)
+            .chain(single_keys.into_iter().flatten())
+    }
 }
 
 /// Thread-safe set of allowed active window IDs (tracked clients)
 pub type AllowedWindows = std::sync::Arc<std::sync::RwLock<std::collections::HashSet<u32>>>;
 
+/// Set while the daemon is paused or hotkeys are suspended; backends then release every hotkey
+/// except the pause and suspend keys (see `CycleCommand::works_while_paused`)
+pub type PausedFlag = std::sync::Arc<std::sync::atomic::AtomicBool>;
+
 /// Hotkey backend trait
 ///
 /// Each backend must implement this trait to be used by the daemon
@@ -53,8 +106,9 @@ pub trait HotkeyBackend: Sized {
     /// * `sender` - Channel to send detected hotkey commands to the main loop
     /// * `config` - Hotkey binding configuration
     /// * `device_id` - Optional specific input device to listen on (backend specific)
-    /// * `require_eve_focus` - If true, backend should only trigger when EVE is focused (optimization)
+    /// * `require_eve_focus` - If true, backend should only trigger when an allowed window is focused (optimization)
     /// * `allowed_windows` - Shared set of allowed active window IDs (tracked clients)
+    /// * `paused` - Shared pause state of the daemon
     ///
     /// Returns handles to spawned threads for cleanup on shutdown
     fn spawn(
@@ -63,6 +117,7 @@ pub trait HotkeyBackend: Sized {
         device_id: Option<String>,
         require_eve_focus: bool,
         allowed_windows: AllowedWindows,
+        paused: PausedFlag,
     ) -> Result<Vec<JoinHandle<()>>>;
 
     /// Check if this backend is available on the current system
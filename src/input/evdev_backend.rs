@@ -12,16 +12,18 @@
 //! Security warning: Requires 'input' group membership, which allows ALL applications
 //! to read keyboard and mouse input. Use only if you need the advanced features.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use evdev::{Device, EventType, KeyCode};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
 use tracing::{debug, error, info, warn};
 
 use crate::common::constants::{input, paths, permissions};
+use crate::config::HotkeyBinding;
 use crate::input::backend::{
-    AllowedWindows, BackendCapabilities, HotkeyBackend, HotkeyConfiguration,
+    AllowedWindows, BackendCapabilities, HotkeyBackend, HotkeyConfiguration, PausedFlag,
 };
 use crate::input::device_detection;
 use crate::input::listener::{CycleCommand, TimestampedCommand};
@@ -35,6 +37,7 @@ impl HotkeyBackend for EvdevBackend {
         selected_device_id: Option<String>,
         require_eve_focus: bool,
         _allowed_windows: AllowedWindows,
+        _paused: PausedFlag, // evdev never grabs keys; the daemon drops commands while paused
     ) -> Result<Vec<JoinHandle<()>>> {
         spawn_listener_impl(sender, config, selected_device_id, require_eve_focus)
     }
@@ -95,21 +98,9 @@ fn spawn_listener_impl(
             info!("Auto-detect mode: using devices from hotkey bindings");
 
             let mut required_devices = std::collections::HashSet::new();
-            for (_, binding) in &config.cycle_hotkeys {
+            for binding in config.bindings() {
                 required_devices.extend(binding.source_devices.iter().cloned());
             }
-            for binding in &config.character_hotkeys {
-                required_devices.extend(binding.source_devices.iter().cloned());
-            }
-            for binding in &config.profile_hotkeys {
-                required_devices.extend(binding.source_devices.iter().cloned());
-            }
-            if let Some(ref skip) = config.toggle_skip_key {
-                required_devices.extend(skip.source_devices.iter().cloned());
-            }
-            if let Some(ref toggle_previews) = config.toggle_previews_key {
-                required_devices.extend(toggle_previews.source_devices.iter().cloned());
-            }
 
             if required_devices.is_empty() {
                 warn!(
@@ -173,12 +164,26 @@ fn spawn_listener_impl(
     let has_profile_hotkeys = !config.profile_hotkeys.is_empty();
     let has_skip_key = config.toggle_skip_key.is_some();
     let has_toggle_previews_key = config.toggle_previews_key.is_some();
+    let has_overview_key = config.overview_key.is_some();
+    let has_flip_key = config.flip_key.is_some();
+    let has_peek_key = config.peek_key.is_some();
+    let has_reveal_key = config.reveal_key.is_some();
+    let has_panic_key = config.panic_key.is_some();
+    let has_pause_key = config.pause_key.is_some();
+    let has_suspend_key = config.suspend_key.is_some();
 
     if cycle_configured
         || has_character_hotkeys
         || has_profile_hotkeys
         || has_skip_key
         || has_toggle_previews_key
+        || has_overview_key
+        || has_flip_key
+        || has_peek_key
+        || has_reveal_key
+        || has_panic_key
+        || has_pause_key
+        || has_suspend_key
     {
         info!(
             cycle_hotkey_count = config.cycle_hotkeys.len(),
@@ -186,6 +191,13 @@ fn spawn_listener_impl(
             profile_hotkey_count = config.profile_hotkeys.len(),
             has_skip_key = has_skip_key,
             has_toggle_previews_key = has_toggle_previews_key,
+            has_overview_key = has_overview_key,
+            has_flip_key = has_flip_key,
+            has_peek_key = has_peek_key,
+            has_reveal_key = has_reveal_key,
+            has_panic_key = has_panic_key,
+            has_pause_key = has_pause_key,
+            has_suspend_key = has_suspend_key,
             device_count = devices.len(),
             "Starting hotkey listeners"
         );
@@ -234,43 +246,38 @@ fn listen_for_hotkeys(
 
             debug!(key_code = key_code, value = event.value(), "Key event");
 
+            // Releasing a hold key ends its action (modifiers don't matter on release)
+            let release_command = [
+                (&config.peek_key, CycleCommand::PeekEnd),
+                (&config.reveal_key, CycleCommand::RevealEnd),
+            ]
+            .into_iter()
+            .find(|(key, _)| key.as_ref().is_some_and(|k| k.key_code == key_code))
+            .map(|(_, command)| command);
+            if event.value() == input::KEY_RELEASE
+                && let Some(command) = release_command
+            {
+                let timestamp = event
+                    .timestamp()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u32;
+                debug!(key_code = key_code, command = ?command, "Hold key released, sending command");
+                sender
+                    .blocking_send(TimestampedCommand { command, timestamp })
+                    .context("Failed to send hotkey command")?;
+                continue;
+            }
+
             // Collect non-modifier key presses that might be hotkeys
-            if pressed {
-                let is_cycle_key = config
-                    .cycle_hotkeys
-                    .iter()
-                    .any(|(_, hk)| hk.key_code == key_code);
-                let is_character_key = config
-                    .character_hotkeys
-                    .iter()
-                    .any(|hk| hk.key_code == key_code);
-                let is_profile_key = config
-                    .profile_hotkeys
-                    .iter()
-                    .any(|hk| hk.key_code == key_code);
-                let is_skip_key = config
-                    .toggle_skip_key
-                    .as_ref()
-                    .is_some_and(|k| k.key_code == key_code);
-                let is_toggle_previews_key = config
-                    .toggle_previews_key
-                    .as_ref()
-                    .is_some_and(|k| k.key_code == key_code);
-
-                if is_cycle_key
-                    || is_character_key
-                    || is_profile_key
-                    || is_skip_key
-                    || is_toggle_previews_key
-                {
-                    // Capture timestamp from the event
-                    let timestamp = event.timestamp();
-                    let millis = timestamp
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_millis() as u32;
-                    potential_hotkey_presses.push((key_code, millis));
-                }
+            if pressed && is_hotkey_key(&config, key_code) {
+                // Capture timestamp from the event
+                let timestamp = event.timestamp();
+                let millis = timestamp
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u32;
+                potential_hotkey_presses.push((key_code, millis));
             }
         }
 
@@ -278,133 +285,344 @@ fn listen_for_hotkeys(
         for (key_code, timestamp) in potential_hotkey_presses {
             // Query modifier state across all devices to handle cross-device hotkeys
             // (e.g., Shift held on keyboard + Mouse Button pressed on mouse)
-            let mut ctrl_pressed = false;
-            let mut shift_pressed = false;
-            let mut alt_pressed = false;
-            let mut super_pressed = false;
-
-            for device_path in all_device_paths.iter() {
-                if let Ok(dev) = Device::open(device_path)
-                    && let Ok(key_state) = dev.get_key_state()
-                {
-                    ctrl_pressed |=
-                        key_state.contains(KeyCode(29)) || key_state.contains(KeyCode(97));
-                    shift_pressed |= key_state.contains(KeyCode(input::KEY_LEFTSHIFT))
-                        || key_state.contains(KeyCode(input::KEY_RIGHTSHIFT));
-                    alt_pressed |=
-                        key_state.contains(KeyCode(56)) || key_state.contains(KeyCode(100));
-                    super_pressed |=
-                        key_state.contains(KeyCode(125)) || key_state.contains(KeyCode(126));
-                }
-            }
+            let (ctrl_pressed, shift_pressed, alt_pressed, super_pressed) =
+                modifier_state(&all_device_paths);
+
+            let command_to_send = command_for_key(
+                &config,
+                key_code,
+                ctrl_pressed,
+                shift_pressed,
+                alt_pressed,
+                super_pressed,
+            );
 
-            // Check cycle hotkeys first
-            let mut handled = false;
-            let mut command_to_send = None;
-
-            for (cmd, binding) in &config.cycle_hotkeys {
-                if binding.matches(
-                    key_code,
-                    ctrl_pressed,
-                    shift_pressed,
-                    alt_pressed,
-                    super_pressed,
-                ) {
-                    info!(
-                        binding = %binding.display_name(),
-                        command = ?cmd,
-                        "Cycle hotkey pressed, sending command"
-                    );
-                    command_to_send = Some(cmd.clone());
-                    handled = true;
-                    break;
-                }
+            if let Some(command) = command_to_send {
+                let timestamped_command = TimestampedCommand { command, timestamp };
+                sender
+                    .blocking_send(timestamped_command)
+                    .context("Failed to send hotkey command")?;
             }
+        }
+    }
+}
 
-            if !handled
-                && let Some(ref skip_key) = config.toggle_skip_key
-                && skip_key.matches(
-                    key_code,
-                    ctrl_pressed,
-                    shift_pressed,
-                    alt_pressed,
-                    super_pressed,
-                )
-            {
+/// Whether `key_code` is the key of any configured hotkey (modifiers aside)
+fn is_hotkey_key(config: &HotkeyConfiguration, key_code: u16) -> bool {
+    config
+        .bindings()
+        .any(|binding| binding.key_code == key_code)
+}
+
+/// Command bound to `key_code` with the given modifiers held, checked in priority order
+fn command_for_key(
+    config: &HotkeyConfiguration,
+    key_code: u16,
+    ctrl_pressed: bool,
+    shift_pressed: bool,
+    alt_pressed: bool,
+    super_pressed: bool,
+) -> Option<CycleCommand> {
+    // Check cycle hotkeys first
+    let mut handled = false;
+    let mut command_to_send = None;
+
+    for (cmd, binding) in &config.cycle_hotkeys {
+        if binding.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        ) {
+            info!(
+                binding = %binding.display_name(),
+                command = ?cmd,
+                "Cycle hotkey pressed, sending command"
+            );
+            command_to_send = Some(cmd.clone());
+            handled = true;
+            break;
+        }
+    }
+
+    if !handled
+        && let Some(ref skip_key) = config.toggle_skip_key
+        && skip_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %skip_key.display_name(),
+            "Toggle skip hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::ToggleSkip);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref toggle_previews_key) = config.toggle_previews_key
+        && toggle_previews_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %toggle_previews_key.display_name(),
+            "Toggle previews hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::TogglePreviews);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref overview_key) = config.overview_key
+        && overview_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %overview_key.display_name(),
+            "Overview hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::ToggleOverview);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref flip_key) = config.flip_key
+        && flip_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %flip_key.display_name(),
+            "Flip hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::FlipPrevious);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref panic_key) = config.panic_key
+        && panic_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %panic_key.display_name(),
+            "Panic hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::Panic);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref pause_key) = config.pause_key
+        && pause_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %pause_key.display_name(),
+            "Pause hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::TogglePause);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref suspend_key) = config.suspend_key
+        && suspend_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %suspend_key.display_name(),
+            "Suspend hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::SuspendHotkeys);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref peek_key) = config.peek_key
+        && peek_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %peek_key.display_name(),
+            "Peek hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::PeekStart);
+        handled = true;
+    }
+
+    if !handled
+        && let Some(ref reveal_key) = config.reveal_key
+        && reveal_key.matches(
+            key_code,
+            ctrl_pressed,
+            shift_pressed,
+            alt_pressed,
+            super_pressed,
+        )
+    {
+        info!(
+            binding = %reveal_key.display_name(),
+            "Reveal hotkey pressed, sending command"
+        );
+        command_to_send = Some(CycleCommand::RevealStart);
+        handled = true;
+    }
+
+    if !handled {
+        // Check per-character hotkeys
+        for char_hotkey in &config.character_hotkeys {
+            if char_hotkey.matches(
+                key_code,
+                ctrl_pressed,
+                shift_pressed,
+                alt_pressed,
+                super_pressed,
+            ) {
                 info!(
-                    binding = %skip_key.display_name(),
-                    "Toggle skip hotkey pressed, sending command"
+                    binding = %char_hotkey.display_name(),
+                    "Per-character hotkey pressed, sending command"
                 );
-                command_to_send = Some(CycleCommand::ToggleSkip);
-                handled = true;
+                command_to_send = Some(CycleCommand::CharacterHotkey(char_hotkey.clone()));
+                break; // Only send one command per keypress
             }
+        }
+    }
 
-            if !handled
-                && let Some(ref toggle_previews_key) = config.toggle_previews_key
-                && toggle_previews_key.matches(
-                    key_code,
-                    ctrl_pressed,
-                    shift_pressed,
-                    alt_pressed,
-                    super_pressed,
-                )
-            {
+    if !handled && command_to_send.is_none() {
+        // Check profile hotkeys
+        for profile_hotkey in &config.profile_hotkeys {
+            if profile_hotkey.matches(
+                key_code,
+                ctrl_pressed,
+                shift_pressed,
+                alt_pressed,
+                super_pressed,
+            ) {
                 info!(
-                    binding = %toggle_previews_key.display_name(),
-                    "Toggle previews hotkey pressed, sending command"
+                    binding = %profile_hotkey.display_name(),
+                    "Profile hotkey pressed, sending command"
                 );
-                command_to_send = Some(CycleCommand::TogglePreviews);
-                handled = true;
+                command_to_send = Some(CycleCommand::ProfileHotkey(profile_hotkey.clone()));
+                break; // Only send one command per keypress
             }
+        }
+    }
 
-            if !handled {
-                // Check per-character hotkeys
-                for char_hotkey in &config.character_hotkeys {
-                    if char_hotkey.matches(
-                        key_code,
-                        ctrl_pressed,
-                        shift_pressed,
-                        alt_pressed,
-                        super_pressed,
-                    ) {
-                        info!(
-                            binding = %char_hotkey.display_name(),
-                            "Per-character hotkey pressed, sending command"
-                        );
-                        command_to_send = Some(CycleCommand::CharacterHotkey(char_hotkey.clone()));
-                        break; // Only send one command per keypress
-                    }
-                }
-            }
+    command_to_send
+}
 
-            if !handled && command_to_send.is_none() {
-                // Check profile hotkeys
-                for profile_hotkey in &config.profile_hotkeys {
-                    if profile_hotkey.matches(
-                        key_code,
-                        ctrl_pressed,
-                        shift_pressed,
-                        alt_pressed,
-                        super_pressed,
-                    ) {
-                        info!(
-                            binding = %profile_hotkey.display_name(),
-                            "Profile hotkey pressed, sending command"
-                        );
-                        command_to_send = Some(CycleCommand::ProfileHotkey(profile_hotkey.clone()));
-                        break; // Only send one command per keypress
-                    }
-                }
-            }
+/// Ctrl, Shift, Alt and Super held on any of the devices at `device_paths`
+fn modifier_state(device_paths: &[std::path::PathBuf]) -> (bool, bool, bool, bool) {
+    let mut state = (false, false, false, false);
+    for device_path in device_paths {
+        if let Ok(dev) = Device::open(device_path)
+            && let Ok(key_state) = dev.get_key_state()
+        {
+            state.0 |= key_state.contains(KeyCode(29)) || key_state.contains(KeyCode(97));
+            state.1 |= key_state.contains(KeyCode(input::KEY_LEFTSHIFT))
+                || key_state.contains(KeyCode(input::KEY_RIGHTSHIFT));
+            state.2 |= key_state.contains(KeyCode(56)) || key_state.contains(KeyCode(100));
+            state.3 |= key_state.contains(KeyCode(125)) || key_state.contains(KeyCode(126));
+        }
+    }
+    state
+}
 
-            if let Some(command) = command_to_send {
-                let timestamped_command = TimestampedCommand { command, timestamp };
-                sender
-                    .blocking_send(timestamped_command)
-                    .context("Failed to send hotkey command")?;
+/// Waits up to `timeout` for `binding` to be pressed on the devices the listener would read
+/// for it under `selected_device_id`
+pub fn wait_for_binding(
+    binding: &HotkeyBinding,
+    selected_device_id: Option<&str>,
+    timeout: Duration,
+) -> Result<()> {
+    let all_devices = device_detection::find_all_input_devices_with_paths()?;
+    let all_device_paths: Vec<_> = all_devices.iter().map(|(_, path)| path.clone()).collect();
+
+    let wanted: Vec<String> = match selected_device_id {
+        None => bail!("No input device is selected, so evdev hotkeys are disabled"),
+        Some("all") => Vec::new(),
+        Some("auto") => binding.source_devices.clone(),
+        Some(device_id) => vec![device_id.to_string()],
+    };
+    let mut devices: Vec<Device> = all_devices
+        .into_iter()
+        .filter(|(_, path)| {
+            wanted.is_empty() || wanted.contains(&device_detection::extract_device_id(path))
+        })
+        .map(|(device, _)| device)
+        .collect();
+    if devices.is_empty() {
+        bail!("The selected input device was not found");
+    }
+    for device in &devices {
+        device.set_nonblocking(true).ok();
+    }
+
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        for device in &mut devices {
+            let Ok(events) = device.fetch_events() else {
+                continue;
+            };
+            let pressed = events.into_iter().any(|event| {
+                event.event_type() == EventType::KEY
+                    && event.code() == binding.key_code
+                    && event.value() == input::KEY_PRESS
+            });
+            if pressed {
+                let (ctrl, shift, alt, super_key) = modifier_state(&all_device_paths);
+                if binding.matches(binding.key_code, ctrl, shift, alt, super_key) {
+                    return Ok(());
+                }
             }
         }
+        thread::sleep(Duration::from_millis(10));
     }
+    bail!(
+        "{} was not pressed within {} seconds",
+        binding.display_name(),
+        timeout.as_secs()
+    )
 }
 
 /// Check if hotkeys are available (user has input group permissions)
@@ -472,3 +690,73 @@ pub fn list_input_devices() -> Result<Vec<(String, String)>> {
 
     Ok(devices)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_key_is_dispatched() {
+        let config = HotkeyConfiguration {
+            panic_key: Some(HotkeyBinding::new(119, true, false, false, false)),
+            ..Default::default()
+        };
+        assert!(is_hotkey_key(&config, 119));
+        assert_eq!(
+            command_for_key(&config, 119, true, false, false, false),
+            Some(CycleCommand::Panic)
+        );
+        assert_eq!(
+            command_for_key(&config, 119, false, false, false, false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pause_key_is_dispatched() {
+        let config = HotkeyConfiguration {
+            pause_key: Some(HotkeyBinding::new(25, false, true, false, false)),
+            ..Default::default()
+        };
+        assert!(is_hotkey_key(&config, 25));
+        assert_eq!(
+            command_for_key(&config, 25, false, true, false, false),
+            Some(CycleCommand::TogglePause)
+        );
+    }
+
+    #[test]
+    fn test_every_single_key_is_dispatched() {
+        let key = |code| Some(HotkeyBinding::new(code, false, false, false, false));
+        let config = HotkeyConfiguration {
+            toggle_skip_key: key(30),
+            toggle_previews_key: key(31),
+            overview_key: key(32),
+            flip_key: key(33),
+            peek_key: key(34),
+            reveal_key: key(35),
+            panic_key: key(36),
+            pause_key: key(37),
+            suspend_key: key(38),
+            ..Default::default()
+        };
+        let expected = [
+            CycleCommand::ToggleSkip,
+            CycleCommand::TogglePreviews,
+            CycleCommand::ToggleOverview,
+            CycleCommand::FlipPrevious,
+            CycleCommand::PeekStart,
+            CycleCommand::RevealStart,
+            CycleCommand::Panic,
+            CycleCommand::TogglePause,
+            CycleCommand::SuspendHotkeys,
+        ];
+        for (code, command) in (30..).zip(expected) {
+            assert!(is_hotkey_key(&config, code));
+            assert_eq!(
+                command_for_key(&config, code, false, false, false, false),
+                Some(command)
+            );
+        }
+    }
+}
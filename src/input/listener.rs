@@ -4,9 +4,16 @@
 //! Re-exports shared types and functions from backend-specific modules.
 
 use anyhow::Result;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tracing::warn;
 
-use crate::config::HotkeyBinding;
-use crate::input::evdev_backend;
+use crate::config::{HotkeyBackendType, HotkeyBinding};
+use crate::input::backend::{AllowedWindows, HotkeyBackend, HotkeyConfiguration, PausedFlag};
+use crate::input::evdev_backend::{self, EvdevBackend};
+use crate::input::portal_backend::PortalBackend;
+use crate::input::x11_backend::{self, X11Backend};
 
 /// Hotkey command sent from input listeners to the main daemon loop
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,6 +30,45 @@ pub enum CycleCommand {
     ToggleSkip,
     /// Triggered when the toggle previews hotkey is pressed (ephemeral)
     TogglePreviews,
+    /// Triggered when the overview hotkey is pressed (tiles all clients across the screen)
+    ToggleOverview,
+    /// Triggered when the flip hotkey is pressed (back to the previously focused client)
+    FlipPrevious,
+    /// Triggered when the peek hotkey is pressed (enlarge the next cycle thumbnail)
+    PeekStart,
+    /// Triggered when the peek hotkey is released
+    PeekEnd,
+    /// Triggered when the reveal hotkey is pressed (show thumbnails hidden by focus rules)
+    RevealStart,
+    /// Triggered when the reveal hotkey is released
+    RevealEnd,
+    /// Triggered when the panic hotkey is pressed (hide everything, or restore on second press)
+    Panic,
+    /// Triggered when the pause hotkey is pressed (pause or resume the whole daemon)
+    TogglePause,
+    /// Triggered when the suspend hotkey is pressed (release other hotkeys for a while, or resume)
+    SuspendHotkeys,
+}
+
+impl CycleCommand {
+    /// Commands that must work whatever is focused, bypassing `hotkey_focus_context` and
+    /// `hotkey_blocked_classes`
+    pub fn ignores_focus_requirement(&self) -> bool {
+        matches!(
+            self,
+            Self::RevealStart
+                | Self::RevealEnd
+                | Self::Panic
+                | Self::TogglePause
+                | Self::SuspendHotkeys
+        )
+    }
+
+    /// Commands whose keys stay bound while the daemon is paused or hotkeys are suspended, so
+    /// either can be ended from the keyboard
+    pub fn works_while_paused(&self) -> bool {
+        matches!(self, Self::TogglePause | Self::SuspendHotkeys)
+    }
 }
 
 /// A wrapper around CycleCommand that includes the timestamp of the input event
@@ -43,3 +89,100 @@ pub fn print_permission_error() {
 pub fn list_input_devices() -> Result<Vec<(String, String)>> {
     evdev_backend::list_input_devices()
 }
+
+/// Backend to run for the requested one: evdev falls through to X11 XGrabKey when
+/// /dev/input is not accessible, so hotkeys keep working without the input group
+pub fn select_backend(requested: HotkeyBackendType) -> HotkeyBackendType {
+    match requested {
+        HotkeyBackendType::Evdev if !EvdevBackend::is_available() => {
+            warn!("evdev hotkeys need access to /dev/input, falling back to X11");
+            print_permission_error();
+            HotkeyBackendType::X11
+        }
+        HotkeyBackendType::Portal if !PortalBackend::is_available() => {
+            warn!("GlobalShortcuts portal is not available, falling back to X11");
+            HotkeyBackendType::X11
+        }
+        backend => backend,
+    }
+}
+
+/// Spawns the hotkey listener for `requested` (see `select_backend`), retrying with X11 when
+/// the evdev or portal listener fails to start. Returns the backend that runs with its thread
+/// handles.
+pub fn spawn_backend(
+    requested: HotkeyBackendType,
+    sender: Sender<TimestampedCommand>,
+    config: HotkeyConfiguration,
+    device_id: Option<String>,
+    require_eve_focus: bool,
+    allowed_windows: AllowedWindows,
+    paused: PausedFlag,
+) -> Result<(HotkeyBackendType, Vec<JoinHandle<()>>)> {
+    match select_backend(requested) {
+        HotkeyBackendType::Evdev => match EvdevBackend::spawn(
+            sender.clone(),
+            config.clone(),
+            device_id,
+            require_eve_focus,
+            allowed_windows.clone(),
+            paused.clone(),
+        ) {
+            Ok(handles) => return Ok((HotkeyBackendType::Evdev, handles)),
+            Err(e) => {
+                warn!(error = %e, "Failed to start evdev hotkey listener, falling back to X11");
+                print_permission_error();
+            }
+        },
+        HotkeyBackendType::Portal => match PortalBackend::spawn(
+            sender.clone(),
+            config.clone(),
+            None,
+            require_eve_focus,
+            allowed_windows.clone(),
+            paused.clone(),
+        ) {
+            Ok(handles) => return Ok((HotkeyBackendType::Portal, handles)),
+            Err(e) => {
+                warn!(error = %e, "Failed to start portal hotkey listener, falling back to X11");
+            }
+        },
+        HotkeyBackendType::X11 => {}
+    }
+    let handles = X11Backend::spawn(
+        sender,
+        config,
+        None,
+        require_eve_focus,
+        allowed_windows,
+        paused,
+    )?;
+    Ok((HotkeyBackendType::X11, handles))
+}
+
+/// Checks that the backend `requested` resolves to can grab `binding`. Only X11 grabs are
+/// exclusive, so evdev and portal bindings always pass.
+pub fn check_grab(requested: HotkeyBackendType, binding: &HotkeyBinding) -> Result<()> {
+    match select_backend(requested) {
+        HotkeyBackendType::X11 => x11_backend::check_grab(binding),
+        HotkeyBackendType::Evdev | HotkeyBackendType::Portal => Ok(()),
+    }
+}
+
+/// Listens for `binding` the way the backend `requested` resolves to would, returning once it
+/// is pressed or failing after `timeout`. Portal shortcuts are confirmed by the desktop's own
+/// bind dialog and can't be tested separately.
+pub fn test_binding(
+    requested: HotkeyBackendType,
+    binding: &HotkeyBinding,
+    device_id: Option<&str>,
+    timeout: Duration,
+) -> Result<()> {
+    match select_backend(requested) {
+        HotkeyBackendType::X11 => x11_backend::wait_for_binding(binding, timeout),
+        HotkeyBackendType::Evdev => evdev_backend::wait_for_binding(binding, device_id, timeout),
+        HotkeyBackendType::Portal => {
+            anyhow::bail!("Portal shortcuts are confirmed by the desktop and can't be tested")
+        }
+    }
+}
@@ -4,4 +4,5 @@ pub mod backend;
 pub mod device_detection;
 pub mod evdev_backend;
 pub mod listener;
+pub mod portal_backend;
 pub mod x11_backend;
@@ -0,0 +1,705 @@
+//! xdg-desktop-portal GlobalShortcuts hotkey backend
+//!
+//! For Wayland compositors where XGrabKey only sees XWayland windows and /dev/input is off
+//! limits. Every configured hotkey becomes a portal shortcut with a stable id and the binding
+//! as its preferred trigger; the compositor confirms them in its own dialog (once per app) and
+//! then reports presses and releases as `Activated` / `Deactivated` signals.
+//!
+//! Limitations:
+//! - The compositor decides the final trigger; users can change it in the desktop settings
+//! - Mouse buttons can't be bound
+//! - Shortcuts are consumed in every app; the daemon drops those fired outside the hotkey focus
+//!   context instead of passing them through
+
+use anyhow::{Context, Result, bail};
+use futures_lite::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread::{self, JoinHandle};
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, error, info, warn};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{Connection, Proxy};
+
+use crate::config::HotkeyBinding;
+use crate::input::backend::{
+    AllowedWindows, BackendCapabilities, HotkeyBackend, HotkeyConfiguration, PausedFlag,
+};
+use crate::input::listener::{CycleCommand, TimestampedCommand};
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SHORTCUTS_IFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+const REQUEST_IFACE: &str = "org.freedesktop.portal.Request";
+
+/// Key names of the XDG shortcut trigger format (xkb keysym names) by evdev key code
+const KEY_NAMES: &[(u16, &str)] = &[
+    (1, "Escape"),
+    (2, "1"),
+    (3, "2"),
+    (4, "3"),
+    (5, "4"),
+    (6, "5"),
+    (7, "6"),
+    (8, "7"),
+    (9, "8"),
+    (10, "9"),
+    (11, "0"),
+    (12, "minus"),
+    (13, "equal"),
+    (14, "BackSpace"),
+    (15, "Tab"),
+    (16, "q"),
+    (17, "w"),
+    (18, "e"),
+    (19, "r"),
+    (20, "t"),
+    (21, "y"),
+    (22, "u"),
+    (23, "i"),
+    (24, "o"),
+    (25, "p"),
+    (26, "bracketleft"),
+    (27, "bracketright"),
+    (28, "Return"),
+    (30, "a"),
+    (31, "s"),
+    (32, "d"),
+    (33, "f"),
+    (34, "g"),
+    (35, "h"),
+    (36, "j"),
+    (37, "k"),
+    (38, "l"),
+    (39, "semicolon"),
+    (40, "apostrophe"),
+    (41, "grave"),
+    (43, "backslash"),
+    (44, "z"),
+    (45, "x"),
+    (46, "c"),
+    (47, "v"),
+    (48, "b"),
+    (49, "n"),
+    (50, "m"),
+    (51, "comma"),
+    (52, "period"),
+    (53, "slash"),
+    (55, "KP_Multiply"),
+    (57, "space"),
+    (59, "F1"),
+    (60, "F2"),
+    (61, "F3"),
+    (62, "F4"),
+    (63, "F5"),
+    (64, "F6"),
+    (65, "F7"),
+    (66, "F8"),
+    (67, "F9"),
+    (68, "F10"),
+    (71, "KP_7"),
+    (72, "KP_8"),
+    (73, "KP_9"),
+    (74, "KP_Subtract"),
+    (75, "KP_4"),
+    (76, "KP_5"),
+    (77, "KP_6"),
+    (78, "KP_Add"),
+    (79, "KP_1"),
+    (80, "KP_2"),
+    (81, "KP_3"),
+    (82, "KP_0"),
+    (83, "KP_Decimal"),
+    (87, "F11"),
+    (88, "F12"),
+    (96, "KP_Enter"),
+    (98, "KP_Divide"),
+    (102, "Home"),
+    (103, "Up"),
+    (104, "Page_Up"),
+    (105, "Left"),
+    (106, "Right"),
+    (107, "End"),
+    (108, "Down"),
+    (109, "Page_Down"),
+    (110, "Insert"),
+    (111, "Delete"),
+    (119, "Pause"),
+];
+
+/// Trigger for `binding` in the XDG shortcut format (e.g. "CTRL+SHIFT+Tab"), or None for keys
+/// the format can't express (mouse buttons, exotic keys)
+pub fn trigger_for(binding: &HotkeyBinding) -> Option<String> {
+    let key = KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == binding.key_code)
+        .map(|(_, name)| *name)?;
+    let mut parts = Vec::new();
+    for (held, modifier) in [
+        (binding.ctrl, "CTRL"),
+        (binding.alt, "ALT"),
+        (binding.shift, "SHIFT"),
+        (binding.super_key, "LOGO"),
+    ] {
+        if held {
+            parts.push(modifier);
+        }
+    }
+    parts.push(key);
+    Some(parts.join("+"))
+}
+
+/// Binding for a trigger chosen in the portal dialog. Accepts both the XDG format and the
+/// human-readable descriptions compositors report ("Ctrl+Shift+Tab", "Meta+F1").
+pub fn binding_from_trigger(trigger: &str) -> Option<HotkeyBinding> {
+    let mut binding = HotkeyBinding::new(0, false, false, false, false);
+    let mut key = None;
+    for part in trigger.split('+').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "primary" => binding.ctrl = true,
+            "alt" => binding.alt = true,
+            "shift" => binding.shift = true,
+            "logo" | "super" | "meta" | "win" => binding.super_key = true,
+            _ => {
+                let name = part.replace(' ', "_");
+                key = KEY_NAMES
+                    .iter()
+                    .find(|(_, known)| known.eq_ignore_ascii_case(&name))
+                    .or_else(|| match name.to_ascii_lowercase().as_str() {
+                        "esc" => KEY_NAMES.iter().find(|(code, _)| *code == 1),
+                        "enter" => KEY_NAMES.iter().find(|(code, _)| *code == 28),
+                        "pgup" => KEY_NAMES.iter().find(|(code, _)| *code == 104),
+                        "pgdown" => KEY_NAMES.iter().find(|(code, _)| *code == 109),
+                        "ins" => KEY_NAMES.iter().find(|(code, _)| *code == 110),
+                        "del" => KEY_NAMES.iter().find(|(code, _)| *code == 111),
+                        _ => None,
+                    })
+                    .map(|(code, _)| *code);
+                key?;
+            }
+        }
+    }
+    binding.key_code = key?;
+    Some(binding)
+}
+
+/// One configured hotkey as a portal shortcut
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PortalShortcut {
+    /// Stable, unique id, so the compositor keeps the user's trigger across restarts. Entries
+    /// from lists carry their index, since names and bindings there can repeat.
+    id: String,
+    description: String,
+    trigger: Option<String>,
+    press: CycleCommand,
+    /// Sent on `Deactivated` (hold keys)
+    release: Option<CycleCommand>,
+}
+
+/// Portal shortcuts for every configured hotkey, in registration order
+fn shortcuts(config: &HotkeyConfiguration) -> Vec<PortalShortcut> {
+    let shortcut =
+        |id: String, description: String, binding: &HotkeyBinding, press| PortalShortcut {
+            id,
+            description,
+            trigger: trigger_for(binding),
+            press,
+            release: None,
+        };
+
+    let mut list = Vec::new();
+    for (idx, (command, binding)) in config.cycle_hotkeys.iter().enumerate() {
+        let (id, description) = match command {
+            CycleCommand::Forward(group) => (
+                format!("cycle-forward:{idx}:{group}"),
+                format!("Cycle forward ({group})"),
+            ),
+            CycleCommand::Backward(group) => (
+                format!("cycle-backward:{idx}:{group}"),
+                format!("Cycle backward ({group})"),
+            ),
+            other => (format!("cycle:{idx}:{other:?}"), format!("{other:?}")),
+        };
+        list.push(shortcut(id, description, binding, command.clone()));
+    }
+
+    let singles = [
+        (
+            &config.toggle_skip_key,
+            "toggle-skip",
+            "Skip current character",
+            CycleCommand::ToggleSkip,
+        ),
+        (
+            &config.toggle_previews_key,
+            "toggle-previews",
+            "Show/hide previews",
+            CycleCommand::TogglePreviews,
+        ),
+        (
+            &config.overview_key,
+            "overview",
+            "Overview of all clients",
+            CycleCommand::ToggleOverview,
+        ),
+        (
+            &config.flip_key,
+            "flip",
+            "Flip to previous character",
+            CycleCommand::FlipPrevious,
+        ),
+        (
+            &config.panic_key,
+            "panic",
+            "Panic (minimize everything)",
+            CycleCommand::Panic,
+        ),
+        (
+            &config.pause_key,
+            "pause",
+            "Pause/resume",
+            CycleCommand::TogglePause,
+        ),
+        (
+            &config.suspend_key,
+            "suspend",
+            "Suspend hotkeys briefly",
+            CycleCommand::SuspendHotkeys,
+        ),
+    ];
+    for (key, id, description, command) in singles {
+        if let Some(binding) = key {
+            list.push(shortcut(
+                id.to_string(),
+                description.to_string(),
+                binding,
+                command,
+            ));
+        }
+    }
+
+    let holds = [
+        (
+            &config.peek_key,
+            "peek",
+            "Peek next client (hold)",
+            CycleCommand::PeekStart,
+            CycleCommand::PeekEnd,
+        ),
+        (
+            &config.reveal_key,
+            "reveal",
+            "Reveal hidden previews (hold)",
+            CycleCommand::RevealStart,
+            CycleCommand::RevealEnd,
+        ),
+    ];
+    for (key, id, description, start, end) in holds {
+        if let Some(binding) = key {
+            let mut hold = shortcut(id.to_string(), description.to_string(), binding, start);
+            hold.release = Some(end);
+            list.push(hold);
+        }
+    }
+
+    for (idx, binding) in config.character_hotkeys.iter().enumerate() {
+        list.push(shortcut(
+            format!("character:{idx}:{}", binding.display_name()),
+            format!("Switch to character ({})", binding.display_name()),
+            binding,
+            CycleCommand::CharacterHotkey(binding.clone()),
+        ));
+    }
+    for (idx, binding) in config.profile_hotkeys.iter().enumerate() {
+        list.push(shortcut(
+            format!("profile:{idx}:{}", binding.display_name()),
+            format!("Switch profile ({})", binding.display_name()),
+            binding,
+            CycleCommand::ProfileHotkey(binding.clone()),
+        ));
+    }
+    list
+}
+
+/// A GlobalShortcuts session on the session bus
+struct PortalSession {
+    conn: Connection,
+    proxy: Proxy<'static>,
+    handle: OwnedObjectPath,
+}
+
+impl PortalSession {
+    async fn create() -> Result<Self> {
+        let conn = Connection::session()
+            .await
+            .context("Failed to connect to the session bus")?;
+        let proxy = Proxy::new(&conn, PORTAL_DEST, PORTAL_PATH, SHORTCUTS_IFACE)
+            .await
+            .context("Failed to reach the GlobalShortcuts portal")?;
+
+        let token = next_token();
+        let options: HashMap<&str, Value> = HashMap::from([
+            ("handle_token", Value::from(token.as_str())),
+            ("session_handle_token", Value::from(token.as_str())),
+        ]);
+        let results = request(&conn, &proxy, "CreateSession", &(options,), &token).await?;
+        let handle = match results.get("session_handle").map(|v| &**v) {
+            Some(Value::Str(handle)) => handle.as_str().to_string(),
+            Some(Value::ObjectPath(handle)) => handle.as_str().to_string(),
+            _ => bail!("GlobalShortcuts portal returned no session handle"),
+        };
+        let handle = OwnedObjectPath::try_from(handle).context("Invalid portal session handle")?;
+        debug!(session = %handle.as_str(), "GlobalShortcuts session created");
+        Ok(Self {
+            conn,
+            proxy,
+            handle,
+        })
+    }
+
+    /// Binds `(id, description, preferred trigger)` shortcuts, letting the compositor show its
+    /// dialog. Returns the trigger description of every bound shortcut by id.
+    async fn bind(
+        &self,
+        shortcuts: &[(String, String, Option<String>)],
+    ) -> Result<HashMap<String, String>> {
+        let list: Vec<(&str, HashMap<&str, Value>)> = shortcuts
+            .iter()
+            .map(|(id, description, trigger)| {
+                let mut properties =
+                    HashMap::from([("description", Value::from(description.as_str()))]);
+                if let Some(trigger) = trigger {
+                    properties.insert("preferred_trigger", Value::from(trigger.as_str()));
+                }
+                (id.as_str(), properties)
+            })
+            .collect();
+        let token = next_token();
+        let options: HashMap<&str, Value> =
+            HashMap::from([("handle_token", Value::from(token.as_str()))]);
+        let body = (&self.handle, list, "", options);
+        let mut results = request(&self.conn, &self.proxy, "BindShortcuts", &body, &token).await?;
+
+        let Some(bound) = results.remove("shortcuts") else {
+            return Ok(HashMap::new());
+        };
+        let bound: Vec<(String, HashMap<String, OwnedValue>)> =
+            bound.try_into().context("Unexpected BindShortcuts reply")?;
+        Ok(bound
+            .into_iter()
+            .map(|(id, properties)| {
+                let trigger = properties
+                    .get("trigger_description")
+                    .and_then(|v| String::try_from(v.clone()).ok())
+                    .unwrap_or_default();
+                (id, trigger)
+            })
+            .collect())
+    }
+}
+
+/// The user closed a portal dialog without confirming it
+#[derive(Debug)]
+pub struct DialogCancelled;
+
+impl std::fmt::Display for DialogCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cancelled in the portal dialog")
+    }
+}
+
+impl std::error::Error for DialogCancelled {}
+
+/// Unique token for portal request and session handles
+fn next_token() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    format!(
+        "eve_preview_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Calls a portal method that answers through a Request object and returns its results.
+/// Subscribes to the response before calling so a fast reply can't be missed.
+async fn request<B>(
+    conn: &Connection,
+    proxy: &Proxy<'_>,
+    method: &str,
+    body: &B,
+    token: &str,
+) -> Result<HashMap<String, OwnedValue>>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+{
+    let sender = conn
+        .unique_name()
+        .context("Session bus connection has no unique name")?
+        .trim_start_matches(':')
+        .replace('.', "_");
+    let path = ObjectPath::try_from(format!("{PORTAL_PATH}/request/{sender}/{token}"))
+        .context("Invalid portal request path")?;
+    let request = Proxy::new(conn, PORTAL_DEST, path, REQUEST_IFACE)
+        .await
+        .context("Failed to create portal request proxy")?;
+    let mut responses = request
+        .receive_signal("Response")
+        .await
+        .context("Failed to subscribe to portal response")?;
+
+    let _: OwnedObjectPath = proxy
+        .call(method, body)
+        .await
+        .context(format!("Portal {method} call failed"))?;
+
+    let response = responses
+        .next()
+        .await
+        .context(format!("Portal {method} request ended without a response"))?;
+    let (code, results): (u32, HashMap<String, OwnedValue>) = response
+        .body()
+        .deserialize()
+        .context("Unexpected portal response")?;
+    match code {
+        0 => Ok(results),
+        1 => Err(DialogCancelled.into()),
+        _ => bail!("Portal {method} request failed"),
+    }
+}
+
+/// Runs `future` on a private current-thread runtime (backend threads and the Manager's UI
+/// thread have none of their own)
+fn block_on<T>(future: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to create runtime for the portal")?
+        .block_on(future)
+}
+
+/// Lets the user pick a trigger in the compositor's shortcut dialog and returns it as a
+/// binding. A fresh shortcut id is used so the compositor asks instead of reusing an earlier
+/// choice.
+pub fn bind_interactively(description: &str) -> Result<HotkeyBinding> {
+    let id = format!("capture-{}", next_token());
+    block_on(async {
+        let session = PortalSession::create().await?;
+        let bound = session
+            .bind(&[(id.clone(), description.to_string(), None)])
+            .await?;
+        let trigger = bound
+            .get(&id)
+            .filter(|t| !t.is_empty())
+            .context("No shortcut was assigned in the dialog")?;
+        binding_from_trigger(trigger).context(format!(
+            "Shortcut '{trigger}' can't be used as an EVE Preview Manager hotkey"
+        ))
+    })
+}
+
+pub struct PortalBackend;
+
+impl HotkeyBackend for PortalBackend {
+    fn spawn(
+        sender: Sender<TimestampedCommand>,
+        config: HotkeyConfiguration,
+        _device_id: Option<String>, // Not used by the portal backend
+        require_eve_focus: bool,
+        _allowed_windows: AllowedWindows,
+        paused: PausedFlag,
+    ) -> Result<Vec<JoinHandle<()>>> {
+        let shortcuts = shortcuts(&config);
+        if shortcuts.is_empty() {
+            info!("No hotkeys configured - portal listener will not be started");
+            return Ok(Vec::new());
+        }
+        if require_eve_focus {
+            warn!(
+                "The GlobalShortcuts portal can't pass shortcuts through; outside the hotkey focus context they're swallowed"
+            );
+        }
+
+        let handle = thread::spawn(move || {
+            if let Err(e) = block_on(run_portal_listener(sender, shortcuts, paused)) {
+                error!(error = %e, "Portal hotkey listener error");
+            }
+        });
+        Ok(vec![handle])
+    }
+
+    fn is_available() -> bool {
+        block_on(async {
+            let conn = Connection::session().await?;
+            let proxy = Proxy::new(&conn, PORTAL_DEST, PORTAL_PATH, SHORTCUTS_IFACE).await?;
+            let version: u32 = proxy.get_property("version").await?;
+            Ok(version)
+        })
+        .inspect_err(|e| debug!(error = %e, "GlobalShortcuts portal unavailable"))
+        .is_ok()
+    }
+
+    fn name() -> &'static str {
+        "portal"
+    }
+
+    fn capabilities() -> BackendCapabilities {
+        BackendCapabilities {
+            supports_cross_device_modifiers: true,
+            supports_device_filtering: false,
+            requires_permissions: false,
+            permission_description: None,
+        }
+    }
+}
+
+async fn run_portal_listener(
+    sender: Sender<TimestampedCommand>,
+    shortcuts: Vec<PortalShortcut>,
+    paused: PausedFlag,
+) -> Result<()> {
+    let session = PortalSession::create().await?;
+    let mut activated = session
+        .proxy
+        .receive_signal("Activated")
+        .await
+        .context("Failed to subscribe to shortcut activations")?;
+    let mut deactivated = session
+        .proxy
+        .receive_signal("Deactivated")
+        .await
+        .context("Failed to subscribe to shortcut releases")?;
+
+    let requested: Vec<(String, String, Option<String>)> = shortcuts
+        .iter()
+        .map(|s| (s.id.clone(), s.description.clone(), s.trigger.clone()))
+        .collect();
+    let bound = session.bind(&requested).await?;
+    for (id, trigger) in &bound {
+        debug!(id = %id, trigger = %trigger, "Portal shortcut bound");
+    }
+    info!(shortcuts = bound.len(), "Portal hotkeys registered");
+
+    let by_id: HashMap<&str, &PortalShortcut> =
+        shortcuts.iter().map(|s| (s.id.as_str(), s)).collect();
+    loop {
+        let (message, pressed) = tokio::select! {
+            Some(message) = activated.next() => (message, true),
+            Some(message) = deactivated.next() => (message, false),
+            else => bail!("GlobalShortcuts portal closed the signal streams"),
+        };
+        let (session_handle, id, timestamp, _): (
+            OwnedObjectPath,
+            String,
+            u64,
+            HashMap<String, OwnedValue>,
+        ) = match message.body().deserialize() {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(error = %e, "Unexpected shortcut signal");
+                continue;
+            }
+        };
+        if session_handle != session.handle {
+            continue;
+        }
+        let Some(shortcut) = by_id.get(id.as_str()) else {
+            continue;
+        };
+        let command = if pressed {
+            shortcut.press.clone()
+        } else {
+            let Some(release) = &shortcut.release else {
+                continue;
+            };
+            release.clone()
+        };
+        // Paused or suspended daemons only listen for the pause and suspend keys
+        if paused.load(Ordering::Relaxed) && !command.works_while_paused() {
+            continue;
+        }
+        debug!(id = %id, command = ?command, "Portal shortcut activated");
+        sender
+            .send(TimestampedCommand {
+                command,
+                timestamp: timestamp as u32,
+            })
+            .await
+            .context("Failed to send hotkey command")?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_round_trip() {
+        let binding = HotkeyBinding::new(15, true, true, false, false);
+        assert_eq!(trigger_for(&binding).as_deref(), Some("CTRL+SHIFT+Tab"));
+        assert_eq!(binding_from_trigger("CTRL+SHIFT+Tab"), Some(binding));
+
+        // Mouse buttons have no trigger
+        assert_eq!(
+            trigger_for(&HotkeyBinding::new(0x113, false, false, false, false)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_binding_from_trigger_description() {
+        assert_eq!(
+            binding_from_trigger("Meta+F1"),
+            Some(HotkeyBinding::new(59, false, false, false, true))
+        );
+        assert_eq!(
+            binding_from_trigger("Ctrl+Page Up"),
+            Some(HotkeyBinding::new(104, true, false, false, false))
+        );
+        assert_eq!(binding_from_trigger("Ctrl+Hyper"), None);
+        assert_eq!(binding_from_trigger("Ctrl+Shift"), None);
+    }
+
+    #[test]
+    fn test_shortcuts_have_unique_ids() {
+        let config = HotkeyConfiguration {
+            cycle_hotkeys: vec![
+                (
+                    CycleCommand::Forward("Main".to_string()),
+                    HotkeyBinding::new(15, false, false, false, false),
+                ),
+                (
+                    CycleCommand::Backward("Main".to_string()),
+                    HotkeyBinding::new(15, false, true, false, false),
+                ),
+                // A second group with the same name
+                (
+                    CycleCommand::Forward("Main".to_string()),
+                    HotkeyBinding::new(16, false, false, false, false),
+                ),
+            ],
+            character_hotkeys: vec![
+                HotkeyBinding::new(59, false, false, false, false),
+                HotkeyBinding::new(59, false, false, false, false),
+            ],
+            profile_hotkeys: Vec::new(),
+            toggle_skip_key: None,
+            toggle_previews_key: None,
+            overview_key: None,
+            flip_key: None,
+            peek_key: Some(HotkeyBinding::new(41, false, false, false, false)),
+            reveal_key: None,
+            panic_key: None,
+            pause_key: None,
+            suspend_key: None,
+            blocked_classes: Vec::new(),
+        };
+        let list = shortcuts(&config);
+        assert_eq!(list.len(), 6);
+        let mut ids: Vec<&str> = list.iter().map(|s| s.id.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 6);
+        let peek = list.iter().find(|s| s.id == "peek").unwrap();
+        assert_eq!(peek.release, Some(CycleCommand::PeekEnd));
+    }
+}
@@ -8,22 +8,23 @@
 //! - May conflict with other applications using the same hotkeys
 //! - Some exotic key combinations may not work under XWayland
 
-use anyhow::{Context, Result};
-use std::collections::HashMap;
+use anyhow::{Context, Result, bail};
+use std::collections::{HashMap, HashSet};
 use std::os::unix::io::AsRawFd;
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
 use tracing::{debug, error, info, warn};
 use x11rb::connection::Connection;
-use x11rb::protocol::Event;
 use x11rb::protocol::xproto::*;
+use x11rb::protocol::{ErrorKind, Event};
 
 use x11rb::rust_connection::RustConnection;
 
 use crate::config::HotkeyBinding;
 use crate::input::backend::{
-    AllowedWindows, BackendCapabilities, HotkeyBackend, HotkeyConfiguration,
+    AllowedWindows, BackendCapabilities, HotkeyBackend, HotkeyConfiguration, PausedFlag,
 };
 use crate::input::listener::{CycleCommand, TimestampedCommand};
 
@@ -36,6 +37,7 @@ impl HotkeyBackend for X11Backend {
         _device_id: Option<String>, // Not used by X11 backend
         require_eve_focus: bool,
         allowed_windows: AllowedWindows,
+        paused: PausedFlag,
     ) -> Result<Vec<JoinHandle<()>>> {
         // Check if we have any hotkeys to register
         let has_cycle = !config.cycle_hotkeys.is_empty();
@@ -43,22 +45,50 @@ impl HotkeyBackend for X11Backend {
         let has_profile = !config.profile_hotkeys.is_empty();
         let has_skip = config.toggle_skip_key.is_some();
         let has_toggle_previews = config.toggle_previews_key.is_some();
-
-        if !has_cycle && !has_character && !has_profile && !has_skip && !has_toggle_previews {
+        let has_overview = config.overview_key.is_some();
+        let has_flip = config.flip_key.is_some();
+        let has_peek = config.peek_key.is_some();
+        let has_reveal = config.reveal_key.is_some();
+        let has_panic = config.panic_key.is_some();
+        let has_pause = config.pause_key.is_some();
+        let has_suspend = config.suspend_key.is_some();
+
+        if !has_cycle
+            && !has_character
+            && !has_profile
+            && !has_skip
+            && !has_toggle_previews
+            && !has_overview
+            && !has_flip
+            && !has_peek
+            && !has_reveal
+            && !has_panic
+            && !has_pause
+            && !has_suspend
+        {
             info!("No hotkeys configured - X11 listener will not be started");
             return Ok(Vec::new());
         }
 
-        debug!(
+        info!(
             has_cycle_keys = has_cycle,
             has_skip_key = has_skip,
             has_toggle_previews_key = has_toggle_previews,
+            has_overview_key = has_overview,
+            has_flip_key = has_flip,
+            has_peek_key = has_peek,
+            has_reveal_key = has_reveal,
+            has_panic_key = has_panic,
+            has_pause_key = has_pause,
+            has_suspend_key = has_suspend,
             character_hotkey_count = config.character_hotkeys.len(),
             "Starting X11 hotkey listener"
         );
 
         let handle = thread::spawn(move || {
-            if let Err(e) = run_x11_listener(sender, config, require_eve_focus, allowed_windows) {
+            if let Err(e) =
+                run_x11_listener(sender, config, require_eve_focus, allowed_windows, paused)
+            {
                 error!(error = %e, "X11 hotkey listener error");
             }
         });
@@ -92,6 +122,7 @@ fn run_x11_listener(
     config: HotkeyConfiguration,
     require_eve_focus: bool,
     allowed_windows: AllowedWindows,
+    paused: PausedFlag,
 ) -> Result<()> {
     // Connect to X11
     let (conn, screen_num) =
@@ -155,6 +186,120 @@ fn run_x11_listener(
         }
     }
 
+    // Register overview hotkey
+    if let Some(ref overview_key) = config.overview_key {
+        if let Some((keycode, modmask)) = evdev_to_x11_key(overview_key) {
+            register_hotkey(&conn, root, keycode, modmask)?;
+            hotkey_map.insert((keycode, modmask), CycleCommand::ToggleOverview);
+            debug!(
+                binding = %overview_key.display_name(),
+                x11_keycode = keycode,
+                modmask = ?modmask,
+                "Registered overview hotkey"
+            );
+        } else {
+            warn!(binding = %overview_key.display_name(), "Failed to map overview key to X11");
+        }
+    }
+
+    // Register panic hotkey
+    if let Some(ref panic_key) = config.panic_key {
+        if let Some((keycode, modmask)) = evdev_to_x11_key(panic_key) {
+            register_hotkey(&conn, root, keycode, modmask)?;
+            hotkey_map.insert((keycode, modmask), CycleCommand::Panic);
+            debug!(
+                binding = %panic_key.display_name(),
+                x11_keycode = keycode,
+                modmask = ?modmask,
+                "Registered panic hotkey"
+            );
+        } else {
+            warn!(binding = %panic_key.display_name(), "Failed to map panic key to X11");
+        }
+    }
+
+    // Register pause hotkey
+    if let Some(ref pause_key) = config.pause_key {
+        if let Some((keycode, modmask)) = evdev_to_x11_key(pause_key) {
+            register_hotkey(&conn, root, keycode, modmask)?;
+            hotkey_map.insert((keycode, modmask), CycleCommand::TogglePause);
+            debug!(
+                binding = %pause_key.display_name(),
+                x11_keycode = keycode,
+                modmask = ?modmask,
+                "Registered pause hotkey"
+            );
+        } else {
+            warn!(binding = %pause_key.display_name(), "Failed to map pause key to X11");
+        }
+    }
+
+    // Register flip hotkey
+    if let Some(ref flip_key) = config.flip_key {
+        if let Some((keycode, modmask)) = evdev_to_x11_key(flip_key) {
+            register_hotkey(&conn, root, keycode, modmask)?;
+            hotkey_map.insert((keycode, modmask), CycleCommand::FlipPrevious);
+            debug!(
+                binding = %flip_key.display_name(),
+                x11_keycode = keycode,
+                modmask = ?modmask,
+                "Registered flip hotkey"
+            );
+        } else {
+            warn!(binding = %flip_key.display_name(), "Failed to map flip key to X11");
+        }
+    }
+
+    // Register suspend hotkey
+    if let Some(ref suspend_key) = config.suspend_key {
+        if let Some((keycode, modmask)) = evdev_to_x11_key(suspend_key) {
+            register_hotkey(&conn, root, keycode, modmask)?;
+            hotkey_map.insert((keycode, modmask), CycleCommand::SuspendHotkeys);
+            debug!(
+                binding = %suspend_key.display_name(),
+                x11_keycode = keycode,
+                modmask = ?modmask,
+                "Registered suspend hotkey"
+            );
+        } else {
+            warn!(binding = %suspend_key.display_name(), "Failed to map suspend key to X11");
+        }
+    }
+
+    // Register hold hotkeys (peek, reveal), which also need the key release to end them
+    let mut release_commands: HashMap<Keycode, CycleCommand> = HashMap::new();
+    let hold_keys = [
+        (
+            &config.peek_key,
+            CycleCommand::PeekStart,
+            CycleCommand::PeekEnd,
+        ),
+        (
+            &config.reveal_key,
+            CycleCommand::RevealStart,
+            CycleCommand::RevealEnd,
+        ),
+    ];
+    for (key, start, end) in hold_keys {
+        let Some(binding) = key else {
+            continue;
+        };
+        if let Some((keycode, modmask)) = evdev_to_x11_key(binding) {
+            register_hotkey(&conn, root, keycode, modmask)?;
+            debug!(
+                binding = %binding.display_name(),
+                x11_keycode = keycode,
+                modmask = ?modmask,
+                command = ?start,
+                "Registered hold hotkey"
+            );
+            hotkey_map.insert((keycode, modmask), start);
+            release_commands.insert(keycode, end);
+        } else {
+            warn!(binding = %binding.display_name(), command = ?start, "Failed to map hold key to X11");
+        }
+    }
+
     // Register character hotkeys
     let character_hotkeys = Arc::new(config.character_hotkeys);
     for char_hotkey in character_hotkeys.iter() {
@@ -202,9 +347,13 @@ fn run_x11_listener(
         "X11 hotkeys registered, entering event loop"
     );
 
-    // Track whether hotkeys are currently grabbed
-    let mut hotkeys_grabbed = true;
+    // Track which hotkeys are currently grabbed
+    let mut grabbed = GrabState::All;
+    let mut manager_focused = false;
+    let mut blocked_focused = false;
     let mut last_focused_window: Option<Window> = None;
+    // Hold keys currently down (their release command is still owed)
+    let mut held_keys: HashSet<Keycode> = HashSet::new();
 
     // Get the raw file descriptor for poll()-based blocking
     let x11_fd = conn.stream().as_raw_fd();
@@ -237,9 +386,12 @@ fn run_x11_listener(
             while let Some(event) = conn.poll_for_event()? {
                 match event {
                     Event::KeyPress(key_event) => {
-                        // If hotkeys are not grabbed, this event shouldn't reach us
+                        // If the hotkey is not grabbed, this event shouldn't reach us
                         // But handle it anyway for robustness
-                        if !hotkeys_grabbed {
+                        let released = hotkey_map
+                            .get(&(key_event.detail, normalize_modmask(key_event.state)))
+                            .is_some_and(|command| !grabbed.includes(command));
+                        if grabbed == GrabState::None || released {
                             conn.allow_events(Allow::REPLAY_KEYBOARD, key_event.time)?;
                             conn.flush()?;
                             continue;
@@ -247,7 +399,10 @@ fn run_x11_listener(
 
                         // Hotkeys are grabbed, process normally
                         // Check if we need EVE focus OR Custom Source focus
-                        if require_eve_focus {
+                        let ignores_focus = hotkey_map
+                            .get(&(key_event.detail, normalize_modmask(key_event.state)))
+                            .is_some_and(CycleCommand::ignores_focus_requirement);
+                        if require_eve_focus && !ignores_focus {
                             let focus_cookie = conn.get_input_focus()?;
                             match focus_cookie.reply() {
                                 Ok(focus_reply) => {
@@ -334,6 +489,13 @@ fn run_x11_listener(
                                 "Hotkey pressed, sending command"
                             );
 
+                            // Auto-repeat re-sends the press while held; only start once
+                            if release_commands.contains_key(&key_event.detail)
+                                && !held_keys.insert(key_event.detail)
+                            {
+                                continue;
+                            }
+
                             let timestamped_command = TimestampedCommand {
                                 command: command.clone(),
                                 timestamp: key_event.time,
@@ -350,6 +512,30 @@ fn run_x11_listener(
                             );
                         }
                     }
+                    Event::KeyRelease(key_event) if held_keys.contains(&key_event.detail) => {
+                        // Auto-repeat generates release/press pairs while the key is still
+                        // physically held, so only a real release ends the hold
+                        if is_key_down(&conn, key_event.detail).unwrap_or(false) {
+                            continue;
+                        }
+
+                        held_keys.remove(&key_event.detail);
+                        let Some(command) = release_commands.get(&key_event.detail) else {
+                            continue;
+                        };
+                        debug!(
+                            keycode = key_event.detail,
+                            command = ?command,
+                            "Hold key released, sending command"
+                        );
+                        let timestamped_command = TimestampedCommand {
+                            command: command.clone(),
+                            timestamp: key_event.time,
+                        };
+                        if let Err(e) = sender.blocking_send(timestamped_command) {
+                            error!(error = %e, "Failed to send hotkey command");
+                        }
+                    }
                     Event::MappingNotify(_) => {
                         // Keyboard mapping changed, we should re-register hotkeys
                         // For now, just log it - full implementation would rebuild the map
@@ -373,30 +559,67 @@ fn run_x11_listener(
         if last_focused_window != Some(focused_window) {
             last_focused_window = Some(focused_window);
             let focused_class = get_window_class_sync(&conn, focused_window).unwrap_or_default();
-            let is_epm_focused = focused_class.eq_ignore_ascii_case("eve-preview-manager");
+            manager_focused = focused_class.eq_ignore_ascii_case("eve-preview-manager");
+            blocked_focused = config
+                .blocked_classes
+                .iter()
+                .map(|c| c.trim())
+                .any(|c| !c.is_empty() && c.eq_ignore_ascii_case(&focused_class));
+        }
 
-            // If Manager gained focus, ungrab hotkeys
-            if is_epm_focused && hotkeys_grabbed {
-                debug!("Manager gained focus, ungrabbing hotkeys to allow normal input");
-                for (keycode, modmask) in hotkey_map.keys() {
-                    ungrab_hotkey(&conn, root, *keycode, *modmask)?;
-                }
-                hotkeys_grabbed = false;
-                conn.flush()?;
-            }
-            // If Manager lost focus, regrab hotkeys
-            else if !is_epm_focused && !hotkeys_grabbed {
-                debug!("Manager lost focus, re-grabbing hotkeys");
-                for (keycode, modmask) in hotkey_map.keys() {
-                    register_hotkey(&conn, root, *keycode, *modmask)?;
+        // Release hotkeys while the Manager has focus (normal input) or the daemon is paused or
+        // suspended (only the pause and suspend keys stay grabbed so they can resume). Blocked
+        // apps get their keys back, except for the hotkeys that work in any focus context.
+        let wanted = if manager_focused {
+            GrabState::None
+        } else if paused.load(std::sync::atomic::Ordering::Relaxed) {
+            GrabState::PauseOnly
+        } else if blocked_focused {
+            GrabState::GlobalOnly
+        } else {
+            GrabState::All
+        };
+        if wanted != grabbed {
+            debug!(from = ?grabbed, to = ?wanted, "Changing grabbed hotkeys");
+            for (&(keycode, modmask), command) in &hotkey_map {
+                match (grabbed.includes(command), wanted.includes(command)) {
+                    (true, false) => ungrab_hotkey(&conn, root, keycode, modmask)?,
+                    (false, true) => register_hotkey(&conn, root, keycode, modmask)?,
+                    _ => {}
                 }
-                hotkeys_grabbed = true;
-                conn.flush()?;
             }
+            grabbed = wanted;
+            conn.flush()?;
         }
     }
 }
 
+/// Which registered hotkeys the listener currently holds grabs for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GrabState {
+    All,
+    PauseOnly,
+    GlobalOnly,
+    None,
+}
+
+impl GrabState {
+    fn includes(self, command: &CycleCommand) -> bool {
+        match self {
+            Self::All => true,
+            Self::PauseOnly => command.works_while_paused(),
+            Self::GlobalOnly => command.ignores_focus_requirement(),
+            Self::None => false,
+        }
+    }
+}
+
+/// Checks whether a key is physically held down right now
+fn is_key_down(conn: &RustConnection, keycode: Keycode) -> Result<bool> {
+    let keymap = conn.query_keymap()?.reply()?;
+    Ok(keymap.keys[keycode as usize / 8] & (1 << (keycode % 8)) != 0)
+}
+
 /// Helper to synchronously get window class
 fn get_window_class_sync(conn: &RustConnection, window: Window) -> Result<String> {
     let cookie = conn.get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 1024)?;
@@ -484,6 +707,63 @@ fn register_hotkey(
     Ok(())
 }
 
+/// Grabs `binding` on a fresh connection and fails if another client already holds it.
+/// Returns the connection (which keeps the grab until dropped) and the grabbed key.
+fn grab_exclusive(binding: &HotkeyBinding) -> Result<(RustConnection, Keycode, ModMask)> {
+    let (keycode, modmask) = evdev_to_x11_key(binding).with_context(|| {
+        format!(
+            "{} (evdev {}) has no X11 keycode",
+            binding.display_name(),
+            binding.key_code
+        )
+    })?;
+    let (conn, screen_num) = x11rb::connect(None).context("Failed to connect to X11")?;
+    let root = conn.setup().roots[screen_num].root;
+    register_hotkey(&conn, root, keycode, modmask)?;
+
+    // Unchecked grab errors arrive as events; the round trip makes sure they are queued
+    conn.get_input_focus()?.reply()?;
+    while let Some(event) = conn.poll_for_event()? {
+        if let Event::Error(e) = event
+            && e.error_kind == ErrorKind::Access
+        {
+            bail!(
+                "{} is already grabbed by another application",
+                binding.display_name()
+            );
+        }
+    }
+    Ok((conn, keycode, modmask))
+}
+
+/// Checks that `binding` can be grabbed: it maps to an X11 keycode and no other client holds it
+pub fn check_grab(binding: &HotkeyBinding) -> Result<()> {
+    grab_exclusive(binding).map(|_| ())
+}
+
+/// Grabs `binding` like the listener does and waits up to `timeout` for it to be pressed
+pub fn wait_for_binding(binding: &HotkeyBinding, timeout: Duration) -> Result<()> {
+    let (conn, keycode, modmask) = grab_exclusive(binding)?;
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        while let Some(event) = conn.poll_for_event()? {
+            if let Event::KeyPress(key_event) = event {
+                conn.allow_events(Allow::ASYNC_KEYBOARD, key_event.time)?;
+                conn.flush()?;
+                if key_event.detail == keycode && normalize_modmask(key_event.state) == modmask {
+                    return Ok(());
+                }
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    bail!(
+        "{} was not pressed within {} seconds",
+        binding.display_name(),
+        timeout.as_secs()
+    )
+}
+
 /// Normalize modifier mask by removing lock keys
 fn normalize_modmask(state: KeyButMask) -> ModMask {
     // Convert KeyButMask to u16 and back to ModMask, filtering out lock keys
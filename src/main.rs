@@ -5,11 +5,12 @@ mod config;
 mod daemon;
 mod input;
 mod manager;
+mod wayland;
 mod x11;
 
 use anyhow::Result;
 use clap::Parser;
-use tracing_subscriber::FmtSubscriber;
+use tracing_subscriber::prelude::*;
 
 #[derive(Parser, Debug)]
 #[command(name = "eve-preview-manager")]
@@ -33,6 +34,24 @@ enum Commands {
         #[arg(long)]
         ipc_server: String,
     },
+    /// Save the current frame of a running EVE client as a PNG file
+    Screenshot {
+        /// Character name, as shown in the client's window title
+        character: String,
+        /// Output file (defaults to "<character>.png")
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Pause the running Manager: hide all thumbnails and release hotkeys until resumed
+    Pause {
+        /// Resume instead when already paused
+        #[arg(long)]
+        toggle: bool,
+    },
+    /// Resume a paused Manager
+    Resume,
+    /// Print the running daemon's latest metrics sample as JSON
+    Metrics,
 }
 
 fn main() -> Result<()> {
@@ -49,7 +68,12 @@ fn main() -> Result<()> {
     let filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(filter_directives));
 
-    let subscriber = FmtSubscriber::builder().with_env_filter(filter).finish();
+    // The round-trip audit counts x11rb's debug spans, so the log filter applies per layer
+    let roundtrip_audit =
+        matches!(cli.command, Some(Commands::Daemon { .. })) && daemon::roundtrip_audit::enabled();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(filter))
+        .with(roundtrip_audit.then(daemon::roundtrip_audit::layer));
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber");
 
     match cli.command {
@@ -68,6 +92,35 @@ fn main() -> Result<()> {
             });
             Ok(())
         }
+        Some(Commands::Screenshot { character, output }) => {
+            let output = output.unwrap_or_else(|| format!("{}.png", character).into());
+            let rules = config::profile::Config::load()?
+                .get_active_profile()
+                .map(|p| p.client_detection_rules.clone())
+                .unwrap_or_else(config::profile::default_client_detection_rules);
+            x11::save_client_screenshot(&character, &output, &rules)?;
+            println!("Saved {}", output.display());
+            Ok(())
+        }
+        Some(Commands::Pause { toggle }) => {
+            let command = if toggle {
+                manager::control_socket::ControlCommand::TogglePause
+            } else {
+                manager::control_socket::ControlCommand::Pause
+            };
+            println!("{}", manager::control_socket::send(command)?);
+            Ok(())
+        }
+        Some(Commands::Resume) => {
+            let command = manager::control_socket::ControlCommand::Resume;
+            println!("{}", manager::control_socket::send(command)?);
+            Ok(())
+        }
+        Some(Commands::Metrics) => {
+            let command = manager::control_socket::ControlCommand::Metrics;
+            println!("{}", manager::control_socket::send(command)?);
+            Ok(())
+        }
         None => {
             // Default mode: launch the configuration Manager which manages the daemon lifecycle
             if cli.debug {
@@ -5,24 +5,29 @@ use std::time::Duration;
 
 use anyhow::{Result, anyhow};
 use eframe::{NativeOptions, egui};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 #[cfg(target_os = "linux")]
 use ksni::TrayMethods;
 
 use super::components;
 use crate::common::constants::manager_ui::*;
+use crate::common::ipc::HotkeyTestResult;
 use crate::config::backup::BackupManager;
-use crate::config::profile::Config;
+use crate::config::profile::{Config, ManagerTheme, UiDensity};
 use crate::manager::components::profile_selector::{ProfileAction, ProfileSelector};
 #[cfg(target_os = "linux")]
 use crate::manager::components::tray::AppTray;
+use crate::manager::control_socket::ControlSocket;
 use crate::manager::state::core::SaveMode;
 use crate::manager::state::{ManagerTab, SharedState, StatusMessage};
+use crate::manager::theme;
 use crate::manager::utils::load_window_icon;
 
 struct ManagerApp {
     state: Arc<Mutex<SharedState>>,
+    /// Receives CLI commands (pause/resume); kept alive for the lifetime of the window
+    _control_socket: Option<ControlSocket>,
 
     // UI-only state (doesn't need to be shared deeply)
     profile_selector: ProfileSelector,
@@ -31,6 +36,14 @@ struct ManagerApp {
     visual_settings_state: components::visual_settings::VisualSettingsState,
     characters_state: components::characters::CharactersState,
     sources_state: components::sources::SourcesTab,
+    /// Theme and accent color currently applied to the egui context
+    applied_theme: Option<(ManagerTheme, String)>,
+    /// UI scale and font size currently applied
+    applied_scale: Option<(f32, f32)>,
+    /// Layout density currently applied
+    applied_density: Option<UiDensity>,
+    /// Set when the user closed the tray-less control panel for this session
+    control_panel_dismissed: bool,
     #[cfg(target_os = "linux")]
     shutdown_signal: std::sync::Arc<tokio::sync::Notify>,
     #[cfg(target_os = "linux")]
@@ -70,6 +83,7 @@ impl ManagerApp {
 
         // Initialize SharedState
         let mut state = SharedState::new(config.clone(), debug_mode);
+        state.sync_metrics_server();
         if let Err(err) = state.start_daemon() {
             error!(error = ?err, "Failed to start preview daemon");
             state.status_message = Some(StatusMessage {
@@ -80,6 +94,10 @@ impl ManagerApp {
         let state = Arc::new(Mutex::new(state));
         let state_clone = state.clone();
 
+        let control_socket = ControlSocket::start(state.clone(), cc.egui_ctx.clone())
+            .inspect_err(|e| warn!(error = %e, "CLI control commands unavailable"))
+            .ok();
+
         #[cfg(target_os = "linux")]
         let shutdown_signal = std::sync::Arc::new(tokio::sync::Notify::new());
         #[cfg(target_os = "linux")]
@@ -100,6 +118,8 @@ impl ManagerApp {
 
             runtime.block_on(async move {
                 let is_flatpak = std::env::var("FLATPAK_ID").is_ok();
+                let tray_state = state_clone.clone();
+                let tray_ctx = ctx.clone();
                 let tray = AppTray {
                     state: state_clone,
                     ctx,
@@ -135,7 +155,11 @@ impl ManagerApp {
                         }
                     }
                     Err(e) => {
-                        error!(error = ?e, "Failed to create tray icon (D-Bus unavailable?)");
+                        warn!(error = ?e, "Failed to create tray icon (no StatusNotifier tray?), showing the control panel");
+                        if let Ok(mut state) = tray_state.lock() {
+                            state.tray_available = false;
+                        }
+                        tray_ctx.request_repaint();
                     }
                 }
             });
@@ -158,6 +182,7 @@ impl ManagerApp {
         #[cfg(target_os = "linux")]
         let app = Self {
             state,
+            _control_socket: control_socket,
             shutdown_signal,
             update_signal,
             profile_selector: ProfileSelector::new(),
@@ -166,18 +191,27 @@ impl ManagerApp {
             visual_settings_state,
             characters_state,
             sources_state: components::sources::SourcesTab::default(),
+            applied_theme: None,
+            applied_scale: None,
+            applied_density: None,
+            control_panel_dismissed: false,
             active_tab: ManagerTab::Behavior,
         };
 
         #[cfg(not(target_os = "linux"))]
         let app = Self {
             state,
+            _control_socket: control_socket,
             profile_selector: ProfileSelector::new(),
             behavior_settings_state,
             hotkey_settings_state,
             visual_settings_state,
             characters_state,
             sources_state: components::sources::SourcesTab::default(),
+            applied_theme: None,
+            applied_scale: None,
+            applied_density: None,
+            control_panel_dismissed: false,
             active_tab: ManagerTab::Behavior,
         };
 
@@ -202,6 +236,45 @@ impl eframe::App for ManagerApp {
         let old_profile_idx = state.selected_profile_idx;
         state.poll_daemon();
 
+        // Re-apply the theme when it was changed in the settings (previewed before saving)
+        let wanted_theme = (
+            state.config.global.theme,
+            state.config.global.accent_color.clone(),
+        );
+        if self.applied_theme.as_ref() != Some(&wanted_theme) {
+            theme::apply(ctx, &state.config.global);
+            self.applied_theme = Some(wanted_theme);
+        }
+
+        // Keyboard zoom (Ctrl +/-) changes egui's zoom factor directly; keep it as the setting
+        let zoom = ctx.zoom_factor();
+        if let Some((scale, _)) = self.applied_scale
+            && (zoom - scale).abs() > f32::EPSILON
+        {
+            state.config.global.ui_scale = theme::clamp_scale(zoom);
+        }
+        let wanted_scale = (
+            state.config.global.ui_scale,
+            state.config.global.ui_font_size,
+        );
+        if self.applied_scale != Some(wanted_scale) {
+            theme::apply_scale(ctx, &state.config.global);
+            self.applied_scale = Some((ctx.zoom_factor(), wanted_scale.1));
+        }
+        if self.applied_density != Some(state.config.global.ui_density) {
+            theme::apply_density(ctx, state.config.global.ui_density);
+            self.applied_density = Some(state.config.global.ui_density);
+        }
+
+        if let Some(fallback) = state.fallback_previews.as_mut() {
+            fallback.show(ctx, &state.config.profiles[state.selected_profile_idx]);
+        }
+
+        // Without a tray the core controls live in a small floating window instead
+        if !state.tray_available && !self.control_panel_dismissed {
+            self.control_panel_dismissed = !components::control_panel::show(ctx, state);
+        }
+
         #[cfg(target_os = "linux")]
         if state.selected_profile_idx != old_profile_idx {
             self.update_signal.notify_one();
@@ -212,13 +285,21 @@ impl eframe::App for ManagerApp {
         let viewport_info = ctx.input(|i| i.viewport().clone());
 
         // Try to get window size from viewport inner_rect first, fall back to content_rect
+        // Rects are in zoomed UI points; the saved size is unzoomed so it survives scale changes
+        let zoom = ctx.zoom_factor();
         let (new_width, new_height) = if let Some(inner_rect) = viewport_info.inner_rect {
-            (inner_rect.width() as u16, inner_rect.height() as u16)
+            (
+                (inner_rect.width() * zoom) as u16,
+                (inner_rect.height() * zoom) as u16,
+            )
         } else {
             // Fallback for platforms where inner_rect is None (e.g., Wayland)
             // Use the content rect as window size
             let content_rect = ctx.content_rect();
-            (content_rect.width() as u16, content_rect.height() as u16)
+            (
+                (content_rect.width() * zoom) as u16,
+                (content_rect.height() * zoom) as u16,
+            )
         };
 
         // Update config if size changed (will be saved on exit)
@@ -292,8 +373,28 @@ impl eframe::App for ManagerApp {
         // Main Content Body
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
+                let profile_names: Vec<String> = state
+                    .config
+                    .profiles
+                    .iter()
+                    .map(|p| p.profile_name.clone())
+                    .collect();
+                let daemon_connected = state.daemon_connected();
                 let current_profile = &mut state.config.profiles[state.selected_profile_idx];
 
+                self.hotkey_settings_state
+                    .daemon_hotkeys
+                    .clone_from(&state.daemon_hotkeys);
+
+                // Locked profiles are read-only until explicitly unlocked from the profile bar
+                if current_profile.profile_locked {
+                    ui.colored_label(
+                        COLOR_WARNING,
+                        "🔒 This profile is locked. Unlock it to make changes.",
+                    );
+                    ui.disable();
+                }
+
                 match self.active_tab {
                     ManagerTab::Behavior => {
                         use components::behavior_settings::BehaviorSettingsAction;
@@ -302,6 +403,11 @@ impl eframe::App for ManagerApp {
                             current_profile,
                             &mut state.config.global,
                             &mut self.behavior_settings_state,
+                            &profile_names,
+                            &state.client_prefixes,
+                            &state.synthetic_input_log,
+                            daemon_connected,
+                            state.fleet_launch.is_some(),
                         ) {
                             BehaviorSettingsAction::SettingsChanged => {
                                 state.settings_changed = true;
@@ -318,6 +424,16 @@ impl eframe::App for ManagerApp {
                                     color: COLOR_SUCCESS,
                                 });
                             }
+                            BehaviorSettingsAction::SaveSnapshot(name) => {
+                                state.request_session_snapshot(&name);
+                            }
+                            BehaviorSettingsAction::RestoreSnapshot(idx) => {
+                                state.request_session_restore(idx);
+                            }
+                            BehaviorSettingsAction::LaunchFleet => state.start_fleet_launch(),
+                            BehaviorSettingsAction::CancelFleetLaunch => {
+                                state.cancel_fleet_launch();
+                            }
                             BehaviorSettingsAction::None => {}
                         }
                     }
@@ -347,10 +463,25 @@ impl eframe::App for ManagerApp {
                             current_profile,
                             &mut self.characters_state,
                             &mut self.hotkey_settings_state,
+                            state.client_layout.as_ref(),
+                            daemon_connected,
+                            &state.session_history,
                         ) {
                             state.settings_changed = true;
                             state.config_status_message = None;
                         }
+                        if self.characters_state.take_layout_request() {
+                            state.request_client_layout();
+                        }
+                        if let Some(character) = self.characters_state.take_frame_request()
+                            && !state.request_frame(&character)
+                        {
+                            let error = "Daemon is not connected".to_string();
+                            self.characters_state.show_frame(ctx, character, Err(error));
+                        }
+                        if let Some((character, png)) = state.captured_frame.take() {
+                            self.characters_state.show_frame(ctx, character, png);
+                        }
                     }
                     ManagerTab::Sources => {
                         if self.sources_state.ui(
@@ -361,8 +492,30 @@ impl eframe::App for ManagerApp {
                             state.settings_changed = true;
                             state.config_status_message = None;
                         }
+                        if let Some(patterns) = self.sources_state.take_match_request()
+                            && !state.request_window_matches(&patterns)
+                        {
+                            let error = "Daemon is not connected".to_string();
+                            self.sources_state.show_matches(patterns, Err(error));
+                        }
+                        if let Some((patterns, windows)) = state.window_matches.take() {
+                            self.sources_state.show_matches(patterns, Ok(windows));
+                        }
                     }
                 }
+
+                // The key capture dialog opens from several tabs
+                if let Some(binding) = self.hotkey_settings_state.take_test_request()
+                    && !state.request_hotkey_test(&binding)
+                {
+                    let error = "Daemon is not connected".to_string();
+                    self.hotkey_settings_state
+                        .show_test_result(&binding, HotkeyTestResult::Failed(error));
+                }
+                if let Some((binding, result)) = state.hotkey_test.take() {
+                    self.hotkey_settings_state
+                        .show_test_result(&binding, result);
+                }
             });
         });
 
@@ -374,6 +527,8 @@ impl eframe::App for ManagerApp {
             if let Err(err) = state.stop_daemon() {
                 error!(error = ?err, "Failed to stop daemon during shutdown");
             }
+            // Nothing polls after exit, so wait for the daemon here
+            state.reap_stopping_daemon(true);
             // Save config (merging daemon positions if needed, though daemon is stopped)
             // Just saving is enough as update loop keeps state.config fresh
             if let Err(err) = state.save_config(SaveMode::Implicit) {
@@ -1,17 +1,32 @@
 //! Behavior settings component (per-profile settings)
 
 use crate::common::constants::manager_ui::*;
+use crate::common::ipc::{SyntheticInput, WinePrefixInfo};
+use crate::common::types::{ClientDetectionRule, validate_title_pattern};
 use crate::config::backup::BackupManager;
-use crate::config::profile::{GlobalSettings, Profile};
+use crate::config::profile::{
+    GlobalSettings, LaunchEntry, PrefixProfileRule, Profile, VisibilityScheduleRule,
+};
+use crate::manager::components::reorderable_list::{self, ListEdit};
+use crate::manager::theme::density_spacing;
 
 use chrono::{DateTime, Local};
 use eframe::egui;
+use std::collections::{HashMap, VecDeque};
 
 #[derive(PartialEq)]
 pub enum BehaviorSettingsAction {
     None,
     SettingsChanged,
     RestoreTriggered,
+    /// Capture the running session under this name
+    SaveSnapshot(String),
+    /// Restore the active profile's session snapshot at this index
+    RestoreSnapshot(usize),
+    /// Start the launch order
+    LaunchFleet,
+    /// Stop a running fleet launch
+    CancelFleetLaunch,
 }
 
 /// State for behavior settings UI
@@ -22,6 +37,10 @@ pub struct BehaviorSettingsState {
     pub show_delete_confirm: bool, // For manual deletion
     pub status_message: Option<String>,
     pub status_type: Option<egui::Color32>,
+    /// Name typed for the next saved thumbnail layout
+    pub new_layout_name: String,
+    /// Name typed for the next session snapshot
+    pub new_snapshot_name: String,
 }
 
 impl BehaviorSettingsState {
@@ -33,6 +52,8 @@ impl BehaviorSettingsState {
             show_delete_confirm: false,
             status_message: None,
             status_type: None,
+            new_layout_name: String::new(),
+            new_snapshot_name: String::new(),
         }
     }
 
@@ -79,11 +100,17 @@ impl Default for BehaviorSettingsState {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn ui(
     ui: &mut egui::Ui,
     profile: &mut Profile,
     global: &mut GlobalSettings,
     state: &mut BehaviorSettingsState,
+    profile_names: &[String],
+    client_prefixes: &HashMap<String, WinePrefixInfo>,
+    synthetic_input: &VecDeque<SyntheticInput>,
+    snapshots_supported: bool,
+    fleet_launching: bool,
 ) -> BehaviorSettingsAction {
     let mut action = BehaviorSettingsAction::None;
 
@@ -91,7 +118,7 @@ pub fn ui(
         // Left Column: Behavior Settings
         columns[0].group(|ui| {
             ui.label(egui::RichText::new("Behavior Settings").strong());
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Minimize clients on switch
             if ui.checkbox(&mut profile.client_minimize_on_switch,
@@ -102,7 +129,22 @@ pub fn ui(
             if profile.client_minimize_on_switch {
                 ui.indent("minimize_overlay_indent", |ui| {
                     if ui.checkbox(&mut profile.client_minimize_show_overlay,
-                        "Show 'MINIMIZED' text overlay").changed() {
+                        "Show text overlay").changed() {
+                        action = BehaviorSettingsAction::SettingsChanged;
+                    }
+                    if render_minimized_placeholder(ui, profile) {
+                        action = BehaviorSettingsAction::SettingsChanged;
+                    }
+                    if ui.checkbox(&mut profile.client_minimize_freeze_frame,
+                        "Keep the last frame of minimized clients")
+                        .on_hover_text("Minimized clients show their last frame, dimmed, instead of a black preview")
+                        .changed() {
+                        action = BehaviorSettingsAction::SettingsChanged;
+                    }
+                    if ui.checkbox(&mut profile.client_minimize_alt_restore,
+                        "Keep the previous client up when switching back")
+                        .on_hover_text("Switching to a client that was minimized on switch leaves the client you came from unminimized, so two clients can be toggled between")
+                        .changed() {
                         action = BehaviorSettingsAction::SettingsChanged;
                     }
                 });
@@ -113,7 +155,29 @@ pub fn ui(
                 .small()
                 .weak());
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            if render_pointer_warp(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            if render_switch_osd(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if ui.checkbox(&mut profile.client_switch_speech,
+                "Speak character name on hotkey switch").changed() {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+            ui.label(egui::RichText::new(
+                "Announces the character switched to through speech-dispatcher (the screen reader voice), or espeak-ng if it is not installed")
+                .small()
+                .weak());
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Hide when no focus
             if ui.checkbox(&mut profile.thumbnail_hide_not_focused,
@@ -121,12 +185,32 @@ pub fn ui(
                 action = BehaviorSettingsAction::SettingsChanged;
             }
 
+            if profile.thumbnail_hide_not_focused {
+                ui.indent("hide_fade_indent", |ui| {
+                    ui.horizontal(|ui| {
+                        let hint = "Fade thumbnails out when they hide and back in when EVE regains focus (0 = instant)";
+                        ui.label("Fade duration:").on_hover_text(hint);
+                        if ui.add(egui::DragValue::new(&mut profile.thumbnail_hide_fade_ms)
+                            .range(0..=crate::common::constants::defaults::timing::FOCUS_LOSS_FADE_MAX_MS)
+                            .speed(10)
+                            .suffix(" ms"))
+                            .on_hover_text(hint)
+                            .changed() {
+                            action = BehaviorSettingsAction::SettingsChanged;
+                        }
+                    });
+                    if render_hide_exempt_classes(ui, profile) {
+                        action = BehaviorSettingsAction::SettingsChanged;
+                    }
+                });
+            }
+
             ui.label(egui::RichText::new(
                 "When enabled, thumbnails disappear when no EVE window is focused")
                 .small()
                 .weak());
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Auto-save thumbnail positions
             if ui.checkbox(
@@ -141,7 +225,7 @@ pub fn ui(
                 .small()
                 .weak());
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Cycle Group Reset Behavior
             if ui.checkbox(
@@ -156,7 +240,22 @@ pub fn ui(
                 .small()
                 .weak());
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            // Keep test-server clients out of group rotations
+            if ui.checkbox(
+                &mut profile.hotkey_cycle_exclude_test_servers,
+                "Skip test-server clients when cycling"
+            ).changed() {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.label(egui::RichText::new(
+                "Singularity, Thunderdome and Duality clients are left out of cycle groups (per-character hotkeys still work)")
+                .small()
+                .weak());
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Preserve thumbnail position on character swap
             if ui.checkbox(&mut profile.thumbnail_preserve_position_on_swap,
@@ -169,7 +268,40 @@ pub fn ui(
                 .small()
                 .weak());
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            // Ctrl+click passthrough
+            if ui.checkbox(&mut profile.thumbnail_click_passthrough,
+                "Ctrl+click passes through to the client").changed() {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.label(egui::RichText::new(
+                "Ctrl+left-click on a thumbnail clicks the same spot in the EVE client without switching to it")
+                .small()
+                .weak());
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if render_hover_zoom(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if ui.checkbox(&mut profile.thumbnail_hover_tooltip,
+                "Show details tooltip on hover").changed() {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+            ui.label(egui::RichText::new(
+                "Lists the full character name, account, minimized state and cycle position next to the hovered thumbnail")
+                .small()
+                .weak());
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if render_scroll_resize(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Snap threshold
             ui.horizontal(|ui| {
@@ -184,12 +316,57 @@ pub fn ui(
                 "Distance for edge/corner snapping (0 = disabled)")
                 .small()
                 .weak());
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if render_timing_section(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+            if render_detection_section(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            ui.separator();
+            if render_prefix_section(ui, profile, profile_names, client_prefixes) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            ui.separator();
+            if render_schedule_section(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            ui.separator();
+            if render_startup_section(ui, profile, &mut state.new_layout_name) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            ui.separator();
+            match render_snapshot_section(
+                ui,
+                profile,
+                &mut state.new_snapshot_name,
+                snapshots_supported,
+            ) {
+                BehaviorSettingsAction::None => {}
+                snapshot_action => action = snapshot_action,
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            ui.separator();
+            match render_launch_order_section(ui, profile, fleet_launching) {
+                BehaviorSettingsAction::None => {}
+                launch_action => action = launch_action,
+            }
         });
 
         // Right Column: Backup Settings
         columns[1].group(|ui| {
             ui.label(egui::RichText::new("Backup & Restore").strong());
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Auto Backup Settings
             if ui.checkbox(&mut global.backup_enabled, "Enable Automatic Backups").changed() {
@@ -216,9 +393,9 @@ pub fn ui(
 
             }
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
             ui.separator();
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Manual Backup
             ui.horizontal(|ui| {
@@ -235,11 +412,28 @@ pub fn ui(
                         }
                     }
                 }
+
+                if ui
+                    .button("🕶 Export Scrubbed Config")
+                    .on_hover_text("Save a copy for sharing, with character names, account names and file paths replaced")
+                    .clicked()
+                {
+                    match crate::config::scrub::export_scrubbed(None) {
+                        Ok(path) => {
+                            state.status_message = Some(format!("Exported to {}", path.display()));
+                            state.status_type = Some(COLOR_SUCCESS);
+                        }
+                        Err(e) => {
+                            state.status_message = Some(format!("Export failed: {}", e));
+                            state.status_type = Some(COLOR_ERROR);
+                        }
+                    }
+                }
             });
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
             ui.separator();
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Restore / Management
             ui.label("Configuration Backups");
@@ -343,9 +537,1371 @@ pub fn ui(
                  ui.label(egui::RichText::new(msg).color(color));
             }
         });
+
+        let gap = density_spacing(&columns[1], ITEM_SPACING);
+        columns[1].add_space(gap);
+        columns[1].group(|ui| {
+            if render_appearance(ui, global) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+        });
+
+        columns[1].add_space(gap);
+        columns[1].group(|ui| {
+            render_synthetic_input_log(ui, synthetic_input);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if render_capture_backend(ui, profile) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            if render_metrics_port(ui, global) {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+            if ui
+                .checkbox(
+                    &mut global.restore_last_focus,
+                    "Focus the last used client on launch",
+                )
+                .on_hover_text(
+                    "Activates the character that had focus when the manager last ran, if its \
+                     client is running. A profile's startup focus target takes precedence.",
+                )
+                .changed()
+            {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+        });
     });
 
-    ui.add_space(SECTION_SPACING);
+    ui.add_space(density_spacing(ui, SECTION_SPACING));
 
     action
 }
+
+/// Window classes whose focus keeps thumbnails shown. Returns true when changed.
+fn render_hide_exempt_classes(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    let mut changed = false;
+
+    ui.label("Keep thumbnails while these apps are focused (window class):")
+        .on_hover_text(
+            "e.g. discord, Mumble, firefox. Matched case-insensitively against WM_CLASS.",
+        );
+
+    let mut remove_idx = None;
+    for (idx, class) in profile.thumbnail_hide_exempt_classes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::TextEdit::singleline(class)
+                        .hint_text("window class")
+                        .desired_width(160.0),
+                )
+                .changed()
+            {
+                changed = true;
+            }
+            if ui.small_button("🗑").on_hover_text("Remove app").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        profile.thumbnail_hide_exempt_classes.remove(idx);
+        changed = true;
+    }
+
+    if ui.small_button("➕ Add App").clicked() {
+        profile.thumbnail_hide_exempt_classes.push(String::new());
+        changed = true;
+    }
+
+    changed
+}
+
+/// Advanced timers (hysteresis and debounces). Returns true when changed.
+fn render_timing_section(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::common::constants::defaults::timing;
+
+    let mut changed = false;
+    egui::CollapsingHeader::new("Advanced Timing")
+        .id_salt("advanced_timing")
+        .show(ui, |ui| {
+            egui::Grid::new("advanced_timing_grid")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    let rows = [
+                        (
+                            "Focus-loss delay:",
+                            "How long EVE must stay unfocused before thumbnails hide. \
+                             Raise it if thumbnails flicker on a slow window manager.",
+                            &mut profile.timing_focus_loss_delay_ms,
+                            0..=timing::FOCUS_LOSS_DELAY_MAX_MS,
+                        ),
+                        (
+                            "Position report debounce:",
+                            "Quiet period before a dragged thumbnail's position is sent to the Manager",
+                            &mut profile.timing_position_debounce_ms,
+                            0..=timing::POSITION_DEBOUNCE_MAX_MS,
+                        ),
+                        (
+                            "Auto-save delay:",
+                            "Quiet period before reported positions are written to disk",
+                            &mut profile.timing_auto_save_delay_ms,
+                            0..=timing::AUTO_SAVE_DELAY_MAX_MS,
+                        ),
+                        (
+                            "Damage repaint interval:",
+                            "Minimum time between repaints of a live thumbnail while its client \
+                             draws. Raise it to trade smoothness for X server CPU.",
+                            &mut profile.timing_damage_frame_ms,
+                            0..=timing::DAMAGE_FRAME_MAX_MS,
+                        ),
+                        (
+                            "Title re-check interval:",
+                            "How often a new window whose title doesn't name a client yet is \
+                             checked again",
+                            &mut profile.timing_identity_retry_interval_ms,
+                            timing::IDENTITY_RETRY_INTERVAL_MIN_MS..=timing::IDENTITY_RETRY_INTERVAL_MAX_MS,
+                        ),
+                        (
+                            "Title re-check window:",
+                            "How long after appearing such a window keeps being re-checked",
+                            &mut profile.timing_identity_retry_window_ms,
+                            0..=timing::IDENTITY_RETRY_WINDOW_MAX_MS,
+                        ),
+                    ];
+                    for (label, hint, value, range) in rows {
+                        ui.label(label).on_hover_text(hint);
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(value)
+                                    .range(range)
+                                    .speed(10)
+                                    .suffix(" ms"),
+                            )
+                            .on_hover_text(hint)
+                            .changed();
+                        ui.end_row();
+                    }
+                });
+
+            if ui.small_button("Reset to defaults").clicked() {
+                profile.timing_focus_loss_delay_ms = timing::FOCUS_LOSS_DELAY_MS;
+                profile.timing_position_debounce_ms = timing::POSITION_DEBOUNCE_MS;
+                profile.timing_auto_save_delay_ms = timing::AUTO_SAVE_DELAY_MS;
+                profile.timing_damage_frame_ms = timing::DAMAGE_FRAME_MS;
+                profile.timing_identity_retry_interval_ms = timing::IDENTITY_RETRY_INTERVAL_MS;
+                profile.timing_identity_retry_window_ms = timing::IDENTITY_RETRY_WINDOW_MS;
+                changed = true;
+            }
+        });
+    changed
+}
+
+/// Window title rules used to recognize game clients. Returns true when changed.
+fn render_detection_section(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    let mut changed = false;
+    egui::CollapsingHeader::new("Client Detection")
+        .id_salt("client_detection")
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(
+                    "Rules recognizing game windows, tried in order. A title starting with the \
+                     prefix is a logged-in client named by the rest of the title.",
+                )
+                .small()
+                .weak(),
+            );
+
+            let mut remove_idx = None;
+            for (idx, rule) in profile.client_detection_rules.iter_mut().enumerate() {
+                ui.push_id(idx, |ui| {
+                    ui.group(|ui| {
+                        egui::Grid::new("detection_rule_grid")
+                            .num_columns(2)
+                            .spacing([10.0, 4.0])
+                            .show(ui, |ui| {
+                                ui.label("Name:");
+                                changed |= ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut rule.name)
+                                            .desired_width(160.0),
+                                    )
+                                    .changed();
+                                ui.end_row();
+
+                                ui.label("Title prefix:");
+                                changed |= ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut rule.title_prefix)
+                                            .hint_text("EVE - ")
+                                            .desired_width(160.0),
+                                    )
+                                    .changed();
+                                ui.end_row();
+
+                                ui.label("Title patterns:").on_hover_text(
+                                    "Regexes for localized titles. The (?P<character>...) \
+                                     capture is the character name.",
+                                );
+                                ui.vertical(|ui| {
+                                    changed |= render_title_patterns(ui, &mut rule.title_patterns);
+                                });
+                                ui.end_row();
+
+                                ui.label("Logged-out title:")
+                                    .on_hover_text("Exact title at character select (optional)");
+                                changed |=
+                                    optional_text_edit(ui, &mut rule.logged_out_title, "EVE");
+                                ui.end_row();
+
+                                ui.label("Window class:").on_hover_text(
+                                    "Only match windows whose WM_CLASS contains this (optional)",
+                                );
+                                changed |= optional_text_edit(ui, &mut rule.class_pattern, "any");
+                                ui.end_row();
+
+                                ui.label("Ignored names:").on_hover_text(
+                                    "Comma-separated; titles whose name contains one are skipped",
+                                );
+                                let mut ignored = rule.ignored_names.join(",");
+                                if ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut ignored)
+                                            .desired_width(160.0),
+                                    )
+                                    .changed()
+                                {
+                                    // Kept verbatim (blanks included) so typing a comma works;
+                                    // matching trims and skips blank entries
+                                    rule.ignored_names =
+                                        ignored.split(',').map(str::to_string).collect();
+                                    changed = true;
+                                }
+                                ui.end_row();
+                            });
+                        if ui.small_button("🗑 Remove Rule").clicked() {
+                            remove_idx = Some(idx);
+                        }
+                    });
+                });
+            }
+            if let Some(idx) = remove_idx {
+                profile.client_detection_rules.remove(idx);
+                changed = true;
+            }
+
+            ui.horizontal(|ui| {
+                if ui.small_button("➕ Add Rule").clicked() {
+                    profile
+                        .client_detection_rules
+                        .push(ClientDetectionRule::default());
+                    changed = true;
+                }
+                if ui.small_button("Reset to defaults").clicked() {
+                    profile.client_detection_rules =
+                        crate::config::profile::default_client_detection_rules();
+                    changed = true;
+                }
+            });
+        });
+    changed
+}
+
+/// Regex list of one detection rule with inline validation. Returns true when changed.
+fn render_title_patterns(ui: &mut egui::Ui, patterns: &mut Vec<String>) -> bool {
+    let mut changed = false;
+    let mut remove_idx = None;
+    for (idx, pattern) in patterns.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(pattern)
+                        .hint_text("^星战前夜 - (?P<character>.+)$")
+                        .desired_width(160.0)
+                        .font(egui::TextStyle::Monospace),
+                )
+                .changed();
+            if ui
+                .small_button("🗑")
+                .on_hover_text("Remove pattern")
+                .clicked()
+            {
+                remove_idx = Some(idx);
+            }
+        });
+        if !pattern.is_empty()
+            && let Err(e) = validate_title_pattern(pattern)
+        {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+    }
+    if let Some(idx) = remove_idx {
+        patterns.remove(idx);
+        changed = true;
+    }
+    if ui.small_button("➕ Add Pattern").clicked() {
+        patterns.push(String::new());
+        changed = true;
+    }
+    changed
+}
+
+/// Single-line editor for an optional string (empty clears it). Returns true when changed.
+fn optional_text_edit(ui: &mut egui::Ui, value: &mut Option<String>, hint: &str) -> bool {
+    let mut text = value.clone().unwrap_or_default();
+    let changed = ui
+        .add(
+            egui::TextEdit::singleline(&mut text)
+                .hint_text(hint)
+                .desired_width(160.0),
+        )
+        .changed();
+    if changed {
+        *value = (!text.is_empty()).then_some(text);
+    }
+    changed
+}
+
+/// Manager window theme, accent color, scale, font size and density. Returns true when changed.
+fn render_appearance(ui: &mut egui::Ui, global: &mut GlobalSettings) -> bool {
+    use crate::common::constants::defaults::manager::{
+        UI_FONT_SIZE, UI_FONT_SIZE_MAX, UI_FONT_SIZE_MIN, UI_SCALE, UI_SCALE_MAX, UI_SCALE_MIN,
+    };
+    use crate::config::profile::{ManagerTheme, UiDensity};
+    use crate::manager::utils::{format_hex_color, parse_hex_color};
+
+    let label = |theme: ManagerTheme| match theme {
+        ManagerTheme::System => "Follow System",
+        ManagerTheme::Dark => "Dark",
+        ManagerTheme::Light => "Light",
+    };
+
+    let mut changed = false;
+    ui.label(egui::RichText::new("Appearance").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+    ui.horizontal(|ui| {
+        ui.label("Theme:");
+        egui::ComboBox::from_id_salt("manager_theme_selector")
+            .selected_text(label(global.theme))
+            .show_ui(ui, |ui| {
+                for theme in [
+                    ManagerTheme::System,
+                    ManagerTheme::Dark,
+                    ManagerTheme::Light,
+                ] {
+                    changed |= ui
+                        .selectable_value(&mut global.theme, theme, label(theme))
+                        .changed();
+                }
+            });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Accent Color:");
+        changed |= ui
+            .add(egui::TextEdit::singleline(&mut global.accent_color).desired_width(100.0))
+            .changed();
+
+        // Empty means egui's own accent; the picker starts from it
+        let mut color = parse_hex_color(&global.accent_color)
+            .unwrap_or_else(|_| ui.visuals().selection.bg_fill);
+        if ui.color_edit_button_srgba(&mut color).changed() {
+            global.accent_color = format_hex_color(color);
+            changed = true;
+        }
+        if !global.accent_color.is_empty() && ui.button("Default").clicked() {
+            global.accent_color.clear();
+            changed = true;
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("UI Scale:");
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut global.ui_scale, UI_SCALE_MIN..=UI_SCALE_MAX)
+                    .step_by(0.05)
+                    .fixed_decimals(2)
+                    .suffix("×"),
+            )
+            .on_hover_text("Also adjustable with Ctrl +/- and Ctrl 0")
+            .changed();
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Font Size:");
+        changed |= ui
+            .add(
+                egui::Slider::new(
+                    &mut global.ui_font_size,
+                    UI_FONT_SIZE_MIN..=UI_FONT_SIZE_MAX,
+                )
+                .step_by(0.5)
+                .suffix(" pt"),
+            )
+            .changed();
+        let customized = (global.ui_scale - UI_SCALE).abs() > f32::EPSILON
+            || (global.ui_font_size - UI_FONT_SIZE).abs() > f32::EPSILON;
+        if customized && ui.button("Reset").clicked() {
+            global.ui_scale = UI_SCALE;
+            global.ui_font_size = UI_FONT_SIZE;
+            changed = true;
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Density:");
+        changed |= ui
+            .radio_value(
+                &mut global.ui_density,
+                UiDensity::Comfortable,
+                "Comfortable",
+            )
+            .changed();
+        changed |= ui
+            .radio_value(&mut global.ui_density, UiDensity::Compact, "Compact")
+            .on_hover_text("Tighter spacing to fit tabs on small screens")
+            .changed();
+    });
+    ui.label(
+        egui::RichText::new("Applies to this window only, not to thumbnails.")
+            .small()
+            .weak(),
+    );
+    changed
+}
+
+/// Localhost port of the Prometheus metrics endpoint. Returns true when changed.
+fn render_metrics_port(ui: &mut egui::Ui, global: &mut GlobalSettings) -> bool {
+    let hint = "Serve daemon metrics in Prometheus format on 127.0.0.1 (0 = disabled). \
+                Applied on save.";
+    ui.horizontal(|ui| {
+        ui.label("Metrics port:").on_hover_text(hint);
+        ui.add(egui::DragValue::new(&mut global.metrics_port).range(0..=65535))
+            .on_hover_text(hint)
+            .changed()
+    })
+    .inner
+}
+
+/// Text, fill color and image drawn on minimized clients. Returns true when changed.
+fn render_minimized_placeholder(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::config::MinimizedImage;
+    use crate::manager::utils::{format_hex_color, parse_hex_color};
+
+    let mut changed = false;
+    ui.add_enabled_ui(profile.client_minimize_show_overlay, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Text:");
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut profile.client_minimize_text)
+                        .desired_width(150.0),
+                )
+                .changed();
+        });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Background:");
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut profile.client_minimize_background)
+                    .desired_width(100.0),
+            )
+            .changed();
+        if let Ok(mut color) = parse_hex_color(&profile.client_minimize_background)
+            && ui.color_edit_button_srgba(&mut color).changed()
+        {
+            profile.client_minimize_background = format_hex_color(color);
+            changed = true;
+        }
+    });
+
+    let label = |image: &MinimizedImage| match image {
+        MinimizedImage::None => "None",
+        MinimizedImage::CornerIcon => "Corner icon",
+        MinimizedImage::Custom { .. } => "PNG file",
+    };
+    ui.horizontal(|ui| {
+        ui.label("Image:");
+        egui::ComboBox::from_id_salt("minimized_image_selector")
+            .selected_text(label(&profile.client_minimize_image))
+            .show_ui(ui, |ui| {
+                for image in [
+                    MinimizedImage::None,
+                    MinimizedImage::CornerIcon,
+                    MinimizedImage::Custom {
+                        path: String::new(),
+                    },
+                ] {
+                    let selected = std::mem::discriminant(&profile.client_minimize_image)
+                        == std::mem::discriminant(&image);
+                    if ui.selectable_label(selected, label(&image)).clicked() && !selected {
+                        profile.client_minimize_image = image;
+                        changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Corner icon uses each character's portrait or custom icon");
+        if let MinimizedImage::Custom { path } = &mut profile.client_minimize_image {
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(path)
+                        .hint_text("/path/to/image.png")
+                        .desired_width(180.0),
+                )
+                .changed();
+        }
+    });
+    changed
+}
+
+/// Pointer warp on hotkey activation. Returns true when changed.
+fn render_pointer_warp(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::config::PointerWarpMode;
+
+    let label = |mode: PointerWarpMode| match mode {
+        PointerWarpMode::Off => "Off",
+        PointerWarpMode::Center => "Client center",
+        PointerWarpMode::Remembered => "Last position in client",
+    };
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Move pointer on hotkey switch:");
+        egui::ComboBox::from_id_salt("pointer_warp_selector")
+            .selected_text(label(profile.client_pointer_warp))
+            .show_ui(ui, |ui| {
+                for mode in [
+                    PointerWarpMode::Off,
+                    PointerWarpMode::Center,
+                    PointerWarpMode::Remembered,
+                ] {
+                    changed |= ui
+                        .selectable_value(&mut profile.client_pointer_warp, mode, label(mode))
+                        .changed();
+                }
+            });
+    });
+    ui.label(
+        egui::RichText::new(
+            "For EVE UI that depends on the mouse position. Last position is recorded whenever \
+             you switch away from a client and falls back to the center for clients not left \
+             yet this session.",
+        )
+        .small()
+        .weak(),
+    );
+    changed
+}
+
+/// Switch OSD on hotkey or click activation. Returns true when changed.
+fn render_switch_osd(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::config::SwitchOsdMode;
+
+    let label = |mode: SwitchOsdMode| match mode {
+        SwitchOsdMode::Off => "Off",
+        SwitchOsdMode::Cursor => "Next to the pointer",
+        SwitchOsdMode::Center => "Center of the client's monitor",
+    };
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Show switch OSD:");
+        egui::ComboBox::from_id_salt("switch_osd_selector")
+            .selected_text(label(profile.client_switch_osd))
+            .show_ui(ui, |ui| {
+                for mode in [
+                    SwitchOsdMode::Off,
+                    SwitchOsdMode::Cursor,
+                    SwitchOsdMode::Center,
+                ] {
+                    changed |= ui
+                        .selectable_value(&mut profile.client_switch_osd, mode, label(mode))
+                        .changed();
+                }
+            });
+    });
+    ui.label(
+        egui::RichText::new(
+            "Briefly shows the character switched to and its place in the cycle group, \
+             e.g. \"→ Alice [3/7]\", and confirms profile switches, pause, hidden previews, \
+             hotkey suspension and skip toggles. Needs a compositor to fade out smoothly.",
+        )
+        .small()
+        .weak(),
+    );
+    changed
+}
+
+/// Zoom factor and anchor of hovered thumbnails. Returns true when changed.
+fn render_hover_zoom(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::config::HoverZoomAnchor;
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Zoom on hover:");
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut profile.thumbnail_hover_zoom, 1.0..=4.0)
+                    .step_by(0.25)
+                    .suffix("×"),
+            )
+            .changed();
+    });
+
+    let zoom_enabled = profile.thumbnail_hover_zoom > 1.0;
+    ui.add_enabled_ui(zoom_enabled, |ui| {
+        ui.horizontal(|ui| {
+            let label = |anchor: HoverZoomAnchor| match anchor {
+                HoverZoomAnchor::Center => "Center",
+                HoverZoomAnchor::TopLeft => "Top left",
+                HoverZoomAnchor::TopRight => "Top right",
+                HoverZoomAnchor::BottomLeft => "Bottom left",
+                HoverZoomAnchor::BottomRight => "Bottom right",
+            };
+            ui.label("Grow from:");
+            egui::ComboBox::from_id_salt("hover_zoom_anchor_selector")
+                .selected_text(label(profile.thumbnail_hover_zoom_anchor))
+                .show_ui(ui, |ui| {
+                    for anchor in [
+                        HoverZoomAnchor::Center,
+                        HoverZoomAnchor::TopLeft,
+                        HoverZoomAnchor::TopRight,
+                        HoverZoomAnchor::BottomLeft,
+                        HoverZoomAnchor::BottomRight,
+                    ] {
+                        changed |= ui
+                            .selectable_value(
+                                &mut profile.thumbnail_hover_zoom_anchor,
+                                anchor,
+                                label(anchor),
+                            )
+                            .changed();
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Hover delay:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut profile.thumbnail_hover_zoom_delay_ms)
+                        .range(
+                            0..=crate::common::constants::defaults::timing::HOVER_ZOOM_DELAY_MAX_MS,
+                        )
+                        .speed(10)
+                        .suffix(" ms"),
+                )
+                .on_hover_text(
+                    "How long the mouse must rest on a thumbnail before it zooms (0 = immediately)",
+                )
+                .changed();
+        });
+    });
+    ui.label(
+        egui::RichText::new(
+            "Enlarges and raises a thumbnail while the mouse is over it and restores it on \
+             leave (1× = off). The anchor stays in place; the rest grows away from it. A hover \
+             delay keeps edge-to-edge thumbnails from zooming as the mouse passes over them.",
+        )
+        .small()
+        .weak(),
+    );
+    changed
+}
+
+/// Scroll-wheel resize step and aspect lock. Returns true when changed.
+fn render_scroll_resize(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Scroll to resize:");
+        changed |= ui
+            .add(
+                egui::DragValue::new(&mut profile.thumbnail_scroll_resize_step)
+                    .range(
+                        0..=crate::common::constants::defaults::thumbnail::SCROLL_RESIZE_STEP_MAX,
+                    )
+                    .suffix(" px"),
+            )
+            .on_hover_text("Width change per scroll-wheel notch (0 = off)")
+            .changed();
+    });
+    ui.add_enabled_ui(profile.thumbnail_scroll_resize_step > 0, |ui| {
+        changed |= ui
+            .checkbox(
+                &mut profile.thumbnail_scroll_resize_lock_aspect,
+                "Keep default thumbnail aspect ratio",
+            )
+            .changed();
+    });
+    ui.label(
+        egui::RichText::new(
+            "Scrolling over a thumbnail grows or shrinks it (0 px = off). The new size is saved \
+             like a dragged position.",
+        )
+        .small()
+        .weak(),
+    );
+    changed
+}
+
+/// Thumbnail capture backend override, scaling filter and vsync. Returns true when changed.
+fn render_capture_backend(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::config::{CaptureBackendType, ScaleFilter};
+
+    let label = |backend: CaptureBackendType| match backend {
+        CaptureBackendType::Auto => "Automatic (Recommended)",
+        CaptureBackendType::Composite => "XComposite",
+        CaptureBackendType::Screenshot => "Screenshot (Slow)",
+        CaptureBackendType::Gpu => "OpenGL (GPU)",
+        CaptureBackendType::Shm => "MIT-SHM (Local Only)",
+    };
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Capture Backend:");
+        egui::ComboBox::from_id_salt("capture_backend_selector")
+            .selected_text(label(profile.thumbnail_capture_backend))
+            .show_ui(ui, |ui| {
+                for backend in [
+                    CaptureBackendType::Auto,
+                    CaptureBackendType::Composite,
+                    CaptureBackendType::Shm,
+                    CaptureBackendType::Screenshot,
+                    CaptureBackendType::Gpu,
+                ] {
+                    changed |= ui
+                        .selectable_value(
+                            &mut profile.thumbnail_capture_backend,
+                            backend,
+                            label(backend),
+                        )
+                        .changed();
+                }
+            });
+    });
+    ui.label(
+        egui::RichText::new(
+            "Forces how thumbnails copy client contents, for debugging blank or corrupt previews. \
+             OpenGL scales on the GPU, which helps with 20+ clients. \
+             MIT-SHM copies through shared memory, for servers without working RENDER scaling. \
+             Unsupported choices fall back to automatic.",
+        )
+        .small()
+        .weak(),
+    );
+
+    ui.add_space(density_spacing(ui, ITEM_SPACING));
+    let filter_label = |filter: ScaleFilter| match filter {
+        ScaleFilter::Nearest => "Nearest (Fastest)",
+        ScaleFilter::Bilinear => "Bilinear (Recommended)",
+        ScaleFilter::Good => "Good",
+        ScaleFilter::Best => "Best (Slowest)",
+    };
+    ui.horizontal(|ui| {
+        ui.label("Scaling Filter:");
+        egui::ComboBox::from_id_salt("scale_filter_selector")
+            .selected_text(filter_label(profile.thumbnail_scale_filter))
+            .show_ui(ui, |ui| {
+                for filter in [
+                    ScaleFilter::Nearest,
+                    ScaleFilter::Bilinear,
+                    ScaleFilter::Good,
+                    ScaleFilter::Best,
+                ] {
+                    changed |= ui
+                        .selectable_value(
+                            &mut profile.thumbnail_scale_filter,
+                            filter,
+                            filter_label(filter),
+                        )
+                        .changed();
+                }
+            })
+            .response
+            .on_hover_text(
+                "How the X server shrinks clients into thumbnails (XComposite capture). \
+                 Nearest is cheapest but small thumbnails shimmer. Bilinear shrinks in two \
+                 passes when a thumbnail is under a third of its client, at a little extra \
+                 X server work. Good and Best average every client pixel and look \
+                 smoothest, but cost noticeably more X server CPU with many clients.",
+            );
+    });
+
+    ui.add_space(density_spacing(ui, ITEM_SPACING));
+    changed |= ui
+        .checkbox(
+            &mut profile.thumbnail_vsync,
+            "Sync Thumbnails to Display Refresh",
+        )
+        .changed();
+    ui.label(
+        egui::RichText::new(
+            "Presents repaints at vblank through the X Present extension to stop tearing on \
+             fast-moving previews, at up to one frame of extra latency.",
+        )
+        .small()
+        .weak(),
+    );
+    changed
+}
+
+/// Audit log of synthetic input the daemon sent to clients (newest first)
+fn render_synthetic_input_log(ui: &mut egui::Ui, log: &VecDeque<SyntheticInput>) {
+    ui.label(egui::RichText::new("Diagnostics").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+    egui::CollapsingHeader::new(format!("Synthetic Input Log ({})", log.len()))
+        .id_salt("synthetic_input_log")
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(
+                    "Events the daemon injected into clients. Anything that would repeat one action \
+                     into a second client is refused.",
+                )
+                .small()
+                .weak(),
+            );
+
+            if log.is_empty() {
+                ui.label(egui::RichText::new("Nothing sent yet").small().weak());
+                return;
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("synthetic_input_grid")
+                        .num_columns(3)
+                        .spacing([10.0, 2.0])
+                        .show(ui, |ui| {
+                            for entry in log.iter().rev() {
+                                let time = DateTime::from_timestamp_millis(entry.unix_ms as i64)
+                                    .map(|t| t.with_timezone(&Local).format("%H:%M:%S").to_string())
+                                    .unwrap_or_default();
+                                ui.label(egui::RichText::new(time).monospace());
+                                ui.label(format!("{} → 0x{:x}", entry.kind.label(), entry.window));
+                                if entry.refused {
+                                    ui.label(egui::RichText::new("REFUSED").color(COLOR_ERROR));
+                                } else {
+                                    ui.label(egui::RichText::new("sent").weak());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+}
+
+/// Wine prefixes of running clients and the per-prefix profile rules.
+/// Returns true if the rules changed.
+fn render_prefix_section(
+    ui: &mut egui::Ui,
+    profile: &mut Profile,
+    profile_names: &[String],
+    client_prefixes: &HashMap<String, WinePrefixInfo>,
+) -> bool {
+    let mut changed = false;
+
+    ui.label(egui::RichText::new("Wine Prefixes").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+    if client_prefixes.is_empty() {
+        ui.label(
+            egui::RichText::new("No running clients reported a Wine prefix")
+                .small()
+                .weak(),
+        );
+    } else {
+        let mut names: Vec<&String> = client_prefixes.keys().collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        egui::Grid::new("client_prefix_grid")
+            .num_columns(2)
+            .spacing([10.0, 2.0])
+            .show(ui, |ui| {
+                for name in names {
+                    let info = &client_prefixes[name];
+                    if info.server.is_test_server() {
+                        ui.label(format!("{} [{}]", name, info.server.badge()));
+                    } else {
+                        ui.label(name.as_str());
+                    }
+                    let prefix = info.prefix.as_deref().unwrap_or("unknown");
+                    let label = match &info.proton {
+                        Some(proton) => format!("{} ({})", prefix, proton),
+                        None => prefix.to_string(),
+                    };
+                    let response = ui.label(egui::RichText::new(label).monospace().small());
+                    if let Some(library) = &info.steam_library {
+                        response.on_hover_text(format!("Steam library: {}", library));
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+    ui.label("Switch profile when a client from this prefix is focused:");
+
+    let mut remove_idx = None;
+    for (idx, rule) in profile.prefix_profile_rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut rule.prefix_pattern)
+                        .hint_text("path contains…")
+                        .desired_width(160.0),
+                )
+                .changed()
+            {
+                changed = true;
+            }
+            ui.label("→");
+            egui::ComboBox::from_id_salt(("prefix_rule_profile", idx))
+                .selected_text(rule.profile.as_str())
+                .show_ui(ui, |ui| {
+                    for name in profile_names {
+                        if ui
+                            .selectable_value(&mut rule.profile, name.clone(), name)
+                            .changed()
+                        {
+                            changed = true;
+                        }
+                    }
+                });
+            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        profile.prefix_profile_rules.remove(idx);
+        changed = true;
+    }
+
+    if ui.button("➕ Add Prefix Rule").clicked() {
+        profile.prefix_profile_rules.push(PrefixProfileRule {
+            prefix_pattern: String::new(),
+            profile: profile.profile_name.clone(),
+        });
+        changed = true;
+    }
+
+    changed
+}
+
+/// Time windows during which previews are hidden. Returns true when changed.
+fn render_schedule_section(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    const DAY_LABELS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+    let mut changed = false;
+
+    ui.label(egui::RichText::new("Visibility Schedule").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+    ui.label(
+        egui::RichText::new(
+            "Hide all previews during these times. The toggle previews hotkey overrides the schedule until its next start or end.",
+        )
+        .small()
+        .weak(),
+    );
+
+    let mut remove_idx = None;
+    for (idx, rule) in profile.visibility_schedule.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            for (day, label) in rule.days.iter_mut().zip(DAY_LABELS) {
+                if ui.selectable_label(*day, label).clicked() {
+                    *day = !*day;
+                    changed = true;
+                }
+            }
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            changed |= time_of_day_edit(ui, &mut rule.start_minute);
+            ui.label("–");
+            changed |= time_of_day_edit(ui, &mut rule.end_minute);
+            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        profile.visibility_schedule.remove(idx);
+        changed = true;
+    }
+
+    if ui.button("➕ Add Schedule Rule").clicked() {
+        profile
+            .visibility_schedule
+            .push(VisibilityScheduleRule::default());
+        changed = true;
+    }
+
+    changed
+}
+
+/// Saved thumbnail layouts and the actions run when the daemon starts with this profile.
+/// Returns true when changed.
+fn render_startup_section(
+    ui: &mut egui::Ui,
+    profile: &mut Profile,
+    new_layout_name: &mut String,
+) -> bool {
+    let mut changed = false;
+
+    ui.label(egui::RichText::new("Startup Actions").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+    ui.label(
+        egui::RichText::new(
+            "Run once the daemon has found the running clients, i.e. on start and on every switch to this profile.",
+        )
+        .small()
+        .weak(),
+    );
+
+    ui.label("Layouts:");
+    if !profile.thumbnail_layouts.is_empty() {
+        ui.label(
+            egui::RichText::new("Right-click a handle to delete a layout.")
+                .weak()
+                .small(),
+        );
+    }
+    let edit = reorderable_list::show(
+        ui,
+        "thumbnail_layouts",
+        &mut profile.thumbnail_layouts,
+        |ui, _, layout| {
+            ui.label(format!(
+                "{} ({} thumbnails)",
+                layout.name,
+                layout.thumbnails.len()
+            ));
+        },
+    );
+    if let Some(edit) = edit {
+        // A startup action can't apply a layout that no longer exists
+        if let ListEdit::Delete(idx) = edit
+            && profile
+                .thumbnail_layouts
+                .get(idx)
+                .is_some_and(|l| l.name == profile.startup_actions.apply_layout)
+        {
+            profile.startup_actions.apply_layout.clear();
+        }
+        changed |= edit.apply(&mut profile.thumbnail_layouts);
+    }
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(new_layout_name)
+                .hint_text("layout name")
+                .desired_width(140.0),
+        );
+        let name = new_layout_name.trim().to_string();
+        if ui
+            .add_enabled(!name.is_empty(), egui::Button::new("➕ Save Current Positions"))
+            .on_hover_text("Save every character's current thumbnail geometry (replaces a layout with the same name)")
+            .clicked()
+        {
+            let layout = profile.capture_layout(&name);
+            profile.thumbnail_layouts.retain(|l| l.name != name);
+            profile.thumbnail_layouts.push(layout);
+            new_layout_name.clear();
+            changed = true;
+        }
+    });
+
+    let layout_names: Vec<String> = profile
+        .thumbnail_layouts
+        .iter()
+        .map(|l| l.name.clone())
+        .collect();
+    let mut characters: Vec<String> = profile.character_thumbnails.keys().cloned().collect();
+    characters.sort_by_key(|name| name.to_lowercase());
+
+    let actions = &mut profile.startup_actions;
+    egui::Grid::new("startup_actions_grid")
+        .num_columns(2)
+        .spacing([10.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Apply layout:");
+            ui.horizontal(|ui| {
+                changed |= name_combo(
+                    ui,
+                    "startup_layout",
+                    &mut actions.apply_layout,
+                    &layout_names,
+                );
+                if !actions.apply_layout.is_empty() && !layout_names.contains(&actions.apply_layout)
+                {
+                    ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                        .on_hover_text("No saved layout has this name");
+                }
+            });
+            ui.end_row();
+
+            ui.label("Minimize all except:");
+            changed |= name_combo(
+                ui,
+                "startup_minimize",
+                &mut actions.minimize_all_except,
+                &characters,
+            );
+            ui.end_row();
+
+            ui.label("Focus character:");
+            changed |= name_combo(
+                ui,
+                "startup_focus",
+                &mut actions.focus_character,
+                &characters,
+            );
+            ui.end_row();
+
+            ui.label("Previews:");
+            let label = |value: Option<bool>| match value {
+                None => "Unchanged",
+                Some(true) => "Show",
+                Some(false) => "Hide",
+            };
+            egui::ComboBox::from_id_salt("startup_previews")
+                .selected_text(label(actions.previews))
+                .show_ui(ui, |ui| {
+                    for value in [None, Some(true), Some(false)] {
+                        changed |= ui
+                            .selectable_value(&mut actions.previews, value, label(value))
+                            .changed();
+                    }
+                });
+            ui.end_row();
+        });
+
+    changed
+}
+
+/// Saved session snapshots with restore / delete buttons and a field to capture a new one
+fn render_snapshot_section(
+    ui: &mut egui::Ui,
+    profile: &mut Profile,
+    new_snapshot_name: &mut String,
+    supported: bool,
+) -> BehaviorSettingsAction {
+    let mut action = BehaviorSettingsAction::None;
+
+    ui.label(egui::RichText::new("Session Snapshots").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+    ui.label(
+        egui::RichText::new(
+            "Running characters with their client geometry, minimized state, focus and thumbnails.",
+        )
+        .small()
+        .weak(),
+    );
+
+    let mut remove_idx = None;
+    for (idx, snapshot) in profile.session_snapshots.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let saved_at = DateTime::from_timestamp(snapshot.saved_at as i64, 0)
+                .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            ui.label(format!(
+                "{} ({} clients, {})",
+                snapshot.name,
+                snapshot.clients.len(),
+                saved_at
+            ))
+            .on_hover_text(
+                snapshot
+                    .clients
+                    .iter()
+                    .map(|c| c.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            if ui
+                .add_enabled(supported, egui::Button::new("Restore").small())
+                .on_hover_text("Move clients and thumbnails back to where they were saved")
+                .clicked()
+            {
+                action = BehaviorSettingsAction::RestoreSnapshot(idx);
+            }
+            if ui
+                .small_button("🗑")
+                .on_hover_text("Remove snapshot")
+                .clicked()
+            {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        profile.session_snapshots.remove(idx);
+        action = BehaviorSettingsAction::SettingsChanged;
+    }
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(new_snapshot_name)
+                .hint_text("snapshot name")
+                .desired_width(140.0),
+        );
+        let name = new_snapshot_name.trim().to_string();
+        if ui
+            .add_enabled(
+                supported && !name.is_empty(),
+                egui::Button::new("📷 Save Session Snapshot"),
+            )
+            .on_hover_text("Capture the running clients (replaces a snapshot with the same name)")
+            .on_disabled_hover_text("Needs a running daemon")
+            .clicked()
+        {
+            action = BehaviorSettingsAction::SaveSnapshot(name);
+            new_snapshot_name.clear();
+        }
+    });
+
+    action
+}
+
+/// Launch order editor with the Launch Fleet / Cancel button
+fn render_launch_order_section(
+    ui: &mut egui::Ui,
+    profile: &mut Profile,
+    fleet_launching: bool,
+) -> BehaviorSettingsAction {
+    let mut action = BehaviorSettingsAction::None;
+
+    ui.label(egui::RichText::new("Launch Order").strong());
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+    ui.label(
+        egui::RichText::new(
+            "Launch Fleet runs each command in turn, waiting for the character to log in and then for the next entry's delay.",
+        )
+        .small()
+        .weak(),
+    );
+
+    let mut characters: Vec<String> = profile.character_thumbnails.keys().cloned().collect();
+    characters.sort_by_key(|name| name.to_lowercase());
+
+    let edit = reorderable_list::show(
+        ui,
+        "launch_order",
+        &mut profile.launch_order,
+        |ui, idx, entry| {
+            ui.horizontal(|ui| {
+                if name_combo(
+                    ui,
+                    &format!("launch_character_{idx}"),
+                    &mut entry.character,
+                    &characters,
+                ) {
+                    action = BehaviorSettingsAction::SettingsChanged;
+                }
+                ui.label("after");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut entry.delay_secs)
+                            .range(0..=600)
+                            .suffix(" s"),
+                    )
+                    .on_hover_text("Wait after the previous character logged in")
+                    .changed()
+                {
+                    action = BehaviorSettingsAction::SettingsChanged;
+                }
+            });
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut entry.command)
+                        .hint_text("launch command, e.g. steam -applaunch 8500")
+                        .desired_width(f32::INFINITY),
+                )
+                .changed()
+            {
+                action = BehaviorSettingsAction::SettingsChanged;
+            }
+        },
+    );
+    if let Some(edit) = edit
+        && edit.apply(&mut profile.launch_order)
+    {
+        action = BehaviorSettingsAction::SettingsChanged;
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button("➕ Add Client").clicked() {
+            profile.launch_order.push(LaunchEntry::default());
+            action = BehaviorSettingsAction::SettingsChanged;
+        }
+        if fleet_launching {
+            if ui.button("⏹ Cancel Launch").clicked() {
+                action = BehaviorSettingsAction::CancelFleetLaunch;
+            }
+        } else {
+            let launchable = profile
+                .launch_order
+                .iter()
+                .any(|e| !e.character.is_empty() && !e.command.trim().is_empty());
+            if ui
+                .add_enabled(launchable, egui::Button::new("🚀 Launch Fleet"))
+                .on_hover_text("Start the clients in order; characters already running are skipped")
+                .on_disabled_hover_text("Add a client with a character and a command first")
+                .clicked()
+            {
+                action = BehaviorSettingsAction::LaunchFleet;
+            }
+        }
+    });
+
+    action
+}
+
+/// Combo box choosing one of `names`, with an empty value shown as "None". Returns true when changed.
+fn name_combo(ui: &mut egui::Ui, id: &str, value: &mut String, names: &[String]) -> bool {
+    let mut changed = false;
+    let selected = if value.is_empty() {
+        "None"
+    } else {
+        value.as_str()
+    };
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected.to_string())
+        .show_ui(ui, |ui| {
+            changed |= ui.selectable_value(value, String::new(), "None").changed();
+            for name in names {
+                changed |= ui.selectable_value(value, name.clone(), name).changed();
+            }
+        });
+    changed
+}
+
+/// Hour/minute editor for a time stored as minutes after midnight. Returns true when changed.
+fn time_of_day_edit(ui: &mut egui::Ui, minutes: &mut u16) -> bool {
+    let mut hour = *minutes / 60 % 24;
+    let mut minute = *minutes % 60;
+    let two_digits = |v: f64, _| format!("{:02}", v as u16);
+
+    let mut changed = ui
+        .add(
+            egui::DragValue::new(&mut hour)
+                .range(0..=23)
+                .custom_formatter(two_digits),
+        )
+        .changed();
+    ui.label(":");
+    changed |= ui
+        .add(
+            egui::DragValue::new(&mut minute)
+                .range(0..=59)
+                .custom_formatter(two_digits),
+        )
+        .changed();
+
+    if changed {
+        *minutes = hour * 60 + minute;
+    }
+    changed
+}
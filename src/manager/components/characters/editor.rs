@@ -1,15 +1,23 @@
-use super::CharactersState;
+use super::{CharactersState, CycleDragPayload};
+use crate::common::constants::defaults;
 use crate::common::constants::manager_ui::*;
+use crate::common::types::{CornerIcon, CropRegion};
 use crate::config::profile::Profile;
 use crate::manager::components::hotkey_settings::HotkeySettingsState;
+use crate::manager::theme::density_spacing;
 use eframe::egui;
 
+/// Tooltip describing the alias template variables
+const ALIAS_TEMPLATE_HELP: &str = "Template variables: {name}, {first}, {last}, {initials}, {order}\nExample: [{order}] Hauler – {name}";
+
 pub struct ThemeDefaults {
     pub active_border_color: String,
     pub active_border_size: u16,
     pub inactive_border_color: String,
     pub inactive_border_size: u16,
     pub text_color: String,
+    pub text_font: String,
+    pub text_size: u16,
 }
 
 pub fn render_character_editor_column(
@@ -21,13 +29,38 @@ pub fn render_character_editor_column(
 ) {
     ui.group(|ui| {
         ui.set_min_width(ui.available_width());
-        ui.heading("Character Manager");
+        ui.horizontal(|ui| {
+            ui.heading("Character Manager");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .button("Clean Up…")
+                    .on_hover_text("Archive or delete characters not seen for a while")
+                    .clicked()
+                {
+                    state.stale_selections.clear();
+                    state.show_cleanup_popup = true;
+                }
+                if ui
+                    .button("Import…")
+                    .on_hover_text("Find characters in the EVE client's local settings and logs")
+                    .clicked()
+                {
+                    state.import_prefixes = crate::manager::eve_import::default_prefixes();
+                    state.import_scan = None;
+                    state.import_scan_rx = None;
+                    state.import_selections.clear();
+                    state.show_import_popup = true;
+                }
+            });
+        });
         ui.label(
-            egui::RichText::new("Edit settings for all known characters.")
-                .weak()
-                .small(),
+            egui::RichText::new(
+                "Edit settings for all known characters. Drag a name into a cycle group to add it.",
+            )
+            .weak()
+            .small(),
         );
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
 
         // Capture defaults before mutable borrow of profile
         let defaults = ThemeDefaults {
@@ -36,171 +69,371 @@ pub fn render_character_editor_column(
             inactive_border_color: profile.thumbnail_inactive_border_color.clone(),
             inactive_border_size: profile.thumbnail_inactive_border_size,
             text_color: profile.thumbnail_text_color.clone(),
+            text_font: profile.thumbnail_text_font.clone(),
+            text_size: profile.thumbnail_text_size,
         };
 
+        // Profile-wide alias template for characters without their own alias
+        ui.horizontal(|ui| {
+            ui.label("Default Alias:");
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut profile.thumbnail_alias_template)
+                        .hint_text("{name}")
+                        .desired_width(180.0),
+                )
+                .on_hover_text(ALIAS_TEMPLATE_HELP)
+                .changed()
+            {
+                *changed = true;
+            }
+        });
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
+
         egui::ScrollArea::vertical()
             .id_salt("char_editor_scroll")
             .show(ui, |ui| {
                 // Get all known characters (keys from character_thumbnails)
                 let mut char_names: Vec<String> =
                     profile.character_thumbnails.keys().cloned().collect();
-                // Case-insensitive sort
-                char_names.sort_by_key(|a| a.to_lowercase());
+                // Ordering keys first, then case-insensitive by display name
+                profile.sort_for_display(&mut char_names);
                 let mut to_delete = Vec::new();
+                let mut account_moves: Vec<(String, Option<usize>)> = Vec::new();
+                let mut account_to_delete = None;
+                let account_names: Vec<String> =
+                    profile.accounts.iter().map(|a| a.name.clone()).collect();
 
-                for character in char_names {
-                    // Ensure CharacterSettings entry exists
-                    let settings = profile
-                        .character_thumbnails
-                        .entry(character.clone())
-                        .or_insert_with(|| {
-                            crate::common::types::CharacterSettings::new(0, 0, 0, 0)
-                        });
-
-                    let is_expanded = *state.expanded_rows.get(&character).unwrap_or(&false);
+                for (account_idx, members) in profile.group_by_account(char_names) {
+                    if !account_names.is_empty() {
+                        render_account_header(
+                            ui,
+                            profile,
+                            account_idx,
+                            members.len(),
+                            state,
+                            &mut account_to_delete,
+                            changed,
+                        );
+                    }
+                    for character in members {
+                        let display_name = profile.display_name(&character);
 
-                    // Minimalist Layout
-                    ui.horizontal(|ui| {
-                        let icon = if is_expanded { "v" } else { ">" };
-                        if ui.small_button(icon).clicked() {
-                            state.expanded_rows.insert(character.clone(), !is_expanded);
-                        }
+                        // Ensure CharacterSettings entry exists
+                        let settings = profile
+                            .character_thumbnails
+                            .entry(character.clone())
+                            .or_insert_with(|| {
+                                crate::common::types::CharacterSettings::new(0, 0, 0, 0)
+                            });
 
-                        ui.label(&character);
+                        let is_expanded = *state.expanded_rows.get(&character).unwrap_or(&false);
 
-                        // Show Alias in parentheses
-                        if let Some(alias) = &settings.alias
-                            && !alias.is_empty()
-                        {
-                            ui.label(egui::RichText::new(format!("({})", alias)));
-                        }
+                        // Minimalist Layout
+                        ui.horizontal(|ui| {
+                            let icon = if is_expanded { "v" } else { ">" };
+                            if ui.small_button(icon).clicked() {
+                                state.expanded_rows.insert(character.clone(), !is_expanded);
+                            }
 
-                        // Show Hotkey in brackets
-                        if let Some(binding) = profile.character_hotkeys.get(&character) {
-                            ui.label(egui::RichText::new(format!("[{}]", binding.display_name())));
-                        }
+                            // Name doubles as a drag handle for dropping into cycle groups
+                            let name_label = ui
+                                .dnd_drag_source(
+                                    egui::Id::new("char_manager_drag").with(&character),
+                                    CycleDragPayload::Character(character.clone()),
+                                    |ui| ui.label(&character),
+                                )
+                                .response;
 
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui
-                                .small_button("🗑")
-                                .on_hover_text("Remove Character")
-                                .clicked()
+                            // Show Notes as hover tooltip with a small indicator
+                            if let Some(notes) = &settings.notes
+                                && !notes.is_empty()
                             {
-                                to_delete.push(character.clone());
-                                *changed = true;
+                                name_label.on_hover_text(notes);
+                                ui.label(egui::RichText::new("📝").weak()).on_hover_text(notes);
+                            }
+
+                            // Show the resolved display name in parentheses
+                            if display_name != character {
+                                ui.label(egui::RichText::new(format!("({})", display_name)));
                             }
+
+                            // Show Hotkey in brackets
+                            if let Some(binding) = profile.character_hotkeys.get(&character) {
+                                ui.label(egui::RichText::new(format!("[{}]", binding.display_name())));
+                            }
+
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui
+                                    .small_button("🗑")
+                                    .on_hover_text("Remove Character")
+                                    .clicked()
+                                {
+                                    to_delete.push(character.clone());
+                                    *changed = true;
+                                }
+                            });
                         });
-                    });
 
-                    if is_expanded {
-                        ui.indent("details", |ui| {
-                            ui.add_space(4.0);
+                        if is_expanded {
+                            ui.indent("details", |ui| {
+                                ui.add_space(4.0);
 
-                            egui::Grid::new(format!("grid_edit_{}", character))
-                                .num_columns(2)
-                                .spacing([10.0, 4.0])
-                                .show(ui, |ui| {
-                                    // Alias
-                                    ui.label("Alias:");
-                                    let mut alias = settings.alias.clone().unwrap_or_default();
-                                    if ui
-                                        .add(
-                                            egui::TextEdit::singleline(&mut alias)
-                                                .hint_text("Display Name"),
-                                        )
-                                        .changed()
-                                    {
-                                        settings.alias =
-                                            if alias.is_empty() { None } else { Some(alias) };
-                                        *changed = true;
-                                    }
-                                    ui.end_row();
-
-                                    // Notes
-                                    ui.label("Notes:");
-                                    let mut notes = settings.notes.clone().unwrap_or_default();
-                                    if ui
-                                        .add(
-                                            egui::TextEdit::multiline(&mut notes)
-                                                .desired_rows(2)
-                                                .hint_text("Optional notes..."),
-                                        )
-                                        .changed()
-                                    {
-                                        settings.notes =
-                                            if notes.is_empty() { None } else { Some(notes) };
-                                        *changed = true;
-                                    }
-                                    ui.end_row();
-
-                                    // Hotkey Binding
-                                    ui.label("Hotkey:");
-                                    ui.horizontal(|ui| {
-                                        if let Some(binding) =
-                                            profile.character_hotkeys.get(&character)
+                                egui::Grid::new(format!("grid_edit_{}", character))
+                                    .num_columns(2)
+                                    .spacing([10.0, 4.0])
+                                    .show(ui, |ui| {
+                                        // Alias
+                                        ui.label("Alias:");
+                                        let mut alias = settings.alias.clone().unwrap_or_default();
+                                        if ui
+                                            .add(
+                                                egui::TextEdit::singleline(&mut alias)
+                                                    .hint_text("Display Name"),
+                                            )
+                                            .on_hover_text(ALIAS_TEMPLATE_HELP)
+                                            .changed()
                                         {
-                                            ui.label(
-                                                egui::RichText::new(binding.display_name())
-                                                    .strong()
-                                                    .color(ui.style().visuals.text_color()),
-                                            );
-                                        } else {
-                                            ui.label(
-                                                egui::RichText::new("Not set")
-                                                    .strong()
-                                                    .color(ui.style().visuals.weak_text_color()),
-                                            );
+                                            settings.alias =
+                                                if alias.is_empty() { None } else { Some(alias) };
+                                            *changed = true;
                                         }
+                                        ui.end_row();
 
-                                        let bind_text = if hotkey_state.is_capturing_for(&character)
+                                        // Ordering key
+                                        ui.label("Order:");
+                                        ui.horizontal(|ui| {
+                                            let mut has_order = settings.display_order.is_some();
+                                            if ui
+                                                .checkbox(&mut has_order, "")
+                                                .on_hover_text("Sort this character before unordered ones")
+                                                .changed()
+                                            {
+                                                settings.display_order = has_order.then_some(1);
+                                                *changed = true;
+                                            }
+                                            if let Some(ref mut order) = settings.display_order
+                                                && ui.add(egui::DragValue::new(order)).changed()
+                                            {
+                                                *changed = true;
+                                            }
+                                        });
+                                        ui.end_row();
+
+                                        // Z-order among overlapping thumbnails
+                                        ui.label("Stack priority:");
+                                        if ui
+                                            .add(egui::DragValue::new(&mut settings.stack_priority))
+                                            .on_hover_text(
+                                                "Where thumbnails overlap, higher priority stays on top (0 = default, negative = below default)",
+                                            )
+                                            .changed()
                                         {
-                                            "Capturing..."
-                                        } else {
-                                            "⌨ Bind"
-                                        };
-
-                                        if ui.button(bind_text).clicked() {
-                                            hotkey_state.start_key_capture_for_character(
-                                                character.clone(),
-                                                profile.hotkey_backend,
-                                            );
+                                            *changed = true;
                                         }
+                                        ui.end_row();
 
-                                        if profile.character_hotkeys.contains_key(&character)
-                                            && ui
-                                                .small_button("✖")
-                                                .on_hover_text("Clear binding")
-                                                .clicked()
+                                        // Timelapse recording
+                                        ui.label("Timelapse:");
+                                        ui.horizontal(|ui| {
+                                            let mut recording = settings.timelapse_interval_secs.is_some();
+                                            if ui
+                                                .checkbox(&mut recording, "")
+                                                .on_hover_text(
+                                                    "Save a PNG of this client periodically (to the timelapse folder in the data directory; the oldest frames are deleted past the retention limit)",
+                                                )
+                                                .changed()
+                                            {
+                                                settings.timelapse_interval_secs = recording.then_some(
+                                                    crate::common::constants::defaults::timing::TIMELAPSE_INTERVAL_SECS,
+                                                );
+                                                *changed = true;
+                                            }
+                                            if let Some(ref mut secs) = settings.timelapse_interval_secs
+                                                && ui
+                                                    .add(
+                                                        egui::DragValue::new(secs)
+                                                            .range(1..=3600)
+                                                            .suffix(" s"),
+                                                    )
+                                                    .changed()
+                                            {
+                                                *changed = true;
+                                            }
+                                        });
+                                        ui.end_row();
+
+                                        // Account membership
+                                        if !account_names.is_empty() {
+                                            ui.label("Account:");
+                                            let mut selected = account_idx;
+                                            egui::ComboBox::from_id_salt(format!(
+                                                "account_{}",
+                                                character
+                                            ))
+                                            .selected_text(
+                                                selected
+                                                    .and_then(|idx| account_names.get(idx))
+                                                    .map(String::as_str)
+                                                    .unwrap_or("None"),
+                                            )
+                                            .show_ui(ui, |ui| {
+                                                ui.selectable_value(&mut selected, None, "None");
+                                                for (idx, name) in account_names.iter().enumerate() {
+                                                    ui.selectable_value(&mut selected, Some(idx), name);
+                                                }
+                                            });
+                                            if selected != account_idx {
+                                                account_moves.push((character.clone(), selected));
+                                            }
+                                            ui.end_row();
+                                        }
+
+                                        // Notes
+                                        ui.label("Notes:");
+                                        let mut notes = settings.notes.clone().unwrap_or_default();
+                                        if ui
+                                            .add(
+                                                egui::TextEdit::multiline(&mut notes)
+                                                    .desired_rows(2)
+                                                    .hint_text("Optional notes..."),
+                                            )
+                                            .changed()
                                         {
-                                            profile.character_hotkeys.remove(&character);
+                                            settings.notes =
+                                                if notes.is_empty() { None } else { Some(notes) };
                                             *changed = true;
                                         }
+                                        ui.end_row();
+
+                                        // Hotkey Binding
+                                        ui.label("Hotkey:");
+                                        ui.horizontal(|ui| {
+                                            if let Some(binding) =
+                                                profile.character_hotkeys.get(&character)
+                                            {
+                                                ui.label(
+                                                    egui::RichText::new(binding.display_name())
+                                                        .strong()
+                                                        .color(ui.style().visuals.text_color()),
+                                                );
+                                            } else {
+                                                ui.label(
+                                                    egui::RichText::new("Not set")
+                                                        .strong()
+                                                        .color(ui.style().visuals.weak_text_color()),
+                                                );
+                                            }
+
+                                            let bind_text = if hotkey_state.is_capturing_for(&character)
+                                            {
+                                                "Capturing..."
+                                            } else {
+                                                "⌨ Bind"
+                                            };
+
+                                            if ui.button(bind_text).clicked() {
+                                                hotkey_state.start_key_capture_for_character(
+                                                    character.clone(),
+                                                    profile.hotkey_backend,
+                                                );
+                                            }
+
+                                            if profile.character_hotkeys.contains_key(&character)
+                                                && ui
+                                                    .small_button("✖")
+                                                    .on_hover_text("Clear binding")
+                                                    .clicked()
+                                            {
+                                                profile.character_hotkeys.remove(&character);
+                                                *changed = true;
+                                            }
+                                        });
+                                        ui.end_row();
+
+                                        // Current frame from the daemon
+                                        ui.label("Frame:");
+                                        ui.vertical(|ui| {
+                                            if ui
+                                                .button("📷 Capture")
+                                                .on_hover_text("Show what this client currently displays")
+                                                .clicked()
+                                            {
+                                                state.request_frame(&character);
+                                            }
+                                            match &state.frame_preview {
+                                                Some((name, Ok(texture))) if *name == character => {
+                                                    let response = ui
+                                                        .add(
+                                                            egui::Image::new(texture)
+                                                                .max_width(240.0)
+                                                                .corner_radius(4.0)
+                                                                .sense(egui::Sense::drag()),
+                                                        )
+                                                        .on_hover_text(
+                                                            "Drag to crop the thumbnail to this part of the client",
+                                                        );
+                                                    if edit_crop_on_frame(
+                                                        ui,
+                                                        &response,
+                                                        texture.size(),
+                                                        &mut settings.crop,
+                                                    ) {
+                                                        *changed = true;
+                                                    }
+                                                }
+                                                Some((name, Err(e))) if *name == character => {
+                                                    ui.label(
+                                                        egui::RichText::new(e)
+                                                            .small()
+                                                            .color(COLOR_ERROR),
+                                                    );
+                                                }
+                                                _ => {}
+                                            }
+                                        });
+                                        ui.end_row();
+
+                                        // Overrides Section
+                                        render_overrides_section(
+                                            ui, &character, settings, &defaults, state, changed,
+                                        );
                                     });
-                                    ui.end_row();
-
-                                    // Overrides Section
-                                    render_overrides_section(
-                                        ui, &character, settings, &defaults, state, changed,
-                                    );
-                                });
-                            ui.add_space(8.0);
-                        });
-                        ui.add_space(4.0);
+                                ui.add_space(8.0);
+                            });
+                            ui.add_space(4.0);
+                        }
                     }
                 }
 
                 // Perform deferred deletion
                 for char_to_delete in to_delete {
-                    profile.character_thumbnails.remove(&char_to_delete);
-                    profile.character_hotkeys.remove(&char_to_delete);
-                    for group in &mut profile.cycle_groups {
-                        group.cycle_list.retain(|slot| match slot {
-                            crate::config::profile::CycleSlot::Eve(name) => name != &char_to_delete,
-                            crate::config::profile::CycleSlot::Source(name) => {
-                                name != &char_to_delete
-                            }
-                        });
-                    }
+                    profile.remove_character(&char_to_delete);
+                }
+
+                for (character, account) in account_moves {
+                    *changed |= profile.set_character_account(&character, account);
+                }
+
+                if let Some(idx) = account_to_delete {
+                    // Characters stay; they just lose the account's defaults
+                    profile.accounts.remove(idx);
+                    state.editing_account = None;
+                    *changed = true;
+                }
+
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
+                if ui
+                    .button("➕ Add Account")
+                    .on_hover_text("Group characters by game account with shared defaults")
+                    .clicked()
+                {
+                    profile.accounts.push(crate::config::profile::Account {
+                        name: format!("Account {}", profile.accounts.len() + 1),
+                        ..Default::default()
+                    });
+                    state.editing_account = Some(profile.accounts.len() - 1);
+                    *changed = true;
                 }
 
                 if profile.character_thumbnails.is_empty() {
@@ -216,6 +449,189 @@ pub fn render_character_editor_column(
         });
 }
 
+/// Header row of an account group in the character list, with the account's settings while
+/// it is being edited. `None` is the group of characters without an account.
+fn render_account_header(
+    ui: &mut egui::Ui,
+    profile: &mut Profile,
+    account_idx: Option<usize>,
+    member_count: usize,
+    state: &mut CharactersState,
+    account_to_delete: &mut Option<usize>,
+    changed: &mut bool,
+) {
+    ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+    let Some(idx) = account_idx else {
+        if member_count > 0 {
+            ui.label(egui::RichText::new("No Account").strong());
+        }
+        return;
+    };
+
+    let default_color = profile.thumbnail_inactive_border_color.clone();
+    let account = &mut profile.accounts[idx];
+    let editing = state.editing_account == Some(idx);
+
+    ui.horizontal(|ui| {
+        if let Some(color) = account
+            .color
+            .as_deref()
+            .and_then(|c| crate::manager::utils::parse_hex_color(c).ok())
+        {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, color);
+        }
+        ui.label(egui::RichText::new(&account.name).strong());
+        if let Some(tag) = account.label_tag() {
+            ui.label(egui::RichText::new(format!("[{}]", tag)).weak());
+        }
+        ui.label(
+            egui::RichText::new(format!("{} characters", member_count))
+                .weak()
+                .small(),
+        );
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .small_button("✏")
+                .on_hover_text("Edit account defaults")
+                .clicked()
+            {
+                state.editing_account = if editing { None } else { Some(idx) };
+            }
+        });
+    });
+
+    if !editing {
+        return;
+    }
+
+    ui.indent(("account_settings", idx), |ui| {
+        egui::Grid::new(("account_grid", idx))
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("Name:");
+                if ui.text_edit_singleline(&mut account.name).changed() {
+                    *changed = true;
+                }
+                ui.end_row();
+
+                ui.label("Tag:");
+                let mut tag = account.tag.clone().unwrap_or_default();
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut tag)
+                            .hint_text("e.g. A1")
+                            .desired_width(60.0),
+                    )
+                    .on_hover_text("Shown before the name on this account's thumbnails")
+                    .changed()
+                {
+                    account.tag = if tag.is_empty() { None } else { Some(tag) };
+                    *changed = true;
+                }
+                ui.end_row();
+
+                ui.label("Border Color:");
+                ui.horizontal(|ui| {
+                    let mut has_color = account.color.is_some();
+                    if ui
+                        .checkbox(&mut has_color, "")
+                        .on_hover_text("Inactive border color for characters without their own")
+                        .changed()
+                    {
+                        account.color = has_color.then(|| default_color.clone());
+                        *changed = true;
+                    }
+                    if let Some(color_str) = account.color.as_mut() {
+                        if ui
+                            .add(egui::TextEdit::singleline(color_str).desired_width(100.0))
+                            .changed()
+                        {
+                            *changed = true;
+                        }
+                        if let Ok(mut color) = crate::manager::utils::parse_hex_color(color_str)
+                            && ui.color_edit_button_srgba(&mut color).changed()
+                        {
+                            *color_str = crate::manager::utils::format_hex_color(color);
+                            *changed = true;
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Minimize Exemption:");
+                if ui
+                    .checkbox(&mut account.exempt_from_minimize, "Enabled")
+                    .changed()
+                {
+                    *changed = true;
+                }
+                ui.end_row();
+            });
+
+        if ui
+            .small_button("🗑 Delete Account")
+            .on_hover_text("Characters are kept and lose the account defaults")
+            .clicked()
+        {
+            *account_to_delete = Some(idx);
+        }
+    });
+}
+
+/// Lets the user drag out a crop region on a captured frame of the client and outlines the
+/// current one. Returns true when the crop changed.
+fn edit_crop_on_frame(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    frame_size: [usize; 2],
+    crop: &mut Option<CropRegion>,
+) -> bool {
+    let image = response.rect;
+    // Frame pixels per screen point
+    let scale = egui::vec2(
+        frame_size[0] as f32 / image.width(),
+        frame_size[1] as f32 / image.height(),
+    );
+    let to_frame = |pos: egui::Pos2| {
+        let p = (pos - image.min) * scale;
+        (
+            p.x.clamp(0.0, frame_size[0] as f32) as u16,
+            p.y.clamp(0.0, frame_size[1] as f32) as u16,
+        )
+    };
+
+    let mut changed = false;
+    if response.dragged()
+        && let (Some(origin), Some(current)) = (
+            ui.input(|i| i.pointer.press_origin()),
+            response.interact_pointer_pos(),
+        )
+    {
+        let (x0, y0) = to_frame(origin);
+        let (x1, y1) = to_frame(current);
+        let region = CropRegion::new(x0.min(x1), y0.min(y1), x0.abs_diff(x1), y0.abs_diff(y1));
+        // Ignore clicks and slivers; the daemon shows the whole client for those anyway
+        if region.width > 1 && region.height > 1 && *crop != Some(region) {
+            *crop = Some(region);
+            changed = true;
+        }
+    }
+
+    if let Some(region) = *crop {
+        let min = image.min + egui::vec2(region.x as f32, region.y as f32) / scale;
+        let size = egui::vec2(region.width as f32, region.height as f32) / scale;
+        ui.painter_at(image).rect_stroke(
+            egui::Rect::from_min_size(min, size),
+            0.0,
+            ui.visuals().selection.stroke,
+            egui::StrokeKind::Inside,
+        );
+    }
+    changed
+}
+
 pub fn render_overrides_section(
     ui: &mut egui::Ui,
     character_name: &str,
@@ -420,6 +836,69 @@ pub fn render_overrides_section(
             });
         }
 
+        // Label Font
+        ui.horizontal(|ui| {
+            ui.label("Label Font:");
+            let mut font_enabled =
+                settings.override_text_font.is_some() || settings.override_text_size.is_some();
+            let cached = state
+                .cached_overrides
+                .entry(character_name.to_string())
+                .or_default();
+
+            if ui.checkbox(&mut font_enabled, "Enabled").changed() {
+                if font_enabled {
+                    settings.override_text_font = cached
+                        .text_font
+                        .clone()
+                        .or_else(|| Some(defaults.text_font.clone()));
+                    settings.override_text_size = cached.text_size.or(Some(defaults.text_size));
+                } else {
+                    cached.text_font = settings.override_text_font.take();
+                    cached.text_size = settings.override_text_size.take();
+                }
+                *changed = true;
+            }
+        });
+
+        // Label Font Settings (Indented)
+        if settings.override_text_font.is_some() || settings.override_text_size.is_some() {
+            let fonts = state.available_fonts.get_or_insert_with(|| {
+                crate::daemon::list_fonts().unwrap_or_else(|e| {
+                    tracing::warn!(error = ?e, "Failed to load font list from fontconfig");
+                    Vec::new()
+                })
+            });
+            ui.indent("text_font_details", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Font:");
+                    let font = settings
+                        .override_text_font
+                        .get_or_insert_with(|| defaults.text_font.clone());
+                    egui::ComboBox::from_id_salt(format!("text_font_{}", character_name))
+                        .selected_text(font.as_str())
+                        .width(200.0)
+                        .show_ui(ui, |ui| {
+                            for family in fonts.iter() {
+                                if ui.selectable_value(font, family.clone(), family).changed() {
+                                    *changed = true;
+                                }
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Size:");
+                    let size = settings
+                        .override_text_size
+                        .get_or_insert(defaults.text_size);
+                    if ui.add(egui::DragValue::new(size).range(8..=48)).changed() {
+                        *changed = true;
+                    }
+                });
+            });
+        }
+
         // Preview Mode (Static Mode)
         ui.horizontal(|ui| {
             ui.label("Static Mode:");
@@ -442,6 +921,47 @@ pub fn render_overrides_section(
             }
         });
 
+        // Preview Mode (Screenshot Mode)
+        ui.horizontal(|ui| {
+            ui.label("Screenshot Mode:");
+            let mut is_snapshot = matches!(
+                settings.preview_mode,
+                crate::common::types::PreviewMode::Snapshot { .. }
+            );
+
+            if ui
+                .checkbox(&mut is_snapshot, "Enabled")
+                .on_hover_text(
+                    "Refresh the preview every few seconds instead of live (saves power)",
+                )
+                .changed()
+            {
+                settings.preview_mode = if is_snapshot {
+                    crate::common::types::PreviewMode::Snapshot {
+                        interval_secs:
+                            crate::common::constants::defaults::thumbnail::SNAPSHOT_INTERVAL_SECS,
+                    }
+                } else {
+                    crate::common::types::PreviewMode::Live
+                };
+                *changed = true;
+            }
+
+            if let crate::common::types::PreviewMode::Snapshot {
+                ref mut interval_secs,
+            } = settings.preview_mode
+                && ui
+                    .add(
+                        egui::DragValue::new(interval_secs)
+                            .range(1..=600)
+                            .suffix(" s"),
+                    )
+                    .changed()
+            {
+                *changed = true;
+            }
+        });
+
         // Static Mode Settings (Indented)
         if let crate::common::types::PreviewMode::Static { ref mut color } = settings.preview_mode {
             ui.indent("static_mode_details", |ui| {
@@ -479,6 +999,32 @@ pub fn render_overrides_section(
             }
         });
 
+        // Hide-when-no-focus exemption
+        ui.horizontal(|ui| {
+            ui.label("Keep Visible Without Focus:");
+            if ui
+                .checkbox(&mut settings.keep_visible_without_focus, "Enabled")
+                .on_hover_text("Stay visible when thumbnails hide because EVE lost focus")
+                .changed()
+            {
+                *changed = true;
+            }
+        });
+
+        // Hide-all exemption
+        ui.horizontal(|ui| {
+            ui.label("Always Visible:");
+            if ui
+                .checkbox(&mut settings.always_visible, "Enabled")
+                .on_hover_text(
+                    "Stay visible when the toggle previews hotkey, panic hotkey or visibility schedule hides all thumbnails",
+                )
+                .changed()
+            {
+                *changed = true;
+            }
+        });
+
         // Preview Visibility Override
         ui.horizontal(|ui| {
             ui.label("Preview Visibility:");
@@ -521,7 +1067,205 @@ pub fn render_overrides_section(
                     }
                 });
         });
+
+        // Corner Icon
+        ui.horizontal(|ui| {
+            ui.label("Corner Icon:");
+
+            let current_label = match settings.corner_icon {
+                CornerIcon::None => "None",
+                CornerIcon::Portrait { .. } => "Portrait",
+                CornerIcon::Custom { .. } => "Custom PNG",
+            };
+
+            egui::ComboBox::from_id_salt(format!("corner_icon_{}", character_name))
+                .selected_text(current_label)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(matches!(settings.corner_icon, CornerIcon::None), "None")
+                        .clicked()
+                    {
+                        settings.corner_icon = CornerIcon::None;
+                        *changed = true;
+                    }
+                    if ui
+                        .selectable_label(
+                            matches!(settings.corner_icon, CornerIcon::Portrait { .. }),
+                            "Portrait",
+                        )
+                        .clicked()
+                        && !matches!(settings.corner_icon, CornerIcon::Portrait { .. })
+                    {
+                        settings.corner_icon = CornerIcon::Portrait { character_id: 0 };
+                        *changed = true;
+                    }
+                    if ui
+                        .selectable_label(
+                            matches!(settings.corner_icon, CornerIcon::Custom { .. }),
+                            "Custom PNG",
+                        )
+                        .clicked()
+                        && !matches!(settings.corner_icon, CornerIcon::Custom { .. })
+                    {
+                        settings.corner_icon = CornerIcon::Custom {
+                            path: String::new(),
+                        };
+                        *changed = true;
+                    }
+                });
+        });
+
+        // Corner Icon Settings (Indented)
+        match settings.corner_icon {
+            CornerIcon::None => {}
+            CornerIcon::Portrait {
+                ref mut character_id,
+            } => {
+                ui.indent("corner_icon_details", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Character ID:");
+                        if ui
+                            .add(egui::DragValue::new(character_id).speed(0.0))
+                            .on_hover_text("EVE character ID used to fetch the portrait")
+                            .changed()
+                        {
+                            *changed = true;
+                        }
+                    });
+                });
+            }
+            CornerIcon::Custom { ref mut path } => {
+                ui.indent("corner_icon_details", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Path:");
+                        if ui
+                            .add(
+                                egui::TextEdit::singleline(path)
+                                    .hint_text("/path/to/icon.png")
+                                    .desired_width(200.0),
+                            )
+                            .changed()
+                        {
+                            *changed = true;
+                        }
+                    });
+                });
+            }
+        }
+
+        // Shape Override
+        ui.horizontal(|ui| {
+            ui.label("Shape:");
+
+            let current_label = settings
+                .override_shape
+                .map(crate::manager::components::visual_settings::shape_label)
+                .unwrap_or("Default");
+
+            egui::ComboBox::from_id_salt(format!("shape_{}", character_name))
+                .selected_text(current_label)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_value(&mut settings.override_shape, None, "Default")
+                        .changed()
+                    {
+                        *changed = true;
+                    }
+                    for shape in [
+                        crate::common::types::ThumbnailShape::Rectangle,
+                        crate::common::types::ThumbnailShape::RoundedRect,
+                        crate::common::types::ThumbnailShape::Circle,
+                    ] {
+                        if ui
+                            .selectable_value(
+                                &mut settings.override_shape,
+                                Some(shape),
+                                crate::manager::components::visual_settings::shape_label(shape),
+                            )
+                            .changed()
+                        {
+                            *changed = true;
+                        }
+                    }
+                });
+        });
+
+        // Crop Region
+        ui.horizontal(|ui| {
+            ui.label("Crop:");
+            let mut cropped = settings.crop.is_some();
+            let cached = state
+                .cached_overrides
+                .entry(character_name.to_string())
+                .or_default();
+
+            if ui
+                .checkbox(&mut cropped, "Enabled")
+                .on_hover_text(
+                    "Show only part of the client (e.g. local chat). \
+                     Capture a frame above and drag on it to select the region.",
+                )
+                .changed()
+            {
+                if cropped {
+                    settings.crop = cached.crop.or(Some(CropRegion::new(
+                        0,
+                        0,
+                        defaults::thumbnail::CROP_WIDTH,
+                        defaults::thumbnail::CROP_HEIGHT,
+                    )));
+                } else {
+                    cached.crop = settings.crop.take();
+                }
+                *changed = true;
+            }
+        });
+
+        // Crop Region Settings (Indented)
+        if let Some(ref mut region) = settings.crop {
+            ui.indent("crop_details", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Position:");
+                    if ui
+                        .add(egui::DragValue::new(&mut region.x).prefix("x: "))
+                        .changed()
+                    {
+                        *changed = true;
+                    }
+                    if ui
+                        .add(egui::DragValue::new(&mut region.y).prefix("y: "))
+                        .changed()
+                    {
+                        *changed = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Size:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut region.width)
+                                .range(2..=u16::MAX)
+                                .suffix(" px"),
+                        )
+                        .changed()
+                    {
+                        *changed = true;
+                    }
+                    ui.label("×");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut region.height)
+                                .range(2..=u16::MAX)
+                                .suffix(" px"),
+                        )
+                        .changed()
+                    {
+                        *changed = true;
+                    }
+                });
+            });
+        }
     });
 
-    ui.add_space(ITEM_SPACING);
+    ui.add_space(density_spacing(ui, ITEM_SPACING));
 }
@@ -1,14 +1,19 @@
-use super::CharactersState;
+use super::{CharactersState, CycleBulkAction, CycleDragPayload};
 use crate::common::constants::manager_ui::*;
+use crate::common::ipc::ClientGeometry;
 use crate::config::profile::Profile;
 use crate::manager::components::hotkey_settings::HotkeySettingsState;
+use crate::manager::components::reorderable_list;
+use crate::manager::theme::density_spacing;
 use eframe::egui;
+use std::time::{Duration, Instant};
 
 pub fn render_cycle_group_column(
     ui: &mut egui::Ui,
     profile: &mut Profile,
     state: &mut CharactersState,
     hotkey_state: &mut HotkeySettingsState,
+    daemon_connected: bool,
     changed: &mut bool,
 ) {
     ui.group(|ui| {
@@ -17,7 +22,7 @@ pub fn render_cycle_group_column(
         ui.horizontal(|ui| {
             ui.heading("Cycle Group");
         });
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
 
         // Group Selector & Management
         ui.horizontal(|ui| {
@@ -128,9 +133,9 @@ pub fn render_cycle_group_column(
             }
         });
 
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
         ui.separator();
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
 
         // Cycle Hotkeys for this Group
         let current_group = &mut profile.cycle_groups[state.selected_cycle_group_index];
@@ -191,9 +196,9 @@ pub fn render_cycle_group_column(
             }
         });
 
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
         ui.separator();
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
 
         // Character List Header
         ui.horizontal(|ui| {
@@ -217,41 +222,128 @@ pub fn render_cycle_group_column(
             });
         });
 
-        let current_group = &mut profile.cycle_groups[state.selected_cycle_group_index];
+        // Bulk actions driven by the daemon's live client list
+        ui.horizontal_wrapped(|ui| {
+            let waiting = state.pending_bulk_action.is_some();
+            ui.add_enabled_ui(daemon_connected && !waiting, |ui| {
+                let actions = [
+                    (
+                        CycleBulkAction::AddActive,
+                        "➕ Add Active",
+                        "Add all running clients that are not in this group yet",
+                    ),
+                    (
+                        CycleBulkAction::RemoveLoggedOff,
+                        "➖ Remove Logged Off",
+                        "Remove characters that are not currently running",
+                    ),
+                    (
+                        CycleBulkAction::SyncLayout,
+                        "↔ Sync to Layout",
+                        "Order the group like the thumbnails on screen (left-to-right, top-to-bottom)",
+                    ),
+                ];
+                for (action, label, hover) in actions {
+                    let response = ui
+                        .small_button(label)
+                        .on_hover_text(hover)
+                        .on_disabled_hover_text("Requires the preview daemon to be running");
+                    if response.clicked() {
+                        state.request_bulk_action(action);
+                    }
+                }
+            });
+
+            if waiting {
+                ui.spinner();
+            } else if let Some((text, ok)) = &state.bulk_action_result {
+                let color = if *ok { COLOR_SUCCESS } else { COLOR_WARNING };
+                ui.label(egui::RichText::new(text).small().color(color));
+            }
+        });
+
+        // Other groups act as drop targets so entries can be moved between groups
+        let selected_group = state.selected_cycle_group_index;
+        let mut pending_drop: Option<(CycleDragPayload, usize, usize)> = None;
+
+        if profile.cycle_groups.len() > 1 {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(egui::RichText::new("Move to:").weak().small());
+                for (group_idx, group) in profile.cycle_groups.iter().enumerate() {
+                    if group_idx == selected_group {
+                        continue;
+                    }
+
+                    let frame = egui::Frame::default()
+                        .inner_margin(egui::Margin::symmetric(6, 2))
+                        .stroke(ui.visuals().widgets.noninteractive.bg_stroke);
+                    let (response, dropped) =
+                        ui.dnd_drop_zone::<CycleDragPayload, ()>(frame, |ui| {
+                            ui.label(format!("{} ({})", group.name, group.cycle_list.len()));
+                        });
+                    response
+                        .response
+                        .on_hover_text("Drop a character here to move it into this group");
+
+                    if let Some(payload) = dropped {
+                        pending_drop = Some(((*payload).clone(), group_idx, usize::MAX));
+                    }
+                }
+            });
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+        }
+
+        let current_group = &profile.cycle_groups[selected_group];
+        let mut to_delete = None;
 
         egui::ScrollArea::vertical()
             .id_salt("cycle_group_scroll")
             .show(ui, |ui| {
-                let mut from_idx = None;
-                let mut to_idx = None;
-                let mut to_delete = None;
-
                 let frame = egui::Frame::default()
                     .inner_margin(4.0)
                     .stroke(ui.visuals().widgets.noninteractive.bg_stroke);
 
-                let (_, dropped_payload) = ui.dnd_drop_zone::<usize, ()>(frame, |ui| {
+                let (_, dropped_payload) = ui.dnd_drop_zone::<CycleDragPayload, ()>(frame, |ui| {
                     ui.set_min_height(100.0);
 
                     for (row_idx, slot) in current_group.cycle_list.iter().enumerate() {
                         let item_id = egui::Id::new("cycle_group_item").with(row_idx);
+                        let row_payload = CycleDragPayload::Slot {
+                            group: selected_group,
+                            index: row_idx,
+                        };
 
                         let response = ui
                             .horizontal(|ui| {
-                                let drag_source = ui.dnd_drag_source(item_id, row_idx, |ui| {
+                                let drag_source = ui.dnd_drag_source(item_id, row_payload.clone(), |ui| {
                                     ui.horizontal(|ui| {
                                         ui.label(egui::RichText::new("::").weak());
 
                                         match slot {
                                             crate::config::profile::CycleSlot::Eve(name) => {
-                                                ui.label(name);
+                                                let display_name = profile.display_name(name);
+                                                let notes = profile
+                                                    .character_thumbnails
+                                                    .get(name)
+                                                    .and_then(|s| s.notes.as_deref())
+                                                    .filter(|n| !n.is_empty());
+                                                let hover = match (display_name != *name, notes) {
+                                                    (true, Some(notes)) => Some(format!("{name}\n\n{notes}")),
+                                                    (true, None) => Some(name.clone()),
+                                                    (false, Some(notes)) => Some(notes.to_string()),
+                                                    (false, None) => None,
+                                                };
+                                                let label = ui.label(display_name);
+                                                if let Some(hover) = hover {
+                                                    label.on_hover_text(hover);
+                                                }
                                             }
                                             crate::config::profile::CycleSlot::Source(name) => {
                                                 ui.colored_label(
                                                     egui::Color32::LIGHT_BLUE,
                                                     "Source",
                                                 );
-                                                ui.label(name);
+                                                ui.label(profile.display_name(name));
                                             }
                                         }
                                     });
@@ -274,58 +366,114 @@ pub fn render_cycle_group_column(
                             })
                             .inner;
 
-                        if let (Some(pointer), Some(hovered_payload)) = (
-                            ui.input(|i| i.pointer.interact_pos()),
-                            response.dnd_hover_payload::<usize>(),
-                        ) {
-                            let rect = response.rect;
-                            let stroke =
-                                egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
-
-                            let insert_row_idx = if *hovered_payload == row_idx {
-                                ui.painter().hline(rect.x_range(), rect.center().y, stroke);
-                                row_idx
-                            } else if pointer.y < rect.center().y {
-                                ui.painter().hline(rect.x_range(), rect.top(), stroke);
-                                row_idx
-                            } else {
-                                ui.painter().hline(rect.x_range(), rect.bottom(), stroke);
-                                row_idx + 1
-                            };
-
-                            if let Some(dragged_payload) = response.dnd_release_payload::<usize>() {
-                                from_idx = Some(*dragged_payload);
-                                to_idx = Some(insert_row_idx);
-                                *changed = true;
+                        if let Some(hovered_payload) =
+                            response.dnd_hover_payload::<CycleDragPayload>()
+                        {
+                            let insert_row_idx = reorderable_list::drop_indicator(
+                                ui,
+                                response.rect,
+                                row_idx,
+                                *hovered_payload == row_payload,
+                            );
+
+                            if let Some(dragged_payload) =
+                                response.dnd_release_payload::<CycleDragPayload>()
+                            {
+                                pending_drop =
+                                    Some(((*dragged_payload).clone(), selected_group, insert_row_idx));
                             }
                         }
                     }
-                });
 
-                if let Some(dragged_payload) = dropped_payload {
-                    from_idx = Some(*dragged_payload);
-                    to_idx = Some(current_group.cycle_list.len());
-                    *changed = true;
-                }
+                    if current_group.cycle_list.is_empty() {
+                        ui.label(
+                            egui::RichText::new(
+                                "No characters in this group. Drag characters here from the Character Manager.",
+                            )
+                            .weak(),
+                        );
+                    }
+                });
 
-                if let Some(idx) = to_delete {
-                    current_group.cycle_list.remove(idx);
+                // Dropped on the empty area below the rows: append
+                if pending_drop.is_none()
+                    && let Some(dragged_payload) = dropped_payload
+                {
+                    pending_drop = Some(((*dragged_payload).clone(), selected_group, usize::MAX));
                 }
+            });
 
-                if let (Some(from), Some(mut to)) = (from_idx, to_idx) {
-                    if from < to {
-                        to -= 1;
-                    }
-                    if from != to {
-                        let item = current_group.cycle_list.remove(from);
-                        let insert_idx = to.min(current_group.cycle_list.len());
-                        current_group.cycle_list.insert(insert_idx, item);
-                    }
-                }
+        if let Some(idx) = to_delete {
+            profile.cycle_groups[selected_group].cycle_list.remove(idx);
+        }
 
-                if current_group.cycle_list.is_empty() {
-                    ui.label(egui::RichText::new("No characters in this group.").weak());
+        if let Some((payload, to_group, to_index)) = pending_drop {
+            *changed |= match payload {
+                CycleDragPayload::Slot { group, index } => {
+                    profile.move_cycle_slot(group, index, to_group, to_index)
                 }
-            });
+                CycleDragPayload::Character(name) => profile.insert_cycle_slot(
+                    to_group,
+                    crate::config::profile::CycleSlot::Eve(name),
+                    to_index,
+                ),
+            };
+        }
     });
 }
+
+/// Applies a queued bulk action once the daemon has answered with a fresh client layout.
+///
+/// Returns true if the selected group was modified.
+pub fn apply_pending_bulk_action(
+    profile: &mut Profile,
+    state: &mut CharactersState,
+    client_layout: Option<&(Instant, Vec<ClientGeometry>)>,
+) -> bool {
+    let Some((action, requested_at)) = state.pending_bulk_action else {
+        return false;
+    };
+
+    let clients = match client_layout {
+        Some((received_at, clients)) if *received_at >= requested_at => clients,
+        _ => {
+            if requested_at.elapsed() > Duration::from_millis(CLIENT_LAYOUT_TIMEOUT_MS) {
+                state.pending_bulk_action = None;
+                state.bulk_action_result =
+                    Some(("Daemon did not report its clients".to_string(), false));
+            }
+            return false;
+        }
+    };
+    state.pending_bulk_action = None;
+
+    let Some(group) = profile
+        .cycle_groups
+        .get_mut(state.selected_cycle_group_index)
+    else {
+        return false;
+    };
+
+    let (text, changed) = match action {
+        CycleBulkAction::AddActive => {
+            let added = group.add_active_clients(clients);
+            (format!("Added {} client(s)", added), added > 0)
+        }
+        CycleBulkAction::RemoveLoggedOff => {
+            let removed = group.remove_inactive(clients);
+            (format!("Removed {} character(s)", removed), removed > 0)
+        }
+        CycleBulkAction::SyncLayout => {
+            let reordered = group.sort_by_layout(clients);
+            let text = if reordered {
+                "Order synced to screen layout"
+            } else {
+                "Order already matches screen layout"
+            };
+            (text.to_string(), reordered)
+        }
+    };
+
+    state.bulk_action_result = Some((text, true));
+    changed
+}
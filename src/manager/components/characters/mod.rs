@@ -1,5 +1,10 @@
+use crate::common::ipc::ClientGeometry;
+use crate::common::types::CropRegion;
 use crate::config::profile::Profile;
+use crate::config::session::SessionHistory;
+use crate::manager::eve_import::ImportScan;
 use eframe::egui;
+use std::time::Instant;
 
 mod editor;
 mod list;
@@ -14,6 +19,48 @@ pub struct CharactersState {
     pub(crate) selected_cycle_group_index: usize,
     pub(crate) renaming_group_idx: Option<usize>,
     pub(crate) rename_buffer: String,
+    pub(crate) pending_bulk_action: Option<(CycleBulkAction, Instant)>,
+    pub(crate) bulk_action_result: Option<(String, bool)>,
+    layout_requested: bool,
+    pub(crate) show_cleanup_popup: bool,
+    pub(crate) stale_days: u32,
+    pub(crate) stale_selections: std::collections::HashMap<String, bool>,
+    pub(crate) show_import_popup: bool,
+    /// Wine prefixes detected when the import dialog was opened
+    pub(crate) import_prefixes: Vec<std::path::PathBuf>,
+    pub(crate) import_extra_prefix: String,
+    pub(crate) import_scan: Option<ImportScan>,
+    /// Scan running on a worker thread (prefixes can hold a lot of files)
+    pub(crate) import_scan_rx: Option<std::sync::mpsc::Receiver<ImportScan>>,
+    pub(crate) import_selections: std::collections::HashMap<String, bool>,
+    pub(crate) import_add_to_group: bool,
+    pub(crate) import_assign_hotkeys: bool,
+    pub(crate) editing_account: Option<usize>,
+    frame_request: Option<String>,
+    /// Latest captured frame shown in a character's details (texture, or why capture failed)
+    pub(crate) frame_preview: Option<(String, Result<egui::TextureHandle, String>)>,
+    /// Installed font families for label font overrides (loaded when first needed)
+    pub(crate) available_fonts: Option<Vec<String>>,
+}
+
+/// Bulk edits of the selected cycle group that need the daemon's live client list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CycleBulkAction {
+    /// Append every active client not yet in the group
+    AddActive,
+    /// Drop entries whose client is not currently running
+    RemoveLoggedOff,
+    /// Reorder entries to match the on-screen thumbnail layout
+    SyncLayout,
+}
+
+/// Drag-and-drop payload for editing cycle groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CycleDragPayload {
+    /// An entry already in a cycle group (group index, row index)
+    Slot { group: usize, index: usize },
+    /// A character dragged in from the Character Manager list
+    Character(String),
 }
 
 #[derive(Debug, Default, Clone)]
@@ -23,6 +70,9 @@ pub struct CachedOverrides {
     pub(crate) active_border_size: Option<u16>,
     pub(crate) inactive_border_size: Option<u16>,
     pub(crate) text_color: Option<String>,
+    pub(crate) text_font: Option<String>,
+    pub(crate) text_size: Option<u16>,
+    pub(crate) crop: Option<CropRegion>,
 }
 
 impl CharactersState {
@@ -35,9 +85,71 @@ impl CharactersState {
             selected_cycle_group_index: 0,
             renaming_group_idx: None,
             rename_buffer: String::new(),
+            pending_bulk_action: None,
+            bulk_action_result: None,
+            layout_requested: false,
+            show_cleanup_popup: false,
+            stale_days: crate::common::constants::config::STALE_CHARACTER_DAYS,
+            stale_selections: std::collections::HashMap::new(),
+            show_import_popup: false,
+            import_prefixes: Vec::new(),
+            import_extra_prefix: String::new(),
+            import_scan: None,
+            import_scan_rx: None,
+            import_selections: std::collections::HashMap::new(),
+            import_add_to_group: false,
+            import_assign_hotkeys: false,
+            editing_account: None,
+            frame_request: None,
+            frame_preview: None,
+            available_fonts: None,
         }
     }
 
+    /// Queues a bulk action; it is applied once a fresh client layout arrives from the daemon
+    pub(crate) fn request_bulk_action(&mut self, action: CycleBulkAction) {
+        self.pending_bulk_action = Some((action, Instant::now()));
+        self.bulk_action_result = None;
+        self.layout_requested = true;
+    }
+
+    /// Returns true once if the UI needs a new client layout from the daemon
+    pub fn take_layout_request(&mut self) -> bool {
+        std::mem::take(&mut self.layout_requested)
+    }
+
+    /// Queues a frame capture for a character; the reply is passed to `show_frame`
+    pub(crate) fn request_frame(&mut self, character: &str) {
+        self.frame_request = Some(character.to_string());
+        self.frame_preview = None;
+    }
+
+    /// Returns the character whose frame the UI wants from the daemon, once
+    pub fn take_frame_request(&mut self) -> Option<String> {
+        self.frame_request.take()
+    }
+
+    /// Shows a frame reply (PNG) from the daemon in the character's details
+    pub fn show_frame(
+        &mut self,
+        ctx: &egui::Context,
+        character: String,
+        png: Result<Vec<u8>, String>,
+    ) {
+        let texture = png.and_then(|bytes| {
+            crate::manager::utils::decode_png_image(&bytes)
+                .map(|image| {
+                    ctx.load_texture(
+                        format!("frame_preview_{}", character),
+                        image,
+                        egui::TextureOptions::LINEAR,
+                    )
+                })
+                .map_err(|e| e.to_string())
+        });
+        self.frame_preview = Some((character, texture));
+    }
+
     pub fn load_from_profile(&mut self, _profile: &Profile) {
         self.cached_overrides.clear();
     }
@@ -54,6 +166,9 @@ pub fn ui(
     profile: &mut Profile,
     state: &mut CharactersState,
     hotkey_state: &mut crate::manager::components::hotkey_settings::HotkeySettingsState,
+    client_layout: Option<&(Instant, Vec<ClientGeometry>)>,
+    daemon_connected: bool,
+    history: &SessionHistory,
 ) -> bool {
     let mut changed = false;
 
@@ -61,12 +176,29 @@ pub fn ui(
         state.selected_cycle_group_index = 0;
     }
 
-    render_two_column_layout(ui, profile, state, hotkey_state, &mut changed);
+    changed |= list::apply_pending_bulk_action(profile, state, client_layout);
+
+    render_two_column_layout(
+        ui,
+        profile,
+        state,
+        hotkey_state,
+        daemon_connected,
+        &mut changed,
+    );
 
     if state.show_add_characters_popup {
         modals::render_add_characters_modal(ui.ctx(), profile, state, &mut changed);
     }
 
+    if state.show_cleanup_popup {
+        modals::render_stale_cleanup_modal(ui.ctx(), profile, state, history, &mut changed);
+    }
+
+    if state.show_import_popup {
+        modals::render_import_modal(ui.ctx(), profile, state, &mut changed);
+    }
+
     if hotkey_state.is_dialog_open() {
         changed |= crate::manager::components::hotkey_settings::render_key_capture_modal(
             ui,
@@ -83,6 +215,7 @@ fn render_two_column_layout(
     profile: &mut Profile,
     state: &mut CharactersState,
     hotkey_state: &mut crate::manager::components::hotkey_settings::HotkeySettingsState,
+    daemon_connected: bool,
     changed: &mut bool,
 ) {
     let spacing = ui.spacing().item_spacing.x;
@@ -102,7 +235,14 @@ fn render_two_column_layout(
             egui::vec2(right_width, ui.available_height()),
             egui::Layout::top_down(egui::Align::Min),
             |ui| {
-                list::render_cycle_group_column(ui, profile, state, hotkey_state, changed);
+                list::render_cycle_group_column(
+                    ui,
+                    profile,
+                    state,
+                    hotkey_state,
+                    daemon_connected,
+                    changed,
+                );
             },
         );
     });
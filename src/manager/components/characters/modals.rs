@@ -1,6 +1,9 @@
 use super::CharactersState;
 use crate::common::constants::manager_ui::*;
 use crate::config::profile::Profile;
+use crate::config::session::{SessionHistory, unix_now};
+use crate::manager::eve_import;
+use crate::manager::theme::density_spacing;
 use eframe::egui;
 
 pub fn render_add_characters_modal(
@@ -18,7 +21,7 @@ pub fn render_add_characters_modal(
         .show(ctx, |ui| {
             ui.set_min_width(300.0);
             ui.label("Select characters to add to cycle order:");
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
             // Select All / Deselect All toggle
             ui.horizontal(|ui| {
@@ -47,9 +50,9 @@ pub fn render_add_characters_modal(
                 }
             });
 
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
             ui.separator();
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
             egui::ScrollArea::vertical()
                 .max_height(300.0)
@@ -57,7 +60,7 @@ pub fn render_add_characters_modal(
                     // Collect and sort names for stable display
                     let mut char_names: Vec<String> =
                         state.character_selections.keys().cloned().collect();
-                    char_names.sort();
+                    profile.sort_for_display(&mut char_names);
 
                     for name in char_names {
                         if let Some(selected) = state.character_selections.get_mut(&name) {
@@ -75,9 +78,9 @@ pub fn render_add_characters_modal(
                                 profile.custom_windows.iter().any(|r| r.alias == name);
 
                             let display_name = if is_custom_source {
-                                format!("[Source] {}", name)
+                                format!("[Source] {}", profile.display_name(&name))
                             } else {
-                                name.clone()
+                                profile.display_name(&name)
                             };
 
                             let label_text = if already_in_cycle {
@@ -91,7 +94,7 @@ pub fn render_add_characters_modal(
                     }
                 });
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
             ui.separator();
 
             ui.horizontal(|ui| {
@@ -137,3 +140,356 @@ pub fn render_add_characters_modal(
         state.show_add_characters_popup = false;
     }
 }
+
+pub fn render_stale_cleanup_modal(
+    ctx: &egui::Context,
+    profile: &mut Profile,
+    state: &mut CharactersState,
+    history: &SessionHistory,
+    changed: &mut bool,
+) {
+    let mut open = true;
+    let now = unix_now();
+
+    egui::Window::new("Clean Up Stale Characters")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.set_min_width(340.0);
+            ui.horizontal(|ui| {
+                ui.label("Not seen for at least");
+                ui.add(
+                    egui::DragValue::new(&mut state.stale_days)
+                        .range(1..=3650)
+                        .suffix(" days"),
+                );
+            });
+            ui.label(
+                egui::RichText::new(
+                    "Archived characters keep their settings and can be restored later.",
+                )
+                .weak()
+                .small(),
+            );
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+            ui.separator();
+
+            let stale = history.stale(profile.character_thumbnails.keys(), state.stale_days, now);
+            state
+                .stale_selections
+                .retain(|name, _| stale.iter().any(|(n, _)| n == name));
+
+            egui::ScrollArea::vertical()
+                .id_salt("stale_characters_scroll")
+                .max_height(250.0)
+                .show(ui, |ui| {
+                    if stale.is_empty() {
+                        ui.label(egui::RichText::new("No stale characters.").weak().italics());
+                    }
+                    for (name, age) in &stale {
+                        let selected = state.stale_selections.entry(name.clone()).or_insert(false);
+                        ui.horizontal(|ui| {
+                            ui.checkbox(selected, profile.display_name(name));
+                            ui.label(egui::RichText::new(format!("{} days ago", age)).weak());
+                        });
+                    }
+                });
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+            ui.separator();
+
+            let selected: Vec<String> = state
+                .stale_selections
+                .iter()
+                .filter(|(_, selected)| **selected)
+                .map(|(name, _)| name.clone())
+                .collect();
+
+            ui.horizontal(|ui| {
+                let all_selected = !stale.is_empty() && state.stale_selections.values().all(|&v| v);
+                if ui
+                    .add_enabled(
+                        !stale.is_empty(),
+                        egui::Button::new(if all_selected {
+                            "Deselect All"
+                        } else {
+                            "Select All"
+                        }),
+                    )
+                    .clicked()
+                {
+                    for value in state.stale_selections.values_mut() {
+                        *value = !all_selected;
+                    }
+                }
+
+                let any = !selected.is_empty();
+                if ui
+                    .add_enabled(
+                        any,
+                        egui::Button::new(format!("Archive ({})", selected.len())),
+                    )
+                    .clicked()
+                {
+                    for name in &selected {
+                        profile.archive_character(name);
+                    }
+                    *changed = true;
+                }
+                if ui
+                    .add_enabled(
+                        any,
+                        egui::Button::new(format!("Delete ({})", selected.len())),
+                    )
+                    .on_hover_text("Permanently remove the selected characters and their settings")
+                    .clicked()
+                {
+                    for name in &selected {
+                        profile.remove_character(name);
+                    }
+                    *changed = true;
+                }
+            });
+
+            if !profile.archived_characters.is_empty() {
+                ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+                egui::CollapsingHeader::new(format!(
+                    "Archived ({})",
+                    profile.archived_characters.len()
+                ))
+                .id_salt("archived_characters")
+                .show(ui, |ui| {
+                    let mut archived: Vec<String> =
+                        profile.archived_characters.keys().cloned().collect();
+                    archived.sort_by_key(|name| name.to_lowercase());
+
+                    for name in archived {
+                        ui.horizontal(|ui| {
+                            ui.label(&name);
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                        profile.archived_characters.remove(&name);
+                                        *changed = true;
+                                    }
+                                    if ui.small_button("Restore").clicked() {
+                                        profile.restore_character(&name);
+                                        *changed = true;
+                                    }
+                                },
+                            );
+                        });
+                    }
+                });
+            }
+        });
+
+    if !open {
+        state.show_cleanup_popup = false;
+    }
+}
+
+pub fn render_import_modal(
+    ctx: &egui::Context,
+    profile: &mut Profile,
+    state: &mut CharactersState,
+    changed: &mut bool,
+) {
+    let mut open = true;
+    egui::Window::new("Import Characters from EVE")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.set_min_width(380.0);
+            ui.label(
+                egui::RichText::new(
+                    "Characters are found through the client's settings files and chat/game \
+                     logs, so only characters with at least one log are listed by name.",
+                )
+                .weak()
+                .small(),
+            );
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+            let detected = &state.import_prefixes;
+            if detected.is_empty() {
+                ui.label(
+                    egui::RichText::new("No Wine prefix detected.")
+                        .weak()
+                        .italics(),
+                );
+            }
+            for prefix in detected {
+                ui.label(egui::RichText::new(prefix.display().to_string()).monospace());
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Other prefix:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.import_extra_prefix)
+                        .hint_text("/path/to/prefix")
+                        .desired_width(220.0),
+                );
+            });
+
+            let mut prefixes = detected.clone();
+            let extra = state.import_extra_prefix.trim();
+            if !extra.is_empty() {
+                prefixes.push(std::path::PathBuf::from(extra));
+            }
+
+            if let Some(rx) = &state.import_scan_rx {
+                match rx.try_recv() {
+                    Ok(scan) => {
+                        state.import_selections = scan
+                            .characters
+                            .iter()
+                            .map(|c| {
+                                let new = !profile.character_thumbnails.contains_key(&c.name);
+                                (c.name.clone(), new)
+                            })
+                            .collect();
+                        state.import_scan = Some(scan);
+                        state.import_scan_rx = None;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        ui.ctx()
+                            .request_repaint_after(std::time::Duration::from_millis(100));
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        state.import_scan_rx = None;
+                    }
+                }
+            }
+
+            let scanning = state.import_scan_rx.is_some();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!prefixes.is_empty() && !scanning, egui::Button::new("Scan"))
+                    .clicked()
+                {
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(eve_import::scan_prefixes(&prefixes));
+                    });
+                    state.import_scan_rx = Some(rx);
+                    state.import_scan = None;
+                }
+                if scanning {
+                    ui.spinner();
+                    ui.label("Scanning…");
+                }
+            });
+
+            let Some(scan) = &state.import_scan else {
+                return;
+            };
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+            ui.separator();
+
+            for prefix in &scan.skipped_prefixes {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("Skipped unreadable prefix {}", prefix.display()),
+                );
+            }
+
+            egui::ScrollArea::vertical()
+                .id_salt("import_characters_scroll")
+                .max_height(250.0)
+                .show(ui, |ui| {
+                    if scan.characters.is_empty() {
+                        ui.label(egui::RichText::new("No characters found.").weak().italics());
+                    }
+                    for character in &scan.characters {
+                        let Some(selected) = state.import_selections.get_mut(&character.name)
+                        else {
+                            continue;
+                        };
+                        ui.horizontal(|ui| {
+                            ui.checkbox(selected, &character.name);
+                            if profile.character_thumbnails.contains_key(&character.name) {
+                                ui.label(egui::RichText::new("(already added)").weak());
+                            }
+                            if let Some(played) = character.last_played {
+                                let played: chrono::DateTime<chrono::Local> = played.into();
+                                ui.label(
+                                    egui::RichText::new(played.format("%Y-%m-%d").to_string())
+                                        .weak(),
+                                )
+                                .on_hover_text("Last log written");
+                            }
+                        });
+                    }
+                });
+
+            if !scan.unnamed_ids.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} more character(s) have settings but no logs to name them.",
+                        scan.unnamed_ids.len()
+                    ))
+                    .weak()
+                    .small(),
+                );
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+            ui.separator();
+
+            let group_name = profile
+                .cycle_groups
+                .get(state.selected_cycle_group_index)
+                .map(|g| g.name.clone())
+                .unwrap_or_default();
+            ui.checkbox(
+                &mut state.import_add_to_group,
+                format!("Also add to cycle group \"{}\"", group_name),
+            );
+            ui.checkbox(
+                &mut state.import_assign_hotkeys,
+                "Assign free Ctrl+F1–F12 hotkeys",
+            )
+            .on_hover_text("Characters that already have a hotkey keep it");
+
+            let mut selected: Vec<String> = state
+                .import_selections
+                .iter()
+                .filter(|(_, selected)| **selected)
+                .map(|(name, _)| name.clone())
+                .collect();
+            selected.sort_by_key(|name| name.to_lowercase());
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !selected.is_empty(),
+                        egui::Button::new(format!("Import Selected ({})", selected.len())),
+                    )
+                    .clicked()
+                {
+                    let group = state
+                        .import_add_to_group
+                        .then_some(state.selected_cycle_group_index);
+                    let added =
+                        profile.import_characters(&selected, group, state.import_assign_hotkeys);
+                    if added > 0 || group.is_some() || state.import_assign_hotkeys {
+                        *changed = true;
+                    }
+                    state.show_import_popup = false;
+                }
+                if ui.button("Cancel").clicked() {
+                    state.show_import_popup = false;
+                }
+            });
+        });
+
+    if !open {
+        state.show_import_popup = false;
+    }
+}
@@ -0,0 +1,87 @@
+//! Floating control panel standing in for the tray icon on desktops without a
+//! StatusNotifier tray (plain window managers, some Wayland bars)
+
+use eframe::egui;
+
+use crate::common::constants::manager_ui::*;
+use crate::manager::state::SharedState;
+use crate::manager::theme::density_spacing;
+
+/// Shows the control panel as a small always-on-top window. Returns false once the user
+/// closed it, so the caller can stop showing it for the rest of the session.
+pub fn show(ctx: &egui::Context, state: &mut SharedState) -> bool {
+    let mut open = true;
+    ctx.show_viewport_immediate(
+        egui::ViewportId::from_hash_of("control_panel"),
+        egui::ViewportBuilder::default()
+            .with_title("EVE Preview Manager - Controls")
+            .with_inner_size([220.0, 170.0])
+            .with_resizable(false)
+            .with_always_on_top()
+            .with_taskbar(false),
+        |ctx, _class| {
+            if ctx.input(|i| i.viewport().close_requested()) {
+                open = false;
+            }
+            egui::CentralPanel::default().show(ctx, |ui| render(ui, state));
+        },
+    );
+    open
+}
+
+fn render(ui: &mut egui::Ui, state: &mut SharedState) {
+    ui.label(egui::RichText::new("No system tray found").weak());
+    ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+    ui.add_enabled_ui(state.daemon.is_some(), |ui| {
+        if ui
+            .button("Toggle Previews")
+            .on_hover_text("Show or hide all thumbnails")
+            .clicked()
+        {
+            state.request_toggle_previews();
+        }
+        let label = if state.paused { "Resume" } else { "Pause" };
+        if ui
+            .button(label)
+            .on_hover_text("Hide all thumbnails and release hotkeys until resumed")
+            .clicked()
+        {
+            state.set_paused(!state.paused);
+        }
+    });
+
+    let mut selected = state.selected_profile_idx;
+    let current = state
+        .config
+        .profiles
+        .get(selected)
+        .map(|p| p.profile_name.clone())
+        .unwrap_or_default();
+    egui::ComboBox::from_id_salt("control_panel_profile")
+        .selected_text(current)
+        .show_ui(ui, |ui| {
+            for (idx, profile) in state.config.profiles.iter().enumerate() {
+                ui.selectable_value(&mut selected, idx, &profile.profile_name);
+            }
+        });
+    if selected != state.selected_profile_idx {
+        state.switch_profile(selected);
+    }
+
+    ui.add_space(density_spacing(ui, ITEM_SPACING));
+    ui.horizontal(|ui| {
+        if ui.button("Show Manager").clicked() {
+            ui.ctx().send_viewport_cmd_to(
+                egui::ViewportId::ROOT,
+                egui::ViewportCommand::Minimized(false),
+            );
+            ui.ctx()
+                .send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Focus);
+        }
+        if ui.button("Quit").clicked() {
+            state.should_quit = true;
+            ui.ctx().request_repaint_of(egui::ViewportId::ROOT);
+        }
+    });
+}
@@ -8,7 +8,7 @@ use tracing::error;
 use crate::common::constants::manager_ui::*;
 use crate::manager::components::profile_selector::{ProfileAction, ProfileSelector};
 use crate::manager::state::core::SaveMode;
-use crate::manager::state::{ManagerTab, SharedState, StatusMessage};
+use crate::manager::state::{ConnectionState, ManagerTab, SharedState, StatusMessage};
 
 /// Renders the global header panel containing daemon status, tabs, and profile controls
 pub fn render(
@@ -28,6 +28,64 @@ pub fn render(
         if let Some(child) = &state.daemon {
             ui.label(format!("(PID: {})", child.id()));
         }
+
+        // IPC link indicator
+        let connection = state.connection_state();
+        let mut details = connection.label();
+        if state.daemon_status_rx.is_some() {
+            details.push_str(&format!(
+                "\nLast heartbeat: {}s ago",
+                state.last_heartbeat.elapsed().as_secs()
+            ));
+        }
+        if let Some(latency) = state.ipc_latency {
+            details.push_str(&format!("\nLatency: {} ms", latency.as_millis()));
+        }
+        if let Some(protocol) = state.daemon_protocol {
+            details.push_str(&format!("\nProtocol: v{protocol}"));
+        }
+        if let Some(metrics) = &state.daemon_metrics {
+            details.push_str(&format!(
+                "\nEvents: {:.1}/s ({} total)\nComposites: {}\nX errors: {}\nIPC: {} in / {} out",
+                metrics.events_per_sec,
+                metrics.events_total,
+                metrics.composite_ops_total,
+                metrics.x_errors_total,
+                metrics.ipc_received_total,
+                metrics.ipc_sent_total,
+            ));
+            for ((error, request), count) in state.x_errors.top(3) {
+                details.push_str(&format!("\n  Bad{error} in {request}: {count}"));
+            }
+            if let Some(bytes) = metrics.resident_memory_bytes {
+                details.push_str(&format!("\nMemory: {:.1} MiB", bytes as f64 / 1048576.0));
+            }
+        }
+        ui.colored_label(connection.color(), "●")
+            .on_hover_text(details);
+        if matches!(connection, ConnectionState::Reconnecting { .. }) {
+            ui.colored_label(connection.color(), connection.label());
+        }
+
+        // Pause / resume the whole daemon
+        let (label, hint) = if state.paused {
+            ("▶ Resume", "Show thumbnails and re-enable hotkeys")
+        } else {
+            (
+                "⏸ Pause",
+                "Hide all thumbnails and release hotkeys until resumed",
+            )
+        };
+        if ui
+            .add_enabled(state.daemon_connected(), egui::Button::new(label))
+            .on_hover_text(hint)
+            .clicked()
+        {
+            state.set_paused(!state.paused);
+        }
+        if state.paused {
+            ui.colored_label(COLOR_WARNING, "Paused");
+        }
         if let Some(message) = &state.status_message {
             ui.add_space(10.0);
             ui.colored_label(message.color, &message.text);
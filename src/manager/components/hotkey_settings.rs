@@ -1,9 +1,11 @@
 //! Hotkey settings component for profile configuration
 
 use crate::common::constants::manager_ui::*;
-use crate::config::HotkeyBackendType;
+use crate::common::ipc::HotkeyTestResult;
 use crate::config::profile::Profile;
+use crate::config::{HotkeyBackendType, HotkeyBinding, HotkeyFocusContext};
 use crate::manager::key_capture::{self, CaptureResult, CaptureState};
+use crate::manager::theme::density_spacing;
 use eframe::egui;
 use std::sync::mpsc::Receiver;
 
@@ -11,11 +13,26 @@ use std::sync::mpsc::Receiver;
 enum CaptureTarget {
     ToggleSkip,         // Hotkey to temporarily skip current character
     TogglePreviews,     // Hotkey to toggle thumbnail visibility
+    Overview,           // Hotkey to open/close the overview of all clients
+    Flip,               // Hotkey to flip back to the previously focused character
+    Peek,               // Hold-to-enlarge hotkey for the next character in the cycle
+    Reveal,             // Hold-to-show hotkey for thumbnails hidden by focus rules
+    Panic,              // Hotkey to minimize/hide everything and restore on second press
+    Pause,              // Hotkey to pause/resume the whole daemon
+    Suspend,            // Hotkey to release the other hotkeys for a few seconds
     Profile,            // Hotkey to switch to this profile
     Character(String),  // Character name for per-character hotkey
     CustomRule(String), // Custom Window Rule alias (Custom Source Hotkey)
 }
 
+/// Progress of testing a captured binding against the daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HotkeyTest {
+    /// The daemon was asked to listen for this binding
+    Waiting(HotkeyBinding),
+    Finished(HotkeyTestResult),
+}
+
 /// State for hotkey settings Manager
 pub struct HotkeySettingsState {
     // Input device state
@@ -31,6 +48,13 @@ pub struct HotkeySettingsState {
     current_capture_state: Option<CaptureState>,
     capture_result: Option<CaptureResult>,
     capture_error: Option<String>,
+
+    // Bindings the running daemon grabs, kept current by the app
+    pub daemon_hotkeys: Vec<HotkeyBinding>,
+
+    // Hotkey test state (request taken by the app and sent to the daemon)
+    test_request: Option<HotkeyBinding>,
+    hotkey_test: Option<HotkeyTest>,
 }
 
 impl HotkeySettingsState {
@@ -55,6 +79,9 @@ impl HotkeySettingsState {
             current_capture_state: None,
             capture_result: None,
             capture_error: None,
+            daemon_hotkeys: Vec::new(),
+            test_request: None,
+            hotkey_test: None,
         }
     }
 
@@ -68,7 +95,7 @@ impl HotkeySettingsState {
         // Ensure any previous capture is cancelled first
         self.cancel_capture();
 
-        match key_capture::start_capture(backend) {
+        match key_capture::start_capture(backend, self.daemon_hotkeys.clone()) {
             Ok((state_rx, result_rx, cancel_tx)) => {
                 self.show_key_capture_dialog = true;
                 self.capture_target = Some(target);
@@ -78,6 +105,8 @@ impl HotkeySettingsState {
                 self.current_capture_state = Some(CaptureState::new());
                 self.capture_result = None;
                 self.capture_error = None;
+                self.test_request = None;
+                self.hotkey_test = None;
             }
             Err(e) => {
                 self.capture_error = Some(format!("Failed to start key capture: {}", e));
@@ -96,6 +125,8 @@ impl HotkeySettingsState {
         self.capture_result_rx = None;
         self.current_capture_state = None;
         self.capture_result = None;
+        self.test_request = None;
+        self.hotkey_test = None;
     }
 
     /// Public method for starting character-specific hotkey capture
@@ -133,6 +164,19 @@ impl HotkeySettingsState {
         }
     }
 
+    /// Binding the user asked to test, for the caller to send to the daemon
+    pub fn take_test_request(&mut self) -> Option<HotkeyBinding> {
+        self.test_request.take()
+    }
+
+    /// Shows the outcome of testing `binding`. Replies for a binding no longer being tested
+    /// (the dialog was closed or the key captured again) are dropped.
+    pub fn show_test_result(&mut self, binding: &HotkeyBinding, result: HotkeyTestResult) {
+        if self.hotkey_test == Some(HotkeyTest::Waiting(binding.clone())) {
+            self.hotkey_test = Some(HotkeyTest::Finished(result));
+        }
+    }
+
     /// Check if the key capture dialog is currently open
     pub fn is_dialog_open(&self) -> bool {
         self.show_key_capture_dialog
@@ -155,7 +199,7 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
     // Show capture error if any
     if let Some(ref error) = state.capture_error {
         ui.colored_label(egui::Color32::from_rgb(200, 0, 0), format!("⚠ {}", error));
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
     }
 
     ui.columns(2, |columns| {
@@ -163,16 +207,17 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
         columns[0].group(|ui| {
             ui.set_min_width(ui.available_width());
             ui.label(egui::RichText::new("General Settings").strong());
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Backend selector
             ui.label("Hotkey Backend:");
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
             use crate::config::HotkeyBackendType;
             let backend_display = match profile.hotkey_backend {
                 HotkeyBackendType::X11 => "X11 (Recommended)",
                 HotkeyBackendType::Evdev => "evdev (Advanced - Requires Permissions)",
+                HotkeyBackendType::Portal => "Desktop Portal (Wayland)",
             };
 
             egui::ComboBox::from_id_salt("hotkey_backend_selector")
@@ -185,9 +230,12 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
                     if ui.selectable_value(&mut profile.hotkey_backend, HotkeyBackendType::Evdev, "evdev (Advanced - Requires Permissions)").clicked() {
                         changed = true;
                     }
+                    if ui.selectable_value(&mut profile.hotkey_backend, HotkeyBackendType::Portal, "Desktop Portal (Wayland)").clicked() {
+                        changed = true;
+                    }
                 });
 
-            ui.add_space(ITEM_SPACING / 4.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 4.0));
 
             // Show backend capabilities and warnings
             match profile.hotkey_backend {
@@ -196,17 +244,22 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
                 }
                 HotkeyBackendType::Evdev => {
                     ui.label(egui::RichText::new("⚠ Security Warning: evdev backend requires 'input' group membership.").small());
+                    ui.label(egui::RichText::new("Without access to /dev/input the daemon falls back to X11 hotkeys.").small().weak());
+                }
+                HotkeyBackendType::Portal => {
+                    ui.label(egui::RichText::new("Shortcuts are confirmed in your desktop's dialog and can be changed in its settings.").small());
+                    ui.label(egui::RichText::new("Mouse buttons can't be bound, and shortcuts outside the focus context are swallowed rather than passed to the app.").small().weak());
                 }
             }
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
             ui.separator();
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Input device selector (only shown for evdev backend)
             if profile.hotkey_backend == HotkeyBackendType::Evdev {
                 ui.label("Input device to monitor:");
-                ui.add_space(ITEM_SPACING / 2.0);
+                ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                 let selected_display = match profile.hotkey_input_device.as_deref() {
                     None => "---".to_string(),
@@ -236,41 +289,38 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
                     });
 
                 if let Some(ref error) = state.device_load_error {
-                    ui.add_space(ITEM_SPACING / 4.0);
+                    ui.add_space(density_spacing(ui, ITEM_SPACING / 4.0));
                     ui.label(egui::RichText::new(format!("⚠ {}", error)).small().color(egui::Color32::from_rgb(200, 100, 0)));
                 }
 
                 // Show helper text for auto-detect mode
                 if profile.hotkey_input_device.as_deref() == Some("auto") {
-                    ui.add_space(ITEM_SPACING / 4.0);
+                    ui.add_space(density_spacing(ui, ITEM_SPACING / 4.0));
                     ui.label(egui::RichText::new("Devices will be automatically detected when you bind keys").small().weak());
                 }
 
                 // Show helper text for all devices mode
                 if profile.hotkey_input_device.as_deref() == Some("all") {
-                    ui.add_space(ITEM_SPACING / 4.0);
+                    ui.add_space(density_spacing(ui, ITEM_SPACING / 4.0));
                     ui.label(egui::RichText::new("Hotkeys will work from any connected input device").small().weak());
                 }
 
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
                  ui.separator();
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
             }
 
             // For X11 backend, device selection is not applicable
             let device_selected = match profile.hotkey_backend {
-                HotkeyBackendType::X11 => true, // Always enabled for X11
+                HotkeyBackendType::X11 | HotkeyBackendType::Portal => true, // No device to pick
                 HotkeyBackendType::Evdev => profile.hotkey_input_device.is_some(),
             };
 
             ui.add_enabled_ui(device_selected, |ui| {
-                // Require EVE focus checkbox
-                if ui.checkbox(&mut profile.hotkey_require_eve_focus, "Require EVE window focus").changed() {
-                    changed = true;
-                }
-                ui.label(egui::RichText::new("Cycle hotkeys only work when an EVE window is focused").small().weak());
+                // Focus context
+                changed |= render_focus_context(ui, profile);
 
-                ui.add_space(ITEM_SPACING);
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                 // Logged-out cycling checkbox
                 if ui.checkbox(&mut profile.hotkey_logged_out_cycle, "Include logged-out characters").changed() {
@@ -284,17 +334,17 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
         columns[1].group(|ui| {
             ui.set_min_width(ui.available_width());
             ui.label(egui::RichText::new("Other Hotkeys").strong());
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // For X11 backend, device selection is not applicable (duplicated logic for right column enabled state)
             let device_selected = match profile.hotkey_backend {
-                HotkeyBackendType::X11 => true,
+                HotkeyBackendType::X11 | HotkeyBackendType::Portal => true,
                 HotkeyBackendType::Evdev => profile.hotkey_input_device.is_some(),
             };
 
             ui.add_enabled_ui(device_selected, |ui| {
                  ui.label("Load Profile Hotkey:");
-                 ui.add_space(ITEM_SPACING / 2.0);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                  ui.horizontal(|ui| {
                     let binding_text = profile.hotkey_profile_switch.as_ref()
@@ -319,16 +369,16 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
                     }
                  });
 
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
                  ui.label(egui::RichText::new("Pressing this hotkey will immediately switch to this profile.").weak().small());
 
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
                  ui.separator();
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                  // Toggle Skip Hotkey
                  ui.label("Toggle Skip Hotkey:");
-                 ui.add_space(ITEM_SPACING / 2.0);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                  ui.horizontal(|ui| {
                     let binding_text = profile.hotkey_toggle_skip.as_ref()
@@ -353,16 +403,16 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
                         changed = true;
                     }
                  });
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
                  ui.label(egui::RichText::new("Temporarily skip the current character from cycling.").weak().small());
 
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
                  ui.separator();
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                  // Toggle Previews Hotkey
                  ui.label("Toggle Previews Hotkey:");
-                 ui.add_space(ITEM_SPACING / 2.0);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                  ui.horizontal(|ui| {
                     let binding_text = profile.hotkey_toggle_previews.as_ref()
@@ -386,12 +436,265 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
                         changed = true;
                     }
                  });
-                 ui.add_space(ITEM_SPACING);
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
                  ui.label(egui::RichText::new("Show/Hide all thumbnails (resets to visible on restart).").weak().small());
 
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Overview Hotkey
+                 ui.label("Overview Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_overview.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_overview.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Overview, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_overview.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_overview = None;
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("Tile large previews of every client across the screen. Click one to switch to it, or press again to close.").weak().small());
+
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Flip Hotkey
+                 ui.label("Flip to Previous Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_flip.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_flip.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Flip, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_flip.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_flip = None;
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("Switch back to the character focused before the current one, like Alt-Tab. Press again to flip back.").weak().small());
+
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Peek Hotkey
+                 ui.label("Peek Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_peek.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_peek.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Peek, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_peek.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_peek = None;
+                        changed = true;
+                    }
+                 });
+                 ui.horizontal(|ui| {
+                    ui.label("Peek Size:");
+                    if ui.add(egui::Slider::new(&mut profile.hotkey_peek_scale, 1.25..=4.0).step_by(0.25).suffix("×")).changed() {
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("While held, enlarges the thumbnail of the next character in the cycle without switching to it.").weak().small());
+
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Reveal Hotkey
+                 ui.label("Reveal Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_reveal.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_reveal.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Reveal, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_reveal.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_reveal = None;
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("While held, shows thumbnails hidden by \"Hide thumbnails when EVE loses focus\" without switching back to EVE. Works even when EVE is not focused.").weak().small());
+
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Panic Hotkey
+                 ui.label("Panic Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_panic.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_panic.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Panic, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_panic.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_panic = None;
+                        changed = true;
+                    }
+                 });
+                 ui.horizontal(|ui| {
+                    ui.label("Decoy window class:");
+                    if ui
+                        .text_edit_singleline(&mut profile.hotkey_panic_decoy_class)
+                        .on_hover_text("WM_CLASS of a window to focus when the panic hotkey fires (leave empty for none)")
+                        .changed()
+                    {
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("Minimizes every EVE client, hides all thumbnails and focuses the decoy window. Press again to restore everything. Works even when EVE is not focused.").weak().small());
+
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Pause Hotkey
+                 ui.label("Pause Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_pause.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_pause.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Pause, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_pause.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_pause = None;
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("Pauses the whole manager: hides all thumbnails and releases every other hotkey until pressed again. Also available from the tray, the header and `eve-preview-manager pause`. Works even when EVE is not focused.").weak().small());
+
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.separator();
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                 // Suspend Hotkey
+                 ui.label("Suspend Hotkey:");
+                 ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
+
+                 ui.horizontal(|ui| {
+                    let binding_text = profile.hotkey_suspend.as_ref()
+                        .map(|b| b.display_name())
+                        .unwrap_or_else(|| "Not set".to_string());
+
+                    let color = if profile.hotkey_suspend.is_none() {
+                         ui.style().visuals.weak_text_color()
+                    } else {
+                        ui.style().visuals.text_color()
+                    };
+
+                    ui.label(egui::RichText::new(binding_text).strong().color(color));
+
+                    if ui.button("⌨ Bind").clicked() {
+                        state.start_key_capture(CaptureTarget::Suspend, profile.hotkey_backend);
+                    }
+
+                    if profile.hotkey_suspend.is_some() && ui.small_button("✖").on_hover_text("Clear binding").clicked() {
+                        profile.hotkey_suspend = None;
+                        changed = true;
+                    }
+                 });
+                 ui.horizontal(|ui| {
+                    ui.label("Suspend For:");
+                    if ui.add(egui::Slider::new(&mut profile.hotkey_suspend_secs, 1..=120).suffix(" s")).changed() {
+                        changed = true;
+                    }
+                 });
+                 ui.add_space(density_spacing(ui, ITEM_SPACING));
+                 ui.label(egui::RichText::new("Releases every other hotkey for a few seconds, so keys like Tab reach EVE while you type in chat. Press again to resume early.").weak().small());
+
 
                  if profile.hotkey_backend == HotkeyBackendType::Evdev {
-                      ui.add_space(ITEM_SPACING);
+                      ui.add_space(density_spacing(ui, ITEM_SPACING));
                       ui.label(egui::RichText::new("Note: Global profile hotkeys require the Evdev backend to work reliably when the EVE client is not focused.").weak().small().italics());
                  }
             });
@@ -406,6 +709,104 @@ pub fn ui(ui: &mut egui::Ui, profile: &mut Profile, state: &mut HotkeySettingsSt
     changed
 }
 
+fn focus_context_label(context: HotkeyFocusContext) -> &'static str {
+    match context {
+        HotkeyFocusContext::Always => "Always",
+        HotkeyFocusContext::Clients => "EVE or a custom source",
+        HotkeyFocusContext::Eve => "EVE only",
+        HotkeyFocusContext::Sources => "Selected custom sources",
+    }
+}
+
+/// Which focused window lets hotkeys fire, and apps that disable them. Returns true when changed.
+fn render_focus_context(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Hotkeys work when focused:");
+        egui::ComboBox::from_id_salt("hotkey_focus_context")
+            .selected_text(focus_context_label(profile.hotkey_focus_context))
+            .show_ui(ui, |ui| {
+                for context in [
+                    HotkeyFocusContext::Always,
+                    HotkeyFocusContext::Clients,
+                    HotkeyFocusContext::Eve,
+                    HotkeyFocusContext::Sources,
+                ] {
+                    if ui
+                        .selectable_value(
+                            &mut profile.hotkey_focus_context,
+                            context,
+                            focus_context_label(context),
+                        )
+                        .clicked()
+                    {
+                        changed = true;
+                    }
+                }
+            });
+    });
+
+    if profile.hotkey_focus_context == HotkeyFocusContext::Sources {
+        if profile.custom_windows.is_empty() {
+            ui.label(
+                egui::RichText::new("No custom sources configured")
+                    .small()
+                    .weak(),
+            );
+        }
+        for rule in &profile.custom_windows {
+            let mut selected = profile.hotkey_focus_sources.contains(&rule.alias);
+            if ui.checkbox(&mut selected, &rule.alias).changed() {
+                if selected {
+                    profile.hotkey_focus_sources.push(rule.alias.clone());
+                } else {
+                    profile.hotkey_focus_sources.retain(|s| s != &rule.alias);
+                }
+                changed = true;
+            }
+        }
+    }
+    ui.label(
+        egui::RichText::new("Panic, pause, suspend and reveal hotkeys work whatever is focused")
+            .small()
+            .weak(),
+    );
+
+    ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+    ui.label("Disable hotkeys while these apps are focused (window class):")
+        .on_hover_text("e.g. firefox, discord. Matched case-insensitively against WM_CLASS.");
+    let mut remove_idx = None;
+    for (idx, class) in profile.hotkey_blocked_classes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::TextEdit::singleline(class)
+                        .hint_text("window class")
+                        .desired_width(160.0),
+                )
+                .changed()
+            {
+                changed = true;
+            }
+            if ui.small_button("🗑").on_hover_text("Remove app").clicked() {
+                remove_idx = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = remove_idx {
+        profile.hotkey_blocked_classes.remove(idx);
+        changed = true;
+    }
+    if ui.small_button("➕ Add App").clicked() {
+        profile.hotkey_blocked_classes.push(String::new());
+        changed = true;
+    }
+
+    changed
+}
+
 /// Renders the key capture modal dialog
 /// Returns true if changes were made (e.g. key bound)
 pub fn render_key_capture_modal(
@@ -452,40 +853,36 @@ pub fn render_key_capture_modal(
     egui::Window::new("⌨ Capture Key")
         .collapsible(false)
         .resizable(false)
-        .fixed_size([370.0, 280.0])
+        .fixed_size([370.0, 340.0])
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
         .show(ui.ctx(), |ui| {
-            let target_name = match state.capture_target {
-                Some(CaptureTarget::ToggleSkip) => "Toggle Skip".to_string(),
-                Some(CaptureTarget::TogglePreviews) => "Toggle Previews".to_string(),
-                Some(CaptureTarget::Profile) => "Switch to Profile".to_string(),
-                Some(CaptureTarget::Character(ref name)) => format!("Character: {}", name),
-                Some(CaptureTarget::CustomRule(ref alias)) => format!("Custom Source: {}", alias),
-                None => "Unknown".to_string(),
-            };
+            let target_name = target_label(state.capture_target.as_ref(), profile);
 
             ui.label(format!("Binding key for: {}", target_name));
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Show current capture state
             if let Some(ref capture_state) = state.current_capture_state {
                 ui.group(|ui| {
                     ui.set_min_width(320.0);
                     ui.vertical_centered(|ui| {
-                        ui.add_space(ITEM_SPACING);
+                        ui.add_space(density_spacing(ui, ITEM_SPACING));
                         ui.label(
                             egui::RichText::new(&capture_state.description)
                                 .size(20.0)
                                 .strong(),
                         );
-                        ui.add_space(ITEM_SPACING);
+                        if let Some(detail) = capture_state.key_detail() {
+                            ui.label(egui::RichText::new(detail).monospace().weak());
+                        }
+                        ui.add_space(density_spacing(ui, ITEM_SPACING));
                     });
                 });
             } else {
                 ui.label("Initializing capture...");
             }
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Reserve space for device list (shown after capture)
             // This prevents the modal from shifting when devices are displayed
@@ -522,14 +919,14 @@ pub fn render_key_capture_modal(
                 ui.spacing_mut().item_spacing.y = 4.0;
             }
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Instructions
             ui.label(egui::RichText::new("Instructions:").strong());
             ui.label("• Press any key combination to bind it");
             ui.label("• Press Esc to cancel");
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Check if capture completed
             if let Some(ref result) = state.capture_result {
@@ -538,12 +935,22 @@ pub fn render_key_capture_modal(
                         let binding_clone = binding.clone();
                         let target = state.capture_target.clone();
 
+                        let conflicts = profile.hotkey_conflicts(binding, &target_name);
+                        if !conflicts.is_empty() {
+                            ui.colored_label(
+                                COLOR_WARNING,
+                                format!("⚠ Also bound to: {}", conflicts.join(", ")),
+                            );
+                        }
+                        render_test_status(ui, state.hotkey_test.as_ref(), binding);
+
                         ui.separator();
-                        ui.add_space(ITEM_SPACING / 2.0);
+                        ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                         let mut should_accept = false;
                         let mut should_retry = false;
                         let mut should_cancel = false;
+                        let mut should_test = false;
 
                         // Check for Escape key to cancel at confirmation step
                         ui.input(|i| {
@@ -557,11 +964,29 @@ pub fn render_key_capture_modal(
                                 should_accept = true;
                             }
 
+                            // Portal shortcuts were just confirmed by the desktop's own dialog
+                            let testable = profile.hotkey_backend != HotkeyBackendType::Portal
+                                && !matches!(state.hotkey_test, Some(HotkeyTest::Waiting(_)));
+                            if ui
+                                .add_enabled(testable, egui::Button::new("🧪 Test"))
+                                .on_hover_text(
+                                    "Check that the daemon receives this key before saving",
+                                )
+                                .clicked()
+                            {
+                                should_test = true;
+                            }
+
                             if ui.button("⟲ Try Again").clicked() {
                                 should_retry = true;
                             }
                         });
 
+                        if should_test {
+                            state.test_request = Some(binding_clone.clone());
+                            state.hotkey_test = Some(HotkeyTest::Waiting(binding_clone.clone()));
+                        }
+
                         if should_cancel {
                             state.cancel_capture();
                         } else if should_accept {
@@ -574,6 +999,34 @@ pub fn render_key_capture_modal(
                                     profile.hotkey_toggle_previews = Some(binding_clone);
                                     changed = true;
                                 }
+                                Some(CaptureTarget::Overview) => {
+                                    profile.hotkey_overview = Some(binding_clone);
+                                    changed = true;
+                                }
+                                Some(CaptureTarget::Flip) => {
+                                    profile.hotkey_flip = Some(binding_clone);
+                                    changed = true;
+                                }
+                                Some(CaptureTarget::Peek) => {
+                                    profile.hotkey_peek = Some(binding_clone);
+                                    changed = true;
+                                }
+                                Some(CaptureTarget::Reveal) => {
+                                    profile.hotkey_reveal = Some(binding_clone);
+                                    changed = true;
+                                }
+                                Some(CaptureTarget::Panic) => {
+                                    profile.hotkey_panic = Some(binding_clone);
+                                    changed = true;
+                                }
+                                Some(CaptureTarget::Pause) => {
+                                    profile.hotkey_pause = Some(binding_clone);
+                                    changed = true;
+                                }
+                                Some(CaptureTarget::Suspend) => {
+                                    profile.hotkey_suspend = Some(binding_clone);
+                                    changed = true;
+                                }
                                 Some(CaptureTarget::Profile) => {
                                     profile.hotkey_profile_switch = Some(binding_clone);
                                     changed = true;
@@ -632,6 +1085,20 @@ pub fn render_key_capture_modal(
                             state.start_key_capture(t.clone(), profile.hotkey_backend);
                         }
                     }
+                    CaptureResult::Rejected(reason) => {
+                        ui.colored_label(COLOR_ERROR, format!("✖ {}", reason));
+                        ui.add_space(density_spacing(ui, ITEM_SPACING));
+                        let target = state.capture_target.clone();
+                        ui.horizontal(|ui| {
+                            if ui.button("⟲ Try Again").clicked()
+                                && let Some(t) = target
+                            {
+                                state.start_key_capture(t, profile.hotkey_backend);
+                            } else if ui.button("Close").clicked() {
+                                state.cancel_capture();
+                            }
+                        });
+                    }
                     CaptureResult::Cancelled => {
                         // Handled automatically above
                     }
@@ -640,7 +1107,7 @@ pub fn render_key_capture_modal(
                             egui::Color32::from_rgb(200, 100, 0),
                             "Capture timed out (no key pressed)",
                         );
-                        ui.add_space(ITEM_SPACING);
+                        ui.add_space(density_spacing(ui, ITEM_SPACING));
                         if ui.button("Close").clicked() {
                             state.cancel_capture();
                         }
@@ -650,7 +1117,7 @@ pub fn render_key_capture_modal(
                             egui::Color32::from_rgb(200, 0, 0),
                             format!("Error: {}", err),
                         );
-                        ui.add_space(ITEM_SPACING);
+                        ui.add_space(density_spacing(ui, ITEM_SPACING));
                         if ui.button("Close").clicked() {
                             state.cancel_capture();
                         }
@@ -658,7 +1125,7 @@ pub fn render_key_capture_modal(
                 }
             } else {
                 ui.separator();
-                ui.add_space(ITEM_SPACING / 2.0);
+                ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
                 if ui.button("✖ Cancel").clicked() {
                     state.cancel_capture();
                 }
@@ -667,3 +1134,66 @@ pub fn render_key_capture_modal(
 
     changed
 }
+
+/// Name of the action a capture binds, matching the labels of `Profile::hotkey_assignments`
+fn target_label(target: Option<&CaptureTarget>, profile: &Profile) -> String {
+    match target {
+        Some(CaptureTarget::ToggleSkip) => "Toggle Skip".to_string(),
+        Some(CaptureTarget::TogglePreviews) => "Toggle Previews".to_string(),
+        Some(CaptureTarget::Overview) => "Overview".to_string(),
+        Some(CaptureTarget::Flip) => "Flip to Previous".to_string(),
+        Some(CaptureTarget::Peek) => "Peek".to_string(),
+        Some(CaptureTarget::Reveal) => "Reveal".to_string(),
+        Some(CaptureTarget::Panic) => "Panic".to_string(),
+        Some(CaptureTarget::Pause) => "Pause".to_string(),
+        Some(CaptureTarget::Suspend) => "Suspend".to_string(),
+        Some(CaptureTarget::Profile) => "Switch to Profile".to_string(),
+        Some(CaptureTarget::Character(name)) => {
+            // Cycle group keys are captured as "GROUP:<index>:FWD" or "GROUP:<index>:BWD"
+            let parts: Vec<&str> = name.split(':').collect();
+            match parts.as_slice() {
+                ["GROUP", idx, direction] => {
+                    let group = idx
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| profile.cycle_groups.get(i))
+                        .map_or("?", |g| g.name.as_str());
+                    let direction = if *direction == "BWD" {
+                        "Backward"
+                    } else {
+                        "Forward"
+                    };
+                    format!("Cycle Group '{}' {}", group, direction)
+                }
+                _ => format!("Character: {}", name),
+            }
+        }
+        Some(CaptureTarget::CustomRule(alias)) => format!("Custom Source: {}", alias),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Progress line for testing `binding` against the daemon
+fn render_test_status(ui: &mut egui::Ui, test: Option<&HotkeyTest>, binding: &HotkeyBinding) {
+    match test {
+        None => {}
+        Some(HotkeyTest::Waiting(_)) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("Press {} now...", binding.display_name()));
+            });
+        }
+        Some(HotkeyTest::Finished(HotkeyTestResult::Received)) => {
+            ui.colored_label(COLOR_SUCCESS, "✔ The daemon received the key");
+        }
+        Some(HotkeyTest::Finished(HotkeyTestResult::AlreadyBound)) => {
+            ui.colored_label(
+                COLOR_SUCCESS,
+                "✔ The running profile already uses this key, so the daemon receives it",
+            );
+        }
+        Some(HotkeyTest::Finished(HotkeyTestResult::Failed(reason))) => {
+            ui.colored_label(COLOR_ERROR, format!("✖ {}", reason));
+        }
+    }
+}
@@ -1,8 +1,10 @@
 pub mod behavior_settings;
 pub mod characters;
+pub mod control_panel;
 pub mod header;
 pub mod hotkey_settings;
 pub mod profile_selector;
+pub mod reorderable_list;
 pub mod sources;
 pub mod tray;
 pub mod visual_settings;
@@ -1,5 +1,7 @@
 use crate::common::constants::manager_ui::*;
 use crate::config::profile::{Config, Profile};
+use crate::manager::components::reorderable_list::{self, ListEdit};
+use crate::manager::theme::density_spacing;
 use eframe::egui;
 
 pub struct ProfileSelector {
@@ -9,6 +11,8 @@ pub struct ProfileSelector {
     show_duplicate_dialog: bool,
     show_delete_confirm: bool,
     show_edit_dialog: bool,
+    show_arrange_dialog: bool,
+    show_lock_dialog: bool,
     pending_profile_idx: Option<usize>,
     /// Index of the profile we are performing an action on (Edit/Duplicate/Delete)
     /// This might be different from selected_idx (active profile) if user is editing a non-active profile
@@ -24,6 +28,8 @@ impl ProfileSelector {
             show_duplicate_dialog: false,
             show_delete_confirm: false,
             show_edit_dialog: false,
+            show_arrange_dialog: false,
+            show_lock_dialog: false,
             pending_profile_idx: None,
             action_target_idx: None,
         }
@@ -95,6 +101,10 @@ impl ProfileSelector {
         // Determine which profile is visually selected in the dropdown
         // If pending_profile_idx is None, it means the dropdown shows the active profile (selected_idx)
         let target_idx = self.pending_profile_idx.unwrap_or(selected_idx);
+        let locked = config
+            .profiles
+            .get(target_idx)
+            .is_some_and(|p| p.profile_locked);
 
         ui.horizontal(|ui| {
             if ui.button("➕ New").clicked() {
@@ -120,7 +130,10 @@ impl ProfileSelector {
             }
 
             if ui
-                .add_enabled(!config.profiles.is_empty(), egui::Button::new("✏ Edit"))
+                .add_enabled(
+                    !config.profiles.is_empty() && !locked,
+                    egui::Button::new("✏ Edit"),
+                )
                 .clicked()
             {
                 self.show_edit_dialog = true;
@@ -132,13 +145,34 @@ impl ProfileSelector {
 
             // Can delete if we have > 1 profile
             if ui
-                .add_enabled(config.profiles.len() > 1, egui::Button::new("🗑 Delete"))
+                .add_enabled(
+                    config.profiles.len() > 1 && !locked,
+                    egui::Button::new("🗑 Delete"),
+                )
                 .clicked()
             {
                 self.show_delete_confirm = true;
                 self.action_target_idx = Some(target_idx);
             }
 
+            let lock_label = if locked { "🔓 Unlock" } else { "🔒 Lock" };
+            if ui
+                .add_enabled(!config.profiles.is_empty(), egui::Button::new(lock_label))
+                .on_hover_text("Locked profiles are read-only: no settings or thumbnail positions are saved to them")
+                .clicked()
+            {
+                self.show_lock_dialog = true;
+                self.action_target_idx = Some(target_idx);
+            }
+
+            if ui
+                .add_enabled(config.profiles.len() > 1, egui::Button::new("⇅ Arrange"))
+                .on_hover_text("Change the order profiles are listed in")
+                .clicked()
+            {
+                self.show_arrange_dialog = true;
+            }
+
             if config.profiles.len() == 1 {
                 ui.label("(Cannot delete last profile)");
             }
@@ -175,6 +209,19 @@ impl ProfileSelector {
             action = self.delete_confirm_dialog(ctx, config, selected_idx, target_idx);
         }
 
+        if self.show_lock_dialog {
+            let target_idx = self.action_target_idx.unwrap_or(*selected_idx);
+            action = self.lock_dialog(ctx, config, target_idx);
+        }
+
+        if self.show_arrange_dialog {
+            // Can open the delete confirmation above, so don't let it mask that dialog's action
+            let arrange_action = self.arrange_profiles_dialog(ctx, config, selected_idx);
+            if arrange_action != ProfileAction::None {
+                action = arrange_action;
+            }
+        }
+
         // Clear pending selection/target after profile modifications
         match action {
             ProfileAction::ProfileCreated
@@ -203,7 +250,7 @@ impl ProfileSelector {
                 ui.label("Description (optional):");
                 ui.text_edit_singleline(&mut self.edit_profile_desc);
 
-                ui.add_space(ITEM_SPACING);
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                 ui.horizontal(|ui| {
                     if ui.button("Create").clicked() && !self.edit_profile_name.is_empty() {
@@ -244,13 +291,15 @@ impl ProfileSelector {
                 ui.label("Description (optional):");
                 ui.text_edit_singleline(&mut self.edit_profile_desc);
 
-                ui.add_space(ITEM_SPACING);
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                 ui.horizontal(|ui| {
                     if ui.button("Duplicate").clicked() && !self.edit_profile_name.is_empty() {
                         let mut new_profile = config.profiles[source_idx].clone();
                         new_profile.profile_name = self.edit_profile_name.clone();
                         new_profile.profile_description = self.edit_profile_desc.clone();
+                        // Copies of a shared profile are the usual way to customize it
+                        new_profile.profile_locked = false;
                         config.profiles.push(new_profile);
 
                         action = ProfileAction::ProfileCreated;
@@ -285,7 +334,7 @@ impl ProfileSelector {
                 ui.label("Description (optional):");
                 ui.text_edit_singleline(&mut self.edit_profile_desc);
 
-                ui.add_space(ITEM_SPACING);
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                 ui.horizontal(|ui| {
                     if ui.button("Save").clicked() && !self.edit_profile_name.is_empty() {
@@ -311,6 +360,133 @@ impl ProfileSelector {
         action
     }
 
+    fn arrange_profiles_dialog(
+        &mut self,
+        ctx: &egui::Context,
+        config: &mut Config,
+        active_idx: &mut usize,
+    ) -> ProfileAction {
+        let mut action = ProfileAction::None;
+        let mut open = true;
+
+        egui::Window::new("Arrange Profiles")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(
+                        "Drag profiles to reorder them. Right-click a handle to delete.",
+                    )
+                    .weak()
+                    .small(),
+                );
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+                let active = *active_idx;
+                let edit = reorderable_list::show(
+                    ui,
+                    "profile_order",
+                    &mut config.profiles,
+                    |ui, idx, profile| {
+                        ui.horizontal(|ui| {
+                            if idx == active {
+                                ui.label(egui::RichText::new(&profile.profile_name).strong());
+                                ui.label(egui::RichText::new("(active)").weak());
+                            } else {
+                                ui.label(&profile.profile_name);
+                            }
+                            if profile.profile_locked {
+                                ui.label("🔒").on_hover_text("Locked");
+                            }
+                            if !profile.profile_description.is_empty() {
+                                ui.label(
+                                    egui::RichText::new(&profile.profile_description)
+                                        .weak()
+                                        .small(),
+                                );
+                            }
+                        });
+                    },
+                );
+
+                match edit {
+                    // Deleting goes through the usual confirmation
+                    Some(ListEdit::Delete(idx))
+                        if config.profiles.len() > 1 && !config.profiles[idx].profile_locked =>
+                    {
+                        self.show_delete_confirm = true;
+                        self.action_target_idx = Some(idx);
+                    }
+                    Some(edit @ ListEdit::Move { .. }) if edit.apply(&mut config.profiles) => {
+                        *active_idx = edit.remap(*active_idx).unwrap_or_default();
+                        action = ProfileAction::ProfileUpdated;
+                    }
+                    _ => {}
+                }
+
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
+                if ui.button("Close").clicked() {
+                    self.show_arrange_dialog = false;
+                }
+            });
+
+        if !open {
+            self.show_arrange_dialog = false;
+        }
+
+        action
+    }
+
+    fn lock_dialog(
+        &mut self,
+        ctx: &egui::Context,
+        config: &mut Config,
+        target_idx: usize,
+    ) -> ProfileAction {
+        let mut action = ProfileAction::None;
+        let profile = &mut config.profiles[target_idx];
+        let locking = !profile.profile_locked;
+
+        egui::Window::new(if locking {
+            "Lock Profile"
+        } else {
+            "Unlock Profile"
+        })
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if locking {
+                ui.label(format!("Lock profile '{}'?", profile.profile_name));
+                ui.label(
+                    "Settings and thumbnail positions will no longer be saved to it until it is unlocked.",
+                );
+            } else {
+                ui.label(format!("Unlock profile '{}'?", profile.profile_name));
+                ui.colored_label(
+                    COLOR_WARNING,
+                    "Changes and thumbnail moves will be saved to it again.",
+                );
+            }
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            ui.horizontal(|ui| {
+                if ui.button(if locking { "Lock" } else { "Unlock" }).clicked() {
+                    profile.profile_locked = locking;
+                    action = ProfileAction::ProfileUpdated;
+                    self.show_lock_dialog = false;
+                }
+
+                if ui.button("Cancel").clicked() {
+                    self.show_lock_dialog = false;
+                }
+            });
+        });
+
+        action
+    }
+
     fn delete_confirm_dialog(
         &mut self,
         ctx: &egui::Context,
@@ -330,7 +506,7 @@ impl ProfileSelector {
                 ));
                 ui.colored_label(egui::Color32::from_rgb(200, 0, 0), "This cannot be undone!");
 
-                ui.add_space(ITEM_SPACING);
+                ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                 ui.horizontal(|ui| {
                     if ui.button("Delete").clicked() {
@@ -0,0 +1,197 @@
+//! Drag-to-reorder list widget
+//!
+//! Every ordered list in the Manager (cycle groups, custom source rules, profiles) is edited the
+//! same way: drag a row by its `::` handle to move it, right-click the handle to delete it. Rows
+//! render their own content; the widget only reports what the user asked for as a [`ListEdit`].
+
+use eframe::egui;
+use std::hash::Hash;
+
+/// Change requested on a reorderable list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListEdit {
+    /// Move the row at `from` so it lands before the row that was at `to` (`to == len` appends)
+    Move { from: usize, to: usize },
+    /// Delete the row at this index
+    Delete(usize),
+}
+
+impl ListEdit {
+    /// Applies the edit to `items`. Returns true if the list changed.
+    pub fn apply<T>(self, items: &mut Vec<T>) -> bool {
+        match self {
+            ListEdit::Move { from, .. } => {
+                if from >= items.len() {
+                    return false;
+                }
+                let target = self.remap(from).unwrap_or(from).min(items.len() - 1);
+                if target == from {
+                    return false;
+                }
+                let item = items.remove(from);
+                items.insert(target, item);
+                true
+            }
+            ListEdit::Delete(index) if index < items.len() => {
+                items.remove(index);
+                true
+            }
+            ListEdit::Delete(_) => false,
+        }
+    }
+
+    /// New index of the row that was at `index` before the edit (None if it was deleted).
+    /// Use this to keep index-keyed UI state (selection, expanded rows) attached to its row.
+    pub fn remap(self, index: usize) -> Option<usize> {
+        match self {
+            ListEdit::Move { from, to } => {
+                // Insertion index after the moved row has been taken out
+                let target = if from < to { to - 1 } else { to };
+                Some(if index == from {
+                    target
+                } else if from < index && index <= target {
+                    index - 1
+                } else if target <= index && index < from {
+                    index + 1
+                } else {
+                    index
+                })
+            }
+            ListEdit::Delete(deleted) => match index.cmp(&deleted) {
+                std::cmp::Ordering::Less => Some(index),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(index - 1),
+            },
+        }
+    }
+}
+
+/// Payload carried while a row is dragged; tied to one list so rows can't cross lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RowPayload {
+    list: egui::Id,
+    index: usize,
+}
+
+/// Draws the insertion line for a row that a drag is hovering and returns the index the
+/// dragged item would be inserted at (before `row_idx` or after it, by pointer position).
+pub fn drop_indicator(ui: &egui::Ui, row_rect: egui::Rect, row_idx: usize, is_self: bool) -> usize {
+    let stroke = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+    let pointer_y = ui
+        .input(|i| i.pointer.interact_pos())
+        .map_or(row_rect.center().y, |p| p.y);
+
+    if is_self {
+        ui.painter()
+            .hline(row_rect.x_range(), row_rect.center().y, stroke);
+        row_idx
+    } else if pointer_y < row_rect.center().y {
+        ui.painter()
+            .hline(row_rect.x_range(), row_rect.top(), stroke);
+        row_idx
+    } else {
+        ui.painter()
+            .hline(row_rect.x_range(), row_rect.bottom(), stroke);
+        row_idx + 1
+    }
+}
+
+/// Shows `items` as reorderable rows. `add_row` draws the content right of the drag handle.
+///
+/// Returns the edit the user made this frame, if any. The caller applies it (usually with
+/// [`ListEdit::apply`]) so it can keep related state in sync or ask for confirmation first.
+pub fn show<T>(
+    ui: &mut egui::Ui,
+    id_salt: impl Hash,
+    items: &mut [T],
+    mut add_row: impl FnMut(&mut egui::Ui, usize, &mut T),
+) -> Option<ListEdit> {
+    let list_id = ui.make_persistent_id(id_salt);
+    let mut edit = None;
+
+    for (index, item) in items.iter_mut().enumerate() {
+        let payload = RowPayload {
+            list: list_id,
+            index,
+        };
+
+        let row = ui
+            .horizontal_top(|ui| {
+                let handle = ui
+                    .add(
+                        egui::Label::new(egui::RichText::new("::").weak())
+                            .sense(egui::Sense::click_and_drag()),
+                    )
+                    .on_hover_cursor(egui::CursorIcon::Grab)
+                    .on_hover_text("Drag to reorder, right-click for options");
+                handle.dnd_set_drag_payload(payload);
+                handle.context_menu(|ui| {
+                    if ui.button("🗑 Delete").clicked() {
+                        edit = Some(ListEdit::Delete(index));
+                        ui.close();
+                    }
+                });
+
+                ui.vertical(|ui| add_row(ui, index, item));
+            })
+            .response;
+
+        if let Some(hovered) = row.dnd_hover_payload::<RowPayload>()
+            && hovered.list == list_id
+        {
+            let to = drop_indicator(ui, row.rect, index, *hovered == payload);
+            if let Some(dropped) = row.dnd_release_payload::<RowPayload>()
+                && dropped.list == list_id
+            {
+                edit = Some(ListEdit::Move {
+                    from: dropped.index,
+                    to,
+                });
+            }
+        }
+    }
+
+    edit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_edit() {
+        let mut items = vec!['a', 'b', 'c', 'd'];
+        assert!(ListEdit::Move { from: 0, to: 3 }.apply(&mut items));
+        assert_eq!(items, ['b', 'c', 'a', 'd']);
+        assert!(ListEdit::Move { from: 3, to: 0 }.apply(&mut items));
+        assert_eq!(items, ['d', 'b', 'c', 'a']);
+        assert!(ListEdit::Move { from: 1, to: 4 }.apply(&mut items));
+        assert_eq!(items, ['d', 'c', 'a', 'b']);
+
+        // Dropping a row onto itself or right below itself is a no-op
+        assert!(!ListEdit::Move { from: 1, to: 1 }.apply(&mut items));
+        assert!(!ListEdit::Move { from: 1, to: 2 }.apply(&mut items));
+    }
+
+    #[test]
+    fn test_remap_follows_rows() {
+        let items = vec!['a', 'b', 'c', 'd'];
+        for edit in [
+            ListEdit::Move { from: 0, to: 3 },
+            ListEdit::Move { from: 3, to: 1 },
+            ListEdit::Delete(1),
+        ] {
+            let mut edited = items.clone();
+            edit.apply(&mut edited);
+            for (old, item) in items.iter().enumerate() {
+                assert_eq!(
+                    edit.remap(old),
+                    edited.iter().position(|i| i == item),
+                    "{:?} at {}",
+                    edit,
+                    old
+                );
+            }
+        }
+    }
+}
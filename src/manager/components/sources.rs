@@ -1,7 +1,34 @@
+use crate::common::constants::manager_ui::RULE_PREVIEW_DEBOUNCE_MS;
+use crate::common::ipc::{RulePatterns, WindowMatch};
 use crate::config::profile::CustomWindowRule;
-use crate::manager::x11_utils::{WindowInfo, get_running_applications};
+use crate::manager::components::reorderable_list::{self, ListEdit};
+use crate::manager::x11_utils::{
+    InspectedWindow, WindowInfo, get_running_applications, inspect_clicked_window,
+};
 use egui::{ScrollArea, Ui};
 use std::collections::HashSet;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Outcome of a window inspector pick (None = cancelled)
+type Inspection = Result<Option<InspectedWindow>, String>;
+
+/// Rule whose patterns are being matched against live windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreviewTarget {
+    NewRule,
+    Rule(usize),
+}
+
+/// Live "which windows would this match" preview for the rule being edited
+struct MatchPreview {
+    target: PreviewTarget,
+    patterns: RulePatterns,
+    /// Last edit not yet sent to the daemon
+    edited_at: Option<Instant>,
+    /// None while waiting for the daemon
+    result: Option<Result<Vec<WindowMatch>, String>>,
+}
 
 pub struct SourcesTab {
     // Component state
@@ -11,12 +38,17 @@ pub struct SourcesTab {
     error_msg: Option<String>,
     // Track expanded rows for editing: index -> expanded
     expanded_rows: HashSet<usize>,
+    // Window inspector: pending pick and last result
+    inspector_rx: Option<mpsc::Receiver<Inspection>>,
+    inspection: Option<Inspection>,
+    match_preview: Option<MatchPreview>,
 }
 
 impl Default for SourcesTab {
     fn default() -> Self {
         Self {
             new_rule: CustomWindowRule {
+                id: 0,
                 title_pattern: None,
                 class_pattern: None,
                 alias: String::new(),
@@ -40,6 +72,9 @@ impl Default for SourcesTab {
             selected_app_idx: None,
             error_msg: None,
             expanded_rows: HashSet::new(),
+            inspector_rx: None,
+            inspection: None,
+            match_preview: None,
         }
     }
 }
@@ -79,557 +114,553 @@ impl SourcesTab {
 
                 let mut remove_idx = None;
 
-                for (idx, rule) in profile.custom_windows.iter_mut().enumerate() {
-                    let is_expanded = self.expanded_rows.contains(&idx);
+                // Computed up front: the list below holds `custom_windows` mutably
+                let alias_errors: Vec<Option<String>> = profile
+                    .custom_windows
+                    .iter()
+                    .enumerate()
+                    .map(|(i, rule)| profile.alias_conflict(&rule.alias, Some(i)))
+                    .collect();
 
-                    ui.horizontal(|ui| {
-                        let icon = if is_expanded { "v" } else { ">" };
-                        if ui.small_button(icon).clicked() {
-                            if is_expanded {
-                                self.expanded_rows.remove(&idx);
-                            } else {
-                                self.expanded_rows.insert(idx);
-                            }
-                        }
-
-                        ui.label(egui::RichText::new(&rule.alias).strong());
+                // Rules are matched top to bottom, so their order matters
+                let edit = reorderable_list::show(
+                    ui,
+                    "custom_source_rules",
+                    &mut profile.custom_windows,
+                    |ui, idx, rule| {
+                        let is_expanded = self.expanded_rows.contains(&idx);
 
-                        // Show brief details when collapsed
-                        if !is_expanded {
-                            let mut details = Vec::new();
-                            if let Some(c) = &rule.class_pattern {
-                                details.push(format!("Class: {}", c));
-                            }
-                            if let Some(t) = &rule.title_pattern {
-                                details.push(format!("Title: {}", t));
+                        ui.horizontal(|ui| {
+                            let icon = if is_expanded { "v" } else { ">" };
+                            if ui.small_button(icon).clicked() {
+                                if is_expanded {
+                                    self.expanded_rows.remove(&idx);
+                                } else {
+                                    self.expanded_rows.insert(idx);
+                                }
                             }
-                            ui.label(egui::RichText::new(details.join(", ")).weak());
-                        }
 
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("🗑").on_hover_text("Delete Rule").clicked() {
-                                remove_idx = Some(idx);
-                                changed = true;
+                            ui.label(egui::RichText::new(&rule.alias).strong());
+                            if let Some(Some(error)) = alias_errors.get(idx) {
+                                ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                                    .on_hover_text(error);
                             }
 
-                            if rule.limit {
-                                ui.colored_label(egui::Color32::LIGHT_BLUE, "(Single)");
+                            // Show brief details when collapsed
+                            if !is_expanded {
+                                let mut details = Vec::new();
+                                if let Some(c) = &rule.class_pattern {
+                                    details.push(format!("Class: {}", c));
+                                }
+                                if let Some(t) = &rule.title_pattern {
+                                    details.push(format!("Title: {}", t));
+                                }
+                                ui.label(egui::RichText::new(details.join(", ")).weak());
                             }
+
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.button("🗑").on_hover_text("Delete Rule").clicked() {
+                                    remove_idx = Some(idx);
+                                    changed = true;
+                                }
+
+                                if rule.limit {
+                                    ui.colored_label(egui::Color32::LIGHT_BLUE, "(Single)");
+                                }
+                            });
                         });
-                    });
 
-                    if is_expanded {
-                        ui.indent("rule_details", |ui| {
-                            ui.add_space(4.0);
-                            egui::Grid::new(format!("grid_edit_rule_{}", idx))
-                                .num_columns(2)
-                                .spacing([10.0, 4.0])
-                                .show(ui, |ui| {
-                                    // Alias
-                                    ui.label("Display Name:");
-                                    if ui.text_edit_singleline(&mut rule.alias).changed() {
-                                        changed = true;
-                                    }
-                                    ui.end_row();
-
-                                    // Class Pattern
-                                    ui.label("Class Pattern:");
-                                    let mut class_text =
-                                        rule.class_pattern.clone().unwrap_or_default();
-                                    if ui.text_edit_singleline(&mut class_text).changed() {
-                                        rule.class_pattern = if class_text.is_empty() {
-                                            None
-                                        } else {
-                                            Some(class_text)
-                                        };
-                                        changed = true;
-                                    }
-                                    ui.end_row();
-
-                                    // Title Pattern
-                                    ui.label("Title Pattern:");
-                                    let mut title_text =
-                                        rule.title_pattern.clone().unwrap_or_default();
-                                    if ui.text_edit_singleline(&mut title_text).changed() {
-                                        rule.title_pattern = if title_text.is_empty() {
-                                            None
-                                        } else {
-                                            Some(title_text)
-                                        };
-                                        changed = true;
-                                    }
-                                    ui.end_row();
-
-                                    // Hotkey
-                                    ui.label("Hotkey:");
-                                    ui.horizontal(|ui| {
-                                        if let Some(binding) = &rule.hotkey {
-                                            ui.label(
-                                                egui::RichText::new(binding.display_name())
-                                                    .strong()
-                                                    .color(ui.style().visuals.text_color()),
-                                            );
-
-                                            if ui
-                                                .small_button("✖")
-                                                .on_hover_text("Clear Hotkey")
-                                                .clicked()
-                                            {
-                                                rule.hotkey = None;
-                                                changed = true;
-                                            }
-                                        } else {
-                                            ui.label(
-                                                egui::RichText::new("Not set")
-                                                    .weak()
-                                                    .color(ui.style().visuals.weak_text_color()),
-                                            );
+                        if is_expanded {
+                            ui.indent("rule_details", |ui| {
+                                ui.add_space(4.0);
+                                egui::Grid::new(format!("grid_edit_rule_{}", idx))
+                                    .num_columns(2)
+                                    .spacing([10.0, 4.0])
+                                    .show(ui, |ui| {
+                                        // Alias
+                                        ui.label("Display Name:");
+                                        if ui.text_edit_singleline(&mut rule.alias).changed() {
+                                            changed = true;
+                                        }
+                                        ui.end_row();
+                                        if let Some(Some(error)) = alias_errors.get(idx) {
+                                            ui.label("");
+                                            ui.colored_label(ui.visuals().error_fg_color, error);
+                                            ui.end_row();
                                         }
 
-                                        let bind_text =
-                                            if hotkey_state.is_capturing_custom_rule(&rule.alias) {
-                                                "Capturing..."
+                                        // Class Pattern
+                                        ui.label("Class Pattern:");
+                                        let mut class_text =
+                                            rule.class_pattern.clone().unwrap_or_default();
+                                        if ui.text_edit_singleline(&mut class_text).changed() {
+                                            rule.class_pattern = if class_text.is_empty() {
+                                                None
                                             } else {
-                                                "⌨ Bind"
+                                                Some(class_text)
                                             };
+                                            self.preview_patterns(PreviewTarget::Rule(idx), rule);
+                                            changed = true;
+                                        }
+                                        ui.end_row();
 
-                                        if ui.button(bind_text).clicked() {
-                                            hotkey_state.start_key_capture_for_custom_rule(
-                                                rule.alias.clone(),
-                                                profile.hotkey_backend,
-                                            );
+                                        // Title Pattern
+                                        ui.label("Title Pattern:");
+                                        let mut title_text =
+                                            rule.title_pattern.clone().unwrap_or_default();
+                                        if ui.text_edit_singleline(&mut title_text).changed() {
+                                            rule.title_pattern = if title_text.is_empty() {
+                                                None
+                                            } else {
+                                                Some(title_text)
+                                            };
+                                            self.preview_patterns(PreviewTarget::Rule(idx), rule);
+                                            changed = true;
                                         }
-                                    });
-                                    ui.end_row();
+                                        ui.end_row();
 
-                                    // --- Visual Overrides ---
-                                    ui.label("Overrides:");
-                                    ui.vertical(|ui| {
-                                        // Active Border
+                                        if self.is_previewing(PreviewTarget::Rule(idx)) {
+                                            ui.label("Matches:");
+                                            self.render_matches(ui);
+                                            ui.end_row();
+                                        }
+
+                                        // Hotkey
+                                        ui.label("Hotkey:");
                                         ui.horizontal(|ui| {
-                                            ui.label("Active Border:");
-                                            let mut enabled = rule.active_border_color.is_some()
-                                                || rule.active_border_size.is_some();
-                                            if ui.checkbox(&mut enabled, "Enabled").changed() {
-                                                if enabled {
-                                                    // Default to profile settings
-                                                    rule.active_border_color = Some(
-                                                        profile
-                                                            .thumbnail_active_border_color
-                                                            .clone(),
-                                                    );
-                                                    rule.active_border_size =
-                                                        Some(profile.thumbnail_active_border_size);
-                                                } else {
-                                                    rule.active_border_color = None;
-                                                    rule.active_border_size = None;
+                                            if let Some(binding) = &rule.hotkey {
+                                                ui.label(
+                                                    egui::RichText::new(binding.display_name())
+                                                        .strong()
+                                                        .color(ui.style().visuals.text_color()),
+                                                );
+
+                                                if ui
+                                                    .small_button("✖")
+                                                    .on_hover_text("Clear Hotkey")
+                                                    .clicked()
+                                                {
+                                                    rule.hotkey = None;
+                                                    changed = true;
                                                 }
-                                                changed = true;
+                                            } else {
+                                                ui.label(
+                                                    egui::RichText::new("Not set")
+                                                        .weak()
+                                                        .color(ui.style().visuals.weak_text_color()),
+                                                );
+                                            }
+
+                                            let bind_text =
+                                                if hotkey_state.is_capturing_custom_rule(&rule.alias) {
+                                                    "Capturing..."
+                                                } else {
+                                                    "⌨ Bind"
+                                                };
+
+                                            if ui.button(bind_text).clicked() {
+                                                hotkey_state.start_key_capture_for_custom_rule(
+                                                    rule.alias.clone(),
+                                                    profile.hotkey_backend,
+                                                );
                                             }
                                         });
-                                        if rule.active_border_color.is_some()
-                                            || rule.active_border_size.is_some()
-                                        {
-                                            ui.indent("active_border_details", |ui| {
-                                                ui.horizontal(|ui| {
-                                                    ui.label("Color:");
-                                                    let color = rule
-                                                        .active_border_color
-                                                        .clone()
-                                                        .unwrap_or_else(|| {
+                                        ui.end_row();
+
+                                        // --- Visual Overrides ---
+                                        ui.label("Overrides:");
+                                        ui.vertical(|ui| {
+                                            // Active Border
+                                            ui.horizontal(|ui| {
+                                                ui.label("Active Border:");
+                                                let mut enabled = rule.active_border_color.is_some()
+                                                    || rule.active_border_size.is_some();
+                                                if ui.checkbox(&mut enabled, "Enabled").changed() {
+                                                    if enabled {
+                                                        // Default to profile settings
+                                                        rule.active_border_color = Some(
                                                             profile
                                                                 .thumbnail_active_border_color
-                                                                .clone()
-                                                        });
-                                                    let mut egui_color =
-                                                        crate::common::color::hex_to_color32(
-                                                            &color,
-                                                        )
-                                                        .unwrap_or(egui::Color32::WHITE);
-                                                    if ui
-                                                        .color_edit_button_srgba(&mut egui_color)
-                                                        .changed()
-                                                    {
-                                                        rule.active_border_color = Some(
-                                                            crate::common::color::color32_to_hex(
-                                                                egui_color,
-                                                            ),
+                                                                .clone(),
                                                         );
-                                                        changed = true;
+                                                        rule.active_border_size =
+                                                            Some(profile.thumbnail_active_border_size);
+                                                    } else {
+                                                        rule.active_border_color = None;
+                                                        rule.active_border_size = None;
                                                     }
-                                                });
-                                                ui.horizontal(|ui| {
-                                                    ui.label("Size:");
-                                                    let mut size =
-                                                        rule.active_border_size.unwrap_or(
-                                                            profile.thumbnail_active_border_size,
-                                                        );
-                                                    if ui
-                                                        .add(
-                                                            egui::DragValue::new(&mut size)
-                                                                .range(1..=20),
-                                                        )
-                                                        .changed()
-                                                    {
-                                                        rule.active_border_size = Some(size);
-                                                        changed = true;
-                                                    }
-                                                });
-                                            });
-                                        }
-
-                                        // Inactive Border
-                                        ui.horizontal(|ui| {
-                                            ui.label("Inactive Border:");
-                                            let mut enabled = rule.inactive_border_color.is_some()
-                                                || rule.inactive_border_size.is_some();
-                                            if ui.checkbox(&mut enabled, "Enabled").changed() {
-                                                if enabled {
-                                                    rule.inactive_border_color = Some(
-                                                        profile
-                                                            .thumbnail_inactive_border_color
-                                                            .clone(),
-                                                    );
-                                                    rule.inactive_border_size = Some(
-                                                        profile.thumbnail_inactive_border_size,
-                                                    );
-                                                } else {
-                                                    rule.inactive_border_color = None;
-                                                    rule.inactive_border_size = None;
+                                                    changed = true;
                                                 }
-                                                changed = true;
+                                            });
+                                            if rule.active_border_color.is_some()
+                                                || rule.active_border_size.is_some()
+                                            {
+                                                ui.indent("active_border_details", |ui| {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Color:");
+                                                        let color = rule
+                                                            .active_border_color
+                                                            .clone()
+                                                            .unwrap_or_else(|| {
+                                                                profile
+                                                                    .thumbnail_active_border_color
+                                                                    .clone()
+                                                            });
+                                                        let mut egui_color =
+                                                            crate::common::color::hex_to_color32(
+                                                                &color,
+                                                            )
+                                                            .unwrap_or(egui::Color32::WHITE);
+                                                        if ui
+                                                            .color_edit_button_srgba(&mut egui_color)
+                                                            .changed()
+                                                        {
+                                                            rule.active_border_color = Some(
+                                                                crate::common::color::color32_to_hex(
+                                                                    egui_color,
+                                                                ),
+                                                            );
+                                                            changed = true;
+                                                        }
+                                                    });
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Size:");
+                                                        let mut size =
+                                                            rule.active_border_size.unwrap_or(
+                                                                profile.thumbnail_active_border_size,
+                                                            );
+                                                        if ui
+                                                            .add(
+                                                                egui::DragValue::new(&mut size)
+                                                                    .range(1..=20),
+                                                            )
+                                                            .changed()
+                                                        {
+                                                            rule.active_border_size = Some(size);
+                                                            changed = true;
+                                                        }
+                                                    });
+                                                });
                                             }
-                                        });
-                                        if rule.inactive_border_color.is_some()
-                                            || rule.inactive_border_size.is_some()
-                                        {
-                                            ui.indent("inactive_border_details", |ui| {
-                                                ui.horizontal(|ui| {
-                                                    ui.label("Color:");
-                                                    let color = rule
-                                                        .inactive_border_color
-                                                        .clone()
-                                                        .unwrap_or_else(|| {
+
+                                            // Inactive Border
+                                            ui.horizontal(|ui| {
+                                                ui.label("Inactive Border:");
+                                                let mut enabled = rule.inactive_border_color.is_some()
+                                                    || rule.inactive_border_size.is_some();
+                                                if ui.checkbox(&mut enabled, "Enabled").changed() {
+                                                    if enabled {
+                                                        rule.inactive_border_color = Some(
                                                             profile
                                                                 .thumbnail_inactive_border_color
-                                                                .clone()
-                                                        });
-                                                    let mut egui_color =
-                                                        crate::common::color::hex_to_color32(
-                                                            &color,
-                                                        )
-                                                        .unwrap_or(egui::Color32::WHITE);
-                                                    if ui
-                                                        .color_edit_button_srgba(&mut egui_color)
-                                                        .changed()
-                                                    {
-                                                        rule.inactive_border_color = Some(
-                                                            crate::common::color::color32_to_hex(
-                                                                egui_color,
-                                                            ),
+                                                                .clone(),
                                                         );
-                                                        changed = true;
-                                                    }
-                                                });
-                                                ui.horizontal(|ui| {
-                                                    ui.label("Size:");
-                                                    let mut size =
-                                                        rule.inactive_border_size.unwrap_or(
+                                                        rule.inactive_border_size = Some(
                                                             profile.thumbnail_inactive_border_size,
                                                         );
-                                                    if ui
-                                                        .add(
-                                                            egui::DragValue::new(&mut size)
-                                                                .range(1..=20),
-                                                        )
-                                                        .changed()
-                                                    {
-                                                        rule.inactive_border_size = Some(size);
-                                                        changed = true;
+                                                    } else {
+                                                        rule.inactive_border_color = None;
+                                                        rule.inactive_border_size = None;
                                                     }
-                                                });
-                                            });
-                                        }
-
-                                        // Text Color
-                                        ui.horizontal(|ui| {
-                                            ui.label("Text Color:");
-                                            let mut enabled = rule.text_color.is_some();
-                                            if ui.checkbox(&mut enabled, "Enabled").changed() {
-                                                if enabled {
-                                                    rule.text_color =
-                                                        Some(profile.thumbnail_text_color.clone());
-                                                } else {
-                                                    rule.text_color = None;
+                                                    changed = true;
                                                 }
-                                                changed = true;
-                                            }
-                                        });
-                                        if let Some(ref mut color_hex) = rule.text_color {
-                                            ui.indent("text_color_details", |ui| {
-                                                ui.horizontal(|ui| {
-                                                    ui.label("Color:");
-                                                    let mut egui_color =
-                                                        crate::common::color::hex_to_color32(
-                                                            color_hex,
-                                                        )
-                                                        .unwrap_or(egui::Color32::WHITE);
-                                                    if ui
-                                                        .color_edit_button_srgba(&mut egui_color)
-                                                        .changed()
-                                                    {
-                                                        *color_hex =
-                                                            crate::common::color::color32_to_hex(
-                                                                egui_color,
+                                            });
+                                            if rule.inactive_border_color.is_some()
+                                                || rule.inactive_border_size.is_some()
+                                            {
+                                                ui.indent("inactive_border_details", |ui| {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Color:");
+                                                        let color = rule
+                                                            .inactive_border_color
+                                                            .clone()
+                                                            .unwrap_or_else(|| {
+                                                                profile
+                                                                    .thumbnail_inactive_border_color
+                                                                    .clone()
+                                                            });
+                                                        let mut egui_color =
+                                                            crate::common::color::hex_to_color32(
+                                                                &color,
+                                                            )
+                                                            .unwrap_or(egui::Color32::WHITE);
+                                                        if ui
+                                                            .color_edit_button_srgba(&mut egui_color)
+                                                            .changed()
+                                                        {
+                                                            rule.inactive_border_color = Some(
+                                                                crate::common::color::color32_to_hex(
+                                                                    egui_color,
+                                                                ),
                                                             );
-                                                        changed = true;
-                                                    }
+                                                            changed = true;
+                                                        }
+                                                    });
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Size:");
+                                                        let mut size =
+                                                            rule.inactive_border_size.unwrap_or(
+                                                                profile.thumbnail_inactive_border_size,
+                                                            );
+                                                        if ui
+                                                            .add(
+                                                                egui::DragValue::new(&mut size)
+                                                                    .range(1..=20),
+                                                            )
+                                                            .changed()
+                                                        {
+                                                            rule.inactive_border_size = Some(size);
+                                                            changed = true;
+                                                        }
+                                                    });
                                                 });
-                                            });
-                                        }
+                                            }
 
-                                        // Preview Mode (Static Mode)
-                                        ui.horizontal(|ui| {
-                                            ui.label("Static Mode:");
-                                            let mut is_static = matches!(
-                                                rule.preview_mode,
-                                                Some(
-                                                    crate::common::types::PreviewMode::Static { .. }
-                                                )
-                                            );
-
-                                            if ui.checkbox(&mut is_static, "Enabled").changed() {
-                                                if is_static {
-                                                    // Enable Static Mode (Default to Black)
-                                                    rule.preview_mode = Some(
-                                                        crate::common::types::PreviewMode::Static {
-                                                            color: "#000000".to_string(),
-                                                        },
-                                                    );
-                                                } else {
-                                                    // Disable Static Mode (Revert to Live/None)
-                                                    rule.preview_mode = None;
+                                            // Text Color
+                                            ui.horizontal(|ui| {
+                                                ui.label("Text Color:");
+                                                let mut enabled = rule.text_color.is_some();
+                                                if ui.checkbox(&mut enabled, "Enabled").changed() {
+                                                    if enabled {
+                                                        rule.text_color =
+                                                            Some(profile.thumbnail_text_color.clone());
+                                                    } else {
+                                                        rule.text_color = None;
+                                                    }
+                                                    changed = true;
                                                 }
-                                                changed = true;
+                                            });
+                                            if let Some(ref mut color_hex) = rule.text_color {
+                                                ui.indent("text_color_details", |ui| {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Color:");
+                                                        let mut egui_color =
+                                                            crate::common::color::hex_to_color32(
+                                                                color_hex,
+                                                            )
+                                                            .unwrap_or(egui::Color32::WHITE);
+                                                        if ui
+                                                            .color_edit_button_srgba(&mut egui_color)
+                                                            .changed()
+                                                        {
+                                                            *color_hex =
+                                                                crate::common::color::color32_to_hex(
+                                                                    egui_color,
+                                                                );
+                                                            changed = true;
+                                                        }
+                                                    });
+                                                });
                                             }
-                                        });
 
-                                        // Static Mode Settings (Indented)
-                                        if let Some(crate::common::types::PreviewMode::Static {
-                                            ref mut color,
-                                        }) = rule.preview_mode
-                                        {
-                                            ui.indent("static_mode_details", |ui| {
-                                                ui.horizontal(|ui| {
-                                                    ui.label("Color:");
-                                                    let mut color_str = color.clone();
-                                                    let text_edit =
-                                                        egui::TextEdit::singleline(&mut color_str)
-                                                            .desired_width(100.0);
-
-                                                    if ui.add(text_edit).changed() {
-                                                        *color = color_str.clone();
-                                                        changed = true;
-                                                    }
+                                            // Preview Mode (Static Mode)
+                                            ui.horizontal(|ui| {
+                                                ui.label("Static Mode:");
+                                                let mut is_static = matches!(
+                                                    rule.preview_mode,
+                                                    Some(
+                                                        crate::common::types::PreviewMode::Static { .. }
+                                                    )
+                                                );
 
-                                                    if let Ok(mut c) =
-                                                        crate::manager::utils::parse_hex_color(
-                                                            &color_str,
-                                                        )
-                                                        && ui
-                                                            .color_edit_button_srgba(&mut c)
-                                                            .changed()
-                                                    {
-                                                        let new_hex =
-                                                            crate::manager::utils::format_hex_color(
-                                                                c,
-                                                            );
-                                                        *color = new_hex;
-                                                        changed = true;
+                                                if ui.checkbox(&mut is_static, "Enabled").changed() {
+                                                    if is_static {
+                                                        // Enable Static Mode (Default to Black)
+                                                        rule.preview_mode = Some(
+                                                            crate::common::types::PreviewMode::Static {
+                                                                color: "#000000".to_string(),
+                                                            },
+                                                        );
+                                                    } else {
+                                                        // Disable Static Mode (Revert to Live/None)
+                                                        rule.preview_mode = None;
                                                     }
-                                                });
+                                                    changed = true;
+                                                }
                                             });
-                                        }
 
-                                        // Minimize Exemption
-                                        ui.horizontal(|ui| {
-                                            ui.label("Minimize Exemption:");
-                                            if ui
-                                                .checkbox(&mut rule.exempt_from_minimize, "Enabled")
-                                                .on_hover_text("If enabled, this source will not be minimized when switching to another window.")
-                                                .changed()
+                                            // Static Mode Settings (Indented)
+                                            if let Some(crate::common::types::PreviewMode::Static {
+                                                ref mut color,
+                                            }) = rule.preview_mode
                                             {
-                                                changed = true;
+                                                ui.indent("static_mode_details", |ui| {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Color:");
+                                                        let mut color_str = color.clone();
+                                                        let text_edit =
+                                                            egui::TextEdit::singleline(&mut color_str)
+                                                                .desired_width(100.0);
+
+                                                        if ui.add(text_edit).changed() {
+                                                            *color = color_str.clone();
+                                                            changed = true;
+                                                        }
+
+                                                        if let Ok(mut c) =
+                                                            crate::manager::utils::parse_hex_color(
+                                                                &color_str,
+                                                            )
+                                                            && ui
+                                                                .color_edit_button_srgba(&mut c)
+                                                                .changed()
+                                                        {
+                                                            let new_hex =
+                                                                crate::manager::utils::format_hex_color(
+                                                                    c,
+                                                                );
+                                                            *color = new_hex;
+                                                            changed = true;
+                                                        }
+                                                    });
+                                                });
                                             }
-                                        });
 
-                                        // Preview Visibility Override
-                                        ui.horizontal(|ui| {
-                                            ui.label("Preview Visibility:");
-                                            let current_label = match rule.override_render_preview {
-                                                None => "Default",
-                                                Some(true) => "Always Show",
-                                                Some(false) => "Always Hide",
-                                            };
-                                            egui::ComboBox::from_id_salt(format!(
-                                                "src_preview_vis_{}",
-                                                idx
-                                            ))
-                                            .selected_text(current_label)
-                                            .show_ui(ui, |ui| {
+                                            // Minimize Exemption
+                                            ui.horizontal(|ui| {
+                                                ui.label("Minimize Exemption:");
                                                 if ui
-                                                    .selectable_value(
-                                                        &mut rule.override_render_preview,
-                                                        None,
-                                                        "Default",
-                                                    )
-                                                    .changed()
-                                                {
-                                                    changed = true;
-                                                }
-                                                if ui
-                                                    .selectable_value(
-                                                        &mut rule.override_render_preview,
-                                                        Some(true),
-                                                        "Always Show",
-                                                    )
-                                                    .changed()
-                                                {
-                                                    changed = true;
-                                                }
-                                                if ui
-                                                    .selectable_value(
-                                                        &mut rule.override_render_preview,
-                                                        Some(false),
-                                                        "Always Hide",
-                                                    )
+                                                    .checkbox(&mut rule.exempt_from_minimize, "Enabled")
+                                                    .on_hover_text("If enabled, this source will not be minimized when switching to another window.")
                                                     .changed()
                                                 {
                                                     changed = true;
                                                 }
                                             });
-                                        });
-                                    });
-                                    ui.end_row();
-
-                                    // --- Aspect Ratio Controls ---
-                                    ui.label("Size & Ratio:");
-                                    ui.vertical(|ui| {
-                                        // Aspect Ratio Logic (Replicated from visual_settings.rs)
-                                        let aspect_ratios = [
-                                            ("16:9", 16.0 / 9.0),
-                                            ("16:10", 16.0 / 10.0),
-                                            ("4:3", 4.0 / 3.0),
-                                            ("21:9", 21.0 / 9.0),
-                                            ("Custom", 0.0),
-                                        ];
-
-                                        let current_ratio =
-                                            rule.default_width as f32 / rule.default_height as f32;
-                                        let detected_preset = {
-                                            let mut preset = "Custom";
-                                            for (name, ratio) in
-                                                &aspect_ratios[..aspect_ratios.len() - 1]
-                                            {
-                                                if (current_ratio - ratio).abs() < 0.01 {
-                                                    preset = name;
-                                                    break;
-                                                }
-                                            }
-                                            preset
-                                        };
-
-                                        // Persistent state for "Custom" mode
-                                        let id = ui
-                                            .make_persistent_id(format!("src_ratio_mode_{}", idx));
-                                        let mut selected_mode = ui.data_mut(|d| {
-                                            d.get_temp::<String>(id)
-                                                .unwrap_or_else(|| detected_preset.to_string())
-                                        });
 
-                                        ui.horizontal(|ui| {
-                                            let mut mode_changed = false;
-                                            egui::ComboBox::from_id_salt(format!(
-                                                "src_ratio_combo_{}",
-                                                idx
-                                            ))
-                                            .selected_text(&selected_mode)
-                                            .show_ui(
-                                                ui,
-                                                |ui| {
-                                                    for (name, ratio) in &aspect_ratios {
-                                                        if ui
-                                                            .selectable_value(
-                                                                &mut selected_mode,
-                                                                name.to_string(),
-                                                                *name,
-                                                            )
-                                                            .changed()
-                                                        {
-                                                            mode_changed = true;
-                                                            if *ratio > 0.0 {
-                                                                rule.default_height =
-                                                                    (rule.default_width as f32
-                                                                        / ratio)
-                                                                        .round()
-                                                                        as u16;
-                                                                changed = true;
-                                                            }
-                                                        }
+                                            // Preview Visibility Override
+                                            ui.horizontal(|ui| {
+                                                ui.label("Preview Visibility:");
+                                                let current_label = match rule.override_render_preview {
+                                                    None => "Default",
+                                                    Some(true) => "Always Show",
+                                                    Some(false) => "Always Hide",
+                                                };
+                                                egui::ComboBox::from_id_salt(format!(
+                                                    "src_preview_vis_{}",
+                                                    idx
+                                                ))
+                                                .selected_text(current_label)
+                                                .show_ui(ui, |ui| {
+                                                    if ui
+                                                        .selectable_value(
+                                                            &mut rule.override_render_preview,
+                                                            None,
+                                                            "Default",
+                                                        )
+                                                        .changed()
+                                                    {
+                                                        changed = true;
+                                                    }
+                                                    if ui
+                                                        .selectable_value(
+                                                            &mut rule.override_render_preview,
+                                                            Some(true),
+                                                            "Always Show",
+                                                        )
+                                                        .changed()
+                                                    {
+                                                        changed = true;
+                                                    }
+                                                    if ui
+                                                        .selectable_value(
+                                                            &mut rule.override_render_preview,
+                                                            Some(false),
+                                                            "Always Hide",
+                                                        )
+                                                        .changed()
+                                                    {
+                                                        changed = true;
                                                     }
-                                                },
-                                            );
-                                            if mode_changed {
-                                                ui.data_mut(|d| {
-                                                    d.insert_temp(id, selected_mode.clone())
                                                 });
-                                            }
+                                            });
                                         });
+                                        ui.end_row();
 
-                                        ui.add_space(2.0);
+                                        // --- Aspect Ratio Controls ---
+                                        ui.label("Size & Ratio:");
+                                        ui.vertical(|ui| {
+                                            // Aspect Ratio Logic (Replicated from visual_settings.rs)
+                                            let aspect_ratios = [
+                                                ("16:9", 16.0 / 9.0),
+                                                ("16:10", 16.0 / 10.0),
+                                                ("4:3", 4.0 / 3.0),
+                                                ("21:9", 21.0 / 9.0),
+                                                ("Custom", 0.0),
+                                            ];
 
-                                        // Sliders
-                                        // Width (Always active)
-                                        ui.horizontal(|ui| {
-                                            ui.label("Width:");
-                                            if ui
-                                                .add(
-                                                    egui::Slider::new(
-                                                        &mut rule.default_width,
-                                                        100..=1200,
-                                                    )
-                                                    .suffix(" px"),
-                                                )
-                                                .changed()
-                                            {
-                                                changed = true;
-                                                // Update height if locked
-                                                if selected_mode != "Custom" {
-                                                    for (name, ratio) in
-                                                        &aspect_ratios[..aspect_ratios.len() - 1]
-                                                    {
-                                                        if name == &selected_mode.as_str() {
-                                                            rule.default_height =
-                                                                (rule.default_width as f32 / ratio)
-                                                                    .round()
-                                                                    as u16;
-                                                            break;
-                                                        }
+                                            let current_ratio =
+                                                rule.default_width as f32 / rule.default_height as f32;
+                                            let detected_preset = {
+                                                let mut preset = "Custom";
+                                                for (name, ratio) in
+                                                    &aspect_ratios[..aspect_ratios.len() - 1]
+                                                {
+                                                    if (current_ratio - ratio).abs() < 0.01 {
+                                                        preset = name;
+                                                        break;
                                                     }
                                                 }
-                                            }
-                                        });
+                                                preset
+                                            };
 
-                                        // Height (Locked unless Custom)
-                                        ui.horizontal(|ui| {
-                                            ui.label("Height:");
-                                            let is_custom = selected_mode == "Custom";
+                                            // Persistent state for "Custom" mode
+                                            let id = ui
+                                                .make_persistent_id(format!("src_ratio_mode_{}", idx));
+                                            let mut selected_mode = ui.data_mut(|d| {
+                                                d.get_temp::<String>(id)
+                                                    .unwrap_or_else(|| detected_preset.to_string())
+                                            });
+
+                                            ui.horizontal(|ui| {
+                                                let mut mode_changed = false;
+                                                egui::ComboBox::from_id_salt(format!(
+                                                    "src_ratio_combo_{}",
+                                                    idx
+                                                ))
+                                                .selected_text(&selected_mode)
+                                                .show_ui(
+                                                    ui,
+                                                    |ui| {
+                                                        for (name, ratio) in &aspect_ratios {
+                                                            if ui
+                                                                .selectable_value(
+                                                                    &mut selected_mode,
+                                                                    name.to_string(),
+                                                                    *name,
+                                                                )
+                                                                .changed()
+                                                            {
+                                                                mode_changed = true;
+                                                                if *ratio > 0.0 {
+                                                                    rule.default_height =
+                                                                        (rule.default_width as f32
+                                                                            / ratio)
+                                                                            .round()
+                                                                            as u16;
+                                                                    changed = true;
+                                                                }
+                                                            }
+                                                        }
+                                                    },
+                                                );
+                                                if mode_changed {
+                                                    ui.data_mut(|d| {
+                                                        d.insert_temp(id, selected_mode.clone())
+                                                    });
+                                                }
+                                            });
+
+                                            ui.add_space(2.0);
 
-                                            ui.add_enabled_ui(is_custom, |ui| {
+                                            // Sliders
+                                            // Width (Always active)
+                                            ui.horizontal(|ui| {
+                                                ui.label("Width:");
                                                 if ui
                                                     .add(
                                                         egui::Slider::new(
-                                                            &mut rule.default_height,
+                                                            &mut rule.default_width,
                                                             100..=1200,
                                                         )
                                                         .suffix(" px"),
@@ -637,33 +668,82 @@ impl SourcesTab {
                                                     .changed()
                                                 {
                                                     changed = true;
+                                                    // Update height if locked
+                                                    if selected_mode != "Custom" {
+                                                        for (name, ratio) in
+                                                            &aspect_ratios[..aspect_ratios.len() - 1]
+                                                        {
+                                                            if name == &selected_mode.as_str() {
+                                                                rule.default_height =
+                                                                    (rule.default_width as f32 / ratio)
+                                                                        .round()
+                                                                        as u16;
+                                                                break;
+                                                            }
+                                                        }
+                                                    }
                                                 }
                                             });
+
+                                            // Height (Locked unless Custom)
+                                            ui.horizontal(|ui| {
+                                                ui.label("Height:");
+                                                let is_custom = selected_mode == "Custom";
+
+                                                ui.add_enabled_ui(is_custom, |ui| {
+                                                    if ui
+                                                        .add(
+                                                            egui::Slider::new(
+                                                                &mut rule.default_height,
+                                                                100..=1200,
+                                                            )
+                                                            .suffix(" px"),
+                                                        )
+                                                        .changed()
+                                                    {
+                                                        changed = true;
+                                                    }
+                                                });
+                                            });
+
+                                            ui.weak(format!(
+                                                "Preview: {}x{}",
+                                                rule.default_width, rule.default_height
+                                            ));
                                         });
+                                        ui.end_row();
 
-                                        ui.weak(format!(
-                                            "Preview: {}x{}",
-                                            rule.default_width, rule.default_height
-                                        ));
+                                        // Limit
+                                        ui.label("Limit:");
+                                        if ui.checkbox(&mut rule.limit, "Single Instance").changed() {
+                                            changed = true;
+                                        }
+                                        ui.end_row();
                                     });
-                                    ui.end_row();
+                                ui.add_space(8.0);
+                            });
+                        }
+                        ui.separator();
+                    },
+                );
 
-                                    // Limit
-                                    ui.label("Limit:");
-                                    if ui.checkbox(&mut rule.limit, "Single Instance").changed() {
-                                        changed = true;
-                                    }
-                                    ui.end_row();
-                                });
-                            ui.add_space(8.0);
-                        });
+                if let Some(edit) = edit.or(remove_idx.map(ListEdit::Delete))
+                    && edit.apply(&mut profile.custom_windows)
+                {
+                    // Keep expanded rows attached to the rules that moved
+                    self.expanded_rows = self
+                        .expanded_rows
+                        .iter()
+                        .filter_map(|&idx| edit.remap(idx))
+                        .collect();
+                    if let Some(preview) = &mut self.match_preview
+                        && let PreviewTarget::Rule(idx) = preview.target
+                    {
+                        match edit.remap(idx) {
+                            Some(idx) => preview.target = PreviewTarget::Rule(idx),
+                            None => self.match_preview = None,
+                        }
                     }
-                    ui.separator();
-                }
-
-                if let Some(idx) = remove_idx {
-                    profile.custom_windows.remove(idx);
-                    self.expanded_rows.remove(&idx);
                     changed = true;
                 }
             });
@@ -719,7 +799,7 @@ impl SourcesTab {
                                         .clicked()
                                     {
                                         // Auto-fill fields from selection
-                                        self.new_rule.alias = app.class.clone();
+                                        self.new_rule.alias = profile.unique_alias(&app.class, None);
                                         self.new_rule.class_pattern = Some(app.class.clone());
                                         self.new_rule.title_pattern = None;
                                     }
@@ -766,6 +846,20 @@ impl SourcesTab {
                     ui.text_edit_singleline(&mut self.new_rule.alias);
                     ui.end_row();
 
+                    if !self.new_rule.alias.is_empty()
+                        && let Some(error) = profile.alias_conflict(&self.new_rule.alias, None)
+                    {
+                        ui.label("");
+                        ui.horizontal(|ui| {
+                            ui.colored_label(ui.visuals().error_fg_color, error);
+                            let suggestion = profile.unique_alias(&self.new_rule.alias, None);
+                            if ui.small_button(format!("Use \"{}\"", suggestion)).clicked() {
+                                self.new_rule.alias = suggestion;
+                            }
+                        });
+                        ui.end_row();
+                    }
+
                     ui.label("Window Class Pattern:");
                     let mut class_text = self.new_rule.class_pattern.clone().unwrap_or_default();
                     if ui.text_edit_singleline(&mut class_text).changed() {
@@ -774,6 +868,7 @@ impl SourcesTab {
                         } else {
                             Some(class_text)
                         };
+                        self.preview_patterns(PreviewTarget::NewRule, &self.new_rule.clone());
                     }
                     ui.end_row();
 
@@ -785,12 +880,19 @@ impl SourcesTab {
                         } else {
                             Some(title_text)
                         };
+                        self.preview_patterns(PreviewTarget::NewRule, &self.new_rule.clone());
                     }
                     ui.end_row();
 
+                    if self.is_previewing(PreviewTarget::NewRule) {
+                        ui.label("Matches:");
+                        self.render_matches(ui);
+                        ui.end_row();
+                    }
+
                     ui.label("");
                     ui.weak(
-                        "A Display Name and at least one pattern (Class or Title) are required.",
+                        "A unique Display Name and at least one pattern (Class or Title) are required.",
                     );
                     ui.end_row();
 
@@ -804,7 +906,7 @@ impl SourcesTab {
 
             ui.add_space(10.0);
 
-            let is_valid = !self.new_rule.alias.is_empty()
+            let is_valid = profile.alias_conflict(&self.new_rule.alias, None).is_none()
                 && (self.new_rule.class_pattern.is_some() || self.new_rule.title_pattern.is_some());
 
             ui.horizontal(|ui| {
@@ -814,6 +916,7 @@ impl SourcesTab {
                         self.new_rule.default_width = profile.thumbnail_default_width;
                         self.new_rule.default_height = profile.thumbnail_default_height;
 
+                        self.new_rule.id = profile.next_rule_id();
                         profile.custom_windows.push(self.new_rule.clone());
                         changed = true;
 
@@ -834,11 +937,17 @@ impl SourcesTab {
                         self.new_rule.exempt_from_minimize = false;
                         self.new_rule.override_render_preview = None;
                         self.new_rule.hotkey = None;
+                        if self.is_previewing(PreviewTarget::NewRule) {
+                            self.match_preview = None;
+                        }
                     }
                 });
             });
         });
 
+        ui.add_space(20.0);
+        self.render_inspector(ui, profile);
+
         // Render Hotkey Modal if active
         if hotkey_state.is_dialog_open() {
             changed |= crate::manager::components::hotkey_settings::render_key_capture_modal(
@@ -850,4 +959,260 @@ impl SourcesTab {
 
         changed
     }
+
+    /// Patterns of the rule being previewed once typing has paused; None when there is
+    /// nothing new to send to the daemon
+    pub fn take_match_request(&mut self) -> Option<RulePatterns> {
+        let preview = self.match_preview.as_mut()?;
+        let edited_at = preview.edited_at?;
+        if edited_at.elapsed() < Duration::from_millis(RULE_PREVIEW_DEBOUNCE_MS) {
+            return None;
+        }
+        preview.edited_at = None;
+        Some(preview.patterns.clone())
+    }
+
+    /// Stores the daemon's answer; replies for patterns edited since are dropped
+    pub fn show_matches(
+        &mut self,
+        patterns: RulePatterns,
+        result: Result<Vec<WindowMatch>, String>,
+    ) {
+        if let Some(preview) = &mut self.match_preview
+            && preview.patterns == patterns
+        {
+            preview.result = Some(result);
+        }
+    }
+
+    fn is_previewing(&self, target: PreviewTarget) -> bool {
+        self.match_preview
+            .as_ref()
+            .is_some_and(|p| p.target == target)
+    }
+
+    /// Restarts the debounce for `rule`'s patterns (clears the preview if both are empty)
+    fn preview_patterns(&mut self, target: PreviewTarget, rule: &CustomWindowRule) {
+        if rule.title_pattern.is_none() && rule.class_pattern.is_none() {
+            self.match_preview = None;
+            return;
+        }
+        self.match_preview = Some(MatchPreview {
+            target,
+            patterns: (rule.title_pattern.clone(), rule.class_pattern.clone()),
+            edited_at: Some(Instant::now()),
+            result: None,
+        });
+    }
+
+    fn render_matches(&self, ui: &mut Ui) {
+        let Some(preview) = &self.match_preview else {
+            return;
+        };
+        ui.vertical(|ui| match &preview.result {
+            None => {
+                if preview.edited_at.is_some() {
+                    // Wake up once the debounce elapses so the request goes out
+                    ui.ctx()
+                        .request_repaint_after(Duration::from_millis(RULE_PREVIEW_DEBOUNCE_MS));
+                }
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(egui::RichText::new("Checking open windows...").weak());
+                });
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().warn_fg_color, e);
+            }
+            Some(Ok(windows)) => {
+                let count = match windows.len() {
+                    0 => "No matching windows".to_string(),
+                    1 => "1 matching window".to_string(),
+                    n => format!("{} matching windows", n),
+                };
+                ui.label(egui::RichText::new(count).strong());
+                for window in windows {
+                    ui.label(
+                        egui::RichText::new(format!("{}  ({})", window.title, window.class))
+                            .monospace()
+                            .small(),
+                    )
+                    .on_hover_text(format!("Window ID: 0x{:x}", window.window));
+                }
+            }
+        });
+    }
+
+    /// "What is this window" tool: pick a window on screen and explain how detection treats it
+    fn render_inspector(&mut self, ui: &mut Ui, profile: &crate::config::profile::Profile) {
+        if let Some(rx) = &self.inspector_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.inspection = Some(result);
+                    self.inspector_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ui.ctx()
+                        .request_repaint_after(std::time::Duration::from_millis(100));
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.inspector_rx = None,
+            }
+        }
+
+        ui.group(|ui| {
+            ui.heading("Window Inspector");
+            ui.label(
+                egui::RichText::new(
+                    "Click a window on screen to see its properties and why it is or isn't detected.",
+                )
+                .weak()
+                .small(),
+            );
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                let picking = self.inspector_rx.is_some();
+                if ui
+                    .add_enabled(!picking, egui::Button::new("🔍 Inspect Window..."))
+                    .clicked()
+                {
+                    let (tx, rx) = mpsc::channel();
+                    std::thread::spawn(move || {
+                        let timeout = std::time::Duration::from_secs(
+                            crate::common::constants::manager_ui::INSPECTOR_TIMEOUT_SECS,
+                        );
+                        let _ = tx.send(inspect_clicked_window(timeout).map_err(|e| e.to_string()));
+                    });
+                    self.inspector_rx = Some(rx);
+                    self.inspection = None;
+                }
+                if picking {
+                    ui.spinner();
+                    ui.label("Click a window (right-click cancels)");
+                }
+            });
+
+            match &self.inspection {
+                None => {}
+                Some(Err(e)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("Inspection failed: {}", e));
+                }
+                Some(Ok(None)) => {
+                    ui.label(egui::RichText::new("No window picked").weak());
+                }
+                Some(Ok(Some(window))) => render_inspection(ui, window, profile),
+            }
+        });
+    }
+}
+
+/// Properties of an inspected window and the verdict of every detection and custom rule
+fn render_inspection(
+    ui: &mut Ui,
+    window: &InspectedWindow,
+    profile: &crate::config::profile::Profile,
+) {
+    use crate::common::types::EveWindowType;
+
+    let or_none = |s: &str| {
+        if s.is_empty() {
+            "(none)".to_string()
+        } else {
+            s.to_string()
+        }
+    };
+    egui::Grid::new("inspector_props")
+        .num_columns(2)
+        .spacing([10.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Window ID:");
+            ui.monospace(format!("0x{:x}", window.id));
+            ui.end_row();
+            ui.label("WM_NAME:");
+            ui.monospace(or_none(&window.wm_name));
+            ui.end_row();
+            ui.label("_NET_WM_NAME:");
+            ui.monospace(or_none(&window.net_wm_name));
+            ui.end_row();
+            ui.label("Class:");
+            ui.monospace(or_none(&window.class));
+            ui.end_row();
+            ui.label("PID:");
+            ui.monospace(
+                window
+                    .pid
+                    .map_or("(unknown)".to_string(), |p| p.to_string()),
+            );
+            ui.end_row();
+            ui.label("Wine:");
+            match &window.wine {
+                Some(wine) => {
+                    let mut parts =
+                        vec![wine.prefix.clone().unwrap_or("default prefix".to_string())];
+                    parts.extend(wine.proton.clone());
+                    parts.push(format!("{:?}", wine.server));
+                    ui.monospace(parts.join(" · "));
+                }
+                None => {
+                    ui.label("Not a Wine process");
+                }
+            }
+            ui.end_row();
+        });
+
+    ui.add_space(5.0);
+    ui.label(egui::RichText::new("Client detection").strong());
+    let mut detected = false;
+    for rule in &profile.client_detection_rules {
+        match rule.explain(&window.wm_name, Some(&window.class)) {
+            Ok(kind) if !detected => {
+                detected = true;
+                let verdict = match kind {
+                    EveWindowType::LoggedIn(name) => format!("logged in as \"{}\"", name),
+                    EveWindowType::LoggedOut => "logged out".to_string(),
+                };
+                ui.label(format!("✔ {}: {}", rule.name, verdict));
+            }
+            Ok(_) => {
+                ui.label(format!(
+                    "✔ {}: matches, but an earlier rule wins",
+                    rule.name
+                ));
+            }
+            Err(reason) => {
+                ui.label(egui::RichText::new(format!("✘ {}: {}", rule.name, reason)).weak());
+            }
+        }
+    }
+    if profile.client_detection_rules.is_empty() {
+        ui.label(egui::RichText::new("No detection rules").weak());
+    }
+
+    ui.add_space(5.0);
+    ui.label(egui::RichText::new("Custom sources").strong());
+    if detected {
+        ui.label(egui::RichText::new("Not checked: the window is a game client").weak());
+        return;
+    }
+    let mut matched = false;
+    for rule in &profile.custom_windows {
+        match rule.mismatch_reason(window.title(), &window.class) {
+            None if !matched => {
+                matched = true;
+                ui.label(format!("✔ {}: matches", rule.alias));
+            }
+            None => {
+                ui.label(format!(
+                    "✔ {}: matches, but an earlier rule wins",
+                    rule.alias
+                ));
+            }
+            Some(reason) => {
+                ui.label(egui::RichText::new(format!("✘ {}: {}", rule.alias, reason)).weak());
+            }
+        }
+    }
+    if profile.custom_windows.is_empty() {
+        ui.label(egui::RichText::new("No custom sources").weak());
+    }
 }
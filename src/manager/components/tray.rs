@@ -15,6 +15,17 @@ pub struct AppTray {
     pub is_flatpak: bool,
 }
 
+#[cfg(target_os = "linux")]
+impl AppTray {
+    /// Records whether a tray hosts the icon (the control panel stands in when not)
+    pub fn set_available(&self, available: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.tray_available = available;
+        }
+        self.ctx.request_repaint();
+    }
+}
+
 #[cfg(target_os = "linux")]
 impl ksni::Tray for AppTray {
     fn id(&self) -> String {
@@ -43,11 +54,22 @@ impl ksni::Tray for AppTray {
             .unwrap_or_default()
     }
 
+    fn watcher_online(&self) {
+        self.set_available(true);
+    }
+
+    fn watcher_offline(&self, reason: ksni::OfflineReason) -> bool {
+        tracing::warn!(reason = ?reason, "System tray went away, showing the control panel");
+        self.set_available(false);
+        // Keep the service running so the icon comes back with the tray
+        true
+    }
+
     fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
         use ksni::menu::*;
 
         // Lock state to get current info
-        let (current_profile_idx, profile_names) = {
+        let (current_profile_idx, profile_names, character_names, paused) = {
             if let Ok(state) = self.state.lock() {
                 let profile_names: Vec<String> = state
                     .config
@@ -56,9 +78,21 @@ impl ksni::Tray for AppTray {
                     .map(|p| p.profile_name.clone())
                     .collect();
                 let idx = state.selected_profile_idx;
-                (idx, profile_names)
+                // Characters of the active profile, as shown on thumbnails
+                let character_names: Vec<String> = state
+                    .config
+                    .profiles
+                    .get(idx)
+                    .map(|profile| {
+                        let mut names: Vec<String> =
+                            profile.character_thumbnails.keys().cloned().collect();
+                        profile.sort_for_display(&mut names);
+                        names.iter().map(|n| profile.display_name(n)).collect()
+                    })
+                    .unwrap_or_default();
+                (idx, profile_names, character_names, state.paused)
             } else {
-                (0, vec!["default".to_string()])
+                (0, vec!["default".to_string()], Vec::new(), false)
             }
         };
 
@@ -75,6 +109,20 @@ impl ksni::Tray for AppTray {
                 ..Default::default()
             }
             .into(),
+            // Pause toggle (hides thumbnails and releases hotkeys)
+            CheckmarkItem {
+                label: "Pause".into(),
+                checked: paused,
+                activate: Box::new(|this: &mut AppTray| {
+                    if let Ok(mut state) = this.state.lock() {
+                        let paused = !state.paused;
+                        state.set_paused(paused);
+                    }
+                    this.ctx.request_repaint();
+                }),
+                ..Default::default()
+            }
+            .into(),
             // Separator
             MenuItem::Separator,
             // Profile selector (radio group)
@@ -95,6 +143,24 @@ impl ksni::Tray for AppTray {
                     .collect(),
             }
             .into(),
+            // Known characters (informational)
+            SubMenu {
+                label: "Characters".into(),
+                enabled: !character_names.is_empty(),
+                submenu: character_names
+                    .into_iter()
+                    .map(|label| {
+                        StandardItem {
+                            label,
+                            enabled: false,
+                            ..Default::default()
+                        }
+                        .into()
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+            .into(),
             // Separator
             MenuItem::Separator,
             // Save Thumbnail Positions
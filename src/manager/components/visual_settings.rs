@@ -1,6 +1,9 @@
+use crate::common::constants::defaults;
 use crate::common::constants::manager_ui::*;
-use crate::common::types::Dimensions;
+use crate::common::types::{Dimensions, ThumbnailShape};
 use crate::config::profile::Profile;
+use crate::config::{BadgeCorner, BorderStyle, TextEffect, UnfocusedEffect};
+use crate::manager::theme::density_spacing;
 use eframe::egui;
 
 /// State for visual settings UI
@@ -13,6 +16,8 @@ pub struct VisualSettingsState {
     current_width: u16,
     current_height: u16,
     last_target: String,
+    /// Monitor name typed for a new per-monitor size multiplier
+    new_monitor_name: String,
 }
 
 impl VisualSettingsState {
@@ -34,6 +39,7 @@ impl VisualSettingsState {
             current_width: 250,
             current_height: 141,
             last_target: "---".to_string(),
+            new_monitor_name: String::new(),
         }
     }
 }
@@ -77,7 +83,7 @@ fn render_visual_controls(
     ui.group(|ui| {
         ui.set_min_width(ui.available_width());
         ui.label(egui::RichText::new("Visual Settings").strong());
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
 
         // Enable/disable thumbnail rendering
         if ui
@@ -94,7 +100,7 @@ fn render_visual_controls(
             .weak(),
         );
 
-        ui.add_space(ITEM_SPACING);
+        ui.add_space(density_spacing(ui, ITEM_SPACING));
 
         // Remaining settings are grayed out when thumbnails disabled
         ui.add_enabled_ui(profile.thumbnail_enabled, |ui| {
@@ -109,7 +115,7 @@ fn render_visual_controls(
                 }
             });
 
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Active Border toggle
             ui.horizontal(|ui| {
@@ -155,6 +161,12 @@ fn render_visual_controls(
                         {
                             changed = true;
                         }
+                        ui.label("Style:");
+                        changed |= border_style_combo(
+                            ui,
+                            "active_border_style",
+                            &mut profile.thumbnail_active_border_style,
+                        );
                     });
                 });
             });
@@ -203,11 +215,135 @@ fn render_visual_controls(
                         {
                             changed = true;
                         }
+                        ui.label("Style:");
+                        changed |= border_style_combo(
+                            ui,
+                            "inactive_border_style",
+                            &mut profile.thumbnail_inactive_border_style,
+                        );
                     });
                 });
             });
 
-            ui.add_space(ITEM_SPACING);
+            // Skipped and minimized clients fall back to the inactive border unless customized
+            let inactive = (
+                profile.thumbnail_inactive_border_style,
+                profile.thumbnail_inactive_border_size,
+            );
+            changed |= render_state_border(
+                ui,
+                "Skipped Border:",
+                "skipped_border_style",
+                &mut profile.thumbnail_skipped_border_style,
+                &mut profile.thumbnail_skipped_border_size,
+                inactive,
+            );
+            changed |= render_state_border(
+                ui,
+                "Minimized Border:",
+                "minimized_border_style",
+                &mut profile.thumbnail_minimized_border_style,
+                &mut profile.thumbnail_minimized_border_size,
+                inactive,
+            );
+
+            // Test-server border (always drawn on unfocused Singularity/Thunderdome clients)
+            ui.horizontal(|ui| {
+                ui.label("Test Server Border:");
+                let text_edit =
+                    egui::TextEdit::singleline(&mut profile.thumbnail_test_server_border_color)
+                        .desired_width(100.0);
+                if ui.add(text_edit).changed() {
+                    changed = true;
+                }
+
+                if let Ok(mut color) = parse_hex_color(&profile.thumbnail_test_server_border_color)
+                    && ui.color_edit_button_srgba(&mut color).changed()
+                {
+                    profile.thumbnail_test_server_border_color = format_hex_color(color);
+                    changed = true;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Border Padding:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut profile.thumbnail_border_padding)
+                            .range(0..=defaults::border::PADDING_MAX)
+                            .suffix(" px"),
+                    )
+                    .on_hover_text("Gap between the border and the client content")
+                    .changed()
+                {
+                    changed = true;
+                }
+                ui.label("Glow:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut profile.thumbnail_border_glow)
+                            .range(0..=defaults::border::GLOW_MAX)
+                            .suffix(" px"),
+                    )
+                    .on_hover_text("Soft glow fading inward from the border (0 = off)")
+                    .changed()
+                {
+                    changed = true;
+                }
+            });
+
+            // Unfocused recoloring
+            ui.horizontal(|ui| {
+                let label = |effect: UnfocusedEffect| match effect {
+                    UnfocusedEffect::None => "Full color",
+                    UnfocusedEffect::Grayscale => "Grayscale",
+                    UnfocusedEffect::Tint => "Tint",
+                };
+                ui.label("Unfocused:");
+                egui::ComboBox::from_id_salt("unfocused_effect_selector")
+                    .selected_text(label(profile.thumbnail_unfocused_effect))
+                    .show_ui(ui, |ui| {
+                        for effect in [
+                            UnfocusedEffect::None,
+                            UnfocusedEffect::Grayscale,
+                            UnfocusedEffect::Tint,
+                        ] {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut profile.thumbnail_unfocused_effect,
+                                    effect,
+                                    label(effect),
+                                )
+                                .changed();
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Recolors thumbnails of unfocused clients so the focused one stands out",
+                    );
+
+                if profile.thumbnail_unfocused_effect == UnfocusedEffect::Tint {
+                    let text_edit =
+                        egui::TextEdit::singleline(&mut profile.thumbnail_unfocused_tint_color)
+                            .desired_width(100.0);
+                    if ui
+                        .add(text_edit)
+                        .on_hover_text("Tint color; its alpha sets the strength")
+                        .changed()
+                    {
+                        changed = true;
+                    }
+
+                    if let Ok(mut color) = parse_hex_color(&profile.thumbnail_unfocused_tint_color)
+                        && ui.color_edit_button_srgba(&mut color).changed()
+                    {
+                        profile.thumbnail_unfocused_tint_color = format_hex_color(color);
+                        changed = true;
+                    }
+                }
+            });
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Text settings
             ui.horizontal(|ui| {
@@ -255,6 +391,8 @@ fn render_visual_controls(
                 }
             });
 
+            changed |= render_text_effect(ui, profile);
+
             // Font family selector
             ui.horizontal(|ui| {
                 ui.label("Font:");
@@ -282,6 +420,89 @@ fn render_visual_controls(
                         }
                     });
             });
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            // Thumbnail shape (XShape)
+            ui.horizontal(|ui| {
+                ui.label("Shape:");
+                egui::ComboBox::from_id_salt("thumbnail_shape")
+                    .selected_text(shape_label(profile.thumbnail_shape))
+                    .show_ui(ui, |ui| {
+                        for shape in [
+                            ThumbnailShape::Rectangle,
+                            ThumbnailShape::RoundedRect,
+                            ThumbnailShape::Circle,
+                        ] {
+                            if ui
+                                .selectable_value(
+                                    &mut profile.thumbnail_shape,
+                                    shape,
+                                    shape_label(shape),
+                                )
+                                .changed()
+                            {
+                                changed = true;
+                            }
+                        }
+                    });
+            });
+
+            ui.indent("shape_settings", |ui| {
+                ui.add_enabled_ui(
+                    profile.thumbnail_shape == ThumbnailShape::RoundedRect,
+                    |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Corner Radius:");
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut profile.thumbnail_corner_radius)
+                                        .range(1..=100)
+                                        .suffix(" px"),
+                                )
+                                .changed()
+                            {
+                                changed = true;
+                            }
+                        });
+                    },
+                );
+            });
+
+            // Corner icon size (icons are configured per character)
+            ui.horizontal(|ui| {
+                ui.label("Corner Icon Size:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut profile.thumbnail_icon_size)
+                            .range(8..=128)
+                            .suffix(" px"),
+                    )
+                    .changed()
+                {
+                    changed = true;
+                }
+            });
+
+            changed |= render_cycle_badge(ui, profile);
+            changed |= render_status_badges(ui, profile);
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+
+            // Character notes as secondary label line
+            if ui
+                .checkbox(&mut profile.thumbnail_show_notes, "Show character notes")
+                .changed()
+            {
+                changed = true;
+            }
+            ui.label(
+                egui::RichText::new(
+                    "Display notes below the name. When off, notes appear only while hovering",
+                )
+                .small()
+                .weak(),
+            );
         }); // Close add_enabled_ui
     }); // Close group
 
@@ -300,7 +521,7 @@ fn render_size_controls(
         ui.group(|ui| {
             ui.set_min_width(ui.available_width());
             ui.label(egui::RichText::new("Default Thumbnail Size").strong());
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Aspect ratio preset definitions
             let aspect_ratios = [
@@ -362,7 +583,7 @@ fn render_size_controls(
                 }
             });
 
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
             // Width slider (primary control)
             ui.horizontal(|ui| {
@@ -435,22 +656,25 @@ fn render_size_controls(
                 ));
             });
 
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
             ui.label(
                 egui::RichText::new("Default size for newly created character thumbnails")
                     .small()
                     .weak(),
             );
+
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
+            changed |= render_monitor_scales(ui, profile, &mut state.new_monitor_name);
         });
 
-        ui.add_space(SECTION_SPACING);
+        ui.add_space(density_spacing(ui, SECTION_SPACING));
 
         // Thumbnail Size Adjustment Group
         ui.group(|ui| {
             ui.set_min_width(ui.available_width());
             ui.label(egui::RichText::new("Thumbnail Size Adjustment").strong());
-            ui.add_space(ITEM_SPACING);
+            ui.add_space(density_spacing(ui, ITEM_SPACING));
 
             // Target selector
             let id = ui.make_persistent_id("thumbnail_resize_target");
@@ -489,7 +713,7 @@ fn render_size_controls(
                 ui.data_mut(|d| d.insert_temp(id, selected_target.clone()));
             });
 
-            ui.add_space(ITEM_SPACING / 2.0);
+            ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
             let is_enabled = selected_target != "---";
 
@@ -571,7 +795,7 @@ fn render_size_controls(
                     }
                 });
 
-                ui.add_space(ITEM_SPACING / 2.0);
+                ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                 // Width slider
                 ui.horizontal(|ui| {
@@ -636,7 +860,7 @@ fn render_size_controls(
                     ));
                 });
 
-                ui.add_space(ITEM_SPACING / 2.0);
+                ui.add_space(density_spacing(ui, ITEM_SPACING / 2.0));
 
                 // Apply button
                 ui.horizontal(|ui| {
@@ -686,13 +910,13 @@ fn render_resize_confirmation(
                         dims.height,
                         profile.character_thumbnails.len()
                     ));
-                    ui.add_space(ITEM_SPACING);
+                    ui.add_space(density_spacing(ui, ITEM_SPACING));
                     ui.label(
                         egui::RichText::new("This will overwrite all individual thumbnail sizes.")
                             .small()
                             .weak(),
                     );
-                    ui.add_space(ITEM_SPACING);
+                    ui.add_space(density_spacing(ui, ITEM_SPACING));
 
                     ui.horizontal(|ui| {
                         if ui.button("Yes, Resize All").clicked() {
@@ -717,6 +941,305 @@ fn render_resize_confirmation(
     changed
 }
 
+/// Cycle-order badge toggle with its corner and size. Returns true when changed.
+fn render_cycle_badge(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut profile.thumbnail_cycle_badge, "Cycle order badge")
+            .on_hover_text("Number each thumbnail with its position in its cycle group")
+            .changed();
+
+        ui.add_enabled_ui(profile.thumbnail_cycle_badge, |ui| {
+            changed |= render_badge_corner(
+                ui,
+                "cycle_badge_corner",
+                &mut profile.thumbnail_cycle_badge_corner,
+            );
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut profile.thumbnail_cycle_badge_size)
+                        .range(10..=64)
+                        .suffix(" px"),
+                )
+                .changed();
+        });
+    });
+    changed
+}
+
+/// Status badge toggle with its corner and icon size. Returns true when changed.
+fn render_status_badges(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut profile.thumbnail_status_badges, "Status badges")
+            .on_hover_text(
+                "Small icons for minimized, skipped, hotkey-bound and custom source thumbnails",
+            )
+            .changed();
+
+        ui.add_enabled_ui(profile.thumbnail_status_badges, |ui| {
+            changed |= render_badge_corner(
+                ui,
+                "status_badge_corner",
+                &mut profile.thumbnail_status_badge_corner,
+            );
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut profile.thumbnail_status_badge_size)
+                        .range(8..=48)
+                        .suffix(" px"),
+                )
+                .changed();
+        });
+    });
+    changed
+}
+
+/// Corner selector shared by the badge settings. Returns true when changed.
+fn render_badge_corner(ui: &mut egui::Ui, id: &str, value: &mut BadgeCorner) -> bool {
+    let label = |corner: BadgeCorner| match corner {
+        BadgeCorner::TopLeft => "Top left",
+        BadgeCorner::TopRight => "Top right",
+        BadgeCorner::BottomLeft => "Bottom left",
+        BadgeCorner::BottomRight => "Bottom right",
+    };
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(label(*value))
+        .show_ui(ui, |ui| {
+            for corner in [
+                BadgeCorner::TopLeft,
+                BadgeCorner::TopRight,
+                BadgeCorner::BottomLeft,
+                BadgeCorner::BottomRight,
+            ] {
+                changed |= ui.selectable_value(value, corner, label(corner)).changed();
+            }
+        });
+    changed
+}
+
+/// Outline/shadow selector with its color and size. Returns true when changed.
+fn render_text_effect(ui: &mut egui::Ui, profile: &mut Profile) -> bool {
+    use crate::common::constants::defaults::text;
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let label = |effect: TextEffect| match effect {
+            TextEffect::None => "None",
+            TextEffect::Outline => "Outline",
+            TextEffect::Shadow => "Drop shadow",
+        };
+        ui.label("Text Effect:");
+        egui::ComboBox::from_id_salt("text_effect_selector")
+            .selected_text(label(profile.thumbnail_text_effect))
+            .show_ui(ui, |ui| {
+                for effect in [TextEffect::None, TextEffect::Outline, TextEffect::Shadow] {
+                    changed |= ui
+                        .selectable_value(&mut profile.thumbnail_text_effect, effect, label(effect))
+                        .changed();
+                }
+            })
+            .response
+            .on_hover_text("Keeps labels readable over bright backgrounds (TrueType fonts only)");
+
+        if profile.thumbnail_text_effect == TextEffect::None {
+            return;
+        }
+
+        let text_edit = egui::TextEdit::singleline(&mut profile.thumbnail_text_effect_color)
+            .desired_width(100.0);
+        changed |= ui.add(text_edit).changed();
+        if let Ok(mut color) = parse_hex_color(&profile.thumbnail_text_effect_color)
+            && ui.color_edit_button_srgba(&mut color).changed()
+        {
+            profile.thumbnail_text_effect_color = format_hex_color(color);
+            changed = true;
+        }
+
+        if profile.thumbnail_text_effect == TextEffect::Outline {
+            ui.label("Width:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut profile.thumbnail_text_outline_width)
+                        .range(1..=text::OUTLINE_WIDTH_MAX)
+                        .suffix(" px"),
+                )
+                .changed();
+        } else {
+            let offset = -text::SHADOW_OFFSET_MAX..=text::SHADOW_OFFSET_MAX;
+            ui.label("X:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut profile.thumbnail_text_shadow_x)
+                        .range(offset.clone()),
+                )
+                .changed();
+            ui.label("Y:");
+            changed |= ui
+                .add(egui::DragValue::new(&mut profile.thumbnail_text_shadow_y).range(offset))
+                .changed();
+        }
+    });
+    changed
+}
+
+/// HiDPI scaling toggle and per-monitor multipliers for default sizes. Returns true when changed.
+fn render_monitor_scales(ui: &mut egui::Ui, profile: &mut Profile, new_name: &mut String) -> bool {
+    use crate::common::constants::defaults::thumbnail;
+
+    let mut changed = false;
+    changed |= ui
+        .checkbox(
+            &mut profile.thumbnail_hidpi_scaling,
+            "Scale for HiDPI monitors",
+        )
+        .on_hover_text(
+            "Grow default sizes on dense monitors (e.g. 1.5× on a 27\" 4K screen), \
+             based on the physical size the monitor reports",
+        )
+        .changed();
+
+    ui.label("Per-monitor multiplier:");
+    let mut names: Vec<String> = profile.thumbnail_monitor_scales.keys().cloned().collect();
+    names.sort();
+    let mut remove = None;
+    for name in names {
+        ui.horizontal(|ui| {
+            ui.label(&name);
+            if let Some(scale) = profile.thumbnail_monitor_scales.get_mut(&name) {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(scale)
+                            .range(thumbnail::MONITOR_SCALE_MIN..=thumbnail::MONITOR_SCALE_MAX)
+                            .speed(0.05)
+                            .fixed_decimals(2)
+                            .suffix("×"),
+                    )
+                    .changed();
+            }
+            if ui
+                .small_button("🗑")
+                .on_hover_text("Remove monitor")
+                .clicked()
+            {
+                remove = Some(name.clone());
+            }
+        });
+    }
+    if let Some(name) = remove {
+        profile.thumbnail_monitor_scales.remove(&name);
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(new_name)
+                .hint_text("monitor, e.g. DP-1")
+                .desired_width(120.0),
+        )
+        .on_hover_text("RandR monitor name, as listed by `xrandr --listmonitors`");
+        let name = new_name.trim();
+        if ui
+            .add_enabled(
+                !name.is_empty(),
+                egui::Button::new("➕ Add Monitor").small(),
+            )
+            .clicked()
+        {
+            profile
+                .thumbnail_monitor_scales
+                .entry(name.to_string())
+                .or_insert(1.0);
+            new_name.clear();
+            changed = true;
+        }
+    });
+
+    changed
+}
+
+/// Border style selector, returns true if the style changed
+fn border_style_combo(ui: &mut egui::Ui, id: &str, style: &mut BorderStyle) -> bool {
+    let label = |style: BorderStyle| match style {
+        BorderStyle::Solid => "Solid",
+        BorderStyle::Dashed => "Dashed",
+        BorderStyle::Double => "Double",
+        BorderStyle::InnerGlow => "Inner glow",
+    };
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(label(*style))
+        .show_ui(ui, |ui| {
+            for option in [
+                BorderStyle::Solid,
+                BorderStyle::Dashed,
+                BorderStyle::Double,
+                BorderStyle::InnerGlow,
+            ] {
+                changed |= ui.selectable_value(style, option, label(option)).changed();
+            }
+        })
+        .response
+        .on_hover_text("Double needs a border of 3 px or more");
+    changed
+}
+
+/// Border row for a client state with its own optional style and size.
+/// Unchecking "Custom" clears both, falling back to the inactive border.
+fn render_state_border(
+    ui: &mut egui::Ui,
+    label: &str,
+    id: &str,
+    style: &mut Option<BorderStyle>,
+    size: &mut Option<u16>,
+    inactive: (BorderStyle, u16),
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut custom = style.is_some() || size.is_some();
+        if ui
+            .checkbox(&mut custom, "Custom")
+            .on_hover_text("Own style and size instead of the inactive border")
+            .changed()
+        {
+            if custom {
+                *style = Some(inactive.0);
+                *size = Some(inactive.1);
+            } else {
+                *style = None;
+                *size = None;
+            }
+            changed = true;
+        }
+
+        if custom {
+            let style = style.get_or_insert(inactive.0);
+            let size = size.get_or_insert(inactive.1);
+            ui.label("Size:");
+            changed |= ui
+                .add(egui::DragValue::new(size).range(0..=20))
+                .on_hover_text("0 hides the border in this state")
+                .changed();
+            ui.label("Style:");
+            changed |= border_style_combo(ui, id, style);
+        }
+    });
+    changed
+}
+
+/// Display label for a thumbnail shape
+pub(crate) fn shape_label(shape: ThumbnailShape) -> &'static str {
+    match shape {
+        ThumbnailShape::Rectangle => "Rectangle",
+        ThumbnailShape::RoundedRect => "Rounded",
+        ThumbnailShape::Circle => "Circle",
+    }
+}
+
 /// Parse hex color string - supports both #RRGGBB and #AARRGGBB formats.
 /// Returns a Color32 if parsing succeeds, treating 6-digit hex as full-opacity RGB.
 fn parse_hex_color(hex: &str) -> Result<egui::Color32, ()> {
@@ -0,0 +1,233 @@
+//! Local control socket letting the CLI (`eve-preview-manager pause` / `resume` / `metrics`)
+//! reach the running Manager
+//!
+//! The protocol is one command per connection: the client writes a line, the Manager answers
+//! with the resulting state (`paused` / `running`), the latest daemon metrics as JSON, or
+//! `error: ...` (including when the previews can't be paused).
+
+use anyhow::{Context, Result, bail};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use eframe::egui;
+
+use crate::common::constants::config;
+use crate::manager::state::SharedState;
+
+/// How often the listener checks for new connections and the stop flag
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+
+/// Commands accepted on the socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    Pause,
+    Resume,
+    TogglePause,
+    /// Latest daemon metrics sample
+    Metrics,
+}
+
+impl ControlCommand {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::TogglePause => "toggle-pause",
+            Self::Metrics => "metrics",
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "pause" => Some(Self::Pause),
+            "resume" => Some(Self::Resume),
+            "toggle-pause" => Some(Self::TogglePause),
+            "metrics" => Some(Self::Metrics),
+            _ => None,
+        }
+    }
+}
+
+/// Socket location (`$XDG_RUNTIME_DIR/eve-preview-manager.sock`, temp dir as a fallback)
+pub fn socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("{}.sock", config::APP_DIR))
+}
+
+/// Sends `command` to the running Manager and returns its answer
+pub fn send(command: ControlCommand) -> Result<String> {
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path).context(format!(
+        "No running EVE Preview Manager found ({})",
+        path.display()
+    ))?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    writeln!(stream, "{}", command.as_str())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    let reply = reply.trim().to_string();
+    if let Some(error) = reply.strip_prefix("error: ") {
+        bail!("{}", error);
+    }
+    Ok(reply)
+}
+
+pub struct ControlSocket {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ControlSocket {
+    /// Binds the socket and applies incoming commands to `state`. Fails when another Manager
+    /// already owns the socket.
+    pub fn start(state: Arc<Mutex<SharedState>>, ctx: egui::Context) -> Result<Self> {
+        let path = socket_path();
+        if UnixStream::connect(&path).is_ok() {
+            bail!("Another Manager is listening on {}", path.display());
+        }
+        // Left behind by a Manager that didn't shut down cleanly
+        let _ = std::fs::remove_file(&path);
+
+        let listener = UnixListener::bind(&path)
+            .context(format!("Failed to bind control socket {}", path.display()))?;
+        listener.set_nonblocking(true)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("control-socket".to_string())
+            .spawn({
+                let stop = stop.clone();
+                move || serve(&listener, &state, &ctx, &stop)
+            })
+            .context("Failed to spawn control socket thread")?;
+
+        info!(path = %path.display(), "Listening for control commands");
+        Ok(Self {
+            path,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn serve(
+    listener: &UnixListener,
+    state: &Mutex<SharedState>,
+    ctx: &egui::Context,
+    stop: &AtomicBool,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = respond(stream, state, ctx) {
+                    warn!(error = %e, "Failed to answer control command");
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+            Err(e) => {
+                warn!(error = %e, "Control socket failed to accept");
+                std::thread::sleep(ACCEPT_POLL);
+            }
+        }
+    }
+}
+
+fn respond(stream: UnixStream, state: &Mutex<SharedState>, ctx: &egui::Context) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+
+    let reply = match ControlCommand::parse(&line) {
+        Some(command) => apply(command, state, ctx),
+        None => format!("error: unknown command {:?}", line.trim()),
+    };
+    writeln!(&stream, "{}", reply)?;
+    Ok(())
+}
+
+/// Applies a command and returns the reply line
+fn apply(command: ControlCommand, state: &Mutex<SharedState>, ctx: &egui::Context) -> String {
+    info!(command = command.as_str(), "Control command received");
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+    let paused = match command {
+        ControlCommand::Pause => true,
+        ControlCommand::Resume => false,
+        ControlCommand::TogglePause => !state.paused,
+        ControlCommand::Metrics => {
+            return match &state.daemon_metrics {
+                Some(metrics) => serde_json::to_string(metrics)
+                    .unwrap_or_else(|e| format!("error: failed to encode metrics: {e}")),
+                None => "error: no metrics received from the daemon yet".to_string(),
+            };
+        }
+    };
+    if !state.pause_supported() {
+        return "error: pausing is not supported by the running previews".to_string();
+    }
+    state.set_paused(paused);
+    ctx.request_repaint();
+    if paused { "paused" } else { "running" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        for command in [
+            ControlCommand::Pause,
+            ControlCommand::Resume,
+            ControlCommand::TogglePause,
+            ControlCommand::Metrics,
+        ] {
+            assert_eq!(
+                ControlCommand::parse(&format!("{}\n", command.as_str())),
+                Some(command)
+            );
+        }
+        assert_eq!(ControlCommand::parse("quit"), None);
+    }
+
+    #[test]
+    fn test_metrics_reply() {
+        use crate::common::ipc::DaemonMetrics;
+        use crate::config::profile::Config;
+
+        let state = Mutex::new(SharedState::new(Config::default(), false));
+        let ctx = egui::Context::default();
+        assert!(apply(ControlCommand::Metrics, &state, &ctx).starts_with("error: "));
+
+        let metrics = DaemonMetrics {
+            events_total: 7,
+            resident_memory_bytes: Some(1024),
+            ..Default::default()
+        };
+        state.lock().unwrap().daemon_metrics = Some(metrics.clone());
+        let reply = apply(ControlCommand::Metrics, &state, &ctx);
+        assert_eq!(
+            serde_json::from_str::<DaemonMetrics>(&reply).unwrap(),
+            metrics
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Character discovery from the EVE client's local files
+//!
+//! The client keeps per-character settings in
+//! `drive_c/users/<user>/AppData/Local/CCP/EVE/<install>/settings_<profile>/core_char_<id>.dat`,
+//! but those files only carry the numeric character ID. Names come from the chat and game log
+//! headers (`Listener: <name>`) under `Documents/EVE/logs`, whose file names end in the same ID
+//! on current clients. Combining both lets the Manager offer every character that ever played on
+//! this machine, before any client is running.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{debug, info, warn};
+
+/// Bytes read from the start of a log file when looking for the header
+const LOG_HEADER_BYTES: usize = 4096;
+
+/// A character found in a Wine prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredCharacter {
+    pub name: String,
+    pub character_id: Option<u64>,
+    /// The client has a `core_char` settings file for this character
+    pub has_settings: bool,
+    /// Modification time of the newest log naming this character
+    pub last_played: Option<SystemTime>,
+}
+
+/// Result of scanning one or more prefixes
+#[derive(Debug, Clone, Default)]
+pub struct ImportScan {
+    /// Characters with a known name, sorted case-insensitively
+    pub characters: Vec<DiscoveredCharacter>,
+    /// Character IDs with settings files but no log naming them
+    pub unnamed_ids: Vec<u64>,
+    /// Prefixes whose `drive_c/users` couldn't be read
+    pub skipped_prefixes: Vec<PathBuf>,
+}
+
+/// Wine prefixes EVE is commonly installed in (only those that exist)
+pub fn default_prefixes() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Ok(prefix) = std::env::var("WINEPREFIX") {
+        candidates.push(PathBuf::from(prefix));
+    }
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".wine"));
+        // Steam/Proton (EVE's Steam app ID is 8500)
+        for steam in [
+            ".steam/steam",
+            ".local/share/Steam",
+            ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+        ] {
+            candidates.push(home.join(steam).join("steamapps/compatdata/8500/pfx"));
+        }
+    }
+
+    let mut seen = BTreeSet::new();
+    candidates
+        .into_iter()
+        .filter(|p| p.join("drive_c").is_dir())
+        .filter(|p| seen.insert(p.canonicalize().unwrap_or_else(|_| p.clone())))
+        .collect()
+}
+
+/// Scan several prefixes and merge the results. Unreadable prefixes are skipped and listed.
+pub fn scan_prefixes(prefixes: &[PathBuf]) -> ImportScan {
+    let mut settings_ids = BTreeSet::new();
+    let mut named: HashMap<String, DiscoveredCharacter> = HashMap::new();
+    let mut skipped_prefixes = Vec::new();
+
+    for prefix in prefixes {
+        let users = prefix.join("drive_c/users");
+        let entries = match fs::read_dir(&users) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!(path = ?users, error = %e, "Skipping unreadable Wine prefix");
+                skipped_prefixes.push(prefix.clone());
+                continue;
+            }
+        };
+
+        for user in entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+            collect_settings_ids(&user.join("AppData/Local/CCP/EVE"), &mut settings_ids);
+            for documents in ["Documents", "My Documents"] {
+                let logs = user.join(documents).join("EVE/logs");
+                for kind in ["Chatlogs", "Gamelogs"] {
+                    collect_log_names(&logs.join(kind), &mut named);
+                }
+            }
+        }
+    }
+
+    let mut characters: Vec<DiscoveredCharacter> = named.into_values().collect();
+    for character in &mut characters {
+        character.has_settings = character
+            .character_id
+            .is_some_and(|id| settings_ids.contains(&id));
+    }
+    characters.sort_by_key(|c| c.name.to_lowercase());
+
+    let named_ids: BTreeSet<u64> = characters.iter().filter_map(|c| c.character_id).collect();
+    let unnamed_ids = settings_ids.difference(&named_ids).copied().collect();
+
+    info!(
+        prefixes = prefixes.len(),
+        skipped = skipped_prefixes.len(),
+        characters = characters.len(),
+        "Scanned EVE local settings"
+    );
+    ImportScan {
+        characters,
+        unnamed_ids,
+        skipped_prefixes,
+    }
+}
+
+/// Collect character IDs from `core_char_<id>.dat` in every `settings_*` folder of every install
+fn collect_settings_ids(eve_dir: &Path, ids: &mut BTreeSet<u64>) {
+    let Ok(installs) = fs::read_dir(eve_dir) else {
+        return;
+    };
+    for install in installs.flatten().map(|e| e.path()) {
+        let Ok(children) = fs::read_dir(&install) else {
+            continue;
+        };
+        let settings_dirs = children.flatten().map(|e| e.path()).filter(|p| {
+            p.is_dir()
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("settings_"))
+        });
+        for dir in settings_dirs {
+            let Ok(files) = fs::read_dir(&dir) else {
+                continue;
+            };
+            ids.extend(files.flatten().filter_map(|f| {
+                f.file_name()
+                    .to_str()
+                    .and_then(|n| n.strip_prefix("core_char_"))
+                    .and_then(|n| n.strip_suffix(".dat"))
+                    .and_then(|id| id.parse::<u64>().ok())
+            }));
+        }
+    }
+}
+
+/// Collect names (and IDs, where the file name has one) from log headers
+fn collect_log_names(log_dir: &Path, named: &mut HashMap<String, DiscoveredCharacter>) {
+    let Ok(files) = fs::read_dir(log_dir) else {
+        return;
+    };
+    for file in files.flatten() {
+        let path = file.path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let Some(name) = read_listener(&path) else {
+            continue;
+        };
+        let character_id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(character_id_from_log_name);
+        let modified = file.metadata().and_then(|m| m.modified()).ok();
+
+        let entry = named
+            .entry(name.clone())
+            .or_insert_with(|| DiscoveredCharacter {
+                name,
+                character_id: None,
+                has_settings: false,
+                last_played: None,
+            });
+        entry.character_id = entry.character_id.or(character_id);
+        entry.last_played = entry.last_played.max(modified);
+    }
+}
+
+/// Character name from the `Listener:` line of a chat or game log
+fn read_listener(path: &Path) -> Option<String> {
+    let mut buf = Vec::with_capacity(LOG_HEADER_BYTES);
+    fs::File::open(path)
+        .and_then(|f| f.take(LOG_HEADER_BYTES as u64).read_to_end(&mut buf))
+        .map_err(|e| debug!(path = ?path, error = %e, "Skipping unreadable log"))
+        .ok()?;
+    parse_listener(&decode_log(&buf))
+}
+
+/// Chat logs are UTF-16LE with a BOM, game logs are UTF-8
+fn decode_log(bytes: &[u8]) -> String {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn parse_listener(header: &str) -> Option<String> {
+    header
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Listener:"))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// `<channel>_<YYYYMMDD>_<HHMMSS>_<id>` (the ID is missing on older clients)
+fn character_id_from_log_name(stem: &str) -> Option<u64> {
+    let parts: Vec<&str> = stem.rsplitn(4, '_').collect();
+    let is_digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    match parts.as_slice() {
+        [id, time, date, ..] if is_digits(time, 6) && is_digits(date, 8) => id.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16_log(lines: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(lines.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_character_id_from_log_name() {
+        assert_eq!(
+            character_id_from_log_name("Local_20240101_120000_90000001"),
+            Some(90000001)
+        );
+        assert_eq!(
+            character_id_from_log_name("20240101_120000_90000001"),
+            Some(90000001)
+        );
+        assert_eq!(character_id_from_log_name("Local_20240101_120000"), None);
+        assert_eq!(
+            character_id_from_log_name("Fleet_Chat_20240101_120000"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_listener_from_utf16_chatlog() {
+        let header =
+            "\r\n  ---------\r\n  Channel Name:    Local\r\n  Listener:        Jane Doe\r\n";
+        assert_eq!(
+            parse_listener(&decode_log(&utf16_log(header))),
+            Some("Jane Doe".to_string())
+        );
+        assert_eq!(parse_listener("  Listener:   \n"), None);
+    }
+
+    #[test]
+    fn test_scan_prefix() {
+        let prefix = tempfile::tempdir().unwrap();
+        let user = prefix.path().join("drive_c/users/steamuser");
+        let settings = user.join("AppData/Local/CCP/EVE/c_eve_tq/settings_Default");
+        let chatlogs = user.join("Documents/EVE/logs/Chatlogs");
+        fs::create_dir_all(&settings).unwrap();
+        fs::create_dir_all(&chatlogs).unwrap();
+
+        for id in [90000001, 90000002] {
+            fs::write(settings.join(format!("core_char_{id}.dat")), b"").unwrap();
+        }
+        fs::write(settings.join("core_char__.dat"), b"").unwrap();
+        fs::write(
+            chatlogs.join("Local_20240101_120000_90000001.txt"),
+            utf16_log("  Listener:        Jane Doe\r\n"),
+        )
+        .unwrap();
+        fs::write(
+            chatlogs.join("Corp_20230101_120000.txt"),
+            utf16_log("  Listener:        Old Alt\r\n"),
+        )
+        .unwrap();
+
+        let missing = prefix.path().join("missing");
+        let scan = scan_prefixes(&[missing.clone(), prefix.path().to_path_buf()]);
+        let names: Vec<&str> = scan.characters.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Jane Doe", "Old Alt"]);
+        assert!(scan.characters[0].has_settings);
+        assert_eq!(scan.characters[0].character_id, Some(90000001));
+        assert!(!scan.characters[1].has_settings);
+        assert_eq!(scan.unnamed_ids, [90000002]);
+        assert_eq!(scan.skipped_prefixes, [missing]);
+    }
+}
@@ -0,0 +1,353 @@
+//! Fallback previews for X servers without the Composite extension
+//!
+//! The daemon needs Composite to read client contents and refuses to start without it. In that
+//! case the Manager grabs a still image of every EVE client every few seconds (plain `GetImage`)
+//! and shows each one in a small borderless egui viewport at the character's saved thumbnail
+//! position (see `x11::capture`), with the daemon's label and border drawn by its egui overlay
+//! backend. Without Composite the server only keeps what is visible on
+//! screen, so obscured parts come out stale or black, but users still get something to look at
+//! and click.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::composite;
+use x11rb::protocol::xproto::Window;
+use x11rb::rust_connection::RustConnection;
+
+use eframe::egui;
+
+use crate::common::constants::manager_ui::*;
+use crate::common::types::{ClientDetectionRule, Dimensions, EveWindowType};
+use crate::config::profile::Profile;
+use crate::config::{DaemonConfig, DisplayConfig};
+use crate::daemon::font::{FontRenderer, profile_font};
+use crate::daemon::{BorderState, EguiOverlay, OverlayBackend};
+use crate::x11::{CachedAtoms, activate_window, capture_frame, get_client_list, is_window_eve};
+
+/// Whether the X server offers the Composite extension (false if X is unreachable)
+pub fn composite_available() -> bool {
+    let Ok((conn, _)) = x11rb::connect(None) else {
+        return false;
+    };
+    conn.extension_information(composite::X11_EXTENSION_NAME)
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Messages from the capture thread
+enum CaptureEvent {
+    Frame {
+        window: Window,
+        name: String,
+        size: [usize; 2],
+        rgba: Vec<u8>,
+    },
+    Gone(Window),
+}
+
+/// Requests from the UI to the capture thread
+enum CaptureCommand {
+    Activate(Window),
+    Stop,
+}
+
+struct Preview {
+    name: String,
+    texture: egui::TextureHandle,
+    /// Label and border, redrawn with each capture
+    overlay: EguiOverlay,
+    /// A capture arrived since the overlay was drawn
+    stale: bool,
+}
+
+/// Periodically captured client images shown in egui viewports
+pub struct FallbackPreviews {
+    events: mpsc::Receiver<CaptureEvent>,
+    commands: mpsc::Sender<CaptureCommand>,
+    thread: Option<JoinHandle<()>>,
+    previews: HashMap<Window, Preview>,
+    /// Label font; without one the title is painted by egui
+    font: Option<FontRenderer>,
+}
+
+impl FallbackPreviews {
+    /// Starts the capture thread. Images are downscaled to the profile's default thumbnail size
+    /// before they reach the UI. Clients are recognized with the profile's detection rules.
+    pub fn start(profile: &Profile) -> Result<Self> {
+        let max_size = [
+            profile.thumbnail_default_width,
+            profile.thumbnail_default_height,
+        ];
+        let rules = profile.client_detection_rules.clone();
+        let (conn, screen_num) =
+            x11rb::connect(None).context("Failed to connect to X11 for fallback previews")?;
+        let atoms = CachedAtoms::new(&conn).context("Failed to cache X11 atoms")?;
+
+        let (event_tx, events) = mpsc::channel();
+        let (commands, command_rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("fallback-capture".to_string())
+            .spawn(move || {
+                capture_loop(
+                    &conn,
+                    screen_num,
+                    &atoms,
+                    max_size,
+                    &rules,
+                    &event_tx,
+                    &command_rx,
+                )
+            })
+            .context("Failed to spawn fallback capture thread")?;
+
+        info!("Composite extension unavailable, showing fallback previews");
+        Ok(Self {
+            events,
+            commands,
+            thread: Some(thread),
+            previews: HashMap::new(),
+            font: profile_font(
+                &profile.thumbnail_text_font,
+                profile.thumbnail_text_size as f32,
+            ),
+        })
+    }
+
+    /// Uploads new captures and shows one viewport per client
+    pub fn show(&mut self, ctx: &egui::Context, profile: &Profile) {
+        for event in self.events.try_iter() {
+            match event {
+                CaptureEvent::Frame {
+                    window,
+                    name,
+                    size,
+                    rgba,
+                } => {
+                    let image = egui::ColorImage::from_rgba_unmultiplied(size, &rgba);
+                    match self.previews.get_mut(&window) {
+                        Some(preview) => {
+                            preview.texture.set(image, egui::TextureOptions::LINEAR);
+                            preview.name = name;
+                            preview.stale = true;
+                        }
+                        None => {
+                            let texture = ctx.load_texture(
+                                format!("fallback_preview_{}", window),
+                                image,
+                                egui::TextureOptions::LINEAR,
+                            );
+                            let preview = Preview {
+                                name,
+                                texture,
+                                overlay: EguiOverlay::new(Dimensions::new(0, 0)),
+                                stale: true,
+                            };
+                            self.previews.insert(window, preview);
+                        }
+                    }
+                }
+                CaptureEvent::Gone(window) => {
+                    self.previews.remove(&window);
+                }
+            }
+        }
+
+        // Built at most once per frame, and only when an overlay needs redrawing
+        let mut display_config: Option<DisplayConfig> = None;
+        for (index, (&window, preview)) in self.previews.iter_mut().enumerate() {
+            let (position, size) = placement(profile, &preview.name, index);
+            let dimensions = Dimensions::new(size.x as u16, size.y as u16);
+            if preview.stale || preview.overlay.dimensions() != dimensions {
+                let config = display_config.get_or_insert_with(|| {
+                    DaemonConfig::for_profile(profile.clone()).build_display_config()
+                });
+                preview.stale = false;
+                if let Err(e) = redraw_overlay(preview, config, dimensions, self.font.as_ref()) {
+                    debug!(character = %preview.name, error = %e, "Failed to draw fallback overlay");
+                }
+            }
+            let preview = &*preview;
+            let title = if preview.name.is_empty() {
+                "EVE (logged out)".to_string()
+            } else {
+                profile.display_name(&preview.name)
+            };
+
+            let mut clicked = false;
+            ctx.show_viewport_immediate(
+                egui::ViewportId::from_hash_of(("fallback_preview", window)),
+                egui::ViewportBuilder::default()
+                    .with_title(format!("{} - preview", title))
+                    .with_position(position)
+                    .with_inner_size(size)
+                    .with_decorations(false)
+                    .with_always_on_top()
+                    .with_taskbar(false),
+                |ctx, _class| {
+                    egui::CentralPanel::default()
+                        .frame(egui::Frame::NONE)
+                        .show(ctx, |ui| {
+                            let response = ui.add(
+                                egui::Image::new(&preview.texture)
+                                    .fit_to_exact_size(ui.available_size())
+                                    .sense(egui::Sense::click()),
+                            );
+                            let mut painter = ui.painter_at(response.rect);
+                            if let Err(e) = preview.overlay.composite(&mut painter, dimensions) {
+                                debug!(character = %preview.name, error = %e, "Failed to paint fallback overlay");
+                            }
+                            // The overlay labels logged-in characters, and only with a font
+                            if preview.name.is_empty() || self.font.is_none() {
+                                painter.text(
+                                    response.rect.left_top() + egui::vec2(6.0, 4.0),
+                                    egui::Align2::LEFT_TOP,
+                                    &title,
+                                    egui::FontId::proportional(14.0),
+                                    egui::Color32::WHITE,
+                                );
+                            }
+                            clicked = response
+                                .on_hover_text("Static preview (no compositor)")
+                                .clicked();
+                        });
+                },
+            );
+
+            if clicked {
+                let _ = self.commands.send(CaptureCommand::Activate(window));
+            }
+        }
+    }
+
+    /// Stops the capture thread (previews disappear with `self`)
+    pub fn stop(&mut self) {
+        let _ = self.commands.send(CaptureCommand::Stop);
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            warn!("Fallback capture thread panicked");
+        }
+    }
+}
+
+impl Drop for FallbackPreviews {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Redraws a preview's overlay at `dimensions`. Fallback previews don't track focus, so they show
+/// the unfocused border.
+fn redraw_overlay(
+    preview: &mut Preview,
+    config: &DisplayConfig,
+    dimensions: Dimensions,
+    font_renderer: Option<&FontRenderer>,
+) -> Result<()> {
+    if preview.overlay.dimensions() != dimensions {
+        preview.overlay.resize(dimensions)?;
+    }
+    preview.overlay.draw(
+        config,
+        &preview.name,
+        BorderState::Unfocused,
+        false,
+        font_renderer,
+    )
+}
+
+/// Saved thumbnail position and size for a character, or a grid slot for unknown ones
+fn placement(profile: &Profile, name: &str, index: usize) -> (egui::Pos2, egui::Vec2) {
+    if let Some(settings) = profile.character_thumbnails.get(name) {
+        return (
+            egui::pos2(settings.x as f32, settings.y as f32),
+            egui::vec2(
+                settings.dimensions.width as f32,
+                settings.dimensions.height as f32,
+            ),
+        );
+    }
+    let width = profile.thumbnail_default_width as f32;
+    let height = profile.thumbnail_default_height as f32;
+    let gap = 10.0;
+    (
+        egui::pos2(gap + index as f32 * (width + gap), gap),
+        egui::vec2(width, height),
+    )
+}
+
+fn capture_loop(
+    conn: &RustConnection,
+    screen_num: usize,
+    atoms: &CachedAtoms,
+    max_size: [u16; 2],
+    rules: &[ClientDetectionRule],
+    events: &mpsc::Sender<CaptureEvent>,
+    commands: &mpsc::Receiver<CaptureCommand>,
+) {
+    let interval = Duration::from_millis(FALLBACK_CAPTURE_INTERVAL_MS);
+    let screen = &conn.setup().roots[screen_num];
+    let mut known: Vec<Window> = Vec::new();
+
+    loop {
+        let started = Instant::now();
+        let windows = get_client_list(conn, atoms).unwrap_or_else(|e| {
+            warn!(error = %e, "Failed to list client windows for fallback previews");
+            Vec::new()
+        });
+
+        let mut seen = Vec::new();
+        for window in windows {
+            let name = match is_window_eve(conn, window, atoms, rules) {
+                Ok(Some(EveWindowType::LoggedIn(name))) => name,
+                Ok(Some(EveWindowType::LoggedOut)) => String::new(),
+                _ => continue,
+            };
+            seen.push(window);
+            match capture_frame(conn, window) {
+                Ok(Some(frame)) => {
+                    let frame = frame.downscaled(max_size);
+                    let frame = CaptureEvent::Frame {
+                        window,
+                        name,
+                        size: [frame.width, frame.height],
+                        rgba: frame.rgba,
+                    };
+                    if events.send(frame).is_err() {
+                        return;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => debug!(window = window, error = %e, "Fallback capture failed"),
+            }
+        }
+
+        for gone in known.iter().filter(|w| !seen.contains(w)) {
+            if events.send(CaptureEvent::Gone(*gone)).is_err() {
+                return;
+            }
+        }
+        known = seen;
+
+        // Wait for the next capture while staying responsive to clicks
+        loop {
+            let remaining = interval.saturating_sub(started.elapsed());
+            match commands.recv_timeout(remaining) {
+                Ok(CaptureCommand::Activate(window)) => {
+                    // No input event to take a timestamp from; CurrentTime is accepted by WMs
+                    if let Err(e) = activate_window(conn, screen, atoms, window, 0) {
+                        warn!(window = window, error = %e, "Failed to activate client");
+                    }
+                }
+                Ok(CaptureCommand::Stop) | Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+            }
+        }
+    }
+}
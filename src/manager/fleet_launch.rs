@@ -0,0 +1,233 @@
+//! Staggered startup of the profile's launch order
+//!
+//! Launching every client at once overloads the login servers and the machine, so "Launch
+//! fleet" starts one client, waits until the daemon reports its character, waits the next
+//! entry's delay and only then starts the next one. Characters already running are skipped; a
+//! client that never logs in is given up on after `FLEET_LAUNCH_APPEAR_TIMEOUT_SECS`.
+
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::common::constants::manager_ui::{
+    CLIENT_LAYOUT_TIMEOUT_MS, FLEET_LAUNCH_APPEAR_TIMEOUT_SECS,
+};
+use crate::config::profile::LaunchEntry;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Phase {
+    /// Waiting for the daemon's client list, so running characters are skipped
+    Layout { requested_at: Instant },
+    /// Next entry is launched at this time
+    Delay { until: Instant },
+    /// Launched `character`, waiting for it to log in until `deadline`
+    Appear {
+        character: String,
+        deadline: Instant,
+    },
+}
+
+/// Progress of a fleet launch
+#[derive(Debug)]
+pub struct FleetLaunch {
+    pending: VecDeque<LaunchEntry>,
+    phase: Phase,
+    launched: usize,
+    total: usize,
+}
+
+impl FleetLaunch {
+    /// Starts with the entries that have a command. With `layout_requested`, launching waits
+    /// for `layout_received` (or a timeout); otherwise the first delay starts right away.
+    pub fn new(entries: &[LaunchEntry], layout_requested: bool, now: Instant) -> Self {
+        let pending: VecDeque<LaunchEntry> = entries
+            .iter()
+            .filter(|e| !e.character.trim().is_empty() && !e.command.trim().is_empty())
+            .cloned()
+            .collect();
+        let mut launch = Self {
+            total: pending.len(),
+            pending,
+            phase: Phase::Layout { requested_at: now },
+            launched: 0,
+        };
+        if !layout_requested {
+            launch.schedule_next(now);
+        }
+        launch
+    }
+
+    /// Drops the characters that are already logged in, then starts the first delay
+    pub fn layout_received<'a>(
+        &mut self,
+        running: impl IntoIterator<Item = &'a str>,
+        now: Instant,
+    ) {
+        if !matches!(self.phase, Phase::Layout { .. }) {
+            return;
+        }
+        for name in running {
+            self.skip(name);
+        }
+        self.schedule_next(now);
+    }
+
+    /// Notes that `name` logged in: moves on when it is the awaited character, and drops it
+    /// from the queue when it was started by hand
+    pub fn character_appeared(&mut self, name: &str, now: Instant) {
+        self.skip(name);
+        if matches!(&self.phase, Phase::Appear { character, .. } if character == name) {
+            self.schedule_next(now);
+        }
+    }
+
+    /// Returns the entry to launch now, if one is due. Launched entries are awaited.
+    pub fn poll(&mut self, now: Instant) -> Option<LaunchEntry> {
+        match &self.phase {
+            Phase::Layout { requested_at } => {
+                if now.duration_since(*requested_at)
+                    >= Duration::from_millis(CLIENT_LAYOUT_TIMEOUT_MS)
+                {
+                    self.schedule_next(now);
+                }
+                None
+            }
+            Phase::Delay { until } if now >= *until => {
+                let entry = self.pending.pop_front()?;
+                self.launched += 1;
+                self.phase = Phase::Appear {
+                    character: entry.character.clone(),
+                    deadline: now + Duration::from_secs(FLEET_LAUNCH_APPEAR_TIMEOUT_SECS),
+                };
+                Some(entry)
+            }
+            Phase::Delay { .. } => None,
+            Phase::Appear {
+                character,
+                deadline,
+            } => {
+                if now >= *deadline {
+                    warn!(character = %character, "Character did not log in, launching the next one");
+                    self.schedule_next(now);
+                }
+                None
+            }
+        }
+    }
+
+    /// Whether every entry was launched and the last one logged in (or timed out)
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty() && matches!(self.phase, Phase::Delay { .. })
+    }
+
+    /// Short progress text for the Manager status line
+    pub fn status(&self) -> String {
+        match &self.phase {
+            Phase::Layout { .. } => "Fleet launch: checking running clients".to_string(),
+            Phase::Appear { character, .. } => format!(
+                "Fleet launch {}/{}: waiting for {} to log in",
+                self.launched, self.total, character
+            ),
+            Phase::Delay { .. } => match self.pending.front() {
+                Some(next) => format!(
+                    "Fleet launch {}/{}: starting {} in {}s",
+                    self.launched, self.total, next.character, next.delay_secs
+                ),
+                None => format!("Fleet launched ({} clients)", self.launched),
+            },
+        }
+    }
+
+    fn skip(&mut self, name: &str) {
+        let before = self.pending.len();
+        self.pending.retain(|e| e.character != name);
+        self.total -= before - self.pending.len();
+    }
+
+    fn schedule_next(&mut self, now: Instant) {
+        let delay = self.pending.front().map_or(0, |e| e.delay_secs);
+        self.phase = Phase::Delay {
+            until: now + Duration::from_secs(delay.into()),
+        };
+    }
+}
+
+/// Runs an entry's command through `sh -c`, detached from the Manager's stdio
+pub fn spawn(entry: &LaunchEntry) -> Result<()> {
+    info!(character = %entry.character, command = %entry.command, "Launching client");
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&entry.command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context(format!("Failed to launch {}", entry.character))?;
+    // Reap the shell when it exits so it doesn't linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(character: &str, delay_secs: u32) -> LaunchEntry {
+        LaunchEntry {
+            character: character.to_string(),
+            command: format!("launch {character}"),
+            delay_secs,
+        }
+    }
+
+    #[test]
+    fn test_launches_in_order_after_each_login() {
+        let start = Instant::now();
+        let mut launch = FleetLaunch::new(&[entry("A", 0), entry("B", 5)], false, start);
+
+        assert_eq!(launch.poll(start).unwrap().character, "A");
+        // B waits for A to log in
+        assert!(launch.poll(start + Duration::from_secs(10)).is_none());
+
+        let login = start + Duration::from_secs(20);
+        launch.character_appeared("A", login);
+        assert!(launch.poll(login + Duration::from_secs(4)).is_none());
+        assert_eq!(
+            launch
+                .poll(login + Duration::from_secs(5))
+                .unwrap()
+                .character,
+            "B"
+        );
+
+        launch.character_appeared("B", login + Duration::from_secs(30));
+        assert!(launch.is_done());
+    }
+
+    #[test]
+    fn test_skips_running_and_incomplete_entries() {
+        let start = Instant::now();
+        let mut no_command = entry("C", 0);
+        no_command.command.clear();
+        let mut launch = FleetLaunch::new(&[entry("A", 0), entry("B", 0), no_command], true, start);
+
+        assert!(launch.poll(start).is_none());
+        launch.layout_received(["A"], start);
+        assert_eq!(launch.poll(start).unwrap().character, "B");
+        launch.character_appeared("B", start);
+        assert!(launch.is_done());
+    }
+
+    #[test]
+    fn test_moves_on_when_client_never_logs_in() {
+        let start = Instant::now();
+        let mut launch = FleetLaunch::new(&[entry("A", 0), entry("B", 0)], false, start);
+
+        assert_eq!(launch.poll(start).unwrap().character, "A");
+        let timeout = start + Duration::from_secs(FLEET_LAUNCH_APPEAR_TIMEOUT_SECS);
+        assert!(launch.poll(timeout).is_none());
+        assert_eq!(launch.poll(timeout).unwrap().character, "B");
+    }
+}
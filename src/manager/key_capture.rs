@@ -8,7 +8,7 @@ use std::thread;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
-use crate::common::constants::{input, paths, permissions};
+use crate::common::constants::input;
 use crate::config::{HotkeyBackendType, HotkeyBinding};
 use crate::input::device_detection;
 use x11rb::connection::Connection;
@@ -19,6 +19,8 @@ use x11rb::protocol::xproto::{ConnectionExt, GrabMode, KeyButMask};
 pub enum CaptureResult {
     /// Key was successfully captured
     Captured(HotkeyBinding),
+    /// Key was captured but the hotkey backend can't grab it (with the reason)
+    Rejected(String),
     /// User pressed Escape to cancel
     Cancelled,
     /// Capture timed out (no key pressed within timeout period)
@@ -53,6 +55,12 @@ impl CaptureState {
         }
     }
 
+    /// evdev name and code of the main key, e.g. "KEY_F1 (59)"
+    pub fn key_detail(&self) -> Option<String> {
+        self.key_code
+            .map(|code| format!("{:?} ({})", evdev::KeyCode(code), code))
+    }
+
     /// Update description based on current state
     pub fn update_description(&mut self) {
         if let Some(key_code) = self.key_code {
@@ -91,20 +99,42 @@ impl Default for CaptureState {
     }
 }
 
+/// Turns a captured binding the hotkey backend can't grab (e.g. another application holds
+/// the same X11 grab) into `Rejected`. Keys in `daemon_hotkeys` are grabbed by our own daemon,
+/// so they aren't probed.
+fn check_grabbable(
+    backend: HotkeyBackendType,
+    daemon_hotkeys: &[HotkeyBinding],
+    result: CaptureResult,
+) -> CaptureResult {
+    match result {
+        CaptureResult::Captured(binding)
+            if daemon_hotkeys.iter().any(|held| held.same_keys(&binding)) =>
+        {
+            CaptureResult::Captured(binding)
+        }
+        CaptureResult::Captured(binding) => {
+            match crate::input::listener::check_grab(backend, &binding) {
+                Ok(()) => CaptureResult::Captured(binding),
+                Err(e) => {
+                    info!(binding = ?binding, error = %e, "Captured key can't be grabbed");
+                    CaptureResult::Rejected(format!("{:#}", e))
+                }
+            }
+        }
+        other => other,
+    }
+}
+
 /// Start capturing a key press in the background
-/// Returns a receiver that will receive updates about capture state and final result
+/// Returns a receiver that will receive updates about capture state and final result.
+/// `daemon_hotkeys` are the bindings the running daemon currently grabs.
 pub fn start_capture(
     backend: HotkeyBackendType,
+    daemon_hotkeys: Vec<HotkeyBinding>,
 ) -> Result<(Receiver<CaptureState>, Receiver<CaptureResult>, Sender<()>)> {
-    // Check permissions first if using evdev
-    if backend == HotkeyBackendType::Evdev && std::fs::read_dir(paths::DEV_INPUT).is_err() {
-        return Err(anyhow::anyhow!(
-            "Cannot access {}. Ensure you're in '{}' group:\n{}\nThen log out and back in.",
-            paths::DEV_INPUT,
-            permissions::INPUT_GROUP,
-            permissions::ADD_TO_INPUT_GROUP
-        ));
-    }
+    // Capture the way the daemon will listen: evdev falls through to X11 without /dev/input
+    let backend = crate::input::listener::select_backend(backend);
 
     let (state_tx, state_rx) = mpsc::channel();
     let (result_tx, result_rx) = mpsc::channel();
@@ -114,11 +144,12 @@ pub fn start_capture(
         let result = match backend {
             HotkeyBackendType::X11 => capture_key_x11(state_tx, cancel_rx),
             HotkeyBackendType::Evdev => capture_key_blocking(state_tx, cancel_rx),
+            HotkeyBackendType::Portal => capture_key_portal(state_tx),
         };
 
         match result {
             Ok(res) => {
-                let _ = result_tx.send(res);
+                let _ = result_tx.send(check_grabbable(backend, &daemon_hotkeys, res));
             }
             Err(e) => {
                 warn!(error = %e, "Key capture error");
@@ -130,6 +161,35 @@ pub fn start_capture(
     Ok((state_rx, result_rx, cancel_tx))
 }
 
+/// Key capture through the GlobalShortcuts portal's bind dialog. The dialog belongs to the
+/// compositor, so it can't be cancelled from here; closing it reports `Cancelled`.
+fn capture_key_portal(state_tx: Sender<CaptureState>) -> Result<CaptureResult> {
+    let mut state = CaptureState::new();
+    state.description = "Choose a shortcut in the desktop's dialog...".to_string();
+    let _ = state_tx.send(state.clone());
+
+    match crate::input::portal_backend::bind_interactively("EVE Preview Manager hotkey") {
+        Ok(binding) => {
+            state.ctrl = binding.ctrl;
+            state.shift = binding.shift;
+            state.alt = binding.alt;
+            state.super_key = binding.super_key;
+            state.key_code = Some(binding.key_code);
+            state.update_description();
+            let _ = state_tx.send(state);
+            info!(binding = ?binding, "Key captured through the portal");
+            Ok(CaptureResult::Captured(binding))
+        }
+        Err(e)
+            if e.downcast_ref::<crate::input::portal_backend::DialogCancelled>()
+                .is_some() =>
+        {
+            Ok(CaptureResult::Cancelled)
+        }
+        Err(e) => Err(e),
+    }
+}
+
 /// Blocking key capture using X11 GrabKeyboard
 fn capture_key_x11(
     state_tx: Sender<CaptureState>,
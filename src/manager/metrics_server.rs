@@ -0,0 +1,127 @@
+//! Serves the latest daemon metrics in Prometheus text format on a localhost port
+//!
+//! Metrics arrive with the daemon's heartbeat; the server only hands out the last sample, so
+//! scrapes never reach into the daemon itself.
+
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::common::ipc::DaemonMetrics;
+
+/// How often the listener checks for new connections and the stop flag
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+
+pub struct MetricsServer {
+    addr: SocketAddr,
+    body: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Binds `127.0.0.1:port` and starts answering scrapes
+    pub fn start(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .context(format!("Failed to bind metrics port {}", port))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let body = Arc::new(Mutex::new(String::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("metrics-server".to_string())
+            .spawn({
+                let body = body.clone();
+                let stop = stop.clone();
+                move || serve(&listener, &body, &stop)
+            })
+            .context("Failed to spawn metrics server thread")?;
+
+        info!(addr = %addr, "Serving daemon metrics");
+        Ok(Self {
+            addr,
+            body,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Replaces the sample handed out to scrapers
+    pub fn publish(&self, metrics: &DaemonMetrics) {
+        *self.body.lock().unwrap_or_else(|e| e.into_inner()) = metrics.to_prometheus();
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(listener: &TcpListener, body: &Mutex<String>, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let body = body.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                if let Err(e) = respond(stream, &body) {
+                    warn!(error = %e, "Failed to answer metrics scrape");
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+            Err(e) => {
+                warn!(error = %e, "Metrics listener failed to accept");
+                std::thread::sleep(ACCEPT_POLL);
+            }
+        }
+    }
+}
+
+/// Answers any request with the metrics (the path doesn't matter)
+fn respond(mut stream: TcpStream, body: &str) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request)?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serves_published_metrics() {
+        let server = MetricsServer::start(0).unwrap();
+        server.publish(&DaemonMetrics {
+            events_total: 7,
+            ..Default::default()
+        });
+
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("eve_preview_events_total 7\n"));
+    }
+}
@@ -2,8 +2,14 @@
 
 mod app;
 pub mod components;
+pub mod control_socket;
+mod eve_import;
+mod fallback;
+mod fleet_launch;
 mod key_capture;
+mod metrics_server;
 pub mod state;
+mod theme;
 pub mod utils;
 pub mod x11_utils;
 
@@ -1,16 +1,25 @@
+use std::collections::{HashMap, VecDeque};
 use std::process::Child;
 use std::sync::mpsc::Receiver;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use tracing::{debug, error, info, warn};
 
 use crate::common::constants::manager_ui::*;
-use crate::common::ipc::{BootstrapMessage, ConfigMessage, DaemonMessage};
-use crate::config::DaemonConfig;
+use crate::common::ipc::{
+    BootstrapMessage, ClientGeometry, ConfigMessage, DaemonMessage, DaemonMetrics,
+    HotkeyTestResult, RulePatterns, SyntheticInput, WindowMatch, WinePrefixInfo,
+};
 use crate::config::profile::Config;
+use crate::config::session::{SessionHistory, unix_now};
+use crate::config::{DaemonConfig, HotkeyBinding};
+use crate::manager::fallback::FallbackPreviews;
+use crate::manager::fleet_launch::FleetLaunch;
+use crate::manager::metrics_server::MetricsServer;
 use ipc_channel::ipc::{IpcReceiver, IpcSender};
 
+use super::x_errors::XErrorTracker;
 use super::{DaemonStatus, StatusMessage};
 
 /// Determines the behavior of `save_config`.
@@ -30,6 +39,10 @@ pub struct SharedState {
     pub config: Config,
     pub debug_mode: bool,
     pub daemon: Option<Child>,
+    /// Daemon sent SIGTERM, reaped by `poll_daemon` (SIGKILL once the deadline passes)
+    pub stopping_daemon: Option<(Child, Instant)>,
+    /// Start a new daemon once the stopping one has exited (restarts)
+    pub start_after_stop: bool,
     pub daemon_status: DaemonStatus,
     pub last_health_check: Instant,
     pub status_message: Option<StatusMessage>,
@@ -37,7 +50,22 @@ pub struct SharedState {
     pub settings_changed: bool,
     pub selected_profile_idx: usize,
     pub should_quit: bool,
-    pub last_save_attempt: Instant,
+    /// Whether a StatusNotifier tray hosts our icon; without one the control panel is shown
+    pub tray_available: bool,
+    /// Daemon paused by the user (re-applied when the daemon restarts)
+    pub paused: bool,
+    /// Shown in the daemon's OSD once the restarted daemon has connected (profile switches)
+    pub pending_announcement: Option<String>,
+    /// Character to focus once the first daemon of this launch has connected
+    pub pending_focus_restore: Option<String>,
+    /// Set once a daemon has been told to run the startup actions, so restarted daemons skip them
+    /// (cleared by a profile switch)
+    pub startup_actions_sent: bool,
+    /// Deadline for writing the last focused character to disk (debounced)
+    pub pending_focus_save: Option<Instant>,
+    // Debounced auto-save of thumbnail positions: deadline and latest geometry per (name, is_custom)
+    pub pending_position_save: Option<Instant>,
+    pub pending_position_acks: HashMap<(String, bool), (i16, i16, u16, u16)>,
 
     // IPC
     pub ipc_config_tx: Option<IpcSender<ConfigMessage>>,
@@ -49,6 +77,61 @@ pub struct SharedState {
     pub ipc_healthy: bool,
     pub last_heartbeat: Instant,
     pub missed_heartbeats: u32,
+
+    // Protocol version negotiated with the running daemon (None until its first message)
+    pub daemon_protocol: Option<u32>,
+    // Set after restarting the daemon over a protocol mismatch, so it is only tried once
+    pub protocol_restart_attempted: bool,
+
+    // Liveness pings (sequence number and send time of the outstanding ping)
+    pub ping_seq: u64,
+    pub pending_ping: Option<(u64, Instant)>,
+    pub last_ping_sent: Instant,
+    pub ipc_latency: Option<Duration>,
+
+    // Automatic reconnect after an unexpected daemon exit
+    pub connected_since: Option<Instant>,
+    pub reconnect_attempts: u32,
+    pub reconnect_at: Option<Instant>,
+
+    // Latest active client layout reported by the daemon, with the time it arrived
+    pub client_layout: Option<(Instant, Vec<ClientGeometry>)>,
+
+    // Latest frame reply from the daemon (character, PNG or error), taken by the UI
+    pub captured_frame: Option<(String, Result<Vec<u8>, String>)>,
+
+    // Latest rule preview reply from the daemon (patterns it was computed for, matches)
+    pub window_matches: Option<(RulePatterns, Vec<WindowMatch>)>,
+
+    // Latest hotkey test reply from the daemon (binding tested, outcome), taken by the UI
+    pub hotkey_test: Option<(HotkeyBinding, HotkeyTestResult)>,
+    // Bindings in the config last sent to the daemon, i.e. the keys it holds grabs for
+    pub daemon_hotkeys: Vec<HotkeyBinding>,
+
+    // Wine prefix of each running client, as reported by the daemon
+    pub client_prefixes: HashMap<String, WinePrefixInfo>,
+
+    // When each character was last detected (persisted separately from the config)
+    pub session_history: SessionHistory,
+
+    // Manager-drawn previews used instead of the daemon when Composite is missing
+    pub fallback_previews: Option<FallbackPreviews>,
+
+    // Synthetic input audit log reported by the daemon (oldest first, capped)
+    pub synthetic_input_log: VecDeque<SyntheticInput>,
+
+    // Latest metrics sample from the daemon and the optional Prometheus endpoint serving it
+    pub daemon_metrics: Option<DaemonMetrics>,
+    pub metrics_server: Option<MetricsServer>,
+
+    // X errors reported by the daemon
+    pub x_errors: XErrorTracker,
+
+    // Name under which the next session capture reply is saved
+    pub pending_snapshot: Option<String>,
+
+    // Fleet launch in progress
+    pub fleet_launch: Option<FleetLaunch>,
 }
 
 impl SharedState {
@@ -59,10 +142,28 @@ impl SharedState {
             .position(|p| p.profile_name == config.global.selected_profile)
             .unwrap_or(0);
 
+        // Characters known before the history existed start counting from today
+        let mut session_history = SessionHistory::load();
+        let known = config
+            .profiles
+            .iter()
+            .flat_map(|p| p.character_thumbnails.keys());
+        if session_history.track_new(known, unix_now())
+            && let Err(e) = session_history.save()
+        {
+            warn!(error = ?e, "Failed to save session history");
+        }
+
+        let pending_focus_restore = (config.global.restore_last_focus
+            && !config.global.last_focused_character.is_empty())
+        .then(|| config.global.last_focused_character.clone());
+
         Self {
             config,
             debug_mode,
             daemon: None,
+            stopping_daemon: None,
+            start_after_stop: false,
             daemon_status: DaemonStatus::Stopped,
             last_health_check: Instant::now(),
             status_message: None,
@@ -70,7 +171,14 @@ impl SharedState {
             settings_changed: false,
             selected_profile_idx,
             should_quit: false,
-            last_save_attempt: Instant::now(),
+            tray_available: cfg!(target_os = "linux"),
+            paused: false,
+            pending_announcement: None,
+            pending_focus_restore,
+            startup_actions_sent: false,
+            pending_focus_save: None,
+            pending_position_save: None,
+            pending_position_acks: HashMap::new(),
 
             ipc_config_tx: None,
             ipc_status_rx: None,
@@ -80,10 +188,45 @@ impl SharedState {
             ipc_healthy: false,
             last_heartbeat: Instant::now(),
             missed_heartbeats: 0,
+
+            daemon_protocol: None,
+            protocol_restart_attempted: false,
+
+            ping_seq: 0,
+            pending_ping: None,
+            last_ping_sent: Instant::now(),
+            ipc_latency: None,
+
+            connected_since: None,
+            reconnect_attempts: 0,
+            reconnect_at: None,
+
+            client_layout: None,
+
+            captured_frame: None,
+
+            window_matches: None,
+
+            hotkey_test: None,
+            daemon_hotkeys: Vec::new(),
+
+            client_prefixes: HashMap::new(),
+            session_history,
+
+            fallback_previews: None,
+
+            synthetic_input_log: VecDeque::new(),
+
+            daemon_metrics: None,
+            metrics_server: None,
+
+            x_errors: XErrorTracker::default(),
+            pending_snapshot: None,
+            fleet_launch: None,
         }
     }
 
-    pub fn sync_to_daemon(&self) -> Result<()> {
+    pub fn sync_to_daemon(&mut self) -> Result<()> {
         if let Some(ref tx) = self.ipc_config_tx {
             let selected_profile = self
                 .config
@@ -93,11 +236,12 @@ impl SharedState {
 
             let mut character_thumbnails = selected_profile.character_thumbnails.clone();
             let mut custom_source_thumbnails = selected_profile.custom_source_thumbnails.clone();
+            let mut custom_source_geometry = selected_profile.custom_source_geometry.clone();
 
-            // If "Auto Save" is disabled, we must ensure we sync the LAST SAVED state to the daemon,
-            // not the current transient in-memory state. This ensures that actions like "Refresh"
-            // or "Profile Switch" revert to the saved positions as expected.
-            if !selected_profile.thumbnail_auto_save_position
+            // If "Auto Save" is disabled (or the profile is locked), we must ensure we sync the LAST
+            // SAVED state to the daemon, not the current transient in-memory state. This ensures that
+            // actions like "Refresh" or "Profile Switch" revert to the saved positions as expected.
+            if !selected_profile.persists_positions()
                 && let Ok(disk_config) = crate::config::profile::Config::load()
                 && let Some(disk_profile) = disk_config
                     .profiles
@@ -107,6 +251,7 @@ impl SharedState {
                 info!("Auto-save disabled: Syncing explicit disk positions to daemon");
                 character_thumbnails = disk_profile.character_thumbnails.clone();
                 custom_source_thumbnails = disk_profile.custom_source_thumbnails.clone();
+                custom_source_geometry = disk_profile.custom_source_geometry.clone();
             }
 
             // Filter based on custom rules in profile.
@@ -136,15 +281,27 @@ impl SharedState {
                 profile: selected_profile,
                 character_thumbnails,
                 custom_source_thumbnails,
+                custom_source_geometry,
                 profile_hotkeys,
                 runtime_hidden: false,
+                active_cycle_group: None,
+                run_startup_actions: false,
             };
 
+            let daemon_hotkeys = daemon_config
+                .profile
+                .hotkey_assignments()
+                .into_iter()
+                .map(|(_, binding)| binding.clone())
+                .chain(daemon_config.profile_hotkeys.keys().cloned())
+                .collect();
+
             if let Err(e) = tx.send(ConfigMessage::Full(Box::new(daemon_config))) {
                 error!(error = %e, "Failed to send config update to daemon");
                 return Err(anyhow::anyhow!("Failed to send config to daemon: {}", e));
             } else {
                 debug!("Sent config update to daemon");
+                self.daemon_hotkeys = daemon_hotkeys;
             }
         }
         Ok(())
@@ -154,28 +311,9 @@ impl SharedState {
         // Prepare config for saving
         // If mode is IMPLICIT (e.g. on exit or settings change),
         // we must ensure we don't accidentally persist transient window movements for profiles
-        // that have "Auto Save Positions" disabled.
+        // that have "Auto Save Positions" disabled. Locked profiles are never written.
         let mut config_to_save = self.config.clone();
-
-        if mode == SaveMode::Implicit {
-            // Restore last explicitly saved positions from disk to prevent persistence of transient moves.
-            if let Ok(disk_config) = crate::config::profile::Config::load() {
-                for profile in config_to_save.profiles.iter_mut() {
-                    if !profile.thumbnail_auto_save_position
-                        && let Some(disk_profile) = disk_config
-                            .profiles
-                            .iter()
-                            .find(|p| p.profile_name == profile.profile_name)
-                    {
-                        profile.character_thumbnails = disk_profile.character_thumbnails.clone();
-                        profile.custom_source_thumbnails =
-                            disk_profile.custom_source_thumbnails.clone();
-                    }
-                }
-            } else {
-                warn!("Failed to load disk config for position revert - saving current state");
-            }
-        }
+        revert_unsaved(&mut config_to_save, mode);
 
         // Write current state to disk - Manager maintains authoritative state via IPC synchronization
         config_to_save.save()?;
@@ -194,6 +332,7 @@ impl SharedState {
             .unwrap_or(0);
 
         self.settings_changed = false;
+        self.sync_metrics_server();
         self.config_status_message = Some(StatusMessage {
             text: "Configuration saved successfully".to_string(),
             color: COLOR_SUCCESS,
@@ -206,25 +345,7 @@ impl SharedState {
     /// Used when the Daemon already knows about the change (e.g., it initiated the PositionChanged event)
     pub fn save_config_no_sync(&mut self, mode: SaveMode) -> Result<()> {
         let mut config_to_save = self.config.clone();
-
-        if mode == SaveMode::Implicit {
-            if let Ok(disk_config) = crate::config::profile::Config::load() {
-                for profile in config_to_save.profiles.iter_mut() {
-                    if !profile.thumbnail_auto_save_position
-                        && let Some(disk_profile) = disk_config
-                            .profiles
-                            .iter()
-                            .find(|p| p.profile_name == profile.profile_name)
-                    {
-                        profile.character_thumbnails = disk_profile.character_thumbnails.clone();
-                        profile.custom_source_thumbnails =
-                            disk_profile.custom_source_thumbnails.clone();
-                    }
-                }
-            } else {
-                warn!("Failed to load disk config for position revert - saving current state");
-            }
-        }
+        revert_unsaved(&mut config_to_save, mode);
 
         config_to_save.save()?;
 
@@ -262,6 +383,12 @@ impl SharedState {
                 });
             } else {
                 // Reload daemon with new profile
+                self.pending_announcement = Some(format!(
+                    "Profile: {}",
+                    self.config.profiles[idx].profile_name
+                ));
+                // The new profile's startup actions run on the restarted daemon
+                self.startup_actions_sent = false;
                 self.reload_daemon_config();
             }
         }
@@ -290,17 +417,65 @@ impl SharedState {
         self.save_config(SaveMode::Explicit)
             .context("Failed to save configuration")?;
 
-        self.config_status_message = Some(StatusMessage {
-            text: "Thumbnail positions saved".to_string(),
-            color: STATUS_RUNNING,
+        let locked = self
+            .config
+            .get_active_profile()
+            .is_some_and(|p| p.profile_locked);
+        self.config_status_message = Some(if locked {
+            StatusMessage {
+                text: "Profile is locked - positions not saved".to_string(),
+                color: COLOR_WARNING,
+            }
+        } else {
+            StatusMessage {
+                text: "Thumbnail positions saved".to_string(),
+                color: STATUS_RUNNING,
+            }
         });
         Ok(())
     }
 }
 
+/// Puts back what must not be written before `config` is saved: profiles locked on disk stay
+/// exactly as saved, and implicit saves keep the last saved positions of profiles without
+/// auto-save.
+fn revert_unsaved(config: &mut Config, mode: SaveMode) {
+    if mode == SaveMode::Explicit && !config.profiles.iter().any(|p| p.profile_locked) {
+        return;
+    }
+    match Config::load() {
+        Ok(disk_config) => revert_to_disk(config, &disk_config, mode),
+        Err(e) => {
+            warn!(error = ?e, "Failed to load disk config for position revert - saving current state")
+        }
+    }
+}
+
+fn revert_to_disk(config: &mut Config, disk_config: &Config, mode: SaveMode) {
+    for profile in config.profiles.iter_mut() {
+        let Some(disk_profile) = disk_config
+            .profiles
+            .iter()
+            .find(|p| p.profile_name == profile.profile_name)
+        else {
+            continue;
+        };
+
+        // Unlocking is the one change a locked profile accepts
+        if profile.profile_locked && disk_profile.profile_locked {
+            *profile = disk_profile.clone();
+        } else if mode == SaveMode::Implicit && !profile.thumbnail_auto_save_position {
+            // Restore last explicitly saved positions to prevent persistence of transient moves
+            profile.character_thumbnails = disk_profile.character_thumbnails.clone();
+            profile.custom_source_thumbnails = disk_profile.custom_source_thumbnails.clone();
+            profile.custom_source_geometry = disk_profile.custom_source_geometry.clone();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::SharedState;
+    use super::{DaemonStatus, SharedState};
     use crate::config::profile::{Config, Profile};
 
     #[test]
@@ -352,6 +527,7 @@ mod tests {
         // Inject a channel to simulate daemon messages
         let (tx, rx) = mpsc::channel();
         state.daemon_status_rx = Some(rx);
+        state.daemon_protocol = Some(crate::common::ipc::PROTOCOL_VERSION);
 
         // Send a heartbeat
         tx.send(DaemonMessage::Heartbeat).unwrap();
@@ -373,4 +549,337 @@ mod tests {
             "Heartbeat should update timestamp"
         );
     }
+
+    #[test]
+    fn test_client_layout_processing() {
+        use crate::common::ipc::{ClientGeometry, DaemonMessage};
+        use std::sync::mpsc;
+
+        let config = Config::default();
+        let mut state = SharedState::new(config, false);
+        assert!(state.client_layout.is_none());
+
+        let (tx, rx) = mpsc::channel();
+        state.daemon_status_rx = Some(rx);
+        state.daemon_protocol = Some(crate::common::ipc::PROTOCOL_VERSION);
+
+        let layout = vec![ClientGeometry {
+            name: "Pilot".to_string(),
+            is_custom: false,
+            x: 10,
+            y: 20,
+            width: 480,
+            height: 270,
+        }];
+        // Prefixes of clients missing from the layout are pruned
+        for name in ["Pilot", "Gone"] {
+            state
+                .client_prefixes
+                .insert(name.to_string(), Default::default());
+        }
+        tx.send(DaemonMessage::ClientLayout(layout.clone()))
+            .unwrap();
+
+        state.poll_daemon();
+
+        let (_, received) = state
+            .client_layout
+            .as_ref()
+            .expect("ClientLayout should be stored");
+        assert_eq!(received, &layout);
+        assert!(state.client_prefixes.contains_key("Pilot"));
+        assert!(!state.client_prefixes.contains_key("Gone"));
+    }
+
+    /// Completes a handshake with a daemon announcing `hello`; true if it was asked to run the
+    /// startup actions
+    fn handshake(state: &mut SharedState, hello: crate::common::ipc::ProtocolHello) -> bool {
+        use crate::common::ipc::{ConfigMessage, DaemonMessage};
+
+        let (tx, rx) = ipc_channel::ipc::channel().unwrap();
+        state.ipc_config_tx = Some(tx);
+        let (status_tx, status_rx) = std::sync::mpsc::channel();
+        state.daemon_status_rx = Some(status_rx);
+        state.daemon_protocol = None;
+
+        status_tx.send(DaemonMessage::Hello(hello)).unwrap();
+        state.poll_daemon();
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .any(|msg| matches!(msg, ConfigMessage::RunStartupActions))
+    }
+
+    #[test]
+    fn test_startup_actions_only_for_first_daemon() {
+        use crate::common::ipc::ProtocolHello;
+
+        let mut state = SharedState::new(Config::default(), false);
+        assert!(handshake(&mut state, ProtocolHello::current()));
+        // A daemon restarted within the session
+        assert!(!handshake(&mut state, ProtocolHello::current()));
+    }
+
+    #[test]
+    fn test_older_compatible_daemon_is_accepted() {
+        use crate::common::ipc::{MIN_PROTOCOL_VERSION, ProtocolHello};
+
+        let mut state = SharedState::new(Config::default(), false);
+        let older = ProtocolHello {
+            protocol_version: MIN_PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            app_version: "older".to_string(),
+        };
+        // It runs the startup actions on its own and doesn't know the message
+        assert!(!handshake(&mut state, older));
+        assert_eq!(state.daemon_protocol, Some(MIN_PROTOCOL_VERSION));
+        assert!(state.daemon_connected());
+    }
+
+    #[test]
+    fn test_pause_supported() {
+        let mut state = SharedState::new(Config::default(), false);
+        // Applied once a daemon connects
+        assert!(state.pause_supported());
+        assert!(!state.daemon_connected());
+        state.daemon_protocol = Some(crate::common::ipc::PROTOCOL_VERSION);
+        assert!(state.pause_supported());
+        assert!(state.daemon_connected());
+    }
+
+    #[test]
+    fn test_legacy_daemon_is_rejected() {
+        use crate::common::ipc::DaemonMessage;
+        use std::sync::mpsc;
+
+        let config = Config::default();
+        let mut state = SharedState::new(config, false);
+        // Skip the restart attempt so the test doesn't spawn a daemon
+        state.protocol_restart_attempted = true;
+
+        let (tx, rx) = mpsc::channel();
+        state.daemon_status_rx = Some(rx);
+
+        // A daemon from before the handshake starts straight away with heartbeats
+        tx.send(DaemonMessage::Heartbeat).unwrap();
+        state.poll_daemon();
+
+        assert_eq!(state.daemon_protocol, None);
+        assert!(!state.request_client_layout());
+        assert!(
+            state
+                .status_message
+                .as_ref()
+                .is_some_and(|s| s.text.contains("incompatible"))
+        );
+    }
+
+    #[test]
+    fn test_hello_negotiates_current_protocol() {
+        use crate::common::ipc::{DaemonMessage, PROTOCOL_VERSION, ProtocolHello};
+        use std::sync::mpsc;
+
+        let config = Config::default();
+        let mut state = SharedState::new(config, false);
+
+        let (tx, rx) = mpsc::channel();
+        state.daemon_status_rx = Some(rx);
+
+        tx.send(DaemonMessage::Hello(ProtocolHello::current()))
+            .unwrap();
+        state.poll_daemon();
+
+        assert_eq!(state.daemon_protocol, Some(PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn test_pong_updates_latency() {
+        use crate::common::ipc::{DaemonMessage, ProtocolHello};
+        use std::sync::mpsc;
+        use std::time::{Duration, Instant};
+
+        let config = Config::default();
+        let mut state = SharedState::new(config, false);
+
+        let (tx, rx) = mpsc::channel();
+        state.daemon_status_rx = Some(rx);
+        tx.send(DaemonMessage::Hello(ProtocolHello::current()))
+            .unwrap();
+        state.poll_daemon();
+
+        state.pending_ping = Some((7, Instant::now() - Duration::from_millis(20)));
+        state.last_heartbeat = Instant::now() - Duration::from_secs(10);
+
+        // A pong for an older ping is ignored
+        tx.send(DaemonMessage::Pong(6)).unwrap();
+        state.poll_daemon();
+        assert!(state.ipc_latency.is_none());
+
+        tx.send(DaemonMessage::Pong(7)).unwrap();
+        state.poll_daemon();
+        assert!(state.ipc_latency.unwrap() >= Duration::from_millis(20));
+        assert!(state.pending_ping.is_none());
+        assert!(state.last_heartbeat.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_reconnect_delay_backs_off() {
+        use super::super::daemon::reconnect_delay;
+        use crate::common::constants::manager_ui::*;
+        use std::time::Duration;
+
+        assert_eq!(
+            reconnect_delay(1),
+            Duration::from_millis(RECONNECT_BASE_DELAY_MS)
+        );
+        assert_eq!(
+            reconnect_delay(2),
+            Duration::from_millis(RECONNECT_BASE_DELAY_MS * 2)
+        );
+        assert_eq!(
+            reconnect_delay(30),
+            Duration::from_millis(RECONNECT_MAX_DELAY_MS)
+        );
+    }
+
+    #[test]
+    fn test_schedule_reconnect_gives_up() {
+        use crate::common::constants::manager_ui::RECONNECT_MAX_ATTEMPTS;
+
+        let config = Config::default();
+        let mut state = SharedState::new(config, false);
+
+        for _ in 0..RECONNECT_MAX_ATTEMPTS {
+            state.schedule_reconnect();
+            assert!(state.reconnect_at.is_some());
+        }
+
+        state.schedule_reconnect();
+        assert!(state.reconnect_at.is_none());
+        assert!(state.status_message.is_some());
+    }
+
+    #[test]
+    fn test_stop_daemon_does_not_block() {
+        let mut state = SharedState::new(Config::default(), false);
+        state.daemon = Some(
+            std::process::Command::new("sleep")
+                .arg("5")
+                .spawn()
+                .unwrap(),
+        );
+
+        state.stop_daemon().unwrap();
+        assert!(state.daemon.is_none());
+        assert!(state.stopping_daemon.is_some());
+        assert_eq!(state.daemon_status, DaemonStatus::Stopping);
+
+        // A restart waits for the old daemon instead of spawning next to it
+        state.start_daemon().unwrap();
+        assert!(state.daemon.is_none());
+        assert!(state.start_after_stop);
+
+        state.stop_daemon().unwrap();
+        assert!(!state.start_after_stop);
+        state.reap_stopping_daemon(true);
+        assert!(state.stopping_daemon.is_none());
+        assert_eq!(state.daemon_status, DaemonStatus::Crashed(None));
+    }
+
+    #[test]
+    fn test_position_changes_are_debounced() {
+        use crate::common::ipc::DaemonMessage;
+        use std::sync::mpsc;
+
+        let mut config = Config::default();
+        config.profiles[0].thumbnail_auto_save_position = true;
+        let mut state = SharedState::new(config, false);
+
+        let (tx, rx) = mpsc::channel();
+        state.daemon_status_rx = Some(rx);
+        state.daemon_protocol = Some(crate::common::ipc::PROTOCOL_VERSION);
+
+        // A burst of updates for the same character collapses into one pending save
+        for x in 0..5 {
+            tx.send(DaemonMessage::PositionChanged {
+                name: "Pilot".to_string(),
+                x,
+                y: 0,
+                width: 200,
+                height: 100,
+                is_custom: false,
+            })
+            .unwrap();
+        }
+        state.poll_daemon();
+
+        assert!(state.pending_position_save.is_some());
+        assert_eq!(state.pending_position_acks.len(), 1);
+        assert_eq!(
+            state.pending_position_acks[&("Pilot".to_string(), false)],
+            (4, 0, 200, 100)
+        );
+    }
+
+    #[test]
+    fn test_focus_changes_are_remembered() {
+        use crate::common::ipc::DaemonMessage;
+        use std::sync::mpsc;
+
+        let mut config = Config::default();
+        config.global.last_focused_character = "Pilot".to_string();
+        let mut state = SharedState::new(config, false);
+        assert_eq!(state.pending_focus_restore.as_deref(), Some("Pilot"));
+
+        let (tx, rx) = mpsc::channel();
+        state.daemon_status_rx = Some(rx);
+        state.daemon_protocol = Some(crate::common::ipc::PROTOCOL_VERSION);
+
+        // Refocusing the remembered character doesn't schedule a write
+        tx.send(DaemonMessage::FocusChanged("Pilot".to_string()))
+            .unwrap();
+        state.poll_daemon();
+        assert!(state.pending_focus_save.is_none());
+
+        tx.send(DaemonMessage::FocusChanged("Scout".to_string()))
+            .unwrap();
+        state.poll_daemon();
+        assert_eq!(state.config.global.last_focused_character, "Scout");
+        assert!(state.pending_focus_save.is_some());
+
+        let mut config = Config::default();
+        config.global.restore_last_focus = false;
+        config.global.last_focused_character = "Pilot".to_string();
+        assert!(
+            SharedState::new(config, false)
+                .pending_focus_restore
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_locked_profiles_keep_disk_state() {
+        use super::{SaveMode, revert_to_disk};
+
+        let mut disk = Config::default();
+        disk.profiles[0].profile_locked = true;
+        disk.profiles[0].thumbnail_auto_save_position = true;
+
+        // Edits and moves in a locked profile are dropped, even by explicit saves
+        let mut config = disk.clone();
+        config.profiles[0].thumbnail_opacity = 10;
+        config.profiles[0].update_thumbnail_position("Pilot", 5, 5, 200, 100, false);
+        revert_to_disk(&mut config, &disk, SaveMode::Explicit);
+        assert_eq!(
+            config.profiles[0].thumbnail_opacity,
+            disk.profiles[0].thumbnail_opacity
+        );
+        assert!(config.profiles[0].character_thumbnails.is_empty());
+
+        // Unlocking is written together with the changes made after it
+        let mut config = disk.clone();
+        config.profiles[0].profile_locked = false;
+        config.profiles[0].thumbnail_opacity = 10;
+        revert_to_disk(&mut config, &disk, SaveMode::Implicit);
+        assert!(!config.profiles[0].profile_locked);
+        assert_eq!(config.profiles[0].thumbnail_opacity, 10);
+    }
 }
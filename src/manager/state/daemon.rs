@@ -5,17 +5,41 @@ use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
 use crate::common::constants::manager_ui::*;
-use crate::common::ipc::{BootstrapMessage, ConfigMessage, DaemonMessage};
+use crate::common::ipc::{
+    BootstrapMessage, ConfigMessage, DaemonMessage, MIN_PROTOCOL_VERSION, ProtocolHello,
+    RulePatterns, STARTUP_ACTIONS_PROTOCOL_VERSION, SyntheticInput,
+};
+use ipc_channel::ipc::IpcError;
 
 use super::core::SaveMode;
+use crate::config::HotkeyBinding;
+use crate::config::profile::{LayoutSlot, SessionSnapshot, SourceGeometry};
+use crate::config::session::unix_now;
+use crate::manager::fallback::{self, FallbackPreviews};
+use crate::manager::fleet_launch::{self, FleetLaunch};
+use crate::manager::metrics_server::MetricsServer;
 use crate::manager::utils::spawn_daemon;
 
-use super::DaemonStatus;
 use super::SharedState;
+use super::{ConnectionState, DaemonStatus};
 
 impl SharedState {
     pub fn start_daemon(&mut self) -> Result<()> {
-        if self.daemon.is_some() {
+        if self.daemon.is_some() || self.fallback_previews.is_some() {
+            return Ok(());
+        }
+        // Two daemons would fight over hotkey grabs and window markers
+        if self.stopping_daemon.is_some() {
+            self.start_after_stop = true;
+            return Ok(());
+        }
+
+        // The daemon can't render thumbnails without Composite; show static previews instead
+        if !fallback::composite_available() {
+            warn!("X server has no Composite extension, using fallback previews");
+            let profile = &self.config.profiles[self.selected_profile_idx];
+            self.fallback_previews = Some(FallbackPreviews::start(profile)?);
+            self.daemon_status = DaemonStatus::Fallback;
             return Ok(());
         }
 
@@ -51,37 +75,106 @@ impl SharedState {
     }
 
     pub fn stop_daemon(&mut self) -> Result<()> {
-        if let Some(mut child) = self.daemon.take() {
+        // Don't lose positions or the focused character still waiting for the debounce
+        self.flush_position_save();
+        self.flush_focus_save();
+
+        // Stopping again (e.g. on exit) cancels a restart that was waiting for the old daemon
+        self.start_after_stop = false;
+
+        if let Some(child) = self.daemon.take() {
             info!(pid = child.id(), "Stopping daemon process");
 
-            if let Err(e) = child.kill() {
-                error!(pid = child.id(), error = %e, "Failed to send SIGKILL to daemon");
+            // SIGTERM first so the daemon can clear its window markers; poll_daemon reaps it
+            if send_sigterm(&child) {
+                let deadline = Instant::now() + Duration::from_millis(DAEMON_STOP_TIMEOUT_MS);
+                self.stopping_daemon = Some((child, deadline));
+                self.daemon_status = DaemonStatus::Stopping;
             } else {
-                debug!(pid = child.id(), "SIGKILL sent successfully");
-            }
-
-            match child.wait() {
-                Ok(status) => {
-                    info!(pid = child.id(), status = ?status, "Daemon exited");
-                    self.daemon_status = if status.success() {
-                        DaemonStatus::Stopped
-                    } else {
-                        DaemonStatus::Crashed(status.code())
-                    };
-                }
-                Err(e) => {
-                    error!(pid = child.id(), error = %e, "Failed to wait for daemon exit");
-                    self.daemon_status = DaemonStatus::Crashed(None);
-                }
+                self.kill_daemon(child);
             }
             // Clear IPC channels immediately to prevent "Broken pipe" errors if save_config is called (e.g. on exit)
             self.ipc_config_tx = None;
             self.ipc_status_rx = None;
             self.daemon_status_rx = None;
+            self.daemon_protocol = None;
+            self.client_layout = None;
+            self.client_prefixes.clear();
+            self.daemon_hotkeys.clear();
+            self.reset_link_state();
         }
+        if self.fallback_previews.take().is_some() {
+            self.daemon_status = DaemonStatus::Stopped;
+        }
+        // An explicit stop cancels any pending automatic reconnect
+        self.reconnect_at = None;
         Ok(())
     }
 
+    /// Reaps a daemon stopped with SIGTERM, killing it once its deadline has passed.
+    /// With `block`, waits for the deadline instead of returning while it is still running.
+    pub fn reap_stopping_daemon(&mut self, block: bool) {
+        let Some((mut child, deadline)) = self.stopping_daemon.take() else {
+            return;
+        };
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    debug!(pid = child.id(), "Daemon exited after SIGTERM");
+                    self.record_daemon_exit(child.id(), status);
+                    break;
+                }
+                Ok(None) if Instant::now() < deadline => {
+                    if !block {
+                        self.stopping_daemon = Some((child, deadline));
+                        return;
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Ok(None) => {
+                    self.kill_daemon(child);
+                    break;
+                }
+                Err(e) => {
+                    error!(pid = child.id(), error = %e, "Failed to query stopping daemon");
+                    self.kill_daemon(child);
+                    break;
+                }
+            }
+        }
+
+        if std::mem::take(&mut self.start_after_stop)
+            && let Err(err) = self.start_daemon()
+        {
+            error!(error = ?err, "Failed to restart daemon");
+        }
+    }
+
+    /// SIGKILLs the daemon and waits for it (a killed process exits without delay)
+    fn kill_daemon(&mut self, mut child: std::process::Child) {
+        if let Err(e) = child.kill() {
+            error!(pid = child.id(), error = %e, "Failed to send SIGKILL to daemon");
+        } else {
+            debug!(pid = child.id(), "SIGKILL sent successfully");
+        }
+        match child.wait() {
+            Ok(status) => self.record_daemon_exit(child.id(), status),
+            Err(e) => {
+                error!(pid = child.id(), error = %e, "Failed to wait for daemon exit");
+                self.daemon_status = DaemonStatus::Crashed(None);
+            }
+        }
+    }
+
+    fn record_daemon_exit(&mut self, pid: u32, status: std::process::ExitStatus) {
+        info!(pid, status = ?status, "Daemon exited");
+        self.daemon_status = if status.success() {
+            DaemonStatus::Stopped
+        } else {
+            DaemonStatus::Crashed(status.code())
+        };
+    }
+
     pub fn restart_daemon(&mut self) {
         info!("Restart requested");
         if let Err(err) = self.stop_daemon().and_then(|_| self.start_daemon()) {
@@ -98,7 +191,252 @@ impl SharedState {
         self.restart_daemon();
     }
 
+    /// True once the running daemon has completed the protocol handshake
+    pub fn daemon_connected(&self) -> bool {
+        self.daemon_protocol.is_some()
+    }
+
+    /// Appends audit entries to the synthetic input log, dropping the oldest past the limit
+    pub fn record_synthetic_input(&mut self, entries: Vec<SyntheticInput>) {
+        self.synthetic_input_log.extend(entries);
+        let excess = self
+            .synthetic_input_log
+            .len()
+            .saturating_sub(SYNTHETIC_INPUT_LOG_LIMIT);
+        self.synthetic_input_log.drain(..excess);
+    }
+
+    /// Starts, moves or stops the Prometheus endpoint to match the configured port (on startup
+    /// and after each save)
+    pub fn sync_metrics_server(&mut self) {
+        let port = self.config.global.metrics_port;
+        let running = self.metrics_server.as_ref().map(|s| s.addr().port());
+        if running == Some(port) || (port == 0 && running.is_none()) {
+            return;
+        }
+
+        self.metrics_server = None;
+        if port == 0 {
+            info!("Metrics endpoint disabled");
+            return;
+        }
+        match MetricsServer::start(port) {
+            Ok(server) => {
+                if let Some(metrics) = &self.daemon_metrics {
+                    server.publish(metrics);
+                }
+                self.metrics_server = Some(server);
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to start metrics endpoint");
+                self.status_message = Some(super::types::StatusMessage {
+                    text: format!("Metrics endpoint failed: {e}"),
+                    color: COLOR_ERROR,
+                });
+            }
+        }
+    }
+
+    /// Asks the daemon for a fresh snapshot of active clients and their thumbnail geometry.
+    ///
+    /// The reply arrives asynchronously and is stored in `client_layout` by `poll_daemon`.
+    /// Returns false when no daemon is connected.
+    pub fn request_client_layout(&self) -> bool {
+        self.send_to_daemon(ConfigMessage::RequestClientLayout, "client layout request")
+    }
+
+    /// Asks the daemon for the current frame of a running character as PNG.
+    ///
+    /// The reply arrives asynchronously and is stored in `captured_frame` by `poll_daemon`.
+    /// Returns false when no daemon is connected.
+    pub fn request_frame(&self, character: &str) -> bool {
+        let request = ConfigMessage::CaptureFrame {
+            character: character.to_string(),
+        };
+        self.send_to_daemon(request, "frame request")
+    }
+
+    /// Asks the daemon to listen for one press of `binding` with its hotkey backend.
+    ///
+    /// The reply arrives asynchronously and is stored in `hotkey_test` by `poll_daemon`.
+    /// Returns false when no daemon is connected.
+    pub fn request_hotkey_test(&self, binding: &HotkeyBinding) -> bool {
+        self.send_to_daemon(ConfigMessage::TestHotkey(binding.clone()), "hotkey test")
+    }
+
+    /// Shows or hides all previews in the running daemon. Returns false when no daemon is
+    /// connected.
+    pub fn request_toggle_previews(&self) -> bool {
+        self.send_to_daemon(ConfigMessage::TogglePreviews, "preview toggle")
+    }
+
+    /// Sends a request to the connected daemon. Returns false when no daemon is connected or
+    /// the send failed (logged with `what`).
+    fn send_to_daemon(&self, msg: ConfigMessage, what: &str) -> bool {
+        if !self.daemon_connected() {
+            return false;
+        }
+        let Some(ref tx) = self.ipc_config_tx else {
+            return false;
+        };
+        match tx.send(msg) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(error = %e, "Failed to send {} to daemon", what);
+                false
+            }
+        }
+    }
+
+    /// False when the previews can't be paused because the Manager draws fallback previews.
+    /// With no daemon yet, the choice is applied on connect.
+    pub fn pause_supported(&self) -> bool {
+        self.fallback_previews.is_none()
+    }
+
+    /// Pauses or resumes the daemon; the choice is re-sent whenever the daemon (re)connects
+    pub fn set_paused(&mut self, paused: bool) {
+        info!(paused = paused, "Pause requested");
+        self.paused = paused;
+        self.send_paused();
+    }
+
+    fn send_paused(&self) {
+        if !self.daemon_connected() {
+            return;
+        }
+        if let Some(ref tx) = self.ipc_config_tx
+            && let Err(e) = tx.send(ConfigMessage::SetPaused(self.paused))
+        {
+            warn!(error = %e, "Failed to send pause state to daemon");
+        }
+    }
+
+    /// Asks the daemon for the state of every running client, saved as snapshot `name` in the
+    /// active profile once the reply arrives. Returns false when no daemon is connected.
+    pub fn request_session_snapshot(&mut self, name: &str) -> bool {
+        let sent = self.send_to_daemon(ConfigMessage::CaptureSession, "session capture request");
+        if sent {
+            self.pending_snapshot = Some(name.to_string());
+        }
+        sent
+    }
+
+    /// Asks the daemon to put clients and thumbnails back as saved in snapshot `idx` of the
+    /// active profile. Returns false when no daemon is connected.
+    pub fn request_session_restore(&self, idx: usize) -> bool {
+        if !self.daemon_connected() {
+            return false;
+        }
+        let Some(snapshot) = self
+            .config
+            .get_active_profile()
+            .and_then(|p| p.session_snapshots.get(idx))
+        else {
+            return false;
+        };
+        let Some(ref tx) = self.ipc_config_tx else {
+            return false;
+        };
+        info!(snapshot = %snapshot.name, "Restoring session snapshot");
+        match tx.send(ConfigMessage::RestoreSession {
+            clients: snapshot.clients.clone(),
+            focused: snapshot.focused.clone(),
+        }) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(error = %e, "Failed to send session snapshot to daemon");
+                false
+            }
+        }
+    }
+
+    /// Starts the active profile's launch order, skipping characters the daemon reports as
+    /// running. Progress is shown in `status_message`.
+    pub fn start_fleet_launch(&mut self) {
+        let Some(profile) = self.config.get_active_profile() else {
+            return;
+        };
+        let entries = profile.launch_order.clone();
+        let layout_requested = self.request_client_layout();
+        let launch = FleetLaunch::new(&entries, layout_requested, Instant::now());
+        info!(clients = entries.len(), "Fleet launch started");
+        self.fleet_launch = Some(launch);
+        self.poll_fleet_launch();
+    }
+
+    pub fn cancel_fleet_launch(&mut self) {
+        if self.fleet_launch.take().is_some() {
+            info!("Fleet launch cancelled");
+            self.status_message = Some(super::types::StatusMessage {
+                text: "Fleet launch cancelled".to_string(),
+                color: COLOR_WARNING,
+            });
+        }
+    }
+
+    /// Launches the next client when it is due and updates the progress message
+    fn poll_fleet_launch(&mut self) {
+        let Some(launch) = self.fleet_launch.as_mut() else {
+            return;
+        };
+        let mut color = COLOR_WARNING;
+        if let Some(entry) = launch.poll(Instant::now())
+            && let Err(e) = fleet_launch::spawn(&entry)
+        {
+            warn!(error = ?e, "Fleet launch command failed");
+            // Nothing will log in; move on right away
+            launch.character_appeared(&entry.character, Instant::now());
+            color = COLOR_ERROR;
+        }
+        let text = launch.status();
+        if launch.is_done() {
+            info!("Fleet launch finished");
+            self.fleet_launch = None;
+            color = COLOR_SUCCESS;
+        }
+        if self.status_message.as_ref().is_none_or(|m| m.text != text) {
+            self.status_message = Some(super::types::StatusMessage { text, color });
+        }
+    }
+
+    /// Asks the daemon which windows a custom rule with these patterns would match.
+    ///
+    /// The reply arrives asynchronously and is stored in `window_matches` by `poll_daemon`.
+    /// Returns false when no daemon is connected.
+    pub fn request_window_matches(&self, patterns: &RulePatterns) -> bool {
+        let request = ConfigMessage::MatchWindows {
+            title_pattern: patterns.0.clone(),
+            class_pattern: patterns.1.clone(),
+        };
+        self.send_to_daemon(request, "window match request")
+    }
+
     pub fn poll_daemon(&mut self) {
+        // Fallback previews activate clients from this process, so its own audit entries count too
+        let local_input = crate::x11::input_guard::drain_audit();
+        if !local_input.is_empty() {
+            self.record_synthetic_input(local_input);
+        }
+
+        self.reap_stopping_daemon(false);
+
+        // 0. Automatic reconnect after an unexpected exit
+        if self.daemon.is_none()
+            && let Some(at) = self.reconnect_at
+            && Instant::now() >= at
+        {
+            self.reconnect_at = None;
+            info!(
+                attempt = self.reconnect_attempts,
+                "Reconnecting to daemon after unexpected exit"
+            );
+            if let Err(err) = self.start_daemon() {
+                error!(error = ?err, "Failed to restart daemon");
+                self.schedule_reconnect();
+            }
+        }
+
         // 1. Check for Bootstrap handshake
         if let Some(ref rx) = self.bootstrap_rx
             && let Ok(msg) = rx.try_recv()
@@ -112,18 +450,36 @@ impl SharedState {
             self.daemon_status_rx = Some(manager_rx);
 
             std::thread::spawn(move || {
-                while let Ok(msg) = status_rx.recv() {
-                    if manager_tx.send(msg).is_err() {
-                        break; // Manager dropped
+                loop {
+                    match status_rx.recv() {
+                        Ok(msg) => {
+                            if manager_tx.send(msg).is_err() {
+                                break; // Manager dropped
+                            }
+                        }
+                        // A message this build cannot decode (newer daemon); skip it rather than stalling
+                        Err(IpcError::Bincode(e)) => {
+                            warn!(error = %e, "Dropping undecodable message from daemon (protocol mismatch?)");
+                        }
+                        Err(_) => break,
                     }
                 }
             });
 
+            // The daemon only accepts the config after our hello
+            if let Some(ref tx) = self.ipc_config_tx
+                && let Err(e) = tx.send(ConfigMessage::Hello(ProtocolHello::current()))
+            {
+                warn!(error = %e, "Failed to send protocol hello to daemon");
+            }
+
             // Sync config to daemon
             let _ = self.sync_to_daemon();
 
             self.bootstrap_rx = None; // Done
             self.daemon_status = DaemonStatus::Running;
+            self.daemon_protocol = None; // Negotiated from the daemon's first message
+            self.connected_since = Some(Instant::now());
 
             // initialize heartbeats
             self.ipc_healthy = true;
@@ -145,8 +501,50 @@ impl SharedState {
             Vec::new()
         };
 
+        let mut protocol_mismatch = None;
+
         for msg in messages {
+            // Daemons from before the handshake never send a hello
+            if self.daemon_protocol.is_none() && !matches!(msg, DaemonMessage::Hello(_)) {
+                warn!("Daemon did not announce a protocol version");
+                protocol_mismatch = Some(ProtocolHello::legacy());
+                break;
+            }
+
             match msg {
+                DaemonMessage::Hello(hello) => match ProtocolHello::current().negotiate(&hello) {
+                    Some(version) => {
+                        info!(
+                            daemon_version = %hello.app_version,
+                            daemon_protocol = hello.protocol_version,
+                            negotiated = version,
+                            "Protocol handshake complete"
+                        );
+                        self.daemon_protocol = Some(version);
+                        self.protocol_restart_attempted = false;
+                        // Daemons before `RunStartupActions` run them on every start
+                        if !self.startup_actions_sent {
+                            self.startup_actions_sent = true;
+                            if version >= STARTUP_ACTIONS_PROTOCOL_VERSION
+                                && let Some(ref tx) = self.ipc_config_tx
+                                && let Err(e) = tx.send(ConfigMessage::RunStartupActions)
+                            {
+                                warn!(error = %e, "Failed to ask the daemon to run startup actions");
+                            }
+                        }
+                        if self.paused {
+                            self.send_paused();
+                        }
+                        if let Some(text) = self.pending_announcement.take()
+                            && let Some(ref tx) = self.ipc_config_tx
+                            && let Err(e) = tx.send(ConfigMessage::Announce(text))
+                        {
+                            warn!(error = %e, "Failed to send announcement to daemon");
+                        }
+                        self.restore_focus();
+                    }
+                    None => protocol_mismatch = Some(hello),
+                },
                 DaemonMessage::Log { level, message } => {
                     info!(level = %level, "Daemon: {}", message);
                 }
@@ -178,40 +576,52 @@ impl SharedState {
                         continue;
                     }
 
-                    let auto_save = self
+                    let auto_save_delay = self
                         .config
                         .get_active_profile()
-                        .map(|p| p.thumbnail_auto_save_position)
-                        .unwrap_or(false);
-
-                    debug!("Position changed: auto_save={}", auto_save);
+                        .filter(|p| p.persists_positions())
+                        .map(|p| p.timing_auto_save_delay_ms);
 
-                    if auto_save {
-                        // Debounce save: only write to disk if it's been at least 1 second since last attempt
-                        if self.last_save_attempt.elapsed()
-                            > Duration::from_millis(AUTO_SAVE_DELAY_MS)
-                        {
-                            // Save to disk only (Daemon already has the correct position)
-                            let _ = self.save_config_no_sync(SaveMode::Explicit);
-
-                            // Send lightweight delta to confirm the position
-                            // Daemon will perform idempotency check and skip redundant X11 operations
-                            if let Some(ref tx) = self.ipc_config_tx {
-                                let _ = tx.send(ConfigMessage::ThumbnailMove {
-                                    name: name.clone(),
-                                    is_custom,
-                                    x,
-                                    y,
-                                    width,
-                                    height,
-                                });
-                            }
+                    debug!("Position changed: auto_save={}", auto_save_delay.is_some());
 
-                            self.last_save_attempt = Instant::now();
-                            debug!("Debounced auto-save triggered with ThumbnailMove delta");
-                        } else {
-                            self.settings_changed = true; // Mark as dirty for final save
-                        }
+                    if let Some(delay_ms) = auto_save_delay {
+                        // Trailing debounce: the save (and the confirming ThumbnailMove deltas) happen
+                        // once positions have stopped changing for the profile's auto-save delay
+                        self.pending_position_acks
+                            .insert((name, is_custom), (x, y, width, height));
+                        self.pending_position_save =
+                            Some(Instant::now() + Duration::from_millis(delay_ms.into()));
+                    }
+                }
+                DaemonMessage::SourceGeometryChanged {
+                    rule_id,
+                    instance,
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    let slot = LayoutSlot {
+                        x,
+                        y,
+                        width,
+                        height,
+                    };
+                    let Some(profile) = self.config.get_active_profile_mut() else {
+                        continue;
+                    };
+                    if !SourceGeometry::upsert(
+                        &mut profile.custom_source_geometry,
+                        rule_id,
+                        instance,
+                        slot,
+                    ) {
+                        continue;
+                    }
+                    // Saved together with the PositionChanged that accompanies it
+                    if profile.persists_positions() {
+                        let delay = Duration::from_millis(profile.timing_auto_save_delay_ms.into());
+                        self.pending_position_save = Some(Instant::now() + delay);
                     }
                 }
                 DaemonMessage::CharacterDetected { name, is_custom } => {
@@ -219,12 +629,153 @@ impl SharedState {
                         info!("Daemon detected custom source: {}", name);
                     } else {
                         info!("Daemon detected character: {}", name);
+                        if let Some(launch) = self.fleet_launch.as_mut() {
+                            launch.character_appeared(&name, Instant::now());
+                        }
+                        if self.session_history.mark_seen(&name, unix_now())
+                            && let Err(e) = self.session_history.save()
+                        {
+                            warn!(error = ?e, "Failed to save session history");
+                        }
                     }
                 }
                 DaemonMessage::RequestProfileSwitch(name) => {
                     info!("Daemon requested profile switch: {}", name);
                     profile_switch_request = Some(name);
                 }
+                DaemonMessage::ClientLayout(layout) => {
+                    debug!(clients = layout.len(), "Received client layout from daemon");
+                    if let Some(launch) = self.fleet_launch.as_mut() {
+                        launch.layout_received(
+                            layout
+                                .iter()
+                                .filter(|c| !c.is_custom)
+                                .map(|c| c.name.as_str()),
+                            Instant::now(),
+                        );
+                    }
+                    // The layout lists every tracked client, so it also prunes stale prefixes
+                    self.client_prefixes
+                        .retain(|name, _| layout.iter().any(|c| !c.is_custom && c.name == *name));
+                    self.client_layout = Some((Instant::now(), layout));
+                }
+                DaemonMessage::Pong(seq) => {
+                    if let Some((pending, sent_at)) = self.pending_ping
+                        && pending == seq
+                    {
+                        self.ipc_latency = Some(sent_at.elapsed());
+                        self.pending_ping = None;
+                        // A pong is stronger evidence of life than a heartbeat
+                        self.ipc_healthy = true;
+                        self.last_heartbeat = Instant::now();
+                        self.missed_heartbeats = 0;
+                    }
+                }
+                DaemonMessage::Frame { character, png } => {
+                    debug!(character = %character, ok = png.is_ok(), "Received frame from daemon");
+                    self.captured_frame = Some((character, png));
+                }
+                DaemonMessage::MatchingWindows {
+                    title_pattern,
+                    class_pattern,
+                    windows,
+                } => {
+                    debug!(
+                        matches = windows.len(),
+                        "Received rule preview matches from daemon"
+                    );
+                    self.window_matches = Some(((title_pattern, class_pattern), windows));
+                }
+                DaemonMessage::HotkeyTested { binding, result } => {
+                    debug!(binding = %binding.display_name(), result = ?result, "Received hotkey test result");
+                    self.hotkey_test = Some((binding, result));
+                }
+                DaemonMessage::SyntheticInput(entries) => {
+                    self.record_synthetic_input(entries);
+                }
+                DaemonMessage::Metrics(metrics) => {
+                    if let Some(server) = &self.metrics_server {
+                        server.publish(&metrics);
+                    }
+                    self.daemon_metrics = Some(metrics);
+                }
+                DaemonMessage::XErrors(errors) => {
+                    if let Some(warning) = self.x_errors.record(Instant::now(), errors) {
+                        warn!(warning = %warning, "X error spike");
+                        self.status_message = Some(super::types::StatusMessage {
+                            text: warning,
+                            color: COLOR_WARNING,
+                        });
+                    }
+                }
+                DaemonMessage::CharacterRemoved { name, is_custom } => {
+                    debug!(character = %name, is_custom = is_custom, "Daemon stopped tracking client");
+                    if !is_custom {
+                        self.client_prefixes.remove(&name);
+                    }
+                    if let Some((_, layout)) = self.client_layout.as_mut() {
+                        layout.retain(|c| c.name != name || c.is_custom != is_custom);
+                    }
+                }
+                DaemonMessage::ClientPrefix { name, info } => {
+                    debug!(character = %name, prefix = ?info.prefix, "Client Wine prefix");
+                    self.client_prefixes.insert(name, info);
+                }
+                DaemonMessage::PausedChanged(paused) => {
+                    info!(paused = paused, "Daemon pause state changed");
+                    self.paused = paused;
+                }
+                DaemonMessage::SessionCaptured { clients, focused } => {
+                    let Some(name) = self.pending_snapshot.take() else {
+                        continue;
+                    };
+                    let Some(profile) = self.config.get_active_profile_mut() else {
+                        continue;
+                    };
+                    info!(snapshot = %name, clients = clients.len(), "Saved session snapshot");
+                    let count = clients.len();
+                    let snapshot = SessionSnapshot {
+                        name: name.clone(),
+                        saved_at: unix_now(),
+                        clients,
+                        focused,
+                    };
+                    match profile
+                        .session_snapshots
+                        .iter_mut()
+                        .find(|s| s.name == name)
+                    {
+                        Some(existing) => *existing = snapshot,
+                        None => profile.session_snapshots.push(snapshot),
+                    }
+                    self.settings_changed = true;
+                    self.status_message = Some(super::types::StatusMessage {
+                        text: format!("Saved session snapshot '{}' ({} clients)", name, count),
+                        color: COLOR_SUCCESS,
+                    });
+                }
+                DaemonMessage::SessionRestored { missing } => {
+                    self.status_message = Some(if missing.is_empty() {
+                        super::types::StatusMessage {
+                            text: "Session snapshot restored".to_string(),
+                            color: COLOR_SUCCESS,
+                        }
+                    } else {
+                        warn!(missing = ?missing, "Session snapshot clients not running");
+                        super::types::StatusMessage {
+                            text: format!("Session restored; not running: {}", missing.join(", ")),
+                            color: COLOR_WARNING,
+                        }
+                    });
+                }
+                DaemonMessage::FocusChanged(name) => {
+                    if name != self.config.global.last_focused_character {
+                        debug!(character = %name, "Focused character changed");
+                        self.config.global.last_focused_character = name;
+                        self.pending_focus_save =
+                            Some(Instant::now() + Duration::from_millis(FOCUS_SAVE_DELAY_MS));
+                    }
+                }
                 DaemonMessage::Heartbeat => {
                     self.ipc_healthy = true;
                     self.last_heartbeat = Instant::now();
@@ -233,6 +784,11 @@ impl SharedState {
             }
         }
 
+        if let Some(hello) = protocol_mismatch {
+            self.handle_protocol_mismatch(&hello);
+            return;
+        }
+
         if let Some(name) = profile_switch_request {
             if let Some(idx) = self
                 .config
@@ -246,6 +802,35 @@ impl SharedState {
             }
         }
 
+        if self
+            .pending_position_save
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.flush_position_save();
+        }
+
+        if self
+            .pending_focus_save
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.flush_focus_save();
+        }
+
+        self.poll_fleet_launch();
+
+        // Liveness ping
+        if self.daemon_connected()
+            && self.last_ping_sent.elapsed() >= Duration::from_millis(PING_INTERVAL_MS)
+            && let Some(ref tx) = self.ipc_config_tx
+        {
+            self.ping_seq += 1;
+            match tx.send(ConfigMessage::Ping(self.ping_seq)) {
+                Ok(()) => self.pending_ping = Some((self.ping_seq, Instant::now())),
+                Err(e) => warn!(error = %e, "Failed to ping daemon"),
+            }
+            self.last_ping_sent = Instant::now();
+        }
+
         // IPC Health Check
         // If connected but no heartbeat for 15s (5s grace * 3), assume hung process
         if self.daemon.is_some()
@@ -286,6 +871,11 @@ impl SharedState {
                     self.ipc_config_tx = None;
                     self.ipc_status_rx = None;
                     self.daemon_status_rx = None;
+                    self.daemon_protocol = None;
+                    self.client_layout = None;
+                    self.client_prefixes.clear();
+                    self.reset_link_state();
+                    self.schedule_reconnect();
                 }
                 Ok(None) => {}
                 Err(err) => {
@@ -294,4 +884,180 @@ impl SharedState {
             }
         }
     }
+
+    /// Focuses the character that had focus at the last shutdown, once per launch. A profile
+    /// with its own startup focus target keeps that instead.
+    fn restore_focus(&mut self) {
+        let Some(character) = self.pending_focus_restore.take() else {
+            return;
+        };
+        let startup_target = self
+            .config
+            .get_active_profile()
+            .is_some_and(|p| !p.startup_actions.focus_character.is_empty());
+        if startup_target {
+            return;
+        }
+        let Some(ref tx) = self.ipc_config_tx else {
+            return;
+        };
+        info!(character = %character, "Restoring focus from the last session");
+        if let Err(e) = tx.send(ConfigMessage::FocusCharacter(character)) {
+            warn!(error = %e, "Failed to send focus restore to daemon");
+        }
+    }
+
+    /// Writes the last focused character to disk without touching unsaved settings
+    pub fn flush_focus_save(&mut self) {
+        if self.pending_focus_save.take().is_none() {
+            return;
+        }
+        let result = crate::config::profile::Config::load().and_then(|mut disk| {
+            disk.global.last_focused_character = self.config.global.last_focused_character.clone();
+            disk.save()
+        });
+        if let Err(e) = result {
+            warn!(error = ?e, "Failed to save last focused character");
+        }
+    }
+
+    /// Writes debounced thumbnail positions to disk and confirms them to the daemon
+    pub fn flush_position_save(&mut self) {
+        if self.pending_position_save.take().is_none() {
+            return;
+        }
+
+        // Save to disk only (Daemon already has the correct positions)
+        if let Err(e) = self.save_config_no_sync(SaveMode::Explicit) {
+            warn!(error = ?e, "Failed to auto-save thumbnail positions");
+        }
+
+        // Send lightweight deltas to confirm the positions
+        // Daemon will perform idempotency check and skip redundant X11 operations
+        let acks = std::mem::take(&mut self.pending_position_acks);
+        if let Some(ref tx) = self.ipc_config_tx {
+            for ((name, is_custom), (x, y, width, height)) in acks {
+                let _ = tx.send(ConfigMessage::ThumbnailMove {
+                    name,
+                    is_custom,
+                    x,
+                    y,
+                    width,
+                    height,
+                });
+            }
+        }
+        debug!("Debounced auto-save triggered with ThumbnailMove deltas");
+    }
+
+    /// Current health of the IPC link, for the header indicator
+    pub fn connection_state(&self) -> ConnectionState {
+        if self.daemon.is_none() {
+            return match self.reconnect_at {
+                Some(at) => ConnectionState::Reconnecting {
+                    attempt: self.reconnect_attempts,
+                    in_secs: at.saturating_duration_since(Instant::now()).as_secs(),
+                },
+                None => ConnectionState::Disconnected,
+            };
+        }
+        if self.ipc_config_tx.is_none() {
+            return ConnectionState::Connecting;
+        }
+        if !self.ipc_healthy || self.missed_heartbeats > 0 {
+            return ConnectionState::Degraded;
+        }
+        ConnectionState::Connected
+    }
+
+    /// Queues an automatic daemon restart with exponential backoff.
+    ///
+    /// Gives up after `RECONNECT_MAX_ATTEMPTS` consecutive failures; a connection that stays up
+    /// for `RECONNECT_STABLE_SECS` resets the counter.
+    pub fn schedule_reconnect(&mut self) {
+        if self
+            .connected_since
+            .take()
+            .is_some_and(|since| since.elapsed() >= Duration::from_secs(RECONNECT_STABLE_SECS))
+        {
+            self.reconnect_attempts = 0;
+        }
+
+        if self.reconnect_attempts >= RECONNECT_MAX_ATTEMPTS {
+            warn!(
+                attempts = self.reconnect_attempts,
+                "Daemon keeps exiting, giving up on automatic reconnect"
+            );
+            self.reconnect_at = None;
+            self.status_message = Some(super::types::StatusMessage {
+                text: "Daemon keeps crashing - automatic reconnect stopped".to_string(),
+                color: STATUS_STOPPED,
+            });
+            return;
+        }
+
+        self.reconnect_attempts += 1;
+        let delay = reconnect_delay(self.reconnect_attempts);
+        info!(
+            attempt = self.reconnect_attempts,
+            delay_ms = delay.as_millis() as u64,
+            "Scheduling daemon reconnect"
+        );
+        self.reconnect_at = Some(Instant::now() + delay);
+    }
+
+    /// Forgets per-connection liveness data after the daemon went away
+    fn reset_link_state(&mut self) {
+        self.pending_ping = None;
+        self.ipc_latency = None;
+        self.ipc_healthy = false;
+    }
+
+    /// Reacts to a daemon that speaks no protocol version in common with this Manager.
+    ///
+    /// The first time, the daemon is restarted from the current executable (fixes a stale daemon
+    /// left over from before an upgrade). If that daemon is still incompatible, it is stopped and
+    /// the user is asked to restart the Manager itself.
+    fn handle_protocol_mismatch(&mut self, hello: &ProtocolHello) {
+        error!(
+            daemon_version = %hello.app_version,
+            daemon_protocol = hello.protocol_version,
+            daemon_min_protocol = hello.min_protocol_version,
+            manager_protocol = crate::common::ipc::PROTOCOL_VERSION,
+            manager_min_protocol = MIN_PROTOCOL_VERSION,
+            "Daemon protocol is incompatible with this Manager"
+        );
+
+        if !self.protocol_restart_attempted {
+            self.protocol_restart_attempted = true;
+            self.restart_daemon();
+            return;
+        }
+
+        let _ = self.stop_daemon();
+        self.status_message = Some(super::types::StatusMessage {
+            text: format!(
+                "Daemon v{} is incompatible with Manager v{}. Restart the Manager.",
+                hello.app_version,
+                env!("CARGO_PKG_VERSION")
+            ),
+            color: STATUS_STOPPED,
+        });
+    }
+}
+
+/// Backoff before reconnect attempt `attempt` (1-based): doubles each time, capped
+pub(crate) fn reconnect_delay(attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis((RECONNECT_BASE_DELAY_MS * factor).min(RECONNECT_MAX_DELAY_MS))
+}
+
+/// Sends SIGTERM to the daemon, returning whether the signal was delivered
+#[allow(unsafe_code)] // Required for libc::kill() system call
+fn send_sigterm(child: &std::process::Child) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(child.id()) else {
+        return false;
+    };
+    // SAFETY: kill() only sends a signal to our own child process
+    unsafe { libc::kill(pid, libc::SIGTERM) == 0 }
 }
@@ -1,6 +1,7 @@
 pub mod core;
 pub mod daemon;
 pub mod types;
+pub mod x_errors;
 
 pub use self::core::SharedState;
 pub use types::*;
@@ -14,15 +14,19 @@ pub enum ManagerTab {
 pub enum DaemonStatus {
     Starting,
     Running,
+    /// SIGTERM sent, waiting for the daemon to exit
+    Stopping,
     Stopped,
     Crashed(Option<i32>),
+    /// No Composite extension: the Manager shows static previews instead of the daemon
+    Fallback,
 }
 
 impl DaemonStatus {
     pub fn color(&self) -> egui::Color32 {
         match self {
             DaemonStatus::Running => STATUS_RUNNING,
-            DaemonStatus::Starting => STATUS_STARTING,
+            DaemonStatus::Starting | DaemonStatus::Fallback => STATUS_STARTING,
             _ => STATUS_STOPPED,
         }
     }
@@ -31,7 +35,9 @@ impl DaemonStatus {
         match self {
             DaemonStatus::Running => "Daemon running".to_string(),
             DaemonStatus::Starting => "Daemon starting...".to_string(),
+            DaemonStatus::Stopping => "Daemon stopping...".to_string(),
             DaemonStatus::Stopped => "Daemon stopped".to_string(),
+            DaemonStatus::Fallback => "Static previews (no compositor)".to_string(),
             DaemonStatus::Crashed(code) => match code {
                 Some(code) => format!("Daemon crashed (exit {code})"),
                 None => "Daemon crashed".to_string(),
@@ -40,6 +46,45 @@ impl DaemonStatus {
     }
 }
 
+/// Health of the Manager <-> Daemon IPC link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No daemon and no reconnect pending
+    Disconnected,
+    /// Daemon spawned, waiting for its IPC channels
+    Connecting,
+    /// Heartbeats arriving on time
+    Connected,
+    /// Connected, but heartbeats are late
+    Degraded,
+    /// Daemon exited unexpectedly; restart scheduled
+    Reconnecting { attempt: u32, in_secs: u64 },
+}
+
+impl ConnectionState {
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            ConnectionState::Connected => STATUS_RUNNING,
+            ConnectionState::Connecting
+            | ConnectionState::Degraded
+            | ConnectionState::Reconnecting { .. } => STATUS_STARTING,
+            ConnectionState::Disconnected => STATUS_STOPPED,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ConnectionState::Disconnected => "IPC disconnected".to_string(),
+            ConnectionState::Connecting => "IPC connecting...".to_string(),
+            ConnectionState::Connected => "IPC connected".to_string(),
+            ConnectionState::Degraded => "IPC not responding".to_string(),
+            ConnectionState::Reconnecting { attempt, in_secs } => {
+                format!("Reconnecting in {in_secs}s (attempt {attempt})")
+            }
+        }
+    }
+}
+
 pub struct StatusMessage {
     pub text: String,
     pub color: egui::Color32,
@@ -0,0 +1,117 @@
+//! X errors reported by the daemon: running totals and spike detection
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::common::constants::manager_ui::{X_ERROR_SPIKE_THRESHOLD, X_ERROR_SPIKE_WINDOW_SECS};
+use crate::common::ipc::XErrorCount;
+
+#[derive(Default)]
+pub struct XErrorTracker {
+    /// Totals by (error kind, request) since the Manager started
+    totals: HashMap<(String, String), u64>,
+    /// Reports within the spike window (arrival time, error count)
+    recent: VecDeque<(Instant, u64)>,
+    spiking: bool,
+}
+
+impl XErrorTracker {
+    /// Adds a report. Returns a warning when the error rate starts spiking.
+    pub fn record(&mut self, now: Instant, errors: Vec<XErrorCount>) -> Option<String> {
+        let count: u64 = errors.iter().map(|e| e.count).sum();
+        for error in errors {
+            *self.totals.entry((error.error, error.request)).or_default() += error.count;
+        }
+
+        let window = Duration::from_secs(X_ERROR_SPIKE_WINDOW_SECS);
+        self.recent.push_back((now, count));
+        while self
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > window)
+        {
+            self.recent.pop_front();
+        }
+
+        let recent: u64 = self.recent.iter().map(|(_, count)| count).sum();
+        let was_spiking = std::mem::replace(&mut self.spiking, recent >= X_ERROR_SPIKE_THRESHOLD);
+        if !self.spiking || was_spiking {
+            return None;
+        }
+        let mut warning = format!("⚠ {} X11 errors in the last minute", recent);
+        if let Some(((error, request), _)) = self.top(1).first() {
+            warning.push_str(&format!(" (mostly Bad{} in {})", error, request));
+        }
+        Some(warning)
+    }
+
+    /// Most frequent (error kind, request) pairs
+    pub fn top(&self, n: usize) -> Vec<(&(String, String), u64)> {
+        let mut entries: Vec<_> = self
+            .totals
+            .iter()
+            .map(|(key, &count)| (key, count))
+            .collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries.truncate(n);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(error: &str, request: &str, count: u64) -> Vec<XErrorCount> {
+        vec![XErrorCount {
+            error: error.to_string(),
+            request: request.to_string(),
+            count,
+        }]
+    }
+
+    #[test]
+    fn test_spike_warns_once() {
+        let mut tracker = XErrorTracker::default();
+        let start = Instant::now();
+
+        assert!(
+            tracker
+                .record(start, report("Window", "GetProperty", 5))
+                .is_none()
+        );
+        let warning = tracker.record(
+            start + Duration::from_secs(3),
+            report("Window", "GetProperty", X_ERROR_SPIKE_THRESHOLD),
+        );
+        assert!(warning.unwrap().contains("BadWindow in GetProperty"));
+        // Still spiking: no repeated warning
+        assert!(
+            tracker
+                .record(
+                    start + Duration::from_secs(6),
+                    report("Match", "GetImage", 1)
+                )
+                .is_none()
+        );
+
+        // The burst leaves the window, then a new one warns again
+        let later = start + Duration::from_secs(X_ERROR_SPIKE_WINDOW_SECS + 10);
+        assert!(
+            tracker
+                .record(later, report("Match", "GetImage", 1))
+                .is_none()
+        );
+        assert!(
+            tracker
+                .record(later, report("Match", "GetImage", X_ERROR_SPIKE_THRESHOLD))
+                .is_some()
+        );
+
+        let top: Vec<u64> = tracker.top(2).into_iter().map(|(_, count)| count).collect();
+        assert_eq!(
+            top,
+            vec![X_ERROR_SPIKE_THRESHOLD + 5, X_ERROR_SPIKE_THRESHOLD + 2]
+        );
+    }
+}
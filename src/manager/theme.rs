@@ -0,0 +1,95 @@
+//! Manager window theme: light/dark mode, accent color, zoom, text size and layout density
+//! from the global settings
+
+use crate::common::constants::defaults::manager::{
+    UI_FONT_SIZE, UI_FONT_SIZE_MAX, UI_FONT_SIZE_MIN, UI_SCALE_MAX, UI_SCALE_MIN,
+};
+use crate::common::constants::manager_ui::{COMPACT_SPACING_FACTOR, COMPACT_WIDGET_FACTOR};
+use crate::config::profile::{GlobalSettings, ManagerTheme, UiDensity};
+use crate::manager::utils::parse_hex_color;
+
+/// Applies the theme and accent color to both egui styles (dark and light), so following
+/// the system theme keeps the accent when the desktop switches
+pub fn apply(ctx: &egui::Context, global: &GlobalSettings) {
+    ctx.set_theme(match global.theme {
+        ManagerTheme::System => egui::ThemePreference::System,
+        ManagerTheme::Dark => egui::ThemePreference::Dark,
+        ManagerTheme::Light => egui::ThemePreference::Light,
+    });
+
+    let accent = parse_hex_color(global.accent_color.trim()).ok();
+    for theme in [egui::Theme::Dark, egui::Theme::Light] {
+        let mut visuals = theme.default_visuals();
+        if let Some(accent) = accent {
+            visuals.selection.bg_fill = accent;
+            visuals.selection.stroke.color = contrast_color(accent);
+            visuals.hyperlink_color = accent;
+            visuals.widgets.hovered.bg_stroke.color = accent;
+            visuals.widgets.active.bg_fill = accent;
+        }
+        ctx.set_visuals_of(theme, visuals);
+    }
+}
+
+/// Applies the zoom factor and scales every text style so body text has the configured size
+pub fn apply_scale(ctx: &egui::Context, global: &GlobalSettings) {
+    ctx.set_zoom_factor(clamp_scale(global.ui_scale));
+
+    let factor = global
+        .ui_font_size
+        .clamp(UI_FONT_SIZE_MIN, UI_FONT_SIZE_MAX)
+        / UI_FONT_SIZE;
+    let defaults = egui::Style::default().text_styles;
+    ctx.all_styles_mut(|style| {
+        for (text_style, font) in style.text_styles.iter_mut() {
+            if let Some(default) = defaults.get(text_style) {
+                font.size = default.size * factor;
+            }
+        }
+    });
+}
+
+/// Applies the density to egui's widget spacing and remembers it for `density_spacing`
+pub fn apply_density(ctx: &egui::Context, density: UiDensity) {
+    let (spacing_factor, widget_factor) = match density {
+        UiDensity::Comfortable => (1.0, 1.0),
+        UiDensity::Compact => (COMPACT_SPACING_FACTOR, COMPACT_WIDGET_FACTOR),
+    };
+    ctx.data_mut(|d| d.insert_temp(density_id(), spacing_factor));
+
+    let defaults = egui::Style::default().spacing;
+    ctx.all_styles_mut(|style| {
+        let spacing = &mut style.spacing;
+        spacing.item_spacing = defaults.item_spacing * widget_factor;
+        spacing.button_padding = defaults.button_padding * widget_factor;
+        spacing.window_margin = defaults.window_margin * widget_factor;
+        spacing.menu_margin = defaults.menu_margin * widget_factor;
+        spacing.indent = defaults.indent * widget_factor;
+        spacing.interact_size.y = defaults.interact_size.y * widget_factor.max(0.8);
+    });
+}
+
+/// `base` layout spacing (`ITEM_SPACING`, `SECTION_SPACING`, ...) for the selected density
+pub fn density_spacing(ui: &egui::Ui, base: f32) -> f32 {
+    base * ui.data(|d| d.get_temp::<f32>(density_id())).unwrap_or(1.0)
+}
+
+fn density_id() -> egui::Id {
+    egui::Id::new("manager_ui_density")
+}
+
+/// Zoom factor within the supported range
+pub fn clamp_scale(scale: f32) -> f32 {
+    scale.clamp(UI_SCALE_MIN, UI_SCALE_MAX)
+}
+
+/// Black or white, whichever reads better on `background`
+fn contrast_color(background: egui::Color32) -> egui::Color32 {
+    let [r, g, b, _] = background.to_array();
+    let luminance = 0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b);
+    if luminance > 140.0 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    }
+}
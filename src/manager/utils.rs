@@ -85,6 +85,30 @@ pub fn load_window_icon() -> Result<egui::IconData> {
     })
 }
 
+/// Decode an RGBA PNG (e.g. a frame captured by the daemon) into an egui image
+pub fn decode_png_image(png_bytes: &[u8]) -> Result<egui::ColorImage> {
+    let decoder = png::Decoder::new(Cursor::new(png_bytes));
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .context("PNG has no output buffer size")?
+    ];
+    let info = reader.next_frame(&mut buf)?;
+    if info.color_type != png::ColorType::Rgba {
+        return Err(anyhow!(
+            "Unsupported frame color type {:?} (expected RGBA)",
+            info.color_type
+        ));
+    }
+
+    Ok(egui::ColorImage::from_rgba_unmultiplied(
+        [info.width as usize, info.height as usize],
+        &buf[..info.buffer_size()],
+    ))
+}
+
 pub fn spawn_daemon(ipc_server_name: &str, debug: bool) -> Result<Child> {
     let exe_path = std::env::current_exe().context("Failed to resolve executable path")?;
     let mut command = Command::new(exe_path);
@@ -1,6 +1,11 @@
 use anyhow::{Context, Result};
+use std::time::{Duration, Instant};
 use x11rb::connection::Connection;
-use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, Window};
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, EventMask, GrabMode, GrabStatus, Window};
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::ipc::WinePrefixInfo;
 
 #[derive(Clone, Debug)]
 pub struct WindowInfo {
@@ -112,3 +117,167 @@ pub fn get_running_applications() -> Result<Vec<WindowInfo>> {
 
     Ok(windows)
 }
+
+/// Properties of a window picked with the inspector
+#[derive(Clone, Debug)]
+pub struct InspectedWindow {
+    pub id: Window,
+    /// WM_NAME, the title client detection reads
+    pub wm_name: String,
+    /// _NET_WM_NAME (UTF-8 title)
+    pub net_wm_name: String,
+    /// WM_CLASS class name
+    pub class: String,
+    pub pid: Option<u32>,
+    pub wine: Option<WinePrefixInfo>,
+}
+
+impl InspectedWindow {
+    /// Title custom source rules see (WM_NAME, falling back to _NET_WM_NAME)
+    pub fn title(&self) -> &str {
+        if self.wm_name.is_empty() {
+            &self.net_wm_name
+        } else {
+            &self.wm_name
+        }
+    }
+}
+
+/// Grabs the pointer and waits for a click, then reports the client window under it.
+/// Blocks until a click or `timeout`; a right click, a click on the desktop or a timeout
+/// returns None.
+pub fn inspect_clicked_window(timeout: Duration) -> Result<Option<InspectedWindow>> {
+    // Glyph of the crosshair in the standard cursor font
+    const XC_CROSSHAIR: u16 = 34;
+
+    let (conn, screen_num) = x11rb::connect(None).context("Failed to connect to X11")?;
+    let root = conn.setup().roots[screen_num].root;
+
+    let font = conn.generate_id()?;
+    conn.open_font(font, b"cursor")?;
+    let cursor = conn.generate_id()?;
+    conn.create_glyph_cursor(
+        cursor,
+        font,
+        font,
+        XC_CROSSHAIR,
+        XC_CROSSHAIR + 1,
+        0,
+        0,
+        0,
+        0xffff,
+        0xffff,
+        0xffff,
+    )?;
+
+    let grab = conn
+        .grab_pointer(
+            false,
+            root,
+            EventMask::BUTTON_PRESS,
+            GrabMode::ASYNC,
+            GrabMode::ASYNC,
+            x11rb::NONE,
+            cursor,
+            x11rb::CURRENT_TIME,
+        )?
+        .reply()
+        .context("Failed to grab pointer")?;
+    if grab.status != GrabStatus::SUCCESS {
+        anyhow::bail!(
+            "Pointer is grabbed by another application ({:?})",
+            grab.status
+        );
+    }
+
+    let deadline = Instant::now() + timeout;
+    let clicked = loop {
+        if let Some(event) = conn.poll_for_event()? {
+            if let Event::ButtonPress(press) = event {
+                // Button 1 picks, anything else cancels
+                break (press.detail == 1 && press.child != x11rb::NONE).then_some(press.child);
+            }
+            continue;
+        }
+        if Instant::now() >= deadline {
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    conn.ungrab_pointer(x11rb::CURRENT_TIME)?;
+    conn.free_cursor(cursor)?;
+    conn.close_font(font)?;
+    conn.flush()?;
+
+    let Some(frame) = clicked else {
+        return Ok(None);
+    };
+    // The click lands on the window manager's frame; the client is the descendant with WM_STATE
+    let wm_state = intern(&conn, b"WM_STATE")?;
+    let id = find_client_window(&conn, frame, wm_state)?.unwrap_or(frame);
+
+    let net_wm_name = intern(&conn, b"_NET_WM_NAME")?;
+    let net_wm_pid = intern(&conn, b"_NET_WM_PID")?;
+    let text = |property: u32, kind: u32| -> Result<String> {
+        let reply = conn
+            .get_property(false, id, property, kind, 0, 1024)?
+            .reply()?;
+        Ok(String::from_utf8_lossy(&reply.value).into_owned())
+    };
+
+    let wm_name = text(AtomEnum::WM_NAME.into(), AtomEnum::STRING.into())?;
+    let net_wm_name = text(net_wm_name, AtomEnum::ANY.into())?;
+    let wm_class = text(AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())?;
+    let class = wm_class
+        .split('\0')
+        .nth(1)
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| wm_class.split('\0').next().unwrap_or_default())
+        .to_string();
+    let pid = conn
+        .get_property(false, id, net_wm_pid, AtomEnum::CARDINAL, 0, 1)?
+        .reply()?
+        .value32()
+        .and_then(|mut v| v.next());
+
+    Ok(Some(InspectedWindow {
+        id,
+        wm_name,
+        net_wm_name,
+        class,
+        pid,
+        wine: pid.and_then(crate::daemon::detect_wine_prefix),
+    }))
+}
+
+fn intern(conn: &RustConnection, name: &[u8]) -> Result<u32> {
+    Ok(conn
+        .intern_atom(false, name)?
+        .reply()
+        .context(format!(
+            "Failed to intern {}",
+            String::from_utf8_lossy(name)
+        ))?
+        .atom)
+}
+
+/// Depth-first search for the first window carrying WM_STATE (the managed client)
+fn find_client_window(
+    conn: &RustConnection,
+    window: Window,
+    wm_state: u32,
+) -> Result<Option<Window>> {
+    let state = conn
+        .get_property(false, window, wm_state, AtomEnum::ANY, 0, 0)?
+        .reply()?;
+    if state.type_ != u32::from(AtomEnum::NONE) {
+        return Ok(Some(window));
+    }
+    for child in conn.query_tree(window)?.reply()?.children.into_iter().rev() {
+        if let Some(client) = find_client_window(conn, child, wm_state)? {
+            return Ok(Some(client));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,26 @@
+//! Native Wayland previews: layer-shell surfaces showing toplevels captured by the compositor.
+//!
+//! Where X11 overlay windows can't composite client contents (or when forced with
+//! `EVE_PREVIEW_BACKEND=wayland`), the daemon draws previews itself. Clients are discovered
+//! through `ext-foreign-toplevel-list`, captured with `ext-image-copy-capture` (per toplevel,
+//! so covered clients stay live) and shown on `wlr-layer-shell` overlay surfaces.
+//! Compositors without `ext-image-copy-capture` are captured with `wlr-screencopy` instead. It
+//! can only copy output regions, and Wayland offers no way to learn where a toplevel sits, so
+//! the region comes from the client's XWayland geometry. That assumes XWayland's root matches
+//! the compositor's logical layout, and a preview shows whatever is on screen there: covered
+//! or hidden clients don't stay live.
+
+mod shm;
+mod state;
+
+pub use shm::ShmBuffer;
+pub use state::{
+    CaptureConstraints, CaptureGlobals, Globals, WaylandEvent, WaylandState, bind_outputs,
+    compositor_supported,
+};
+
+/// Whether the session runs on a Wayland compositor
+pub fn session_is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some_and(|d| !d.is_empty())
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+}
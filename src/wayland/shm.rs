@@ -0,0 +1,99 @@
+//! Shared-memory buffers for previews and captured frames
+//!
+//! Buffers live in an unlinked file in the runtime directory. Pixels are moved with
+//! `pread`/`pwrite` rather than a mapping, which keeps this module free of unsafe code.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::os::fd::AsFd;
+use std::os::unix::fs::FileExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use wayland_client::QueueHandle;
+use wayland_client::protocol::wl_buffer::WlBuffer;
+use wayland_client::protocol::wl_shm::{Format, WlShm};
+use wayland_client::protocol::wl_shm_pool::WlShmPool;
+
+use super::state::WaylandState;
+use crate::common::shm::anonymous_file;
+
+/// User data of a `wl_buffer`: set while the compositor still reads from it
+#[derive(Debug, Default, Clone)]
+pub struct BufferBusy(pub Arc<AtomicBool>);
+
+/// A `wl_buffer` of `width`x`height` 32-bit pixels
+pub struct ShmBuffer {
+    file: File,
+    pool: WlShmPool,
+    buffer: WlBuffer,
+    busy: BufferBusy,
+    pub width: u32,
+    pub height: u32,
+    pub format: Format,
+}
+
+impl ShmBuffer {
+    pub fn new(
+        shm: &WlShm,
+        qh: &QueueHandle<WaylandState>,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<Self> {
+        let size = width as usize * height as usize * 4;
+        let file = anonymous_file(size as u64)?;
+        let pool = shm.create_pool(file.as_fd(), size as i32, qh, ());
+        let busy = BufferBusy::default();
+        let buffer = pool.create_buffer(
+            0,
+            width as i32,
+            height as i32,
+            (width * 4) as i32,
+            format,
+            qh,
+            busy.clone(),
+        );
+        Ok(Self {
+            file,
+            pool,
+            buffer,
+            busy,
+            width,
+            height,
+            format,
+        })
+    }
+
+    pub fn buffer(&self) -> &WlBuffer {
+        &self.buffer
+    }
+
+    /// Whether the compositor hasn't released the last attached contents yet
+    pub fn is_busy(&self) -> bool {
+        self.busy.0.load(Ordering::Relaxed)
+    }
+
+    /// Replaces the buffer contents (`width * height * 4` bytes) and marks it as in use
+    pub fn write(&self, pixels: &[u8]) -> Result<()> {
+        self.file
+            .write_all_at(pixels, 0)
+            .context("Failed to write shm buffer")?;
+        self.busy.0.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Reads the whole buffer into `pixels`
+    pub fn read(&self, pixels: &mut Vec<u8>) -> Result<()> {
+        pixels.resize(self.width as usize * self.height as usize * 4, 0);
+        self.file
+            .read_exact_at(pixels, 0)
+            .context("Failed to read shm buffer")
+    }
+}
+
+impl Drop for ShmBuffer {
+    fn drop(&mut self) {
+        self.buffer.destroy();
+        self.pool.destroy();
+    }
+}
@@ -0,0 +1,653 @@
+//! Wayland globals and event dispatch
+//!
+//! The dispatch handlers only record what happened: toplevel metadata and output geometry
+//! are kept in `WaylandState`, everything the daemon reacts to is queued in `events` and
+//! drained by the event loop after each dispatch.
+
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use tracing::{debug, warn};
+use wayland_client::backend::ObjectId;
+use wayland_client::globals::{GlobalList, GlobalListContents};
+use wayland_client::protocol::wl_buffer::{self, WlBuffer};
+use wayland_client::protocol::wl_compositor::WlCompositor;
+use wayland_client::protocol::wl_output::{self, WlOutput};
+use wayland_client::protocol::wl_pointer::{self, WlPointer};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::protocol::wl_seat::{self, WlSeat};
+use wayland_client::protocol::wl_shm::{self, WlShm};
+use wayland_client::protocol::wl_shm_pool::WlShmPool;
+use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_client::{
+    Connection, Dispatch, Proxy, QueueHandle, WEnum, delegate_noop, event_created_child,
+};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::{
+    self, ExtForeignToplevelHandleV1,
+};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_list_v1::{
+    self, ExtForeignToplevelListV1,
+};
+use wayland_protocols::ext::image_capture_source::v1::client::ext_foreign_toplevel_image_capture_source_manager_v1::ExtForeignToplevelImageCaptureSourceManagerV1;
+use wayland_protocols::ext::image_capture_source::v1::client::ext_image_capture_source_v1::ExtImageCaptureSourceV1;
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_frame_v1::{
+    self, ExtImageCopyCaptureFrameV1,
+};
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_manager_v1::ExtImageCopyCaptureManagerV1;
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_session_v1::{
+    self, ExtImageCopyCaptureSessionV1,
+};
+use wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1::ZwlrLayerShellV1;
+use wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::{
+    self, ZwlrLayerSurfaceV1,
+};
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_frame_v1::{
+    self, ZwlrScreencopyFrameV1,
+};
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1;
+
+use super::shm::BufferBusy;
+
+/// How previews read client contents
+pub enum CaptureGlobals {
+    /// `ext-image-copy-capture` of each toplevel (covered clients stay live)
+    Toplevel {
+        sources: ExtForeignToplevelImageCaptureSourceManagerV1,
+        image_copy: ExtImageCopyCaptureManagerV1,
+    },
+    /// `wlr-screencopy` of the output region a client covers, located through XWayland
+    Region(ZwlrScreencopyManagerV1),
+}
+
+/// Globals the Wayland backend needs; binding fails when any of them is missing
+pub struct Globals {
+    pub compositor: WlCompositor,
+    pub shm: WlShm,
+    pub layer_shell: ZwlrLayerShellV1,
+    /// Held so the compositor keeps announcing toplevels
+    _toplevel_list: ExtForeignToplevelListV1,
+    pub capture: CaptureGlobals,
+    /// Held for its pointer; previews still show without a seat
+    _seat: Option<WlSeat>,
+}
+
+impl Globals {
+    pub fn bind(globals: &GlobalList, qh: &QueueHandle<WaylandState>) -> Result<Self> {
+        let missing = globals.contents().with_list(|list| {
+            let names: Vec<&str> = list.iter().map(|g| g.interface.as_str()).collect();
+            missing_globals(&names)
+        });
+        if !missing.is_empty() {
+            bail!(
+                "Compositor lacks required Wayland protocols: {}",
+                missing.join(", ")
+            );
+        }
+
+        let has = |name: &str| {
+            globals
+                .contents()
+                .with_list(|list| list.iter().any(|g| g.interface == name))
+        };
+        let capture = if TOPLEVEL_CAPTURE_GLOBALS.iter().all(|name| has(name)) {
+            CaptureGlobals::Toplevel {
+                sources: globals.bind(qh, 1..=1, ()).context(
+                    "Failed to bind ext_foreign_toplevel_image_capture_source_manager_v1",
+                )?,
+                image_copy: globals
+                    .bind(qh, 1..=1, ())
+                    .context("Failed to bind ext_image_copy_capture_manager_v1")?,
+            }
+        } else {
+            CaptureGlobals::Region(
+                globals
+                    .bind(qh, 1..=3, ())
+                    .context("Failed to bind zwlr_screencopy_manager_v1")?,
+            )
+        };
+
+        Ok(Self {
+            compositor: globals
+                .bind(qh, 4..=6, ())
+                .context("Failed to bind wl_compositor")?,
+            shm: globals
+                .bind(qh, 1..=1, ())
+                .context("Failed to bind wl_shm")?,
+            layer_shell: globals
+                .bind(qh, 1..=4, ())
+                .context("Failed to bind zwlr_layer_shell_v1")?,
+            _toplevel_list: globals
+                .bind(qh, 1..=1, ())
+                .context("Failed to bind ext_foreign_toplevel_list_v1")?,
+            capture,
+            _seat: globals.bind(qh, 1..=7, ()).ok(),
+        })
+    }
+}
+
+/// Interfaces checked by `Globals::bind` and `compositor_supported`
+const REQUIRED_GLOBALS: [&str; 4] = [
+    "wl_compositor",
+    "wl_shm",
+    "zwlr_layer_shell_v1",
+    "ext_foreign_toplevel_list_v1",
+];
+
+/// Per-toplevel capture, preferred over `SCREENCOPY_GLOBAL`
+const TOPLEVEL_CAPTURE_GLOBALS: [&str; 2] = [
+    "ext_foreign_toplevel_image_capture_source_manager_v1",
+    "ext_image_copy_capture_manager_v1",
+];
+
+const SCREENCOPY_GLOBAL: &str = "zwlr_screencopy_manager_v1";
+
+/// Required interfaces missing from `announced`; a capture protocol is reported as missing
+/// when neither per-toplevel capture nor screencopy is offered
+fn missing_globals<'a>(announced: &[&str]) -> Vec<&'a str> {
+    let mut missing: Vec<&str> = REQUIRED_GLOBALS
+        .into_iter()
+        .filter(|name| !announced.contains(name))
+        .collect();
+    let toplevel_capture = TOPLEVEL_CAPTURE_GLOBALS
+        .iter()
+        .all(|name| announced.contains(name));
+    if !toplevel_capture && !announced.contains(&SCREENCOPY_GLOBAL) {
+        missing.push("ext_image_copy_capture_manager_v1 or zwlr_screencopy_manager_v1");
+    }
+    missing
+}
+
+/// Whether the compositor behind `WAYLAND_DISPLAY` offers everything the backend needs
+pub fn compositor_supported() -> bool {
+    let Ok(conn) = Connection::connect_to_env() else {
+        return false;
+    };
+    let Ok((globals, _queue)) = wayland_client::globals::registry_queue_init::<WaylandState>(&conn)
+    else {
+        return false;
+    };
+    globals.contents().with_list(|list| {
+        let names: Vec<&str> = list.iter().map(|g| g.interface.as_str()).collect();
+        missing_globals(&names).is_empty()
+    })
+}
+
+/// Binds every `wl_output` announced at startup (hotplugged outputs are not tracked)
+pub fn bind_outputs(globals: &GlobalList, qh: &QueueHandle<WaylandState>) -> Vec<WlOutput> {
+    globals.contents().with_list(|list| {
+        list.iter()
+            .filter(|g| g.interface == "wl_output")
+            .map(|g| {
+                globals
+                    .registry()
+                    .bind::<WlOutput, _, _>(g.name, g.version.min(4), qh, ())
+            })
+            .collect()
+    })
+}
+
+/// Title and app id of a toplevel announced by the compositor
+#[derive(Debug)]
+pub struct Toplevel {
+    pub handle: ExtForeignToplevelHandleV1,
+    pub title: String,
+    pub app_id: String,
+}
+
+/// Logical placement of an output in the compositor space
+#[derive(Debug, Clone)]
+pub struct OutputInfo {
+    pub output: WlOutput,
+    pub x: i32,
+    pub y: i32,
+    pub mode_width: i32,
+    pub mode_height: i32,
+    pub scale: i32,
+}
+
+impl OutputInfo {
+    /// Whether `(x, y)` (compositor coordinates) lies on this output
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        let scale = self.scale.max(1);
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.mode_width / scale
+            && y < self.y + self.mode_height / scale
+    }
+}
+
+/// Buffer constraints announced by a capture session
+#[derive(Debug, Clone, Default)]
+pub struct CaptureConstraints {
+    pub width: u32,
+    pub height: u32,
+    pub formats: Vec<wl_shm::Format>,
+}
+
+/// Something the event loop has to react to
+#[derive(Debug)]
+pub enum WaylandEvent {
+    /// A toplevel's title or app id changed (or it was just announced)
+    ToplevelUpdated(ObjectId),
+    ToplevelClosed(ObjectId),
+    /// A layer surface got its first (or a new) configure and can be drawn into
+    LayerConfigured(ObjectId),
+    LayerClosed(ObjectId),
+    PointerEnter {
+        surface: ObjectId,
+        x: f64,
+        y: f64,
+    },
+    PointerLeave,
+    PointerMotion {
+        x: f64,
+        y: f64,
+    },
+    PointerButton {
+        button: u32,
+        pressed: bool,
+    },
+    /// Keyed by capture session
+    CaptureReady(ObjectId, CaptureConstraints),
+    CaptureStopped(ObjectId),
+    /// Keyed by the capture session the frame belongs to
+    FrameReady(ObjectId),
+    FrameFailed(ObjectId),
+    /// Keyed by the toplevel whose region is copied: the buffer the frame needs
+    RegionBuffer(ObjectId, CaptureConstraints),
+    /// The region frame was copied; `y_invert` when its rows are stored bottom up
+    RegionReady {
+        toplevel: ObjectId,
+        y_invert: bool,
+    },
+    RegionFailed(ObjectId),
+}
+
+#[derive(Default)]
+pub struct WaylandState {
+    pub toplevels: HashMap<ObjectId, Toplevel>,
+    pub outputs: Vec<OutputInfo>,
+    pub events: Vec<WaylandEvent>,
+    pointer: Option<WlPointer>,
+    constraints: HashMap<ObjectId, CaptureConstraints>,
+    /// Region frames whose rows are stored bottom up
+    y_inverted: std::collections::HashSet<ObjectId>,
+}
+
+impl WaylandState {
+    /// Output containing `(x, y)`, falling back to the first one
+    pub fn output_at(&self, x: i32, y: i32) -> Option<&OutputInfo> {
+        self.outputs
+            .iter()
+            .find(|o| o.contains(x, y))
+            .or(self.outputs.first())
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // Globals are only read once at startup
+    }
+}
+
+delegate_noop!(WaylandState: ignore WlCompositor);
+delegate_noop!(WaylandState: ignore WlShm);
+delegate_noop!(WaylandState: ignore WlShmPool);
+delegate_noop!(WaylandState: ignore WlSurface);
+delegate_noop!(WaylandState: ignore ZwlrLayerShellV1);
+delegate_noop!(WaylandState: ExtForeignToplevelImageCaptureSourceManagerV1);
+delegate_noop!(WaylandState: ExtImageCaptureSourceV1);
+delegate_noop!(WaylandState: ExtImageCopyCaptureManagerV1);
+delegate_noop!(WaylandState: ZwlrScreencopyManagerV1);
+
+impl Dispatch<WlBuffer, BufferBusy> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlBuffer,
+        event: wl_buffer::Event,
+        busy: &BufferBusy,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            busy.0.store(false, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+}
+
+impl Dispatch<WlOutput, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        output: &WlOutput,
+        event: wl_output::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let idx = match state.outputs.iter().position(|o| &o.output == output) {
+            Some(idx) => idx,
+            None => {
+                state.outputs.push(OutputInfo {
+                    output: output.clone(),
+                    x: 0,
+                    y: 0,
+                    mode_width: 0,
+                    mode_height: 0,
+                    scale: 1,
+                });
+                state.outputs.len() - 1
+            }
+        };
+        let info = &mut state.outputs[idx];
+        match event {
+            wl_output::Event::Geometry { x, y, .. } => {
+                info.x = x;
+                info.y = y;
+            }
+            wl_output::Event::Mode {
+                flags: WEnum::Value(flags),
+                width,
+                height,
+                ..
+            } if flags.contains(wl_output::Mode::Current) => {
+                info.mode_width = width;
+                info.mode_height = height;
+            }
+            wl_output::Event::Scale { factor } => info.scale = factor,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        seat: &WlSeat,
+        event: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+            && capabilities.contains(wl_seat::Capability::Pointer)
+            && state.pointer.is_none()
+        {
+            state.pointer = Some(seat.get_pointer(qh, ()));
+        }
+    }
+}
+
+impl Dispatch<WlPointer, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &WlPointer,
+        event: wl_pointer::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let event = match event {
+            wl_pointer::Event::Enter {
+                surface,
+                surface_x,
+                surface_y,
+                ..
+            } => WaylandEvent::PointerEnter {
+                surface: surface.id(),
+                x: surface_x,
+                y: surface_y,
+            },
+            wl_pointer::Event::Leave { .. } => WaylandEvent::PointerLeave,
+            wl_pointer::Event::Motion {
+                surface_x,
+                surface_y,
+                ..
+            } => WaylandEvent::PointerMotion {
+                x: surface_x,
+                y: surface_y,
+            },
+            wl_pointer::Event::Button {
+                button,
+                state: WEnum::Value(button_state),
+                ..
+            } => WaylandEvent::PointerButton {
+                button,
+                pressed: button_state == wl_pointer::ButtonState::Pressed,
+            },
+            _ => return,
+        };
+        state.events.push(event);
+    }
+}
+
+impl Dispatch<ZwlrLayerSurfaceV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        layer: &ZwlrLayerSurfaceV1,
+        event: zwlr_layer_surface_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_layer_surface_v1::Event::Configure { serial, .. } => {
+                layer.ack_configure(serial);
+                state.events.push(WaylandEvent::LayerConfigured(layer.id()));
+            }
+            zwlr_layer_surface_v1::Event::Closed => {
+                state.events.push(WaylandEvent::LayerClosed(layer.id()));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtForeignToplevelListV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &ExtForeignToplevelListV1,
+        event: ext_foreign_toplevel_list_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_foreign_toplevel_list_v1::Event::Toplevel { toplevel } => {
+                state.toplevels.insert(
+                    toplevel.id(),
+                    Toplevel {
+                        handle: toplevel,
+                        title: String::new(),
+                        app_id: String::new(),
+                    },
+                );
+            }
+            ext_foreign_toplevel_list_v1::Event::Finished => {
+                warn!("Compositor stopped sending toplevel updates");
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(WaylandState, ExtForeignToplevelListV1, [
+        ext_foreign_toplevel_list_v1::EVT_TOPLEVEL_OPCODE => (ExtForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ExtForeignToplevelHandleV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        handle: &ExtForeignToplevelHandleV1,
+        event: ext_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let id = handle.id();
+        match event {
+            ext_foreign_toplevel_handle_v1::Event::Title { title } => {
+                if let Some(toplevel) = state.toplevels.get_mut(&id) {
+                    toplevel.title = title;
+                }
+            }
+            ext_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                if let Some(toplevel) = state.toplevels.get_mut(&id) {
+                    toplevel.app_id = app_id;
+                }
+            }
+            ext_foreign_toplevel_handle_v1::Event::Done => {
+                state.events.push(WaylandEvent::ToplevelUpdated(id));
+            }
+            ext_foreign_toplevel_handle_v1::Event::Closed => {
+                if let Some(toplevel) = state.toplevels.remove(&id) {
+                    toplevel.handle.destroy();
+                }
+                state.events.push(WaylandEvent::ToplevelClosed(id));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtImageCopyCaptureSessionV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        session: &ExtImageCopyCaptureSessionV1,
+        event: ext_image_copy_capture_session_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let id = session.id();
+        match event {
+            ext_image_copy_capture_session_v1::Event::BufferSize { width, height } => {
+                let constraints = state.constraints.entry(id).or_default();
+                constraints.width = width;
+                constraints.height = height;
+            }
+            ext_image_copy_capture_session_v1::Event::ShmFormat {
+                format: WEnum::Value(format),
+            } => {
+                state
+                    .constraints
+                    .entry(id)
+                    .or_default()
+                    .formats
+                    .push(format);
+            }
+            ext_image_copy_capture_session_v1::Event::Done => {
+                let constraints = state.constraints.remove(&id).unwrap_or_default();
+                debug!(
+                    width = constraints.width,
+                    height = constraints.height,
+                    "Capture session constraints received"
+                );
+                state
+                    .events
+                    .push(WaylandEvent::CaptureReady(id, constraints));
+            }
+            ext_image_copy_capture_session_v1::Event::Stopped => {
+                state.constraints.remove(&id);
+                state.events.push(WaylandEvent::CaptureStopped(id));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtImageCopyCaptureFrameV1, ObjectId> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &ExtImageCopyCaptureFrameV1,
+        event: ext_image_copy_capture_frame_v1::Event,
+        session: &ObjectId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_image_copy_capture_frame_v1::Event::Ready => {
+                state.events.push(WaylandEvent::FrameReady(session.clone()));
+            }
+            ext_image_copy_capture_frame_v1::Event::Failed { reason } => {
+                debug!(reason = ?reason, "Frame capture failed");
+                state
+                    .events
+                    .push(WaylandEvent::FrameFailed(session.clone()));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwlrScreencopyFrameV1, ObjectId> for WaylandState {
+    fn event(
+        state: &mut Self,
+        frame: &ZwlrScreencopyFrameV1,
+        event: zwlr_screencopy_frame_v1::Event,
+        toplevel: &ObjectId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let id = frame.id();
+        match event {
+            zwlr_screencopy_frame_v1::Event::Buffer {
+                format,
+                width,
+                height,
+                stride,
+            } => {
+                let constraints = state.constraints.entry(id.clone()).or_default();
+                constraints.width = width;
+                constraints.height = height;
+                // Shm buffers are allocated with packed rows
+                if let WEnum::Value(format) = format
+                    && stride == width * 4
+                {
+                    constraints.formats.push(format);
+                }
+                // Before version 3 there is no buffer_done: the buffer event is the only offer
+                if frame.version() < 3 {
+                    let constraints = state.constraints.remove(&id).unwrap_or_default();
+                    state
+                        .events
+                        .push(WaylandEvent::RegionBuffer(toplevel.clone(), constraints));
+                }
+            }
+            zwlr_screencopy_frame_v1::Event::BufferDone => {
+                let constraints = state.constraints.remove(&id).unwrap_or_default();
+                state
+                    .events
+                    .push(WaylandEvent::RegionBuffer(toplevel.clone(), constraints));
+            }
+            zwlr_screencopy_frame_v1::Event::Flags {
+                flags: WEnum::Value(flags),
+            } if flags.contains(zwlr_screencopy_frame_v1::Flags::YInvert) => {
+                state.y_inverted.insert(id);
+            }
+            zwlr_screencopy_frame_v1::Event::Ready { .. } => {
+                let y_invert = state.y_inverted.remove(&id);
+                state.events.push(WaylandEvent::RegionReady {
+                    toplevel: toplevel.clone(),
+                    y_invert,
+                });
+            }
+            zwlr_screencopy_frame_v1::Event::Failed => {
+                state.constraints.remove(&id);
+                state.y_inverted.remove(&id);
+                debug!("Region capture failed");
+                state
+                    .events
+                    .push(WaylandEvent::RegionFailed(toplevel.clone()));
+            }
+            _ => {}
+        }
+    }
+}
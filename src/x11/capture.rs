@@ -0,0 +1,268 @@
+//! Still frame capture of client windows
+//!
+//! One implementation behind the `CaptureFrame` IPC request, the `screenshot` subcommand and the
+//! Manager's fallback previews. While the daemon has a window redirected with Composite,
+//! `GetImage` reads its offscreen contents, so covered clients are captured correctly. Without
+//! Composite only the visible parts of a window are current.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use x11rb::protocol::xproto::{ConnectionExt, ImageFormat, MapState, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::{CachedAtoms, get_client_list, is_window_eve};
+use crate::common::types::{ClientDetectionRule, EveWindowType};
+
+/// An RGBA image of a window's contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl Frame {
+    /// Converts a 32bpp BGRX image (X's 24/32-bit ZPixmap layout) to an opaque RGBA frame
+    pub fn from_bgrx(data: &[u8], width: usize, height: usize) -> Self {
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for i in (0..width * height).map(|p| p * 4) {
+            match data.get(i..i + 3) {
+                Some(&[b, g, r]) => rgba.extend_from_slice(&[r, g, b, 255]),
+                _ => rgba.extend_from_slice(&[0, 0, 0, 255]),
+            }
+        }
+        Self {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    /// Nearest-neighbour downscale to the largest size with the same aspect ratio that fits in
+    /// `max_size` (never upscales)
+    pub fn downscaled(&self, max_size: [u16; 2]) -> Self {
+        let dst = fit_within([self.width, self.height], max_size);
+        if dst == [self.width, self.height] {
+            return self.clone();
+        }
+        let mut rgba = Vec::with_capacity(dst[0] * dst[1] * 4);
+        for y in 0..dst[1] {
+            let sy = y * self.height / dst[1];
+            for x in 0..dst[0] {
+                let sx = x * self.width / dst[0];
+                let i = (sy * self.width + sx) * 4;
+                rgba.extend_from_slice(&self.rgba[i..i + 4]);
+            }
+        }
+        Self {
+            width: dst[0],
+            height: dst[1],
+            rgba,
+        }
+    }
+
+    /// Nearest-neighbour resize to exactly `width` x `height` (aspect ratio is not kept)
+    pub fn stretched(&self, width: usize, height: usize) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            let sy = y * self.height / height;
+            for x in 0..width {
+                let sx = x * self.width / width;
+                let i = (sy * self.width + sx) * 4;
+                rgba.extend_from_slice(&self.rgba[i..i + 4]);
+            }
+        }
+        Self {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    /// The `width` x `height` part at `x`, `y`, clipped to the frame (at least 1x1)
+    pub fn cropped(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let x = x.min(self.width.saturating_sub(1));
+        let y = y.min(self.height.saturating_sub(1));
+        let width = width.clamp(1, self.width - x);
+        let height = height.clamp(1, self.height - y);
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for row in y..y + height {
+            let start = (row * self.width + x) * 4;
+            rgba.extend_from_slice(&self.rgba[start..start + width * 4]);
+        }
+        Self {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    /// 32bpp BGRX image data for `PutImage`, the inverse of `from_bgrx`
+    pub fn to_bgrx(&self) -> Vec<u8> {
+        self.rgba
+            .chunks_exact(4)
+            .flat_map(|px| [px[2], px[1], px[0], 0])
+            .collect()
+    }
+
+    /// PNG encoding of the frame (fast compression; frames are large and short-lived)
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::Fast);
+        let mut writer = encoder
+            .write_header()
+            .context("Failed to write PNG header")?;
+        writer
+            .write_image_data(&self.rgba)
+            .context("Failed to encode frame as PNG")?;
+        writer.finish().context("Failed to finish PNG stream")?;
+        Ok(out)
+    }
+}
+
+/// Grabs the current contents of `window`. Returns None for unmapped or degenerate windows.
+pub fn capture_frame(conn: &RustConnection, window: Window) -> Result<Option<Frame>> {
+    let attrs = conn
+        .get_window_attributes(window)?
+        .reply()
+        .context(format!("Failed to get attributes of window {}", window))?;
+    if attrs.map_state != MapState::VIEWABLE {
+        return Ok(None);
+    }
+    let geom = conn
+        .get_geometry(window)?
+        .reply()
+        .context(format!("Failed to get geometry of window {}", window))?;
+    // 1x1 windows are not mapped yet; other depths don't use the 32bpp layout
+    if geom.width <= 1 || geom.height <= 1 || geom.depth < 24 {
+        return Ok(None);
+    }
+
+    let image = conn
+        .get_image(
+            ImageFormat::Z_PIXMAP,
+            window,
+            0,
+            0,
+            geom.width,
+            geom.height,
+            !0,
+        )?
+        .reply()
+        .context(format!("GetImage failed for window {}", window))?;
+
+    Ok(Some(Frame::from_bgrx(
+        &image.data,
+        geom.width as usize,
+        geom.height as usize,
+    )))
+}
+
+/// Captures the client logged in as `character` and writes it to `path` as PNG
+pub fn save_client_screenshot(
+    character: &str,
+    path: &Path,
+    rules: &[ClientDetectionRule],
+) -> Result<()> {
+    let (conn, _) = x11rb::connect(None).context("Failed to connect to X11 server")?;
+    let atoms = CachedAtoms::new(&conn).context("Failed to cache X11 atoms")?;
+
+    let window = get_client_list(&conn, &atoms)?
+        .into_iter()
+        .find(|&window| {
+            matches!(
+                is_window_eve(&conn, window, &atoms, rules),
+                Ok(Some(EveWindowType::LoggedIn(ref name))) if name == character
+            )
+        })
+        .ok_or_else(|| anyhow::anyhow!("No running EVE client for '{}'", character))?;
+
+    let frame = capture_frame(&conn, window)?
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not visible (minimized or unmapped)", character))?;
+    std::fs::write(path, frame.encode_png()?)
+        .context(format!("Failed to write screenshot to {}", path.display()))
+}
+
+/// Largest size with the source aspect ratio that fits in `max_size`
+fn fit_within(src: [usize; 2], max_size: [u16; 2]) -> [usize; 2] {
+    let scale = (max_size[0] as f32 / src[0] as f32)
+        .min(max_size[1] as f32 / src[1] as f32)
+        .min(1.0);
+    [
+        ((src[0] as f32 * scale) as usize).max(1),
+        ((src[1] as f32 * scale) as usize).max(1),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2x2 image: blue, green / red, white
+    const BGRX: [u8; 16] = [
+        255, 0, 0, 0, 0, 255, 0, 0, //
+        0, 0, 255, 0, 255, 255, 255, 0,
+    ];
+
+    #[test]
+    fn test_fit_within_keeps_aspect_ratio() {
+        assert_eq!(fit_within([1920, 1080], [480, 270]), [480, 270]);
+        assert_eq!(fit_within([1920, 1200], [480, 270]), [432, 270]);
+        // Never upscales
+        assert_eq!(fit_within([200, 100], [480, 270]), [200, 100]);
+    }
+
+    #[test]
+    fn test_frame_from_bgrx_and_downscale() {
+        let frame = Frame::from_bgrx(&BGRX, 2, 2);
+        assert_eq!(
+            frame.rgba,
+            [
+                0, 0, 255, 255, 0, 255, 0, 255, //
+                255, 0, 0, 255, 255, 255, 255, 255,
+            ]
+        );
+        let small = frame.downscaled([1, 1]);
+        assert_eq!((small.width, small.height), (1, 1));
+        assert_eq!(small.rgba, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_frame_stretch_and_bgrx_round_trip() {
+        let frame = Frame::from_bgrx(&BGRX, 2, 2);
+        assert_eq!(frame.to_bgrx(), BGRX);
+        let wide = frame.stretched(4, 1);
+        assert_eq!((wide.width, wide.height), (4, 1));
+        assert_eq!(&wide.rgba[..8], [0, 0, 255, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_frame_cropped() {
+        let frame = Frame::from_bgrx(&BGRX, 2, 2);
+        let right = frame.cropped(1, 0, 1, 2);
+        assert_eq!((right.width, right.height), (1, 2));
+        assert_eq!(right.rgba, [0, 255, 0, 255, 255, 255, 255, 255]);
+        // Clipped to the frame
+        let corner = frame.cropped(1, 1, 5, 5);
+        assert_eq!((corner.width, corner.height), (1, 1));
+        assert_eq!(corner.rgba, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_encode_png_round_trip() {
+        let frame = Frame::from_bgrx(&BGRX, 2, 2);
+        let png_bytes = frame.encode_png().unwrap();
+
+        let decoder = png::Decoder::new(std::io::Cursor::new(png_bytes));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (2, 2));
+        assert_eq!(&buf[..info.buffer_size()], frame.rgba.as_slice());
+    }
+}
@@ -1,11 +1,17 @@
 //! Application context and cached X11 state
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
+use tracing::{debug, warn};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::randr::{self, ConnectionExt as RandrExt};
 use x11rb::protocol::render::{ConnectionExt as RenderExt, Fixed, Pictformat};
 use x11rb::protocol::xproto::*;
 use x11rb::rust_connection::RustConnection;
 
-use crate::common::constants::{fixed_point, x11};
+use crate::common::constants::{defaults, fixed_point, x11};
 
 /// Application context holding immutable shared state
 pub struct AppContext<'a> {
@@ -13,6 +19,7 @@ pub struct AppContext<'a> {
     pub screen: &'a Screen,
     pub atoms: &'a CachedAtoms,
     pub formats: &'a CachedFormats,
+    pub monitors: &'a CachedMonitors,
 }
 
 /// Pre-cached X11 atoms to avoid repeated roundtrips
@@ -44,6 +51,12 @@ pub struct CachedAtoms {
     pub net_wm_window_type_dnd: Atom,
     pub net_wm_name: Atom,
     pub net_wm_visible_name: Atom,
+    pub net_moveresize_window: Atom,
+    pub utf8_string: Atom,
+    /// Set by us on tracked EVE clients: the character logged in (UTF-8)
+    pub epm_character: Atom,
+    /// Set by the user on windows that must never be tracked
+    pub epm_ignore: Atom,
 }
 
 impl CachedAtoms {
@@ -205,6 +218,30 @@ impl CachedAtoms {
                 .reply()
                 .context("Failed to get reply for _NET_WM_VISIBLE_NAME atom")?
                 .atom,
+            net_moveresize_window: conn
+                .intern_atom(false, b"_NET_MOVERESIZE_WINDOW")
+                .context("Failed to intern _NET_MOVERESIZE_WINDOW atom")?
+                .reply()
+                .context("Failed to get reply for _NET_MOVERESIZE_WINDOW atom")?
+                .atom,
+            utf8_string: conn
+                .intern_atom(false, b"UTF8_STRING")
+                .context("Failed to intern UTF8_STRING atom")?
+                .reply()
+                .context("Failed to get reply for UTF8_STRING atom")?
+                .atom,
+            epm_character: conn
+                .intern_atom(false, b"_EPM_CHARACTER")
+                .context("Failed to intern _EPM_CHARACTER atom")?
+                .reply()
+                .context("Failed to get reply for _EPM_CHARACTER atom")?
+                .atom,
+            epm_ignore: conn
+                .intern_atom(false, b"_EPM_IGNORE")
+                .context("Failed to intern _EPM_IGNORE atom")?
+                .reply()
+                .context("Failed to get reply for _EPM_IGNORE atom")?
+                .atom,
         })
     }
 }
@@ -214,6 +251,8 @@ impl CachedAtoms {
 pub struct CachedFormats {
     pub rgb: Pictformat,
     pub argb: Pictformat,
+    /// 16-bit R5G6B5 format, if the server has one and can create 16-bit pixmaps
+    pub rgb16: Option<Pictformat>,
 }
 
 impl CachedFormats {
@@ -238,8 +277,210 @@ impl CachedFormats {
             .ok_or_else(|| anyhow::anyhow!("No ARGB format found for depth {}", x11::ARGB_DEPTH))?
             .id;
 
-        Ok(Self { rgb, argb })
+        let has_16bpp_pixmaps = conn
+            .setup()
+            .pixmap_formats
+            .iter()
+            .any(|f| f.depth == 16 && f.bits_per_pixel == 16);
+        let rgb16 = formats_reply
+            .formats
+            .iter()
+            .find(|f| {
+                let d = &f.direct;
+                f.depth == 16
+                    && (d.red_shift, d.red_mask) == (11, 0x1f)
+                    && (d.green_shift, d.green_mask) == (5, 0x3f)
+                    && (d.blue_shift, d.blue_mask) == (0, 0x1f)
+                    && d.alpha_mask == 0
+            })
+            .filter(|_| has_16bpp_pixmaps)
+            .map(|f| f.id);
+
+        Ok(Self { rgb, argb, rgb16 })
+    }
+}
+
+/// Geometry of one monitor in root coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Monitor {
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    /// Physical width in millimetres as reported by the display (0 = unknown)
+    pub width_mm: u32,
+    /// RandR monitor name (`NONE` without RandR 1.5)
+    pub name: Atom,
+}
+
+impl Monitor {
+    /// HiDPI scale from the physical pixel density, in half steps from 1.0 (96 DPI) up.
+    /// Unknown or implausible physical sizes (projectors, some TVs) count as 1.0.
+    pub fn dpi_scale(&self) -> f32 {
+        if self.width_mm == 0 {
+            return 1.0;
+        }
+        let dpi = f32::from(self.width) * 25.4 / self.width_mm as f32;
+        if !(defaults::thumbnail::MIN_PLAUSIBLE_DPI..=defaults::thumbnail::MAX_PLAUSIBLE_DPI)
+            .contains(&dpi)
+        {
+            return 1.0;
+        }
+        ((dpi / defaults::thumbnail::REFERENCE_DPI * 2.0).round() / 2.0)
+            .clamp(1.0, defaults::thumbnail::MAX_DPI_SCALE)
     }
+
+    pub fn contains(&self, x: i16, y: i16) -> bool {
+        x >= self.x
+            && y >= self.y
+            && (x as i32) < self.x as i32 + self.width as i32
+            && (y as i32) < self.y as i32 + self.height as i32
+    }
+}
+
+/// Monitor layout from RandR, refreshed when outputs change. Without RandR 1.5 the whole
+/// root window counts as one monitor.
+#[derive(Debug)]
+pub struct CachedMonitors {
+    root: Window,
+    randr: bool,
+    /// Physical screen width, for the single-monitor fallback
+    screen_width_mm: u32,
+    monitors: RefCell<Vec<Monitor>>,
+    /// Resolved RandR monitor names (output names such as `DP-1`)
+    names: RefCell<HashMap<Atom, String>>,
+}
+
+impl CachedMonitors {
+    /// Queries the monitor layout and subscribes the root window to RandR change notifications
+    pub fn new(conn: &RustConnection, screen: &Screen) -> Self {
+        let randr = has_randr_monitors(conn);
+        if randr
+            && let Err(e) = conn.randr_select_input(
+                screen.root,
+                randr::NotifyMask::SCREEN_CHANGE
+                    | randr::NotifyMask::CRTC_CHANGE
+                    | randr::NotifyMask::OUTPUT_CHANGE,
+            )
+        {
+            warn!(error = %e, "Failed to subscribe to RandR notifications");
+        }
+        if !randr {
+            debug!("X server lacks RandR 1.5, treating the screen as one monitor");
+        }
+
+        let monitors = Self {
+            root: screen.root,
+            randr,
+            screen_width_mm: u32::from(screen.width_in_millimeters),
+            monitors: RefCell::new(Vec::new()),
+            names: RefCell::new(HashMap::new()),
+        };
+        monitors.refresh(conn);
+        monitors
+    }
+
+    /// Re-reads the monitor layout, keeping the previous one if the query fails
+    pub fn refresh(&self, conn: &RustConnection) {
+        match self.query(conn) {
+            Ok(monitors) => {
+                debug!(monitors = ?monitors, "Monitor layout updated");
+                self.resolve_names(conn, &monitors);
+                *self.monitors.borrow_mut() = monitors;
+            }
+            Err(e) => warn!(error = %e, "Failed to query monitor layout"),
+        }
+    }
+
+    /// Looks up the names of monitors not seen before
+    fn resolve_names(&self, conn: &RustConnection, monitors: &[Monitor]) {
+        let mut names = self.names.borrow_mut();
+        for monitor in monitors {
+            if monitor.name == x11rb::NONE || names.contains_key(&monitor.name) {
+                continue;
+            }
+            let name = conn
+                .get_atom_name(monitor.name)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| String::from_utf8_lossy(&reply.name).into_owned());
+            match name {
+                Some(name) => {
+                    names.insert(monitor.name, name);
+                }
+                None => warn!(atom = monitor.name, "Failed to resolve monitor name"),
+            }
+        }
+    }
+
+    fn query(&self, conn: &RustConnection) -> Result<Vec<Monitor>> {
+        if self.randr {
+            let reply = conn
+                .randr_get_monitors(self.root, true)
+                .context("Failed to send RandR monitor query")?
+                .reply()
+                .context("Failed to get RandR monitors reply")?;
+            if !reply.monitors.is_empty() {
+                return Ok(reply
+                    .monitors
+                    .iter()
+                    .map(|m| Monitor {
+                        x: m.x,
+                        y: m.y,
+                        width: m.width,
+                        height: m.height,
+                        width_mm: m.width_in_millimeters,
+                        name: m.name,
+                    })
+                    .collect());
+            }
+        }
+        let root = conn
+            .get_geometry(self.root)
+            .context("Failed to send root geometry query")?
+            .reply()
+            .context("Failed to get root geometry reply")?;
+        Ok(vec![Monitor {
+            x: 0,
+            y: 0,
+            width: root.width,
+            height: root.height,
+            width_mm: self.screen_width_mm,
+            name: x11rb::NONE,
+        }])
+    }
+
+    /// All monitors in RandR order
+    pub fn all(&self) -> Vec<Monitor> {
+        self.monitors.borrow().clone()
+    }
+
+    /// RandR name of a monitor, used to key per-monitor settings
+    pub fn name(&self, monitor: &Monitor) -> Option<String> {
+        self.names.borrow().get(&monitor.name).cloned()
+    }
+
+    /// Monitor containing the point, or the first monitor when the point is off-screen
+    pub fn at(&self, x: i16, y: i16) -> Option<Monitor> {
+        let monitors = self.monitors.borrow();
+        monitors
+            .iter()
+            .find(|m| m.contains(x, y))
+            .or(monitors.first())
+            .copied()
+    }
+}
+
+fn has_randr_monitors(conn: &RustConnection) -> bool {
+    conn.extension_information(randr::X11_EXTENSION_NAME)
+        .ok()
+        .flatten()
+        .is_some()
+        && conn
+            .randr_query_version(1, 5)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|v| (v.major_version, v.minor_version) >= (1, 5))
 }
 
 /// Converts standard float values to the 16.16 fixed-point format required by the X11 Render extension
@@ -251,6 +492,26 @@ pub fn to_fixed(v: f32) -> Fixed {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_monitor_dpi_scale() {
+        let monitor = |width, width_mm| Monitor {
+            x: 0,
+            y: 0,
+            width,
+            height: 0,
+            width_mm,
+            name: x11rb::NONE,
+        };
+        // 24" 1080p, 27" 1440p, 27" 4K, 15" 4K laptop
+        assert_eq!(monitor(1920, 531).dpi_scale(), 1.0);
+        assert_eq!(monitor(2560, 597).dpi_scale(), 1.0);
+        assert_eq!(monitor(3840, 597).dpi_scale(), 1.5);
+        assert_eq!(monitor(3840, 344).dpi_scale(), 3.0);
+        // Unknown or bogus physical size
+        assert_eq!(monitor(3840, 0).dpi_scale(), 1.0);
+        assert_eq!(monitor(3840, 16).dpi_scale(), 1.0);
+    }
+
     #[test]
     fn test_to_fixed_whole_numbers() {
         assert_eq!(to_fixed(1.0), 65536);
@@ -0,0 +1,238 @@
+//! Verified window activation
+//!
+//! Some window managers drop `_NET_ACTIVE_WINDOW` requests whose timestamp is older than the
+//! last focus change, and evdev/portal hotkeys don't carry X server timestamps at all. So the
+//! timestamp is replaced with a fresh server time when it is missing or implausible, the result
+//! is checked by reading `_NET_ACTIVE_WINDOW` back, and a request the WM ignored is retried
+//! once with `XSetInputFocus` before giving up. The read-backs are driven by a main loop timer
+//! so the event loop keeps running while the window manager reacts.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+use x11rb::connection::Connection;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::*;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+use super::{CachedAtoms, activate_window, get_active_window};
+use crate::common::constants::x11;
+
+/// Private connection used to read the server clock. Its events never reach the main loop.
+static CLOCK: Mutex<Option<ServerClock>> = Mutex::new(None);
+
+/// Reads the X server time by touching a property on an unmapped window and reading the
+/// timestamp of the resulting `PropertyNotify`
+struct ServerClock {
+    conn: RustConnection,
+    window: Window,
+}
+
+impl ServerClock {
+    fn connect() -> Result<Self> {
+        let (conn, screen_num) =
+            x11rb::connect(None).context("Failed to connect to X11 for the server clock")?;
+        let root = conn.setup().roots[screen_num].root;
+        let window = conn.generate_id()?;
+        conn.create_window(
+            0,
+            window,
+            root,
+            -1,
+            -1,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_ONLY,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )?
+        .check()
+        .context("Failed to create server clock window")?;
+        Ok(Self { conn, window })
+    }
+
+    fn now(&self) -> Result<Timestamp> {
+        // A zero-length append changes nothing but still reports the server time
+        self.conn.change_property8(
+            PropMode::APPEND,
+            self.window,
+            AtomEnum::WM_NAME,
+            AtomEnum::STRING,
+            &[],
+        )?;
+        self.conn.flush()?;
+        loop {
+            if let Event::PropertyNotify(event) = self.conn.wait_for_event()?
+                && event.window == self.window
+            {
+                return Ok(event.time);
+            }
+        }
+    }
+}
+
+/// Current X server time. A clock whose connection failed is dropped and reconnected on the
+/// next call.
+fn server_time() -> Result<Timestamp> {
+    let mut clock = CLOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let current = match clock.take() {
+        Some(current) => current,
+        None => ServerClock::connect()?,
+    };
+    let now = current.now()?;
+    *clock = Some(current);
+    Ok(now)
+}
+
+/// Whether `timestamp` can't be trusted as an activation time at server time `now`: missing
+/// (`CURRENT_TIME`), ahead of the server (not a server timestamp) or older than the focus
+/// timestamp limit
+fn is_stale(timestamp: Timestamp, now: Timestamp) -> bool {
+    timestamp == x11rb::CURRENT_TIME
+        || now.wrapping_sub(timestamp) > x11::FOCUS_TIMESTAMP_MAX_AGE_MS
+}
+
+/// An activation whose effect has not been confirmed yet
+#[derive(Debug, Clone, Copy)]
+pub struct PendingFocus {
+    pub window: Window,
+    timestamp: Timestamp,
+    /// The `XSetInputFocus` fallback has been tried
+    retried: bool,
+    /// When the current attempt is given up on
+    deadline: Instant,
+    /// When focus is read back next
+    pub next_check: Instant,
+}
+
+/// Result of reading back a pending activation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusCheck {
+    Focused,
+    /// Not focused yet; check again at `next_check`
+    Waiting,
+    /// Both attempts failed
+    Failed,
+}
+
+/// Activates `window` like `activate_window`, with a trustworthy timestamp. Pass the returned
+/// activation to `check_focus` until it is settled.
+pub fn focus_window(
+    conn: &RustConnection,
+    screen: &Screen,
+    atoms: &CachedAtoms,
+    window: Window,
+    timestamp: Timestamp,
+) -> Result<PendingFocus> {
+    let timestamp = match server_time() {
+        Ok(now) if is_stale(timestamp, now) => {
+            debug!(
+                window = window,
+                timestamp = timestamp,
+                server_time = now,
+                "Using fresh server time for activation"
+            );
+            now
+        }
+        Ok(_) => timestamp,
+        Err(e) => {
+            debug!(error = %e, "Failed to read X server time, using event timestamp");
+            timestamp
+        }
+    };
+
+    activate_window(conn, screen, atoms, window, timestamp)?;
+    let now = Instant::now();
+    Ok(PendingFocus {
+        window,
+        timestamp,
+        retried: false,
+        deadline: now + Duration::from_millis(x11::FOCUS_VERIFY_TIMEOUT_MS),
+        next_check: now + Duration::from_millis(x11::FOCUS_VERIFY_POLL_MS),
+    })
+}
+
+/// Checks whether `pending` is the active window (or holds input focus). Once the window
+/// manager has ignored the request for the verify timeout, it is retried once with
+/// `XSetInputFocus`, which is only sent to a viewable window (anything else is a BadMatch).
+pub fn check_focus(
+    conn: &RustConnection,
+    screen: &Screen,
+    atoms: &CachedAtoms,
+    pending: &mut PendingFocus,
+) -> Result<FocusCheck> {
+    let window = pending.window;
+    if get_active_window(conn, screen, atoms)? == Some(window)
+        || conn.get_input_focus()?.reply()?.focus == window
+    {
+        return Ok(FocusCheck::Focused);
+    }
+
+    let now = Instant::now();
+    pending.next_check = now + Duration::from_millis(x11::FOCUS_VERIFY_POLL_MS);
+    if now < pending.deadline {
+        return Ok(FocusCheck::Waiting);
+    }
+
+    if pending.retried {
+        let active = get_active_window(conn, screen, atoms)?;
+        warn!(
+            window = window,
+            active = ?active,
+            timestamp = pending.timestamp,
+            "Focus did not move after _NET_ACTIVE_WINDOW and XSetInputFocus"
+        );
+        return Ok(FocusCheck::Failed);
+    }
+    pending.retried = true;
+    pending.deadline = now + Duration::from_millis(x11::FOCUS_VERIFY_TIMEOUT_MS);
+
+    let viewable = conn
+        .get_window_attributes(window)?
+        .reply()
+        .context(format!("Failed to get attributes of window {}", window))?
+        .map_state
+        == MapState::VIEWABLE;
+    if !viewable {
+        debug!(
+            window = window,
+            "Window manager ignored _NET_ACTIVE_WINDOW and the window is not viewable, waiting"
+        );
+        return Ok(FocusCheck::Waiting);
+    }
+
+    debug!(
+        window = window,
+        "Window manager ignored _NET_ACTIVE_WINDOW, retrying with XSetInputFocus"
+    );
+    if let Err(e) = conn
+        .set_input_focus(InputFocus::PARENT, window, pending.timestamp)?
+        .check()
+    {
+        warn!(window = window, error = %e, "XSetInputFocus failed");
+        return Ok(FocusCheck::Failed);
+    }
+    Ok(FocusCheck::Waiting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stale() {
+        let now = 1_000_000;
+        assert!(!is_stale(now, now));
+        assert!(!is_stale(now - x11::FOCUS_TIMESTAMP_MAX_AGE_MS, now));
+        assert!(is_stale(now - x11::FOCUS_TIMESTAMP_MAX_AGE_MS - 1, now));
+        assert!(is_stale(x11rb::CURRENT_TIME, now));
+        // Unix-time timestamps from evdev are ahead of the server clock
+        assert!(is_stale(now + 1, now));
+        // Server time wraps after ~49 days
+        assert!(!is_stale(u32::MAX, 5));
+    }
+}
@@ -0,0 +1,172 @@
+//! Broadcast guard and audit log for synthetic input
+//!
+//! Synthetic input only ever goes to the single client the user acted on: a pointer-state
+//! refresh after activating it, or a passthrough click. Replaying one action into several
+//! clients is input broadcasting, which the EVE EULA forbids, so every synthetic event is
+//! checked here first. Anything that would fan one user action out to a second client is
+//! refused. Both sent and refused events are kept in an audit log for diagnostics.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use tracing::warn;
+use x11rb::protocol::xproto::Window;
+
+use crate::common::ipc::{SyntheticInput, SyntheticInputKind};
+
+/// Two clicks into different clients closer together than this look like one action replayed
+const BROADCAST_WINDOW: Duration = Duration::from_millis(250);
+
+/// Audit entries kept until they are drained
+const AUDIT_CAPACITY: usize = 128;
+
+static GUARD: Mutex<InputGuard> = Mutex::new(InputGuard::new());
+
+/// Checks a synthetic event about to be sent and records it in the audit log.
+///
+/// Returns an error (and sends nothing) when it would broadcast one user action to a second client.
+pub fn permit(kind: SyntheticInputKind, window: Window, timestamp: u32) -> Result<()> {
+    let mut guard = GUARD.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.permit(kind, window, timestamp, Instant::now()) {
+        return Ok(());
+    }
+    warn!(
+        window = window,
+        kind = kind.label(),
+        timestamp = timestamp,
+        "Refused synthetic input that would reach a second client (input broadcasting)"
+    );
+    anyhow::bail!(
+        "Refused {} to window {}: it would repeat an action already sent to another client",
+        kind.label(),
+        window
+    )
+}
+
+/// Takes the audit entries recorded since the last call
+pub fn drain_audit() -> Vec<SyntheticInput> {
+    let mut guard = GUARD.lock().unwrap_or_else(|e| e.into_inner());
+    guard.audit.drain(..).collect()
+}
+
+struct Sent {
+    at: Instant,
+    kind: SyntheticInputKind,
+    window: Window,
+    timestamp: u32,
+}
+
+struct InputGuard {
+    /// Events permitted within the last `BROADCAST_WINDOW` (plus the latest one)
+    recent: VecDeque<Sent>,
+    audit: VecDeque<SyntheticInput>,
+}
+
+impl InputGuard {
+    const fn new() -> Self {
+        Self {
+            recent: VecDeque::new(),
+            audit: VecDeque::new(),
+        }
+    }
+
+    fn permit(
+        &mut self,
+        kind: SyntheticInputKind,
+        window: Window,
+        timestamp: u32,
+        now: Instant,
+    ) -> bool {
+        // Keep the latest event regardless of age so a reused X timestamp is always caught
+        while self.recent.len() > 1
+            && self
+                .recent
+                .front()
+                .is_some_and(|sent| now.duration_since(sent.at) > BROADCAST_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+
+        let broadcast = self.recent.iter().any(|sent| {
+            sent.window != window
+                // The same user event (X timestamp) driving input into two clients
+                && ((timestamp != 0 && sent.timestamp == timestamp)
+                    // Clicks landing in different clients faster than a person could aim them
+                    || (kind.is_click()
+                        && sent.kind.is_click()
+                        && now.duration_since(sent.at) <= BROADCAST_WINDOW))
+        });
+
+        if !broadcast {
+            self.recent.push_back(Sent {
+                at: now,
+                kind,
+                window,
+                timestamp,
+            });
+        }
+
+        if self.audit.len() >= AUDIT_CAPACITY {
+            self.audit.pop_front();
+        }
+        self.audit.push_back(SyntheticInput {
+            unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            kind,
+            window,
+            refused: broadcast,
+        });
+
+        !broadcast
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLICK: SyntheticInputKind = SyntheticInputKind::Click { button: 1 };
+
+    #[test]
+    fn test_single_client_input_is_permitted() {
+        let mut guard = InputGuard::new();
+        let now = Instant::now();
+        assert!(guard.permit(SyntheticInputKind::PointerRefresh, 1, 100, now));
+        assert!(guard.permit(CLICK, 1, 200, now));
+        assert!(guard.permit(CLICK, 1, 201, now + Duration::from_millis(50)));
+        assert_eq!(guard.audit.len(), 3);
+        assert!(guard.audit.iter().all(|entry| !entry.refused));
+    }
+
+    #[test]
+    fn test_same_timestamp_to_second_client_is_refused() {
+        let mut guard = InputGuard::new();
+        let now = Instant::now();
+        assert!(guard.permit(SyntheticInputKind::PointerRefresh, 1, 100, now));
+        assert!(!guard.permit(
+            SyntheticInputKind::PointerRefresh,
+            2,
+            100,
+            now + Duration::from_secs(5)
+        ));
+        // CurrentTime (0) carries no identity, so sequential activations stay allowed
+        let later = now + Duration::from_secs(6);
+        assert!(guard.permit(SyntheticInputKind::PointerRefresh, 3, 0, later));
+        assert!(guard.permit(SyntheticInputKind::PointerRefresh, 4, 0, later));
+        assert!(guard.audit[1].refused);
+    }
+
+    #[test]
+    fn test_rapid_clicks_across_clients_are_refused() {
+        let mut guard = InputGuard::new();
+        let now = Instant::now();
+        assert!(guard.permit(CLICK, 1, 100, now));
+        assert!(!guard.permit(CLICK, 2, 101, now + Duration::from_millis(100)));
+        // A deliberate click on another thumbnail a moment later is fine
+        assert!(guard.permit(CLICK, 2, 102, now + Duration::from_millis(400)));
+    }
+}
@@ -1,9 +1,16 @@
 //! X11 u window detection.
 
+mod capture;
 mod context;
+mod focus;
+pub mod input_guard;
 mod ops;
 mod query;
+mod shm;
 
-pub use context::{AppContext, CachedAtoms, CachedFormats, to_fixed};
+pub use capture::{Frame, capture_frame, save_client_screenshot};
+pub use context::{AppContext, CachedAtoms, CachedFormats, CachedMonitors, Monitor, to_fixed};
+pub use focus::{FocusCheck, PendingFocus, check_focus, focus_window};
 pub use ops::*;
 pub use query::*;
+pub use shm::{ShmReader, put_image, supports_attach_fd};
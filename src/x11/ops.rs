@@ -7,9 +7,12 @@ use x11rb::protocol::xproto::{
     ConnectionExt, KeyButMask, MOTION_NOTIFY_EVENT, Motion, MotionNotifyEvent,
 };
 use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
 
-use super::CachedAtoms;
+use super::{CachedAtoms, input_guard};
 use crate::common::constants::x11;
+use crate::common::ipc::SyntheticInputKind;
+use crate::common::types::{Dimensions, Position};
 
 /// Requests the window manager to grant focus to the specified window using standard EWMH protocols
 ///
@@ -57,6 +60,42 @@ pub fn activate_window(
     Ok(())
 }
 
+/// Publishes the character logged in on a tracked EVE client as `_EPM_CHARACTER` (UTF-8), for
+/// scripts and window manager rules. An empty name (logged out) removes the property.
+pub fn set_character_marker(
+    conn: &RustConnection,
+    atoms: &CachedAtoms,
+    window: Window,
+    character_name: &str,
+) -> Result<()> {
+    if character_name.is_empty() {
+        return clear_character_marker(conn, atoms, window);
+    }
+    conn.change_property8(
+        PropMode::REPLACE,
+        window,
+        atoms.epm_character,
+        atoms.utf8_string,
+        character_name.as_bytes(),
+    )
+    .context(format!("Failed to set _EPM_CHARACTER on window {}", window))?;
+    Ok(())
+}
+
+/// Removes `_EPM_CHARACTER` from a window the daemon stops tracking
+pub fn clear_character_marker(
+    conn: &RustConnection,
+    atoms: &CachedAtoms,
+    window: Window,
+) -> Result<()> {
+    conn.delete_property(window, atoms.epm_character)
+        .context(format!(
+            "Failed to delete _EPM_CHARACTER on window {}",
+            window
+        ))?;
+    Ok(())
+}
+
 /// Requests the window manager to hide/minimize the window using EWMH status flags
 pub fn minimize_window(
     conn: &RustConnection,
@@ -176,6 +215,134 @@ pub fn unminimize_window(
     Ok(())
 }
 
+/// Asks the window manager to move and resize a client window (`_NET_MOVERESIZE_WINDOW`).
+/// Coordinates are those of the client window itself, as returned by `get_window_geometry`.
+pub fn move_resize_window(
+    conn: &RustConnection,
+    screen: &Screen,
+    atoms: &CachedAtoms,
+    window: Window,
+    position: Position,
+    dimensions: Dimensions,
+) -> Result<()> {
+    let event = ClientMessageEvent {
+        response_type: CLIENT_MESSAGE_EVENT,
+        format: 32,
+        sequence: 0,
+        window,
+        type_: atoms.net_moveresize_window,
+        data: ClientMessageData::from([
+            x11::MOVERESIZE_STATIC_ALL,
+            position.x as i32 as u32,
+            position.y as i32 as u32,
+            dimensions.width.into(),
+            dimensions.height.into(),
+        ]),
+    };
+
+    conn.send_event(
+        false,
+        screen.root,
+        EventMask::SUBSTRUCTURE_NOTIFY | EventMask::SUBSTRUCTURE_REDIRECT,
+        event,
+    )
+    .context(format!(
+        "Failed to send _NET_MOVERESIZE_WINDOW event for window {}",
+        window
+    ))?;
+
+    conn.flush()
+        .context("Failed to flush X11 connection after window move")?;
+    Ok(())
+}
+
+/// Sends a synthetic button press and release at `(x, y)` in `window` without moving the
+/// pointer or changing focus.
+///
+/// The events go to the deepest child under the point, since that is the window a real click
+/// would be delivered to. Clients are free to ignore synthetic (SendEvent) input.
+pub fn send_click(
+    conn: &RustConnection,
+    screen: &Screen,
+    window: Window,
+    x: i16,
+    y: i16,
+    button: u8,
+    timestamp: u32,
+) -> Result<()> {
+    let (mut target, mut event_x, mut event_y) = (window, x, y);
+    loop {
+        let child = conn
+            .translate_coordinates(target, target, event_x, event_y)?
+            .reply()
+            .context(format!(
+                "Failed to find child of window {} under point",
+                target
+            ))?
+            .child;
+        if child == x11rb::NONE {
+            break;
+        }
+        let reply = conn
+            .translate_coordinates(target, child, event_x, event_y)?
+            .reply()
+            .context(format!("Failed to translate point into window {}", child))?;
+        (target, event_x, event_y) = (child, reply.dst_x, reply.dst_y);
+    }
+    input_guard::permit(SyntheticInputKind::Click { button }, window, timestamp)?;
+
+    let root = conn
+        .translate_coordinates(target, screen.root, event_x, event_y)?
+        .reply()
+        .context("Failed to translate click point to root coordinates")?;
+
+    let press = ButtonPressEvent {
+        response_type: BUTTON_PRESS_EVENT,
+        detail: button,
+        sequence: 0,
+        time: timestamp,
+        root: screen.root,
+        event: target,
+        child: x11rb::NONE,
+        root_x: root.dst_x,
+        root_y: root.dst_y,
+        event_x,
+        event_y,
+        state: KeyButMask::default(),
+        same_screen: true,
+    };
+    let release = ButtonReleaseEvent {
+        response_type: BUTTON_RELEASE_EVENT,
+        // The release carries the pressed button in its state (Button1Mask..Button5Mask)
+        state: KeyButMask::from(
+            u16::from(KeyButMask::BUTTON1) << button.clamp(1, 5).saturating_sub(1),
+        ),
+        ..press
+    };
+
+    conn.send_event(true, target, EventMask::BUTTON_PRESS, press)
+        .context(format!(
+            "Failed to send synthetic ButtonPress to window {}",
+            target
+        ))?;
+    conn.send_event(true, target, EventMask::BUTTON_RELEASE, release)
+        .context(format!(
+            "Failed to send synthetic ButtonRelease to window {}",
+            target
+        ))?;
+    conn.flush()
+        .context("Failed to flush X11 connection after synthetic click")?;
+
+    tracing::debug!(
+        window = window,
+        target = target,
+        x = event_x,
+        y = event_y,
+        "Forwarded synthetic click"
+    );
+    Ok(())
+}
+
 /// Injects a synthetic MotionNotify event to force the client to re-evaluate the cursor position.
 ///
 /// This is necessary for XWayland compatibility (e.g., Wine/Proton games) where clients
@@ -183,6 +350,8 @@ pub fn unminimize_window(
 ///
 /// Use this instead of WarpPointer on Wayland sessions.
 fn refresh_pointer_state(conn: &RustConnection, window: Window, timestamp: u32) -> Result<()> {
+    input_guard::permit(SyntheticInputKind::PointerRefresh, window, timestamp)?;
+
     // Construct a synthetic MotionNotify event
     // The goal is to tell the client "the mouse is right here" without moving it physically.
     let motion_event = MotionNotifyEvent {
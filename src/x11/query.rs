@@ -8,49 +8,80 @@ use x11rb::protocol::xproto::*;
 use x11rb::rust_connection::RustConnection;
 
 use super::CachedAtoms;
-use crate::common::constants::{eve, x11};
-use crate::common::types::EveWindowType;
+use crate::common::constants::x11;
+use crate::common::types::{
+    ClientDetectionRule, Dimensions, EveWindowType, Position, identify_client,
+};
 
-/// Identifies if a window belongs to EVE Online by inspecting its properties and title
+/// Identifies if a window is a game client by matching its title (and class, when a rule needs
+/// it) against the detection rules
 pub fn is_window_eve(
     conn: &RustConnection,
     window: Window,
     atoms: &CachedAtoms,
+    rules: &[ClientDetectionRule],
 ) -> Result<Option<EveWindowType>> {
-    let cookie = conn
-        .get_property(false, window, atoms.wm_name, AtomEnum::STRING, 0, 1024)
-        .context(format!(
-            "Failed to query WM_NAME property for window {}",
-            window
-        ))?;
-    let name_prop = match cookie.reply() {
-        Ok(reply) => reply,
-        Err(ReplyError::X11Error(err)) if err.error_kind == x11rb::protocol::ErrorKind::Window => {
-            debug!(
-                window = window,
-                "Window destroyed before WM_NAME reply, skipping"
-            );
-            return Ok(None);
-        }
-        Err(err) => {
-            return Err(err).context(format!("Failed to get WM_NAME reply for window {}", window));
-        }
+    let Some(title) = get_window_title(conn, window, atoms)? else {
+        return Ok(None);
     };
-    let title = String::from_utf8_lossy(&name_prop.value).into_owned();
-    Ok(
-        if let Some(name) = title.strip_prefix(eve::WINDOW_TITLE_PREFIX) {
-            if name.to_lowercase().contains("steam_app_") {
-                debug!(window=window, name=%name, "Ignored steam_app container title");
-                None
-            } else {
-                Some(EveWindowType::LoggedIn(name.to_string()))
+    let class = if rules.iter().any(ClientDetectionRule::needs_class) {
+        get_window_class(conn, window, atoms)?
+    } else {
+        None
+    };
+    Ok(identify_client(rules, &title, class.as_deref()))
+}
+
+/// Title of a window: `_NET_WM_NAME` (UTF-8) if set, otherwise `WM_NAME` in whatever encoding
+/// the client used. None if the window is gone.
+pub fn get_window_title(
+    conn: &RustConnection,
+    window: Window,
+    atoms: &CachedAtoms,
+) -> Result<Option<String>> {
+    for (property, kind, label) in [
+        (atoms.net_wm_name, atoms.utf8_string, "_NET_WM_NAME"),
+        (atoms.wm_name, AtomEnum::ANY.into(), "WM_NAME"),
+    ] {
+        let cookie = conn
+            .get_property(false, window, property, kind, 0, 1024)
+            .context(format!(
+                "Failed to query {} property for window {}",
+                label, window
+            ))?;
+        let reply = match cookie.reply() {
+            Ok(reply) => reply,
+            Err(ReplyError::X11Error(err))
+                if err.error_kind == x11rb::protocol::ErrorKind::Window =>
+            {
+                debug!(
+                    window = window,
+                    "Window destroyed before {} reply, skipping", label
+                );
+                return Ok(None);
             }
-        } else if title == eve::LOGGED_OUT_TITLE {
-            Some(EveWindowType::LoggedOut)
-        } else {
-            None
-        },
-    )
+            Err(err) => {
+                return Err(err).context(format!(
+                    "Failed to get {} reply for window {}",
+                    label, window
+                ));
+            }
+        };
+        if !reply.value.is_empty() {
+            return Ok(Some(decode_text_property(reply.type_, &reply.value)));
+        }
+    }
+    Ok(Some(String::new()))
+}
+
+/// Decodes a text property: `STRING` is Latin-1 by definition, `UTF8_STRING` is UTF-8, and
+/// `COMPOUND_TEXT` is read as UTF-8 too (it matches for the ASCII titles that use it)
+fn decode_text_property(kind: Atom, value: &[u8]) -> String {
+    if kind == u32::from(AtomEnum::STRING) {
+        value.iter().map(|&b| char::from(b)).collect()
+    } else {
+        String::from_utf8_lossy(value).into_owned()
+    }
 }
 
 /// Get the WM_CLASS property of a window (returns the second string, which is the class name)
@@ -161,15 +192,70 @@ pub fn is_window_minimized(
     Ok(false)
 }
 
+/// Whether the user marked the window with `_EPM_IGNORE` (any type or value) to keep it
+/// untracked, e.g. `xprop -id <window> -f _EPM_IGNORE 32c -set _EPM_IGNORE 1`
+pub fn is_window_ignored(
+    conn: &RustConnection,
+    window: Window,
+    atoms: &CachedAtoms,
+) -> Result<bool> {
+    let cookie = conn
+        .get_property(false, window, atoms.epm_ignore, AtomEnum::ANY, 0, 0)
+        .context(format!("Failed to query _EPM_IGNORE for window {}", window))?;
+    match cookie.reply() {
+        Ok(reply) => Ok(reply.type_ != u32::from(AtomEnum::NONE)),
+        Err(ReplyError::X11Error(err)) if err.error_kind == x11rb::protocol::ErrorKind::Window => {
+            debug!(window = window, "Window destroyed before _EPM_IGNORE reply");
+            Ok(false)
+        }
+        Err(err) => Err(err).context(format!(
+            "Failed to get _EPM_IGNORE reply for window {}",
+            window
+        )),
+    }
+}
+
+/// Position (root coordinates, excluding the WM frame) and size of a client window
+pub fn get_window_geometry(
+    conn: &RustConnection,
+    root: Window,
+    window: Window,
+) -> Result<(Position, Dimensions)> {
+    let geometry = conn
+        .get_geometry(window)
+        .context(format!("Failed to query geometry of window {}", window))?
+        .reply()
+        .context(format!(
+            "Failed to get geometry reply for window {}",
+            window
+        ))?;
+    let origin = conn
+        .translate_coordinates(window, root, 0, 0)
+        .context(format!(
+            "Failed to translate coordinates of window {}",
+            window
+        ))?
+        .reply()
+        .context(format!(
+            "Failed to get coordinate translation for window {}",
+            window
+        ))?;
+    Ok((
+        Position::new(origin.dst_x, origin.dst_y),
+        Dimensions::new(geometry.width, geometry.height),
+    ))
+}
+
 pub fn get_active_eve_window(
     conn: &RustConnection,
     screen: &Screen,
     atoms: &CachedAtoms,
+    rules: &[ClientDetectionRule],
 ) -> Result<Option<Window>> {
     let active_window = get_active_window(conn, screen, atoms)?;
 
     if let Some(active_window) = active_window {
-        if is_window_eve(conn, active_window, atoms)
+        if is_window_eve(conn, active_window, atoms, rules)
             .context(format!(
                 "Failed to check if active window {} is EVE client",
                 active_window
@@ -312,3 +398,70 @@ pub fn get_client_list(conn: &RustConnection, atoms: &CachedAtoms) -> Result<Vec
 
     Ok(windows)
 }
+
+/// Whether a `DISPLAY` string names a server reached over the network (TCP, including SSH
+/// X forwarding such as `localhost:10.0`) rather than a local socket (`:0`, `unix:0`)
+pub fn is_remote_display(display: &str) -> bool {
+    // Paths (e.g. XQuartz's "/private/tmp/...:0") are local sockets
+    if display.starts_with('/') {
+        return false;
+    }
+    let Some((host, _)) = display.rsplit_once(':') else {
+        return false;
+    };
+    // Strip an explicit protocol ("tcp/host:0", "unix/:0")
+    let host = match host.split_once('/') {
+        Some(("unix", _)) => return false,
+        Some((_, host)) => host,
+        None => host,
+    };
+    !(host.is_empty() || host == "unix")
+}
+
+/// Whether this process's X connection (from `DISPLAY`) is remote. Cached after the first call.
+pub fn display_is_remote() -> bool {
+    static REMOTE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *REMOTE.get_or_init(|| {
+        std::env::var("DISPLAY")
+            .map(|display| is_remote_display(&display))
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text_property() {
+        // "EVE - Zoë" as Latin-1 and as UTF-8
+        let latin1 = b"EVE - Zo\xeb";
+        assert_eq!(
+            decode_text_property(AtomEnum::STRING.into(), latin1),
+            "EVE - Zoë"
+        );
+        // UTF8_STRING is not a predefined atom, so any other atom stands in for it
+        let utf8_string = 300;
+        assert_eq!(
+            decode_text_property(utf8_string, "EVE - Zoë".as_bytes()),
+            "EVE - Zoë"
+        );
+    }
+
+    #[test]
+    fn test_is_remote_display() {
+        assert!(!is_remote_display(":0"));
+        assert!(!is_remote_display(":1.0"));
+        assert!(!is_remote_display("unix:0"));
+        assert!(!is_remote_display("unix/:0"));
+        assert!(!is_remote_display("host/unix:0"));
+        assert!(!is_remote_display(
+            "/private/tmp/com.apple.launchd.abc/org.xquartz:0"
+        ));
+        assert!(!is_remote_display(""));
+
+        assert!(is_remote_display("localhost:10.0"));
+        assert!(is_remote_display("192.168.1.20:0"));
+        assert!(is_remote_display("tcp/gamebox:0"));
+    }
+}
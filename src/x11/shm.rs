@@ -0,0 +1,272 @@
+//! MIT-SHM image transfers
+//!
+//! Large images are written to a shared segment and drawn with `ShmPutImage`, so only the
+//! request crosses the X socket instead of the pixels. The segment is an unlinked file handed to
+//! the server with `ShmAttachFd` (see `common::shm`). Servers without MIT-SHM 1.2 and remote
+//! displays, where the attach fails, get a core `PutImage` instead. `ShmReader` does the same
+//! for reads with `ShmGetImage` (the SHM capture backend).
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::os::fd::OwnedFd;
+use std::os::unix::fs::FileExt;
+use x11rb::protocol::xproto::Window;
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::shm::{self, ConnectionExt as ShmExt};
+use x11rb::protocol::xproto::{ConnectionExt as XprotoExt, Drawable, Gcontext, ImageFormat};
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::constants::x11;
+use crate::common::shm::anonymous_file;
+
+thread_local! {
+    /// Upload state for the daemon's X connection (all overlay drawing happens on its thread)
+    static UPLOADER: RefCell<Uploader> = const { RefCell::new(Uploader::Unknown) };
+}
+
+enum Uploader {
+    /// Server support not checked yet
+    Unknown,
+    /// MIT-SHM can't be used; every upload goes through PutImage
+    Unsupported,
+    /// MIT-SHM works; the segment is (re)attached on demand, sized for the largest image so far
+    Ready(Option<Segment>),
+}
+
+impl Uploader {
+    /// Draws `data` through the shared segment. Returns false when the caller should fall back
+    /// to PutImage.
+    #[allow(clippy::too_many_arguments)]
+    fn put_image(
+        &mut self,
+        conn: &RustConnection,
+        drawable: Drawable,
+        gc: Gcontext,
+        width: u16,
+        height: u16,
+        depth: u8,
+        data: &[u8],
+    ) -> bool {
+        if let Self::Unknown = self {
+            *self = if supports_attach_fd(conn) {
+                Self::Ready(None)
+            } else {
+                info!("MIT-SHM 1.2 not available, uploading images with PutImage");
+                Self::Unsupported
+            };
+        }
+        let Self::Ready(segment) = self else {
+            return false;
+        };
+
+        if segment.as_ref().is_none_or(|s| s.size < data.len()) {
+            if let Some(old) = segment.take() {
+                old.detach(conn);
+            }
+            match Segment::attach(conn, data.len().next_power_of_two(), true) {
+                Ok(new) => *segment = Some(new),
+                Err(e) => {
+                    warn!(error = %e, "MIT-SHM attach failed, uploading images with PutImage");
+                    *self = Self::Unsupported;
+                    return false;
+                }
+            }
+        }
+        let Self::Ready(Some(segment)) = self else {
+            return false;
+        };
+
+        match segment.put_image(conn, drawable, gc, width, height, depth, data) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(error = %e, "MIT-SHM upload failed, uploading images with PutImage");
+                *self = Self::Unsupported;
+                false
+            }
+        }
+    }
+}
+
+/// A file-backed segment attached to the server (read-only for uploads)
+struct Segment {
+    seg: shm::Seg,
+    file: File,
+    size: usize,
+}
+
+impl Segment {
+    fn attach(conn: &RustConnection, size: usize, read_only: bool) -> Result<Self> {
+        let file = anonymous_file(size as u64)?;
+        let seg = conn.generate_id()?;
+        let fd = OwnedFd::from(file.try_clone().context("Failed to duplicate shm fd")?);
+        conn.shm_attach_fd(seg, fd, read_only)?
+            .check()
+            .context("Failed to attach MIT-SHM segment")?;
+        debug!(seg = seg, size = size, "Attached MIT-SHM segment");
+        Ok(Self { seg, file, size })
+    }
+
+    fn detach(self, conn: &RustConnection) {
+        if let Err(e) = conn.shm_detach(self.seg) {
+            debug!(seg = self.seg, error = %e, "Failed to detach MIT-SHM segment");
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn put_image(
+        &self,
+        conn: &RustConnection,
+        drawable: Drawable,
+        gc: Gcontext,
+        width: u16,
+        height: u16,
+        depth: u8,
+        data: &[u8],
+    ) -> Result<()> {
+        self.file
+            .write_all_at(data, 0)
+            .context("Failed to write MIT-SHM segment")?;
+        conn.shm_put_image(
+            drawable,
+            gc,
+            width,
+            height,
+            0,
+            0,
+            width,
+            height,
+            0,
+            0,
+            depth,
+            ImageFormat::Z_PIXMAP.into(),
+            false,
+            self.seg,
+            0,
+        )?
+        // The server reads the segment when it processes the request; wait for that so the
+        // next upload can't overwrite the pixels first
+        .check()
+        .context("ShmPutImage failed")?;
+        Ok(())
+    }
+}
+
+/// Reads window contents through a shared segment with `ShmGetImage`
+#[derive(Debug, Default)]
+pub struct ShmReader {
+    /// Sized for the largest image read so far
+    segment: RefCell<Option<Segment>>,
+}
+
+impl std::fmt::Debug for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Segment")
+            .field("seg", &self.seg)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl ShmReader {
+    /// Z-pixmap contents of the `width` x `height` area of `window` at (`x`, `y`)
+    pub fn get_image(
+        &self,
+        conn: &RustConnection,
+        window: Window,
+        (x, y): (i16, i16),
+        (width, height): (u16, u16),
+        bytes_per_pixel: usize,
+    ) -> Result<Vec<u8>> {
+        let needed = usize::from(width) * usize::from(height) * bytes_per_pixel;
+        let mut slot = self.segment.borrow_mut();
+        let segment = match slot.take() {
+            Some(current) if current.size >= needed => current,
+            old => {
+                if let Some(old) = old {
+                    old.detach(conn);
+                }
+                Segment::attach(conn, needed.next_power_of_two(), false)?
+            }
+        };
+        let segment = slot.insert(segment);
+
+        let reply = conn
+            .shm_get_image(
+                window,
+                x,
+                y,
+                width,
+                height,
+                !0,
+                ImageFormat::Z_PIXMAP.into(),
+                segment.seg,
+                0,
+            )?
+            .reply()
+            .context("ShmGetImage failed")?;
+        let mut data = vec![0; (reply.size as usize).min(segment.size)];
+        segment
+            .file
+            .read_exact_at(&mut data, 0)
+            .context("Failed to read MIT-SHM segment")?;
+        Ok(data)
+    }
+
+    /// Detaches the segment from the server
+    pub fn release(&self, conn: &RustConnection) {
+        if let Some(segment) = self.segment.take() {
+            segment.detach(conn);
+        }
+    }
+}
+
+/// Whether the server has MIT-SHM 1.2 (`ShmAttachFd`)
+pub fn supports_attach_fd(conn: &RustConnection) -> bool {
+    if !matches!(
+        conn.extension_information(shm::X11_EXTENSION_NAME),
+        Ok(Some(_))
+    ) {
+        return false;
+    }
+    conn.shm_query_version()
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .is_some_and(|version| (version.major_version, version.minor_version) >= (1, 2))
+}
+
+/// Uploads a Z-pixmap image into `drawable` at (0, 0). Images of at least
+/// `SHM_MIN_UPLOAD_BYTES` go through MIT-SHM when the server supports it; the rest, and every
+/// image on servers without it, use PutImage.
+pub fn put_image(
+    conn: &RustConnection,
+    drawable: Drawable,
+    gc: Gcontext,
+    width: u16,
+    height: u16,
+    depth: u8,
+    data: &[u8],
+) -> Result<()> {
+    if data.len() >= x11::SHM_MIN_UPLOAD_BYTES
+        && UPLOADER.with_borrow_mut(|uploader| {
+            uploader.put_image(conn, drawable, gc, width, height, depth, data)
+        })
+    {
+        return Ok(());
+    }
+    conn.put_image(
+        ImageFormat::Z_PIXMAP,
+        drawable,
+        gc,
+        width,
+        height,
+        0,
+        0,
+        0,
+        depth,
+        data,
+    )?;
+    Ok(())
+}